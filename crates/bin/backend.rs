@@ -0,0 +1,97 @@
+//! Output formats implemented as self-contained backends. Unlike the plain,
+//! JSON, and HTML formats, which every subcommand handles itself, a backend
+//! only renders the main listing, after it's been filtered, sorted, and
+//! paginated, along with the types selected the same way. Adding a format means implementing [OutputBackend] in its own
+//! module and listing it in [registry], behind a cargo feature if it brings in
+//! dependencies of its own.
+use crate::dot::CallGraph;
+use crate::go::GoStructs;
+use crate::hookplan::HookPlan;
+use crate::yara::YaraRules;
+use crate::zig::ZigStructs;
+use ezpdb::render::RenderOptions;
+use ezpdb::symbol_types::ParsedPdb;
+use ezpdb::type_info::TypeId;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Command line options which backends may depend on
+#[derive(Debug, Default, Clone)]
+pub struct BackendOptions {
+    /// Size in bytes of the patch written over a procedure's entry
+    pub patch_size: usize,
+    /// The image the PDB belongs to, given with `--image`
+    pub image: Option<PathBuf>,
+    /// How many of a procedure's leading bytes the yara format's rules match
+    pub yara_bytes: usize,
+    /// How type names are formatted, e.g. in the comments of generated code
+    pub render: RenderOptions,
+}
+
+/// An output format which renders a parsed PDB
+pub trait OutputBackend {
+    /// Name of the format, as given to `--format`
+    fn name(&self) -> &'static str;
+
+    /// Extensions of `--output` paths which imply this format, without the
+    /// leading dot. Extensions are matched case-insensitively.
+    fn extensions(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether the format renders types, and so can render the output of the
+    /// `type` subcommand
+    fn renders_types(&self) -> bool {
+        false
+    }
+
+    /// Writes `pdb_info` to `output`. `types` are the classes, unions, and enums
+    /// of the listing which passed its filter and pagination, for backends which
+    /// render types. The listings of `pdb_info` have already been restricted.
+    fn render(
+        &self,
+        pdb_info: &ParsedPdb,
+        types: &[TypeId],
+        output: &mut dyn Write,
+    ) -> anyhow::Result<()>;
+}
+
+/// Returns every available backend
+pub fn registry(options: &BackendOptions) -> Vec<Box<dyn OutputBackend>> {
+    vec![
+        Box::new(CallGraph),
+        Box::new(GoStructs {
+            render: options.render,
+        }),
+        Box::new(HookPlan {
+            patch_size: options.patch_size,
+        }),
+        Box::new(YaraRules {
+            image: options.image.clone(),
+            pattern_len: options.yara_bytes,
+        }),
+        Box::new(ZigStructs {
+            render: options.render,
+        }),
+    ]
+}
+
+/// Returns the backend named `name`
+pub fn find(name: &str, options: &BackendOptions) -> Option<Box<dyn OutputBackend>> {
+    registry(options)
+        .into_iter()
+        .find(|backend| backend.name().eq_ignore_ascii_case(name))
+}
+
+/// Returns the name of the backend implied by an `--output` path's extension
+pub fn name_for_extension(extension: &str) -> Option<&'static str> {
+    registry(&BackendOptions::default())
+        .iter()
+        .find(|backend| {
+            backend
+                .extensions()
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+        })
+        .map(|backend| backend.name())
+}
@@ -0,0 +1,154 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+use termcolor::{BufferedStandardStream, ColorSpec, WriteColor};
+
+/// Where the command's output is written
+pub enum Destination {
+    Stdout(BufferedStandardStream),
+    File(AtomicFile),
+}
+
+impl Destination {
+    /// Flushes any buffered output. For files this also moves the finished file
+    /// into place, so nothing is written to the destination path unless this
+    /// is called.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Destination::Stdout(mut stdout) => stdout.flush(),
+            Destination::File(file) => file.finish(),
+        }
+    }
+}
+
+impl Write for Destination {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Destination::Stdout(stdout) => stdout.write(buf),
+            Destination::File(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Destination::Stdout(stdout) => stdout.flush(),
+            Destination::File(file) => file.flush(),
+        }
+    }
+}
+
+impl WriteColor for Destination {
+    fn supports_color(&self) -> bool {
+        match self {
+            Destination::Stdout(stdout) => stdout.supports_color(),
+            Destination::File(_) => false,
+        }
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        match self {
+            Destination::Stdout(stdout) => stdout.set_color(spec),
+            Destination::File(_) => Ok(()),
+        }
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        match self {
+            Destination::Stdout(stdout) => stdout.reset(),
+            Destination::File(_) => Ok(()),
+        }
+    }
+}
+
+enum Encoder {
+    Plain(BufWriter<NamedTempFile>),
+    Gzip(GzEncoder<BufWriter<NamedTempFile>>),
+}
+
+/// A file which is written to a temporary path next to its destination and
+/// renamed over the destination once complete, so that readers never observe
+/// a partially written file. The temporary file is removed if the write is
+/// abandoned. Paths ending in `.gz` are gzip-compressed.
+pub struct AtomicFile {
+    path: PathBuf,
+    encoder: Option<Encoder>,
+}
+
+impl AtomicFile {
+    /// Creates the temporary file for `path`, creating any missing parent directories
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty());
+        if let Some(parent) = parent {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file_name = path.file_name().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "output path has no file name")
+        })?;
+        let mut prefix = std::ffi::OsString::from(".");
+        prefix.push(file_name);
+        prefix.push(".");
+        // Created exclusively, under a random name, so that nothing already at
+        // the temporary path is followed or overwritten
+        let mut builder = tempfile::Builder::new();
+        builder.prefix(&prefix).suffix(".tmp");
+        // Leave the permissions to the umask, as for any other new file,
+        // rather than restricting them to the owner
+        #[cfg(unix)]
+        builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+        let file = builder.tempfile_in(parent.unwrap_or_else(|| Path::new(".")))?;
+
+        let writer = BufWriter::new(file);
+        let encoder = if is_gzip(path) {
+            Encoder::Gzip(GzEncoder::new(writer, Compression::default()))
+        } else {
+            Encoder::Plain(writer)
+        };
+
+        Ok(AtomicFile {
+            path: path.to_owned(),
+            encoder: Some(encoder),
+        })
+    }
+
+    fn encoder(&mut self) -> &mut dyn Write {
+        match self.encoder.as_mut().expect("file was already finished") {
+            Encoder::Plain(writer) => writer,
+            Encoder::Gzip(writer) => writer,
+        }
+    }
+
+    /// Finishes compression, syncs the temporary file, and renames it over the destination
+    pub fn finish(mut self) -> io::Result<()> {
+        let writer = match self.encoder.take().expect("file was already finished") {
+            Encoder::Plain(writer) => writer,
+            Encoder::Gzip(encoder) => encoder.finish()?,
+        };
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.as_file().sync_all()?;
+
+        file.persist(&self.path)?;
+        Ok(())
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.encoder().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.encoder().flush()
+    }
+}
+
+/// Returns whether `path` should be gzip-compressed
+pub fn is_gzip(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"))
+}
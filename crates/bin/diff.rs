@@ -0,0 +1,313 @@
+//! Comparing two PDBs, e.g. of successive builds of the same image, as a set of
+//! added, removed, and modified records. Records only refer to types by name,
+//! so records of different PDBs can be compared even though their type indices
+//! differ.
+use crate::output::{format_procedure_signature, format_type_name, is_listed_type, type_key};
+use ezpdb::render::RenderOptions;
+use ezpdb::symbol_types::ParsedPdb;
+use ezpdb::type_info::*;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+/// What happened to a record between the old and new PDB
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Op {
+    /// The record is only in the new PDB
+    Add,
+    /// The record is only in the old PDB
+    Remove,
+    /// The record is in both PDBs, but differs
+    Replace,
+}
+
+impl Op {
+    /// Returns the name of the JSON Patch operation
+    fn as_str(self) -> &'static str {
+        match self {
+            Op::Add => "add",
+            Op::Remove => "remove",
+            Op::Replace => "replace",
+        }
+    }
+}
+
+/// A record which differs between two PDBs, modeled after a JSON Patch
+/// operation
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub op: Op,
+    /// JSON Pointer to the record, made of its category and name, e.g.
+    /// `/procedures/main`
+    pub path: String,
+    /// The record in the old PDB, unless it was added
+    pub before: Option<Value>,
+    /// The record in the new PDB, unless it was removed
+    pub after: Option<Value>,
+}
+
+/// Escapes `/` and `~` in a JSON Pointer reference token
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Returns the records of `pdb` keyed by their path. Symbols which share a name,
+/// such as static functions in different object files, are told apart by a
+/// `#2`, `#3`, ... suffix in the order they appear. Type names are formatted as
+/// `render` says.
+fn records(pdb: &ParsedPdb, render: &RenderOptions) -> BTreeMap<String, Value> {
+    let mut records = BTreeMap::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut insert = |category: &str, name: &str, record: Value| {
+        let path = format!("/{}/{}", category, escape(name));
+        let count = counts.entry(path.clone()).or_insert(0);
+        *count += 1;
+        let path = match *count {
+            1 => path,
+            count => format!("{}#{}", path, count),
+        };
+        records.insert(path, record);
+    };
+
+    for public in &pdb.public_symbols {
+        insert(
+            "public-symbols",
+            &public.name,
+            json!({
+                "name": public.name,
+                "rva": public.offset.map(|address| address.rva),
+                "is_code": public.is_code,
+                "is_function": public.is_function,
+                "is_managed": public.is_managed,
+                "is_msil": public.is_msil,
+            }),
+        );
+    }
+    for procedure in &pdb.procedures {
+        insert(
+            "procedures",
+            &procedure.name,
+            json!({
+                "name": procedure.name,
+                "rva": procedure.address.map(|address| address.rva),
+                "len": procedure.len,
+                "signature": format_procedure_signature(pdb, procedure, render),
+                "is_global": procedure.is_global,
+            }),
+        );
+    }
+    for data in &pdb.global_data {
+        insert(
+            "global-data",
+            &data.name,
+            json!({
+                "name": data.name,
+                "rva": data.offset.map(|address| address.rva),
+                "type": format_type_name(pdb, &pdb.types[data.ty], render),
+                "storage": data.storage,
+            }),
+        );
+    }
+    for (_id, ty) in pdb.types.iter() {
+        if !is_listed_type(ty) {
+            continue;
+        }
+        if let Some(name) = type_key(ty) {
+            insert("types", name, type_record(pdb, ty, render));
+        }
+    }
+
+    records
+}
+
+/// Returns the record of a class, union, or enum, which lists its members or
+/// variants as text
+fn type_record(pdb: &ParsedPdb, ty: &Type, render: &RenderOptions) -> Value {
+    let members = |fields: &[TypeId]| -> Vec<String> {
+        fields
+            .iter()
+            .filter_map(|field| match &pdb.types[*field] {
+                Type::Member(member) => Some(format!(
+                    "+0x{:X} {} {}",
+                    member.offset,
+                    format_type_name(pdb, &pdb.types[member.underlying_type], render),
+                    member.name
+                )),
+                Type::BaseClass(base) => Some(format!(
+                    "+0x{:X} base {}",
+                    base.offset,
+                    format_type_name(pdb, &pdb.types[base.base_class], render)
+                )),
+                _ => None,
+            })
+            .collect()
+    };
+
+    match ty {
+        Type::Class(class) => json!({
+            "name": class.name,
+            "kind": class.kind.to_string().to_ascii_lowercase(),
+            "size": ty.type_size(pdb),
+            "members": members(&class.fields),
+        }),
+        Type::Union(union) => json!({
+            "name": union.name,
+            "kind": "union",
+            "size": ty.type_size(pdb),
+            "members": members(&union.fields),
+        }),
+        Type::Enumeration(e) => json!({
+            "name": e.name,
+            "kind": "enum",
+            "underlying_type": format_type_name(pdb, &pdb.types[e.underlying_type], render),
+            "variants": e
+                .variants
+                .iter()
+                .map(|variant| format!("{} = 0x{:X}", variant.name, e.variant_value(variant, pdb)))
+                .collect::<Vec<_>>(),
+        }),
+        _ => Value::Null,
+    }
+}
+
+/// Returns `record` without its address, for comparisons which ignore symbols
+/// moving
+fn without_address(record: &Value) -> Value {
+    let mut record = record.clone();
+    if let Value::Object(fields) = &mut record {
+        fields.remove("rva");
+    }
+
+    record
+}
+
+/// Returns the records which were added, removed, or modified between `old`
+/// and `new`, ordered by path. If `ignore_addresses` is set, records which only
+/// moved aren't considered modified. Type names are formatted as `render` says.
+pub fn diff(
+    old: &ParsedPdb,
+    new: &ParsedPdb,
+    ignore_addresses: bool,
+    render: &RenderOptions,
+) -> Vec<Change> {
+    let mut old_records = records(old, render);
+    let new_records = records(new, render);
+
+    let mut changes = vec![];
+    for (path, after) in new_records {
+        match old_records.remove(&path) {
+            None => changes.push(Change {
+                op: Op::Add,
+                path,
+                before: None,
+                after: Some(after),
+            }),
+            Some(before) => {
+                let modified = if ignore_addresses {
+                    without_address(&before) != without_address(&after)
+                } else {
+                    before != after
+                };
+                if modified {
+                    changes.push(Change {
+                        op: Op::Replace,
+                        path,
+                        before: Some(before),
+                        after: Some(after),
+                    });
+                }
+            }
+        }
+    }
+    changes.extend(old_records.into_iter().map(|(path, before)| Change {
+        op: Op::Remove,
+        path,
+        before: Some(before),
+        after: None,
+    }));
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    changes
+}
+
+/// Formats a field of a record for plain output
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "none".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_u64() {
+            Some(n) => format!("0x{:X}", n),
+            None => n.to_string(),
+        },
+        value => value.to_string(),
+    }
+}
+
+/// Writes `changes` one record per line, prefixed with `+` if it was added, `-`
+/// if it was removed, or `~` if it was modified. The fields of modified records
+/// which changed follow, with elements added to or removed from lists, such as
+/// a class's members, listed individually.
+pub fn print_changes(output: &mut impl Write, changes: &[Change]) -> io::Result<()> {
+    let empty = Map::new();
+    for change in changes {
+        let path = change.path.trim_start_matches('/');
+        match change.op {
+            Op::Add => writeln!(output, "+ {}", path)?,
+            Op::Remove => writeln!(output, "- {}", path)?,
+            Op::Replace => {
+                writeln!(output, "~ {}", path)?;
+                let before = change.before.as_ref().and_then(Value::as_object);
+                let after = change.after.as_ref().and_then(Value::as_object);
+                let (before, after) = (before.unwrap_or(&empty), after.unwrap_or(&empty));
+                for (field, new_value) in after {
+                    let old_value = before.get(field).unwrap_or(&Value::Null);
+                    if old_value == new_value {
+                        continue;
+                    }
+                    match (old_value, new_value) {
+                        (Value::Array(old_items), Value::Array(new_items)) => {
+                            for item in old_items.iter().filter(|item| !new_items.contains(item)) {
+                                writeln!(output, "    - {}: {}", field, format_value(item))?;
+                            }
+                            for item in new_items.iter().filter(|item| !old_items.contains(item)) {
+                                writeln!(output, "    + {}: {}", field, format_value(item))?;
+                            }
+                        }
+                        _ => writeln!(
+                            output,
+                            "    {}: {} -> {}",
+                            field,
+                            format_value(old_value),
+                            format_value(new_value)
+                        )?,
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes `changes` as a JSON array of operations. Each has the `op` and `path`
+/// of a JSON Patch operation, along with the record `before` the change unless
+/// it was added, and `after` it unless it was removed.
+pub fn write_changes(output: &mut impl Write, changes: &[Change]) -> io::Result<()> {
+    let changes: Vec<_> = changes
+        .iter()
+        .map(|change| {
+            let mut operation = Map::new();
+            operation.insert("op".to_string(), json!(change.op.as_str()));
+            operation.insert("path".to_string(), json!(change.path));
+            if let Some(before) = &change.before {
+                operation.insert("before".to_string(), before.clone());
+            }
+            if let Some(after) = &change.after {
+                operation.insert("after".to_string(), after.clone());
+            }
+            Value::Object(operation)
+        })
+        .collect();
+
+    write!(output, "{}", Value::Array(changes))
+}
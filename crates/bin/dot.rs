@@ -0,0 +1,63 @@
+use crate::backend::OutputBackend;
+use ezpdb::symbol_types::*;
+use ezpdb::type_info::TypeId;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Escapes text for inclusion in a quoted Graphviz ID
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the call graph of `pdb_info` as a Graphviz digraph. Edges are labeled
+/// with their invocation counts when the PDB records them.
+pub fn print_call_graph(output: &mut impl Write, pdb_info: &ParsedPdb) -> io::Result<()> {
+    writeln!(output, "digraph calls {{")?;
+    for edge in &pdb_info.call_graph {
+        write!(
+            output,
+            "    \"{}\" -> \"{}\"",
+            Escape(&edge.caller),
+            Escape(&edge.callee)
+        )?;
+        if let Some(invocations) = edge.invocations {
+            write!(output, " [label=\"{}\"]", invocations)?;
+        }
+        writeln!(output, ";")?;
+    }
+    writeln!(output, "}}")
+}
+
+/// The `dot` output format, which only includes the call graph
+pub struct CallGraph;
+
+impl OutputBackend for CallGraph {
+    fn name(&self) -> &'static str {
+        "dot"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["dot", "gv"]
+    }
+
+    fn render(
+        &self,
+        pdb_info: &ParsedPdb,
+        _types: &[TypeId],
+        mut output: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        Ok(print_call_graph(&mut output, pdb_info)?)
+    }
+}
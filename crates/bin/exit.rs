@@ -0,0 +1,64 @@
+//! The process exit codes, which let scripts tell apart the ways a run can end
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+
+/// The run completed without problems
+pub const SUCCESS: i32 = 0;
+
+/// The run failed for a reason not covered by the other codes
+pub const FAILURE: i32 = 1;
+
+/// The run completed, but some records couldn't be parsed and were skipped
+pub const PARSED_WITH_DIAGNOSTICS: i32 = 2;
+
+/// No PDB matching the executable being processed was found
+pub const PDB_MISMATCH: i32 = 3;
+
+/// A file couldn't be read or written
+pub const IO_ERROR: i32 = 4;
+
+/// The input isn't a PDB or executable, or uses a format which isn't supported
+pub const UNSUPPORTED_FORMAT: i32 = 5;
+
+static HAD_DIAGNOSTICS: AtomicBool = AtomicBool::new(false);
+
+/// No PDB matching the executable being processed could be found
+#[derive(Error, Debug)]
+#[error("{0}")]
+pub struct PdbMismatch(pub String);
+
+/// Notes that a parsed PDB had records which were skipped, so that the run
+/// exits with [PARSED_WITH_DIAGNOSTICS] if it otherwise succeeds
+pub fn record_diagnostics(pdb: &ezpdb::ParsedPdb) {
+    if !pdb.diagnostics.is_empty() {
+        HAD_DIAGNOSTICS.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Returns the exit code for the outcome of the run
+pub fn code(result: &anyhow::Result<()>) -> i32 {
+    let error = match result {
+        Ok(()) if HAD_DIAGNOSTICS.load(Ordering::Relaxed) => return PARSED_WITH_DIAGNOSTICS,
+        Ok(()) => return SUCCESS,
+        Err(error) => error,
+    };
+
+    for cause in error.chain() {
+        if cause.is::<PdbMismatch>() {
+            return PDB_MISMATCH;
+        }
+        if cause.is::<std::io::Error>() {
+            return IO_ERROR;
+        }
+        if let Some(error) = cause.downcast_ref::<ezpdb::error::Error>() {
+            if error.is_io() {
+                return IO_ERROR;
+            }
+            if error.is_unsupported_format() {
+                return UNSUPPORTED_FORMAT;
+            }
+        }
+    }
+
+    FAILURE
+}
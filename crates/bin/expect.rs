@@ -0,0 +1,68 @@
+use crate::CliArgumentError;
+use ezpdb::ParsedPdb;
+use std::str::FromStr;
+
+/// A property the parsed PDB is expected to have, checked with `--expect`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    NonEmptyPublics,
+    NonEmptyProcedures,
+    NonEmptyGlobals,
+    NonEmptyTypes,
+    NonEmptyModules,
+    /// Every record was parsed
+    NoDiagnostics,
+}
+
+impl FromStr for Expectation {
+    type Err = CliArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let result = match s.trim().to_ascii_lowercase().as_ref() {
+            "non-empty-publics" => Expectation::NonEmptyPublics,
+            "non-empty-procedures" => Expectation::NonEmptyProcedures,
+            "non-empty-globals" => Expectation::NonEmptyGlobals,
+            "non-empty-types" => Expectation::NonEmptyTypes,
+            "non-empty-modules" => Expectation::NonEmptyModules,
+            "no-diagnostics" => Expectation::NoDiagnostics,
+            _ => return Err(CliArgumentError::InvalidValue("expect", s.to_string())),
+        };
+
+        Ok(result)
+    }
+}
+
+impl Expectation {
+    /// Returns a description of how `pdb` falls short of this expectation, or
+    /// `None` if it's met
+    fn check(self, pdb: &ParsedPdb) -> Option<String> {
+        let empty = |what: &str, is_empty: bool| {
+            if is_empty {
+                Some(format!("the PDB has no {}", what))
+            } else {
+                None
+            }
+        };
+
+        match self {
+            Expectation::NonEmptyPublics => empty("public symbols", pdb.public_symbols.is_empty()),
+            Expectation::NonEmptyProcedures => empty("procedures", pdb.procedures.is_empty()),
+            Expectation::NonEmptyGlobals => empty("global data", pdb.global_data.is_empty()),
+            Expectation::NonEmptyTypes => empty("types", pdb.types.is_empty()),
+            Expectation::NonEmptyModules => empty("debug modules", pdb.debug_modules.is_empty()),
+            Expectation::NoDiagnostics if !pdb.diagnostics.is_empty() => Some(format!(
+                "{} records couldn't be parsed",
+                pdb.diagnostics.len()
+            )),
+            Expectation::NoDiagnostics => None,
+        }
+    }
+}
+
+/// Returns a description of each of `expectations` which `pdb` doesn't meet
+pub fn unmet(pdb: &ParsedPdb, expectations: &[Expectation]) -> Vec<String> {
+    expectations
+        .iter()
+        .filter_map(|expectation| expectation.check(pdb))
+        .collect()
+}
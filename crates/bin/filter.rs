@@ -0,0 +1,792 @@
+//! A small expression language used by `--where` to filter listings, e.g.
+//! `kind == 'procedure' && len > 0x1000 && name =~ 'Crypt'`
+use ezpdb::render::RenderOptions;
+use ezpdb::search::NamePattern;
+use ezpdb::symbol_types::{
+    Address, Annotation, CallGraphEdge, CallSite, CoffGroup, Data, FastlinkReference,
+    HeapAllocationSite, ManagedProcedure, ParsedPdb, Procedure, ProvenanceString, PublicSymbol,
+    Section, SecuritySymbol, SeparatedCode, StaticInitializer, Trampoline,
+};
+use ezpdb::type_info::{Class, Type, Typed, Union};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FilterError {
+    #[error("unexpected character `{0}` at offset {1}")]
+    UnexpectedChar(char, usize),
+
+    #[error("unterminated string starting at offset {0}")]
+    UnterminatedString(usize),
+
+    #[error("invalid number `{0}`")]
+    InvalidNumber(String),
+
+    #[error("expected {0} but found {1}")]
+    Expected(&'static str, String),
+
+    #[error("unknown attribute `{0}`. Attributes include: {}", ATTRIBUTES.join(", "))]
+    UnknownAttribute(String),
+
+    #[error("expression is nested more than {0} levels deep")]
+    TooDeep(usize),
+}
+
+/// How deeply parentheses, `!`, `&&`, and `||` may nest. Parsing and evaluating
+/// recurse once per level, so deeper expressions would overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Every attribute name some [Filterable] record has. Names outside of this list
+/// are rejected when the filter is parsed rather than never matching.
+pub const ATTRIBUTES: &[&str] = &[
+    "address",
+    "callee",
+    "caller",
+    "characteristics",
+    "custom_calling_convention",
+    "epilogue_start",
+    "file",
+    "flags",
+    "function",
+    "group",
+    "has_eh",
+    "has_seh",
+    "invocations",
+    "is_code",
+    "is_dpc",
+    "is_function",
+    "is_global",
+    "is_lexical_scope",
+    "is_local",
+    "is_managed",
+    "is_msil",
+    "isa",
+    "kind",
+    "len",
+    "line",
+    "mitigation",
+    "module",
+    "modules",
+    "name",
+    "no_inline",
+    "no_return",
+    "offset",
+    "origin",
+    "packing",
+    "parent",
+    "procedure_name",
+    "prologue_end",
+    "provenance",
+    "public_name",
+    "returns_to_parent",
+    "rva",
+    "signature",
+    "size",
+    "storage",
+    "target",
+    "token",
+    "value",
+    "variable",
+];
+
+/// A value an attribute or literal can take
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(u64),
+    Str(String),
+    Bool(bool),
+}
+
+/// A record which can be filtered by a [Filter]
+pub trait Filterable {
+    /// Returns the value of the attribute `name`, or `None` if this record doesn't
+    /// have it. Type names are formatted as `render` says.
+    fn attribute(&self, pdb: &ParsedPdb, render: &RenderOptions, name: &str) -> Option<Value>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Matches,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    /// A bare operand, which is truthy if it is `true`, a non-zero integer, or a non-empty string
+    Operand(Operand),
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Attribute(String),
+    Literal(Value),
+}
+
+/// A parsed `--where` expression
+#[derive(Debug, Clone)]
+pub struct Filter(Expr);
+
+impl Filter {
+    /// Returns whether `record` satisfies this filter. Comparisons against an
+    /// attribute the record doesn't have, e.g. the `len` of a public symbol, are
+    /// always false. Type names are compared as `render` formats them.
+    pub fn matches(
+        &self,
+        pdb: &ParsedPdb,
+        render: &RenderOptions,
+        record: &dyn Filterable,
+    ) -> bool {
+        eval(&self.0, &|name| record.attribute(pdb, render, name))
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.or()?;
+
+        match parser.peek() {
+            None => Ok(Filter(expr)),
+            Some(token) => Err(FilterError::Expected(
+                "end of expression",
+                format!("{:?}", token),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(Value),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, FilterError> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Op(CompareOp::Eq), 2),
+            ('=', Some('~')) => (Token::Op(CompareOp::Matches), 2),
+            ('!', Some('=')) => (Token::Op(CompareOp::Ne), 2),
+            ('<', Some('=')) => (Token::Op(CompareOp::Le), 2),
+            ('>', Some('=')) => (Token::Op(CompareOp::Ge), 2),
+            ('<', _) => (Token::Op(CompareOp::Lt), 1),
+            ('>', _) => (Token::Op(CompareOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('\'', _) | ('"', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|ch| *ch == c)
+                    .ok_or(FilterError::UnterminatedString(i))?;
+                let value: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Literal(Value::Str(value)), end + 2)
+            }
+            (c, _) if c.is_ascii_digit() => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_ascii_alphanumeric())
+                    .count();
+                let text: String = chars[i..i + len].iter().collect();
+                let value = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => text.parse(),
+                }
+                .map_err(|_| FilterError::InvalidNumber(text.clone()))?;
+                (Token::Literal(Value::Int(value)), len)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_alphanumeric() || **ch == '_')
+                    .count();
+                let text: String = chars[i..i + len].iter().collect();
+                let token = match text.as_str() {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    _ => Token::Ident(text),
+                };
+                (token, len)
+            }
+            (c, _) => return Err(FilterError::UnexpectedChar(c, i)),
+        };
+
+        tokens.push(token);
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// How many levels deep the expression being parsed is nested
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Enters a level of nesting. Each operand of a chain of `&&` or `||` is a
+    /// level deeper than the one before it, as the chain nests to the left.
+    fn nest(&mut self) -> Result<(), FilterError> {
+        if self.depth == MAX_DEPTH {
+            return Err(FilterError::TooDeep(MAX_DEPTH));
+        }
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Expr, FilterError> {
+        let depth = self.depth;
+        let mut lhs = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            self.nest()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        self.depth = depth;
+
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, FilterError> {
+        let depth = self.depth;
+        let mut lhs = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            self.nest()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        self.depth = depth;
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FilterError> {
+        let depth = self.depth;
+        let expr = match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                self.nest()?;
+                Expr::Not(Box::new(self.unary()?))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                self.nest()?;
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::RParen) => expr,
+                    other => return Err(FilterError::Expected("`)`", describe(other))),
+                }
+            }
+            _ => self.comparison()?,
+        };
+        self.depth = depth;
+
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, FilterError> {
+        let lhs = self.operand()?;
+        match self.peek() {
+            Some(Token::Op(op)) => {
+                let op = *op;
+                self.pos += 1;
+                Ok(Expr::Compare(lhs, op, self.operand()?))
+            }
+            _ => Ok(Expr::Operand(lhs)),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, FilterError> {
+        match self.next() {
+            Some(Token::Ident(name)) if ATTRIBUTES.contains(&name.as_str()) => {
+                Ok(Operand::Attribute(name))
+            }
+            Some(Token::Ident(name)) => Err(FilterError::UnknownAttribute(name)),
+            Some(Token::Literal(value)) => Ok(Operand::Literal(value)),
+            other => Err(FilterError::Expected(
+                "an attribute name or value",
+                describe(other),
+            )),
+        }
+    }
+}
+
+fn describe(token: Option<Token>) -> String {
+    token
+        .map(|token| format!("{:?}", token))
+        .unwrap_or_else(|| "end of expression".to_string())
+}
+
+type Attributes<'a> = dyn Fn(&str) -> Option<Value> + 'a;
+
+fn resolve(operand: &Operand, record: &Attributes<'_>) -> Option<Value> {
+    match operand {
+        Operand::Attribute(name) => record(name),
+        Operand::Literal(value) => Some(value.clone()),
+    }
+}
+
+fn eval(expr: &Expr, record: &Attributes<'_>) -> bool {
+    match expr {
+        Expr::Or(lhs, rhs) => eval(lhs, record) || eval(rhs, record),
+        Expr::And(lhs, rhs) => eval(lhs, record) && eval(rhs, record),
+        Expr::Not(expr) => !eval(expr, record),
+        Expr::Operand(operand) => match resolve(operand, record) {
+            Some(Value::Bool(value)) => value,
+            Some(Value::Int(value)) => value != 0,
+            Some(Value::Str(value)) => !value.is_empty(),
+            None => false,
+        },
+        Expr::Compare(lhs, op, rhs) => {
+            let (lhs, rhs) = match (resolve(lhs, record), resolve(rhs, record)) {
+                (Some(lhs), Some(rhs)) => (lhs, rhs),
+                _ => return false,
+            };
+
+            if *op == CompareOp::Matches {
+                return match (lhs, rhs) {
+                    (Value::Str(value), Value::Str(pattern)) => {
+                        NamePattern::new(&pattern).is_match(&value)
+                    }
+                    _ => false,
+                };
+            }
+
+            let ordering = match (&lhs, &rhs) {
+                (Value::Int(lhs), Value::Int(rhs)) => lhs.cmp(rhs),
+                (Value::Str(lhs), Value::Str(rhs)) => lhs.cmp(rhs),
+                (Value::Bool(lhs), Value::Bool(rhs)) => lhs.cmp(rhs),
+                _ => return false,
+            };
+
+            match op {
+                CompareOp::Eq => ordering.is_eq(),
+                CompareOp::Ne => ordering.is_ne(),
+                CompareOp::Lt => ordering.is_lt(),
+                CompareOp::Le => ordering.is_le(),
+                CompareOp::Gt => ordering.is_gt(),
+                CompareOp::Ge => ordering.is_ge(),
+                CompareOp::Matches => unreachable!(),
+            }
+        }
+    }
+}
+
+/// Values of addresses are rebased if a base address was given
+fn address(address: Option<Address>) -> Option<Value> {
+    address.map(|address| Value::Int(address.value() as u64))
+}
+
+fn rva(address: Option<Address>) -> Option<Value> {
+    address.map(|address| Value::Int(address.rva as u64))
+}
+
+impl Filterable for PublicSymbol {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("public".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "is_code" => Value::Bool(self.is_code),
+            "is_function" => Value::Bool(self.is_function),
+            "is_managed" => Value::Bool(self.is_managed),
+            "is_msil" => Value::Bool(self.is_msil),
+            "procedure_name" => return self.procedure_name.clone().map(Value::Str),
+            "isa" => return self.isa.map(|isa| Value::Str(isa.to_string())),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for Procedure {
+    fn attribute(&self, pdb: &ParsedPdb, render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("procedure".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => return address(self.address),
+            "rva" => return rva(self.address),
+            "len" | "size" => Value::Int(self.len as u64),
+            "is_global" => Value::Bool(self.is_global),
+            "is_dpc" => Value::Bool(self.is_dpc),
+            "prologue_end" => Value::Int(self.prologue_end as u64),
+            "epilogue_start" => Value::Int(self.epilogue_start as u64),
+            "public_name" => return self.public_name.clone().map(Value::Str),
+            "origin" => Value::Str(self.origin.to_string()),
+            "isa" => return self.isa.map(|isa| Value::Str(isa.to_string())),
+            "no_return" => Value::Bool(self.flags.no_return),
+            "no_inline" => Value::Bool(self.flags.no_inline),
+            "custom_calling_convention" => Value::Bool(self.flags.custom_calling_convention),
+            "has_eh" => Value::Bool(self.flags.has_eh),
+            "has_seh" => Value::Bool(self.flags.has_seh),
+            "file" => {
+                return self
+                    .source
+                    .as_ref()
+                    .map(|source| Value::Str(source.file.clone()))
+            }
+            "line" => {
+                return self
+                    .source
+                    .as_ref()
+                    .map(|source| Value::Int(source.first_line.into()))
+            }
+            "signature" => {
+                return crate::output::format_procedure_signature(pdb, self, render).map(Value::Str)
+            }
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for Data {
+    fn attribute(&self, pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" if self.module.is_some() => Value::Str("local".to_string()),
+            "kind" => Value::Str("global".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "len" | "size" => Value::Int(pdb.types[self.ty].type_size(pdb) as u64),
+            "is_global" => Value::Bool(self.is_global),
+            "is_managed" => Value::Bool(self.is_managed),
+            "module" => return self.module.clone().map(Value::Str),
+            "function" => return self.function.clone().map(Value::Str),
+            "storage" => return self.storage.map(|storage| Value::Str(storage.to_string())),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// Annotations are filterable by `address` and `name`, which is all of their
+/// strings separated by spaces
+impl Filterable for Annotation {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("annotation".to_string()),
+            "name" => Value::Str(self.strings.join(" ")),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for Trampoline {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("trampoline".to_string()),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "len" | "size" => Value::Int(self.size as u64),
+            "target" => return address(self.target),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for SeparatedCode {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("separated_code".to_string()),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "len" | "size" => Value::Int(self.len as u64),
+            "parent" => return address(self.parent_offset),
+            "is_lexical_scope" => Value::Bool(self.is_lexical_scope),
+            "returns_to_parent" => Value::Bool(self.returns_to_parent),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// Call sites are filterable by `address`, `caller`, and `name`, which is the
+/// signature of the called function
+impl Filterable for CallSite {
+    fn attribute(&self, pdb: &ParsedPdb, render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("call_site".to_string()),
+            "name" => Value::Str(crate::output::format_type_name(
+                pdb,
+                &pdb.types[self.ty?],
+                render,
+            )),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "caller" => return self.caller.clone().map(Value::Str),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for CallGraphEdge {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("call".to_string()),
+            "caller" => Value::Str(self.caller.clone()),
+            "callee" => Value::Str(self.callee.clone()),
+            "invocations" => return self.invocations.map(|count| Value::Int(count as u64)),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for Section {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("section".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => Value::Int(self.offset.value() as u64),
+            "rva" => Value::Int(self.offset.rva as u64),
+            "len" | "size" => Value::Int(self.len as u64),
+            "characteristics" => Value::Int(self.characteristics as u64),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for CoffGroup {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("coff_group".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "len" | "size" => Value::Int(self.len as u64),
+            "characteristics" => Value::Int(self.characteristics as u64),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// Static initializers are filterable by `name`, `address`, `variable`, which
+/// is the global they construct or destroy, and `group`, which is the CRT table
+/// they're an entry of
+impl Filterable for StaticInitializer {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("static_initializer".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "variable" => Value::Str(self.variable.clone()?),
+            "group" => Value::Str(self.group.clone()?),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for SecuritySymbol {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("security".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "mitigation" => Value::Str(self.feature.to_string()),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for ProvenanceString {
+    fn attribute(&self, _pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("provenance".to_string()),
+            "name" | "value" => Value::Str(self.value.clone()),
+            "provenance" => Value::Str(self.kind.to_string()),
+            "modules" => Value::Int(self.modules as u64),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for FastlinkReference {
+    fn attribute(&self, pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("fastlink_reference".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "is_local" => Value::Bool(self.is_local),
+            "module" => Value::Str(crate::output::format_module_name(pdb, self.module_index)),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+impl Filterable for ManagedProcedure {
+    fn attribute(&self, pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let value = match name {
+            "kind" => Value::Str("managed_procedure".to_string()),
+            "name" => Value::Str(self.name.clone()),
+            "address" | "offset" => return address(self.address),
+            "rva" => return rva(self.address),
+            "len" | "size" => Value::Int(self.len as u64),
+            "token" => Value::Int(self.token.into()),
+            "is_global" => Value::Bool(self.is_global),
+            "module" => Value::Str(crate::output::format_module_name(pdb, self.module_index)),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// Heap allocation sites are filterable by `address`, `size`, and `name`, which
+/// is the name of the allocated type
+impl Filterable for HeapAllocationSite {
+    fn attribute(&self, pdb: &ParsedPdb, render: &RenderOptions, name: &str) -> Option<Value> {
+        let ty = &pdb.types[self.ty];
+        let value = match name {
+            "kind" => Value::Str("heap_allocation_site".to_string()),
+            "name" => Value::Str(crate::output::format_type_name(pdb, ty, render)),
+            "address" | "offset" => return address(self.offset),
+            "rva" => return rva(self.offset),
+            "len" | "size" => Value::Int(ty.type_size(pdb) as u64),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// Types are filterable by `kind` (`class`, `struct`, `interface`, `union`, or
+/// `enum`), `name`, and `size`
+impl Filterable for Type {
+    fn attribute(&self, pdb: &ParsedPdb, _render: &RenderOptions, name: &str) -> Option<Value> {
+        let (kind, type_name) = match self {
+            Type::Class(class) => (class.kind.to_string().to_lowercase(), &class.name),
+            Type::Union(union) => ("union".to_string(), &union.name),
+            Type::Enumeration(e) => ("enum".to_string(), &e.name),
+            _ => return None,
+        };
+
+        let value = match name {
+            "kind" => Value::Str(kind),
+            "name" => Value::Str(type_name.to_string()),
+            "len" | "size" => Value::Int(self.type_size(pdb) as u64),
+            "flags" => match self {
+                Type::Enumeration(e) => Value::Bool(e.is_flags(pdb)),
+                _ => return None,
+            },
+            "packing" => match self {
+                Type::Class(Class { packing, .. }) | Type::Union(Union { packing, .. }) => {
+                    Value::Int((*packing)? as u64)
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_attributes_parse() {
+        let filter = "kind == 'procedure' && len > 0x1000 && !no_return";
+        assert!(filter.parse::<Filter>().is_ok());
+    }
+
+    #[test]
+    fn unknown_attributes_are_rejected() {
+        match "kind == 'procedure' && lenght > 0x1000".parse::<Filter>() {
+            Err(FilterError::UnknownAttribute(name)) => assert_eq!(name, "lenght"),
+            other => panic!("expected an unknown attribute error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        let parens = format!("{}true{}", "(".repeat(50_000), ")".repeat(50_000));
+        let nots = format!("{}true", "!".repeat(50_000));
+        let chain = vec!["false"; 50_000].join(" || ");
+        for filter in &[parens, nots, chain] {
+            match filter.parse::<Filter>() {
+                Err(FilterError::TooDeep(depth)) => assert_eq!(depth, MAX_DEPTH),
+                other => panic!("expected a nesting depth error, got {:?}", other),
+            }
+        }
+
+        let nested = format!("{}true{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert!(nested.parse::<Filter>().is_ok());
+    }
+}
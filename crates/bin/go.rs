@@ -0,0 +1,407 @@
+use crate::backend::OutputBackend;
+use crate::output::type_key;
+use ezpdb::codegen::definitions::{Definitions, MemberType, Spelling, UniqueNames};
+use ezpdb::codegen::layout::{self, Slot};
+use ezpdb::render::RenderOptions;
+use ezpdb::symbol_types::ParsedPdb;
+use ezpdb::type_info::*;
+use std::io::{self, Write};
+
+/// Go's keywords, which can't be used as a package name
+const KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+/// Turns `name` into an exported Go identifier, e.g. `demo::Point` into
+/// `Demo_Point`. Runs of characters which can't be part of an identifier become
+/// a single underscore.
+fn identifier(name: &str) -> String {
+    let mut identifier = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            identifier.push(c);
+        } else if !identifier.ends_with('_') {
+            identifier.push('_');
+        }
+    }
+
+    let identifier = identifier.trim_matches('_');
+    match identifier.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => {
+            first.to_ascii_uppercase().to_string() + &identifier[1..]
+        }
+        _ => format!("T{}", identifier),
+    }
+}
+
+/// Returns the name of the package to write the types of `pdb_info` into,
+/// which is the PDB's file name without its extension or punctuation
+fn package_name(pdb_info: &ParsedPdb) -> String {
+    let stem = pdb_info
+        .path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let name: String = stem.chars().filter(char::is_ascii_alphanumeric).collect();
+    if name.is_empty()
+        || name.starts_with(|c: char| c.is_ascii_digit())
+        || KEYWORDS.contains(&name.as_str())
+    {
+        format!("pdb{}", name)
+    } else {
+        name
+    }
+}
+
+/// How Go spells the types members are written as. Pointers are written as
+/// integers, since the pointee isn't in Go's address space.
+struct Go;
+
+impl Spelling for Go {
+    fn identifier(&self, name: &str) -> String {
+        identifier(name)
+    }
+
+    fn primitive(&self, kind: PrimitiveKind) -> Option<&'static str> {
+        Some(match kind {
+            PrimitiveKind::Char | PrimitiveKind::RChar | PrimitiveKind::I8 => "int8",
+            PrimitiveKind::UChar | PrimitiveKind::U8 => "uint8",
+            PrimitiveKind::Short | PrimitiveKind::I16 => "int16",
+            PrimitiveKind::UShort
+            | PrimitiveKind::U16
+            | PrimitiveKind::WChar
+            | PrimitiveKind::RChar16
+            | PrimitiveKind::Bool16 => "uint16",
+            PrimitiveKind::Long | PrimitiveKind::I32 | PrimitiveKind::HRESULT => "int32",
+            PrimitiveKind::ULong
+            | PrimitiveKind::U32
+            | PrimitiveKind::RChar32
+            | PrimitiveKind::Bool32 => "uint32",
+            PrimitiveKind::Quad | PrimitiveKind::I64 => "int64",
+            PrimitiveKind::UQuad | PrimitiveKind::U64 | PrimitiveKind::Bool64 => "uint64",
+            PrimitiveKind::F32 | PrimitiveKind::F32PP => "float32",
+            PrimitiveKind::F64 => "float64",
+            PrimitiveKind::Bool8 => "bool",
+            _ => return None,
+        })
+    }
+
+    fn array(&self, len: usize, element: &str) -> String {
+        format!("[{}]{}", len, element)
+    }
+
+    fn bytes(&self, size: usize) -> String {
+        format!("[{}]byte", size)
+    }
+}
+
+/// Writes `rows` as the fields of a struct, aligning their columns the way
+/// `gofmt` does
+fn write_rows(output: &mut impl Write, rows: &[Row]) -> io::Result<()> {
+    let name_width = rows
+        .iter()
+        .filter(|row| !row.ty.is_empty())
+        .map(|row| row.name.len())
+        .max()
+        .unwrap_or(0);
+    let type_width = rows.iter().map(|row| row.ty.len()).max().unwrap_or(0);
+    for row in rows {
+        if row.ty.is_empty() {
+            writeln!(output, "\t{} {}", row.name, row.comment)?;
+        } else {
+            writeln!(
+                output,
+                "\t{:name_width$} {:type_width$} // {}",
+                row.name,
+                row.ty,
+                row.comment,
+                name_width = name_width,
+                type_width = type_width
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A line of a struct definition
+struct Row {
+    name: String,
+    ty: String,
+    comment: String,
+}
+
+struct GoWriter<'a> {
+    definitions: Definitions<'a, Go>,
+}
+
+impl<'a> GoWriter<'a> {
+    fn type_name(&self, ty: &Type) -> String {
+        self.definitions.type_name(ty)
+    }
+
+    fn member_type(&self, id: TypeId) -> MemberType {
+        self.definitions.member_type(id)
+    }
+
+    /// Returns the rows of a struct of `size` bytes with `fields`, padded so
+    /// that each member is at its offset, along with the struct's alignment.
+    /// Members Go would align differently than the PDB's layout are written as
+    /// bytes, and members which overlap the one before them as comments.
+    fn struct_rows(&self, fields: &[TypeId], size: usize) -> (Vec<Row>, usize) {
+        let pdb = self.definitions.pdb;
+        let max_align = layout::max_alignment(size);
+        let mut rows = vec![];
+        let mut used = UniqueNames::default();
+        let mut align = 1;
+        for slot in layout::layout(pdb, fields, size) {
+            let (offset, name, mut ty) = match slot {
+                Slot::Member { offset, name, ty } => {
+                    (offset, identifier(name), self.member_type(ty))
+                }
+                Slot::Base { offset, ty } => {
+                    let name = match type_key(&pdb.types[ty]) {
+                        Some(_) => format!("Base_{}", self.type_name(&pdb.types[ty])),
+                        None => "Base".to_string(),
+                    };
+                    (offset, identifier(&name), self.member_type(ty))
+                }
+                Slot::Bitfields {
+                    offset,
+                    storage,
+                    bits,
+                } => {
+                    let bits: Vec<String> = bits
+                        .iter()
+                        .map(|bits| format!("{}:{}@{}", bits.name, bits.len, bits.position))
+                        .collect();
+                    let storage = self.member_type(storage);
+                    (
+                        offset,
+                        format!("Bitfield_{:X}", offset),
+                        MemberType {
+                            note: Some(bits.join(", ")),
+                            ..storage
+                        },
+                    )
+                }
+                Slot::Padding { offset, len } => {
+                    rows.push(Row {
+                        name: "_".to_string(),
+                        ty: format!("[{}]byte", len),
+                        comment: format!("+0x{:X} padding", offset),
+                    });
+                    continue;
+                }
+                Slot::Overlapping { offset, name, ty } => {
+                    rows.push(Row {
+                        name: format!("// {}", identifier(name)),
+                        ty: String::new(),
+                        comment: format!(
+                            "+0x{:X} {} overlaps the previous member",
+                            offset,
+                            self.type_name(&pdb.types[ty])
+                        ),
+                    });
+                    continue;
+                }
+            };
+
+            if ty.align > max_align || !offset.is_multiple_of(ty.align) {
+                let note = ty.note.take().unwrap_or(ty.name);
+                ty = MemberType::bytes(&Go, ty.size).noted(note);
+            }
+            align = align.max(ty.align);
+            rows.push(Row {
+                name: used.unique(&name),
+                ty: ty.name,
+                comment: match ty.note {
+                    Some(note) => format!("+0x{:X} {}", offset, note),
+                    None => format!("+0x{:X}", offset),
+                },
+            });
+        }
+
+        (rows, align)
+    }
+
+    /// Writes the definition of the class, union, or enum `id`
+    fn write_definition(&mut self, output: &mut impl Write, id: TypeId) -> io::Result<()> {
+        let pdb = self.definitions.pdb;
+        let ty = &pdb.types[id];
+        let name = match self.definitions.name(ty) {
+            Some(name) => name.to_string(),
+            None => return Ok(()),
+        };
+        let size = ty.type_size(pdb);
+
+        match ty {
+            Type::Class(class) => {
+                let (rows, align) = self.struct_rows(&class.fields, size);
+                self.definitions.set_alignment(ty, align);
+
+                let kind = class.kind.to_string().to_ascii_lowercase();
+                writeln!(
+                    output,
+                    "// {} is `{}` ({}, 0x{:X} bytes)",
+                    name, class.name, kind, size
+                )?;
+                writeln!(output, "// unsafe.Sizeof({}{{}}) == 0x{:X}", name, size)?;
+                writeln!(output, "type {} struct {{", name)?;
+                write_rows(output, &rows)?;
+                writeln!(output, "}}")?;
+            }
+            Type::Union(union) => {
+                // Go has no unions, so they're opaque bytes
+                self.definitions.set_alignment(ty, 1);
+
+                writeln!(
+                    output,
+                    "// {} is `{}` (union, 0x{:X} bytes)",
+                    name, union.name, size
+                )?;
+                writeln!(output, "// unsafe.Sizeof({}{{}}) == 0x{:X}", name, size)?;
+                for field in &union.fields {
+                    if let Type::Member(member) = &pdb.types[*field] {
+                        writeln!(
+                            output,
+                            "//   +0x{:X} {} {}",
+                            member.offset,
+                            self.type_name(&pdb.types[member.underlying_type]),
+                            member.name
+                        )?;
+                    }
+                }
+                writeln!(output, "type {} [{}]byte", name, size)?;
+            }
+            Type::Enumeration(e) => {
+                let underlying = self.member_type(e.underlying_type);
+                writeln!(
+                    output,
+                    "// {} is `{}` (enum, 0x{:X} bytes)",
+                    name, e.name, size
+                )?;
+                writeln!(output, "// unsafe.Sizeof({}(0)) == 0x{:X}", name, size)?;
+                writeln!(output, "type {} {}", name, underlying.name)?;
+
+                let signed = underlying.name.starts_with("int");
+                let integer = signed || underlying.name.starts_with("uint");
+                if integer && !e.variants.is_empty() {
+                    let bits = (underlying.size * 8) as u32;
+                    let mut used = UniqueNames::default();
+                    writeln!(output)?;
+                    writeln!(output, "const (")?;
+                    for variant in &e.variants {
+                        let variant_name =
+                            used.unique(&format!("{}_{}", name, identifier(&variant.name)));
+                        let value = variant.value.as_u64();
+                        let value = if signed {
+                            // Sign-extend from the enum's size
+                            let shift = 64u32.saturating_sub(bits);
+                            (((value << shift) as i64) >> shift).to_string()
+                        } else {
+                            let mask = u64::MAX
+                                .checked_shr(64u32.saturating_sub(bits))
+                                .unwrap_or(0);
+                            format!("0x{:X}", value & mask)
+                        };
+                        writeln!(output, "\t{} {} = {}", variant_name, name, value)?;
+                    }
+                    writeln!(output, ")")?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes `types` and the classes, unions, and enums they contain by value as
+/// Go type definitions, each type after the ones it depends upon. Struct fields
+/// are laid out exactly as in the PDB, with blank fields for padding, so
+/// `encoding/binary` or `unsafe` reads of process memory line up. Pointers are
+/// written as integers, and unions and members Go can't place at their offset
+/// are written as bytes.
+pub fn print_go(
+    output: &mut impl Write,
+    pdb: &ParsedPdb,
+    types: &[TypeId],
+    render: &RenderOptions,
+) -> io::Result<()> {
+    let mut writer = GoWriter {
+        definitions: Definitions::new(pdb, types, render, Go, &[]),
+    };
+
+    let file_name = pdb
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    writeln!(
+        output,
+        "// Code generated by pdbview from {}. DO NOT EDIT.",
+        file_name
+    )?;
+    writeln!(output)?;
+    writeln!(output, "package {}", package_name(pdb))?;
+    for id in writer.definitions.order.clone() {
+        writeln!(output)?;
+        writer.write_definition(output, id)?;
+    }
+
+    Ok(())
+}
+
+/// The `go` output format, which writes every class, union, and enum as a Go
+/// type definition
+pub struct GoStructs {
+    pub render: RenderOptions,
+}
+
+impl OutputBackend for GoStructs {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["go"]
+    }
+
+    fn renders_types(&self) -> bool {
+        true
+    }
+
+    fn render(
+        &self,
+        pdb_info: &ParsedPdb,
+        types: &[TypeId],
+        mut output: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        Ok(print_go(&mut output, pdb_info, types, &self.render)?)
+    }
+}
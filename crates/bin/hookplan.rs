@@ -0,0 +1,80 @@
+//! The `hookplan` output format: the boundaries of each procedure and where
+//! its prologue and epilogue are, for instrumentation engines deciding where
+//! and whether a procedure can be hooked
+use crate::backend::OutputBackend;
+use ezpdb::symbol_types::*;
+use ezpdb::type_info::TypeId;
+use serde_json::json;
+use std::io::{self, Write};
+
+/// Writes a JSON hook plan for the procedures of `pdb_info` which have an
+/// address. Procedures whose prologue or body is shorter than `patch_size`
+/// bytes carry warnings, as patching their entry would overwrite code past the
+/// prologue or past the procedure's end.
+pub fn print_hook_plan(
+    output: &mut impl Write,
+    pdb_info: &ParsedPdb,
+    patch_size: usize,
+) -> io::Result<()> {
+    let procedures: Vec<_> = pdb_info
+        .procedures
+        .iter()
+        .filter_map(|procedure| {
+            let address = procedure.address?;
+
+            let mut warnings = vec![];
+            if procedure.len < patch_size {
+                warnings.push(format!(
+                    "the procedure is {} bytes, shorter than the {} byte patch",
+                    procedure.len, patch_size
+                ));
+            } else if procedure.prologue_end < patch_size {
+                warnings.push(format!(
+                    "the prologue is {} bytes, shorter than the {} byte patch",
+                    procedure.prologue_end, patch_size
+                ));
+            }
+
+            Some(json!({
+                "name": procedure.name,
+                "public_name": procedure.public_name,
+                "rva": address.rva,
+                "virtual_address": address.virtual_address,
+                "len": procedure.len,
+                "prologue_end": procedure.prologue_end,
+                "epilogue_start": procedure.epilogue_start,
+                "warnings": warnings,
+            }))
+        })
+        .collect();
+
+    let plan = json!({
+        "pdb": pdb_info.path,
+        "guid": pdb_info.guid.to_string(),
+        "age": pdb_info.age,
+        "patch_size": patch_size,
+        "procedures": procedures,
+    });
+
+    write!(output, "{}", plan)
+}
+
+/// The `hookplan` output format
+pub struct HookPlan {
+    pub patch_size: usize,
+}
+
+impl OutputBackend for HookPlan {
+    fn name(&self) -> &'static str {
+        "hookplan"
+    }
+
+    fn render(
+        &self,
+        pdb_info: &ParsedPdb,
+        _types: &[TypeId],
+        mut output: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        Ok(print_hook_plan(&mut output, pdb_info, self.patch_size)?)
+    }
+}
@@ -0,0 +1,755 @@
+use crate::output::{
+    bitfield_member, format_address, format_bitfield_position, format_data_scope,
+    format_initializer_target, format_module_name, format_procedure_flags, format_procedure_name,
+    format_source_range, format_storage, format_type_name, is_listed_type, layout_fields,
+    module_source_count, ListingOptions, OutputSection, FASTLINK_GUIDANCE,
+};
+use ezpdb::render::RenderOptions;
+use ezpdb::symbol_types::*;
+use ezpdb::type_info::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.4em; word-break: break-all; }
+h2 { margin-top: 1.5em; border-bottom: 1px solid #ccc; }
+table { border-collapse: collapse; font-family: monospace; }
+th, td { text-align: left; padding: 0.1em 1em 0.1em 0; vertical-align: top; }
+th { border-bottom: 1px solid #ccc; }
+details { font-family: monospace; margin: 0.2em 0; }
+details > table { margin: 0.3em 0 0.6em 2em; }
+summary { cursor: pointer; }
+.address { color: #a06000; }
+.name { color: #106010; }
+.type { color: #0060a0; }
+.kind { color: #777; }
+#search { position: sticky; top: 0; padding: 0.5em 0; background: #fff; }
+#search input { width: 30em; padding: 0.3em; font-size: 1em; }
+.hidden { display: none; }
+"#;
+
+const SCRIPT: &str = r#"
+const input = document.getElementById("search-input");
+const count = document.getElementById("search-count");
+const items = document.querySelectorAll("tr.item, details.item");
+input.addEventListener("input", () => {
+  const needle = input.value.toLowerCase();
+  let matches = 0;
+  for (const item of items) {
+    const text = (item.dataset.name || item.textContent).toLowerCase();
+    const hidden = needle.length > 0 && !text.includes(needle);
+    item.classList.toggle("hidden", hidden);
+    if (!hidden) matches++;
+  }
+  count.textContent = needle.length > 0 ? matches + " matches" : "";
+});
+function openTarget() {
+  const target = document.getElementById(location.hash.slice(1));
+  if (target && target.tagName === "DETAILS") {
+    target.open = true;
+    target.classList.remove("hidden");
+  }
+}
+window.addEventListener("hashchange", openTarget);
+openTarget();
+"#;
+
+/// Escapes text for inclusion in HTML element content or attribute values
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&#39;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a single self-contained HTML page describing `pdb_info`. Types are
+/// rendered as collapsible trees whose field types link to their definitions,
+/// and a search box filters every listing by name. Type names are formatted as
+/// `render` says.
+pub fn print_html(
+    output: &mut impl Write,
+    pdb_info: &ParsedPdb,
+    listing: &ListingOptions,
+    flatten_anonymous: bool,
+    render: &RenderOptions,
+) -> io::Result<()> {
+    let title = pdb_info.path.display().to_string();
+
+    writeln!(output, "<!DOCTYPE html>")?;
+    writeln!(output, "<html lang=\"en\">")?;
+    writeln!(output, "<head>")?;
+    writeln!(output, "<meta charset=\"utf-8\">")?;
+    writeln!(output, "<title>{}</title>", Escape(&title))?;
+    writeln!(output, "<style>{}</style>", STYLE)?;
+    writeln!(output, "</head>")?;
+    writeln!(output, "<body>")?;
+
+    // region: Header info
+    if listing.shows(OutputSection::Header) {
+        writeln!(output, "<h1>{}</h1>", Escape(&title))?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>PDB Version</th><td>{}</td></tr>",
+            Escape(&format!("{:?}", pdb_info.version))
+        )?;
+        writeln!(
+            output,
+            "<tr><th>Machine Type</th><td>{}</td></tr>",
+            Escape(
+                &pdb_info
+                    .machine_type
+                    .as_ref()
+                    .map(|ty| format!("{:?}", ty))
+                    .unwrap_or_else(|| "Unknown".to_string())
+            )
+        )?;
+        if let Some(compiler_info) = &pdb_info.assembly_info.compiler_info {
+            writeln!(
+                output,
+                "<tr><th>Language</th><td>{}</td></tr>",
+                Escape(&compiler_info.language.to_string())
+            )?;
+        }
+        writeln!(output, "</table>")?;
+        if pdb_info.is_fastlink {
+            writeln!(output, "<p>{}</p>", Escape(FASTLINK_GUIDANCE))?;
+        }
+    }
+    // endregion
+
+    writeln!(
+        output,
+        "<div id=\"search\"><input id=\"search-input\" type=\"search\" \
+         placeholder=\"Search symbols and types\" autofocus> <span id=\"search-count\"></span></div>"
+    )?;
+
+    // region: Public symbols
+    if listing.shows(OutputSection::PublicSymbols) {
+        writeln!(output, "<h2>Public symbols</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(output, "<tr><th>Offset</th><th>Name</th></tr>")?;
+        for symbol in listing.select(pdb_info, &pdb_info.public_symbols) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                 <td class=\"name\">{name}</td></tr>",
+                format_address(symbol.offset),
+                name = Escape(&symbol.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Procedures
+    if listing.shows(OutputSection::Procedures) {
+        writeln!(output, "<h2>Procedures</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Length</th><th>Prologue End</th><th>Epilogue Start</th><th>Flags</th><th>Source</th><th>Name</th></tr>"
+        )?;
+        for procedure in listing.select(pdb_info, &pdb_info.procedures) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                 <td>0x{:08X}</td><td>0x{:08X}</td><td>0x{:08X}</td><td>{}</td><td title=\"{}\">{}</td><td class=\"name\">{name}</td></tr>",
+                format_address(procedure.address),
+                procedure.len,
+                procedure.prologue_end,
+                procedure.epilogue_start,
+                format_procedure_flags(procedure),
+                Escape(procedure.source.as_ref().map_or("", |source| source.file.as_str())),
+                Escape(&format_source_range(procedure.source.as_ref())),
+                name = Escape(&format_procedure_name(pdb_info, procedure, render)),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Data
+    if listing.shows(OutputSection::Globals) {
+        writeln!(output, "<h2>Globals</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Size</th><th>Storage</th><th>Managed</th><th>Type</th><th>Name</th></tr>"
+        )?;
+        for global in listing.select(pdb_info, &pdb_info.global_data) {
+            let ty: &Type = &pdb_info.types[global.ty];
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                 <td>0x{:X}</td><td>{}</td><td>{}</td><td class=\"type\">{}</td><td class=\"name\">{name}</td></tr>",
+                format_address(global.offset),
+                ty.type_size(pdb_info),
+                format_storage(global),
+                global.is_managed,
+                Escape(&format_type_name(pdb_info, ty, render)),
+                name = Escape(&global.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Local data
+    if listing.shows(OutputSection::LocalData) && !pdb_info.local_data.is_empty() {
+        writeln!(output, "<h2>Local data</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Size</th><th>Storage</th><th>Type</th><th>Scope</th><th>Name</th></tr>"
+        )?;
+        for data in listing.select(pdb_info, &pdb_info.local_data) {
+            let ty: &Type = &pdb_info.types[data.ty];
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                 <td>0x{:X}</td><td>{}</td><td class=\"type\">{}</td><td>{}</td><td class=\"name\">{name}</td></tr>",
+                format_address(data.offset),
+                ty.type_size(pdb_info),
+                format_storage(data),
+                Escape(&format_type_name(pdb_info, ty, render)),
+                Escape(&format_data_scope(data)),
+                name = Escape(&data.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Annotations
+    if listing.shows(OutputSection::Annotations) && !pdb_info.annotations.is_empty() {
+        writeln!(output, "<h2>Annotations</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(output, "<tr><th>Offset</th><th>Strings</th></tr>")?;
+        for annotation in listing.select(pdb_info, &pdb_info.annotations) {
+            writeln!(
+                output,
+                "<tr class=\"item\"><td class=\"address\">{}</td><td class=\"name\">{}</td></tr>",
+                format_address(annotation.offset),
+                Escape(&annotation.strings.join(" ")),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Trampolines
+    if listing.shows(OutputSection::Trampolines) && !pdb_info.trampolines.is_empty() {
+        writeln!(output, "<h2>Trampolines</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Size</th><th>Kind</th><th>Target</th></tr>"
+        )?;
+        for trampoline in listing.select(pdb_info, &pdb_info.trampolines) {
+            writeln!(
+                output,
+                "<tr class=\"item\"><td class=\"address\">{}</td><td>0x{:X}</td>\
+                 <td>{:?}</td><td class=\"address\">{}</td></tr>",
+                format_address(trampoline.offset),
+                trampoline.size,
+                trampoline.kind,
+                format_address(trampoline.target),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Separated code
+    if listing.shows(OutputSection::SeparatedCode) && !pdb_info.separated_code.is_empty() {
+        writeln!(output, "<h2>Separated code</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Length</th><th>Parent</th></tr>"
+        )?;
+        for code in listing.select(pdb_info, &pdb_info.separated_code) {
+            writeln!(
+                output,
+                "<tr class=\"item\"><td class=\"address\">{}</td><td>0x{:08X}</td>\
+                 <td class=\"address\">{}</td></tr>",
+                format_address(code.offset),
+                code.len,
+                format_address(code.parent_offset),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Calls
+    if listing.shows(OutputSection::Calls) {
+        if !pdb_info.call_sites.is_empty() {
+            writeln!(output, "<h2>Call sites</h2>")?;
+            writeln!(output, "<table>")?;
+            writeln!(
+                output,
+                "<tr><th>Offset</th><th>Caller</th><th>Signature</th></tr>"
+            )?;
+            for site in listing.select(pdb_info, &pdb_info.call_sites) {
+                writeln!(
+                    output,
+                    "<tr class=\"item\"><td class=\"address\">{}</td><td class=\"name\">{}</td>\
+                     <td class=\"type\">{}</td></tr>",
+                    format_address(site.offset),
+                    Escape(site.caller.as_deref().unwrap_or_default()),
+                    Escape(
+                        &site
+                            .ty
+                            .map(|ty| format_type_name(pdb_info, &pdb_info.types[ty], render))
+                            .unwrap_or_default()
+                    ),
+                )?;
+            }
+            writeln!(output, "</table>")?;
+        }
+
+        if !pdb_info.call_graph.is_empty() {
+            writeln!(output, "<h2>Call graph</h2>")?;
+            writeln!(output, "<table>")?;
+            writeln!(
+                output,
+                "<tr><th>Invocations</th><th>Caller</th><th>Callee</th></tr>"
+            )?;
+            for edge in listing.select(pdb_info, &pdb_info.call_graph) {
+                writeln!(
+                    output,
+                    "<tr class=\"item\"><td>{}</td><td class=\"name\">{}</td><td class=\"name\">{}</td></tr>",
+                    edge.invocations
+                        .map(|count| count.to_string())
+                        .unwrap_or_default(),
+                    Escape(&edge.caller),
+                    Escape(&edge.callee),
+                )?;
+            }
+            writeln!(output, "</table>")?;
+        }
+    }
+    // endregion
+
+    // region: Sections
+    if listing.shows(OutputSection::Sections) {
+        if !pdb_info.sections.is_empty() {
+            writeln!(output, "<h2>Sections</h2>")?;
+            writeln!(output, "<table>")?;
+            writeln!(
+                output,
+                "<tr><th>Offset</th><th>Size</th><th>Characteristics</th><th>Name</th></tr>"
+            )?;
+            for section in listing.select(pdb_info, &pdb_info.sections) {
+                writeln!(
+                    output,
+                    "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                     <td>0x{:X}</td><td>0x{:08X}</td><td class=\"name\">{name}</td></tr>",
+                    format_address(Some(section.offset)),
+                    section.len,
+                    section.characteristics,
+                    name = Escape(&section.name),
+                )?;
+            }
+            writeln!(output, "</table>")?;
+        }
+
+        if !pdb_info.coff_groups.is_empty() {
+            writeln!(output, "<h2>COFF groups</h2>")?;
+            writeln!(output, "<table>")?;
+            writeln!(
+                output,
+                "<tr><th>Offset</th><th>Size</th><th>Characteristics</th><th>Name</th></tr>"
+            )?;
+            for group in listing.select(pdb_info, &pdb_info.coff_groups) {
+                writeln!(
+                    output,
+                    "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                     <td>0x{:X}</td><td>0x{:08X}</td><td class=\"name\">{name}</td></tr>",
+                    format_address(group.offset),
+                    group.len,
+                    group.characteristics,
+                    name = Escape(&group.name),
+                )?;
+            }
+            writeln!(output, "</table>")?;
+        }
+    }
+    // endregion
+
+    // region: Static initializers
+    if listing.shows(OutputSection::StaticInitializers) && !pdb_info.static_initializers.is_empty()
+    {
+        writeln!(output, "<h2>Static initializers</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Kind</th><th>Target</th><th>Name</th></tr>"
+        )?;
+        for initializer in listing.select(pdb_info, &pdb_info.static_initializers) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                 <td>{:?}</td><td>{}</td><td class=\"name\">{name}</td></tr>",
+                format_address(initializer.offset),
+                initializer.kind,
+                Escape(&format_initializer_target(initializer)),
+                name = Escape(&initializer.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Security metadata
+    if listing.shows(OutputSection::SecuritySymbols) && !pdb_info.security_symbols.is_empty() {
+        writeln!(output, "<h2>Security metadata</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Mitigation</th><th>Name</th></tr>"
+        )?;
+        for symbol in listing.select(pdb_info, &pdb_info.security_symbols) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td class=\"address\">{}</td>\
+                 <td>{}</td><td class=\"name\">{name}</td></tr>",
+                format_address(symbol.offset),
+                symbol.feature,
+                name = Escape(&symbol.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Provenance
+    if listing.shows(OutputSection::Provenance) && !pdb_info.provenance.is_empty() {
+        writeln!(output, "<h2>Provenance</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Kind</th><th>Modules</th><th>Value</th></tr>"
+        )?;
+        for string in listing.select(pdb_info, &pdb_info.provenance) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{value}\"><td>{}</td><td>{}</td>\
+                 <td class=\"name\">{value}</td></tr>",
+                string.kind,
+                string.modules,
+                value = Escape(&string.value),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Fastlink references
+    if listing.shows(OutputSection::FastlinkReferences) && !pdb_info.fastlink_references.is_empty()
+    {
+        writeln!(output, "<h2>Fastlink references</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Kind</th><th>Local</th><th>Module</th><th>Name</th></tr>"
+        )?;
+        for reference in listing.select(pdb_info, &pdb_info.fastlink_references) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td>{:?}</td><td>{}</td>\
+                 <td>{}</td><td class=\"name\">{name}</td></tr>",
+                reference.kind,
+                reference.is_local,
+                Escape(&format_module_name(pdb_info, reference.module_index)),
+                name = Escape(&reference.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Managed procedures
+    if listing.shows(OutputSection::ManagedProcedures) && !pdb_info.managed_procedures.is_empty() {
+        writeln!(output, "<h2>Managed procedures</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Offset</th><th>Len</th><th>Token</th><th>Module</th><th>Name</th></tr>"
+        )?;
+        for procedure in listing.select(pdb_info, &pdb_info.managed_procedures) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td>{}</td><td>0x{:X}</td>\
+                 <td>0x{:08X}</td><td>{}</td><td class=\"name\">{name}</td></tr>",
+                format_address(procedure.address),
+                procedure.len,
+                procedure.token,
+                Escape(&format_module_name(pdb_info, procedure.module_index)),
+                name = Escape(&procedure.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Heap allocation sites
+    if listing.shows(OutputSection::HeapAllocationSites)
+        && !pdb_info.heap_allocation_sites.is_empty()
+    {
+        writeln!(output, "<h2>Heap allocation sites</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(output, "<tr><th>Offset</th><th>Size</th><th>Type</th></tr>")?;
+        for site in listing.select(pdb_info, &pdb_info.heap_allocation_sites) {
+            let ty: &Type = &pdb_info.types[site.ty];
+            writeln!(
+                output,
+                "<tr class=\"item\"><td class=\"address\">{}</td><td>0x{:X}</td>\
+                 <td class=\"type\">{}</td></tr>",
+                format_address(site.offset),
+                ty.type_size(pdb_info),
+                Escape(&format_type_name(pdb_info, ty, render)),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Modules
+    if listing.shows(OutputSection::Modules) && !pdb_info.debug_modules.is_empty() {
+        writeln!(output, "<h2>Modules</h2>")?;
+        writeln!(output, "<table>")?;
+        writeln!(
+            output,
+            "<tr><th>Size</th><th>Symbols</th><th>Types</th><th>Sources</th><th>Library</th><th>Name</th></tr>"
+        )?;
+        for module in listing.pagination.slice(&pdb_info.debug_modules) {
+            writeln!(
+                output,
+                "<tr class=\"item\" data-name=\"{name}\"><td>0x{:X}</td><td>{}</td><td>{}</td>\
+                 <td>{}</td><td>{}</td><td class=\"name\">{name}</td></tr>",
+                module.contribution_size,
+                module.symbol_count,
+                module.type_count,
+                module_source_count(module),
+                Escape(module.library.as_deref().unwrap_or_default()),
+                name = Escape(&module.name),
+            )?;
+        }
+        writeln!(output, "</table>")?;
+    }
+    // endregion
+
+    // region: Types
+    if listing.shows(OutputSection::Types) {
+        writeln!(output, "<h2>Types</h2>")?;
+        let types: Vec<&Type> = pdb_info
+            .types
+            .iter()
+            .map(|(_id, ty)| ty)
+            .filter(|ty| is_listed_type(ty))
+            .collect();
+        let types = listing.select(pdb_info, &types);
+
+        // Field types link to the definition of the type they refer to, which may be
+        // a different type record than the field's (e.g. a forward reference)
+        let anchors: HashMap<&str, usize> = types
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ty)| type_name(ty).map(|name| (name, i)))
+            .collect();
+
+        for (i, ty) in types.iter().enumerate() {
+            print_type(output, pdb_info, ty, i, &anchors, flatten_anonymous, render)?;
+        }
+    }
+    // endregion
+
+    writeln!(output, "<script>{}</script>", SCRIPT)?;
+    writeln!(output, "</body>")?;
+    writeln!(output, "</html>")?;
+
+    Ok(())
+}
+
+fn print_type(
+    output: &mut impl Write,
+    pdb_info: &ParsedPdb,
+    ty: &Type,
+    index: usize,
+    anchors: &HashMap<&str, usize>,
+    flatten_anonymous: bool,
+    render: &RenderOptions,
+) -> io::Result<()> {
+    let (kind, name, size, packing) = match ty {
+        Type::Class(class) => (
+            class.kind.to_string(),
+            &class.name,
+            class.size,
+            class.packing,
+        ),
+        Type::Union(union) => ("Union".to_string(), &union.name, union.size, union.packing),
+        Type::Enumeration(e) => {
+            let kind = if e.is_flags(pdb_info) {
+                "Flags enum"
+            } else {
+                "Enum"
+            };
+            (kind.to_string(), &e.name, ty.type_size(pdb_info), None)
+        }
+        _ => return Ok(()),
+    };
+
+    writeln!(
+        output,
+        "<details class=\"item\" id=\"type-{}\" data-name=\"{name}\"><summary>\
+         <span class=\"kind\">{}</span> <span class=\"type\">{name}</span> \
+         <span class=\"kind\">(0x{:X} bytes{})</span></summary>",
+        index,
+        Escape(&kind),
+        size,
+        packing
+            .map(|packing| format!(", packed to {}", packing))
+            .unwrap_or_default(),
+        name = Escape(name),
+    )?;
+    writeln!(output, "<table>")?;
+
+    match ty {
+        Type::Class(Class { fields, .. }) | Type::Union(Union { fields, .. }) => {
+            writeln!(output, "<tr><th>Offset</th><th>Name</th><th>Type</th></tr>")?;
+            let fields = layout_fields(pdb_info, fields, flatten_anonymous);
+            // The storage unit shared by the bitfields listed most recently
+            let mut storage = None;
+            for field in &fields {
+                let bitfield = bitfield_member(pdb_info, field);
+                let field_storage =
+                    bitfield.map(|(member, bitfield)| (member.offset, bitfield.underlying_type));
+                if let Some((offset, storage_type)) =
+                    field_storage.filter(|_| field_storage != storage)
+                {
+                    writeln!(
+                        output,
+                        "<tr><td class=\"address\">0x{:04X}</td><td>&lt;Bitfields&gt;</td><td class=\"type\">{}</td></tr>",
+                        offset,
+                        TypeLink {
+                            pdb_info,
+                            ty: &pdb_info.types[storage_type],
+                            anchors,
+                            render,
+                        },
+                    )?;
+                }
+                storage = field_storage;
+
+                if let Some((member, bitfield)) = bitfield {
+                    writeln!(
+                        output,
+                        "<tr><td></td><td class=\"name\">&nbsp;&nbsp;{}</td><td class=\"type\">{}</td></tr>",
+                        Escape(&member.name),
+                        format_bitfield_position(bitfield),
+                    )?;
+                    continue;
+                }
+
+                let (offset, name, field_ty) = match field.as_ref() {
+                    Type::Member(member) => (member.offset, &*member.name, member.underlying_type),
+                    Type::BaseClass(base) => (base.offset, "<BaseClass>", base.base_class),
+                    _ => continue,
+                };
+
+                writeln!(
+                    output,
+                    "<tr><td class=\"address\">0x{:04X}</td><td class=\"name\">{}</td><td class=\"type\">{}</td></tr>",
+                    offset,
+                    Escape(name),
+                    TypeLink {
+                        pdb_info,
+                        ty: &pdb_info.types[field_ty],
+                        anchors,
+                        render,
+                    },
+                )?;
+            }
+        }
+        Type::Enumeration(e) => {
+            writeln!(output, "<tr><th>Value</th><th>Name</th></tr>")?;
+            for variant in &e.variants {
+                let value = e.variant_value(variant, pdb_info);
+
+                writeln!(
+                    output,
+                    "<tr><td>0x{:08X}</td><td class=\"name\">{}</td></tr>",
+                    value,
+                    Escape(&variant.name)
+                )?;
+            }
+        }
+        _ => unreachable!(),
+    }
+
+    writeln!(output, "</table>")?;
+    writeln!(output, "</details>")
+}
+
+/// A formatted type name which links to the definition of the class, union, or
+/// enum it refers to, if that type is part of the report
+struct TypeLink<'a> {
+    pdb_info: &'a ParsedPdb,
+    ty: &'a Type,
+    anchors: &'a HashMap<&'a str, usize>,
+    render: &'a RenderOptions,
+}
+
+impl fmt::Display for TypeLink<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let formatted = format_type_name(self.pdb_info, self.ty, self.render);
+        let anchor = referenced_type(self.pdb_info, self.ty)
+            .and_then(type_name)
+            .and_then(|name| self.anchors.get(name));
+
+        match anchor {
+            Some(index) => write!(f, "<a href=\"#type-{}\">{}</a>", index, Escape(&formatted)),
+            None => write!(f, "{}", Escape(&formatted)),
+        }
+    }
+}
+
+/// Follows pointers, modifiers, and arrays to the type they ultimately refer to
+fn referenced_type<'a>(pdb_info: &'a ParsedPdb, ty: &'a Type) -> Option<&'a Type> {
+    match ty {
+        Type::Pointer(pointer) => pointer
+            .underlying_type
+            .and_then(|id| referenced_type(pdb_info, &pdb_info.types[id])),
+        Type::Modifier(modifier) => {
+            referenced_type(pdb_info, &pdb_info.types[modifier.underlying_type])
+        }
+        Type::Array(array) => referenced_type(pdb_info, &pdb_info.types[array.element_type]),
+        other => Some(other),
+    }
+}
+
+fn type_name(ty: &Type) -> Option<&str> {
+    match ty {
+        Type::Class(class) => Some(&class.name),
+        Type::Union(union) => Some(&union.name),
+        Type::Enumeration(e) => Some(&e.name),
+        _ => None,
+    }
+}
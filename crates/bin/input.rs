@@ -0,0 +1,37 @@
+use std::io::{self, BufWriter};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// The file name which reads the input from stdin
+pub const STDIN_PATH: &str = "-";
+
+/// The name input read from stdin is reported by
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// Input read from stdin and buffered into a temporary file, since PDBs are
+/// read out of order. The file is removed when this is dropped.
+pub struct StdinFile {
+    file: NamedTempFile,
+}
+
+impl StdinFile {
+    /// Copies all of stdin into a new temporary file. The file gets a random
+    /// name and is created exclusively, so an existing file or link by its name
+    /// is never written through.
+    pub fn read() -> io::Result<Self> {
+        let file = tempfile::Builder::new()
+            .prefix("pdbview-stdin.")
+            .suffix(".pdb")
+            .tempfile()?;
+
+        let mut writer = BufWriter::new(file.as_file());
+        io::copy(&mut io::stdin().lock(), &mut writer)?;
+        writer.into_inner().map_err(|e| e.into_error())?;
+
+        Ok(StdinFile { file })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
@@ -0,0 +1,141 @@
+use crate::CliArgumentError;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Metadata};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::{Context, Filter, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{filter::FilterExt, Layer};
+
+/// Warnings logged so far. Set once logging is initialized.
+static WARNINGS: OnceLock<Mutex<HashMap<WarningKey, usize>>> = OnceLock::new();
+
+/// How log messages are written to stderr
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One human-readable line per message
+    Plain,
+    /// One JSON object per line with the message's timestamp, level, target,
+    /// fields, and the spans it was logged in
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = CliArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let result = match s.to_ascii_lowercase().as_ref() {
+            "plain" => LogFormat::Plain,
+            "json" => LogFormat::Json,
+            _ => return Err(CliArgumentError::InvalidValue("log-format", s.to_string())),
+        };
+
+        Ok(result)
+    }
+}
+
+/// Installs a subscriber which writes messages at `level` and above to stderr.
+/// The start and end of each span, such as those of the parsing stages, are
+/// logged at the span's level.
+pub fn init(level: LevelFilter, format: LogFormat) -> anyhow::Result<()> {
+    if WARNINGS.set(Mutex::new(HashMap::new())).is_err() {
+        anyhow::bail!("logging was already initialized");
+    }
+
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE);
+    let layer = match format {
+        LogFormat::Plain => layer.with_ansi(false).boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    };
+    tracing_subscriber::registry()
+        .with(layer.with_filter(level.and(DeduplicateWarnings)))
+        .try_init()?;
+
+    Ok(())
+}
+
+/// Logs how many times each warning was repeated, word for word, after it was
+/// first logged. Does nothing if logging wasn't initialized.
+pub fn summarize_repeated_warnings() {
+    let warnings = match WARNINGS.get().map(Mutex::lock) {
+        Some(Ok(mut warnings)) => std::mem::take(&mut *warnings),
+        _ => return,
+    };
+
+    let mut repeated: Vec<(WarningKey, usize)> = warnings
+        .into_iter()
+        .filter(|(_, repeats)| *repeats > 0)
+        .collect();
+    // Most repeated first, in a stable order otherwise
+    repeated
+        .sort_by(|(a_key, a), (b_key, b)| b.cmp(a).then_with(|| a_key.message.cmp(&b_key.message)));
+    for (key, repeats) in repeated {
+        tracing::warn!(repeated = repeats, "{}", key.message);
+    }
+}
+
+/// Where a warning was logged from, and its text
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct WarningKey {
+    file: Option<&'static str>,
+    line: Option<u32>,
+    message: String,
+}
+
+/// Reads the message of an event
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+/// Lets through only the first of identical warnings logged from the same
+/// place in the code, counting the rest for [summarize_repeated_warnings].
+/// Stripped PDBs can otherwise produce thousands of copies of the same warning.
+struct DeduplicateWarnings;
+
+impl<S> Filter<S> for DeduplicateWarnings {
+    fn enabled(&self, _metadata: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        true
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, _cx: &Context<'_, S>) -> bool {
+        let metadata = event.metadata();
+        if *metadata.level() != Level::WARN {
+            return true;
+        }
+        let mut warnings = match WARNINGS.get().map(Mutex::lock) {
+            Some(Ok(warnings)) => warnings,
+            _ => return true,
+        };
+
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+        let key = WarningKey {
+            file: metadata.file(),
+            line: metadata.line(),
+            message: message.0,
+        };
+        match warnings.get_mut(&key) {
+            Some(repeats) => {
+                *repeats += 1;
+                false
+            }
+            None => {
+                warnings.insert(key, 0);
+                true
+            }
+        }
+    }
+}
@@ -1,9 +1,36 @@
-use std::path::PathBuf;
+use anyhow::Context;
+use destination::{AtomicFile, Destination};
+use ezpdb::render::{Language, RenderOptions};
+use ezpdb::sources::SourceStatus;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Instant;
+use structopt::clap::{Error as ClapError, ErrorKind};
 use structopt::StructOpt;
+use termcolor::{BufferedStandardStream, ColorChoice};
 use thiserror::Error;
+use tracing::level_filters::LevelFilter;
 
+mod backend;
+mod destination;
+mod diff;
+mod dot;
+mod exit;
+mod expect;
+mod filter;
+mod go;
+mod hookplan;
+mod html;
+mod input;
+mod logging;
+mod names;
 mod output;
+mod pagination;
+mod progress;
+mod table;
+mod yara;
+mod zig;
 
 #[derive(Error, Debug)]
 pub enum CliArgumentError {
@@ -14,28 +41,713 @@ pub enum CliArgumentError {
 #[derive(StructOpt, Debug)]
 #[structopt(name = "pdbview")]
 struct Opt {
-    /// Print debug information
-    #[structopt(short, long)]
+    /// Print debug information. Equivalent to `--log-level debug`
+    #[structopt(short, long, global = true)]
     debug: bool,
 
-    /// Output format type. Options include: plain, json
-    #[structopt(short, long, default_value = "plain")]
-    format: OutputFormatType,
+    /// Only log errors
+    #[structopt(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log more: `-v` adds the start and end of each parsing stage, `-vv` adds
+    /// debug information, and `-vvv` adds every parsed type. By default errors
+    /// and warnings are logged, with each repeated warning logged once and
+    /// counted at the end of the run
+    #[structopt(short, long, global = true, parse(from_occurrences))]
+    verbose: u8,
+
+    /// Log messages at this level and above to stderr: off, error, warn, info,
+    /// debug, or trace. Overrides `--quiet` and `--verbose`
+    #[structopt(long, global = true)]
+    log_level: Option<LevelFilter>,
+
+    /// Format of log messages: plain, or json (one object per line). Defaults
+    /// to plain
+    #[structopt(long, global = true)]
+    log_format: Option<logging::LogFormat>,
+
+    /// Display a progress bar on stderr while parsing
+    #[structopt(short, long)]
+    progress: bool,
+
+    /// Print the time and peak memory spent in each parsing stage to stderr
+    #[structopt(long)]
+    timing: bool,
+
+    /// Print public symbols and procedures as they're parsed instead of waiting
+    /// for the entire PDB to be parsed. All other sections are omitted. Only
+    /// supported by the plain output format
+    #[structopt(short, long)]
+    stream: bool,
+
+    /// Number of items to skip at the start of each listing (public symbols,
+    /// procedures, globals, types, etc.)
+    #[structopt(long, default_value = "0")]
+    offset: usize,
+
+    /// Maximum number of items to print in each listing
+    #[structopt(long)]
+    limit: Option<usize>,
+
+    /// Print only the first N items of each listing. Equivalent to `--limit`
+    #[structopt(long, conflicts_with = "limit")]
+    head: Option<usize>,
+
+    /// Print only the last N items of each listing
+    #[structopt(long, conflicts_with = "stream")]
+    tail: Option<usize>,
+
+    /// Only output symbols and types matching an expression, e.g.
+    /// `kind == 'procedure' && len > 0x1000 && name =~ 'Crypt'`. Supported operators are
+    /// `==`, `!=`, `<`, `<=`, `>`, `>=`, `=~` (wildcard/substring match), `&&`, `||`, and `!`
+    #[structopt(long = "where", conflicts_with = "stream")]
+    filter: Option<filter::Filter>,
+
+    /// Comma-separated blocks of the plain and HTML output to print, e.g.
+    /// `header,procedures`. Options include: header, publics, procedures,
+    /// globals, locals, annotations, trampolines, separated-code, calls,
+    /// sections, initializers, security, provenance, fastlink, managed,
+    /// allocations, modules, types. By default all are printed
+    #[structopt(long, use_delimiter = true, conflicts_with = "stream")]
+    sections: Vec<output::OutputSection>,
+
+    /// Comma-separated checks the PDB must pass, failing the run otherwise:
+    /// non-empty-publics, non-empty-procedures, non-empty-globals,
+    /// non-empty-types, non-empty-modules, no-diagnostics
+    #[structopt(long, use_delimiter = true, conflicts_with = "stream")]
+    expect: Vec<expect::Expectation>,
+
+    /// Also list file- and function-static variables along with the module and
+    /// function which own them
+    #[structopt(long, conflicts_with = "stream")]
+    include_local_data: bool,
+
+    /// Which problems found while parsing stop the run: never, corrupt
+    /// (malformed records), unknown-kind (also records of unsupported kinds),
+    /// or any (also unresolved types and missing streams). Problems which don't
+    /// stop the run are skipped and listed in the JSON output's diagnostics.
+    /// Defaults to corrupt
+    #[structopt(long, global = true, parse(try_from_str = parse_failure_policy))]
+    fail_on: Option<ezpdb::FailurePolicy>,
+
+    /// Omit public symbols which share an address with a procedure. The procedure
+    /// listing includes the public symbol's name instead
+    #[structopt(long, conflicts_with = "stream")]
+    dedup: bool,
+
+    /// Only list procedures which were most likely written by: user, runtime
+    /// (the C runtime and other libraries shipped with the compiler or OS), stl,
+    /// or compiler-generated. Origins are guessed from procedure and module names
+    #[structopt(long, conflicts_with = "stream", parse(try_from_str = parse_origin))]
+    only: Option<ezpdb::symbol_types::CodeOrigin>,
+
+    /// Only list procedures defined in a source file whose path matches a
+    /// wildcard pattern, e.g. `*crypto*.c`. Paths are matched case-insensitively
+    #[structopt(long = "file", conflicts_with = "stream")]
+    source_file: Option<String>,
+
+    /// Sort the public symbols, procedures, and globals listings. Options include:
+    /// address, name, size, file (groups procedures by source file and orders
+    /// them by line)
+    #[structopt(long, conflicts_with = "stream")]
+    sort: Option<SortKey>,
+
+    /// Size in bytes of the patch written over a procedure's entry by the
+    /// hookplan format's consumer. Procedures whose prologue is shorter are
+    /// flagged with a warning
+    #[structopt(long, default_value = "5")]
+    patch_size: usize,
+
+    /// How many of a procedure's leading bytes the yara format's rules match,
+    /// when the image is given with `--image`. Bytes the loader relocates match
+    /// any value
+    #[structopt(long, default_value = "32")]
+    yara_bytes: usize,
+
+    /// List the members of anonymous structs and unions in place of the
+    /// `<unnamed-tag>` member which contains them, with offsets relative to the
+    /// enclosing type
+    #[structopt(long, global = true)]
+    flatten_anonymous: bool,
+
+    /// Name primitive types the way code in this language would, e.g. `long` and
+    /// `wchar_t` for C and C++ or `i32` and `u16` for Rust. Options include: c,
+    /// cpp, rust. By default fixed-width names such as `int32_t` are used
+    #[structopt(long, global = true, parse(try_from_str = parse_language))]
+    lang: Option<Language>,
+
+    /// Replace template arguments in symbol and type names with `<>`, e.g.
+    /// `std::vector<int>::size` becomes `std::vector<>::size`
+    #[structopt(long, global = true)]
+    strip_template_args: bool,
+
+    /// Replace anonymous namespace markers in names, such as `anonymous
+    /// namespace', with `anon`
+    #[structopt(long, global = true)]
+    collapse_anonymous_namespaces: bool,
+
+    /// Truncate symbol and type names to this many characters
+    #[structopt(long, global = true)]
+    max_name_len: Option<usize>,
+
+    /// End names truncated by `--max-name-len` with a hash of the full name,
+    /// so that names sharing a prefix stay distinct
+    #[structopt(long, global = true, requires = "max-name-len")]
+    hash_long_names: bool,
+
+    /// Rename procedures and data which share a name with a different symbol,
+    /// such as static functions of the same name in different object files,
+    /// by appending the name of their module or their RVA. Options include:
+    /// module, rva
+    #[structopt(long, global = true)]
+    disambiguate_names: Option<NameSuffix>,
 
-    /// Base address of module in-memory. If provided, all "offset" fields
-    /// will be added to the provided base address
+    /// Write each rename made by `--disambiguate-names` to this file as JSON
+    #[structopt(
+        long,
+        global = true,
+        parse(from_os_str),
+        requires = "disambiguate-names"
+    )]
+    name_map: Option<PathBuf>,
+
+    /// Disable colors in the plain output format. Colors are also disabled when
+    /// stdout isn't a terminal or the `NO_COLOR` environment variable is set
+    #[structopt(long, global = true)]
+    no_color: bool,
+
+    /// Output format type. Options include: plain, json, html, dot, hookplan,
+    /// go, zig, yara. Defaults to the format implied by the `--output` file's
+    /// extension, or plain. The dot format only includes the call graph, the
+    /// hookplan format only the boundaries and prologues of procedures, the go
+    /// and zig formats only type definitions, and the yara format only a rule
+    /// for each procedure
+    #[structopt(short, long, global = true)]
+    format: Option<OutputFormatType>,
+
+    /// Write output to a file instead of stdout. The file is replaced atomically
+    /// once the output is complete, missing parent directories are created, and
+    /// paths ending in `.gz` are gzip-compressed
+    #[structopt(short, long, parse(from_os_str), global = true)]
+    output: Option<PathBuf>,
+
+    /// Base address of module in-memory. If provided, addresses are printed
+    /// rebased onto it, and JSON output records them alongside their RVAs
     #[structopt(short, long)]
     base_address: Option<usize>,
 
-    /// PDB file to process
+    /// The executable the PDB was built for. Its section headers are used to
+    /// compute addresses if the PDB doesn't have its own
+    #[structopt(long, parse(from_os_str))]
+    image: Option<PathBuf>,
+
+    /// Where to look for the PDB when FILE is an executable, in the syntax of
+    /// `_NT_SYMBOL_PATH`, e.g. `C:\symbols;srv*C:\cache*\\server\symbols`.
+    /// Defaults to `_NT_SYMBOL_PATH`. The path recorded in the executable and
+    /// the executable's directory are searched afterwards
+    #[structopt(long, global = true)]
+    symbol_path: Option<String>,
+
+    /// PDB file to process, or an executable whose PDB should be found. `-`
+    /// reads the file from stdin
     #[structopt(name = "FILE", parse(from_os_str))]
-    file: PathBuf,
+    file: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
+    /// Set when FILE was read from stdin into a temporary file
+    #[structopt(skip)]
+    stdin_input: bool,
 }
 
-#[derive(Debug)]
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Print classes, unions, or enums and the types they depend on without
+    /// parsing the rest of the PDB
+    Type {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Names of the types to print
+        #[structopt(name = "NAME", required = true)]
+        names: Vec<String>,
+
+        /// Comma-separated patterns of dependencies to leave out, along with the
+        /// types only they depend on, e.g. `std::*`. `*` and `?` wildcards are
+        /// supported. Only supported by the plain output format
+        #[structopt(long, use_delimiter = true)]
+        exclude: Vec<String>,
+
+        /// Only print dependencies this many levels from the named types. Only
+        /// supported by the plain output format
+        #[structopt(long)]
+        max_depth: Option<usize>,
+    },
+    /// Print the variants of an enum, or the name of the variant with a given value.
+    /// Values of flag enums are named by the flags they combine, e.g. `A | B`
+    Enum {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Name of the enum
+        #[structopt(name = "NAME")]
+        name: String,
+
+        /// Value to look up, in decimal or hex with a `0x` prefix
+        #[structopt(name = "VALUE", parse(try_from_str = parse_integer))]
+        value: Option<u64>,
+    },
+    /// Search the names of symbols, types, fields, and enum variants. `*` and `?`
+    /// wildcards are supported, e.g. `nt!*Token*`
+    Grep {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Pattern to search for. A pattern without wildcards matches any name
+        /// containing it
+        #[structopt(name = "PATTERN")]
+        pattern: String,
+
+        /// Ignore case when matching
+        #[structopt(short, long)]
+        ignore_case: bool,
+
+        /// Only match whole words
+        #[structopt(short, long)]
+        word: bool,
+    },
+    /// Look up symbols by name. With `--fuzzy`, the symbols whose names are most
+    /// similar to NAME are listed best first, so that e.g. `ObRefObByHandle`
+    /// finds `ObReferenceObjectByHandle`
+    Find {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Name of the symbol
+        #[structopt(name = "NAME")]
+        name: String,
+
+        /// List symbols with similar names rather than only those named NAME
+        #[structopt(long)]
+        fuzzy: bool,
+
+        /// Maximum number of symbols listed by `--fuzzy`
+        #[structopt(long, default_value = "10")]
+        limit: usize,
+    },
+    /// Print every address a symbol name resolves to: public symbols,
+    /// procedures, and data with the name, including import thunks and
+    /// `__imp_` slots and names decorated with x86 calling conventions
+    Resolve {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Undecorated name of the symbol, e.g. `CreateFileW`
+        #[structopt(name = "NAME")]
+        name: String,
+    },
+    /// List the procedures, globals, and static locals whose types refer to a
+    /// class, union, or enum, whether directly or through pointers, arrays, or
+    /// function signatures
+    XrefType {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Name of the class, union, or enum
+        #[structopt(name = "NAME")]
+        name: String,
+    },
+    /// List the function pointers in writable global and static data, whether a
+    /// variable is a function pointer itself or holds some in its members or
+    /// array elements. Overwriting these redirects control flow.
+    FunctionPointers {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// List the groups of functions which identical COMDAT folding (`/OPT:ICF`)
+    /// merged into one copy of the code, so that addresses in coverage and
+    /// crash reports can be attributed to every function they may belong to
+    Folding {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Compare two PDBs, e.g. of successive builds of the same image, listing
+    /// the public symbols, procedures, global data, and types which were added,
+    /// removed, or modified. The json output format writes the changes as JSON
+    /// Patch-style operations with the records before and after each change
+    Diff {
+        /// PDB file of the earlier build
+        #[structopt(name = "OLD", parse(from_os_str))]
+        old: PathBuf,
+
+        /// PDB file of the later build
+        #[structopt(name = "NEW", parse(from_os_str))]
+        new: PathBuf,
+
+        /// Don't consider records modified if only their address changed
+        #[structopt(long)]
+        ignore_addresses: bool,
+    },
+    /// Measure how long each stage of parsing takes, either for a PDB file read
+    /// into memory beforehand or for a synthetic PDB generated in memory, so that
+    /// performance can be compared between builds
+    Bench {
+        /// PDB file to process. Not needed with `--synthetic`
+        #[structopt(
+            name = "FILE",
+            parse(from_os_str),
+            required_unless = "synthetic",
+            conflicts_with = "synthetic"
+        )]
+        file: Option<PathBuf>,
+
+        /// Parse a synthetic PDB with this many types and procedures, a quarter
+        /// as many globals, and a module for every thousand procedures
+        #[structopt(long)]
+        synthetic: Option<usize>,
+
+        /// Number of times to parse the PDB
+        #[structopt(long, default_value = "5")]
+        iterations: usize,
+    },
+    /// Print the PDB's GUID, age, machine type, version, and stream and module
+    /// counts. Only the PDB information and DBI streams are read, so this is
+    /// fast even for very large PDBs
+    Info {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// List the modules (object files) linked into the image along with the
+    /// library each came from and how much each contributes
+    Modules {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Sort the modules. Options include: name, library, size (of section
+        /// contributions), symbols, types, sources
+        #[structopt(long)]
+        sort: Option<ModuleSortKey>,
+    },
+    /// Rank classes, unions, and enums by how many types and symbols refer to
+    /// them, alongside the number of types each depends upon
+    Stats {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Instead rank source files by the number of bytes of code their
+        /// procedures contribute
+        #[structopt(long)]
+        by_file: bool,
+    },
+    /// Check the PDB's container, stream lengths, string table references,
+    /// and type indices for corruption or truncation
+    Verify {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Report the security mitigations (/GS, /sdl, /guard:cf) and other
+    /// notable flags each module was compiled with, flagging C and C++ modules
+    /// compiled without mitigations
+    Audit {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Compare the export table of the image the PDB belongs to with the PDB's
+    /// symbols, listing exports the PDB has no symbol for or places elsewhere,
+    /// and exports the PDB records which the image doesn't have. Exits with
+    /// status 3 if any are found
+    Exports {
+        /// PDB file to process, or the image itself
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// The image the PDB belongs to. Defaults to FILE if it's an image
+        #[structopt(long, parse(from_os_str))]
+        image: Option<PathBuf>,
+    },
+    /// List the system call services (`Nt*`) of a Windows kernel PDB alongside
+    /// their `Zw*` stubs. Given the kernel image, the system call numbers are
+    /// read from its service table
+    Syscalls {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// The ntoskrnl image the PDB belongs to
+        #[structopt(long, parse(from_os_str))]
+        image: Option<PathBuf>,
+    },
+    /// Hex-dump a TPI record and name its leaf kind without parsing it, along
+    /// with why it couldn't be parsed if it couldn't
+    RawType {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Index of the type, in decimal or hex with a `0x` prefix
+        #[structopt(name = "TYPE_INDEX", parse(try_from_str = parse_type_index))]
+        type_index: u32,
+    },
+    /// List the source files of every module along with their checksums
+    Sources {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Hash the local copy of each source file under `--root` and report
+        /// files which are missing or whose checksums don't match
+        #[structopt(long, requires = "root")]
+        verify: bool,
+
+        /// Local source tree to verify against. Each source file is looked up
+        /// by the longest trailing portion of its path which exists under here.
+        #[structopt(long, parse(from_os_str))]
+        root: Option<PathBuf>,
+    },
+    /// Write a copy of the PDB holding only its public symbols, like `pdbcopy
+    /// /p`, leaving out types, private symbols, and source file information
+    Strip {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Path to write the stripped PDB to
+        #[structopt(name = "STRIPPED_FILE", parse(from_os_str))]
+        stripped_file: PathBuf,
+
+        /// Keep the section contributions, which map address ranges to the
+        /// modules which contributed them
+        #[structopt(long)]
+        section_contributions: bool,
+    },
+    /// Write the types, functions, global variables, and line information as
+    /// DWARF in an ELF file, for use by gdb, addr2line, perf, and other Linux
+    /// tooling. Addresses are RVAs unless `--base-address` is given.
+    Dwarf {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Path to write the ELF file to
+        #[structopt(name = "ELF_FILE", parse(from_os_str))]
+        elf_file: PathBuf,
+    },
+    /// Write a compact table of the symbols' address ranges sorted by RVA, for
+    /// emulators and instrumentation tools to memory-map and binary search.
+    /// The format is described in the documentation of `ezpdb::addrmap`.
+    Addrmap {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Path to write the address map to
+        #[structopt(name = "MAP_FILE", parse(from_os_str))]
+        map_file: PathBuf,
+    },
+    /// Write an ELF file holding only a symbol table of the functions, which
+    /// perf can resolve the image's functions by, e.g. under Wine. Addresses
+    /// are RVAs unless `--base-address` is given.
+    Symtab {
+        /// PDB file to process
+        #[structopt(name = "FILE", parse(from_os_str))]
+        file: PathBuf,
+
+        /// Path to write the ELF file to
+        #[structopt(name = "ELF_FILE", parse(from_os_str))]
+        elf_file: PathBuf,
+    },
+}
+
+impl Opt {
+    fn color_choice(&self) -> ColorChoice {
+        if self.no_color
+            || std::env::var_os("NO_COLOR").is_some()
+            || !atty::is(atty::Stream::Stdout)
+        {
+            ColorChoice::Never
+        } else {
+            ColorChoice::Auto
+        }
+    }
+
+    /// Opens the destination for the command's output: the file passed to `--output`,
+    /// or stdout
+    fn output(&self) -> anyhow::Result<Destination> {
+        let output = match &self.output {
+            Some(path) => Destination::File(
+                AtomicFile::create(path)
+                    .with_context(|| format!("failed to create {}", path.display()))?,
+            ),
+            None => Destination::Stdout(BufferedStandardStream::stdout(self.color_choice())),
+        };
+
+        Ok(output)
+    }
+
+    /// Returns the format passed to `--format`, or the format implied by the
+    /// extension of the `--output` path
+    fn format(&self) -> OutputFormatType {
+        if let Some(format) = self.format {
+            return format;
+        }
+
+        let path = match &self.output {
+            Some(path) if destination::is_gzip(path) => path.with_extension(""),
+            Some(path) => path.clone(),
+            None => return OutputFormatType::Plain,
+        };
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => OutputFormatType::Json,
+            Some(extension)
+                if extension.eq_ignore_ascii_case("html")
+                    || extension.eq_ignore_ascii_case("htm") =>
+            {
+                OutputFormatType::Html
+            }
+            Some(extension) => backend::name_for_extension(extension)
+                .map_or(OutputFormatType::Plain, OutputFormatType::Backend),
+            None => OutputFormatType::Plain,
+        }
+    }
+
+    /// Returns the width plain output should be truncated to, if any
+    fn max_width(&self) -> Option<usize> {
+        if self.output.is_some() {
+            None
+        } else {
+            table::terminal_width()
+        }
+    }
+
+    fn parse_options(&self) -> ezpdb::ParseOptions {
+        ezpdb::ParseOptions {
+            base_address: self.base_address,
+            include_local_data: self.include_local_data,
+            fail_on: self.fail_on.unwrap_or_default(),
+            image_path: self.image.clone(),
+            build_index: false,
+        }
+    }
+
+    fn backend_options(&self) -> backend::BackendOptions {
+        backend::BackendOptions {
+            patch_size: self.patch_size,
+            image: self.image.clone(),
+            yara_bytes: self.yara_bytes,
+            render: self.render_options(),
+        }
+    }
+
+    fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            language: self.lang,
+        }
+    }
+
+    fn name_options(&self) -> names::NameOptions {
+        names::NameOptions {
+            strip_template_arguments: self.strip_template_args,
+            collapse_anonymous_namespaces: self.collapse_anonymous_namespaces,
+            max_len: self.max_name_len,
+            hash_long_names: self.hash_long_names,
+        }
+    }
+
+    /// Returns the path `file` is reported by: [input::STDIN_NAME] if it was read
+    /// from stdin, rather than the temporary file it was buffered into
+    fn input_path(&self, file: &Path) -> PathBuf {
+        if self.stdin_input {
+            PathBuf::from(input::STDIN_NAME)
+        } else {
+            file.to_owned()
+        }
+    }
+
+    /// Returns the file being processed, whether it was given to a subcommand or not
+    fn file_mut(&mut self) -> Option<&mut PathBuf> {
+        match &mut self.command {
+            None => self.file.as_mut(),
+            Some(Command::Type { file, .. })
+            | Some(Command::Enum { file, .. })
+            | Some(Command::Grep { file, .. })
+            | Some(Command::Find { file, .. })
+            | Some(Command::Resolve { file, .. })
+            | Some(Command::XrefType { file, .. })
+            | Some(Command::FunctionPointers { file })
+            | Some(Command::Folding { file })
+            | Some(Command::Info { file })
+            | Some(Command::Modules { file, .. })
+            | Some(Command::Stats { file, .. })
+            | Some(Command::Verify { file })
+            | Some(Command::RawType { file, .. })
+            | Some(Command::Syscalls { file, .. })
+            | Some(Command::Exports { file, .. })
+            | Some(Command::Audit { file })
+            | Some(Command::Sources { file, .. })
+            | Some(Command::Strip { file, .. })
+            | Some(Command::Dwarf { file, .. })
+            | Some(Command::Symtab { file, .. })
+            | Some(Command::Addrmap { file, .. }) => Some(file),
+            Some(Command::Bench { file, .. }) => file.as_mut(),
+            // Both PDBs of a diff are read as they are
+            Some(Command::Diff { .. }) => None,
+        }
+    }
+
+    fn log_level(&self) -> LevelFilter {
+        if let Some(level) = self.log_level {
+            return level;
+        }
+
+        if self.quiet {
+            return LevelFilter::ERROR;
+        }
+
+        // `--debug` is equivalent to `-vv`
+        let verbosity = if self.debug {
+            self.verbose.max(2)
+        } else {
+            self.verbose
+        };
+        match verbosity {
+            0 => LevelFilter::WARN,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+
+    fn pagination(&self) -> pagination::Pagination {
+        pagination::Pagination {
+            offset: self.offset,
+            limit: self.limit.or(self.head),
+            tail: self.tail,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
 enum OutputFormatType {
     Plain,
     Json,
+    Html,
+    /// A format rendered by the [backend::OutputBackend] of this name
+    Backend(&'static str),
 }
 
 impl FromStr for OutputFormatType {
@@ -45,28 +757,960 @@ impl FromStr for OutputFormatType {
         let result = match s.to_ascii_lowercase().as_ref() {
             "plain" => OutputFormatType::Plain,
             "json" => OutputFormatType::Json,
-            _ => return Err(CliArgumentError::InvalidValue("format", s.to_string())),
+            "html" => OutputFormatType::Html,
+            name => match backend::find(name, &backend::BackendOptions::default()) {
+                Some(backend) => OutputFormatType::Backend(backend.name()),
+                None => return Err(CliArgumentError::InvalidValue("format", s.to_string())),
+            },
+        };
+
+        Ok(result)
+    }
+}
+
+impl std::fmt::Display for OutputFormatType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            OutputFormatType::Plain => "plain",
+            OutputFormatType::Json => "json",
+            OutputFormatType::Html => "html",
+            OutputFormatType::Backend(name) => name,
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum SortKey {
+    Address,
+    Name,
+    Size,
+    File,
+}
+
+impl FromStr for SortKey {
+    type Err = CliArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let result = match s.to_ascii_lowercase().as_ref() {
+            "address" => SortKey::Address,
+            "name" => SortKey::Name,
+            "size" => SortKey::Size,
+            "file" => SortKey::File,
+            _ => return Err(CliArgumentError::InvalidValue("sort", s.to_string())),
+        };
+
+        Ok(result)
+    }
+}
+
+/// What names are suffixed with to tell apart symbols which share a name
+#[derive(Debug, Copy, Clone)]
+pub enum NameSuffix {
+    /// The file name of the symbol's module, or its RVA if it has no module
+    Module,
+    Rva,
+}
+
+impl FromStr for NameSuffix {
+    type Err = CliArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let result = match s.to_ascii_lowercase().as_ref() {
+            "module" => NameSuffix::Module,
+            "rva" => NameSuffix::Rva,
+            _ => {
+                return Err(CliArgumentError::InvalidValue(
+                    "disambiguate-names",
+                    s.to_string(),
+                ))
+            }
         };
 
         Ok(result)
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    let opt = Opt::from_args();
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModuleSortKey {
+    Name,
+    Library,
+    Size,
+    Symbols,
+    Types,
+    Sources,
+}
+
+impl FromStr for ModuleSortKey {
+    type Err = CliArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let result = match s.to_ascii_lowercase().as_ref() {
+            "name" => ModuleSortKey::Name,
+            "library" => ModuleSortKey::Library,
+            "size" => ModuleSortKey::Size,
+            "symbols" => ModuleSortKey::Symbols,
+            "types" => ModuleSortKey::Types,
+            "sources" => ModuleSortKey::Sources,
+            _ => return Err(CliArgumentError::InvalidValue("sort", s.to_string())),
+        };
+
+        Ok(result)
+    }
+}
+
+fn parse_language(s: &str) -> Result<Language, CliArgumentError> {
+    let result = match s.to_ascii_lowercase().as_ref() {
+        "c" => Language::C,
+        "cpp" | "c++" => Language::Cpp,
+        "rust" => Language::Rust,
+        _ => return Err(CliArgumentError::InvalidValue("lang", s.to_string())),
+    };
+
+    Ok(result)
+}
+
+fn parse_origin(s: &str) -> Result<ezpdb::symbol_types::CodeOrigin, CliArgumentError> {
+    use ezpdb::symbol_types::CodeOrigin;
+
+    let result = match s.to_ascii_lowercase().as_ref() {
+        "user" => CodeOrigin::User,
+        "runtime" => CodeOrigin::Runtime,
+        "stl" => CodeOrigin::Stl,
+        "compiler-generated" => CodeOrigin::CompilerGenerated,
+        _ => return Err(CliArgumentError::InvalidValue("only", s.to_string())),
+    };
+
+    Ok(result)
+}
+
+fn parse_failure_policy(s: &str) -> Result<ezpdb::FailurePolicy, CliArgumentError> {
+    use ezpdb::FailurePolicy;
+
+    let result = match s.to_ascii_lowercase().as_ref() {
+        "never" => FailurePolicy::Never,
+        "corrupt" => FailurePolicy::Corrupt,
+        "unknown-kind" => FailurePolicy::UnknownKind,
+        "any" => FailurePolicy::Any,
+        _ => return Err(CliArgumentError::InvalidValue("fail-on", s.to_string())),
+    };
+
+    Ok(result)
+}
+
+/// Parses an integer written in decimal or hex with a `0x` prefix
+fn parse_integer(s: &str) -> Result<u64, std::num::ParseIntError> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+}
+
+fn parse_type_index(s: &str) -> Result<u32, CliArgumentError> {
+    parse_integer(s)
+        .ok()
+        .and_then(|index| std::convert::TryFrom::try_from(index).ok())
+        .ok_or_else(|| CliArgumentError::InvalidValue("TYPE_INDEX", s.to_string()))
+}
+
+fn main() {
+    let mut opt = Opt::from_args();
+
+    let result = logging::init(
+        opt.log_level(),
+        opt.log_format.unwrap_or(logging::LogFormat::Plain),
+    )
+    .and_then(|_| {
+        read_stdin_input(&mut opt).and_then(|_stdin_file| {
+            resolve_image_input(&mut opt)?;
+            run(&opt)
+        })
+    });
+    logging::summarize_repeated_warnings();
 
-    if opt.debug {
-        simplelog::SimpleLogger::init(log::LevelFilter::Debug, simplelog::Config::default())?;
+    if let Err(e) = &result {
+        eprintln!("Error: {:?}", e);
     }
+    std::process::exit(exit::code(&result));
+}
+
+/// If the file to process is `-`, buffers stdin into a temporary file and
+/// processes that instead. The file is removed once the returned guard is dropped.
+fn read_stdin_input(opt: &mut Opt) -> anyhow::Result<Option<input::StdinFile>> {
+    let file = match opt.file_mut() {
+        Some(file) if file.as_os_str() == input::STDIN_PATH => file,
+        _ => return Ok(None),
+    };
+
+    let stdin_file = input::StdinFile::read().context("could not read the PDB from stdin")?;
+    *file = stdin_file.path().to_owned();
+    opt.stdin_input = true;
+
+    Ok(Some(stdin_file))
+}
+
+/// If the file to process is an executable, replaces it with the executable's
+/// PDB and uses the executable for addresses unless `--image` was given
+fn resolve_image_input(opt: &mut Opt) -> anyhow::Result<()> {
+    let file = match opt.file_mut() {
+        Some(file) => file,
+        None => return Ok(()),
+    };
+    let reference = match ezpdb::symbol_path::image_pdb_reference(&*file)? {
+        Some(reference) => reference,
+        None => return Ok(()),
+    };
+
+    let symbol_path = match &opt.symbol_path {
+        Some(path) => Some(ezpdb::symbol_path::SymbolPath::parse(path)),
+        None => ezpdb::symbol_path::SymbolPath::from_env(),
+    };
+    let file = opt.file_mut().unwrap();
+    let pdb = ezpdb::symbol_path::find_image_pdb(file, &reference, symbol_path.as_ref())
+        .ok_or_else(|| {
+            exit::PdbMismatch(format!(
+                "could not find {} ({}) for {:?}. Set `--symbol-path` or `{}` to where it can be found",
+                reference.file_name(),
+                reference.store_key(),
+                file,
+                ezpdb::symbol_path::SYMBOL_PATH_VARIABLE
+            ))
+        })?;
+    tracing::info!("using {:?} for {:?}", pdb, file);
+
+    let image = std::mem::replace(file, pdb);
+    opt.image.get_or_insert(image);
 
-    let parsed_pdb = ezpdb::parse_pdb(&opt.file, opt.base_address)?;
-    let stdout = std::io::stdout();
-    let mut stdout_lock = stdout.lock();
+    Ok(())
+}
+
+fn run(opt: &Opt) -> anyhow::Result<()> {
+    if let Some(command) = &opt.command {
+        return run_command(opt, command);
+    }
+
+    let file = match &opt.file {
+        Some(file) => file,
+        None => ClapError::with_description(
+            "the following required arguments were not provided: <FILE>",
+            ErrorKind::MissingRequiredArgument,
+        )
+        .exit(),
+    };
+    let options = opt.parse_options();
+
+    if opt.stream {
+        return stream_plain(file, &options, opt);
+    }
+
+    let mut parsed_pdb = parse(opt, file)?;
+
+    let unmet = expect::unmet(&parsed_pdb, &opt.expect);
+    if !unmet.is_empty() {
+        anyhow::bail!("{}", unmet.join("; "));
+    }
+
+    let mut output = opt.output()?;
+
+    if opt.dedup {
+        output::dedup_public_symbols(&mut parsed_pdb);
+    }
+
+    if let Some(origin) = opt.only {
+        output::retain_origin(&mut parsed_pdb, origin);
+    }
 
-    match opt.format {
-        OutputFormatType::Plain => output::print_plain(&mut stdout_lock, &parsed_pdb)?,
-        OutputFormatType::Json => output::print_json(&mut stdout_lock, &parsed_pdb)?,
+    if let Some(pattern) = &opt.source_file {
+        let pattern = ezpdb::search::NamePattern::new(pattern).case_insensitive(true);
+        output::retain_source_file(&mut parsed_pdb, &pattern);
     }
 
+    if let Some(sort) = opt.sort {
+        output::sort(&mut parsed_pdb, sort);
+    }
+
+    let listing = output::ListingOptions {
+        filter: opt.filter.clone(),
+        pagination: opt.pagination(),
+        sections: opt.sections.clone(),
+        render: opt.render_options(),
+    };
+    match opt.format() {
+        OutputFormatType::Plain => output::print_plain(
+            &mut output,
+            &parsed_pdb,
+            &listing,
+            opt.max_width(),
+            opt.flatten_anonymous,
+            &listing.render,
+        )?,
+        OutputFormatType::Json => {
+            output::restrict_listings(&mut parsed_pdb, &listing);
+            output::print_json(&mut output, &parsed_pdb)?
+        }
+        OutputFormatType::Html => html::print_html(
+            &mut output,
+            &parsed_pdb,
+            &listing,
+            opt.flatten_anonymous,
+            &listing.render,
+        )?,
+        OutputFormatType::Backend(name) => {
+            let backend = backend::find(name, &opt.backend_options())
+                .ok_or_else(|| anyhow::anyhow!("no output format is named {}", name))?;
+            let types = listing.select_types(&parsed_pdb);
+            output::restrict_listings(&mut parsed_pdb, &listing);
+            backend.render(&parsed_pdb, &types, &mut output)?
+        }
+    }
+    output.finish()?;
+
+    Ok(())
+}
+
+/// Returns the error for a subcommand which doesn't support `format`
+fn unsupported_format(format: OutputFormatType, command: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "the {} output format is not supported by {}",
+        format,
+        command
+    )
+}
+
+/// Fully parses `file`, reporting progress and timing as requested by `opt`
+fn parse(opt: &Opt, file: &Path) -> anyhow::Result<ezpdb::ParsedPdb> {
+    let options = opt.parse_options();
+    let mut progress = (
+        opt.progress.then(progress::ProgressBar::new),
+        ezpdb::timing::StageTimer::new(),
+    );
+    let mut parsed_pdb = ezpdb::parse_pdb_with_options(file, &options, &mut progress)?;
+    parsed_pdb.path = opt.input_path(file);
+    exit::record_diagnostics(&parsed_pdb);
+    names::normalize_names(&mut parsed_pdb, &opt.name_options());
+
+    if let Some(suffix) = opt.disambiguate_names {
+        let renames = names::disambiguate_names(&mut parsed_pdb, suffix);
+        for rename in &renames {
+            tracing::info!(
+                "renamed {} {} to {}",
+                rename.kind,
+                rename.name,
+                rename.renamed
+            );
+        }
+
+        if let Some(path) = &opt.name_map {
+            let mut file = AtomicFile::create(path)
+                .with_context(|| format!("could not create {}", path.display()))?;
+            names::write_renames(&mut file, &renames)?;
+            file.finish()?;
+        }
+    }
+
+    if opt.timing {
+        output::print_timings(&mut std::io::stderr(), progress.1.timings())?;
+    }
+
+    Ok(parsed_pdb)
+}
+
+fn run_command(opt: &Opt, command: &Command) -> anyhow::Result<()> {
+    let mut output = opt.output()?;
+
+    match command {
+        Command::Type {
+            file,
+            names,
+            exclude,
+            max_depth,
+        } => {
+            let roots: Vec<&str> = names.iter().map(String::as_str).collect();
+            let (mut parsed_pdb, ids) = ezpdb::parse_types(file, &roots, &opt.parse_options())?;
+            parsed_pdb.path = opt.input_path(file);
+            let missing: Vec<String> = names
+                .iter()
+                .zip(&ids)
+                .filter(|(_name, id)| id.is_none())
+                .map(|(name, _id)| format!("`{}`", name))
+                .collect();
+            if !missing.is_empty() {
+                anyhow::bail!("no type named {} was found", missing.join(", "));
+            }
+            let ids: Vec<_> = ids.into_iter().flatten().collect();
+            let closure_options = ezpdb::xref::ClosureOptions {
+                exclude: exclude
+                    .iter()
+                    .map(|pattern| ezpdb::search::NamePattern::new(pattern))
+                    .collect(),
+                max_depth: *max_depth,
+            };
+            let is_limited = !exclude.is_empty() || max_depth.is_some();
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    // Print the requested types first, followed by their dependencies
+                    let max_width = opt.max_width();
+                    for id in ezpdb::xref::dependency_closure(&parsed_pdb, &ids, &closure_options) {
+                        output::print_type(
+                            &mut output,
+                            &parsed_pdb,
+                            &parsed_pdb.types[id],
+                            max_width,
+                            opt.flatten_anonymous,
+                            &opt.render_options(),
+                        )?;
+                    }
+                }
+                format @ OutputFormatType::Backend(name) => {
+                    let backend = backend::find(name, &opt.backend_options())
+                        .filter(|backend| backend.renders_types())
+                        .ok_or_else(|| unsupported_format(format, "type"))?;
+                    let types =
+                        ezpdb::xref::dependency_closure(&parsed_pdb, &ids, &closure_options);
+                    backend.render(&parsed_pdb, &types, &mut output)?
+                }
+                format if is_limited => anyhow::bail!(
+                    "--exclude and --max-depth are not supported by the {} output format",
+                    format
+                ),
+                OutputFormatType::Json => output::print_json(&mut output, &parsed_pdb)?,
+                OutputFormatType::Html => html::print_html(
+                    &mut output,
+                    &parsed_pdb,
+                    &Default::default(),
+                    opt.flatten_anonymous,
+                    &opt.render_options(),
+                )?,
+            }
+        }
+        Command::Enum { file, name, value } => {
+            let (parsed_pdb, id) = ezpdb::parse_single_type(file, name, &opt.parse_options())?
+                .filter(|(parsed_pdb, id)| {
+                    matches!(
+                        parsed_pdb.types[*id],
+                        ezpdb::type_info::Type::Enumeration(_)
+                    )
+                })
+                .ok_or_else(|| anyhow::anyhow!("no enum named `{}` was found", name))?;
+
+            let value_name = match value {
+                Some(value) => Some(parsed_pdb.enum_value_name(name, *value).ok_or_else(|| {
+                    anyhow::anyhow!("no variant of `{}` has the value 0x{:X}", name, value)
+                })?),
+                None => None,
+            };
+
+            match (opt.format(), value_name) {
+                (OutputFormatType::Plain, Some(value_name)) => writeln!(output, "{}", value_name)?,
+                (OutputFormatType::Plain, None) => output::print_type(
+                    &mut output,
+                    &parsed_pdb,
+                    &parsed_pdb.types[id],
+                    opt.max_width(),
+                    opt.flatten_anonymous,
+                    &opt.render_options(),
+                )?,
+                (OutputFormatType::Json, Some(value_name)) => {
+                    write!(output, "{}", serde_json::to_string(&value_name)?)?
+                }
+                (OutputFormatType::Json, None) => {
+                    write!(output, "{}", serde_json::to_string(&parsed_pdb.types[id])?)?
+                }
+                (format, _) => return Err(unsupported_format(format, "enum")),
+            }
+        }
+        Command::Grep {
+            file,
+            pattern,
+            ignore_case,
+            word,
+        } => {
+            let parsed_pdb = parse(opt, file)?;
+            let pattern = ezpdb::search::NamePattern::new(pattern)
+                .case_insensitive(*ignore_case)
+                .whole_word(*word);
+            let hits = ezpdb::search::search(&parsed_pdb, &pattern);
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_search_hits(&mut output, &hits)?,
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&hits)?)?,
+                format => return Err(unsupported_format(format, "grep")),
+            }
+        }
+        Command::Find {
+            file,
+            name,
+            fuzzy,
+            limit,
+        } => {
+            let parsed_pdb = parse(opt, file)?;
+            let matches = if *fuzzy {
+                ezpdb::search::FuzzyIndex::new(&parsed_pdb).search(name, *limit)
+            } else {
+                ezpdb::search::find_symbol(&parsed_pdb, name)
+            };
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_symbol_matches(&mut output, &matches, *fuzzy, opt.max_width())?
+                }
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&matches)?)?,
+                format => return Err(unsupported_format(format, "find")),
+            }
+        }
+        Command::Resolve { file, name } => {
+            let parsed_pdb = parse(opt, file)?;
+            let resolved = parsed_pdb.resolve_name(name);
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_resolved_symbols(&mut output, &resolved, opt.max_width())?
+                }
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&resolved)?)?,
+                format => return Err(unsupported_format(format, "resolve")),
+            }
+        }
+        Command::XrefType { file, name } => {
+            let parsed_pdb = parse(opt, file)?;
+            let index = ezpdb::xref::TypeReferenceIndex::new(&parsed_pdb);
+            let references = index.find(&parsed_pdb, name);
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_type_references(
+                    &mut output,
+                    &parsed_pdb,
+                    &references,
+                    opt.max_width(),
+                    &opt.render_options(),
+                )?,
+                OutputFormatType::Json => {
+                    write!(output, "{}", serde_json::to_string(&references)?)?
+                }
+                format => return Err(unsupported_format(format, "xref-type")),
+            }
+        }
+        Command::FunctionPointers { file } => {
+            let parsed_pdb = parse(opt, file)?;
+            let pointers = ezpdb::function_pointers::writable_function_pointers(&parsed_pdb);
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_writable_function_pointers(
+                    &mut output,
+                    &parsed_pdb,
+                    &pointers,
+                    opt.max_width(),
+                    &opt.render_options(),
+                )?,
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&pointers)?)?,
+                format => return Err(unsupported_format(format, "function-pointers")),
+            }
+        }
+        Command::Modules { file, sort } => {
+            let mut parsed_pdb = parse(opt, file)?;
+            if let Some(sort) = sort {
+                output::sort_modules(&mut parsed_pdb.debug_modules, *sort);
+            }
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_modules(&mut output, &parsed_pdb.debug_modules, opt.max_width())?
+                }
+                OutputFormatType::Json => write!(
+                    output,
+                    "{}",
+                    serde_json::to_string(&parsed_pdb.debug_modules)?
+                )?,
+                format => return Err(unsupported_format(format, "modules")),
+            }
+        }
+        Command::Info { file } => {
+            let summary = ezpdb::read_summary(file)?;
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_summary(&mut output, &summary)?,
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&summary)?)?,
+                format => return Err(unsupported_format(format, "info")),
+            }
+        }
+        Command::Stats {
+            file,
+            by_file: true,
+        } => {
+            let parsed_pdb = parse(opt, file)?;
+            let statistics = ezpdb::sources::file_statistics(&parsed_pdb);
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_file_statistics(&mut output, &statistics, opt.max_width())?
+                }
+                OutputFormatType::Json => {
+                    write!(output, "{}", serde_json::to_string(&statistics)?)?
+                }
+                format => return Err(unsupported_format(format, "stats")),
+            }
+        }
+        Command::Stats {
+            file,
+            by_file: false,
+        } => {
+            let parsed_pdb = parse(opt, file)?;
+            let metrics = ezpdb::xref::type_metrics(&parsed_pdb);
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_type_metrics(&mut output, &parsed_pdb, &metrics, opt.max_width())?
+                }
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&metrics)?)?,
+                format => return Err(unsupported_format(format, "stats")),
+            }
+        }
+        Command::Verify { file } => {
+            let issues = ezpdb::verify::verify_pdb(file)?;
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_verify_issues(&mut output, &issues)?,
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&issues)?)?,
+                format => return Err(unsupported_format(format, "verify")),
+            }
+
+            if !issues.is_empty() {
+                output.finish()?;
+                anyhow::bail!("found {} problems in {}", issues.len(), file.display());
+            }
+        }
+        Command::Audit { file } => {
+            let parsed_pdb = parse(opt, file)?;
+            let audits = ezpdb::audit::audit_modules(&parsed_pdb);
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_module_audits(&mut output, &audits, opt.max_width())?
+                }
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&audits)?)?,
+                format => return Err(unsupported_format(format, "audit")),
+            }
+
+            let flagged = audits
+                .iter()
+                .filter(|audit| !audit.missing.is_empty())
+                .count();
+            if flagged > 0 {
+                output.finish()?;
+                anyhow::bail!(
+                    "{} modules of {} were compiled without mitigations",
+                    flagged,
+                    file.display()
+                );
+            }
+        }
+        Command::Exports { file, image } => {
+            let image = image.as_ref().or(opt.image.as_ref()).ok_or_else(|| {
+                anyhow::anyhow!("exports needs the image, given with --image or as FILE")
+            })?;
+            let parsed_pdb = parse(opt, file)?;
+            let mismatches = ezpdb::exports::compare_exports(&parsed_pdb, image, opt.base_address)?;
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_export_mismatches(&mut output, &mismatches, opt.max_width())?
+                }
+                OutputFormatType::Json => {
+                    write!(output, "{}", serde_json::to_string(&mismatches)?)?
+                }
+                format => return Err(unsupported_format(format, "exports")),
+            }
+
+            if !mismatches.is_empty() {
+                output.finish()?;
+                return Err(exit::PdbMismatch(format!(
+                    "{} exports of {} don't match {}",
+                    mismatches.len(),
+                    image.display(),
+                    file.display()
+                ))
+                .into());
+            }
+        }
+        Command::Syscalls { file, image } => {
+            let parsed_pdb = parse(opt, file)?;
+            let syscalls = ezpdb::syscalls::syscall_table(&parsed_pdb, image.as_deref())?;
+
+            match opt.format() {
+                OutputFormatType::Plain => {
+                    output::print_syscalls(&mut output, &syscalls, opt.max_width())?
+                }
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&syscalls)?)?,
+                format => return Err(unsupported_format(format, "syscalls")),
+            }
+        }
+        Command::RawType { file, type_index } => {
+            let record = ezpdb::read_raw_type(file, *type_index)?.ok_or_else(|| {
+                anyhow::anyhow!("the TPI stream has no type with index 0x{:X}", type_index)
+            })?;
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_raw_type_record(&mut output, &record)?,
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&record)?)?,
+                format => return Err(unsupported_format(format, "raw-type")),
+            }
+        }
+        Command::Sources { file, verify, root } => {
+            let parsed_pdb = parse(opt, file)?;
+
+            match (verify, root) {
+                (true, Some(root)) => {
+                    let results = ezpdb::sources::verify_sources(&parsed_pdb, root);
+                    match opt.format() {
+                        OutputFormatType::Plain => output::print_source_verification(
+                            &mut output,
+                            &results,
+                            opt.max_width(),
+                        )?,
+                        OutputFormatType::Json => {
+                            write!(output, "{}", serde_json::to_string(&results)?)?
+                        }
+                        format => return Err(unsupported_format(format, "sources")),
+                    }
+
+                    let failures = results
+                        .iter()
+                        .filter(|result| {
+                            matches!(
+                                result.status,
+                                SourceStatus::Mismatch { .. } | SourceStatus::Missing
+                            )
+                        })
+                        .count();
+                    if failures > 0 {
+                        output.finish()?;
+                        anyhow::bail!(
+                            "{} source files are missing or don't match {}",
+                            failures,
+                            file.display()
+                        );
+                    }
+                }
+                _ => {
+                    let files = ezpdb::sources::source_files(&parsed_pdb);
+                    match opt.format() {
+                        OutputFormatType::Plain => {
+                            output::print_source_files(&mut output, &files, opt.max_width())?
+                        }
+                        OutputFormatType::Json => {
+                            write!(output, "{}", serde_json::to_string(&files)?)?
+                        }
+                        format => return Err(unsupported_format(format, "sources")),
+                    }
+                }
+            }
+        }
+        Command::Strip {
+            file,
+            stripped_file,
+            section_contributions,
+        } => {
+            let options = ezpdb::strip::StripOptions {
+                section_contributions: *section_contributions,
+            };
+            let public_count = ezpdb::strip::strip_pdb(file, stripped_file, &options)
+                .with_context(|| format!("failed to strip {}", file.display()))?;
+
+            match opt.format() {
+                OutputFormatType::Plain => writeln!(
+                    output,
+                    "wrote {} public symbols to {}",
+                    public_count,
+                    stripped_file.display()
+                )?,
+                OutputFormatType::Json => write!(
+                    output,
+                    "{}",
+                    serde_json::json!({
+                        "path": stripped_file,
+                        "public_symbols": public_count,
+                    })
+                )?,
+                format => return Err(unsupported_format(format, "strip")),
+            }
+        }
+        Command::Dwarf { file, elf_file } => {
+            let parsed_pdb = parse(opt, file)?;
+            let summary = ezpdb::dwarf::write_dwarf(&parsed_pdb, &opt.parse_options(), elf_file)
+                .with_context(|| format!("failed to write DWARF for {}", file.display()))?;
+
+            match opt.format() {
+                OutputFormatType::Plain => writeln!(
+                    output,
+                    "wrote {} types, {} functions, {} variables, and {} line rows to {}",
+                    summary.types,
+                    summary.functions,
+                    summary.variables,
+                    summary.lines,
+                    elf_file.display()
+                )?,
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&summary)?)?,
+                format => return Err(unsupported_format(format, "dwarf")),
+            }
+        }
+        Command::Folding { file } => {
+            let parsed_pdb = parse(opt, file)?;
+            let groups = ezpdb::folding::folded_groups(&parsed_pdb);
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_folded_groups(&mut output, &groups)?,
+                OutputFormatType::Json => write!(output, "{}", serde_json::to_string(&groups)?)?,
+                format => return Err(unsupported_format(format, "folding")),
+            }
+        }
+        Command::Diff {
+            old,
+            new,
+            ignore_addresses,
+        } => {
+            let old = parse(opt, old)?;
+            let new = parse(opt, new)?;
+            let changes = diff::diff(&old, &new, *ignore_addresses, &opt.render_options());
+
+            match opt.format() {
+                OutputFormatType::Plain => diff::print_changes(&mut output, &changes)?,
+                OutputFormatType::Json => diff::write_changes(&mut output, &changes)?,
+                format => return Err(unsupported_format(format, "diff")),
+            }
+        }
+        Command::Bench {
+            file,
+            synthetic,
+            iterations,
+        } => {
+            let (data, path) = match (synthetic, file) {
+                (Some(size), _) => {
+                    let options = ezpdb::synthetic::SyntheticOptions::scaled(*size);
+                    let path = PathBuf::from(format!("synthetic-{}.pdb", size));
+                    (ezpdb::synthetic::synthetic_pdb(&options), path)
+                }
+                (None, Some(file)) => {
+                    let data = std::fs::read(file)
+                        .with_context(|| format!("could not read {}", file.display()))?;
+                    (data, opt.input_path(file))
+                }
+                (None, None) => anyhow::bail!("a FILE or `--synthetic` is required"),
+            };
+
+            let options = opt.parse_options();
+            let mut runs = vec![];
+            let mut totals = vec![];
+            for _ in 0..(*iterations).max(1) {
+                let data = data.clone();
+                let mut timer = ezpdb::timing::StageTimer::new();
+                let started = Instant::now();
+                let parsed_pdb =
+                    ezpdb::parse_pdb_from_bytes(data, path.clone(), &options, &mut timer)?;
+                totals.push(started.elapsed());
+                drop(parsed_pdb);
+                runs.push(timer.timings().to_vec());
+            }
+
+            match opt.format() {
+                OutputFormatType::Plain => output::print_bench(
+                    &mut output,
+                    &path,
+                    data.len(),
+                    &ezpdb::timing::summarize(&runs),
+                    &totals,
+                )?,
+                format => return Err(unsupported_format(format, "bench")),
+            }
+        }
+        Command::Addrmap { file, map_file } => {
+            let parsed_pdb = parse(opt, file)?;
+            let entry_count = ezpdb::addrmap::write_address_map(&parsed_pdb, map_file)
+                .with_context(|| {
+                    format!("failed to write an address map for {}", file.display())
+                })?;
+
+            match opt.format() {
+                OutputFormatType::Plain => writeln!(
+                    output,
+                    "wrote {} entries to {}",
+                    entry_count,
+                    map_file.display()
+                )?,
+                OutputFormatType::Json => write!(
+                    output,
+                    "{}",
+                    serde_json::json!({
+                        "path": map_file,
+                        "entries": entry_count,
+                    })
+                )?,
+                format => return Err(unsupported_format(format, "addrmap")),
+            }
+        }
+        Command::Symtab { file, elf_file } => {
+            let parsed_pdb = parse(opt, file)?;
+            let symbol_count =
+                ezpdb::elf::write_symbol_table(&parsed_pdb, &opt.parse_options(), elf_file)
+                    .with_context(|| {
+                        format!("failed to write a symbol table for {}", file.display())
+                    })?;
+
+            match opt.format() {
+                OutputFormatType::Plain => writeln!(
+                    output,
+                    "wrote {} symbols to {}",
+                    symbol_count,
+                    elf_file.display()
+                )?,
+                OutputFormatType::Json => write!(
+                    output,
+                    "{}",
+                    serde_json::json!({
+                        "path": elf_file,
+                        "symbols": symbol_count,
+                    })
+                )?,
+                format => return Err(unsupported_format(format, "symtab")),
+            }
+        }
+    }
+    output.finish()?;
+
+    Ok(())
+}
+
+fn stream_plain(file: &Path, options: &ezpdb::ParseOptions, opt: &Opt) -> anyhow::Result<()> {
+    if !matches!(opt.format(), OutputFormatType::Plain) {
+        anyhow::bail!("--stream is only supported by the plain output format");
+    }
+
+    let mut output = opt.output()?;
+
+    let pagination = opt.pagination();
+
+    output::print_public_symbols_header(&mut output)?;
+    let mut index = 0;
+    ezpdb::stream::stream_public_symbols(file, options, |symbol| {
+        if pagination.contains_streamed(index) {
+            output::print_public_symbol(&mut output, &symbol)?;
+        }
+        index += 1;
+
+        Ok(())
+    })?;
+
+    output::print_procedures_header(&mut output)?;
+    let mut index = 0;
+    ezpdb::stream::stream_procedures(file, options, |procedure| {
+        if pagination.contains_streamed(index) {
+            output::print_procedure(&mut output, &procedure)?;
+        }
+        index += 1;
+
+        Ok(())
+    })?;
+    output.finish()?;
+
     Ok(())
 }
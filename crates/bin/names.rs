@@ -0,0 +1,530 @@
+//! Normalization of symbol and type names, for consumers such as IDA and
+//! Ghidra scripts which can't cope with the very long names templated C++ and
+//! Rust code produces
+use crate::NameSuffix;
+use ezpdb::symbol_types::ParsedPdb;
+use ezpdb::type_info::Type;
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// What the marker of an anonymous namespace is collapsed to
+const ANONYMOUS_NAMESPACE: &str = "anon";
+
+/// The ways an anonymous namespace is written in names: by MSVC and by Clang/GCC
+const ANONYMOUS_NAMESPACE_MARKERS: [&str; 2] = ["`anonymous namespace'", "(anonymous namespace)"];
+
+/// Operators containing angle brackets, longest first
+const OPERATORS: [&str; 11] = [
+    "<=>", "<<=", ">>=", "->*", "<<", ">>", "<=", ">=", "->", "<", ">",
+];
+
+/// How names are rewritten before being output
+#[derive(Debug, Default, Clone)]
+pub struct NameOptions {
+    /// Replace template arguments with `<>`, e.g. `std::vector<int>::size`
+    /// becomes `std::vector<>::size`
+    pub strip_template_arguments: bool,
+    /// Replace anonymous namespace markers with `anon`
+    pub collapse_anonymous_namespaces: bool,
+    /// Truncate names to this many characters
+    pub max_len: Option<usize>,
+    /// End truncated names with a hash of the full name, so that names which
+    /// share a prefix stay distinct
+    pub hash_long_names: bool,
+}
+
+impl NameOptions {
+    /// Returns whether names are left as they are
+    pub fn is_identity(&self) -> bool {
+        !self.strip_template_arguments
+            && !self.collapse_anonymous_namespaces
+            && self.max_len.is_none()
+    }
+
+    /// Returns `name` rewritten as these options ask for
+    pub fn normalize(&self, name: &str) -> String {
+        let mut name = name.to_string();
+        if self.collapse_anonymous_namespaces {
+            for marker in ANONYMOUS_NAMESPACE_MARKERS {
+                name = name.replace(marker, ANONYMOUS_NAMESPACE);
+            }
+        }
+        if self.strip_template_arguments {
+            name = strip_template_arguments(&name);
+        }
+
+        match self.max_len {
+            Some(max_len) if name.chars().count() > max_len => {
+                if self.hash_long_names {
+                    let hash = format!("~{:016x}", fnv1a(name.as_bytes()));
+                    let prefix_len = max_len.saturating_sub(hash.len());
+                    let mut truncated: String = name.chars().take(prefix_len).collect();
+                    truncated.push_str(&hash);
+                    truncated.chars().take(max_len).collect()
+                } else {
+                    name.chars().take(max_len).collect()
+                }
+            }
+            _ => name,
+        }
+    }
+}
+
+/// Rewrites the names of the symbols and of the classes, unions, and enums of `pdb_info`
+pub fn normalize_names(pdb_info: &mut ParsedPdb, options: &NameOptions) {
+    if options.is_identity() {
+        return;
+    }
+
+    let normalize = |name: &mut String| *name = options.normalize(name);
+    for symbol in &mut pdb_info.public_symbols {
+        normalize(&mut symbol.name);
+        if let Some(name) = &mut symbol.procedure_name {
+            normalize(name);
+        }
+    }
+    for procedure in &mut pdb_info.procedures {
+        normalize(&mut procedure.name);
+        if let Some(name) = &mut procedure.public_name {
+            normalize(name);
+        }
+    }
+    for data in pdb_info
+        .global_data
+        .iter_mut()
+        .chain(pdb_info.local_data.iter_mut())
+    {
+        normalize(&mut data.name);
+        if let Some(name) = &mut data.function {
+            normalize(name);
+        }
+    }
+
+    for id in pdb_info.types.ids().collect::<Vec<_>>() {
+        let name = match &mut pdb_info.types[id] {
+            Type::Class(class) => &mut class.name,
+            Type::Union(union) => &mut union.name,
+            Type::Enumeration(e) => &mut e.name,
+            _ => continue,
+        };
+        *name = Arc::from(options.normalize(name));
+    }
+}
+
+/// A symbol which was renamed to tell it apart from others of the same name
+#[derive(Debug, Clone)]
+pub struct Rename {
+    /// `procedure`, `global data`, or `local data`
+    pub kind: &'static str,
+    pub name: String,
+    pub renamed: String,
+    pub rva: Option<usize>,
+    pub module: Option<String>,
+}
+
+/// Which listing a symbol being disambiguated is in, and its index there
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum SymbolRef {
+    Procedure(usize),
+    GlobalData(usize),
+    LocalData(usize),
+}
+
+/// What's known about a symbol for naming it
+#[derive(Debug)]
+struct NamedSymbol {
+    symbol: SymbolRef,
+    rva: Option<usize>,
+    is_global: bool,
+    module: Option<String>,
+}
+
+/// Renames procedures and global and local data which share a name with a
+/// different symbol, such as static functions of the same name in different
+/// object files, by appending `suffix`. Symbols at the same address are the
+/// same symbol and keep the same name. If exactly one of the symbols sharing a
+/// name is global it keeps the name, as other code refers to it by that name.
+///
+/// Renaming is deterministic: the same PDB and options always produce the same
+/// names. Returns each rename, ordered by name and address.
+pub fn disambiguate_names(pdb_info: &mut ParsedPdb, suffix: NameSuffix) -> Vec<Rename> {
+    let module_name = |index: Option<usize>| {
+        index
+            .and_then(|index| pdb_info.debug_modules.get(index))
+            .map(|module| module.name.clone())
+    };
+
+    let mut by_name: BTreeMap<String, Vec<NamedSymbol>> = BTreeMap::new();
+    for (index, procedure) in pdb_info.procedures.iter().enumerate() {
+        by_name
+            .entry(procedure.name.clone())
+            .or_default()
+            .push(NamedSymbol {
+                symbol: SymbolRef::Procedure(index),
+                rva: procedure.address.map(|address| address.rva),
+                is_global: procedure.is_global,
+                module: module_name(procedure.module_index),
+            });
+    }
+    for (index, data) in pdb_info.global_data.iter().enumerate() {
+        by_name
+            .entry(data.name.clone())
+            .or_default()
+            .push(NamedSymbol {
+                symbol: SymbolRef::GlobalData(index),
+                rva: data.offset.map(|address| address.rva),
+                is_global: data.is_global,
+                module: data.module.clone(),
+            });
+    }
+    for (index, data) in pdb_info.local_data.iter().enumerate() {
+        by_name
+            .entry(data.name.clone())
+            .or_default()
+            .push(NamedSymbol {
+                symbol: SymbolRef::LocalData(index),
+                rva: data.offset.map(|address| address.rva),
+                is_global: data.is_global,
+                module: data.module.clone(),
+            });
+    }
+
+    let mut taken: HashSet<String> = by_name.keys().cloned().collect();
+    let mut renames = vec![];
+    for (name, mut symbols) in by_name {
+        symbols.sort_by_key(|symbol| (symbol.rva.is_none(), symbol.rva, symbol.symbol));
+        let addresses: HashSet<Option<usize>> = symbols.iter().map(|symbol| symbol.rva).collect();
+        if addresses.len() < 2 {
+            continue;
+        }
+
+        let global_addresses: HashSet<Option<usize>> = symbols
+            .iter()
+            .filter(|symbol| symbol.is_global)
+            .map(|symbol| symbol.rva)
+            .collect();
+        let keeper = match global_addresses.len() {
+            1 => global_addresses.into_iter().next(),
+            _ => None,
+        };
+
+        // Symbols without an address can't be told apart by it, so they're numbered
+        let mut unaddressed = 0;
+        let mut new_names: BTreeMap<Option<usize>, String> = BTreeMap::new();
+        for symbol in symbols {
+            if keeper == Some(symbol.rva) {
+                continue;
+            }
+
+            let renamed = new_names
+                .entry(symbol.rva)
+                .or_insert_with(|| {
+                    let fallback = match symbol.rva {
+                        Some(rva) => format!("{:x}", rva),
+                        None => {
+                            unaddressed += 1;
+                            unaddressed.to_string()
+                        }
+                    };
+                    let preferred = match suffix {
+                        NameSuffix::Module => symbol
+                            .module
+                            .as_deref()
+                            .map(module_stem)
+                            .unwrap_or_else(|| fallback.clone()),
+                        NameSuffix::Rva => fallback.clone(),
+                    };
+
+                    let mut renamed = format!("{}_{}", name, preferred);
+                    if taken.contains(&renamed) && preferred != fallback {
+                        renamed = format!("{}_{}_{}", name, preferred, fallback);
+                    }
+                    let base = renamed.clone();
+                    let mut counter = 1;
+                    while taken.contains(&renamed) {
+                        counter += 1;
+                        renamed = format!("{}_{}", base, counter);
+                    }
+                    taken.insert(renamed.clone());
+                    renamed
+                })
+                .clone();
+
+            let target = match symbol.symbol {
+                SymbolRef::Procedure(index) => &mut pdb_info.procedures[index].name,
+                SymbolRef::GlobalData(index) => &mut pdb_info.global_data[index].name,
+                SymbolRef::LocalData(index) => &mut pdb_info.local_data[index].name,
+            };
+            *target = renamed.clone();
+
+            renames.push(Rename {
+                kind: match symbol.symbol {
+                    SymbolRef::Procedure(_) => "procedure",
+                    SymbolRef::GlobalData(_) => "global data",
+                    SymbolRef::LocalData(_) => "local data",
+                },
+                name: name.clone(),
+                renamed,
+                rva: symbol.rva,
+                module: symbol.module,
+            });
+        }
+    }
+
+    renames
+}
+
+/// Writes `renames` as a JSON array of objects
+pub fn write_renames(output: &mut impl Write, renames: &[Rename]) -> io::Result<()> {
+    let renames: Vec<_> = renames
+        .iter()
+        .map(|rename| {
+            json!({
+                "kind": rename.kind,
+                "name": rename.name,
+                "renamed": rename.renamed,
+                "rva": rename.rva,
+                "module": rename.module,
+            })
+        })
+        .collect();
+
+    write!(output, "{}", serde_json::Value::Array(renames))
+}
+
+/// Returns the file name of a module without its directory or extension, with
+/// characters which can't appear in identifiers replaced by `_`
+fn module_stem(module: &str) -> String {
+    let file_name = module.rsplit(['/', '\\']).next().unwrap_or(module);
+    let stem = match file_name.rfind('.') {
+        Some(dot) if dot > 0 => &file_name[..dot],
+        _ => file_name,
+    };
+
+    stem.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Replaces the arguments of each template in `name` with `<>`. The angle
+/// brackets of operators such as `operator<` and `operator->` are kept, and
+/// don't open or close a template when they appear in template arguments.
+fn strip_template_arguments(name: &str) -> String {
+    let mut stripped = String::with_capacity(name.len());
+    let mut depth = 0usize;
+    let mut previous = None;
+    let mut rest = name;
+    while let Some(c) = rest.chars().next() {
+        let is_identifier_start = !previous.is_some_and(|c: char| c.is_alphanumeric() || c == '_');
+        if is_identifier_start {
+            if let Some(after) = rest.strip_prefix("operator") {
+                let operator = OPERATORS
+                    .iter()
+                    .find(|operator| after.starts_with(*operator))
+                    .map_or("", |operator| *operator);
+                let (operator, after) = rest.split_at("operator".len() + operator.len());
+                if depth == 0 {
+                    stripped.push_str(operator);
+                }
+                previous = operator.chars().next_back();
+                rest = after;
+                continue;
+            }
+        }
+
+        match c {
+            '<' => {
+                if depth == 0 {
+                    stripped.push_str("<>");
+                }
+                depth += 1;
+            }
+            '>' if depth > 0 => depth -= 1,
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+        previous = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    stripped
+}
+
+/// The 64-bit FNV-1a hash of `data`, which is stable across runs and platforms
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ezpdb::symbol_types::{Address, Data};
+    use ezpdb::type_info::TypeId;
+    use std::path::PathBuf;
+
+    #[test]
+    fn operators_of_templates_are_kept() {
+        assert_eq!(
+            strip_template_arguments("std::less<int>::operator<"),
+            "std::less<>::operator<"
+        );
+        assert_eq!(
+            strip_template_arguments("std::unique_ptr<Widget>::operator->"),
+            "std::unique_ptr<>::operator->"
+        );
+        assert_eq!(
+            strip_template_arguments("Version<2>::operator<=>"),
+            "Version<>::operator<=>"
+        );
+        assert_eq!(
+            strip_template_arguments("Widget::operator< <int>"),
+            "Widget::operator< <>"
+        );
+    }
+
+    #[test]
+    fn operators_in_template_arguments_are_stripped() {
+        assert_eq!(
+            strip_template_arguments("Sort<&Key::operator< >::run"),
+            "Sort<>::run"
+        );
+        assert_eq!(
+            strip_template_arguments("Dispatch<&Widget::operator->>::call"),
+            "Dispatch<>::call"
+        );
+        assert_eq!(
+            strip_template_arguments("Compare<&Key::operator<=>>::run"),
+            "Compare<>::run"
+        );
+    }
+
+    #[test]
+    fn nested_templates_are_stripped() {
+        assert_eq!(
+            strip_template_arguments("std::map<std::string,std::vector<int> >::insert"),
+            "std::map<>::insert"
+        );
+        assert_eq!(
+            strip_template_arguments("Outer<Inner<int>>::Nested<char>::get<Outer<int>>"),
+            "Outer<>::Nested<>::get<>"
+        );
+        assert_eq!(
+            strip_template_arguments("my_operator<int>"),
+            "my_operator<>"
+        );
+    }
+
+    #[test]
+    fn long_names_are_truncated() {
+        let options = NameOptions {
+            max_len: Some(24),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.normalize("std::basic_string<char>::append"),
+            "std::basic_string<char>:"
+        );
+        assert_eq!(
+            options.normalize("std::string::append"),
+            "std::string::append"
+        );
+    }
+
+    #[test]
+    fn hashed_names_keep_the_hash() {
+        let options = NameOptions {
+            max_len: Some(24),
+            hash_long_names: true,
+            ..Default::default()
+        };
+        let name = options.normalize("std::basic_string<char>::append");
+        assert_eq!(name.len(), 24);
+        assert!(name.starts_with("std::ba~"));
+        assert_ne!(name, options.normalize("std::basic_string<char>::assign"));
+    }
+
+    #[test]
+    fn hashed_names_shorter_than_the_hash_are_truncated_hashes() {
+        let options = NameOptions {
+            max_len: Some(8),
+            hash_long_names: true,
+            ..Default::default()
+        };
+        let name = options.normalize("std::basic_string<char>::append");
+        assert_eq!(name.len(), 8);
+        assert!(name.starts_with('~'));
+        assert_eq!(name, options.normalize("std::basic_string<char>::append"));
+        assert_ne!(name, options.normalize("std::basic_string<char>::assign"));
+    }
+
+    fn data(name: &str, rva: usize, is_global: bool, module: &str) -> Data {
+        Data {
+            name: name.to_string(),
+            is_global,
+            is_managed: false,
+            ty: TypeId(0),
+            offset: Some(Address::new(rva, None)),
+            module: Some(module.to_string()),
+            function: None,
+            folded_with: vec![],
+            storage: None,
+        }
+    }
+
+    fn disambiguated(local_data: Vec<Data>, suffix: NameSuffix) -> Vec<(String, usize)> {
+        let mut pdb_info = ParsedPdb::new(PathBuf::from("test.pdb"));
+        pdb_info.local_data = local_data;
+        disambiguate_names(&mut pdb_info, suffix);
+
+        let mut names: Vec<_> = pdb_info
+            .local_data
+            .iter()
+            .map(|data| (data.name.clone(), data.offset.unwrap().rva))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn shared_names_are_renamed_by_module() {
+        let symbols = vec![
+            data("counter", 0x3000, false, "C:\\src\\b.obj"),
+            data("counter", 0x1000, false, "C:\\src\\a.obj"),
+            data("counter", 0x2000, false, "C:\\src\\a.obj"),
+            data("counter", 0x2000, false, "C:\\src\\a.obj"),
+            data("unique", 0x4000, false, "C:\\src\\a.obj"),
+        ];
+        let expected = vec![
+            ("counter_a".to_string(), 0x1000),
+            ("counter_a_2000".to_string(), 0x2000),
+            ("counter_a_2000".to_string(), 0x2000),
+            ("counter_b".to_string(), 0x3000),
+            ("unique".to_string(), 0x4000),
+        ];
+        assert_eq!(disambiguated(symbols.clone(), NameSuffix::Module), expected);
+
+        // The order symbols are listed in doesn't change their names
+        let mut reversed = symbols;
+        reversed.reverse();
+        assert_eq!(disambiguated(reversed, NameSuffix::Module), expected);
+    }
+
+    #[test]
+    fn the_only_global_keeps_its_name() {
+        let symbols = vec![
+            data("counter", 0x1000, false, "a.obj"),
+            data("counter", 0x2000, true, "b.obj"),
+        ];
+        assert_eq!(
+            disambiguated(symbols, NameSuffix::Rva),
+            vec![
+                ("counter".to_string(), 0x2000),
+                ("counter_1000".to_string(), 0x1000),
+            ]
+        );
+    }
+}
@@ -1,532 +1,2080 @@
+use crate::filter::{Filter, Filterable};
+use crate::pagination::Pagination;
+use crate::table::{write_styled, Style, Table};
+use crate::{CliArgumentError, ModuleSortKey, SortKey};
+use ezpdb::audit::ModuleAudit;
+use ezpdb::exports::ExportMismatch;
+use ezpdb::folding::FoldedGroup;
+use ezpdb::function_pointers::WritableFunctionPointer;
+use ezpdb::render::{self, RenderOptions};
+use ezpdb::search::{NamePattern, SearchHit, SymbolMatch};
+use ezpdb::sources::{SourceFileStatistics, SourceStatus, SourceVerification};
 use ezpdb::symbol_types::*;
+use ezpdb::syscalls::Syscall;
+use ezpdb::timing::{StageSummary, StageTiming};
 use ezpdb::type_info::*;
-use log::{debug, warn};
+use ezpdb::verify::Issue;
+use ezpdb::xref::{TypeMetrics, TypeReferences};
+use std::borrow::Cow;
 use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use termcolor::WriteColor;
+use tracing::debug;
 
-pub fn print_plain(output: &mut impl Write, pdb_info: &ParsedPdb) -> io::Result<()> {
+/// Prints `pdb_info` as aligned tables, with type names formatted as `render`
+/// says. If `max_width` is provided, long names are truncated so that rows fit
+/// within it.
+pub fn print_plain(
+    output: &mut impl WriteColor,
+    pdb_info: &ParsedPdb,
+    listing: &ListingOptions,
+    max_width: Option<usize>,
+    flatten_anonymous: bool,
+    render: &RenderOptions,
+) -> io::Result<()> {
     // region: Header info
-    // Print header information
-    writeln!(output, "{:?}:", &pdb_info.path)?;
+    if listing.shows(OutputSection::Header) {
+        // Print header information
+        writeln!(output, "{:?}:", &pdb_info.path)?;
 
-    writeln!(output, "PDB Version: {:?}", pdb_info.version)?;
-    writeln!(
-        output,
-        "Machine Type: {}",
-        pdb_info
-            .machine_type
-            .as_ref()
-            .map(|ty| format!("{:?}", ty))
-            .unwrap_or_else(|| "Unknown".to_string())
-    )?;
-
-    writeln!(output, "Assembly Info:")?;
-
-    writeln!(output, "\tBuild Info:")?;
-
-    writeln!(output, "\tCompiler Info:")?;
-    let width = 40usize;
-    if let Some(compiler_info) = &pdb_info.assembly_info.compiler_info {
-        writeln!(output, "\t\tLanguage: {}", compiler_info.language)?;
-
-        let CompileFlags {
-            edit_and_continue,
-            no_debug_info,
-            link_time_codegen,
-            no_data_align,
-            managed,
-            security_checks,
-            hot_patch,
-            cvtcil,
-            msil_module,
-            sdl,
-            pgo,
-            exp_module,
-        } = compiler_info.flags;
-        writeln!(output, "\t\tFlags:")?;
-
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Edit and continue:",
-            edit_and_continue,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "No debug info:",
-            no_debug_info,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Link-time codegen (LTCG):",
-            link_time_codegen,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "No data align (/bzalign):",
-            no_data_align,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Managed code or data is present:",
-            managed,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Security checks (/GS):",
-            security_checks,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Hot patching (/hotpatch):",
-            hot_patch,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "CvtCIL:",
-            cvtcil,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Is MSIL .NET module:",
-            msil_module,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Compiled with /SDL:",
-            sdl,
-            width = width
-        )?;
+        writeln!(output, "PDB Version: {:?}", pdb_info.version)?;
         writeln!(
             output,
-            "\t\t\t{:width$} {}",
-            "PGO (`/ltcg:pgo` or `pgo:`):",
-            pgo,
-            width = width
-        )?;
-        writeln!(
-            output,
-            "\t\t\t{:width$} {}",
-            "Is .exp module:",
-            exp_module,
-            width = width
-        )?;
-        writeln!(output, "\t\tCPU type: {}", compiler_info.cpu_type,)?;
-        let CompilerVersion {
-            major,
-            minor,
-            build,
-            qfe,
-        } = compiler_info.frontend_version;
-        writeln!(
-            output,
-            "\t\tFrontend version: {}.{}.{}, QFE={}",
-            major,
-            minor,
-            build,
-            qfe.map(|qfe| format!("{}", qfe))
-                .unwrap_or_else(|| "None".to_string())
+            "Machine Type: {}",
+            pdb_info
+                .machine_type
+                .as_ref()
+                .map(|ty| format!("{:?}", ty))
+                .unwrap_or_else(|| "Unknown".to_string())
         )?;
+        if pdb_info.is_fastlink {
+            writeln!(output, "Fastlink: yes")?;
+            writeln!(output, "\t{}", FASTLINK_GUIDANCE)?;
+        }
+        if let Some(hybrid) = &pdb_info.hybrid {
+            writeln!(output, "Hybrid Metadata:")?;
+            writeln!(output, "\tSymbols:")?;
+            for symbol in &hybrid.metadata {
+                writeln!(
+                    output,
+                    "\t\t{:<10} {}",
+                    format_address(symbol.address),
+                    symbol.name
+                )?;
+            }
+            writeln!(output, "\tSections: {}", hybrid.sections.join(", "))?;
+        }
 
-        let CompilerVersion {
-            major,
-            minor,
-            build,
-            qfe,
-        } = compiler_info.backend_version;
-        writeln!(
-            output,
-            "\t\tBackend version: {}.{}.{}, QFE={}",
-            major,
-            minor,
-            build,
-            qfe.map(|qfe| format!("{}", qfe))
-                .unwrap_or_else(|| "None".to_string())
-        )?;
-        writeln!(
-            output,
-            "\t\tVersion string: {}",
-            compiler_info.version_string
-        )?;
+        writeln!(output, "Assembly Info:")?;
+
+        writeln!(output, "\tBuild Info:")?;
+
+        writeln!(output, "\tCompiler Info:")?;
+        let width = 40usize;
+        if let Some(compiler_info) = &pdb_info.assembly_info.compiler_info {
+            writeln!(output, "\t\tLanguage: {}", compiler_info.language)?;
+
+            let CompileFlags {
+                edit_and_continue,
+                no_debug_info,
+                link_time_codegen,
+                no_data_align,
+                managed,
+                security_checks,
+                hot_patch,
+                cvtcil,
+                msil_module,
+                sdl,
+                pgo,
+                exp_module,
+            } = compiler_info.flags;
+            writeln!(output, "\t\tFlags:")?;
+
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Edit and continue:",
+                edit_and_continue,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "No debug info:",
+                no_debug_info,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Link-time codegen (LTCG):",
+                link_time_codegen,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "No data align (/bzalign):",
+                no_data_align,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Managed code or data is present:",
+                managed,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Security checks (/GS):",
+                security_checks,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Hot patching (/hotpatch):",
+                hot_patch,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "CvtCIL:",
+                cvtcil,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Is MSIL .NET module:",
+                msil_module,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Compiled with /SDL:",
+                sdl,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "PGO (`/ltcg:pgo` or `pgo:`):",
+                pgo,
+                width = width
+            )?;
+            writeln!(
+                output,
+                "\t\t\t{:width$} {}",
+                "Is .exp module:",
+                exp_module,
+                width = width
+            )?;
+            writeln!(output, "\t\tCPU type: {}", compiler_info.cpu_type,)?;
+            let CompilerVersion {
+                major,
+                minor,
+                build,
+                qfe,
+            } = compiler_info.frontend_version;
+            writeln!(
+                output,
+                "\t\tFrontend version: {}.{}.{}, QFE={}",
+                major,
+                minor,
+                build,
+                qfe.map(|qfe| format!("{}", qfe))
+                    .unwrap_or_else(|| "None".to_string())
+            )?;
+
+            let CompilerVersion {
+                major,
+                minor,
+                build,
+                qfe,
+            } = compiler_info.backend_version;
+            writeln!(
+                output,
+                "\t\tBackend version: {}.{}.{}, QFE={}",
+                major,
+                minor,
+                build,
+                qfe.map(|qfe| format!("{}", qfe))
+                    .unwrap_or_else(|| "None".to_string())
+            )?;
+            writeln!(
+                output,
+                "\t\tVersion string: {}",
+                compiler_info.version_string
+            )?;
+        }
     }
     // endregion
 
     // region: Public symbols
-    writeln!(output, "Public symbols:")?;
-    writeln!(output, "\t{:<10} Name", "Offset")?;
-    for symbol in &pdb_info.public_symbols {
-        write!(output, "\t")?;
-        if let Some(offset) = symbol.offset {
-            write!(output, "0x{:08X} ", offset)?;
-        } else {
-            write!(output, "{:<10} ", "")?;
+    if listing.shows(OutputSection::PublicSymbols) {
+        write_styled(output, Style::Heading, "Public symbols:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Name"]);
+        for symbol in listing.select(pdb_info, &pdb_info.public_symbols) {
+            table.row(vec![
+                (format_address(symbol.offset), Style::Address),
+                (symbol.name.clone(), Style::Name),
+            ]);
         }
-        writeln!(output, "{}", symbol.name)?;
+        table.write(output, max_width)?;
     }
     // endregion
 
     // region: Procedures
-    writeln!(output, "Procedures:")?;
-    writeln!(
-        output,
-        "\t{:<10} {:<10} {:<15} {:<15} {:<10}",
-        "Offset", "Length", "Prologue End", "Epilogue Start", "Name"
-    )?;
-
-    for procedure in &pdb_info.procedures {
-        write!(output, "\t")?;
-        if let Some(address) = procedure.address {
-            write!(output, "0x{:08X} ", address)?;
-        } else {
-            write!(output, "{:<10} ", "")?;
+    if listing.shows(OutputSection::Procedures) {
+        write_styled(output, Style::Heading, "Procedures:")?;
+        writeln!(output)?;
+        let mut table = Table::new(
+            "\t",
+            &[
+                "Offset",
+                "Length",
+                "Prologue End",
+                "Epilogue Start",
+                "Flags",
+                "Source",
+                "Name",
+            ],
+        );
+        for procedure in listing.select(pdb_info, &pdb_info.procedures) {
+            table.row(vec![
+                (format_address(procedure.address), Style::Address),
+                (format!("0x{:08X}", procedure.len), Style::Plain),
+                (format!("0x{:08X}", procedure.prologue_end), Style::Plain),
+                (format!("0x{:08X}", procedure.epilogue_start), Style::Plain),
+                (format_procedure_flags(procedure), Style::Plain),
+                (format_source_range(procedure.source.as_ref()), Style::Plain),
+                (
+                    format_procedure_name(pdb_info, procedure, render),
+                    Style::Name,
+                ),
+            ]);
         }
-
-        write!(output, "0x{:08X} ", procedure.len)?;
-        write!(
-            output,
-            "{:<15}",
-            format!("0x{:08X} ", procedure.prologue_end)
-        )?;
-        write!(
-            output,
-            "{:<15}",
-            format!("0x{:08X} ", procedure.epilogue_start)
-        )?;
-        writeln!(output, "{}", procedure.name)?;
+        table.write(output, max_width)?;
     }
     // endregion
 
     // region: Data
-    writeln!(output, "Globals:")?;
-    writeln!(output, "\t{:<10} {:<10}", "Offset", "Name")?;
+    if listing.shows(OutputSection::Globals) {
+        write_styled(output, Style::Heading, "Globals:")?;
+        writeln!(output)?;
+        let mut table = Table::new(
+            "\t",
+            &["Offset", "Size", "Storage", "Managed", "Type", "Name"],
+        );
+        for global in listing.select(pdb_info, &pdb_info.global_data) {
+            let ty: &Type = &pdb_info.types[global.ty];
+            table.row(vec![
+                (format_address(global.offset), Style::Address),
+                (format!("0x{:X}", ty.type_size(pdb_info)), Style::Plain),
+                (format_storage(global), Style::Plain),
+                (global.is_managed.to_string(), Style::Plain),
+                (format_type_name(pdb_info, ty, render), Style::Type),
+                (global.name.clone(), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
 
-    for global in &pdb_info.global_data {
-        write!(output, "\t")?;
-        if let Some(offset) = global.offset {
-            write!(output, "0x{:08X} ", offset)?;
-        } else {
-            write!(output, "{:<10} ", "")?;
+    // region: Local data
+    if listing.shows(OutputSection::LocalData) && !pdb_info.local_data.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Local data:")?;
+        writeln!(output)?;
+        let mut table = Table::new(
+            "\t",
+            &["Offset", "Size", "Storage", "Type", "Scope", "Name"],
+        );
+        for data in listing.select(pdb_info, &pdb_info.local_data) {
+            let ty: &Type = &pdb_info.types[data.ty];
+            table.row(vec![
+                (format_address(data.offset), Style::Address),
+                (format!("0x{:X}", ty.type_size(pdb_info)), Style::Plain),
+                (format_storage(data), Style::Plain),
+                (format_type_name(pdb_info, ty, render), Style::Type),
+                (format_data_scope(data), Style::Plain),
+                (data.name.clone(), Style::Name),
+            ]);
         }
-        writeln!(output, "{}", global.name)?;
+        table.write(output, max_width)?;
+    }
+    // endregion
 
-        let ty: &Type = &*global.ty.as_ref().borrow();
-        writeln!(output, "\t\tType: {}", format_type_name(ty))?;
-        writeln!(output, "\t\tSize: 0x{:X}", ty.type_size(pdb_info))?;
-        writeln!(output, "\t\tIs Managed: {}", global.is_managed)?;
+    // region: Annotations
+    if listing.shows(OutputSection::Annotations) && !pdb_info.annotations.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Annotations:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Strings"]);
+        for annotation in listing.select(pdb_info, &pdb_info.annotations) {
+            table.row(vec![
+                (format_address(annotation.offset), Style::Address),
+                (annotation.strings.join(" "), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
     }
     // endregion
 
-    // region: Types
-    writeln!(output)?;
-    writeln!(output, "Types:")?;
-
-    let width = 20usize;
-    for ty in pdb_info.types.values() {
-        let ty: &Type = &*ty.as_ref().borrow();
-        match ty {
-            Type::Class(class) => {
-                if class.properties.forward_reference {
-                    continue;
-                }
+    // region: Trampolines
+    if listing.shows(OutputSection::Trampolines) && !pdb_info.trampolines.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Trampolines:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Size", "Kind", "Target"]);
+        for trampoline in listing.select(pdb_info, &pdb_info.trampolines) {
+            table.row(vec![
+                (format_address(trampoline.offset), Style::Address),
+                (format!("0x{:X}", trampoline.size), Style::Plain),
+                (format!("{:?}", trampoline.kind), Style::Plain),
+                (format_address(trampoline.target), Style::Address),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
 
-                writeln!(
-                    output,
-                    "\t{:width$} {} {}",
-                    class.kind,
-                    class.name,
-                    class.unique_name.as_ref().map(String::as_ref).unwrap_or(""),
-                    width = 10
-                )?;
-                writeln!(output, "\tSize: 0x{:X}", class.size)?;
-                // writeln!(
-                //     output,
-                //     "\t\t{:width$} {}",
-                //     "Name:",
-                //     class.name,
-                //     width = width
-                // )?;
-                // writeln!(
-                //     output,
-                //     "\t\t{:width$} {}",
-                //     "Unique name:",
-                //     class.unique_name.as_ref().map(String::as_ref).unwrap_or(""),
-                //     width = width
-                // )?;
-                writeln!(output, "\tFields:")?;
-                for field in &class.fields {
-                    let field: &Type = &*field.as_ref().borrow();
-
-                    match field {
-                        Type::Member(member) => {
-                            let member_ty: &Type = &*member.underlying_type.as_ref().borrow();
-                            writeln!(
-                                output,
-                                "\t\t0x{:04X} {:width$} {}",
-                                member.offset,
-                                member.name,
-                                format_type_name(member_ty),
-                                width = width
-                            )?;
-                        }
-                        Type::BaseClass(base) => {
-                            writeln!(
-                                output,
-                                "\t\t0x{:04X} <BaseClass> {}",
-                                base.offset,
-                                format_type_name(&*base.base_class.as_ref().borrow())
-                            )?;
-                        }
-                        Type::VirtualBaseClass(_) => {
-                            // ignore
-                        }
-                        Type::Nested(_nested) => {
-                            // writeln!(
-                            //     output,
-                            //     "\t\t (NestedType) {} {}",
-                            //     nested.name,
-                            //     format_type_name(&*nested.nested_type.as_ref().borrow())
-                            // )?;
-                        }
-                        Type::Method(_) | Type::OverloadedMethod(_) => {
-                            // ignore methods
-                        }
-                        Type::VTable(_) => {
-                            // ignore vtable
-                        }
-                        Type::StaticMember(_) => {
-                            // ignore
-                        }
-                        other => {
-                            debug!("Unexpected field type present in class: {:?}", other)
-                        }
-                    }
-                }
+    // region: Separated code
+    if listing.shows(OutputSection::SeparatedCode) && !pdb_info.separated_code.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Separated code:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Length", "Parent"]);
+        for code in listing.select(pdb_info, &pdb_info.separated_code) {
+            table.row(vec![
+                (format_address(code.offset), Style::Address),
+                (format!("0x{:08X}", code.len), Style::Plain),
+                (format_address(code.parent_offset), Style::Address),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
+
+    // region: Calls
+    if listing.shows(OutputSection::Calls) {
+        if !pdb_info.call_sites.is_empty() {
+            writeln!(output)?;
+            write_styled(output, Style::Heading, "Call sites:")?;
+            writeln!(output)?;
+            let mut table = Table::new("\t", &["Offset", "Caller", "Signature"]);
+            for site in listing.select(pdb_info, &pdb_info.call_sites) {
+                table.row(vec![
+                    (format_address(site.offset), Style::Address),
+                    (site.caller.clone().unwrap_or_default(), Style::Name),
+                    (
+                        site.ty
+                            .map(|ty| format_type_name(pdb_info, &pdb_info.types[ty], render))
+                            .unwrap_or_default(),
+                        Style::Type,
+                    ),
+                ]);
             }
-            Type::Union(union) => {
-                if union.properties.forward_reference {
-                    continue;
-                }
+            table.write(output, max_width)?;
+        }
 
-                writeln!(
-                    output,
-                    "\tUnion {} {}",
-                    union.name,
-                    union.unique_name.as_ref().map(String::as_ref).unwrap_or(""),
-                )?;
-                writeln!(output, "\tSize: 0x{:X}", union.size)?;
-                // writeln!(
-                //     output,
-                //     "\t\t{:width$} {}",
-                //     "Name:",
-                //     class.name,
-                //     width = width
-                // )?;
-                // writeln!(
-                //     output,
-                //     "\t\t{:width$} {}",
-                //     "Unique name:",
-                //     class.unique_name.as_ref().map(String::as_ref).unwrap_or(""),
-                //     width = width
-                // )?;
-                writeln!(output, "\tFields:")?;
-                for field in &union.fields {
-                    let field: &Type = &*field.as_ref().borrow();
-
-                    match field {
-                        Type::Member(member) => {
-                            let member_ty: &Type = &*member.underlying_type.as_ref().borrow();
-                            writeln!(
-                                output,
-                                "\t\t0x{:04X} {:width$} {}",
-                                member.offset,
-                                member.name,
-                                format_type_name(member_ty),
-                                width = width
-                            )?;
-                        }
-                        Type::BaseClass(base) => {
-                            writeln!(
-                                output,
-                                "\t\t0x{:04X} <BaseClass> {}",
-                                base.offset,
-                                format_type_name(&*base.base_class.as_ref().borrow())
-                            )?;
-                        }
-                        Type::VirtualBaseClass(_) => {
-                            // ignore
-                        }
-                        Type::Nested(_nested) => {
-                            // ignore nested types
-                            // writeln!(
-                            //     output,
-                            //     "\t\t (NestedType) {} {}",
-                            //     nested.name,
-                            //     format_type_name(&*nested.nested_type.as_ref().borrow())
-                            // )?;
-                        }
-                        Type::Method(_) | Type::OverloadedMethod(_) => {
-                            // ignore methods
-                        }
-                        Type::VTable(_) => {
-                            // ignore vtable
-                        }
-                        Type::StaticMember(_) => {
-                            // ignore
-                        }
-                        other => {
-                            debug!("Unexpected field type present in class: {:?}", other)
-                        }
-                    }
-                }
+        if !pdb_info.call_graph.is_empty() {
+            writeln!(output)?;
+            write_styled(output, Style::Heading, "Call graph:")?;
+            writeln!(output)?;
+            let mut table = Table::new("\t", &["Invocations", "Caller", "Callee"]);
+            for edge in listing.select(pdb_info, &pdb_info.call_graph) {
+                table.row(vec![
+                    (
+                        edge.invocations
+                            .map(|count| count.to_string())
+                            .unwrap_or_default(),
+                        Style::Plain,
+                    ),
+                    (edge.caller.clone(), Style::Name),
+                    (edge.callee.clone(), Style::Name),
+                ]);
             }
-            Type::Enumeration(e) => {
-                if e.properties.forward_reference {
-                    continue;
-                }
+            table.write(output, max_width)?;
+        }
+    }
+    // endregion
 
-                writeln!(
-                    output,
-                    "\tEnum {} {}",
-                    e.name,
-                    e.unique_name.as_ref().map(String::as_ref).unwrap_or(""),
-                )?;
-                if let Type::Primitive(primitive) = &*e.underlying_type.borrow() {
-                    writeln!(output, "\tSize: 0x{:X}", primitive.size())?;
-                }
-                let underlying_type = e.underlying_type.borrow();
-                writeln!(output, "\tType: {}", format_type_name(&*underlying_type))?;
-                writeln!(output, "\tVariants:")?;
-                for variant in &e.variants {
-                    let value = match variant.value {
-                        VariantValue::U8(v) => v as u64,
-                        VariantValue::U16(v) => v as u64,
-                        VariantValue::U32(v) => v as u64,
-                        VariantValue::U64(v) => v as u64,
-                        VariantValue::I8(v) => v as u64,
-                        VariantValue::I16(v) => v as u64,
-                        VariantValue::I32(v) => v as u64,
-                        VariantValue::I64(v) => v as u64,
-                    };
-
-                    writeln!(output, "\t\t0x{:08X} {}", value, variant.name)?;
-                }
+    // region: Sections
+    if listing.shows(OutputSection::Sections) {
+        if !pdb_info.sections.is_empty() {
+            writeln!(output)?;
+            write_styled(output, Style::Heading, "Sections:")?;
+            writeln!(output)?;
+            let mut table = Table::new("\t", &["Offset", "Size", "Characteristics", "Name"]);
+            for section in listing.select(pdb_info, &pdb_info.sections) {
+                table.row(vec![
+                    (format_address(Some(section.offset)), Style::Address),
+                    (format!("0x{:X}", section.len), Style::Plain),
+                    (format!("0x{:08X}", section.characteristics), Style::Plain),
+                    (section.name.clone(), Style::Name),
+                ]);
             }
-            _ => {
-                continue;
+            table.write(output, max_width)?;
+        }
+
+        if !pdb_info.coff_groups.is_empty() {
+            writeln!(output)?;
+            write_styled(output, Style::Heading, "COFF groups:")?;
+            writeln!(output)?;
+            let mut table = Table::new("\t", &["Offset", "Size", "Characteristics", "Name"]);
+            for group in listing.select(pdb_info, &pdb_info.coff_groups) {
+                table.row(vec![
+                    (format_address(group.offset), Style::Address),
+                    (format!("0x{:X}", group.len), Style::Plain),
+                    (format!("0x{:08X}", group.characteristics), Style::Plain),
+                    (group.name.clone(), Style::Name),
+                ]);
             }
+            table.write(output, max_width)?;
         }
+    }
+    // endregion
+
+    // region: Static initializers
+    if listing.shows(OutputSection::StaticInitializers) && !pdb_info.static_initializers.is_empty()
+    {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Static initializers:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Kind", "Target", "Name"]);
+        for initializer in listing.select(pdb_info, &pdb_info.static_initializers) {
+            table.row(vec![
+                (format_address(initializer.offset), Style::Address),
+                (format!("{:?}", initializer.kind), Style::Plain),
+                (format_initializer_target(initializer), Style::Plain),
+                (initializer.name.clone(), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
+
+    // region: Security metadata
+    if listing.shows(OutputSection::SecuritySymbols) && !pdb_info.security_symbols.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Security metadata:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Mitigation", "Name"]);
+        for symbol in listing.select(pdb_info, &pdb_info.security_symbols) {
+            table.row(vec![
+                (format_address(symbol.offset), Style::Address),
+                (symbol.feature.to_string(), Style::Plain),
+                (symbol.name.clone(), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
+
+    // region: Provenance
+    if listing.shows(OutputSection::Provenance) && !pdb_info.provenance.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Provenance:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Kind", "Modules", "Value"]);
+        for string in listing.select(pdb_info, &pdb_info.provenance) {
+            table.row(vec![
+                (string.kind.to_string(), Style::Plain),
+                (string.modules.to_string(), Style::Plain),
+                (string.value.clone(), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
+
+    // region: Fastlink references
+    if listing.shows(OutputSection::FastlinkReferences) && !pdb_info.fastlink_references.is_empty()
+    {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Fastlink references:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Kind", "Local", "Module", "Name"]);
+        for reference in listing.select(pdb_info, &pdb_info.fastlink_references) {
+            table.row(vec![
+                (format!("{:?}", reference.kind), Style::Plain),
+                (reference.is_local.to_string(), Style::Plain),
+                (
+                    format_module_name(pdb_info, reference.module_index),
+                    Style::Plain,
+                ),
+                (reference.name.clone(), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
+
+    // region: Managed procedures
+    if listing.shows(OutputSection::ManagedProcedures) && !pdb_info.managed_procedures.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Managed procedures:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Len", "Token", "Module", "Name"]);
+        for procedure in listing.select(pdb_info, &pdb_info.managed_procedures) {
+            table.row(vec![
+                (format_address(procedure.address), Style::Address),
+                (format!("0x{:X}", procedure.len), Style::Plain),
+                (format!("0x{:08X}", procedure.token), Style::Plain),
+                (
+                    format_module_name(pdb_info, procedure.module_index),
+                    Style::Plain,
+                ),
+                (procedure.name.clone(), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
+
+    // region: Heap allocation sites
+    if listing.shows(OutputSection::HeapAllocationSites)
+        && !pdb_info.heap_allocation_sites.is_empty()
+    {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Heap allocation sites:")?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Size", "Type"]);
+        for site in listing.select(pdb_info, &pdb_info.heap_allocation_sites) {
+            let ty: &Type = &pdb_info.types[site.ty];
+            table.row(vec![
+                (format_address(site.offset), Style::Address),
+                (format!("0x{:X}", ty.type_size(pdb_info)), Style::Plain),
+                (format_type_name(pdb_info, ty, render), Style::Type),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+    // endregion
+
+    // region: Modules
+    if listing.shows(OutputSection::Modules) && !pdb_info.debug_modules.is_empty() {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Modules:")?;
+        writeln!(output)?;
+        print_modules(
+            output,
+            listing.pagination.slice(&pdb_info.debug_modules),
+            max_width,
+        )?;
+    }
+    // endregion
+
+    // region: Types
+    if listing.shows(OutputSection::Types) {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, "Types:")?;
         writeln!(output)?;
+
+        let types: Vec<&Type> = pdb_info
+            .types
+            .iter()
+            .map(|(_id, ty)| ty)
+            .filter(|ty| is_listed_type(ty))
+            .collect();
+        for ty in listing.select(pdb_info, &types) {
+            print_type(output, pdb_info, ty, max_width, flatten_anonymous, render)?;
+        }
     }
     // endregion
 
     Ok(())
 }
 
-fn format_type_name(ty: &Type) -> String {
+pub fn print_public_symbols_header(output: &mut impl Write) -> io::Result<()> {
+    writeln!(output, "Public symbols:")?;
+    writeln!(output, "\t{:<10} Name", "Offset")
+}
+
+pub fn print_public_symbol(output: &mut impl Write, symbol: &PublicSymbol) -> io::Result<()> {
+    write!(output, "\t")?;
+    if let Some(offset) = symbol.offset {
+        write!(output, "0x{:08X} ", offset.value())?;
+    } else {
+        write!(output, "{:<10} ", "")?;
+    }
+    writeln!(output, "{}", symbol.name)
+}
+
+pub fn print_procedures_header(output: &mut impl Write) -> io::Result<()> {
+    writeln!(output, "Procedures:")?;
+    writeln!(
+        output,
+        "\t{:<10} {:<10} {:<15} {:<15} {:<10}",
+        "Offset", "Length", "Prologue End", "Epilogue Start", "Name"
+    )
+}
+
+pub fn print_procedure(
+    output: &mut impl Write,
+    procedure: &ezpdb::symbol_types::Procedure,
+) -> io::Result<()> {
+    write!(output, "\t")?;
+    if let Some(address) = procedure.address {
+        write!(output, "0x{:08X} ", address.value())?;
+    } else {
+        write!(output, "{:<10} ", "")?;
+    }
+
+    write!(output, "0x{:08X} ", procedure.len)?;
+    write!(
+        output,
+        "{:<15}",
+        format!("0x{:08X} ", procedure.prologue_end)
+    )?;
+    write!(
+        output,
+        "{:<15}",
+        format!("0x{:08X} ", procedure.epilogue_start)
+    )?;
+    writeln!(output, "{}", procedure.name)
+}
+
+/// Returns whether `ty` is one of the types shown in the "Types" listing
+pub fn is_listed_type(ty: &Type) -> bool {
     match ty {
-        Type::Class(class) => class.name.clone(),
-        Type::Union(union) => union.name.clone(),
-        Type::Array(array) => format!(
-            "{}{}",
-            format_type_name(&*array.element_type.as_ref().borrow()),
-            array
-                .dimensions_elements
-                .iter()
-                .fold(String::new(), |accum, dimension| format!(
-                    "{}[0x{:X}]",
-                    accum, dimension
-                ))
-        ),
-        Type::Pointer(pointer) => {
-            // TODO: Attributes
-            match pointer.underlying_type.as_ref() {
-                Some(underlying_type) => {
-                    format!("{}*", format_type_name(&*underlying_type.as_ref().borrow()))
-                }
-                None => "<UNRESOLVED_POINTER_TYPE>".to_string(),
+        Type::Class(class) => !class.properties.forward_reference,
+        Type::Union(union) => !union.properties.forward_reference,
+        Type::Enumeration(e) => !e.properties.forward_reference,
+        _ => false,
+    }
+}
+
+/// Returns the unique name (or name) of a class, union, or enum, which
+/// identifies its definition across forward references
+pub fn type_key(ty: &Type) -> Option<&str> {
+    match ty {
+        Type::Class(class) => Some(class.unique_name.as_deref().unwrap_or(&class.name)),
+        Type::Union(union) => Some(union.unique_name.as_deref().unwrap_or(&union.name)),
+        Type::Enumeration(e) => Some(e.unique_name.as_deref().unwrap_or(&e.name)),
+        _ => None,
+    }
+}
+
+/// Prints the layout of a class, union, or enum. Other kinds of types and
+/// forward references are skipped. If `flatten_anonymous` is set, members of
+/// anonymous classes and unions are listed in place of the anonymous member.
+/// Type names are formatted as `render` says.
+pub fn print_type(
+    output: &mut impl WriteColor,
+    pdb_info: &ParsedPdb,
+    ty: &Type,
+    max_width: Option<usize>,
+    flatten_anonymous: bool,
+    render: &RenderOptions,
+) -> io::Result<()> {
+    match ty {
+        Type::Class(class) => {
+            if class.properties.forward_reference {
+                return Ok(());
             }
+
+            write!(output, "\t{:width$} ", class.kind.to_string(), width = 10)?;
+            write_styled(output, Style::Type, &class.name)?;
+            writeln!(output, " {}", class.unique_name.as_deref().unwrap_or(""))?;
+            writeln!(output, "\tSize: 0x{:X}", class.size)?;
+            if let Some(packing) = class.packing {
+                writeln!(output, "\tPacking: {}", packing)?;
+            }
+            if let Some(shape) = &class.vtable_shape {
+                writeln!(output, "\tVTable slots: {}", shape.slots.len())?;
+            }
+            writeln!(output, "\tFields:")?;
+            print_fields(
+                output,
+                pdb_info,
+                &class.fields,
+                max_width,
+                flatten_anonymous,
+                render,
+            )?;
+            print_methods(output, pdb_info, &class.fields, max_width, render)?;
+        }
+        Type::Union(union) => {
+            if union.properties.forward_reference {
+                return Ok(());
+            }
+
+            write!(output, "\tUnion ")?;
+            write_styled(output, Style::Type, &union.name)?;
+            writeln!(output, " {}", union.unique_name.as_deref().unwrap_or(""))?;
+            writeln!(output, "\tSize: 0x{:X}", union.size)?;
+            if let Some(packing) = union.packing {
+                writeln!(output, "\tPacking: {}", packing)?;
+            }
+            writeln!(output, "\tFields:")?;
+            print_fields(
+                output,
+                pdb_info,
+                &union.fields,
+                max_width,
+                flatten_anonymous,
+                render,
+            )?;
+        }
+        Type::Enumeration(e) => {
+            if e.properties.forward_reference {
+                return Ok(());
+            }
+
+            write!(output, "\tEnum ")?;
+            write_styled(output, Style::Type, &e.name)?;
+            writeln!(output, " {}", e.unique_name.as_deref().unwrap_or(""))?;
+            if let Type::Primitive(primitive) = &pdb_info.types[e.underlying_type] {
+                writeln!(output, "\tSize: 0x{:X}", primitive.size())?;
+            }
+            let underlying_type = &pdb_info.types[e.underlying_type];
+            write!(output, "\tType: ")?;
+            write_styled(
+                output,
+                Style::Type,
+                &format_type_name(pdb_info, underlying_type, render),
+            )?;
+            writeln!(output)?;
+            if e.is_flags(pdb_info) {
+                writeln!(output, "\tFlags: yes")?;
+            }
+            writeln!(output, "\tVariants:")?;
+
+            let mut table = Table::new("\t\t", &["Value", "Name"]);
+            for variant in &e.variants {
+                let value = e.variant_value(variant, pdb_info);
+
+                table.row(vec![
+                    (format!("0x{:08X}", value), Style::Plain),
+                    (variant.name.to_string(), Style::Name),
+                ]);
+            }
+            table.write(output, max_width)?;
+        }
+        _ => {
+            return Ok(());
         }
-        Type::Primitive(primitive) => match primitive.kind {
-            PrimitiveKind::Void => "void".to_string(),
-            PrimitiveKind::Char | PrimitiveKind::RChar => "char".to_string(),
-            PrimitiveKind::UChar => "unsigned char".to_string(),
+    }
+    writeln!(output)?;
+
+    Ok(())
+}
 
-            PrimitiveKind::I8 => "int8_t".to_string(),
-            PrimitiveKind::U8 => "uint8_t".to_string(),
-            PrimitiveKind::I16 | PrimitiveKind::Short => "int16_t".to_string(),
-            PrimitiveKind::U16 | PrimitiveKind::UShort => "uint16_t".to_string(),
-            PrimitiveKind::I32 | PrimitiveKind::Long => "int32_t".to_string(),
-            PrimitiveKind::U32 | PrimitiveKind::ULong => "uint32_t".to_string(),
-            PrimitiveKind::I64 | PrimitiveKind::Quad => "int64_t".to_string(),
-            PrimitiveKind::U64 | PrimitiveKind::UQuad => "uint64_t".to_string(),
+/// Prints the data members and base classes of a class or union
+fn print_fields(
+    output: &mut impl WriteColor,
+    pdb_info: &ParsedPdb,
+    fields: &[TypeId],
+    max_width: Option<usize>,
+    flatten_anonymous: bool,
+    render: &RenderOptions,
+) -> io::Result<()> {
+    let fields = layout_fields(pdb_info, fields, flatten_anonymous);
+    let mut table = Table::new("\t\t", &["Offset", "Name", "Type"]);
+    // The storage unit shared by the bitfields listed most recently
+    let mut storage = None;
+    for field in &fields {
+        let bitfield = bitfield_member(pdb_info, field);
+        let field_storage =
+            bitfield.map(|(member, bitfield)| (member.offset, bitfield.underlying_type));
+        if let Some((offset, storage_type)) = field_storage.filter(|_| field_storage != storage) {
+            table.row(vec![
+                (format!("0x{:04X}", offset), Style::Address),
+                ("<Bitfields>".to_string(), Style::Plain),
+                (
+                    format_type_name(pdb_info, &pdb_info.types[storage_type], render),
+                    Style::Type,
+                ),
+            ]);
+        }
+        storage = field_storage;
 
-            PrimitiveKind::F32 => "float".to_string(),
-            PrimitiveKind::F64 => "double".to_string(),
+        if let Some((member, bitfield)) = bitfield {
+            table.row(vec![
+                (String::new(), Style::Address),
+                (format!("  {}", member.name), Style::Name),
+                (format_bitfield_position(bitfield), Style::Type),
+            ]);
+            continue;
+        }
 
-            PrimitiveKind::Bool8 => "bool".to_string(),
+        match field.as_ref() {
+            Type::Member(member) => {
+                let member_ty: &Type = &pdb_info.types[member.underlying_type];
+                table.row(vec![
+                    (format!("0x{:04X}", member.offset), Style::Address),
+                    (member.name.to_string(), Style::Name),
+                    (format_type_name(pdb_info, member_ty, render), Style::Type),
+                ]);
+            }
+            Type::BaseClass(base) => {
+                table.row(vec![
+                    (format!("0x{:04X}", base.offset), Style::Address),
+                    ("<BaseClass>".to_string(), Style::Plain),
+                    (
+                        format_type_name(pdb_info, &pdb_info.types[base.base_class], render),
+                        Style::Type,
+                    ),
+                ]);
+            }
+            Type::VirtualBaseClass(_) => {
+                // ignore
+            }
+            Type::Nested(_nested) => {
+                // ignore nested types
+            }
+            Type::Method(_) | Type::OverloadedMethod(_) => {
+                // ignore methods
+            }
+            Type::VTable(_) => {
+                // ignore vtable
+            }
+            Type::StaticMember(_) => {
+                // ignore
+            }
             other => {
-                format!("{}", other)
+                debug!("Unexpected field type present in class: {:?}", other)
             }
+        }
+    }
+
+    table.write(output, max_width)
+}
+
+/// Returns `field` and its type if it's a bitfield member
+pub fn bitfield_member<'a>(
+    pdb_info: &'a ParsedPdb,
+    field: &'a Type,
+) -> Option<(&'a Member, &'a Bitfield)> {
+    match field {
+        Type::Member(member) => match &pdb_info.types[member.underlying_type] {
+            Type::Bitfield(bitfield) => Some((member, bitfield)),
+            _ => None,
         },
-        Type::Modifier(modifier) => format_type_name(&*modifier.underlying_type.as_ref().borrow()),
-        Type::Bitfield(bitfield) => format!(
-            "{}:{}",
-            format_type_name(&*bitfield.underlying_type.as_ref().borrow()),
-            bitfield.len
-        ),
-        Type::Procedure(proc) => format!(
-            "{} (*function){}",
-            format_type_name(&*proc.return_type.as_ref().unwrap().as_ref().borrow()),
-            proc.argument_list
-                .iter()
-                .fold(String::new(), |accum, argument| {
-                    format!(
-                        "{}{}{}",
-                        &accum,
-                        if accum.is_empty() { "" } else { "," },
-                        format_type_name(&*argument.as_ref().borrow())
-                    )
-                })
-        ),
-        Type::Enumeration(e) => e.name.clone(),
-        Type::MemberFunction(member) => {
-            format!(
-                "{} (*function){}",
-                format_type_name(&*member.return_type.as_ref().borrow()),
-                member
-                    .argument_list
-                    .iter()
-                    .fold(String::new(), |accum, argument| {
-                        format!(
-                            "{}{}{}",
-                            &accum,
-                            if accum.is_empty() { "" } else { "," },
-                            format_type_name(&*argument.as_ref().borrow())
-                        )
-                    })
-            )
-        }
-        other => panic!("unimplemented type format: {:?}", other),
+        _ => None,
     }
 }
 
-pub fn print_json(output: &mut impl Write, pdb_info: &ParsedPdb) -> io::Result<()> {
-    write!(output, "{}", serde_json::to_string(pdb_info)?)
+/// Formats the bits a bitfield occupies within its storage unit the way WinDbg
+/// does, e.g. `Pos 3, 2 Bits`
+pub fn format_bitfield_position(bitfield: &Bitfield) -> String {
+    format!(
+        "Pos {}, {} Bit{}",
+        bitfield.position,
+        bitfield.len,
+        if bitfield.len == 1 { "" } else { "s" }
+    )
+}
+
+/// Returns the fields of a class or union to show in its layout
+pub fn layout_fields<'a>(
+    pdb_info: &'a ParsedPdb,
+    fields: &[TypeId],
+    flatten_anonymous: bool,
+) -> Vec<Cow<'a, Type>> {
+    if flatten_anonymous {
+        flatten_anonymous_fields(pdb_info, fields)
+    } else {
+        fields
+            .iter()
+            .map(|field| Cow::Borrowed(&pdb_info.types[*field]))
+            .collect()
+    }
+}
+
+/// Formats what a static initializer acts upon: the variable it constructs or
+/// destroys, or the CRT table it's an entry of
+pub fn format_initializer_target(initializer: &StaticInitializer) -> String {
+    initializer
+        .variable
+        .as_ref()
+        .or(initializer.group.as_ref())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Formats an optional address as fixed-width hex, or an empty string if it's unknown
+/// Explains what's missing from `/DEBUG:FASTLINK` PDBs and how to get it
+pub const FASTLINK_GUIDANCE: &str =
+    "This PDB was linked with /DEBUG:FASTLINK, so most types and symbols \
+     are only referenced from the object files listed under \"Fastlink references\". Relink with \
+     /DEBUG:FULL, or convert the PDB with mspdbcmf.exe, for complete output.";
+
+/// Formats the object file name of the debug module at `module_index`
+pub fn format_module_name(pdb: &ParsedPdb, module_index: Option<usize>) -> String {
+    module_index
+        .and_then(|index| pdb.debug_modules.get(index))
+        .map(|module| module.name.clone())
+        .unwrap_or_default()
+}
+
+/// Formats the kind of section `data` is stored in, or nothing if it's unknown
+pub fn format_storage(data: &Data) -> String {
+    data.storage
+        .map(|storage| storage.to_string())
+        .unwrap_or_default()
+}
+
+/// Formats the rebased address if a base address was given, otherwise the RVA
+pub fn format_address(address: Option<Address>) -> String {
+    address
+        .map(|address| format!("0x{:08X}", address.value()))
+        .unwrap_or_default()
+}
+
+/// Formats the name of `ty` as it would appear in a C cast, e.g. `int (*)[0x8]`,
+/// with primitive types named as `render` says
+pub fn format_type_name(pdb: &ParsedPdb, ty: &Type, render: &RenderOptions) -> String {
+    render::type_name(pdb, ty, render)
+}
+
+/// Prints the methods among `fields`, if there are any
+fn print_methods(
+    output: &mut impl WriteColor,
+    pdb_info: &ParsedPdb,
+    fields: &[TypeId],
+    max_width: Option<usize>,
+    render: &RenderOptions,
+) -> io::Result<()> {
+    let mut table = Table::new("\t\t", &["VTable Offset", "Attributes", "Name", "Type"]);
+    let mut add_row =
+        |name: &str, attributes: &FieldAttributes, ty: TypeId, vtable_offset: Option<usize>| {
+            table.row(vec![
+                (
+                    vtable_offset
+                        .map(|offset| format!("0x{:04X}", offset))
+                        .unwrap_or_default(),
+                    Style::Address,
+                ),
+                (format_method_attributes(attributes), Style::Plain),
+                (name.to_string(), Style::Name),
+                (
+                    format_type_name(pdb_info, &pdb_info.types[ty], render),
+                    Style::Type,
+                ),
+            ]);
+        };
+
+    let mut any = false;
+    for field in fields {
+        match &pdb_info.types[*field] {
+            Type::Method(method) => {
+                add_row(
+                    &method.name,
+                    &method.attributes,
+                    method.method_type,
+                    method.vtable_offset,
+                );
+                any = true;
+            }
+            Type::OverloadedMethod(overloaded) => {
+                if let Type::MethodList(list) = &pdb_info.types[overloaded.method_list] {
+                    for entry in &list.0 {
+                        add_row(
+                            &overloaded.name,
+                            &entry.attributes,
+                            entry.method_type,
+                            entry.vtable_offset,
+                        );
+                        any = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !any {
+        return Ok(());
+    }
+
+    writeln!(output, "\tMethods:")?;
+    table.write(output, max_width)
+}
+
+/// Formats the access and properties of a method, e.g. `public virtual`
+pub fn format_method_attributes(attributes: &FieldAttributes) -> String {
+    let mut words = vec![];
+    if !matches!(attributes.access, Access::None) {
+        words.push(attributes.access.to_string());
+    }
+    if attributes.is_static {
+        words.push("static".to_string());
+    }
+    if attributes.is_pure_virtual {
+        words.push("pure virtual".to_string());
+    } else if attributes.is_virtual || attributes.is_introducing_virtual {
+        words.push("virtual".to_string());
+    }
+    if attributes.is_introducing_virtual {
+        words.push("(introducing)".to_string());
+    }
+
+    words.join(" ")
+}
+
+/// Formats the flags of a procedure which affect how it's called or how control
+/// flows through it, and whether it was folded with other symbols
+pub fn format_procedure_flags(procedure: &ezpdb::symbol_types::Procedure) -> String {
+    let flags = &procedure.flags;
+    let mut words = vec![];
+    if flags.no_return {
+        words.push("noreturn");
+    }
+    if flags.no_inline {
+        words.push("noinline");
+    }
+    if flags.custom_calling_convention {
+        words.push("custom-cc");
+    }
+    if flags.naked {
+        words.push("naked");
+    }
+    if flags.has_eh {
+        words.push("eh");
+    }
+    if flags.has_seh {
+        words.push("seh");
+    }
+    if flags.unreachable {
+        words.push("unreachable");
+    }
+    if !procedure.folded_with.is_empty() {
+        words.push("folded");
+    }
+
+    words.join(" ")
+}
+
+/// Formats the signature of `procedure` (or just its name if its type couldn't be
+/// resolved), followed by the name of its public symbol if that differs (e.g.
+/// because it's decorated)
+pub fn format_procedure_name(
+    pdb: &ParsedPdb,
+    procedure: &ezpdb::symbol_types::Procedure,
+    render: &RenderOptions,
+) -> String {
+    let name = format_procedure_signature(pdb, procedure, render)
+        .unwrap_or_else(|| procedure.name.clone());
+    match &procedure.public_name {
+        Some(public_name) if *public_name != procedure.name => {
+            format!("{} ({})", name, public_name)
+        }
+        _ => name,
+    }
+}
+
+/// Formats `procedure` as a declaration, e.g. `int32_t main(int32_t argc, char** argv)`,
+/// if its type can be resolved. Parameter names are included when the PDB has them.
+pub fn format_procedure_signature(
+    pdb: &ParsedPdb,
+    procedure: &ezpdb::symbol_types::Procedure,
+    render: &RenderOptions,
+) -> Option<String> {
+    let ty = *pdb.type_indices.get(&procedure.type_index)?;
+    let (return_type, arguments) = match &pdb.types[ty] {
+        Type::Procedure(ty) => (ty.return_type, &ty.argument_list),
+        Type::MemberFunction(ty) => (Some(ty.return_type), &ty.argument_list),
+        _ => return None,
+    };
+
+    // Member functions have a `this` parameter which isn't in their argument list
+    let names = &procedure.parameter_names;
+    let names = &names[names.len().saturating_sub(arguments.len())..];
+    let arguments = arguments
+        .iter()
+        .enumerate()
+        .map(|(i, argument)| {
+            let ty = format_type_name(pdb, &pdb.types[*argument], render);
+            match names.get(i) {
+                Some(name) => format!("{} {}", ty, name),
+                None => ty,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let return_type = match return_type {
+        Some(return_type) => format_type_name(pdb, &pdb.types[return_type], render),
+        None => "void".to_string(),
+    };
+
+    Some(format!("{} {}({})", return_type, procedure.name, arguments))
+}
+
+/// Formats the module and function which own a local variable as `module!function`,
+/// using only the file name of the module
+pub fn format_data_scope(data: &Data) -> String {
+    let module = data
+        .module
+        .as_deref()
+        .map(|module| module.rsplit(['\\', '/']).next().unwrap_or(module))
+        .unwrap_or("");
+    match &data.function {
+        Some(function) => format!("{}!{}", module, function),
+        None => module.to_string(),
+    }
+}
+
+/// Removes the public symbols which have a linked procedure, since the procedure
+/// listing already includes their name
+pub fn dedup_public_symbols(pdb_info: &mut ParsedPdb) {
+    pdb_info
+        .public_symbols
+        .retain(|symbol| symbol.procedure_name.is_none());
+}
+
+/// Removes the procedures which weren't written by `origin`
+pub fn retain_origin(pdb_info: &mut ParsedPdb, origin: CodeOrigin) {
+    pdb_info
+        .procedures
+        .retain(|procedure| procedure.origin == origin);
+}
+
+/// Removes procedures which weren't defined in a source file matching `pattern`
+pub fn retain_source_file(pdb_info: &mut ParsedPdb, pattern: &NamePattern) {
+    pdb_info.procedures.retain(|procedure| {
+        procedure
+            .source
+            .as_ref()
+            .is_some_and(|source| pattern.is_match(&source.file))
+    });
+}
+
+/// Sorts the public symbols, procedures, globals, and local data of `pdb_info` by `key`.
+/// Symbols without an address sort after all others, and since public symbols
+/// have no size they keep their original order when sorting by size.
+pub fn sort(pdb_info: &mut ParsedPdb, key: SortKey) {
+    match key {
+        SortKey::Address => {
+            pdb_info
+                .public_symbols
+                .sort_by_key(|symbol| (symbol.offset.is_none(), symbol.offset));
+            pdb_info
+                .procedures
+                .sort_by_key(|procedure| (procedure.address.is_none(), procedure.address));
+            pdb_info
+                .global_data
+                .sort_by_key(|global| (global.offset.is_none(), global.offset));
+            pdb_info
+                .local_data
+                .sort_by_key(|data| (data.offset.is_none(), data.offset));
+            pdb_info
+                .annotations
+                .sort_by_key(|annotation| (annotation.offset.is_none(), annotation.offset));
+            pdb_info
+                .trampolines
+                .sort_by_key(|trampoline| (trampoline.offset.is_none(), trampoline.offset));
+            pdb_info
+                .separated_code
+                .sort_by_key(|code| (code.offset.is_none(), code.offset));
+            pdb_info
+                .heap_allocation_sites
+                .sort_by_key(|site| (site.offset.is_none(), site.offset));
+            pdb_info
+                .managed_procedures
+                .sort_by_key(|procedure| (procedure.address.is_none(), procedure.address));
+            pdb_info.sections.sort_by_key(|section| section.offset);
+            pdb_info
+                .call_sites
+                .sort_by_key(|site| (site.offset.is_none(), site.offset));
+            pdb_info
+                .coff_groups
+                .sort_by_key(|group| (group.offset.is_none(), group.offset));
+            pdb_info
+                .static_initializers
+                .sort_by_key(|initializer| (initializer.offset.is_none(), initializer.offset));
+            pdb_info
+                .security_symbols
+                .sort_by_key(|symbol| (symbol.offset.is_none(), symbol.offset));
+        }
+        SortKey::Name => {
+            pdb_info.public_symbols.sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info.procedures.sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info.global_data.sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info.local_data.sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info.sections.sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info.coff_groups.sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info
+                .static_initializers
+                .sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info
+                .security_symbols
+                .sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info.provenance.sort_by(|a, b| a.value.cmp(&b.value));
+            pdb_info
+                .fastlink_references
+                .sort_by(|a, b| a.name.cmp(&b.name));
+            pdb_info
+                .managed_procedures
+                .sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        SortKey::Size => {
+            pdb_info.procedures.sort_by_key(|procedure| procedure.len);
+            pdb_info
+                .trampolines
+                .sort_by_key(|trampoline| trampoline.size);
+            pdb_info.separated_code.sort_by_key(|code| code.len);
+            pdb_info
+                .managed_procedures
+                .sort_by_key(|procedure| procedure.len);
+            pdb_info.sections.sort_by_key(|section| section.len);
+            pdb_info.coff_groups.sort_by_key(|group| group.len);
+
+            let mut global_data = std::mem::take(&mut pdb_info.global_data);
+            global_data.sort_by_key(|global| pdb_info.types[global.ty].type_size(pdb_info));
+            pdb_info.global_data = global_data;
+
+            let mut local_data = std::mem::take(&mut pdb_info.local_data);
+            local_data.sort_by_key(|data| pdb_info.types[data.ty].type_size(pdb_info));
+            pdb_info.local_data = local_data;
+
+            let mut sites = std::mem::take(&mut pdb_info.heap_allocation_sites);
+            sites.sort_by_key(|site| pdb_info.types[site.ty].type_size(pdb_info));
+            pdb_info.heap_allocation_sites = sites;
+        }
+        SortKey::File => {
+            pdb_info.procedures.sort_by(|a, b| {
+                let key = |procedure: &ezpdb::symbol_types::Procedure| {
+                    procedure
+                        .source
+                        .as_ref()
+                        .map(|source| (source.file.to_lowercase(), source.first_line))
+                };
+                (a.source.is_none(), key(a)).cmp(&(b.source.is_none(), key(b)))
+            });
+        }
+    }
+}
+
+/// A block of the plain and HTML output which can be chosen with `--sections`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OutputSection {
+    Header,
+    PublicSymbols,
+    Procedures,
+    Globals,
+    LocalData,
+    Annotations,
+    Trampolines,
+    SeparatedCode,
+    Calls,
+    Sections,
+    StaticInitializers,
+    SecuritySymbols,
+    Provenance,
+    FastlinkReferences,
+    ManagedProcedures,
+    HeapAllocationSites,
+    Modules,
+    Types,
+}
+
+impl FromStr for OutputSection {
+    type Err = CliArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let result = match s.trim().to_ascii_lowercase().as_ref() {
+            "header" => OutputSection::Header,
+            "publics" => OutputSection::PublicSymbols,
+            "procedures" => OutputSection::Procedures,
+            "globals" => OutputSection::Globals,
+            "locals" => OutputSection::LocalData,
+            "annotations" => OutputSection::Annotations,
+            "trampolines" => OutputSection::Trampolines,
+            "separated-code" => OutputSection::SeparatedCode,
+            "calls" => OutputSection::Calls,
+            "sections" => OutputSection::Sections,
+            "initializers" => OutputSection::StaticInitializers,
+            "security" => OutputSection::SecuritySymbols,
+            "provenance" => OutputSection::Provenance,
+            "fastlink" => OutputSection::FastlinkReferences,
+            "managed" => OutputSection::ManagedProcedures,
+            "allocations" => OutputSection::HeapAllocationSites,
+            "modules" => OutputSection::Modules,
+            "types" => OutputSection::Types,
+            _ => return Err(CliArgumentError::InvalidValue("sections", s.to_string())),
+        };
+
+        Ok(result)
+    }
+}
+
+/// Controls which items of each listing (public symbols, procedures, etc.) are output
+#[derive(Debug, Default)]
+pub struct ListingOptions {
+    pub filter: Option<Filter>,
+    /// How type names are formatted when the filter compares them
+    pub render: RenderOptions,
+    pub pagination: Pagination,
+    /// The blocks of the plain and HTML output to print. All are printed if this is empty.
+    pub sections: Vec<OutputSection>,
+}
+
+impl ListingOptions {
+    /// Returns whether the block `section` of the output should be printed
+    pub fn shows(&self, section: OutputSection) -> bool {
+        self.sections.is_empty() || self.sections.contains(&section)
+    }
+
+    /// Returns the items of `items` which pass the filter and fall inside the pagination window
+    pub fn select<'a, T: Filterable + 'a, I: std::borrow::Borrow<T>>(
+        &self,
+        pdb_info: &ParsedPdb,
+        items: &'a [I],
+    ) -> Vec<&'a T> {
+        let items: Vec<&T> = items
+            .iter()
+            .map(|item| item.borrow())
+            .filter(|item| self.is_match(pdb_info, *item))
+            .collect();
+
+        self.pagination.slice(&items).to_vec()
+    }
+
+    /// Returns the classes, unions, and enums of the types listing which pass the
+    /// filter and fall inside the pagination window
+    pub fn select_types(&self, pdb_info: &ParsedPdb) -> Vec<TypeId> {
+        let types: Vec<TypeId> = pdb_info
+            .types
+            .iter()
+            .filter(|(_id, ty)| is_listed_type(ty) && self.is_match(pdb_info, *ty))
+            .map(|(id, _ty)| id)
+            .collect();
+
+        self.pagination.slice(&types).to_vec()
+    }
+
+    fn is_match(&self, pdb_info: &ParsedPdb, item: &dyn Filterable) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|filter| filter.matches(pdb_info, &self.render, item))
+    }
+
+    /// Removes the items of `items` which fail the filter or fall outside the pagination window
+    fn retain<T: Filterable>(&self, pdb_info: &ParsedPdb, items: &mut Vec<T>) {
+        items.retain(|item| self.is_match(pdb_info, item));
+        self.pagination.retain(items);
+    }
+}
+
+/// Restricts each listing of `pdb_info` to the items selected by `listing`.
+/// The type arena is left untouched since symbols and other types refer to types
+/// by their position in it. The types listing is selected separately with
+/// [ListingOptions::select_types].
+pub fn restrict_listings(pdb_info: &mut ParsedPdb, listing: &ListingOptions) {
+    let mut public_symbols = std::mem::take(&mut pdb_info.public_symbols);
+    listing.retain(pdb_info, &mut public_symbols);
+    pdb_info.public_symbols = public_symbols;
+
+    let mut procedures = std::mem::take(&mut pdb_info.procedures);
+    listing.retain(pdb_info, &mut procedures);
+    pdb_info.procedures = procedures;
+
+    let mut global_data = std::mem::take(&mut pdb_info.global_data);
+    listing.retain(pdb_info, &mut global_data);
+    pdb_info.global_data = global_data;
+
+    let mut local_data = std::mem::take(&mut pdb_info.local_data);
+    listing.retain(pdb_info, &mut local_data);
+    pdb_info.local_data = local_data;
+
+    let mut annotations = std::mem::take(&mut pdb_info.annotations);
+    listing.retain(pdb_info, &mut annotations);
+    pdb_info.annotations = annotations;
+
+    let mut trampolines = std::mem::take(&mut pdb_info.trampolines);
+    listing.retain(pdb_info, &mut trampolines);
+    pdb_info.trampolines = trampolines;
+
+    let mut separated_code = std::mem::take(&mut pdb_info.separated_code);
+    listing.retain(pdb_info, &mut separated_code);
+    pdb_info.separated_code = separated_code;
+
+    let mut sites = std::mem::take(&mut pdb_info.heap_allocation_sites);
+    listing.retain(pdb_info, &mut sites);
+    pdb_info.heap_allocation_sites = sites;
+
+    let mut sections = std::mem::take(&mut pdb_info.sections);
+    listing.retain(pdb_info, &mut sections);
+    pdb_info.sections = sections;
+
+    let mut coff_groups = std::mem::take(&mut pdb_info.coff_groups);
+    listing.retain(pdb_info, &mut coff_groups);
+    pdb_info.coff_groups = coff_groups;
+
+    let mut call_sites = std::mem::take(&mut pdb_info.call_sites);
+    listing.retain(pdb_info, &mut call_sites);
+    pdb_info.call_sites = call_sites;
+
+    let mut call_graph = std::mem::take(&mut pdb_info.call_graph);
+    listing.retain(pdb_info, &mut call_graph);
+    pdb_info.call_graph = call_graph;
+
+    let mut static_initializers = std::mem::take(&mut pdb_info.static_initializers);
+    listing.retain(pdb_info, &mut static_initializers);
+    pdb_info.static_initializers = static_initializers;
+
+    let mut security_symbols = std::mem::take(&mut pdb_info.security_symbols);
+    listing.retain(pdb_info, &mut security_symbols);
+    pdb_info.security_symbols = security_symbols;
+
+    let mut provenance = std::mem::take(&mut pdb_info.provenance);
+    listing.retain(pdb_info, &mut provenance);
+    pdb_info.provenance = provenance;
+
+    let mut fastlink_references = std::mem::take(&mut pdb_info.fastlink_references);
+    listing.retain(pdb_info, &mut fastlink_references);
+    pdb_info.fastlink_references = fastlink_references;
+
+    let mut managed_procedures = std::mem::take(&mut pdb_info.managed_procedures);
+    listing.retain(pdb_info, &mut managed_procedures);
+    pdb_info.managed_procedures = managed_procedures;
+
+    listing.pagination.retain(&mut pdb_info.debug_modules);
+}
+
+pub fn print_json(output: &mut impl Write, pdb_info: &ParsedPdb) -> io::Result<()> {
+    write!(output, "{}", serde_json::to_string(pdb_info)?)
+}
+
+pub fn print_search_hits(output: &mut impl Write, hits: &[SearchHit]) -> io::Result<()> {
+    for hit in hits {
+        write!(output, "{:<13} ", hit.category.to_string())?;
+        match &hit.parent {
+            Some(parent) => writeln!(output, "{}::{}", parent, hit.name)?,
+            None => writeln!(output, "{}", hit.name)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the symbols found by `find`, along with how closely their names
+/// matched if `fuzzy` is set
+pub fn print_symbol_matches(
+    output: &mut impl WriteColor,
+    matches: &[SymbolMatch],
+    fuzzy: bool,
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let headers: &[&str] = if fuzzy {
+        &["Score", "Kind", "Offset", "Name"]
+    } else {
+        &["Kind", "Offset", "Name"]
+    };
+    let mut table = Table::new("", headers);
+    for symbol in matches {
+        let mut row = vec![];
+        if fuzzy {
+            row.push((format!("{:.2}", symbol.score), Style::Plain));
+        }
+        row.push((symbol.category.to_string(), Style::Plain));
+        row.push((format_address(symbol.address), Style::Address));
+        row.push((symbol.name.clone(), Style::Name));
+        table.row(row);
+    }
+
+    table.write(output, max_width)
+}
+
+/// Prints the symbols a name resolved to
+pub fn print_resolved_symbols(
+    output: &mut impl WriteColor,
+    resolved: &[ResolvedSymbol],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let mut table = Table::new("", &["Offset", "Kind", "Module", "Name"]);
+    for symbol in resolved {
+        table.row(vec![
+            (format_address(symbol.address), Style::Address),
+            (symbol.kind.to_string(), Style::Plain),
+            (symbol.module.clone().unwrap_or_default(), Style::Plain),
+            (symbol.name.clone(), Style::Name),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+/// Prints each group of folded functions: their shared address and length,
+/// followed by their names
+pub fn print_folded_groups(output: &mut impl WriteColor, groups: &[FoldedGroup]) -> io::Result<()> {
+    for group in groups {
+        write_styled(output, Style::Address, &format_address(Some(group.address)))?;
+        writeln!(
+            output,
+            " (0x{:X} bytes, {} functions):",
+            group.len,
+            group.procedures.len()
+        )?;
+        for name in &group.procedures {
+            write!(output, "\t")?;
+            write_styled(output, Style::Name, name)?;
+            writeln!(output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the symbols which refer to a type, grouped by kind
+pub fn print_type_references(
+    output: &mut impl WriteColor,
+    pdb_info: &ParsedPdb,
+    references: &TypeReferences,
+    max_width: Option<usize>,
+    render: &RenderOptions,
+) -> io::Result<()> {
+    write_styled(output, Style::Heading, "Procedures:")?;
+    writeln!(output)?;
+    let mut table = Table::new("\t", &["Offset", "Name"]);
+    for procedure in &references.procedures {
+        table.row(vec![
+            (format_address(procedure.address), Style::Address),
+            (
+                format_procedure_name(pdb_info, procedure, render),
+                Style::Name,
+            ),
+        ]);
+    }
+    table.write(output, max_width)?;
+
+    for (heading, listing) in [
+        ("Globals:", &references.global_data),
+        ("Local data:", &references.local_data),
+    ] {
+        writeln!(output)?;
+        write_styled(output, Style::Heading, heading)?;
+        writeln!(output)?;
+        let mut table = Table::new("\t", &["Offset", "Type", "Scope", "Name"]);
+        for data in listing {
+            table.row(vec![
+                (format_address(data.offset), Style::Address),
+                (
+                    format_type_name(pdb_info, &pdb_info.types[data.ty], render),
+                    Style::Type,
+                ),
+                (format_data_scope(data), Style::Plain),
+                (data.name.clone(), Style::Name),
+            ]);
+        }
+        table.write(output, max_width)?;
+    }
+
+    Ok(())
+}
+
+/// Prints the function pointers in writable data, with the variable each one
+/// is in followed by the members and array elements leading to it
+pub fn print_writable_function_pointers(
+    output: &mut impl WriteColor,
+    pdb_info: &ParsedPdb,
+    pointers: &[WritableFunctionPointer<'_>],
+    max_width: Option<usize>,
+    render: &RenderOptions,
+) -> io::Result<()> {
+    let mut table = Table::new(
+        "\t",
+        &["Offset", "Count", "Storage", "Scope", "Type", "Name"],
+    );
+    for pointer in pointers {
+        table.row(vec![
+            (format_address(pointer.address), Style::Address),
+            (pointer.count.to_string(), Style::Plain),
+            (pointer.storage.to_string(), Style::Plain),
+            (format_data_scope(pointer.data), Style::Plain),
+            (
+                format_type_name(pdb_info, &pdb_info.types[pointer.ty], render),
+                Style::Type,
+            ),
+            (
+                format!("{}{}", pointer.data.name, pointer.path),
+                Style::Name,
+            ),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+/// Prints the reference count and dependency closure size of each type
+pub fn print_type_metrics(
+    output: &mut impl WriteColor,
+    pdb_info: &ParsedPdb,
+    metrics: &[TypeMetrics],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let mut table = Table::new("\t", &["References", "Dependencies", "Size", "Name"]);
+    for metric in metrics {
+        table.row(vec![
+            (metric.references.to_string(), Style::Plain),
+            (metric.dependencies.to_string(), Style::Plain),
+            (
+                format!("0x{:X}", pdb_info.types[metric.ty].type_size(pdb_info)),
+                Style::Plain,
+            ),
+            (metric.name.to_string(), Style::Type),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+pub fn print_modules(
+    output: &mut impl WriteColor,
+    modules: &[DebugModule],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let mut table = Table::new(
+        "\t",
+        &["Size", "Symbols", "Types", "Sources", "Library", "Name"],
+    );
+    for module in modules {
+        table.row(vec![
+            (format!("0x{:X}", module.contribution_size), Style::Plain),
+            (module.symbol_count.to_string(), Style::Plain),
+            (module.type_count.to_string(), Style::Plain),
+            (module_source_count(module).to_string(), Style::Plain),
+            (module.library.clone().unwrap_or_default(), Style::Plain),
+            (module.name.clone(), Style::Name),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+pub fn module_source_count(module: &DebugModule) -> usize {
+    module.source_files.as_ref().map_or(0, Vec::len)
+}
+
+/// Sorts `modules` by `key`, breaking ties by name
+pub fn sort_modules(modules: &mut [DebugModule], key: ModuleSortKey) {
+    modules.sort_by(|a, b| {
+        let ordering = match key {
+            ModuleSortKey::Name => std::cmp::Ordering::Equal,
+            ModuleSortKey::Library => a.library.cmp(&b.library),
+            ModuleSortKey::Size => a.contribution_size.cmp(&b.contribution_size),
+            ModuleSortKey::Symbols => a.symbol_count.cmp(&b.symbol_count),
+            ModuleSortKey::Types => a.type_count.cmp(&b.type_count),
+            ModuleSortKey::Sources => module_source_count(a).cmp(&module_source_count(b)),
+        };
+
+        ordering.then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+pub fn print_summary(output: &mut impl Write, summary: &PdbSummary) -> io::Result<()> {
+    writeln!(output, "{:?}:", &summary.path)?;
+    writeln!(output, "PDB Version: {:?}", summary.version)?;
+    writeln!(output, "GUID: {}", summary.guid)?;
+    writeln!(output, "Age: {}", summary.age)?;
+    writeln!(output, "Timestamp: 0x{:08X}", summary.timestamp)?;
+    writeln!(
+        output,
+        "Machine Type: {}",
+        summary
+            .machine_type
+            .as_ref()
+            .map(|ty| format!("{:?}", ty))
+            .unwrap_or_else(|| "Unknown".to_string())
+    )?;
+    writeln!(output, "Streams: {}", summary.stream_count)?;
+    writeln!(output, "Modules: {}", summary.module_count)?;
+    if summary.is_fastlink {
+        writeln!(output, "Fastlink: yes")?;
+        writeln!(output, "\t{}", FASTLINK_GUIDANCE)?;
+    }
+
+    Ok(())
+}
+
+pub fn print_file_statistics(
+    output: &mut impl WriteColor,
+    statistics: &[SourceFileStatistics],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let mut table = Table::new("\t", &["Bytes", "Procedures", "File"]);
+    for file in statistics {
+        table.row(vec![
+            (format!("0x{:X}", file.bytes), Style::Plain),
+            (file.procedures.to_string(), Style::Plain),
+            (file.file.clone(), Style::Name),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+pub fn print_verify_issues(output: &mut impl WriteColor, issues: &[Issue]) -> io::Result<()> {
+    if issues.is_empty() {
+        return writeln!(output, "No problems found");
+    }
+
+    for issue in issues {
+        let location = match (issue.stream, issue.offset) {
+            (Some(stream), Some(offset)) => format!("stream {} @ 0x{:X}", stream, offset),
+            (Some(stream), None) => format!("stream {}", stream),
+            (None, Some(offset)) => format!("file @ 0x{:X}", offset),
+            (None, None) => "MSF container".to_string(),
+        };
+        let location = match issue.kind {
+            Some(kind) => format!("{} (kind 0x{:04X})", location, kind),
+            None => location,
+        };
+        write_styled(output, Style::Address, &location)?;
+        writeln!(output, ": {}", issue.description)?;
+    }
+
+    Ok(())
+}
+
+pub fn print_module_audits(
+    output: &mut impl WriteColor,
+    audits: &[ModuleAudit],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let flag = |enabled: Option<bool>| match enabled {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "?",
+    };
+
+    let mut table = Table::new(
+        "\t",
+        &[
+            "GS", "SDL", "CFG", "Hotpatch", "LTCG", "Language", "Missing", "Module",
+        ],
+    );
+    for audit in audits {
+        let missing = audit
+            .missing
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        table.row(vec![
+            (flag(audit.security_checks).to_string(), Style::Plain),
+            (flag(audit.sdl).to_string(), Style::Plain),
+            (flag(audit.control_flow_guard).to_string(), Style::Plain),
+            (flag(audit.hot_patch).to_string(), Style::Plain),
+            (flag(audit.link_time_codegen).to_string(), Style::Plain),
+            (audit.language.clone().unwrap_or_default(), Style::Plain),
+            (missing, Style::Plain),
+            (audit.name.clone(), Style::Name),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+pub fn print_syscalls(
+    output: &mut impl WriteColor,
+    syscalls: &[Syscall],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let mut table = Table::new("\t", &["Number", "Address", "Arguments", "Service", "Stub"]);
+    for syscall in syscalls {
+        table.row(vec![
+            (
+                syscall
+                    .number
+                    .map(|number| format!("0x{:X}", number))
+                    .unwrap_or_default(),
+                Style::Plain,
+            ),
+            (format_address(syscall.address), Style::Address),
+            (
+                syscall
+                    .stack_arguments
+                    .map(|arguments| arguments.to_string())
+                    .unwrap_or_default(),
+                Style::Plain,
+            ),
+            (
+                syscall.name.clone().unwrap_or_else(|| "?".to_string()),
+                Style::Name,
+            ),
+            (syscall.stub_name.clone().unwrap_or_default(), Style::Name),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+pub fn print_export_mismatches(
+    output: &mut impl WriteColor,
+    mismatches: &[ExportMismatch],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let format_ordinal = |ordinal: Option<u32>| ordinal.map(|ordinal| ordinal.to_string());
+    let mut table = Table::new(
+        "\t",
+        &[
+            "Kind",
+            "Name",
+            "Image Ordinal",
+            "Image Address",
+            "PDB Ordinal",
+            "PDB Addresses",
+        ],
+    );
+    for mismatch in mismatches {
+        let pdb_addresses: Vec<_> = mismatch
+            .pdb_addresses
+            .iter()
+            .map(|address| format_address(Some(*address)))
+            .collect();
+        table.row(vec![
+            (mismatch.kind.to_string(), Style::Plain),
+            (
+                mismatch
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| "(by ordinal)".to_string()),
+                Style::Name,
+            ),
+            (
+                format_ordinal(mismatch.image_ordinal).unwrap_or_default(),
+                Style::Plain,
+            ),
+            (format_address(mismatch.image_address), Style::Address),
+            (
+                format_ordinal(mismatch.pdb_ordinal).unwrap_or_default(),
+                Style::Plain,
+            ),
+            (pdb_addresses.join(", "), Style::Address),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+pub fn print_raw_type_record(output: &mut impl Write, record: &RawTypeRecord) -> io::Result<()> {
+    writeln!(output, "Type Index: 0x{:X}", record.type_index)?;
+    match &record.kind_name {
+        Some(name) => writeln!(output, "Kind: 0x{:04X} ({})", record.kind, name)?,
+        None => writeln!(output, "Kind: 0x{:04X} (unknown)", record.kind)?,
+    }
+    writeln!(output, "TPI Offset: 0x{:X}", record.offset)?;
+    writeln!(output, "Length: {}", record.data.len())?;
+    if let Some(error) = &record.parse_error {
+        writeln!(output, "Parse Error: {}", error)?;
+    }
+    writeln!(output)?;
+
+    for (i, line) in record.data.chunks(16).enumerate() {
+        let hex = line
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let ascii: String = line
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        writeln!(output, "{:08X}  {:<47}  {}", i * 16, hex, ascii)?;
+    }
+
+    Ok(())
+}
+
+/// Formats `source` as `file.c:10-42`, without the file's directory
+pub fn format_source_range(source: Option<&SourceRange>) -> String {
+    let source = match source {
+        Some(source) => source,
+        None => return String::new(),
+    };
+    let file_name = source
+        .file
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or(&source.file);
+
+    if source.first_line == source.last_line {
+        format!("{}:{}", file_name, source.first_line)
+    } else {
+        format!("{}:{}-{}", file_name, source.first_line, source.last_line)
+    }
+}
+
+/// Returns the name of `checksum`'s algorithm and its value in hex
+fn format_checksum(checksum: &Checksum) -> (&'static str, String) {
+    let (algorithm, bytes) = match checksum {
+        Checksum::None => return ("None", String::new()),
+        Checksum::Md5(bytes) => ("MD5", bytes),
+        Checksum::Sha1(bytes) => ("SHA1", bytes),
+        Checksum::Sha256(bytes) => ("SHA256", bytes),
+    };
+
+    (
+        algorithm,
+        bytes.iter().map(|byte| format!("{:02X}", byte)).collect(),
+    )
+}
+
+pub fn print_source_files(
+    output: &mut impl WriteColor,
+    files: &[&FileInfo],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let mut table = Table::new("\t", &["Algorithm", "Checksum", "Name"]);
+    for file in files {
+        let (algorithm, checksum) = format_checksum(&file.checksum);
+        table.row(vec![
+            (algorithm.to_string(), Style::Plain),
+            (checksum, Style::Plain),
+            (file.name.clone(), Style::Name),
+        ]);
+    }
+
+    table.write(output, max_width)
+}
+
+/// Prints the source files which are missing or don't match, followed by a summary
+pub fn print_source_verification(
+    output: &mut impl WriteColor,
+    results: &[SourceVerification],
+    max_width: Option<usize>,
+) -> io::Result<()> {
+    let mut table = Table::new("\t", &["Status", "Name", "Detail"]);
+    let mut matched = 0;
+    let mut unchecked = 0;
+    for result in results {
+        let (status, detail) = match &result.status {
+            SourceStatus::Match => {
+                matched += 1;
+                continue;
+            }
+            SourceStatus::NoChecksum => {
+                unchecked += 1;
+                continue;
+            }
+            SourceStatus::Missing => ("Missing", String::new()),
+            SourceStatus::Mismatch { expected, actual } => {
+                let (algorithm, expected) = format_checksum(expected);
+                let (_, actual) = format_checksum(actual);
+                (
+                    "Mismatch",
+                    format!("{} expected {}, found {}", algorithm, expected, actual),
+                )
+            }
+        };
+        let detail = match &result.local_path {
+            Some(path) if detail.is_empty() => path.display().to_string(),
+            Some(path) => format!("{} ({})", path.display(), detail),
+            None => detail,
+        };
+
+        table.row(vec![
+            (status.to_string(), Style::Plain),
+            (result.name.clone(), Style::Name),
+            (detail, Style::Plain),
+        ]);
+    }
+
+    if matched + unchecked < results.len() {
+        table.write(output, max_width)?;
+        writeln!(output)?;
+    }
+
+    writeln!(
+        output,
+        "{} of {} source files match",
+        matched,
+        results.len() - unchecked
+    )?;
+    if unchecked > 0 {
+        writeln!(output, "{} source files have no checksum", unchecked)?;
+    }
+
+    Ok(())
+}
+
+pub fn print_bench(
+    output: &mut impl Write,
+    path: &Path,
+    size: usize,
+    stages: &[StageSummary],
+    totals: &[Duration],
+) -> io::Result<()> {
+    writeln!(
+        output,
+        "{} ({:.1} MiB), {} iterations",
+        path.display(),
+        size as f64 / (1024.0 * 1024.0),
+        totals.len()
+    )?;
+    writeln!(
+        output,
+        "{:<16} {:>12} {:>12} {:>12} {:>12}",
+        "Stage", "Min", "Mean", "Max", "Items"
+    )?;
+
+    let milliseconds = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    for stage in stages {
+        writeln!(
+            output,
+            "{:<16} {:>10.2}ms {:>10.2}ms {:>10.2}ms {:>12}",
+            stage.stage.name(),
+            milliseconds(stage.min),
+            milliseconds(stage.mean),
+            milliseconds(stage.max),
+            stage.items
+        )?;
+    }
+
+    if let (Some(min), Some(max)) = (totals.iter().min(), totals.iter().max()) {
+        let mean = totals.iter().sum::<Duration>() / totals.len() as u32;
+        writeln!(
+            output,
+            "{:<16} {:>10.2}ms {:>10.2}ms {:>10.2}ms",
+            "total",
+            milliseconds(*min),
+            milliseconds(mean),
+            milliseconds(*max)
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn print_timings(output: &mut impl Write, timings: &[StageTiming]) -> io::Result<()> {
+    writeln!(
+        output,
+        "{:<16} {:>12} {:>12} {:>12}",
+        "Stage", "Time", "Items", "Peak RSS"
+    )?;
+
+    for timing in timings {
+        writeln!(
+            output,
+            "{:<16} {:>10.2}ms {:>12} {:>12}",
+            timing.stage.name(),
+            timing.elapsed.as_secs_f64() * 1000.0,
+            timing.items,
+            timing
+                .peak_memory
+                .map(|bytes| format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)))
+                .unwrap_or_else(|| "Unknown".to_string())
+        )?;
+    }
+
+    Ok(())
 }
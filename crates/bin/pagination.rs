@@ -0,0 +1,51 @@
+use std::ops::Range;
+
+/// A window applied to each listing section (public symbols, procedures, etc.)
+/// of the output
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Pagination {
+    /// Number of leading items to skip
+    pub offset: usize,
+    /// Maximum number of items to keep after `offset` is applied
+    pub limit: Option<usize>,
+    /// Only keep this many items from the end of the window
+    pub tail: Option<usize>,
+}
+
+impl Pagination {
+    /// Returns the range of indices which fall inside this window for a
+    /// listing with `len` items
+    pub fn range(&self, len: usize) -> Range<usize> {
+        let start = self.offset.min(len);
+        let end = match self.limit {
+            Some(limit) => start.saturating_add(limit).min(len),
+            None => len,
+        };
+
+        let start = match self.tail {
+            Some(tail) => start.max(end.saturating_sub(tail)),
+            None => start,
+        };
+
+        start..end
+    }
+
+    /// Returns whether the item at `index` of a listing whose length isn't known
+    /// yet falls inside this window. `tail` can't be applied without knowing the
+    /// length and is ignored.
+    pub fn contains_streamed(&self, index: usize) -> bool {
+        index >= self.offset && self.limit.is_none_or(|limit| index - self.offset < limit)
+    }
+
+    /// Returns the items of `items` which fall inside this window
+    pub fn slice<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        &items[self.range(items.len())]
+    }
+
+    /// Removes all items of `items` which fall outside of this window
+    pub fn retain<T>(&self, items: &mut Vec<T>) {
+        let range = self.range(items.len());
+        items.truncate(range.end);
+        items.drain(..range.start);
+    }
+}
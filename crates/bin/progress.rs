@@ -0,0 +1,47 @@
+use ezpdb::progress::{ParseStage, ProgressSink};
+use indicatif::ProgressStyle;
+
+/// Draws a single-line progress bar to stderr for each parse stage
+#[derive(Debug, Default)]
+pub struct ProgressBar {
+    bar: Option<indicatif::ProgressBar>,
+}
+
+impl ProgressBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ProgressSink for ProgressBar {
+    fn stage_started(&mut self, stage: ParseStage, total: Option<usize>) {
+        let bar = match total {
+            Some(total) if total > 0 => indicatif::ProgressBar::new(total as u64).with_style(
+                ProgressStyle::with_template("{prefix:<16} [{bar:30}] {percent:>3}% ({pos}/{len})")
+                    .expect("the progress bar template is valid")
+                    .progress_chars("= "),
+            ),
+            _ => indicatif::ProgressBar::new_spinner().with_style(
+                ProgressStyle::with_template("{prefix:<16} {spinner} {pos} processed")
+                    .expect("the spinner template is valid"),
+            ),
+        };
+        bar.set_prefix(stage.name());
+        self.bar = Some(bar);
+    }
+
+    fn progress(&mut self, _stage: ParseStage, processed: usize, total: Option<usize>) {
+        if let Some(bar) = &self.bar {
+            if let Some(total) = total.filter(|&total| total > 0) {
+                bar.set_length(total as u64);
+            }
+            bar.set_position(processed as u64);
+        }
+    }
+
+    fn stage_finished(&mut self, _stage: ParseStage) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish();
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::io;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// How a cell's contents should be colored
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    Heading,
+    Address,
+    Name,
+    Type,
+}
+
+impl Style {
+    fn color_spec(&self) -> ColorSpec {
+        let mut spec = ColorSpec::new();
+        match self {
+            Style::Plain => {}
+            Style::Heading => {
+                spec.set_bold(true);
+            }
+            Style::Address => {
+                spec.set_fg(Some(Color::Yellow));
+            }
+            Style::Name => {
+                spec.set_fg(Some(Color::Green));
+            }
+            Style::Type => {
+                spec.set_fg(Some(Color::Cyan));
+            }
+        }
+
+        spec
+    }
+}
+
+/// Writes `text` to `output` in the given style
+pub fn write_styled(output: &mut impl WriteColor, style: Style, text: &str) -> io::Result<()> {
+    if style == Style::Plain {
+        return write!(output, "{}", text);
+    }
+
+    output.set_color(&style.color_spec())?;
+    write!(output, "{}", text)?;
+    output.reset()
+}
+
+/// A column-aligned table. Every column except the last is padded to the width
+/// of its widest cell, and the last column is truncated so that rows fit within
+/// the maximum width.
+#[derive(Debug)]
+pub struct Table {
+    indent: &'static str,
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<(String, Style)>>,
+}
+
+impl Table {
+    /// Creates a table whose rows are all prefixed with `indent`
+    pub fn new(indent: &'static str, headers: &[&'static str]) -> Self {
+        Table {
+            indent,
+            headers: headers.to_vec(),
+            rows: vec![],
+        }
+    }
+
+    pub fn row(&mut self, cells: Vec<(String, Style)>) {
+        self.rows.push(cells);
+    }
+
+    /// Writes the table to `output`. If `max_width` is provided, the last
+    /// column is truncated so that no row exceeds it.
+    pub fn write(&self, output: &mut impl WriteColor, max_width: Option<usize>) -> io::Result<()> {
+        let columns = self.headers.len();
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, (text, _)) in widths.iter_mut().zip(row) {
+                *width = (*width).max(text.chars().count());
+            }
+        }
+
+        // Tabs in the indent are assumed to expand to 8 columns
+        let indent_width: usize = self
+            .indent
+            .chars()
+            .map(|c| if c == '\t' { 8 } else { 1 })
+            .sum();
+        let fixed_width = indent_width + widths[..columns - 1].iter().map(|w| w + 1).sum::<usize>();
+        let last_width = max_width.map(|max| max.saturating_sub(fixed_width).max(8));
+
+        let headers = self
+            .headers
+            .iter()
+            .map(|header| (header.to_string(), Style::Heading))
+            .collect::<Vec<_>>();
+        self.write_row(output, &headers, &widths, last_width)?;
+        for row in &self.rows {
+            self.write_row(output, row, &widths, last_width)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_row(
+        &self,
+        output: &mut impl WriteColor,
+        row: &[(String, Style)],
+        widths: &[usize],
+        last_width: Option<usize>,
+    ) -> io::Result<()> {
+        write!(output, "{}", self.indent)?;
+        for (i, (text, style)) in row.iter().enumerate() {
+            if i == row.len() - 1 {
+                write_styled(output, *style, &truncate(text, last_width))?;
+            } else {
+                write_styled(output, *style, text)?;
+                let padding = widths[i] - text.chars().count() + 1;
+                write!(output, "{:padding$}", "", padding = padding)?;
+            }
+        }
+
+        writeln!(output)
+    }
+}
+
+/// Shortens `text` to at most `width` characters, marking the truncation with `...`
+fn truncate(text: &str, width: Option<usize>) -> String {
+    match width {
+        Some(width) if text.chars().count() > width => {
+            let mut truncated: String = text.chars().take(width.saturating_sub(3)).collect();
+            truncated.push_str("...");
+            truncated
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// Returns the width of the terminal attached to stdout, if there is one
+#[cfg(unix)]
+pub fn terminal_width() -> Option<usize> {
+    if !atty::is(atty::Stream::Stdout) {
+        return None;
+    }
+
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return Some(columns);
+    }
+
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: TIOCGWINSZ only writes to the provided `winsize`
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result == 0 && size.ws_col > 0 {
+        Some(size.ws_col as usize)
+    } else {
+        None
+    }
+}
+
+/// Returns the width of the terminal attached to stdout, if there is one
+#[cfg(not(unix))]
+pub fn terminal_width() -> Option<usize> {
+    if !atty::is(atty::Stream::Stdout) {
+        return None;
+    }
+
+    std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok())
+}
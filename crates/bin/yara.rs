@@ -0,0 +1,169 @@
+//! The `yara` output format: a YARA rule skeleton for each procedure, recording
+//! where the procedure is in the image it was built into. Given the image, the
+//! rules also match the procedure's leading bytes.
+use crate::backend::OutputBackend;
+use ezpdb::symbol_types::*;
+use ezpdb::type_info::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The longest identifier YARA accepts
+const MAX_IDENTIFIER_LEN: usize = 128;
+
+/// Escapes text for inclusion in a YARA text string
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_ascii_control() => write!(f, "\\x{:02x}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the identifier of the rule for the procedure `name` at `rva`. Names
+/// are reduced to the characters identifiers may contain, and the RVA keeps
+/// rules of procedures which share a name, or reduce to the same identifier,
+/// apart.
+fn rule_identifier(name: &str, rva: usize) -> String {
+    let mut identifier = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            identifier.push(c);
+        } else if !identifier.is_empty() && !identifier.ends_with('_') {
+            identifier.push('_');
+        }
+    }
+    let suffix = format!("{:X}", rva);
+    let max_len = MAX_IDENTIFIER_LEN - suffix.len() - 1;
+    if identifier.len() > max_len {
+        identifier.truncate(max_len);
+    }
+    if !identifier.ends_with('_') {
+        identifier.push('_');
+    }
+    // Identifiers can't start with a digit, or be empty
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) || identifier == "_" {
+        identifier.insert_str(0, "fn");
+    }
+
+    identifier + &suffix
+}
+
+/// Formats `bytes` as a YARA hex string, with relocated bytes as wildcards.
+/// Wildcards at either end are left out, as they don't narrow what the string
+/// matches. Returns `None` if every byte is a wildcard.
+fn hex_string(bytes: &[Option<u8>]) -> Option<String> {
+    let start = bytes.iter().position(Option::is_some)?;
+    let end = bytes.iter().rposition(Option::is_some)? + 1;
+    let bytes: Vec<_> = bytes[start..end]
+        .iter()
+        .map(|byte| match byte {
+            Some(byte) => format!("{:02X}", byte),
+            None => "??".to_string(),
+        })
+        .collect();
+
+    Some(format!("{{ {} }}", bytes.join(" ")))
+}
+
+/// Writes a YARA rule for each procedure of `pdb_info` which has an address,
+/// with the procedure's name, RVA, and length as metadata. If `code` has the
+/// leading bytes of a procedure, keyed by RVA, its rule matches them. Rules of
+/// other procedures are skeletons, which never match until a condition is
+/// written for them.
+pub fn print_yara_rules(
+    output: &mut dyn Write,
+    pdb_info: &ParsedPdb,
+    code: &HashMap<usize, Vec<Option<u8>>>,
+) -> anyhow::Result<()> {
+    let pdb_name = pdb_info
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let mut first = true;
+    for procedure in &pdb_info.procedures {
+        let rva = match procedure.address {
+            Some(address) => address.rva,
+            None => continue,
+        };
+        if !first {
+            writeln!(output)?;
+        }
+        first = false;
+
+        writeln!(output, "rule {}", rule_identifier(&procedure.name, rva))?;
+        writeln!(output, "{{")?;
+        writeln!(output, "    meta:")?;
+        writeln!(output, "        function = \"{}\"", Escape(&procedure.name))?;
+        writeln!(output, "        rva = \"0x{:X}\"", rva)?;
+        writeln!(output, "        length = {}", procedure.len)?;
+        writeln!(output, "        pdb = \"{}\"", Escape(&pdb_name))?;
+        writeln!(output, "        pdb_guid = \"{}\"", pdb_info.guid)?;
+        writeln!(output, "        pdb_age = {}", pdb_info.age)?;
+
+        match code.get(&rva).and_then(|bytes| hex_string(bytes)) {
+            Some(pattern) => {
+                writeln!(output, "    strings:")?;
+                writeln!(output, "        $entry = {}", pattern)?;
+                writeln!(output, "    condition:")?;
+                writeln!(output, "        $entry")?;
+            }
+            None => {
+                writeln!(output, "    condition:")?;
+                writeln!(
+                    output,
+                    "        // Match the function's code here, or pass the image with --image"
+                )?;
+                writeln!(output, "        false")?;
+            }
+        }
+        writeln!(output, "}}")?;
+    }
+
+    Ok(())
+}
+
+/// The `yara` output format
+pub struct YaraRules {
+    /// The image the PDB belongs to, to read procedures' leading bytes from
+    pub image: Option<PathBuf>,
+    /// How many of a procedure's leading bytes its rule matches
+    pub pattern_len: usize,
+}
+
+impl OutputBackend for YaraRules {
+    fn name(&self) -> &'static str {
+        "yara"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yar", "yara"]
+    }
+
+    fn render(
+        &self,
+        pdb_info: &ParsedPdb,
+        _types: &[TypeId],
+        output: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        let code = match &self.image {
+            Some(image) => ezpdb::code::leading_bytes(pdb_info, image, self.pattern_len)?,
+            None => HashMap::new(),
+        };
+
+        print_yara_rules(output, pdb_info, &code)
+    }
+}
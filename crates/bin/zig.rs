@@ -0,0 +1,486 @@
+use crate::backend::OutputBackend;
+use ezpdb::codegen::definitions::{Definitions, MemberType, Spelling, UniqueNames};
+use ezpdb::codegen::layout::{self, Slot};
+use ezpdb::render::RenderOptions;
+use ezpdb::symbol_types::ParsedPdb;
+use ezpdb::type_info::*;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Zig's keywords, and the names of its primitive values and types other than
+/// the arbitrary width integers, none of which can be used as an identifier
+/// without quoting it
+const RESERVED: &[&str] = &[
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+    "anyerror",
+    "anyopaque",
+    "bool",
+    "comptime_float",
+    "comptime_int",
+    "false",
+    "isize",
+    "noreturn",
+    "null",
+    "true",
+    "type",
+    "undefined",
+    "usize",
+    "void",
+    "f16",
+    "f32",
+    "f64",
+    "f80",
+    "f128",
+    "c_char",
+    "c_short",
+    "c_ushort",
+    "c_int",
+    "c_uint",
+    "c_long",
+    "c_ulong",
+    "c_longlong",
+    "c_ulonglong",
+    "c_longdouble",
+];
+
+/// Returns `name` as a Zig identifier. Names which aren't valid identifiers,
+/// e.g. `demo::Point`, are quoted as `@"demo::Point"` rather than changed, so
+/// they can still be searched for.
+fn identifier(name: &str) -> String {
+    let is_plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    // `i7`, `u24`, and so on are integer types
+    let is_integer_type = name.len() > 1
+        && name.starts_with(['i', 'u'])
+        && name[1..].chars().all(|c| c.is_ascii_digit());
+    if is_plain && !is_integer_type && !RESERVED.contains(&name) {
+        return name.to_string();
+    }
+
+    let mut quoted = String::with_capacity(name.len() + 3);
+    quoted.push_str("@\"");
+    for c in name.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// Returns the greatest power of two which `offset` is a multiple of, capped
+/// at `max`
+fn offset_alignment(offset: usize, max: usize) -> usize {
+    match offset & offset.wrapping_neg() {
+        0 => max,
+        align => align.min(max),
+    }
+}
+
+/// How Zig spells the types members are written as. Pointers are written as
+/// integers, as the image the PDB describes may have been built for a different
+/// pointer size than the tool reading its memory.
+struct Zig;
+
+impl Spelling for Zig {
+    fn identifier(&self, name: &str) -> String {
+        identifier(name)
+    }
+
+    fn primitive(&self, kind: PrimitiveKind) -> Option<&'static str> {
+        Some(match kind {
+            PrimitiveKind::Char | PrimitiveKind::RChar | PrimitiveKind::I8 => "i8",
+            PrimitiveKind::UChar | PrimitiveKind::U8 => "u8",
+            PrimitiveKind::Short | PrimitiveKind::I16 => "i16",
+            PrimitiveKind::UShort
+            | PrimitiveKind::U16
+            | PrimitiveKind::WChar
+            | PrimitiveKind::RChar16
+            | PrimitiveKind::Bool16 => "u16",
+            PrimitiveKind::Long | PrimitiveKind::I32 | PrimitiveKind::HRESULT => "i32",
+            PrimitiveKind::ULong
+            | PrimitiveKind::U32
+            | PrimitiveKind::RChar32
+            | PrimitiveKind::Bool32 => "u32",
+            PrimitiveKind::Quad | PrimitiveKind::I64 => "i64",
+            PrimitiveKind::UQuad | PrimitiveKind::U64 | PrimitiveKind::Bool64 => "u64",
+            PrimitiveKind::Octa | PrimitiveKind::I128 => "i128",
+            PrimitiveKind::UOcta | PrimitiveKind::U128 => "u128",
+            PrimitiveKind::F16 => "f16",
+            PrimitiveKind::F32 | PrimitiveKind::F32PP => "f32",
+            PrimitiveKind::F64 => "f64",
+            PrimitiveKind::Bool8 => "bool",
+            _ => return None,
+        })
+    }
+
+    fn array(&self, len: usize, element: &str) -> String {
+        format!("[{}]{}", len, element)
+    }
+
+    fn bytes(&self, size: usize) -> String {
+        format!("[{}]u8", size)
+    }
+}
+
+struct ZigWriter<'a> {
+    definitions: Definitions<'a, Zig>,
+}
+
+impl<'a> ZigWriter<'a> {
+    fn type_name(&self, ty: &Type) -> String {
+        self.definitions.type_name(ty)
+    }
+
+    fn member_type(&self, id: TypeId) -> MemberType {
+        self.definitions.member_type(id)
+    }
+
+    /// Writes a field of type `ty` at `offset`. Fields which the C ABI would
+    /// align further than the PDB's layout has them are given a lower
+    /// alignment. Returns the field's alignment.
+    fn write_field(
+        &self,
+        output: &mut impl Write,
+        name: &str,
+        offset: usize,
+        ty: MemberType,
+        max_align: usize,
+    ) -> io::Result<usize> {
+        let align = offset_alignment(offset, max_align).min(ty.align);
+        write!(output, "    {}: {}", name, ty.name)?;
+        if align < ty.align {
+            write!(output, " align({})", align)?;
+        }
+        match ty.note {
+            Some(note) => writeln!(output, ", // +0x{:X} {}", offset, note)?,
+            None => writeln!(output, ", // +0x{:X}", offset)?,
+        }
+
+        Ok(align)
+    }
+
+    /// Writes the fields of a struct of `size` bytes with `fields`, with
+    /// padding fields so that each member is at its offset. Returns the
+    /// struct's alignment.
+    fn write_struct_fields(
+        &self,
+        output: &mut impl Write,
+        fields: &[TypeId],
+        size: usize,
+    ) -> io::Result<usize> {
+        let pdb = self.definitions.pdb;
+        let max_align = layout::max_alignment(size);
+        let mut used = UniqueNames::default();
+        let mut align = 1;
+        for slot in layout::layout(pdb, fields, size) {
+            match slot {
+                Slot::Member { offset, name, ty } => {
+                    let name = used.unique_spelled(name, identifier);
+                    let field_align =
+                        self.write_field(output, &name, offset, self.member_type(ty), max_align)?;
+                    align = align.max(field_align);
+                }
+                Slot::Base { offset, ty } => {
+                    let name = format!("base_{}", self.type_name(&pdb.types[ty]));
+                    let name = used.unique_spelled(&name, identifier);
+                    let field_align =
+                        self.write_field(output, &name, offset, self.member_type(ty), max_align)?;
+                    align = align.max(field_align);
+                }
+                Slot::Bitfields {
+                    offset,
+                    storage,
+                    bits,
+                } => {
+                    let bits: Vec<String> = bits
+                        .iter()
+                        .map(|bits| format!("{}:{}@{}", bits.name, bits.len, bits.position))
+                        .collect();
+                    let name = used.unique(&format!("bitfield_0x{:X}", offset));
+                    let storage = MemberType {
+                        note: Some(bits.join(", ")),
+                        ..self.member_type(storage)
+                    };
+                    let field_align =
+                        self.write_field(output, &name, offset, storage, max_align)?;
+                    align = align.max(field_align);
+                }
+                Slot::Padding { offset, len } => {
+                    let name = used.unique(&format!("_padding_0x{:X}", offset));
+                    writeln!(
+                        output,
+                        "    {}: [{}]u8 = [_]u8{{0}} ** {}, // +0x{:X}",
+                        name, len, len, offset
+                    )?;
+                }
+                Slot::Overlapping { offset, name, ty } => writeln!(
+                    output,
+                    "    // {}: {} at +0x{:X} overlaps the previous member",
+                    identifier(name),
+                    self.type_name(&pdb.types[ty]),
+                    offset
+                )?,
+            }
+        }
+
+        Ok(align)
+    }
+
+    /// Writes the members of a union of `size` bytes with `fields`. Members
+    /// which don't start at the beginning of the union can't be written, and a
+    /// field of `size` bytes is added if no member is that large. Returns the
+    /// union's alignment.
+    fn write_union_fields(
+        &self,
+        output: &mut impl Write,
+        fields: &[TypeId],
+        size: usize,
+    ) -> io::Result<usize> {
+        let pdb = self.definitions.pdb;
+        let max_align = layout::max_alignment(size);
+        let mut used = UniqueNames::default();
+        let mut align = 1;
+        let mut largest = 0;
+        for field in fields {
+            let member = match &pdb.types[*field] {
+                Type::Member(member) => member,
+                _ => continue,
+            };
+            let member_type = match &pdb.types[member.underlying_type] {
+                Type::Bitfield(_) => None,
+                _ => Some(self.member_type(member.underlying_type)),
+            };
+            match member_type {
+                Some(ty) if member.offset == 0 => {
+                    largest = largest.max(ty.size);
+                    let name = used.unique_spelled(&member.name, identifier);
+                    align = align.max(self.write_field(output, &name, 0, ty, max_align)?);
+                }
+                _ => writeln!(
+                    output,
+                    "    // {}: {} at +0x{:X} can't be a member of an extern union",
+                    identifier(&member.name),
+                    self.type_name(&pdb.types[member.underlying_type]),
+                    member.offset
+                )?,
+            }
+        }
+        if largest < size {
+            let name = used.unique("_bytes");
+            writeln!(output, "    {}: [{}]u8,", name, size)?;
+        }
+
+        Ok(align)
+    }
+
+    /// Writes the definition of the class, union, or enum `id`
+    fn write_definition(&mut self, output: &mut impl Write, id: TypeId) -> io::Result<()> {
+        let pdb = self.definitions.pdb;
+        let ty = &pdb.types[id];
+        let name = match self.definitions.name(ty) {
+            Some(name) => name.to_string(),
+            None => return Ok(()),
+        };
+        let size = ty.type_size(pdb);
+
+        match ty {
+            Type::Class(class) => {
+                let kind = class.kind.to_string().to_ascii_lowercase();
+                writeln!(
+                    output,
+                    "/// `{}` ({}, 0x{:X} bytes)",
+                    class.name, kind, size
+                )?;
+                writeln!(output, "pub const {} = extern struct {{", name)?;
+                let align = self.write_struct_fields(output, &class.fields, size)?;
+                writeln!(output, "}};")?;
+                self.definitions.set_alignment(ty, align);
+            }
+            Type::Union(union) => {
+                writeln!(output, "/// `{}` (union, 0x{:X} bytes)", union.name, size)?;
+                writeln!(output, "pub const {} = extern union {{", name)?;
+                let align = self.write_union_fields(output, &union.fields, size)?;
+                writeln!(output, "}};")?;
+                self.definitions.set_alignment(ty, align);
+            }
+            Type::Enumeration(e) => {
+                let underlying = self.member_type(e.underlying_type);
+                let signed = underlying.name.starts_with('i');
+                let integer = underlying.note.is_none()
+                    && (signed || underlying.name.starts_with('u'))
+                    && underlying.name[1..].chars().all(|c| c.is_ascii_digit());
+
+                writeln!(output, "/// `{}` (enum, 0x{:X} bytes)", e.name, size)?;
+                if !integer {
+                    writeln!(output, "pub const {} = {};", name, underlying.name)?;
+                } else {
+                    // Enums are non-exhaustive, as C allows any value of the
+                    // underlying type. Zig doesn't allow two variants to have
+                    // the same value, so later ones are declared as aliases.
+                    writeln!(output, "pub const {} = enum({}) {{", name, underlying.name)?;
+                    let bits = (underlying.size * 8) as u32;
+                    let mut used = UniqueNames::default();
+                    let mut values: HashMap<String, String> = HashMap::new();
+                    let mut aliases = vec![];
+                    for variant in &e.variants {
+                        let variant_name = used.unique_spelled(&variant.name, identifier);
+                        let value = variant.value.as_u64();
+                        let value = if signed {
+                            // Sign-extend from the enum's size
+                            let shift = 64u32.saturating_sub(bits);
+                            (((value << shift) as i64) >> shift).to_string()
+                        } else {
+                            let mask = u64::MAX
+                                .checked_shr(64u32.saturating_sub(bits))
+                                .unwrap_or(0);
+                            format!("0x{:X}", value & mask)
+                        };
+                        match values.get(&value) {
+                            Some(original) => aliases.push((variant_name, original.clone())),
+                            None => {
+                                writeln!(output, "    {} = {},", variant_name, value)?;
+                                values.insert(value, variant_name);
+                            }
+                        }
+                    }
+                    writeln!(output, "    _,")?;
+                    if !aliases.is_empty() {
+                        writeln!(output)?;
+                    }
+                    for (alias, original) in aliases {
+                        writeln!(output, "    pub const {} = @This().{};", alias, original)?;
+                    }
+                    writeln!(output, "}};")?;
+                }
+            }
+            _ => return Ok(()),
+        }
+
+        writeln!(output)?;
+        writeln!(output, "comptime {{")?;
+        writeln!(
+            output,
+            "    std.debug.assert(@sizeOf({}) == 0x{:X});",
+            name, size
+        )?;
+        writeln!(output, "}}")
+    }
+}
+
+/// Writes `types` and the classes, unions, and enums they contain by value as
+/// Zig `extern struct`, `extern union`, and `enum` definitions, each type after
+/// the ones it depends upon. Structs have padding fields so that each member is
+/// at the offset the PDB records, and a compile-time assertion of their size.
+/// Pointers are written as integers, and bitfields as their storage unit.
+pub fn print_zig(
+    output: &mut impl Write,
+    pdb: &ParsedPdb,
+    types: &[TypeId],
+    render: &RenderOptions,
+) -> io::Result<()> {
+    // `std` is imported under its own name
+    let mut writer = ZigWriter {
+        definitions: Definitions::new(pdb, types, render, Zig, &["std"]),
+    };
+
+    let file_name = pdb
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    writeln!(
+        output,
+        "// Code generated by pdbview from {}. DO NOT EDIT.",
+        file_name
+    )?;
+    writeln!(output)?;
+    writeln!(output, "const std = @import(\"std\");")?;
+    for id in writer.definitions.order.clone() {
+        writeln!(output)?;
+        writer.write_definition(output, id)?;
+    }
+
+    Ok(())
+}
+
+/// The `zig` output format, which writes every class, union, and enum as a Zig
+/// type definition
+pub struct ZigStructs {
+    pub render: RenderOptions,
+}
+
+impl OutputBackend for ZigStructs {
+    fn name(&self) -> &'static str {
+        "zig"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["zig"]
+    }
+
+    fn renders_types(&self) -> bool {
+        true
+    }
+
+    fn render(
+        &self,
+        pdb_info: &ParsedPdb,
+        types: &[TypeId],
+        mut output: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        Ok(print_zig(&mut output, pdb_info, types, &self.render)?)
+    }
+}
@@ -0,0 +1,259 @@
+//! Lookup tables over a [ParsedPdb]'s symbols and types, so that finding a
+//! symbol by name or address or a type by name doesn't need a scan of every
+//! symbol or type
+use crate::symbol_types::{ParsedPdb, ResolvedKind, ResolvedSymbol, SymbolRef};
+use crate::type_info::{Type, TypeId};
+use std::collections::{BTreeMap, HashMap};
+
+/// Where a symbol is stored in a [ParsedPdb]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SymbolId {
+    /// Index into [ParsedPdb::public_symbols]
+    Public(usize),
+    /// Index into [ParsedPdb::procedures]
+    Procedure(usize),
+    /// Index into [ParsedPdb::global_data]
+    GlobalData(usize),
+    /// Index into [ParsedPdb::local_data]
+    LocalData(usize),
+}
+
+/// Maps from names and addresses to the symbols and types of a [ParsedPdb].
+/// Built by [ParsedPdb::build_index], and not updated when the PDB is: build it
+/// again after renaming or adding symbols or types.
+#[derive(Debug, Clone, Default)]
+pub struct PdbIndex {
+    /// Maps names to the symbols with that name
+    pub symbols_by_name: HashMap<String, Vec<SymbolId>>,
+    /// Maps RVAs to the symbols which start there
+    pub symbols_by_rva: BTreeMap<usize, Vec<SymbolId>>,
+    /// Maps names to the classes, unions, and enums with that name. Forward
+    /// references aren't included.
+    pub types_by_name: HashMap<String, Vec<TypeId>>,
+}
+
+impl PdbIndex {
+    /// Indexes the symbols and types of `pdb`
+    pub fn new(pdb: &ParsedPdb) -> Self {
+        let mut index = PdbIndex::default();
+        for (id, symbol) in pdb.symbols() {
+            index
+                .symbols_by_name
+                .entry(symbol.name().to_string())
+                .or_default()
+                .push(id);
+            if let Some(address) = symbol.address() {
+                index
+                    .symbols_by_rva
+                    .entry(address.rva)
+                    .or_default()
+                    .push(id);
+            }
+        }
+
+        for (id, ty) in pdb.types.iter() {
+            if let Some(name) = definition_name(ty) {
+                index
+                    .types_by_name
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(id);
+            }
+        }
+
+        index
+    }
+}
+
+/// Returns the name of `ty` if it's a class, union, or enum definition
+fn definition_name(ty: &Type) -> Option<&str> {
+    match ty {
+        Type::Class(class) if !class.properties.forward_reference => Some(&class.name),
+        Type::Union(union) if !union.properties.forward_reference => Some(&union.name),
+        Type::Enumeration(e) if !e.properties.forward_reference => Some(&e.name),
+        _ => None,
+    }
+}
+
+impl ParsedPdb {
+    /// Builds [ParsedPdb::index] from the current symbols and types
+    pub fn build_index(&mut self) {
+        self.index = Some(PdbIndex::new(self));
+    }
+
+    /// Returns every public symbol, procedure, and global and local variable
+    /// along with where it's stored
+    pub fn symbols(&self) -> impl Iterator<Item = (SymbolId, SymbolRef<'_>)> {
+        let publics = self
+            .public_symbols
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| (SymbolId::Public(index), SymbolRef::Public(symbol)));
+        let procedures = self
+            .procedures
+            .iter()
+            .enumerate()
+            .map(|(index, procedure)| {
+                (SymbolId::Procedure(index), SymbolRef::Procedure(procedure))
+            });
+        let global_data = self
+            .global_data
+            .iter()
+            .enumerate()
+            .map(|(index, data)| (SymbolId::GlobalData(index), SymbolRef::Data(data)));
+        let local_data = self
+            .local_data
+            .iter()
+            .enumerate()
+            .map(|(index, data)| (SymbolId::LocalData(index), SymbolRef::Data(data)));
+
+        publics
+            .chain(procedures)
+            .chain(global_data)
+            .chain(local_data)
+    }
+
+    /// Returns the symbol stored at `id`
+    pub fn symbol(&self, id: SymbolId) -> Option<SymbolRef<'_>> {
+        match id {
+            SymbolId::Public(index) => self.public_symbols.get(index).map(SymbolRef::Public),
+            SymbolId::Procedure(index) => self.procedures.get(index).map(SymbolRef::Procedure),
+            SymbolId::GlobalData(index) => self.global_data.get(index).map(SymbolRef::Data),
+            SymbolId::LocalData(index) => self.local_data.get(index).map(SymbolRef::Data),
+        }
+    }
+
+    /// Returns the symbols named `name`. Uses [ParsedPdb::index] if it was built.
+    pub fn symbols_named(&self, name: &str) -> Vec<SymbolRef<'_>> {
+        match &self.index {
+            Some(index) => self.resolve(index.symbols_by_name.get(name)),
+            None => self
+                .symbols()
+                .map(|(_id, symbol)| symbol)
+                .filter(|symbol| symbol.name() == name)
+                .collect(),
+        }
+    }
+
+    /// Returns the symbols which start at `rva`. Uses [ParsedPdb::index] if it
+    /// was built.
+    pub fn symbols_at(&self, rva: usize) -> Vec<SymbolRef<'_>> {
+        match &self.index {
+            Some(index) => self.resolve(index.symbols_by_rva.get(&rva)),
+            None => self
+                .symbols()
+                .map(|(_id, symbol)| symbol)
+                .filter(|symbol| symbol.address().is_some_and(|address| address.rva == rva))
+                .collect(),
+        }
+    }
+
+    /// Returns the classes, unions, and enums named `name` which aren't forward
+    /// references. Uses [ParsedPdb::index] if it was built.
+    pub fn types_named(&self, name: &str) -> Vec<TypeId> {
+        match &self.index {
+            Some(index) => index.types_by_name.get(name).cloned().unwrap_or_default(),
+            None => self
+                .types
+                .iter()
+                .filter(|(_id, ty)| definition_name(ty) == Some(name))
+                .map(|(id, _ty)| id)
+                .collect(),
+        }
+    }
+
+    /// Returns every symbol which `name` may refer to, ordered by address:
+    /// those named `name`, and those whose names are `name` decorated as
+    /// imports (`__imp_` prefix) or with the x86 `__cdecl` or `__stdcall`
+    /// conventions (`_` prefix, `@N` suffix). A function may have several, e.g.
+    /// both an import thunk and its import address table slot.
+    pub fn resolve_name(&self, name: &str) -> Vec<ResolvedSymbol> {
+        let module_name = |index: Option<usize>| {
+            index
+                .and_then(|index| self.debug_modules.get(index))
+                .map(|module| module.name.clone())
+        };
+        let procedure_modules: HashMap<usize, Option<usize>> = self
+            .procedures
+            .iter()
+            .filter_map(|procedure| Some((procedure.address?.rva, procedure.module_index)))
+            .collect();
+
+        let mut resolved: Vec<ResolvedSymbol> = self
+            .symbols()
+            .filter_map(|(id, symbol)| {
+                let is_import = matches_decorated(symbol.name(), name)?;
+                let (kind, module) = match id {
+                    SymbolId::Public(_) => {
+                        let kind = if is_import {
+                            ResolvedKind::Import
+                        } else {
+                            ResolvedKind::Public
+                        };
+                        let module = symbol
+                            .address()
+                            .and_then(|address| procedure_modules.get(&address.rva))
+                            .and_then(|index| module_name(*index));
+                        (kind, module)
+                    }
+                    SymbolId::Procedure(index) => (
+                        ResolvedKind::Procedure,
+                        module_name(self.procedures[index].module_index),
+                    ),
+                    SymbolId::GlobalData(index) => (
+                        ResolvedKind::GlobalData,
+                        self.global_data[index].module.clone(),
+                    ),
+                    SymbolId::LocalData(index) => (
+                        ResolvedKind::LocalData,
+                        self.local_data[index].module.clone(),
+                    ),
+                };
+
+                Some(ResolvedSymbol {
+                    name: symbol.name().to_string(),
+                    kind,
+                    address: symbol.address(),
+                    module,
+                })
+            })
+            .collect();
+        resolved.sort_by_key(|symbol| {
+            let rva = symbol.address.map(|address| address.rva);
+            (rva.is_none(), rva)
+        });
+
+        resolved
+    }
+
+    fn resolve(&self, ids: Option<&Vec<SymbolId>>) -> Vec<SymbolRef<'_>> {
+        ids.into_iter()
+            .flatten()
+            .filter_map(|id| self.symbol(*id))
+            .collect()
+    }
+}
+
+/// Returns whether `decorated` is `name` with import or x86 calling convention
+/// decorations, and if so whether it's an import, or `None` if it isn't `name`
+fn matches_decorated(decorated: &str, name: &str) -> Option<bool> {
+    let (undecorated, is_import) = match decorated.strip_prefix("__imp_") {
+        Some(rest) => (rest, true),
+        None => (decorated, false),
+    };
+    if undecorated == name {
+        return Some(is_import);
+    }
+
+    let undecorated = undecorated.strip_prefix('_')?;
+    let undecorated = match undecorated.rsplit_once('@') {
+        Some((base, arguments_size))
+            if !arguments_size.is_empty() && arguments_size.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => undecorated,
+    };
+
+    (undecorated == name).then_some(is_import)
+}
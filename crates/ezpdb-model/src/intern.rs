@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A cheaply-clonable handle to an interned string. Names which appear many times
+/// throughout a PDB (e.g. a class's name referenced by each of its forward
+/// references) all share a single allocation.
+pub type Name = Arc<str>;
+
+/// A pool of interned strings
+#[derive(Debug, Default, Clone)]
+pub struct StringPool {
+    strings: HashSet<Name>,
+}
+
+impl StringPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the interned copy of `s`, adding it to the pool if it is not
+    /// already present
+    pub fn intern(&mut self, s: &str) -> Name {
+        if let Some(existing) = self.strings.get(s) {
+            return Arc::clone(existing);
+        }
+
+        let name: Name = Arc::from(s);
+        self.strings.insert(Arc::clone(&name));
+
+        name
+    }
+
+    /// Returns the number of unique strings in the pool
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
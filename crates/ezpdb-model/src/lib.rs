@@ -0,0 +1,9 @@
+//! The data model produced by [ezpdb](https://crates.io/crates/ezpdb). This crate
+//! has no dependency on the PDB parser, so consumers which only read pdbview's
+//! serialized output can depend on it alone.
+pub mod index;
+pub mod intern;
+pub mod symbol_types;
+pub mod type_info;
+
+pub use crate::symbol_types::ParsedPdb;
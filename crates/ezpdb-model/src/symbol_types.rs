@@ -0,0 +1,1278 @@
+use crate::index::PdbIndex;
+use crate::intern::StringPool;
+use crate::type_info::{Class, ClassKind, Partial, Type, TypeArena, TypeId, VTableShape};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeBounds;
+use std::path::PathBuf;
+
+pub type TypeIndexNumber = u32;
+
+/// Represents a PDB that has been fully parsed
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParsedPdb {
+    pub path: PathBuf,
+    pub assembly_info: AssemblyInfo,
+    pub public_symbols: Vec<PublicSymbol>,
+    /// Every type parsed from the PDB
+    pub types: TypeArena,
+    /// Maps TPI type indices to the [TypeId] of the type they were parsed as
+    pub type_indices: HashMap<TypeIndexNumber, TypeId>,
+    /// Maps the TPI indices of `LF_VTSHAPE` records to their contents. Shapes
+    /// are also attached to the classes which use them.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub vtable_shapes: HashMap<TypeIndexNumber, VTableShape>,
+    /// Maps the TPI indices of records the `pdb` crate can't parse, but which
+    /// have a fallback decoder, to what could be decoded of them
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub partial_types: HashMap<TypeIndexNumber, Partial>,
+    /// Maps the TPI indices of pointers to members to their raw `CV_pmtype`,
+    /// which the `pdb` crate doesn't keep
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub member_pointer_representations: HashMap<TypeIndexNumber, u16>,
+    pub procedures: Vec<Procedure>,
+    pub global_data: Vec<Data>,
+    /// File- and function-static variables. Only populated when requested with
+    /// `ParseOptions::include_local_data`.
+    pub local_data: Vec<Data>,
+    /// Strings attached to code with the `__annotation` intrinsic
+    pub annotations: Vec<Annotation>,
+    /// References from the global symbol stream to [Annotation]s in module symbol streams
+    pub annotation_references: Vec<AnnotationReference>,
+    /// The symbols the image exports, as the linker recorded them when it
+    /// wrote the PDB
+    pub exports: Vec<Export>,
+    /// Thunks inserted by the linker, e.g. for incremental linking
+    pub trampolines: Vec<Trampoline>,
+    /// Blocks of code which were moved out of their parent procedure
+    pub separated_code: Vec<SeparatedCode>,
+    /// Calls to heap allocation functions along with the type being allocated
+    pub heap_allocation_sites: Vec<HeapAllocationSite>,
+    /// Sections of the image as recorded by the linker
+    pub sections: Vec<Section>,
+    /// Groups of COFF sections (e.g. `.text$mn`) which were merged into the image's sections
+    pub coff_groups: Vec<CoffGroup>,
+    /// Indirect calls, e.g. through function pointers or vtables
+    pub call_sites: Vec<CallSite>,
+    /// Direct calls between functions, as recorded by the compiler
+    pub call_graph: Vec<CallGraphEdge>,
+    /// Code which the CRT runs before `main` or at exit, e.g. the constructors
+    /// of global objects
+    pub static_initializers: Vec<StaticInitializer>,
+    /// Symbols of the image's load configuration and of the tables and checks
+    /// of security mitigations such as Control Flow Guard
+    pub security_symbols: Vec<SecuritySymbol>,
+    /// Strings which reveal where and by whom the image was built, such as
+    /// absolute paths and build command lines
+    pub provenance: Vec<ProvenanceString>,
+    /// Symbols whose debug information was left in object files. Only present
+    /// if [ParsedPdb::is_fastlink].
+    pub fastlink_references: Vec<FastlinkReference>,
+    /// Managed (.NET) methods compiled into a mixed-mode image
+    pub managed_procedures: Vec<ManagedProcedure>,
+    /// References from the global symbol stream to [ManagedProcedure]s in
+    /// module symbol streams
+    pub token_references: Vec<TokenReference>,
+    /// The token map stream of mixed-mode PDBs. Entry `n` is the metadata RID the
+    /// method with RID `n` was given in the final image, so tokens which were
+    /// renumbered when the image's metadata was merged can be looked up. Empty if
+    /// the PDB has no token map.
+    pub token_rid_map: Vec<u32>,
+    pub debug_modules: Vec<DebugModule>,
+    pub version: Version,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_uuid",
+            deserialize_with = "deserialize_uuid"
+        )
+    )]
+    pub guid: uuid::Uuid,
+    pub age: u32,
+    pub timestamp: u32,
+    pub machine_type: Option<MachineType>,
+    /// What ARM64EC and ARM64X images record about how their code mixes
+    /// instruction sets. `None` for other images.
+    pub hybrid: Option<HybridInfo>,
+    /// Whether the PDB was linked with `/DEBUG:FASTLINK`. Such PDBs only
+    /// reference the types and symbols in the object files they were built
+    /// from, so most listings will be close to empty.
+    pub is_fastlink: bool,
+    /// Pool which names in the type graph are interned into. Empty when the
+    /// PDB was deserialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub strings: StringPool,
+    /// Records which couldn't be parsed and were skipped
+    pub diagnostics: Vec<Diagnostic>,
+    /// Lookup tables over the symbols and types. Only built when requested with
+    /// `ParseOptions::build_index` or [ParsedPdb::build_index].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub index: Option<PdbIndex>,
+}
+
+impl ParsedPdb {
+    /// Constructs a new [ParsedPdb] with the corresponding path
+    pub fn new(path: PathBuf) -> Self {
+        ParsedPdb {
+            path,
+            assembly_info: AssemblyInfo::default(),
+            public_symbols: vec![],
+            types: TypeArena::new(),
+            type_indices: Default::default(),
+            vtable_shapes: Default::default(),
+            partial_types: Default::default(),
+            member_pointer_representations: Default::default(),
+            procedures: vec![],
+            global_data: vec![],
+            local_data: vec![],
+            annotations: vec![],
+            annotation_references: vec![],
+            exports: vec![],
+            trampolines: vec![],
+            separated_code: vec![],
+            heap_allocation_sites: vec![],
+            sections: vec![],
+            coff_groups: vec![],
+            call_sites: vec![],
+            call_graph: vec![],
+            static_initializers: vec![],
+            security_symbols: vec![],
+            provenance: vec![],
+            fastlink_references: vec![],
+            managed_procedures: vec![],
+            token_references: vec![],
+            token_rid_map: vec![],
+            debug_modules: vec![],
+            version: Version::Other(0),
+            guid: uuid::Uuid::nil(),
+            age: 0,
+            timestamp: 0,
+            machine_type: None,
+            hybrid: None,
+            is_fastlink: false,
+            strings: StringPool::new(),
+            diagnostics: vec![],
+            index: None,
+        }
+    }
+
+    /// Returns the name of the variant of the enum `enum_name` with the value
+    /// `value`, e.g. `NonPagedPoolNx` for `("_POOL_TYPE", 0x200)`. See
+    /// [crate::type_info::Enumeration::value_name] for how flag combinations are named. Returns
+    /// `None` if there's no such enum or no variant matches.
+    pub fn enum_value_name(&self, enum_name: &str, value: u64) -> Option<String> {
+        self.types
+            .iter()
+            .find_map(|(_id, ty)| match ty {
+                Type::Enumeration(e)
+                    if !e.properties.forward_reference && &*e.name == enum_name =>
+                {
+                    Some(e)
+                }
+                _ => None,
+            })?
+            .value_name(value, self)
+    }
+
+    /// Returns the managed procedure with the metadata token `token`, e.g.
+    /// `0x06000001`. If the PDB has a token map, the token's RID is remapped
+    /// through it first.
+    pub fn managed_procedure(&self, token: u32) -> Option<&ManagedProcedure> {
+        let rid = (token & METADATA_RID_MASK) as usize;
+        let token = match self.token_rid_map.get(rid) {
+            Some(&mapped) if mapped != 0 => (token & !METADATA_RID_MASK) | mapped,
+            _ => token,
+        };
+
+        self.managed_procedures
+            .iter()
+            .find(|procedure| procedure.token == token)
+    }
+
+    /// Returns the procedures whose RVA is within `range`, e.g. `0x1000..0x2000`
+    pub fn procedures_in_range<'a, R>(&'a self, range: R) -> impl Iterator<Item = &'a Procedure>
+    where
+        R: RangeBounds<usize> + 'a,
+    {
+        self.procedures.iter().filter(move |procedure| {
+            procedure
+                .address
+                .is_some_and(|address| range.contains(&address.rva))
+        })
+    }
+
+    /// Returns the public symbols, procedures, and global and local data whose
+    /// names `matches` accepts, e.g. `|name| regex.is_match(name)`
+    pub fn symbols_matching<'a, F>(&'a self, matches: F) -> impl Iterator<Item = SymbolRef<'a>>
+    where
+        F: Fn(&str) -> bool + 'a,
+    {
+        self.symbols()
+            .map(|(_id, symbol)| symbol)
+            .filter(move |symbol| matches(symbol.name()))
+    }
+
+    /// Returns the classes, structs, or interfaces which are definitions rather
+    /// than forward references
+    pub fn types_by_kind(&self, kind: ClassKind) -> impl Iterator<Item = (TypeId, &Class)> {
+        self.types.iter().filter_map(move |(id, ty)| match ty {
+            Type::Class(class) if class.kind == kind && !class.properties.forward_reference => {
+                Some((id, class))
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the global data whose type is the class, union, or enum named
+    /// `name`, ignoring cv-qualifiers. Pointers to and arrays of the type don't
+    /// match.
+    pub fn globals_of_type<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Data> {
+        self.global_data.iter().filter(move |data| {
+            let mut ty = self.types.get(data.ty);
+            while let Some(Type::Modifier(modifier)) = ty {
+                ty = self.types.get(modifier.underlying_type);
+            }
+
+            match ty {
+                Some(Type::Class(class)) => &*class.name == name,
+                Some(Type::Union(union)) => &*union.name == name,
+                Some(Type::Enumeration(e)) => &*e.name == name,
+                _ => false,
+            }
+        })
+    }
+}
+
+/// What kind of symbol a [ResolvedSymbol] is
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ResolvedKind {
+    Public,
+    /// A public symbol for the import address table slot of an imported
+    /// function, e.g. `__imp_CreateFileW`
+    Import,
+    Procedure,
+    GlobalData,
+    LocalData,
+}
+
+impl std::fmt::Display for ResolvedKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ResolvedKind::Public => "public",
+            ResolvedKind::Import => "import",
+            ResolvedKind::Procedure => "procedure",
+            ResolvedKind::GlobalData => "global",
+            ResolvedKind::LocalData => "local",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A symbol returned by [ParsedPdb::resolve_name]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResolvedSymbol {
+    /// The symbol's name as recorded, which may be decorated
+    pub name: String,
+    pub kind: ResolvedKind,
+    pub address: Option<Address>,
+    /// Name of the module which defines the symbol. Public symbols have no
+    /// module of their own, so this is the module of the procedure at the same
+    /// address, if any.
+    pub module: Option<String>,
+}
+
+/// A symbol returned by [ParsedPdb::symbols_matching]
+#[derive(Debug, Copy, Clone)]
+pub enum SymbolRef<'a> {
+    Public(&'a PublicSymbol),
+    Procedure(&'a Procedure),
+    /// Global or local data
+    Data(&'a Data),
+}
+
+impl<'a> SymbolRef<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            SymbolRef::Public(symbol) => &symbol.name,
+            SymbolRef::Procedure(procedure) => &procedure.name,
+            SymbolRef::Data(data) => &data.name,
+        }
+    }
+
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            SymbolRef::Public(symbol) => symbol.offset,
+            SymbolRef::Procedure(procedure) => procedure.address,
+            SymbolRef::Data(data) => data.offset,
+        }
+    }
+}
+
+/// The bits of a metadata token holding the row (RID) it refers to. The top
+/// byte is the table, e.g. `0x06` for methods.
+const METADATA_RID_MASK: u32 = 0x00FF_FFFF;
+
+/// A record which couldn't be parsed, along with enough of its location to
+/// find it again without the PDB
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Diagnostic {
+    pub category: DiagnosticCategory,
+    /// The stream holding the record. `None` if it isn't known.
+    pub stream: Option<u32>,
+    /// Byte offset of the record within `stream`
+    pub offset: Option<usize>,
+    /// The record's kind, i.e. its `LF_*` or `S_*` constant
+    pub kind: Option<u16>,
+    /// The index of the record if it's a type
+    pub type_index: Option<TypeIndexNumber>,
+    pub description: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)?;
+        if let Some(stream) = self.stream {
+            write!(f, " (stream {}", stream)?;
+            if let Some(offset) = self.offset {
+                write!(f, ", offset 0x{:X}", offset)?;
+            }
+            write!(f, ")")?;
+        }
+        if let Some(kind) = self.kind {
+            write!(f, " (kind 0x{:04X})", kind)?;
+        }
+        if let Some(type_index) = self.type_index {
+            write!(f, " (type 0x{:X})", type_index)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What went wrong with the record a [Diagnostic] describes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "extra-traits", derive(Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DiagnosticCategory {
+    /// The record's data is malformed
+    Corrupt,
+    /// The record is of a kind which can't be parsed
+    UnknownKind,
+    /// The record refers to a type which couldn't be found
+    UnresolvedType,
+    /// A stream the record would be read from is missing
+    MissingStream,
+}
+
+/// Header-level information about a PDB, read without parsing its types or symbols
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PdbSummary {
+    pub path: PathBuf,
+    pub version: Version,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_uuid",
+            deserialize_with = "deserialize_uuid"
+        )
+    )]
+    pub guid: uuid::Uuid,
+    pub age: u32,
+    pub timestamp: u32,
+    pub machine_type: Option<MachineType>,
+    pub is_fastlink: bool,
+    /// The number of streams in the MSF container, including deleted ones
+    pub stream_count: u32,
+    /// The number of modules in the DBI stream
+    pub module_count: usize,
+}
+
+/// A TPI record exactly as it's stored, read without parsing it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RawTypeRecord {
+    pub type_index: TypeIndexNumber,
+    /// The record's leaf kind, e.g. `0x1505` for `LF_STRUCTURE`
+    pub kind: u16,
+    /// The `LF_*` name of the leaf kind, if it's a known one
+    pub kind_name: Option<String>,
+    /// Offset of the record within the TPI stream
+    pub offset: usize,
+    /// The record's bytes, starting with its length
+    pub data: Vec<u8>,
+    /// Why the `pdb` crate couldn't parse the record, if it couldn't
+    pub parse_error: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+fn serialize_uuid<S: serde::Serializer>(uuid: &uuid::Uuid, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(uuid.to_string().as_ref())
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_uuid<'de, D: serde::Deserializer<'de>>(d: D) -> Result<uuid::Uuid, D::Error> {
+    let uuid = String::deserialize(d)?;
+    uuid::Uuid::parse_str(&uuid).map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MachineType {
+    /// The contents of this field are assumed to be applicable to any machine type.
+    Unknown,
+    /// Matsushita AM33
+    Am33,
+    /// x64
+    Amd64,
+    /// ARM little endian
+    Arm,
+    /// ARM64 little endian
+    Arm64,
+    /// ARM64 code following the x64 ABI, which can be mixed with x64 code
+    Arm64EC,
+    /// A hybrid image holding both ARM64 and ARM64EC code, which loads as
+    /// either an ARM64 or an ARM64EC image
+    Arm64X,
+    /// ARM64 code following the x86 ABI, which can be mixed with x86 code
+    /// (CHPE x86)
+    ChpeX86,
+    /// ARM Thumb-2 little endian
+    ArmNT,
+    /// EFI byte code
+    Ebc,
+    /// Intel 386 or later processors and compatible processors
+    X86,
+    /// Intel Itanium processor family
+    Ia64,
+    /// Mitsubishi M32R little endian
+    M32R,
+    /// MIPS16
+    Mips16,
+    /// MIPS with FPU
+    MipsFpu,
+    /// MIPS16 with FPU
+    MipsFpu16,
+    /// Power PC little endian
+    PowerPC,
+    /// Power PC with floating point support
+    PowerPCFP,
+    /// MIPS little endian
+    R4000,
+    /// RISC-V 32-bit address space
+    RiscV32,
+    /// RISC-V 64-bit address space
+    RiscV64,
+    /// RISC-V 128-bit address space
+    RiscV128,
+    /// Hitachi SH3
+    SH3,
+    /// Hitachi SH3 DSP
+    SH3DSP,
+    /// Hitachi SH4
+    SH4,
+    /// Hitachi SH5
+    SH5,
+    /// Thumb
+    Thumb,
+    /// MIPS little-endian WCE v2
+    WceMipsV2,
+    /// Invalid value
+    Invalid,
+    /// A machine type this version doesn't know, by its `IMAGE_FILE_MACHINE_*` value
+    Other(u16),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Version {
+    V41,
+    V50,
+    V60,
+    V70,
+    V110,
+    Other(u32),
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AssemblyInfo {
+    pub build_info: Option<BuildInfo>,
+    pub compiler_info: Option<CompilerInfo>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BuildInfo {
+    pub arguments: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompilerInfo {
+    // TODO: cpu_type, flags, language
+    pub language: String,
+    pub flags: CompileFlags,
+    pub cpu_type: String,
+    pub frontend_version: CompilerVersion,
+    pub backend_version: CompilerVersion,
+    pub version_string: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompileFlags {
+    /// Compiled for edit and continue.
+    pub edit_and_continue: bool,
+    /// Compiled without debugging info.
+    pub no_debug_info: bool,
+    /// Compiled with `LTCG`.
+    pub link_time_codegen: bool,
+    /// Compiled with `/bzalign`.
+    pub no_data_align: bool,
+    /// Managed code or data is present.
+    pub managed: bool,
+    /// Compiled with `/GS`.
+    pub security_checks: bool,
+    /// Compiled with `/hotpatch`.
+    pub hot_patch: bool,
+    /// Compiled with `CvtCIL`.
+    pub cvtcil: bool,
+    /// This is a MSIL .NET Module.
+    pub msil_module: bool,
+    /// Compiled with `/sdl`.
+    pub sdl: bool,
+    /// Compiled with `/ltcg:pgo` or `pgo:`.
+    pub pgo: bool,
+    /// This is a .exp module.
+    pub exp_module: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompilerVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub build: u16,
+    pub qfe: Option<u16>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DebugModule {
+    pub name: String,
+    pub object_file_name: String,
+    /// The static library the module's object file was linked from, if any
+    pub library: Option<String>,
+    pub source_files: Option<Vec<FileInfo>>,
+    /// The number of symbols in the module's symbol stream. Only counted when
+    /// the whole PDB is parsed.
+    pub symbol_count: usize,
+    /// The number of user-defined type symbols (`S_UDT`) in the module's
+    /// symbol stream, e.g. typedefs and types local to a function. Only counted
+    /// when the whole PDB is parsed.
+    pub type_count: usize,
+    /// The total size of the module's section contributions, in bytes. Only
+    /// computed when the whole PDB is parsed.
+    pub contribution_size: usize,
+    /// The compiler and flags the module was built with (`S_COMPILE3`). Only
+    /// read when the whole PDB is parsed.
+    pub compiler_info: Option<CompilerInfo>,
+    /// The command line the module was built with. Only read when the whole
+    /// PDB is parsed.
+    pub build_info: Option<BuildInfo>,
+    /// The entries of the module's environment block (`S_ENVBLOCK`), which the
+    /// linker's module has, e.g. `cwd` and `cmd`. Only read when the whole PDB
+    /// is parsed.
+    pub environment: Vec<EnvironmentEntry>,
+}
+
+/// An entry of a module's environment block
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnvironmentEntry {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Checksum {
+    None,
+    Md5(Vec<u8>),
+    Sha1(Vec<u8>),
+    Sha256(Vec<u8>),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileInfo {
+    pub name: String,
+    pub checksum: Checksum,
+}
+
+/// A location in the image. The RVA is always kept so that addresses can be
+/// reused with images loaded at a different base.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Address {
+    /// Offset from the start of the image
+    pub rva: usize,
+    /// `rva` rebased onto the base address given when parsing, if there was one
+    pub virtual_address: Option<usize>,
+}
+
+impl Address {
+    pub fn new(rva: usize, base_address: Option<usize>) -> Self {
+        Address {
+            rva,
+            virtual_address: base_address.map(|base_address| base_address + rva),
+        }
+    }
+
+    /// Returns the rebased address if there is one, otherwise the RVA
+    pub fn value(&self) -> usize {
+        self.virtual_address.unwrap_or(self.rva)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PublicSymbol {
+    pub name: String,
+    pub is_code: bool,
+    pub is_function: bool,
+    pub is_managed: bool,
+    pub is_msil: bool,
+    pub offset: Option<Address>,
+    /// Name of the [Procedure] at the same address as this symbol, if any
+    pub procedure_name: Option<String>,
+    /// The instruction set of the symbol's code, if it could be told from its
+    /// name or the [Procedure] at the same address
+    pub isa: Option<Isa>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Data {
+    pub name: String,
+
+    pub is_global: bool,
+
+    pub is_managed: bool,
+
+    pub ty: TypeId,
+
+    pub offset: Option<Address>,
+
+    /// Name of the module whose symbol stream this was found in. Only recorded
+    /// for local (file- or function-static) data.
+    pub module: Option<String>,
+
+    /// Name of the procedure this is a static variable of, if any
+    pub function: Option<String>,
+
+    /// Names of the other procedures and data whose address ranges overlap this
+    /// variable's, e.g. because identical constants were folded together
+    pub folded_with: Vec<String>,
+
+    /// Whether the variable is in read-only, writable, or zero-initialized
+    /// data, if its section is known
+    pub storage: Option<Storage>,
+}
+
+/// Strings attached to an address with the `__annotation` intrinsic. Windows
+/// uses these to record e.g. hot-patching metadata.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Annotation {
+    pub offset: Option<Address>,
+
+    pub strings: Vec<String>,
+
+    /// Index of the [DebugModule] whose symbol stream this was found in
+    pub module_index: usize,
+
+    /// Offset of this symbol within its module's symbol stream
+    pub symbol_index: u32,
+}
+
+/// Points to the [Annotation] with the matching `module_index` and `symbol_index`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnnotationReference {
+    pub name: String,
+
+    /// Index of the [DebugModule] containing the annotation
+    pub module_index: Option<usize>,
+
+    pub symbol_index: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TrampolineKind {
+    /// A jump through which calls are routed so that the target can be moved
+    /// by an incremental link
+    Incremental,
+    /// A jump to a target that's too far away to be reached by a direct branch
+    BranchIsland,
+    Unknown,
+}
+
+/// An indirect call and the signature of the function being called
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CallSite {
+    /// Address of the call instruction
+    pub offset: Option<Address>,
+
+    /// Signature of the called function, if its type could be resolved
+    pub ty: Option<TypeId>,
+
+    /// Name of the procedure which makes the call
+    pub caller: Option<String>,
+}
+
+/// A call from `caller` to `callee`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CallGraphEdge {
+    pub caller: String,
+
+    pub callee: String,
+
+    /// Number of times the call was made according to profile data, if recorded
+    pub invocations: Option<u32>,
+}
+
+/// Code which runs before `main` (or `DriverEntry`) or when the image exits
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StaticInitializer {
+    pub kind: StaticInitializerKind,
+
+    /// Name of the procedure, or of the table entry for [StaticInitializerKind::CrtTableEntry]
+    pub name: String,
+
+    pub offset: Option<Address>,
+
+    /// The global being constructed or destroyed, if known
+    pub variable: Option<String>,
+
+    /// The COFF group (e.g. `.CRT$XCU`) a table entry is in
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StaticInitializerKind {
+    /// A compiler-generated function which constructs a global
+    DynamicInitializer,
+    /// A compiler-generated function registered with `atexit` which destroys a global
+    AtexitDestructor,
+    /// A function pointer in one of the CRT's initializer or terminator tables,
+    /// which are the `.CRT$XC*`, `.CRT$XI*`, `.CRT$XP*`, and `.CRT$XT*` COFF groups
+    CrtTableEntry,
+}
+
+/// A symbol which locates the metadata or runtime support of a security
+/// mitigation, e.g. Control Flow Guard's `__guard_fids_table`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SecuritySymbol {
+    pub name: String,
+
+    pub offset: Option<Address>,
+
+    pub feature: SecurityFeature,
+}
+
+/// The security mitigation a [SecuritySymbol] belongs to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SecurityFeature {
+    /// The load configuration directory itself, `_load_config_used`
+    LoadConfig,
+    /// Control Flow Guard's function tables and check function pointers
+    ControlFlowGuard,
+    /// eXtended Flow Guard, which also checks the signature of call targets
+    ExtendedFlowGuard,
+    /// The valid targets of exception handling continuations, used with CET
+    /// shadow stacks
+    EhContinuation,
+    /// The stack cookie and its checks (`/GS`)
+    StackCookie,
+    /// The table of safe exception handlers of x86 images (`/SAFESEH`)
+    SafeSeh,
+    /// CastGuard's checks of casts between classes
+    CastGuard,
+    /// Retpoline thunks which mitigate branch target injection
+    Retpoline,
+}
+
+impl std::fmt::Display for SecurityFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecurityFeature::LoadConfig => write!(f, "load-config"),
+            SecurityFeature::ControlFlowGuard => write!(f, "control-flow-guard"),
+            SecurityFeature::ExtendedFlowGuard => write!(f, "extended-flow-guard"),
+            SecurityFeature::EhContinuation => write!(f, "eh-continuation"),
+            SecurityFeature::StackCookie => write!(f, "stack-cookie"),
+            SecurityFeature::SafeSeh => write!(f, "safe-seh"),
+            SecurityFeature::CastGuard => write!(f, "cast-guard"),
+            SecurityFeature::Retpoline => write!(f, "retpoline"),
+        }
+    }
+}
+
+/// A string which reveals where or by whom the image was built, e.g. the
+/// absolute path of an object file under a user's home directory
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProvenanceString {
+    pub kind: ProvenanceKind,
+
+    pub value: String,
+
+    /// Number of modules which record the string
+    pub modules: usize,
+}
+
+/// What a [ProvenanceString] is
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ProvenanceKind {
+    /// Path of the PDB the compiler or linker wrote
+    PdbPath,
+    /// Absolute path of an object file or static library
+    ObjectPath,
+    /// The working directory of the compiler or linker
+    WorkingDirectory,
+    /// Path of the compiler or linker
+    BuildTool,
+    /// The arguments the compiler or linker was run with
+    CommandLine,
+    /// A user name in one of the paths, e.g. `alice` in `C:\Users\alice`
+    UserName,
+    /// A machine name in one of the paths, e.g. `build01` in `\\build01\share`
+    HostName,
+}
+
+impl std::fmt::Display for ProvenanceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProvenanceKind::PdbPath => write!(f, "pdb-path"),
+            ProvenanceKind::ObjectPath => write!(f, "object-path"),
+            ProvenanceKind::WorkingDirectory => write!(f, "working-directory"),
+            ProvenanceKind::BuildTool => write!(f, "build-tool"),
+            ProvenanceKind::CommandLine => write!(f, "command-line"),
+            ProvenanceKind::UserName => write!(f, "user-name"),
+            ProvenanceKind::HostName => write!(f, "host-name"),
+        }
+    }
+}
+
+/// A symbol of a `/DEBUG:FASTLINK` PDB whose debug information was left in the
+/// object file it was compiled into
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FastlinkReference {
+    pub name: String,
+
+    pub kind: FastlinkReferenceKind,
+
+    /// Index of the [DebugModule] for the object file holding the debug information
+    pub module_index: Option<usize>,
+
+    /// Whether the symbol is only visible within its module, e.g. a `static` function
+    pub is_local: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FastlinkReferenceKind {
+    Function,
+    Data,
+    /// A user-defined type
+    Type,
+    Label,
+    Constant,
+}
+
+/// A managed (.NET) method compiled into a mixed-mode image, which the runtime
+/// identifies by its metadata token rather than its name
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ManagedProcedure {
+    pub name: String,
+    /// The method's metadata token, e.g. `0x06000001`
+    pub token: u32,
+    pub address: Option<Address>,
+    pub len: usize,
+    pub is_global: bool,
+    /// Index of the [DebugModule] whose symbol stream the method was found in
+    pub module_index: Option<usize>,
+}
+
+/// A reference from the global symbol stream to a [ManagedProcedure] in a
+/// module's symbol stream
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TokenReference {
+    pub name: String,
+    /// Index of the [DebugModule] holding the managed procedure
+    pub module_index: Option<usize>,
+    /// Offset of the managed procedure within the module's symbol stream
+    pub symbol_offset: u32,
+}
+
+/// A section of the image, e.g. `.text`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Section {
+    pub name: String,
+
+    /// 1-based index of the section in the image's section headers
+    pub index: u16,
+
+    pub offset: Address,
+
+    pub len: usize,
+
+    /// Alignment of the section in bytes
+    pub alignment: usize,
+
+    /// The `IMAGE_SCN_*` flags of the section
+    pub characteristics: u32,
+}
+
+/// What an ARM64EC or ARM64X image records about how its code mixes
+/// instruction sets. The code map itself is in the image rather than the PDB,
+/// but the PDB names the metadata the loader reads it from.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HybridInfo {
+    /// Symbols of the CHPE metadata, e.g. `__chpe_metadata`, the
+    /// `__hybrid_code_map` of ARM64EC and x64 code ranges, and the
+    /// `__os_arm64x_*` dispatch pointers the loader fills in
+    pub metadata: Vec<HybridSymbol>,
+    /// Names of the sections and COFF groups only hybrid images have, e.g.
+    /// `.hexpthk` for hybrid export thunks
+    pub sections: Vec<String>,
+}
+
+/// A symbol of the CHPE metadata of an ARM64EC or ARM64X image
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HybridSymbol {
+    pub name: String,
+    pub address: Option<Address>,
+}
+
+/// A group of COFF sections which the linker merged into one of the image's
+/// sections, e.g. `.text$mn` or `.CRT$XCU`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CoffGroup {
+    pub name: String,
+
+    pub offset: Option<Address>,
+
+    pub len: usize,
+
+    /// The `IMAGE_SCN_*` flags of the group
+    pub characteristics: u32,
+}
+
+/// A call to a heap allocation function (e.g. `operator new`) and the type it allocates
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HeapAllocationSite {
+    /// Address of the call instruction
+    pub offset: Option<Address>,
+
+    /// Length of the call instruction in bytes
+    pub call_len: usize,
+
+    /// The type being allocated
+    pub ty: TypeId,
+}
+
+/// A symbol the image exports, from an `S_EXPORT` record. The record doesn't
+/// say where the symbol is, which the image's export table does.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Export {
+    /// Name the symbol is exported by. Empty if it's only exported by ordinal.
+    pub name: String,
+
+    pub ordinal: u16,
+
+    /// Whether the export is data rather than code
+    pub is_data: bool,
+
+    pub is_constant: bool,
+
+    /// Whether the export is left out of the import library (`PRIVATE`)
+    pub is_private: bool,
+
+    /// Whether the export is only exported by ordinal (`NONAME`)
+    pub is_ordinal_only: bool,
+
+    /// Whether the export forwards to a symbol of another image
+    pub is_forwarder: bool,
+}
+
+/// A linker-generated thunk which jumps to `target`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trampoline {
+    pub kind: TrampolineKind,
+
+    pub offset: Option<Address>,
+
+    /// Size of the thunk's code in bytes
+    pub size: usize,
+
+    pub target: Option<Address>,
+}
+
+/// A block of code which the compiler moved out of its parent procedure,
+/// e.g. rarely executed code placed in a separate section by PGO
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SeparatedCode {
+    pub offset: Option<Address>,
+
+    pub len: usize,
+
+    /// Address of the procedure this code was separated from
+    pub parent_offset: Option<Address>,
+
+    /// Whether this block is also a lexical scope
+    pub is_lexical_scope: bool,
+
+    /// Whether this block returns to its parent rather than its parent's caller
+    pub returns_to_parent: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Procedure {
+    pub name: String,
+
+    pub signature: Option<String>,
+    pub type_index: TypeIndexNumber,
+
+    /// This reflects the RVA in the transformed address space. See [PdbInternalSectionOffset docs](https://docs.rs/pdb/latest/pdb/struct.PdbInternalSectionOffset.html)
+    /// for more details.
+    pub address: Option<Address>,
+    pub len: usize,
+
+    pub is_global: bool,
+    pub is_dpc: bool,
+    /// length of this procedure in BYTES
+    pub prologue_end: usize,
+    pub epilogue_start: usize,
+    /// Name of the [PublicSymbol] at the same address as this procedure, if any.
+    /// This is usually the decorated (mangled) form of `name`.
+    pub public_name: Option<String>,
+    /// Names of the procedure's parameters in order, including `this` for
+    /// member functions. Only available if the PDB has private symbols.
+    pub parameter_names: Vec<String>,
+    /// Index of the module in [ParsedPdb::debug_modules] whose symbol stream
+    /// the procedure was found in
+    pub module_index: Option<usize>,
+    /// Who most likely wrote the procedure, as guessed from its name and module
+    pub origin: CodeOrigin,
+    pub flags: ProcedureFlags,
+    /// The source file and lines which define the procedure, from its module's
+    /// line information
+    pub source: Option<SourceRange>,
+    /// Names of the other procedures and data whose address ranges overlap this
+    /// procedure's, e.g. because identical COMDAT folding (`/OPT:ICF`) merged
+    /// functions with the same code
+    pub folded_with: Vec<String>,
+    /// The instruction set of the procedure's code, if it could be told from
+    /// its module's compiler information or its name
+    pub isa: Option<Isa>,
+}
+
+/// A range of lines within a source file
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceRange {
+    /// Path of the file as recorded in the PDB
+    pub file: String,
+    pub first_line: u32,
+    pub last_line: u32,
+}
+
+/// Attributes of a procedure from its symbol and, if the PDB has private
+/// symbols, its frame information
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProcedureFlags {
+    /// The procedure never returns, e.g. it was declared `__declspec(noreturn)`
+    pub no_return: bool,
+    /// The procedure is never called
+    pub unreachable: bool,
+    pub no_inline: bool,
+    pub custom_calling_convention: bool,
+    /// The procedure keeps a frame pointer
+    pub frame_pointer: bool,
+    pub interrupt_return: bool,
+    pub far_return: bool,
+    /// Debug information for optimized code is present
+    pub optimized_debug_info: bool,
+    /// The procedure has C++ exception handling. Only set from frame information.
+    pub has_eh: bool,
+    /// The procedure has structured exception handling. Only set from frame information.
+    pub has_seh: bool,
+    /// The procedure was declared `__declspec(naked)`. Only set from frame information.
+    pub naked: bool,
+}
+
+/// Who wrote a piece of code
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum CodeOrigin {
+    /// The project the PDB was built for
+    User,
+    /// The C runtime, the compiler's support libraries, or the OS's import libraries
+    Runtime,
+    /// The C++ standard library
+    Stl,
+    /// Code the compiler or linker generated, e.g. deleting destructors and thunks
+    CompilerGenerated,
+}
+
+/// The instruction set code was compiled for
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Isa {
+    X86,
+    X64,
+    /// 32-bit ARM, including Thumb-2
+    Arm,
+    Arm64,
+    /// ARM64 code following the x64 ABI, found in ARM64EC and ARM64X images
+    #[cfg_attr(feature = "serde", serde(rename = "arm64ec"))]
+    Arm64EC,
+}
+
+impl std::fmt::Display for Isa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Isa::X86 => write!(f, "x86"),
+            Isa::X64 => write!(f, "x64"),
+            Isa::Arm => write!(f, "arm"),
+            Isa::Arm64 => write!(f, "arm64"),
+            Isa::Arm64EC => write!(f, "arm64ec"),
+        }
+    }
+}
+
+/// The kind of section a variable is stored in, as told by the section's
+/// characteristics
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Storage {
+    /// Constant data, e.g. in `.rdata`
+    ReadOnly,
+    /// Initialized writable data, e.g. in `.data`
+    Writable,
+    /// Writable data which the loader fills with zeroes, e.g. in `.bss`
+    ZeroInitialized,
+}
+
+impl std::fmt::Display for Storage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Storage::ReadOnly => write!(f, "read-only"),
+            Storage::Writable => write!(f, "writable"),
+            Storage::ZeroInitialized => write!(f, "zero-initialized"),
+        }
+    }
+}
+
+impl std::fmt::Display for CodeOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeOrigin::User => write!(f, "user"),
+            CodeOrigin::Runtime => write!(f, "runtime"),
+            CodeOrigin::Stl => write!(f, "stl"),
+            CodeOrigin::CompilerGenerated => write!(f, "compiler-generated"),
+        }
+    }
+}
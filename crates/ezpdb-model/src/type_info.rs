@@ -0,0 +1,1356 @@
+use crate::intern::Name;
+use crate::symbol_types::{ParsedPdb, TypeIndexNumber};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+use tracing::warn;
+
+/// Identifies a [Type] stored in a [TypeArena]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct TypeId(pub usize);
+
+/// Owns every [Type] parsed from a PDB. Types reference each other by [TypeId]
+/// rather than by pointer.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+pub struct TypeArena {
+    types: Vec<Type>,
+    /// The TPI type index each type was parsed from. Types which are embedded
+    /// in another TPI record (e.g. the members of a field list) have none.
+    type_indices: Vec<Option<TypeIndexNumber>>,
+}
+
+impl TypeArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `typ` to the arena, returning its newly-assigned ID
+    pub fn insert(&mut self, typ: Type) -> TypeId {
+        self.types.push(typ);
+        self.type_indices.push(None);
+        TypeId(self.types.len() - 1)
+    }
+
+    pub fn get(&self, id: TypeId) -> Option<&Type> {
+        self.types.get(id.0)
+    }
+
+    pub fn get_mut(&mut self, id: TypeId) -> Option<&mut Type> {
+        self.types.get_mut(id.0)
+    }
+
+    /// Returns the TPI type index the type with `id` was parsed from
+    pub fn type_index(&self, id: TypeId) -> Option<TypeIndexNumber> {
+        self.type_indices.get(id.0).copied().flatten()
+    }
+
+    /// Records that the type with `id` was parsed from the TPI record at `index`
+    pub fn set_type_index(&mut self, id: TypeId, index: TypeIndexNumber) {
+        self.type_indices[id.0] = Some(index);
+    }
+
+    /// Returns an iterator over all types and their IDs in the order they were inserted
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, &Type)> {
+        self.types
+            .iter()
+            .enumerate()
+            .map(|(id, typ)| (TypeId(id), typ))
+    }
+
+    /// Returns an iterator over the IDs of all types in the arena
+    pub fn ids(&self) -> impl Iterator<Item = TypeId> {
+        (0..self.types.len()).map(TypeId)
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+/// Serializes as a sequence of types, each with an additional `type_index` key
+/// if it was parsed from its own TPI record
+#[cfg(feature = "serde")]
+impl Serialize for TypeArena {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        #[derive(Serialize)]
+        struct Entry<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            type_index: Option<TypeIndexNumber>,
+            #[serde(flatten)]
+            typ: &'a Type,
+        }
+
+        let mut seq = serializer.serialize_seq(Some(self.types.len()))?;
+        for (typ, type_index) in self.types.iter().zip(&self.type_indices) {
+            seq.serialize_element(&Entry {
+                type_index: *type_index,
+                typ,
+            })?;
+        }
+
+        seq.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TypeArena {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Entry {
+            #[serde(default)]
+            type_index: Option<TypeIndexNumber>,
+            #[serde(flatten)]
+            typ: Type,
+        }
+
+        let entries = Vec::<Entry>::deserialize(deserializer)?;
+        let mut arena = TypeArena::new();
+        for entry in entries {
+            arena.types.push(entry.typ);
+            arena.type_indices.push(entry.type_index);
+        }
+
+        Ok(arena)
+    }
+}
+
+impl Index<TypeId> for TypeArena {
+    type Output = Type;
+
+    fn index(&self, id: TypeId) -> &Self::Output {
+        &self.types[id.0]
+    }
+}
+
+impl IndexMut<TypeId> for TypeArena {
+    fn index_mut(&mut self, id: TypeId) -> &mut Self::Output {
+        &mut self.types[id.0]
+    }
+}
+
+pub trait Typed {
+    /// Returns the size (in bytes) of this type
+    fn type_size(&self, pdb: &ParsedPdb) -> usize;
+
+    /// Called after all types have been parsed
+    fn on_complete(&mut self, _pdb: &ParsedPdb) {}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Type {
+    Class(Class),
+    VirtualBaseClass(VirtualBaseClass),
+    Union(Union),
+    Bitfield(Bitfield),
+    Enumeration(Enumeration),
+    EnumVariant(EnumVariant),
+    Pointer(Pointer),
+    Primitive(Primitive),
+    Array(Array),
+    FieldList(FieldList),
+    ArgumentList(ArgumentList),
+    Modifier(Modifier),
+    Member(Member),
+    Procedure(Procedure),
+    MemberFunction(MemberFunction),
+    MethodList(MethodList),
+    MethodListEntry(MethodListEntry),
+    Nested(Nested),
+    OverloadedMethod(OverloadedMethod),
+    Method(Method),
+    StaticMember(StaticMember),
+    BaseClass(BaseClass),
+    VTable(VTable),
+    /// A record the `pdb` crate can't parse, decoded as far as it could be
+    Partial(Partial),
+}
+
+impl Typed for Type {
+    fn type_size(&self, pdb: &ParsedPdb) -> usize {
+        match self {
+            Type::Class(class) => class.type_size(pdb),
+            Type::Union(union) => union.type_size(pdb),
+            Type::Bitfield(bitfield) => pdb.types[bitfield.underlying_type].type_size(pdb),
+            Type::Enumeration(e) => pdb.types[e.underlying_type].type_size(pdb),
+            // Pointers to members are larger than plain pointers when the class
+            // has multiple or virtual bases, which only the record's size reflects
+            Type::Pointer(p) if p.attributes.size != 0 => p.attributes.size,
+            Type::Pointer(p) => p.attributes.kind.type_size(pdb),
+            Type::Primitive(p) => p.type_size(pdb),
+            Type::Array(a) => a.type_size(pdb),
+            Type::FieldList(fields) => fields
+                .0
+                .iter()
+                .fold(0, |acc, field| acc + pdb.types[*field].type_size(pdb)),
+            Type::EnumVariant(_) => panic!("type_size() invoked for EnumVariant"),
+            Type::Modifier(modifier) => pdb.types[modifier.underlying_type].type_size(pdb),
+            Type::Member(_) => panic!("type_size() invoked for Member"),
+            Type::ArgumentList(_) => panic!("type_size() invoked for ArgumentList"),
+            Type::Procedure(_) => panic!("type_size() invoked for Procedure"),
+            Type::MemberFunction(_) => panic!("type_size() invoked for MemberFunction"),
+            Type::MethodList(_) => panic!("type_size() invoked for MethodList"),
+            Type::MethodListEntry(_) => panic!("type_size() invoked for MethodListEntry"),
+            Type::VirtualBaseClass(_) => panic!("type_size() invoked for VirtualBaseClass"),
+            Type::Nested(_) => panic!("type_size() invoked for Nested"),
+            Type::OverloadedMethod(_) => panic!("type_size() invoked for overloaded method"),
+            Type::Method(_) => panic!("type_size() invoked for overloaded method"),
+            Type::StaticMember(_) => panic!("type_size() invoked for StaticMember"),
+            Type::VTable(_) => panic!("type_size() invoked for VTable"),
+            Type::BaseClass(_) => panic!("type_size() invoked for BaseClass"),
+            Type::Partial(partial) => partial
+                .referenced_type
+                .map_or(0, |referenced| pdb.types[referenced].type_size(pdb)),
+        }
+    }
+
+    fn on_complete(&mut self, pdb: &ParsedPdb) {
+        match self {
+            Type::Class(class) => class.on_complete(pdb),
+            Type::Union(union) => union.on_complete(pdb),
+            Type::Array(a) => a.on_complete(pdb),
+            _ => {}
+        }
+    }
+}
+
+impl Type {
+    /// Returns the size of a variable of this type, like [Typed::type_size]
+    /// does but without panicking. `None` for types no variable can have, such
+    /// as procedures and members, and for based pointers.
+    pub fn data_size(&self, pdb: &ParsedPdb) -> Option<usize> {
+        let size_of = |id| pdb.types.get(id)?.data_size(pdb);
+        match self {
+            Type::Class(_) | Type::Union(_) | Type::Primitive(_) | Type::Array(_) => {
+                Some(self.type_size(pdb))
+            }
+            Type::Pointer(p) if p.attributes.size != 0 => Some(p.attributes.size),
+            Type::Pointer(p) => p.attributes.kind.size(),
+            Type::Bitfield(bitfield) => size_of(bitfield.underlying_type),
+            Type::Enumeration(e) => size_of(e.underlying_type),
+            Type::Modifier(modifier) => size_of(modifier.underlying_type),
+            Type::Partial(partial) => partial.referenced_type.and_then(size_of),
+            _ => None,
+        }
+    }
+
+    /// Returns the types this type refers to directly, e.g. a class's fields or a
+    /// pointer's pointee. Referenced types are not followed any further.
+    pub fn referenced_types(&self) -> Vec<TypeId> {
+        match self {
+            Type::Class(class) => class
+                .derived_from
+                .iter()
+                .chain(&class.fields)
+                .copied()
+                .collect(),
+            Type::VirtualBaseClass(base) => vec![base.base_class, base.base_pointer],
+            Type::Union(union) => union.fields.clone(),
+            Type::Bitfield(bitfield) => vec![bitfield.underlying_type],
+            Type::Enumeration(e) => vec![e.underlying_type],
+            Type::Pointer(pointer) => pointer
+                .underlying_type
+                .into_iter()
+                .chain(
+                    pointer
+                        .member
+                        .as_ref()
+                        .and_then(|member| member.containing_class),
+                )
+                .collect(),
+            Type::Array(array) => vec![array.element_type, array.indexing_type],
+            Type::FieldList(fields) => fields.0.clone(),
+            Type::ArgumentList(arguments) => arguments.0.clone(),
+            Type::Modifier(modifier) => vec![modifier.underlying_type],
+            Type::Member(member) => vec![member.underlying_type],
+            Type::Procedure(procedure) => procedure
+                .return_type
+                .into_iter()
+                .chain(procedure.argument_list.iter().copied())
+                .collect(),
+            Type::MemberFunction(member) => [member.return_type, member.class_type]
+                .iter()
+                .copied()
+                .chain(member.this_pointer_type)
+                .chain(member.argument_list.iter().copied())
+                .collect(),
+            Type::MethodList(methods) => methods.0.iter().map(|entry| entry.method_type).collect(),
+            Type::MethodListEntry(entry) => vec![entry.method_type],
+            Type::Nested(nested) => vec![nested.nested_type],
+            Type::OverloadedMethod(overloaded) => vec![overloaded.method_list],
+            Type::Method(method) => vec![method.method_type],
+            Type::StaticMember(member) => vec![member.field_type],
+            Type::BaseClass(base) => vec![base.base_class],
+            Type::VTable(vtable) => vec![vtable.0],
+            Type::Partial(partial) => partial.referenced_type.into_iter().collect(),
+            Type::EnumVariant(_) | Type::Primitive(_) => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypeProperties {
+    pub packed: bool,
+    pub constructors: bool,
+    pub overlapped_operators: bool,
+    pub is_nested_type: bool,
+    pub contains_nested_types: bool,
+    pub overload_assignment: bool,
+    pub overload_coasting: bool,
+    pub forward_reference: bool,
+    pub scoped_definition: bool,
+    pub has_unique_name: bool,
+    pub sealed: bool,
+    pub hfa: u8,
+    pub intristic_type: bool,
+    pub mocom: u8,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Class {
+    pub name: Name,
+    pub unique_name: Option<Name>,
+    pub kind: ClassKind,
+    pub properties: TypeProperties,
+    pub derived_from: Option<TypeId>,
+    pub fields: Vec<TypeId>,
+    pub size: usize,
+    /// Layout of the class's vtable, if it has one
+    pub vtable_shape: Option<VTableShape>,
+    /// The `#pragma pack` value the class was compiled with as inferred from the
+    /// offsets of its members. `None` if its layout is naturally aligned.
+    pub packing: Option<usize>,
+}
+
+impl Typed for Class {
+    fn type_size(&self, pdb: &ParsedPdb) -> usize {
+        if self.properties.forward_reference {
+            // Find the implementation
+            for (_id, value) in pdb.types.iter() {
+                if let Type::Class(class) = value {
+                    if !class.properties.forward_reference && class.unique_name == self.unique_name
+                    {
+                        return class.type_size(pdb);
+                    }
+                }
+            }
+
+            warn!("could not get forward reference for {}", self.name);
+        }
+
+        self.size
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BaseClass {
+    pub kind: ClassKind,
+    pub base_class: TypeId,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VirtualBaseClass {
+    pub direct: bool,
+    pub base_class: TypeId,
+    pub base_pointer: TypeId,
+    pub base_pointer_offset: usize,
+    pub virtual_base_offset: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "extra-traits", derive(Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClassKind {
+    Class,
+    Struct,
+    Interface,
+}
+
+impl std::fmt::Display for ClassKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClassKind::Class => write!(f, "Class"),
+            ClassKind::Struct => write!(f, "Struct"),
+            ClassKind::Interface => write!(f, "Interface"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Union {
+    pub name: Name,
+    pub unique_name: Option<Name>,
+    pub properties: TypeProperties,
+    pub size: usize,
+    pub count: usize,
+    pub fields: Vec<TypeId>,
+    /// The `#pragma pack` value the union was compiled with as inferred from the
+    /// offsets of its members. `None` if its layout is naturally aligned.
+    pub packing: Option<usize>,
+}
+
+impl Typed for Union {
+    fn type_size(&self, pdb: &ParsedPdb) -> usize {
+        if self.properties.forward_reference {
+            // Find the implementation
+            for (_id, value) in pdb.types.iter() {
+                if let Type::Union(union) = value {
+                    if !union.properties.forward_reference && union.unique_name == self.unique_name
+                    {
+                        return union.type_size(pdb);
+                    }
+                }
+            }
+
+            warn!("could not get forward reference for {}", self.name);
+        }
+
+        self.size
+    }
+}
+
+/// Infers the packing of classes and unions from the offsets and natural
+/// alignments of their members. Alignments are cached, so a single instance
+/// should be used for all of a PDB's types.
+pub struct PackingInference<'a> {
+    pdb: &'a ParsedPdb,
+    /// Maps unique names to the class or union defining them, for resolving
+    /// forward references
+    definitions: HashMap<&'a str, TypeId>,
+    alignments: HashMap<TypeId, usize>,
+}
+
+impl<'a> PackingInference<'a> {
+    pub fn new(pdb: &'a ParsedPdb) -> Self {
+        let definitions = pdb
+            .types
+            .iter()
+            .filter_map(|(id, ty)| match ty {
+                Type::Class(class) if !class.properties.forward_reference => {
+                    class.unique_name.as_deref().map(|name| (name, id))
+                }
+                Type::Union(union) if !union.properties.forward_reference => {
+                    union.unique_name.as_deref().map(|name| (name, id))
+                }
+                _ => None,
+            })
+            .collect();
+
+        PackingInference {
+            pdb,
+            definitions,
+            alignments: HashMap::new(),
+        }
+    }
+
+    /// Returns the packing of the class or union `id`, or `None` if its layout
+    /// is consistent with natural alignment
+    pub fn packing(&mut self, id: TypeId) -> Option<usize> {
+        self.layout(id).1
+    }
+
+    /// Returns the alignment `id` has when it's a member of another type
+    pub fn alignment(&mut self, id: TypeId) -> usize {
+        if let Some(alignment) = self.alignments.get(&id) {
+            return *alignment;
+        }
+
+        let pdb = self.pdb;
+        let alignment = match &pdb.types[id] {
+            Type::Class(Class {
+                properties,
+                unique_name,
+                ..
+            })
+            | Type::Union(Union {
+                properties,
+                unique_name,
+                ..
+            }) => {
+                let definition = if properties.forward_reference {
+                    unique_name
+                        .as_deref()
+                        .and_then(|name| self.definitions.get(name))
+                        .copied()
+                } else {
+                    Some(id)
+                };
+
+                match definition {
+                    Some(definition) => match self.layout(definition) {
+                        (natural, Some(packing)) => natural.min(packing),
+                        (natural, None) => natural,
+                    },
+                    None => 1,
+                }
+            }
+            Type::Bitfield(bitfield) => self.alignment(bitfield.underlying_type),
+            Type::Enumeration(e) => self.alignment(e.underlying_type),
+            Type::Modifier(modifier) => self.alignment(modifier.underlying_type),
+            Type::Array(array) => self.alignment(array.element_type),
+            Type::Pointer(pointer) => lowest_power_of_two(pointer.attributes.size),
+            Type::Primitive(primitive) => lowest_power_of_two(primitive.size()),
+            _ => 1,
+        };
+
+        self.alignments.insert(id, alignment);
+        alignment
+    }
+
+    /// Returns the natural alignment of the class or union `id` and its
+    /// inferred packing
+    fn layout(&mut self, id: TypeId) -> (usize, Option<usize>) {
+        let pdb = self.pdb;
+        let (fields, size) = match &pdb.types[id] {
+            Type::Class(class) => (&class.fields, class.size),
+            Type::Union(union) => (&union.fields, union.size),
+            _ => return (1, None),
+        };
+
+        let mut natural = 1;
+        let mut packing = usize::MAX;
+        for field in fields {
+            let (offset, ty) = match &pdb.types[*field] {
+                Type::Member(member) => (member.offset, member.underlying_type),
+                Type::BaseClass(base) => (base.offset, base.base_class),
+                _ => continue,
+            };
+
+            let alignment = self.alignment(ty);
+            natural = natural.max(alignment);
+            if offset % alignment != 0 {
+                // A member can only be misaligned by packing no larger than the
+                // alignment of its offset
+                packing = packing.min(lowest_power_of_two(offset));
+            }
+        }
+
+        // Types are padded out to a multiple of their alignment
+        if size != 0 && size % natural.min(packing) != 0 {
+            packing = packing.min(lowest_power_of_two(size));
+        }
+
+        (natural, Some(packing).filter(|packing| *packing < natural))
+    }
+}
+
+/// Returns the largest power of two which divides `n`, or 1 if `n` is zero
+fn lowest_power_of_two(n: usize) -> usize {
+    if n == 0 {
+        1
+    } else {
+        1 << n.trailing_zeros()
+    }
+}
+
+/// Returns whether `name` is one the compiler generates for an anonymous class
+/// or union, e.g. `<unnamed-tag>`
+pub fn is_anonymous_name(name: &str) -> bool {
+    name == "__unnamed" || name.starts_with("<unnamed-") || name.starts_with("<anonymous-")
+}
+
+/// Returns `fields` with each member of an anonymous class or union type
+/// replaced by that type's own members, the way WinDbg's `dt` displays them.
+/// Offsets of the replacement members are relative to the start of the
+/// containing type. Nested anonymous types are flattened recursively.
+pub fn flatten_anonymous_fields<'a>(pdb: &'a ParsedPdb, fields: &[TypeId]) -> Vec<Cow<'a, Type>> {
+    let mut flattened = vec![];
+    for field in fields {
+        let field = &pdb.types[*field];
+        let nested = match field {
+            Type::Member(member) => anonymous_fields(pdb, &pdb.types[member.underlying_type])
+                .map(|fields| (member.offset, fields)),
+            _ => None,
+        };
+
+        match nested {
+            Some((offset, fields)) => {
+                for nested_field in flatten_anonymous_fields(pdb, fields) {
+                    // Only data members take part in the parent's layout
+                    if let Type::Member(member) = nested_field.as_ref() {
+                        let mut member = member.clone();
+                        member.offset += offset;
+                        flattened.push(Cow::Owned(Type::Member(member)));
+                    }
+                }
+            }
+            None => flattened.push(Cow::Borrowed(field)),
+        }
+    }
+
+    flattened
+}
+
+/// Returns the fields of `ty` if it's an anonymous class or union, following
+/// forward references to their definition
+fn anonymous_fields<'a>(pdb: &'a ParsedPdb, ty: &'a Type) -> Option<&'a [TypeId]> {
+    let (name, unique_name, forward_reference, fields) = match ty {
+        Type::Class(class) => (
+            &class.name,
+            &class.unique_name,
+            class.properties.forward_reference,
+            &class.fields,
+        ),
+        Type::Union(union) => (
+            &union.name,
+            &union.unique_name,
+            union.properties.forward_reference,
+            &union.fields,
+        ),
+        _ => return None,
+    };
+
+    if !is_anonymous_name(name) {
+        return None;
+    }
+
+    if !forward_reference {
+        return Some(fields);
+    }
+
+    // Anonymous types still have a unique name, so the definition can be found
+    unique_name.as_ref()?;
+    pdb.types.iter().find_map(|(_id, value)| match value {
+        Type::Class(class)
+            if !class.properties.forward_reference && &class.unique_name == unique_name =>
+        {
+            Some(class.fields.as_slice())
+        }
+        Type::Union(union)
+            if !union.properties.forward_reference && &union.unique_name == unique_name =>
+        {
+            Some(union.fields.as_slice())
+        }
+        _ => None,
+    })
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Bitfield {
+    pub underlying_type: TypeId,
+    pub len: usize,
+    pub position: usize,
+}
+
+impl Typed for Bitfield {
+    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
+        panic!("calling type_size() directly on a bitfield is probably not what you want");
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Enumeration {
+    pub name: Name,
+    pub unique_name: Option<Name>,
+    pub underlying_type: TypeId,
+    pub variants: Vec<EnumVariant>,
+    pub properties: TypeProperties,
+}
+
+impl Enumeration {
+    /// Returns the mask of the bits the enum's underlying type holds, e.g.
+    /// `0xFFFF` for a `short`. Negative values only have bits set beyond
+    /// these because [VariantValue::as_u64] sign-extends them.
+    pub fn value_mask(&self, pdb: &ParsedPdb) -> u64 {
+        match pdb.types[self.underlying_type].type_size(pdb) {
+            size @ 1..=7 => (1 << (size * 8)) - 1,
+            _ => u64::MAX,
+        }
+    }
+
+    /// Returns the value of `variant` as the enum's underlying type holds it,
+    /// e.g. `0xFFFF` rather than `0xFFFFFFFFFFFFFFFF` for `-1` of a `short` enum
+    pub fn variant_value(&self, variant: &EnumVariant, pdb: &ParsedPdb) -> u64 {
+        variant.value.as_u64() & self.value_mask(pdb)
+    }
+
+    /// Returns whether the enum's variants are bit flags: at least two variants
+    /// are distinct single bits, and every other nonzero variant is a
+    /// combination of those bits. Enums whose values are consecutive are never
+    /// considered flags, even if they happen to satisfy these conditions.
+    pub fn is_flags(&self, pdb: &ParsedPdb) -> bool {
+        let mut values: Vec<u64> = self
+            .variants
+            .iter()
+            .map(|variant| self.variant_value(variant, pdb))
+            .collect();
+        values.sort_unstable();
+        values.dedup();
+
+        let bits = values
+            .iter()
+            .filter(|value| value.is_power_of_two())
+            .fold(0, |bits, value| bits | value);
+        if bits.count_ones() < 2 || bits < 4 {
+            return false;
+        }
+
+        let consecutive = values.windows(2).all(|pair| pair[1] == pair[0] + 1);
+        !consecutive && values.iter().all(|value| value & !bits == 0)
+    }
+
+    /// Returns the name of the variant with the value `value`. Values of flag
+    /// enums (see [Enumeration::is_flags]) which no single variant has are
+    /// described by the flags they combine, e.g. `A | B`, with any bits which
+    /// aren't a flag appended in hex. Bits beyond those of the enum's underlying
+    /// type are ignored. Returns `None` if no variant matches.
+    pub fn value_name(&self, value: u64, pdb: &ParsedPdb) -> Option<String> {
+        let value = value & self.value_mask(pdb);
+        if let Some(variant) = self
+            .variants
+            .iter()
+            .find(|variant| self.variant_value(variant, pdb) == value)
+        {
+            return Some(variant.name.to_string());
+        }
+
+        if value == 0 || !self.is_flags(pdb) {
+            return None;
+        }
+
+        let mut names = vec![];
+        let mut remaining = value;
+        for variant in &self.variants {
+            let bit = self.variant_value(variant, pdb);
+            if bit.is_power_of_two() && remaining & bit != 0 {
+                names.push(variant.name.to_string());
+                remaining &= !bit;
+            }
+        }
+        if remaining != 0 {
+            names.push(format!("0x{:X}", remaining));
+        }
+
+        Some(names.join(" | "))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnumVariant {
+    pub name: Name,
+    pub value: VariantValue,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VariantValue {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+}
+
+impl VariantValue {
+    /// Returns the value's bits. Signed values are sign-extended.
+    pub fn as_u64(&self) -> u64 {
+        match *self {
+            VariantValue::U8(v) => v as u64,
+            VariantValue::U16(v) => v as u64,
+            VariantValue::U32(v) => v as u64,
+            VariantValue::U64(v) => v,
+            VariantValue::I8(v) => v as u64,
+            VariantValue::I16(v) => v as u64,
+            VariantValue::I32(v) => v as u64,
+            VariantValue::I64(v) => v as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pointer {
+    pub underlying_type: Option<TypeId>,
+    pub attributes: PointerAttributes,
+    /// Set if this is a pointer to a member of a class
+    pub member: Option<MemberPointer>,
+}
+
+/// The class and kind of member a pointer to member refers to. The size of the
+/// pointer's representation is recorded in [PointerAttributes::size].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MemberPointer {
+    pub containing_class: Option<TypeId>,
+    pub kind: MemberPointerKind,
+    pub representation: MemberPointerRepresentation,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MemberPointerKind {
+    /// A pointer to a data member, e.g. `int Foo::*`
+    Data,
+    /// A pointer to a member function, e.g. `void (Foo::*)()`
+    Function,
+}
+
+/// How a pointer to member is represented, which depends on the inheritance of
+/// the class it's a member of (`CV_pmtype`)
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MemberPointerRepresentation {
+    /// The representation wasn't recorded
+    Undefined,
+    /// A member of a class with single inheritance: just an offset or address
+    Single,
+    /// A member of a class with multiple inheritance, which adds an adjustment
+    /// of `this`
+    Multiple,
+    /// A member of a class with virtual bases, which adds the offset of the
+    /// virtual base in the class's vbtable
+    Virtual,
+    /// A member of an incomplete class, whose inheritance is unknown, which may
+    /// need every part of the other representations
+    General,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PointerKind {
+    Near16,
+    Far16,
+    Huge16,
+    BaseSeg,
+    BaseVal,
+    BaseSegVal,
+    BaseAddr,
+    BaseSegAddr,
+    BaseType,
+    BaseSelf,
+    Near32,
+    Far32,
+    Ptr64,
+}
+
+impl Typed for PointerKind {
+    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
+        self.size()
+            .unwrap_or_else(|| panic!("type_size() not implemented for pointer type: {:?}", self))
+    }
+}
+
+impl PointerKind {
+    /// Returns the size of pointers of this kind. `None` for based pointers,
+    /// whose size depends on what they're based on.
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            PointerKind::Near16 | PointerKind::Far16 | PointerKind::Huge16 => Some(2),
+            PointerKind::Near32 | PointerKind::Far32 => Some(4),
+            PointerKind::Ptr64 => Some(8),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PointerAttributes {
+    pub kind: PointerKind,
+    pub is_volatile: bool,
+    pub is_const: bool,
+    pub is_unaligned: bool,
+    pub is_restrict: bool,
+    pub is_reference: bool,
+    /// Set if this is an rvalue (`&&`) rather than lvalue reference
+    pub is_rvalue_reference: bool,
+    pub size: usize,
+    pub is_mocom: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Primitive {
+    pub kind: PrimitiveKind,
+    pub indirection: Option<Indirection>,
+}
+
+impl Typed for Primitive {
+    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
+        self.size()
+    }
+}
+
+impl Primitive {
+    pub fn size(&self) -> usize {
+        if let Some(indirection) = self.indirection.as_ref() {
+            return indirection.size();
+        }
+
+        self.kind.size()
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Indirection {
+    Near16,
+    Far16,
+    Huge16,
+    Near32,
+    Far32,
+    Near64,
+    Near128,
+}
+
+impl Typed for Indirection {
+    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
+        self.size()
+    }
+}
+
+impl Indirection {
+    pub fn size(&self) -> usize {
+        match self {
+            Indirection::Near16 | Indirection::Far16 | Indirection::Huge16 => 2,
+            Indirection::Near32 | Indirection::Far32 => 4,
+            Indirection::Near64 => 8,
+            Indirection::Near128 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PrimitiveKind {
+    NoType,
+    Void,
+    Char,
+    UChar,
+    RChar,
+    WChar,
+    RChar16,
+    RChar32,
+    I8,
+    U8,
+    Short,
+    UShort,
+    I16,
+    U16,
+    Long,
+    ULong,
+    I32,
+    U32,
+    Quad,
+    UQuad,
+    I64,
+    U64,
+    Octa,
+    UOcta,
+    I128,
+    U128,
+    F16,
+    F32,
+    F32PP,
+    F48,
+    F64,
+    F80,
+    F128,
+    Complex32,
+    Complex64,
+    Complex80,
+    Complex128,
+    Bool8,
+    Bool16,
+    Bool32,
+    Bool64,
+    HRESULT,
+}
+
+impl Typed for PrimitiveKind {
+    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
+        self.size()
+    }
+}
+
+impl PrimitiveKind {
+    pub fn size(&self) -> usize {
+        match self {
+            PrimitiveKind::NoType | PrimitiveKind::Void => 0,
+
+            PrimitiveKind::Char
+            | PrimitiveKind::UChar
+            | PrimitiveKind::RChar
+            | PrimitiveKind::I8
+            | PrimitiveKind::U8
+            | PrimitiveKind::Bool8 => 1,
+
+            PrimitiveKind::RChar16
+            | PrimitiveKind::WChar
+            | PrimitiveKind::Short
+            | PrimitiveKind::UShort
+            | PrimitiveKind::I16
+            | PrimitiveKind::U16
+            | PrimitiveKind::F16
+            | PrimitiveKind::Bool16 => 2,
+
+            PrimitiveKind::RChar32
+            | PrimitiveKind::Long
+            | PrimitiveKind::ULong
+            | PrimitiveKind::I32
+            | PrimitiveKind::U32
+            | PrimitiveKind::F32
+            | PrimitiveKind::F32PP
+            | PrimitiveKind::Bool32
+            | PrimitiveKind::HRESULT
+            | PrimitiveKind::Complex32 => 4,
+
+            PrimitiveKind::F48 => 6,
+
+            PrimitiveKind::Quad
+            | PrimitiveKind::UQuad
+            | PrimitiveKind::I64
+            | PrimitiveKind::U64
+            | PrimitiveKind::F64
+            | PrimitiveKind::Bool64
+            | PrimitiveKind::Complex64 => 8,
+
+            PrimitiveKind::Octa
+            | PrimitiveKind::UOcta
+            | PrimitiveKind::I128
+            | PrimitiveKind::U128 => 16,
+
+            PrimitiveKind::F80 | PrimitiveKind::Complex80 => 10,
+
+            PrimitiveKind::F128 | PrimitiveKind::Complex128 => 16,
+        }
+    }
+}
+
+impl std::fmt::Display for PrimitiveKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrimitiveKind::NoType => write!(f, "NoType"),
+            PrimitiveKind::Void => write!(f, "Void"),
+            PrimitiveKind::Char => write!(f, "Char"),
+            PrimitiveKind::UChar => write!(f, "UChar"),
+            PrimitiveKind::RChar => write!(f, "RChar"),
+            PrimitiveKind::WChar => write!(f, "WChar"),
+            PrimitiveKind::RChar16 => write!(f, "RChar16"),
+            PrimitiveKind::RChar32 => write!(f, "RChar32"),
+            PrimitiveKind::I8 => write!(f, "I8"),
+            PrimitiveKind::U8 => write!(f, "U8"),
+            PrimitiveKind::Short => write!(f, "Short"),
+            PrimitiveKind::UShort => write!(f, "UShort"),
+            PrimitiveKind::I16 => write!(f, "I16"),
+            PrimitiveKind::U16 => write!(f, "U16"),
+            PrimitiveKind::Long => write!(f, "Long"),
+            PrimitiveKind::ULong => write!(f, "ULong"),
+            PrimitiveKind::I32 => write!(f, "I32"),
+            PrimitiveKind::U32 => write!(f, "U32"),
+            PrimitiveKind::Quad => write!(f, "Quad"),
+            PrimitiveKind::UQuad => write!(f, "UQuad"),
+            PrimitiveKind::I64 => write!(f, "I64"),
+            PrimitiveKind::U64 => write!(f, "U64"),
+            PrimitiveKind::Octa => write!(f, "Octa"),
+            PrimitiveKind::UOcta => write!(f, "UOcta"),
+            PrimitiveKind::I128 => write!(f, "I128"),
+            PrimitiveKind::U128 => write!(f, "U128"),
+            PrimitiveKind::F16 => write!(f, "F16"),
+            PrimitiveKind::F32 => write!(f, "F32"),
+            PrimitiveKind::F32PP => write!(f, "F32PP"),
+            PrimitiveKind::F48 => write!(f, "F48"),
+            PrimitiveKind::F64 => write!(f, "F64"),
+            PrimitiveKind::F80 => write!(f, "F80"),
+            PrimitiveKind::F128 => write!(f, "F128"),
+            PrimitiveKind::Complex32 => write!(f, "Complex32"),
+            PrimitiveKind::Complex64 => write!(f, "Complex64"),
+            PrimitiveKind::Complex80 => write!(f, "Complex80"),
+            PrimitiveKind::Complex128 => write!(f, "Complex128"),
+            PrimitiveKind::Bool8 => write!(f, "Bool8"),
+            PrimitiveKind::Bool16 => write!(f, "Bool16"),
+            PrimitiveKind::Bool32 => write!(f, "Bool32"),
+            PrimitiveKind::Bool64 => write!(f, "Bool64"),
+            PrimitiveKind::HRESULT => write!(f, "HRESULT"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Array {
+    pub element_type: TypeId,
+    pub indexing_type: TypeId,
+    /// Distance in bytes between consecutive elements, if it differs from the
+    /// size of the element type
+    pub stride: Option<u32>,
+    pub size: usize,
+    /// Byte size of each dimension, innermost first
+    pub dimensions_bytes: Vec<usize>,
+    /// Number of elements in each dimension, innermost first
+    pub dimensions_elements: Vec<usize>,
+}
+
+impl Array {
+    /// Returns the distance in bytes between consecutive elements
+    pub fn element_stride(&self, pdb: &ParsedPdb) -> usize {
+        match self.stride {
+            Some(stride) => stride as usize,
+            None => pdb.types[self.element_type].type_size(pdb),
+        }
+    }
+}
+
+impl Typed for Array {
+    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
+        self.size
+    }
+
+    fn on_complete(&mut self, pdb: &ParsedPdb) {
+        self.dimensions_elements.clear();
+
+        if self.size == 0 {
+            self.dimensions_elements.push(0);
+            return;
+        }
+
+        // Each dimension's byte size includes all of the dimensions inside it
+        let mut running_size = self.element_stride(pdb);
+
+        for byte_size in &self.dimensions_bytes {
+            // TODO: may be incorrect behavior
+            if running_size == 0 {
+                continue;
+            }
+
+            self.dimensions_elements.push(*byte_size / running_size);
+
+            running_size = *byte_size;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FieldList(pub Vec<TypeId>);
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ArgumentList(pub Vec<TypeId>);
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Modifier {
+    pub underlying_type: TypeId,
+    pub constant: bool,
+    pub volatile: bool,
+    pub unaligned: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Member {
+    pub name: Name,
+    pub underlying_type: TypeId,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Procedure {
+    pub return_type: Option<TypeId>,
+    pub argument_list: Vec<TypeId>,
+    pub attributes: FunctionAttributes,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FunctionAttributes {
+    pub calling_convention: u8,
+    pub cxx_return_udt: bool,
+    pub is_constructor: bool,
+    pub is_constructor_with_virtual_bases: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MemberFunction {
+    pub return_type: TypeId,
+    pub class_type: TypeId,
+    pub this_pointer_type: Option<TypeId>,
+    pub argument_list: Vec<TypeId>,
+    pub attributes: FunctionAttributes,
+    pub this_adjustment: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MethodList(pub Vec<MethodListEntry>);
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MethodListEntry {
+    pub attributes: FieldAttributes,
+    pub method_type: TypeId,
+    pub vtable_offset: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Nested {
+    pub name: Name,
+    pub nested_type: TypeId,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OverloadedMethod {
+    pub name: Name,
+    pub method_list: TypeId,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Method {
+    pub name: Name,
+    pub attributes: FieldAttributes,
+    pub method_type: TypeId,
+    pub vtable_offset: Option<usize>,
+}
+
+/// Access and method properties of a class member
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FieldAttributes {
+    pub access: Access,
+    pub is_static: bool,
+    pub is_virtual: bool,
+    pub is_pure_virtual: bool,
+    /// Whether this method introduces a new vtable slot rather than overriding
+    /// one from a base class
+    pub is_introducing_virtual: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Access {
+    None,
+    Private,
+    Protected,
+    Public,
+}
+
+impl std::fmt::Display for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Access::None => write!(f, ""),
+            Access::Private => write!(f, "private"),
+            Access::Protected => write!(f, "protected"),
+            Access::Public => write!(f, "public"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StaticMember {
+    pub name: Name,
+    pub field_type: TypeId,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VTable(pub TypeId);
+
+/// A type whose record the `pdb` crate can't parse, such as `LF_VFTABLE` or
+/// `LF_PRECOMP`. Records of kinds with a fallback decoder have their name and
+/// referenced type filled in; others only have their kind. Representing these
+/// rather than dropping them lets the types which refer to them be parsed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Partial {
+    /// The record's leaf kind
+    pub kind: u16,
+    /// The `LF_*` name of the leaf kind, if it's a known one
+    pub kind_name: Option<String>,
+    /// The name the record gives, e.g. a vftable's or precompiled header's name
+    pub name: Option<Name>,
+    /// The TPI index of the type the record refers to, e.g. the class owning a
+    /// vftable or the type an alias names
+    pub referenced_type_index: Option<TypeIndexNumber>,
+    /// The type at `referenced_type_index`, if it could be parsed
+    pub referenced_type: Option<TypeId>,
+}
+
+/// The number and kinds of slots in a vtable, as described by an `LF_VTSHAPE` record
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VTableShape {
+    pub slots: Vec<VTableSlotKind>,
+}
+
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "extra-traits", derive(PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VTableSlotKind {
+    Near16,
+    Far16,
+    Thin,
+    /// An address point displacement to the outermost class
+    Outer,
+    /// A far pointer to a metaclass descriptor
+    Meta,
+    Near32,
+    Far32,
+    Unused,
+}
@@ -0,0 +1,38 @@
+//! Measures how long parsing synthetic PDBs of several sizes takes. Run with
+//! `cargo bench -p ezpdb --features synthetic`. `pdbview bench` breaks the time
+//! down by parsing stage.
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use ezpdb::progress::NoProgress;
+use ezpdb::synthetic::{synthetic_pdb, SyntheticOptions};
+use ezpdb::ParseOptions;
+use std::path::PathBuf;
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+fn parse(c: &mut Criterion) {
+    let options = ParseOptions::default();
+    let mut group = c.benchmark_group("parse");
+    // Parsing the largest PDB takes long enough that the default of 100
+    // samples would make a run take minutes
+    group.sample_size(10);
+    for size in SIZES {
+        let data = synthetic_pdb(&SyntheticOptions::scaled(size));
+        let path = PathBuf::from(format!("synthetic-{}.pdb", size));
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            b.iter_batched(
+                || data.clone(),
+                |data| {
+                    ezpdb::parse_pdb_from_bytes(data, path.clone(), &options, &mut NoProgress)
+                        .expect("failed to parse a synthetic PDB")
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
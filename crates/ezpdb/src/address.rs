@@ -0,0 +1,81 @@
+//! Translation of the section offsets stored in symbols into RVAs
+use crate::ParseOptions;
+use tracing::{debug, warn};
+
+/// Maps section offsets to RVAs using the PDB's address map. Some stripped PDBs
+/// lack the section headers the address map is built from, in which case the
+/// section headers of the executable given in [ParseOptions::image_path] are
+/// used instead.
+#[derive(Debug)]
+pub(crate) struct AddressMapping<'s> {
+    address_map: Option<pdb::AddressMap<'s>>,
+    /// Virtual address of each of the executable's sections
+    image_sections: Option<Vec<u32>>,
+}
+
+impl<'s> AddressMapping<'s> {
+    pub fn new<S: pdb::Source<'s> + 's>(pdb: &mut pdb::PDB<'s, S>, options: &ParseOptions) -> Self {
+        debug!("getting address map");
+        let address_map = match pdb.address_map() {
+            Ok(address_map) => Some(address_map),
+            Err(e) => {
+                warn!("could not read the address map: {}", e);
+                None
+            }
+        };
+
+        let has_sections = matches!(pdb.sections(), Ok(Some(sections)) if !sections.is_empty());
+        if address_map.is_some() && has_sections {
+            return AddressMapping {
+                address_map,
+                image_sections: None,
+            };
+        }
+
+        let image_sections = options.image_path.as_ref().and_then(|path| {
+            match std::fs::read(path)
+                .map_err(crate::error::Error::from)
+                .and_then(|image| crate::raw::image_section_addresses(&image))
+            {
+                Ok(sections) => {
+                    debug!("using the section headers of {:?} for addresses", path);
+                    Some(sections)
+                }
+                Err(e) => {
+                    warn!("could not read section headers from {:?}: {}", path, e);
+                    None
+                }
+            }
+        });
+
+        AddressMapping {
+            address_map,
+            image_sections,
+        }
+    }
+
+    /// Only uses the PDB's own address map
+    pub fn from_pdb<S: pdb::Source<'s> + 's>(pdb: &mut pdb::PDB<'s, S>) -> Self {
+        AddressMapping {
+            address_map: pdb.address_map().ok(),
+            image_sections: None,
+        }
+    }
+
+    pub fn to_rva(&self, offset: pdb::PdbInternalSectionOffset) -> Option<u32> {
+        if let Some(rva) = self
+            .address_map
+            .as_ref()
+            .and_then(|address_map| offset.to_rva(address_map))
+        {
+            return Some(u32::from(rva));
+        }
+
+        // Section indices are 1-based
+        let section = self
+            .image_sections
+            .as_ref()?
+            .get((offset.section as usize).checked_sub(1)?)?;
+        section.checked_add(offset.offset)
+    }
+}
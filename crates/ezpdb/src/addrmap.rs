@@ -0,0 +1,161 @@
+//! Writing a compact, sorted table of the address ranges of a PDB's symbols,
+//! laid out for emulators and dynamic binary instrumentation tools to
+//! memory-map and binary search without parsing.
+//!
+//! All integers are little endian. The file starts with a 32-byte header:
+//!
+//! | Offset | Size | Field                                          |
+//! |--------|------|------------------------------------------------|
+//! | 0      | 8    | Magic, `PDBVMAP\0`                             |
+//! | 8      | 4    | Format version, currently 1                    |
+//! | 12     | 4    | Number of entries                              |
+//! | 16     | 4    | File offset of the entries                     |
+//! | 20     | 4    | File offset of the string table                |
+//! | 24     | 4    | Size of the string table                       |
+//! | 28     | 4    | Reserved, 0                                    |
+//!
+//! Entries are 16 bytes each and sorted by RVA:
+//!
+//! | Offset | Size | Field                                          |
+//! |--------|------|------------------------------------------------|
+//! | 0      | 4    | RVA                                            |
+//! | 4      | 4    | Length in bytes                                |
+//! | 8      | 4    | Offset of the name in the string table         |
+//! | 12     | 4    | Flags: bit 0 is set for code                   |
+//!
+//! The string table holds each distinct name once, NUL-terminated, so the
+//! offset of a name also serves as its identifier.
+use crate::error::Error;
+use crate::symbol_types::ParsedPdb;
+use crate::type_info::Typed;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Magic bytes the file starts with
+pub const MAGIC: [u8; 8] = *b"PDBVMAP\0";
+
+/// Version of the format written
+pub const VERSION: u32 = 1;
+
+/// Size of the header
+const HEADER_SIZE: usize = 32;
+
+/// Flag of entries which are code
+pub const FLAG_CODE: u32 = 0x1;
+
+/// A symbol's address range
+#[derive(Debug)]
+struct Entry<'a> {
+    rva: u32,
+    /// Unknown for public symbols
+    len: Option<u32>,
+    name: &'a str,
+    flags: u32,
+}
+
+/// Writes the address map of `pdb` to `output`: its procedures, global data,
+/// and the public symbols which aren't procedures. Procedures and data have the
+/// length the PDB records for them. Public symbols have no length, so each
+/// extends to the next entry. Returns the number of entries written.
+pub fn write_address_map<P: AsRef<Path>>(pdb: &ParsedPdb, output: P) -> Result<usize, Error> {
+    let entries = entries(pdb);
+
+    let mut strings = vec![];
+    let mut string_offsets = HashMap::new();
+    let mut records = Vec::with_capacity(entries.len() * 16);
+    for entry in &entries {
+        let name = *string_offsets.entry(entry.name).or_insert_with(|| {
+            let offset = strings.len() as u32;
+            strings.extend_from_slice(entry.name.as_bytes());
+            strings.push(0);
+            offset
+        });
+
+        for value in [entry.rva, entry.len.unwrap_or(0), name, entry.flags] {
+            records.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    let strings_offset = HEADER_SIZE + records.len();
+    let mut file = Vec::with_capacity(strings_offset + strings.len());
+    file.extend_from_slice(&MAGIC);
+    for value in [
+        VERSION,
+        entries.len() as u32,
+        HEADER_SIZE as u32,
+        strings_offset as u32,
+        strings.len() as u32,
+        0,
+    ] {
+        file.extend_from_slice(&value.to_le_bytes());
+    }
+    file.extend_from_slice(&records);
+    file.extend_from_slice(&strings);
+
+    std::fs::write(output, file)?;
+
+    Ok(entries.len())
+}
+
+/// Returns the entries of the address map, sorted by RVA
+fn entries(pdb: &ParsedPdb) -> Vec<Entry<'_>> {
+    let mut entries: Vec<Entry<'_>> = pdb
+        .procedures
+        .iter()
+        .filter_map(|procedure| {
+            Some(Entry {
+                rva: procedure.address?.rva as u32,
+                len: Some(procedure.len as u32),
+                name: &procedure.name,
+                flags: FLAG_CODE,
+            })
+        })
+        .collect();
+
+    entries.extend(pdb.global_data.iter().filter_map(|data| {
+        Some(Entry {
+            rva: data.offset?.rva as u32,
+            len: Some(
+                pdb.types
+                    .get(data.ty)
+                    .map_or(0, |typ| typ.type_size(pdb) as u32),
+            ),
+            name: &data.name,
+            flags: 0,
+        })
+    }));
+
+    let known: HashSet<u32> = entries.iter().map(|entry| entry.rva).collect();
+    entries.extend(pdb.public_symbols.iter().filter_map(|symbol| {
+        let rva = symbol.offset?.rva as u32;
+        if known.contains(&rva) {
+            return None;
+        }
+
+        Some(Entry {
+            rva,
+            len: None,
+            name: &symbol.name,
+            flags: if symbol.is_code || symbol.is_function {
+                FLAG_CODE
+            } else {
+                0
+            },
+        })
+    }));
+
+    entries.sort_by(|a, b| a.rva.cmp(&b.rva).then_with(|| a.name.cmp(b.name)));
+    for index in 0..entries.len() {
+        if entries[index].len.is_some() {
+            continue;
+        }
+
+        let rva = entries[index].rva;
+        entries[index].len = entries[index + 1..]
+            .iter()
+            .find(|next| next.rva > rva)
+            .map(|next| next.rva - rva);
+    }
+
+    entries
+}
@@ -0,0 +1,116 @@
+//! Auditing the flags each module was compiled with for missing security mitigations
+use crate::symbol_types::{BuildInfo, ParsedPdb};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A security mitigation which is enabled by a compiler flag
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Mitigation {
+    /// Stack buffer overrun checks (`/GS`)
+    SecurityChecks,
+    /// Additional security checks and warnings (`/sdl`)
+    Sdl,
+    /// Control Flow Guard (`/guard:cf`)
+    ControlFlowGuard,
+}
+
+impl fmt::Display for Mitigation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = match self {
+            Mitigation::SecurityChecks => "/GS",
+            Mitigation::Sdl => "/sdl",
+            Mitigation::ControlFlowGuard => "/guard:cf",
+        };
+
+        f.write_str(flag)
+    }
+}
+
+/// The security-relevant flags a module was compiled with
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ModuleAudit {
+    /// Index of the module in [ParsedPdb::debug_modules]
+    pub module_index: usize,
+    pub name: String,
+    /// The module's source language, e.g. `Cpp`. `None` if the module has no
+    /// compiler information, e.g. because it was produced by the linker.
+    pub language: Option<String>,
+    /// Compiled with `/GS`
+    pub security_checks: Option<bool>,
+    /// Compiled with `/sdl`
+    pub sdl: Option<bool>,
+    /// Compiled with `/hotpatch`
+    pub hot_patch: Option<bool>,
+    /// Compiled with `/GL` for link-time code generation
+    pub link_time_codegen: Option<bool>,
+    /// Compiled with `/guard:cf`. Only known if the module's command line was recorded.
+    pub control_flow_guard: Option<bool>,
+    /// The mitigations the module was compiled without. Only C and C++ modules
+    /// are checked, as the flags don't apply to other languages.
+    pub missing: Vec<Mitigation>,
+}
+
+/// Reports the security-relevant compiler flags of each module of `pdb`. The
+/// PDB must have been parsed in full, as compiler information is read from the
+/// modules' symbol streams.
+pub fn audit_modules(pdb: &ParsedPdb) -> Vec<ModuleAudit> {
+    pdb.debug_modules
+        .iter()
+        .enumerate()
+        .map(|(module_index, module)| {
+            let compiler = module.compiler_info.as_ref();
+            let flags = compiler.map(|compiler| &compiler.flags);
+            let control_flow_guard = module.build_info.as_ref().map(has_control_flow_guard);
+
+            let mut missing = vec![];
+            let is_c = compiler
+                .is_some_and(|compiler| compiler.language == "C" || compiler.language == "Cpp");
+            if let (true, Some(flags)) = (is_c, flags) {
+                if !flags.security_checks {
+                    missing.push(Mitigation::SecurityChecks);
+                }
+                if !flags.sdl {
+                    missing.push(Mitigation::Sdl);
+                }
+                if control_flow_guard == Some(false) {
+                    missing.push(Mitigation::ControlFlowGuard);
+                }
+            }
+
+            ModuleAudit {
+                module_index,
+                name: module.name.clone(),
+                language: compiler.map(|compiler| compiler.language.clone()),
+                security_checks: flags.map(|flags| flags.security_checks),
+                sdl: flags.map(|flags| flags.sdl),
+                hot_patch: flags.map(|flags| flags.hot_patch),
+                link_time_codegen: flags.map(|flags| flags.link_time_codegen),
+                control_flow_guard,
+                missing,
+            }
+        })
+        .collect()
+}
+
+/// Returns whether the command line in `build_info` enables Control Flow Guard.
+/// A later `/guard:cf-` overrides an earlier `/guard:cf`, as it does for the compiler.
+fn has_control_flow_guard(build_info: &BuildInfo) -> bool {
+    let mut enabled = false;
+    for option in build_info
+        .arguments
+        .iter()
+        .flat_map(|argument| argument.split_whitespace())
+    {
+        let option = option.trim_matches('"').to_ascii_lowercase();
+        match option.trim_start_matches(['/', '-']) {
+            "guard:cf" => enabled = true,
+            "guard:cf-" => enabled = false,
+            _ => {}
+        }
+    }
+
+    enabled
+}
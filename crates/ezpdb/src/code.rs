@@ -0,0 +1,63 @@
+//! Reading the code of procedures from the image a PDB belongs to
+use crate::error::Error;
+use crate::raw;
+use crate::symbol_types::ParsedPdb;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Returns the key symbol stores file `pdb` under, to compare against an image's reference
+fn pdb_key(pdb: &ParsedPdb) -> String {
+    format!("{:X}{:X}", pdb.guid.simple(), pdb.age)
+}
+
+/// Returns an error unless the PE image `image` was linked with `pdb`
+pub(crate) fn check_image(pdb: &ParsedPdb, image: &[u8]) -> Result<(), Error> {
+    match raw::image_pdb_reference(image)? {
+        Some(reference) if reference.store_key() == pdb_key(pdb) => Ok(()),
+        Some(_) => Err(Error::InvalidImage(
+            "the image was not linked with this PDB",
+        )),
+        None => Err(Error::InvalidImage(
+            "the image has no CodeView debug information",
+        )),
+    }
+}
+
+/// Reads up to the first `count` bytes of each procedure of `pdb` which has an
+/// address from `image`, keyed by the procedure's RVA. Procedures shorter than
+/// `count` bytes are read in full, and bytes past the end of a section's data
+/// in the file aren't read. Bytes of addresses which the loader relocates are
+/// `None`, as they differ between loads of the image.
+pub fn leading_bytes(
+    pdb: &ParsedPdb,
+    image: &Path,
+    count: usize,
+) -> Result<HashMap<usize, Vec<Option<u8>>>, Error> {
+    let data = std::fs::read(image)?;
+    check_image(pdb, &data)?;
+    let image = raw::Image::parse(&data)?;
+
+    let mut relocated = HashSet::new();
+    for (rva, size) in image.relocations()? {
+        relocated.extend((rva..rva + size).map(|rva| rva as usize));
+    }
+
+    let mut code = HashMap::new();
+    for procedure in &pdb.procedures {
+        let rva = match procedure.address {
+            Some(address) => address.rva,
+            None => continue,
+        };
+        let mut reader = image.reader_at(rva as u32)?;
+        let len = procedure.len.min(count).min(reader.remaining());
+        let bytes = reader
+            .take(len)?
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| Some(*byte).filter(|_| !relocated.contains(&(rva + i))))
+            .collect();
+        code.insert(rva, bytes);
+    }
+
+    Ok(code)
+}
@@ -0,0 +1,5 @@
+//! Building blocks for generating source code, such as C and C++ headers, from
+//! a PDB's types
+pub mod definitions;
+pub mod layout;
+pub mod ordering;
@@ -0,0 +1,288 @@
+//! Writing classes, unions, and enums as type definitions in languages which
+//! don't share C++'s layout rules, such as Go and Zig. Which types are defined,
+//! in what order, under what names, and what each member is written as are
+//! worked out here, so that a language only has to say how it spells them.
+use super::ordering::{self, Item};
+use crate::render::{self, RenderOptions};
+use crate::symbol_types::ParsedPdb;
+use crate::type_info::{Primitive, PrimitiveKind, Type, TypeId, Typed};
+use std::collections::{HashMap, HashSet};
+
+/// How a language spells the types members are written as
+pub trait Spelling {
+    /// Returns `name`, e.g. `demo::Point`, as an identifier of the language
+    fn identifier(&self, name: &str) -> String;
+
+    /// Returns the name of the language's equivalent of `kind`, if it has one
+    fn primitive(&self, kind: PrimitiveKind) -> Option<&'static str>;
+
+    /// Returns an array of `len` elements of type `element`
+    fn array(&self, len: usize, element: &str) -> String;
+
+    /// Returns an array of `size` bytes, for types the language has no
+    /// equivalent of
+    fn bytes(&self, size: usize) -> String;
+}
+
+/// The type a member is written as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberType {
+    pub name: String,
+    pub size: usize,
+    pub align: usize,
+    /// The C type, if the language's type doesn't show it, e.g. for pointers
+    pub note: Option<String>,
+}
+
+impl MemberType {
+    /// A type of `size` bytes, aligned to its size
+    pub fn new(name: impl Into<String>, size: usize) -> Self {
+        MemberType {
+            name: name.into(),
+            size,
+            align: size.clamp(1, 8),
+            note: None,
+        }
+    }
+
+    /// An array of `size` bytes, for types the language has no equivalent of
+    pub fn bytes(spelling: &impl Spelling, size: usize) -> Self {
+        MemberType {
+            name: spelling.bytes(size),
+            size,
+            align: 1,
+            note: None,
+        }
+    }
+
+    pub fn noted(mut self, note: String) -> Self {
+        self.note = Some(note);
+        self
+    }
+}
+
+/// Hands out names which haven't been handed out before
+#[derive(Debug, Clone, Default)]
+pub struct UniqueNames {
+    used: HashSet<String>,
+}
+
+impl UniqueNames {
+    /// Names which are never handed out, e.g. those of imports
+    pub fn reserving(names: &[&str]) -> Self {
+        UniqueNames {
+            used: names.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    /// Returns `name`, or `name` with a numeric suffix if it's been handed out
+    pub fn unique(&mut self, name: &str) -> String {
+        self.unique_spelled(name, str::to_string)
+    }
+
+    /// Returns `name` spelled by `spell`, or `name` with a numeric suffix
+    /// spelled by `spell` if the former has been handed out. The suffix is added
+    /// before spelling, so that e.g. quoting stays valid.
+    pub fn unique_spelled(&mut self, name: &str, spell: impl Fn(&str) -> String) -> String {
+        let mut candidate = spell(name);
+        let mut suffix = 2;
+        while self.used.contains(&candidate) {
+            candidate = spell(&format!("{}_{}", name, suffix));
+            suffix += 1;
+        }
+        self.used.insert(candidate.clone());
+
+        candidate
+    }
+}
+
+/// Returns the unique name (or name) of a class, union, or enum, which
+/// identifies its definition across forward references
+fn type_key(ty: &Type) -> Option<&str> {
+    ordering::named_type(ty).map(|(name, _forward_reference)| name)
+}
+
+/// The classes, unions, and enums being defined, and what the types of their
+/// members are written as
+pub struct Definitions<'a, S> {
+    pub pdb: &'a ParsedPdb,
+    /// How the C names noted alongside types are formatted
+    pub render: &'a RenderOptions,
+    pub spelling: S,
+    /// The types to define, each after the ones it depends upon
+    pub order: Vec<TypeId>,
+    /// Names of the types being defined, keyed by [type_key]
+    names: HashMap<&'a str, String>,
+    /// Alignment of the classes and unions defined so far, keyed by [type_key]
+    alignments: HashMap<&'a str, usize>,
+}
+
+impl<'a, S: Spelling> Definitions<'a, S> {
+    /// Orders the definitions of `types` and the classes, unions, and enums
+    /// they contain by value, and names each one with `spelling`. Names are
+    /// made unique, and never one of `reserved`.
+    pub fn new(
+        pdb: &'a ParsedPdb,
+        types: &[TypeId],
+        render: &'a RenderOptions,
+        spelling: S,
+        reserved: &[&str],
+    ) -> Self {
+        let order: Vec<TypeId> = ordering::order(pdb, types)
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Define(id) => Some(id),
+                Item::Declare(_) => None,
+            })
+            .collect();
+
+        let mut names = HashMap::new();
+        let mut used = UniqueNames::reserving(reserved);
+        for id in &order {
+            let ty = &pdb.types[*id];
+            let name = match ty {
+                Type::Class(class) => &class.name,
+                Type::Union(union) => &union.name,
+                Type::Enumeration(e) => &e.name,
+                _ => continue,
+            };
+            if let Some(key) = type_key(ty) {
+                names
+                    .entry(key)
+                    .or_insert_with(|| used.unique_spelled(name, |name| spelling.identifier(name)));
+            }
+        }
+
+        Definitions {
+            pdb,
+            render,
+            spelling,
+            order,
+            names,
+            alignments: HashMap::new(),
+        }
+    }
+
+    /// Returns the name the class, union, or enum `ty` is defined under, if
+    /// it's being defined
+    pub fn name(&self, ty: &Type) -> Option<&str> {
+        type_key(ty)
+            .and_then(|key| self.names.get(key))
+            .map(String::as_str)
+    }
+
+    /// Records the alignment of the class or union `ty` once it's defined, so
+    /// that members of its type can be written as it
+    pub fn set_alignment(&mut self, ty: &'a Type, align: usize) {
+        if let Some(key) = type_key(ty) {
+            self.alignments.insert(key, align);
+        }
+    }
+
+    /// Returns the C name of `ty`, for noting alongside the type it's written as
+    pub fn type_name(&self, ty: &Type) -> String {
+        render::type_name(self.pdb, ty, self.render)
+    }
+
+    /// Returns a pointer of `size` bytes, which is written as an unsigned
+    /// integer, since the pointee isn't in the address space of the program
+    /// reading it and may not even have the same pointer size
+    fn pointer(&self, size: usize) -> MemberType {
+        let kind = match size {
+            4 => PrimitiveKind::U32,
+            8 => PrimitiveKind::U64,
+            _ => return MemberType::bytes(&self.spelling, size),
+        };
+        match self.spelling.primitive(kind) {
+            Some(name) => MemberType::new(name, size),
+            None => MemberType::bytes(&self.spelling, size),
+        }
+    }
+
+    fn primitive(&self, primitive: &Primitive) -> MemberType {
+        let note = || self.type_name(&Type::Primitive(primitive.clone()));
+        let size = primitive.size();
+        if primitive.indirection.is_some() {
+            return self.pointer(size).noted(note());
+        }
+
+        match self.spelling.primitive(primitive.kind) {
+            Some(name) => MemberType::new(name, size),
+            None => MemberType::bytes(&self.spelling, size).noted(note()),
+        }
+    }
+
+    /// Returns the type a member of type `id` is written as. Classes and
+    /// unions which aren't being defined, or haven't been yet, are written as
+    /// bytes.
+    pub fn member_type(&self, id: TypeId) -> MemberType {
+        let ty = &self.pdb.types[id];
+        match ty {
+            Type::Primitive(primitive) => self.primitive(primitive),
+            Type::Pointer(pointer) => self
+                .pointer(pointer.attributes.size)
+                .noted(self.type_name(ty)),
+            Type::Modifier(modifier) => self.member_type(modifier.underlying_type),
+            Type::Array(array) => {
+                let element = self.member_type(array.element_type);
+                match array.size.checked_div(element.size) {
+                    Some(count) if array.element_stride(self.pdb) == element.size => MemberType {
+                        name: self.spelling.array(count, &element.name),
+                        size: array.size,
+                        align: element.align,
+                        note: element.note,
+                    },
+                    _ => MemberType::bytes(&self.spelling, array.size).noted(self.type_name(ty)),
+                }
+            }
+            Type::Enumeration(e) => {
+                let underlying = self.member_type(e.underlying_type);
+                match self.name(ty) {
+                    Some(name) => MemberType {
+                        name: name.to_string(),
+                        ..underlying
+                    },
+                    None => underlying.noted(self.type_name(ty)),
+                }
+            }
+            Type::Class(_) | Type::Union(_) => {
+                let size = ty.type_size(self.pdb);
+                let align = type_key(ty).and_then(|key| self.alignments.get(key));
+                match self.name(ty).zip(align) {
+                    Some((name, &align)) => MemberType {
+                        name: name.to_string(),
+                        size,
+                        align,
+                        note: None,
+                    },
+                    None => MemberType::bytes(&self.spelling, size).noted(self.type_name(ty)),
+                }
+            }
+            _ => {
+                MemberType::bytes(&self.spelling, ty.type_size(self.pdb)).noted(self.type_name(ty))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_names() {
+        let mut names = UniqueNames::reserving(&["std"]);
+        assert_eq!(names.unique("std"), "std_2");
+        assert_eq!(names.unique("Point"), "Point");
+        assert_eq!(names.unique("Point"), "Point_2");
+        assert_eq!(names.unique("Point"), "Point_3");
+    }
+
+    #[test]
+    fn suffixes_are_added_before_spelling() {
+        let quote = |name: &str| format!("@\"{}\"", name);
+        let mut names = UniqueNames::default();
+        assert_eq!(names.unique_spelled("a::b", quote), "@\"a::b\"");
+        assert_eq!(names.unique_spelled("a::b", quote), "@\"a::b_2\"");
+    }
+}
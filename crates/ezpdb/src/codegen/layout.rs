@@ -0,0 +1,130 @@
+//! Laying out the members of classes at the offsets the PDB records them at.
+//! Languages without C++'s layout rules can't rely on their compiler placing
+//! each member where MSVC did, so definitions spell out the padding between
+//! members instead, which this works out independently of the language.
+use crate::symbol_types::ParsedPdb;
+use crate::type_info::{Type, TypeId, Typed};
+
+/// A part of a class, in the order it's laid out in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Slot<'a> {
+    /// A data member
+    Member {
+        offset: usize,
+        name: &'a str,
+        ty: TypeId,
+    },
+    /// A base class, laid out like a member
+    Base { offset: usize, ty: TypeId },
+    /// Bitfields sharing a storage unit of type `storage`
+    Bitfields {
+        offset: usize,
+        storage: TypeId,
+        bits: Vec<Bits<'a>>,
+    },
+    /// Bytes no member occupies
+    Padding { offset: usize, len: usize },
+    /// A member which starts before the previous one ends, e.g. one of an
+    /// anonymous union's members. It can't be written as a field.
+    Overlapping {
+        offset: usize,
+        name: &'a str,
+        ty: TypeId,
+    },
+}
+
+/// A bitfield within its storage unit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bits<'a> {
+    pub name: &'a str,
+    pub len: usize,
+    pub position: usize,
+}
+
+/// Returns the greatest alignment a member of a class of `size` bytes can have,
+/// as a type's size is a multiple of its alignment. Alignments greater than 8
+/// are never needed, so they're capped there.
+pub fn max_alignment(size: usize) -> usize {
+    match size & size.wrapping_neg() {
+        0 => 8,
+        align => align.min(8),
+    }
+}
+
+/// Returns the members, base classes, and bitfields of a class or union of
+/// `size` bytes with `fields`, ordered by offset, with padding between them and
+/// after the last one
+pub fn layout<'a>(pdb: &'a ParsedPdb, fields: &[TypeId], size: usize) -> Vec<Slot<'a>> {
+    let mut members: Vec<(usize, Option<&'a str>, TypeId)> = fields
+        .iter()
+        .filter_map(|field| match &pdb.types[*field] {
+            Type::Member(member) => {
+                Some((member.offset, Some(&*member.name), member.underlying_type))
+            }
+            Type::BaseClass(base) => Some((base.offset, None, base.base_class)),
+            _ => None,
+        })
+        .collect();
+    members.sort_by_key(|(offset, _name, _ty)| *offset);
+
+    let mut slots = vec![];
+    let mut cursor = 0;
+    let mut index = 0;
+    while index < members.len() {
+        let (offset, name, ty) = members[index];
+        index += 1;
+
+        let slot = match (&pdb.types[ty], name) {
+            (Type::Bitfield(bitfield), Some(name)) => {
+                let mut bits = vec![Bits {
+                    name,
+                    len: bitfield.len,
+                    position: bitfield.position,
+                }];
+                while let Some(&(next_offset, Some(name), ty)) = members.get(index) {
+                    match &pdb.types[ty] {
+                        Type::Bitfield(next) if next_offset == offset => {
+                            bits.push(Bits {
+                                name,
+                                len: next.len,
+                                position: next.position,
+                            });
+                            index += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                Slot::Bitfields {
+                    offset,
+                    storage: bitfield.underlying_type,
+                    bits,
+                }
+            }
+            (_, Some(name)) if offset < cursor => {
+                slots.push(Slot::Overlapping { offset, name, ty });
+                continue;
+            }
+            // An empty base class shares its offset with the first member
+            (_, None) if offset < cursor => continue,
+            (_, Some(name)) => Slot::Member { offset, name, ty },
+            (_, None) => Slot::Base { offset, ty },
+        };
+
+        if offset > cursor {
+            slots.push(Slot::Padding {
+                offset: cursor,
+                len: offset - cursor,
+            });
+        }
+        cursor = cursor.max(offset + pdb.types[ty].type_size(pdb));
+        slots.push(slot);
+    }
+    if cursor < size {
+        slots.push(Slot::Padding {
+            offset: cursor,
+            len: size - cursor,
+        });
+    }
+
+    slots
+}
@@ -0,0 +1,428 @@
+//! Ordering the definitions of classes, unions, and enums so that each one
+//! comes after the types it depends upon. A type which contains another by
+//! value, inherits from it, or uses an enum needs its definition first. A type
+//! which only refers to another through pointers, references, or function
+//! signatures only needs it to have been declared, so it's forward declared
+//! instead, which is also how cycles between types are broken.
+use crate::symbol_types::ParsedPdb;
+use crate::type_info::{Type, TypeId};
+use std::collections::{HashMap, HashSet};
+
+/// A step of emitting types in order
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Item {
+    /// Forward declare the class or union, e.g. `struct Foo;`
+    Declare(TypeId),
+    /// Define the type
+    Define(TypeId),
+}
+
+/// The classes, unions, and enums a type depends upon
+#[derive(Debug, Clone, Default)]
+pub struct Dependencies {
+    /// Types which must be defined first: members and array elements held by
+    /// value, base classes, and enums
+    pub by_value: Vec<TypeId>,
+    /// Types which only need to have been declared, as they're only referred
+    /// to through pointers, references, or function signatures
+    pub by_reference: Vec<TypeId>,
+}
+
+/// Returns the order to emit the definitions of `types` in, with forward
+/// declarations where they're needed. Types are kept in the order given
+/// wherever their dependencies allow it. Dependencies which aren't among
+/// `types` are assumed to be defined elsewhere. Forward declarations given in
+/// `types` are replaced with their definitions, if the PDB has them.
+pub fn order(pdb: &ParsedPdb, types: &[TypeId]) -> Vec<Item> {
+    let definitions = Definitions::new(pdb);
+    let wanted: Vec<TypeId> = types.iter().map(|&id| definitions.resolve(id)).collect();
+
+    let mut ordering = Ordering {
+        pdb,
+        definitions: &definitions,
+        wanted: wanted.iter().copied().collect(),
+        items: vec![],
+        defined: HashSet::new(),
+        declared: HashSet::new(),
+        visiting: HashSet::new(),
+    };
+    for id in wanted {
+        ordering.define(id);
+    }
+
+    ordering.items
+}
+
+/// Returns the definitions of the classes, unions, and enums `id` depends upon.
+/// A type's dependencies upon itself are left out.
+pub fn dependencies(pdb: &ParsedPdb, id: TypeId) -> Dependencies {
+    let definitions = Definitions::new(pdb);
+    collect_dependencies(pdb, &definitions, definitions.resolve(id))
+}
+
+/// Maps the names of classes, unions, and enums to their definitions, so that
+/// references to forward declarations can be followed
+struct Definitions<'a> {
+    pdb: &'a ParsedPdb,
+    by_name: HashMap<&'a str, TypeId>,
+}
+
+impl<'a> Definitions<'a> {
+    fn new(pdb: &'a ParsedPdb) -> Self {
+        let mut by_name = HashMap::new();
+        for (id, ty) in pdb.types.iter() {
+            if let Some((name, false)) = named_type(ty) {
+                by_name.entry(name).or_insert(id);
+            }
+        }
+
+        Definitions { pdb, by_name }
+    }
+
+    /// Returns the definition of `id` if it's a forward declaration, or `id`
+    fn resolve(&self, id: TypeId) -> TypeId {
+        match self.pdb.types.get(id).and_then(named_type) {
+            Some((name, true)) => self.by_name.get(name).copied().unwrap_or(id),
+            _ => id,
+        }
+    }
+}
+
+/// Returns the unique name (or name) of a class, union, or enum and whether
+/// it's a forward declaration
+pub(crate) fn named_type(ty: &Type) -> Option<(&str, bool)> {
+    match ty {
+        Type::Class(class) => Some((
+            class.unique_name.as_deref().unwrap_or(&class.name),
+            class.properties.forward_reference,
+        )),
+        Type::Union(union) => Some((
+            union.unique_name.as_deref().unwrap_or(&union.name),
+            union.properties.forward_reference,
+        )),
+        Type::Enumeration(e) => Some((
+            e.unique_name.as_deref().unwrap_or(&e.name),
+            e.properties.forward_reference,
+        )),
+        _ => None,
+    }
+}
+
+fn collect_dependencies(
+    pdb: &ParsedPdb,
+    definitions: &Definitions<'_>,
+    id: TypeId,
+) -> Dependencies {
+    let mut by_value = vec![];
+    let mut by_reference = vec![];
+    let mut seen = HashSet::new();
+    // Each pending type is paired with whether it's reached by value
+    let mut pending: Vec<(TypeId, bool)> = match pdb.types.get(id) {
+        Some(ty) => ty
+            .referenced_types()
+            .into_iter()
+            .map(|id| (id, true))
+            .collect(),
+        None => vec![],
+    };
+    while let Some((next, by_val)) = pending.pop() {
+        if !seen.insert((next, by_val)) {
+            continue;
+        }
+        let ty = match pdb.types.get(next) {
+            Some(ty) => ty,
+            None => continue,
+        };
+
+        match ty {
+            Type::Class(_) | Type::Union(_) | Type::Enumeration(_) => {
+                let definition = definitions.resolve(next);
+                if definition == id {
+                    continue;
+                }
+                // Enums can't be forward declared in C, so they're always defined first
+                if by_val || matches!(ty, Type::Enumeration(_)) {
+                    by_value.push(definition);
+                } else {
+                    by_reference.push(definition);
+                }
+            }
+            Type::Pointer(_)
+            | Type::Procedure(_)
+            | Type::MemberFunction(_)
+            | Type::Method(_)
+            | Type::MethodList(_)
+            | Type::MethodListEntry(_)
+            | Type::OverloadedMethod(_)
+            | Type::Nested(_)
+            | Type::StaticMember(_)
+            | Type::VTable(_) => {
+                pending.extend(ty.referenced_types().into_iter().map(|id| (id, false)));
+            }
+            _ => pending.extend(ty.referenced_types().into_iter().map(|id| (id, by_val))),
+        }
+    }
+
+    for list in [&mut by_value, &mut by_reference] {
+        list.sort();
+        list.dedup();
+    }
+    by_reference.retain(|id| by_value.binary_search(id).is_err());
+
+    Dependencies {
+        by_value,
+        by_reference,
+    }
+}
+
+/// The state of a depth-first walk which emits each type after its dependencies
+struct Ordering<'a> {
+    pdb: &'a ParsedPdb,
+    definitions: &'a Definitions<'a>,
+    wanted: HashSet<TypeId>,
+    items: Vec<Item>,
+    defined: HashSet<TypeId>,
+    declared: HashSet<TypeId>,
+    /// Types whose dependencies are being emitted
+    visiting: HashSet<TypeId>,
+}
+
+impl Ordering<'_> {
+    fn define(&mut self, id: TypeId) {
+        if self.defined.contains(&id) || !self.visiting.insert(id) {
+            return;
+        }
+
+        let dependencies = collect_dependencies(self.pdb, self.definitions, id);
+        for &dependency in &dependencies.by_value {
+            if !self.wanted.contains(&dependency) {
+                continue;
+            }
+            if self.visiting.contains(&dependency) {
+                // Types can't contain each other by value, so the PDB is
+                // malformed. Declaring the type is the best that can be done.
+                self.declare(dependency);
+            } else {
+                self.define(dependency);
+            }
+        }
+        for &dependency in &dependencies.by_reference {
+            if self.wanted.contains(&dependency) && !self.defined.contains(&dependency) {
+                self.declare(dependency);
+            }
+        }
+
+        self.visiting.remove(&id);
+        self.defined.insert(id);
+        self.items.push(Item::Define(id));
+    }
+
+    fn declare(&mut self, id: TypeId) {
+        if self.declared.insert(id) {
+            self.items.push(Item::Declare(id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_info::*;
+    use std::path::PathBuf;
+
+    fn properties(forward_reference: bool) -> TypeProperties {
+        TypeProperties {
+            packed: false,
+            constructors: false,
+            overlapped_operators: false,
+            is_nested_type: false,
+            contains_nested_types: false,
+            overload_assignment: false,
+            overload_coasting: false,
+            forward_reference,
+            scoped_definition: false,
+            has_unique_name: true,
+            sealed: false,
+            hfa: 0,
+            intristic_type: false,
+            mocom: 0,
+        }
+    }
+
+    /// Builds the types of a PDB one at a time
+    struct Builder {
+        pdb: ParsedPdb,
+    }
+
+    impl Builder {
+        fn new() -> Self {
+            Builder {
+                pdb: ParsedPdb::new(PathBuf::from("test.pdb")),
+            }
+        }
+
+        fn int(&mut self) -> TypeId {
+            self.pdb.types.insert(Type::Primitive(Primitive {
+                kind: PrimitiveKind::I32,
+                indirection: None,
+            }))
+        }
+
+        fn pointer(&mut self, to: TypeId) -> TypeId {
+            self.pdb.types.insert(Type::Pointer(Pointer {
+                underlying_type: Some(to),
+                attributes: PointerAttributes {
+                    kind: PointerKind::Ptr64,
+                    is_volatile: false,
+                    is_const: false,
+                    is_unaligned: false,
+                    is_restrict: false,
+                    is_reference: false,
+                    is_rvalue_reference: false,
+                    size: 8,
+                    is_mocom: false,
+                },
+                member: None,
+            }))
+        }
+
+        fn member(&mut self, name: &str, ty: TypeId) -> TypeId {
+            self.pdb.types.insert(Type::Member(Member {
+                name: name.into(),
+                underlying_type: ty,
+                offset: 0,
+            }))
+        }
+
+        fn base(&mut self, base_class: TypeId) -> TypeId {
+            self.pdb.types.insert(Type::BaseClass(BaseClass {
+                kind: ClassKind::Struct,
+                base_class,
+                offset: 0,
+            }))
+        }
+
+        /// Adds a struct, or a forward declaration of one if `fields` is `None`
+        fn class(&mut self, name: &str, fields: Option<Vec<TypeId>>) -> TypeId {
+            self.pdb.types.insert(Type::Class(Class {
+                name: name.into(),
+                unique_name: Some(format!(".?AU{}@@", name).into()),
+                kind: ClassKind::Struct,
+                properties: properties(fields.is_none()),
+                derived_from: None,
+                fields: fields.unwrap_or_default(),
+                size: 8,
+                vtable_shape: None,
+                packing: None,
+            }))
+        }
+
+        /// Replaces the fields of the struct `id`, for types which refer to
+        /// each other
+        fn set_fields(&mut self, id: TypeId, fields: Vec<TypeId>) {
+            if let Type::Class(class) = &mut self.pdb.types[id] {
+                class.fields = fields;
+            }
+        }
+
+        fn enumeration(&mut self, name: &str) -> TypeId {
+            let underlying_type = self.int();
+            self.pdb.types.insert(Type::Enumeration(Enumeration {
+                name: name.into(),
+                unique_name: Some(format!(".?AW4{}@@", name).into()),
+                underlying_type,
+                variants: vec![],
+                properties: properties(false),
+            }))
+        }
+    }
+
+    fn position(items: &[Item], item: Item) -> usize {
+        items
+            .iter()
+            .position(|other| *other == item)
+            .unwrap_or_else(|| panic!("{:?} isn't in {:?}", item, items))
+    }
+
+    #[test]
+    fn pointer_cycle_is_broken_with_a_forward_declaration() {
+        // struct A { B *b; }; struct B { A *a; };
+        let mut builder = Builder::new();
+        let a = builder.class("A", Some(vec![]));
+        let b = builder.class("B", Some(vec![]));
+        let a_pointer = builder.pointer(a);
+        let b_pointer = builder.pointer(b);
+        let a_member = builder.member("a", a_pointer);
+        let b_member = builder.member("b", b_pointer);
+        builder.set_fields(a, vec![b_member]);
+        builder.set_fields(b, vec![a_member]);
+
+        let items = order(&builder.pdb, &[a, b]);
+        assert_eq!(items, [Item::Declare(b), Item::Define(a), Item::Define(b)]);
+    }
+
+    #[test]
+    fn types_held_by_value_or_inherited_are_defined_first() {
+        // struct Derived : Base { Inner inner; };
+        let mut builder = Builder::new();
+        let base = builder.class("Base", Some(vec![]));
+        let int = builder.int();
+        let x = builder.member("x", int);
+        let inner = builder.class("Inner", Some(vec![x]));
+        let base_field = builder.base(base);
+        let inner_member = builder.member("inner", inner);
+        let derived = builder.class("Derived", Some(vec![base_field, inner_member]));
+
+        let items = order(&builder.pdb, &[derived, inner, base]);
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|item| matches!(item, Item::Define(_))));
+        let derived_position = position(&items, Item::Define(derived));
+        assert!(position(&items, Item::Define(base)) < derived_position);
+        assert!(position(&items, Item::Define(inner)) < derived_position);
+
+        let dependencies = dependencies(&builder.pdb, derived);
+        assert_eq!(dependencies.by_value, [base, inner]);
+        assert!(dependencies.by_reference.is_empty());
+    }
+
+    #[test]
+    fn enums_used_as_fields_are_defined_first() {
+        // enum Color { ... }; struct Pixel { Color color; Color *previous; };
+        let mut builder = Builder::new();
+        let color = builder.enumeration("Color");
+        let color_pointer = builder.pointer(color);
+        let color_member = builder.member("color", color);
+        let previous_member = builder.member("previous", color_pointer);
+        let pixel = builder.class("Pixel", Some(vec![color_member, previous_member]));
+
+        let items = order(&builder.pdb, &[pixel, color]);
+        assert_eq!(items, [Item::Define(color), Item::Define(pixel)]);
+
+        // Enums can't be forward declared, even when only pointed to
+        let dependencies = dependencies(&builder.pdb, pixel);
+        assert_eq!(dependencies.by_value, [color]);
+        assert!(dependencies.by_reference.is_empty());
+    }
+
+    #[test]
+    fn forward_references_resolve_to_their_definitions() {
+        // struct Node; struct List { Node head; }; struct Node { int value; };
+        let mut builder = Builder::new();
+        let node_declaration = builder.class("Node", None);
+        let head = builder.member("head", node_declaration);
+        let list = builder.class("List", Some(vec![head]));
+        let int = builder.int();
+        let value = builder.member("value", int);
+        let node = builder.class("Node", Some(vec![value]));
+
+        assert_eq!(
+            order(&builder.pdb, &[node_declaration]),
+            [Item::Define(node)]
+        );
+        assert_eq!(
+            order(&builder.pdb, &[list, node_declaration]),
+            [Item::Define(node), Item::Define(list)]
+        );
+        assert_eq!(dependencies(&builder.pdb, list).by_value, [node]);
+    }
+}
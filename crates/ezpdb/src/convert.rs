@@ -0,0 +1,32 @@
+//! Conversions from the `pdb` crate's records into the [ezpdb_model] types. These
+//! mirror [From] and [TryFrom], which can't be implemented here since both the
+//! source tuples and the model types are defined in other crates.
+use crate::error::Error;
+
+pub(crate) trait FromPdb<T> {
+    fn from_pdb(value: T) -> Self;
+}
+
+pub(crate) trait TryFromPdb<T>: Sized {
+    fn try_from_pdb(value: T) -> Result<Self, Error>;
+}
+
+pub(crate) trait IntoModel<U> {
+    fn into_model(self) -> U;
+}
+
+impl<T, U: FromPdb<T>> IntoModel<U> for T {
+    fn into_model(self) -> U {
+        U::from_pdb(self)
+    }
+}
+
+pub(crate) trait TryIntoModel<U> {
+    fn try_into_model(self) -> Result<U, Error>;
+}
+
+impl<T, U: TryFromPdb<T>> TryIntoModel<U> for T {
+    fn try_into_model(self) -> Result<U, Error> {
+        U::try_from_pdb(self)
+    }
+}
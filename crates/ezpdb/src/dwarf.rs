@@ -0,0 +1,1146 @@
+//! Exporting a parsed PDB's types, functions, global variables, and line
+//! information as DWARF, in an ELF file which Linux tooling such as gdb,
+//! addr2line, and perf can load alongside a Windows binary
+use crate::address::AddressMapping;
+use crate::elf::{self, address_of, ElfSection};
+use crate::error::Error;
+use crate::symbol_types::ParsedPdb;
+use crate::type_info::{Class, ClassKind, Type, TypeId, Typed, Union};
+use crate::ParseOptions;
+use pdb::FallibleIterator;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+/// What [write_dwarf] exported
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DwarfSummary {
+    pub types: usize,
+    pub functions: usize,
+    pub variables: usize,
+    /// Rows of the line number program
+    pub lines: usize,
+    /// Entries of the ELF symbol table
+    pub symbols: usize,
+}
+
+const DW_TAG_ARRAY_TYPE: u16 = 0x01;
+const DW_TAG_CLASS_TYPE: u16 = 0x02;
+const DW_TAG_ENUMERATION_TYPE: u16 = 0x04;
+const DW_TAG_FORMAL_PARAMETER: u16 = 0x05;
+const DW_TAG_MEMBER: u16 = 0x0d;
+const DW_TAG_POINTER_TYPE: u16 = 0x0f;
+const DW_TAG_REFERENCE_TYPE: u16 = 0x10;
+const DW_TAG_COMPILE_UNIT: u16 = 0x11;
+const DW_TAG_STRUCTURE_TYPE: u16 = 0x13;
+const DW_TAG_SUBROUTINE_TYPE: u16 = 0x15;
+const DW_TAG_UNION_TYPE: u16 = 0x17;
+const DW_TAG_INHERITANCE: u16 = 0x1c;
+const DW_TAG_PTR_TO_MEMBER_TYPE: u16 = 0x1f;
+const DW_TAG_SUBRANGE_TYPE: u16 = 0x21;
+const DW_TAG_BASE_TYPE: u16 = 0x24;
+const DW_TAG_CONST_TYPE: u16 = 0x26;
+const DW_TAG_ENUMERATOR: u16 = 0x28;
+const DW_TAG_SUBPROGRAM: u16 = 0x2e;
+const DW_TAG_VARIABLE: u16 = 0x34;
+const DW_TAG_VOLATILE_TYPE: u16 = 0x35;
+const DW_TAG_RVALUE_REFERENCE_TYPE: u16 = 0x42;
+
+const DW_AT_LOCATION: u16 = 0x02;
+const DW_AT_NAME: u16 = 0x03;
+const DW_AT_BYTE_SIZE: u16 = 0x0b;
+const DW_AT_BIT_SIZE: u16 = 0x0d;
+const DW_AT_STMT_LIST: u16 = 0x10;
+const DW_AT_LOW_PC: u16 = 0x11;
+const DW_AT_HIGH_PC: u16 = 0x12;
+const DW_AT_LANGUAGE: u16 = 0x13;
+const DW_AT_CONST_VALUE: u16 = 0x1c;
+const DW_AT_CONTAINING_TYPE: u16 = 0x1d;
+const DW_AT_PRODUCER: u16 = 0x25;
+const DW_AT_PROTOTYPED: u16 = 0x27;
+const DW_AT_ARTIFICIAL: u16 = 0x34;
+const DW_AT_COUNT: u16 = 0x37;
+const DW_AT_DATA_MEMBER_LOCATION: u16 = 0x38;
+const DW_AT_DECL_FILE: u16 = 0x3a;
+const DW_AT_DECL_LINE: u16 = 0x3b;
+const DW_AT_DECLARATION: u16 = 0x3c;
+const DW_AT_ENCODING: u16 = 0x3e;
+const DW_AT_EXTERNAL: u16 = 0x3f;
+const DW_AT_TYPE: u16 = 0x49;
+const DW_AT_VIRTUALITY: u16 = 0x4c;
+const DW_AT_DATA_BIT_OFFSET: u16 = 0x6b;
+const DW_AT_LINKAGE_NAME: u16 = 0x6e;
+
+const DW_FORM_ADDR: u8 = 0x01;
+const DW_FORM_DATA2: u8 = 0x05;
+const DW_FORM_SDATA: u8 = 0x0d;
+const DW_FORM_STRP: u8 = 0x0e;
+const DW_FORM_UDATA: u8 = 0x0f;
+const DW_FORM_REF4: u8 = 0x13;
+const DW_FORM_SEC_OFFSET: u8 = 0x17;
+const DW_FORM_EXPRLOC: u8 = 0x18;
+const DW_FORM_FLAG_PRESENT: u8 = 0x19;
+
+const DW_ATE_BOOLEAN: u8 = 0x02;
+const DW_ATE_COMPLEX_FLOAT: u8 = 0x03;
+const DW_ATE_FLOAT: u8 = 0x04;
+const DW_ATE_SIGNED: u8 = 0x05;
+const DW_ATE_SIGNED_CHAR: u8 = 0x06;
+const DW_ATE_UNSIGNED: u8 = 0x07;
+const DW_ATE_UNSIGNED_CHAR: u8 = 0x08;
+const DW_ATE_UTF: u8 = 0x10;
+
+const DW_LANG_C_PLUS_PLUS: u16 = 0x0004;
+const DW_OP_ADDR: u8 = 0x03;
+const DW_VIRTUALITY_VIRTUAL: u64 = 1;
+
+const DW_LNS_COPY: u8 = 0x01;
+const DW_LNS_ADVANCE_PC: u8 = 0x02;
+const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+const DW_LNS_SET_FILE: u8 = 0x04;
+const DW_LNE_END_SEQUENCE: u8 = 0x01;
+const DW_LNE_SET_ADDRESS: u8 = 0x02;
+
+/// Version of the DWARF written
+const DWARF_VERSION: u16 = 4;
+/// Lengths of the operands of the standard line number opcodes
+const STANDARD_OPCODE_LENGTHS: [u8; 12] = [0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1];
+
+/// Writes the types, procedures, global variables, and line information of
+/// `pdb` as DWARF 4 to an ELF file at `output`, along with a symbol table of its
+/// procedures and public symbols. The image's sections are described as
+/// sections without contents so that symbols have an address to be placed at.
+///
+/// Line information isn't kept by [ParsedPdb], so it's read again from the PDB
+/// at [ParsedPdb::path] using `options`, which should be the options `pdb` was
+/// parsed with. Addresses are virtual addresses if `pdb` was parsed with a base
+/// address, and RVAs otherwise.
+pub fn write_dwarf<P: AsRef<Path>>(
+    pdb: &ParsedPdb,
+    options: &ParseOptions,
+    output: P,
+) -> Result<DwarfSummary, Error> {
+    let address_size = if elf::is_64bit(pdb) { 8 } else { 4 };
+
+    let line_rows = read_line_rows(pdb, options)?;
+    let mut files = FileTable::default();
+    for row in &line_rows {
+        files.index(&row.file);
+    }
+
+    let mut builder = DieBuilder::new(pdb);
+    let mut summary = DwarfSummary::default();
+    let mut children = builder.type_dies();
+    summary.types = children.len();
+
+    let mut ranges = vec![];
+    for procedure in &pdb.procedures {
+        let address = match procedure.address {
+            Some(address) => address_of(address),
+            None => continue,
+        };
+
+        let mut attributes = vec![(DW_AT_NAME, Value::String(procedure.name.clone()))];
+        if let Some(public_name) = procedure
+            .public_name
+            .as_ref()
+            .filter(|public_name| **public_name != procedure.name)
+        {
+            attributes.push((DW_AT_LINKAGE_NAME, Value::String(public_name.clone())));
+        }
+        if procedure.is_global {
+            attributes.push((DW_AT_EXTERNAL, Value::Flag));
+        }
+        attributes.push((DW_AT_LOW_PC, Value::Address(address)));
+        attributes.push((DW_AT_HIGH_PC, Value::Udata(procedure.len as u64)));
+        if let Some(source) = &procedure.source {
+            attributes.push((DW_AT_DECL_FILE, Value::Udata(files.index(&source.file))));
+            attributes.push((DW_AT_DECL_LINE, Value::Udata(u64::from(source.first_line))));
+        }
+
+        let signature = pdb
+            .type_indices
+            .get(&procedure.type_index)
+            .map(|&id| builder.signature(id))
+            .unwrap_or_default();
+        if let Some(return_type) = signature.return_type {
+            attributes.push((DW_AT_TYPE, Value::Reference(return_type)));
+        }
+        let parameters = signature
+            .parameters
+            .into_iter()
+            .enumerate()
+            .map(|(index, (parameter_type, artificial))| {
+                let mut attributes = vec![];
+                if let Some(name) = procedure.parameter_names.get(index) {
+                    attributes.push((DW_AT_NAME, Value::String(name.clone())));
+                }
+                if let Some(parameter_type) = parameter_type {
+                    attributes.push((DW_AT_TYPE, Value::Reference(parameter_type)));
+                }
+                if artificial {
+                    attributes.push((DW_AT_ARTIFICIAL, Value::Flag));
+                }
+                Die::new(DW_TAG_FORMAL_PARAMETER, attributes)
+            })
+            .collect();
+
+        children.push(Die::new(DW_TAG_SUBPROGRAM, attributes).with_children(parameters));
+        ranges.push((address, procedure.len as u64));
+        summary.functions += 1;
+    }
+
+    for data in pdb.global_data.iter().chain(&pdb.local_data) {
+        let address = match data.offset {
+            Some(address) => address_of(address),
+            None => continue,
+        };
+
+        let mut location = vec![DW_OP_ADDR];
+        location.extend_from_slice(&address.to_le_bytes()[..address_size as usize]);
+        let mut attributes = vec![(DW_AT_NAME, Value::String(data.name.clone()))];
+        if let Some(data_type) = builder.type_reference(data.ty) {
+            attributes.push((DW_AT_TYPE, Value::Reference(data_type)));
+        }
+        if data.is_global {
+            attributes.push((DW_AT_EXTERNAL, Value::Flag));
+        }
+        attributes.push((DW_AT_LOCATION, Value::Expression(location)));
+
+        children.push(Die::new(DW_TAG_VARIABLE, attributes));
+        summary.variables += 1;
+    }
+    children.extend(builder.base_type_dies());
+
+    let low_pc = ranges.iter().map(|&(start, _)| start).min().unwrap_or(0);
+    let high_pc = ranges
+        .iter()
+        .map(|&(start, len)| start + len)
+        .max()
+        .unwrap_or(low_pc);
+    let file_name = pdb
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let compile_unit = Die::new(
+        DW_TAG_COMPILE_UNIT,
+        vec![
+            (
+                DW_AT_PRODUCER,
+                Value::String(format!("pdbview {}", env!("CARGO_PKG_VERSION"))),
+            ),
+            (DW_AT_LANGUAGE, Value::Data2(DW_LANG_C_PLUS_PLUS)),
+            (DW_AT_NAME, Value::String(file_name)),
+            (DW_AT_STMT_LIST, Value::SectionOffset(0)),
+            (DW_AT_LOW_PC, Value::Address(low_pc)),
+            (DW_AT_HIGH_PC, Value::Udata(high_pc - low_pc)),
+        ],
+    )
+    .with_children(children);
+
+    let mut writer = InfoWriter::new(address_size);
+    writer.write_unit(&compile_unit);
+    let (line, lines) = line_program(&line_rows, &files, address_size);
+    summary.lines = lines;
+
+    let mut sections = elf::image_sections(pdb, options)?;
+    let symbols = elf::symbols(pdb);
+    summary.symbols = symbols.len();
+    sections.push(ElfSection::contents(".debug_abbrev", writer.abbreviations));
+    sections.push(ElfSection::contents(".debug_info", writer.info));
+    sections.push(ElfSection::contents(".debug_str", writer.strings.data));
+    sections.push(ElfSection::contents(".debug_line", line));
+    sections.push(ElfSection::contents(
+        ".debug_aranges",
+        address_ranges(&ranges, address_size),
+    ));
+
+    let elf = elf::write_elf(pdb, sections, &symbols);
+    std::fs::write(output, elf)?;
+
+    Ok(summary)
+}
+
+/// A row of a module's line information
+#[derive(Debug, Clone)]
+struct LineRow {
+    address: u64,
+    /// Number of bytes of code the row covers, if known
+    len: Option<u32>,
+    file: String,
+    line: u32,
+}
+
+/// Reads the line information of every module of the PDB `pdb` was parsed from
+fn read_line_rows(pdb: &ParsedPdb, options: &ParseOptions) -> Result<Vec<LineRow>, Error> {
+    let mut source = crate::open_pdb(&pdb.path)?;
+    let address_map = AddressMapping::new(&mut source, options);
+    let string_table = source.string_table().ok();
+    let base_address = options.base_address.unwrap_or(0) as u64;
+
+    let debug_information = source.debug_information()?;
+    let modules: Vec<_> = debug_information.modules()?.collect()?;
+    let mut rows = vec![];
+    for module in &modules {
+        let info = match source.module_info(module)? {
+            Some(info) => info,
+            None => continue,
+        };
+        let program = match info.line_program() {
+            Ok(program) => program,
+            Err(e) => {
+                warn!(
+                    "could not read line information of {}: {}",
+                    module.module_name(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        let mut file_names = HashMap::new();
+        let mut lines = program.lines();
+        while let Some(line) = lines.next()? {
+            let rva = match address_map.to_rva(line.offset) {
+                Some(rva) => rva,
+                None => continue,
+            };
+
+            let file = file_names.entry(line.file_index).or_insert_with(|| {
+                program
+                    .get_file_info(line.file_index)
+                    .ok()
+                    .zip(string_table.as_ref())
+                    .and_then(|(file, strings)| file.name.to_string_lossy(strings).ok())
+                    .map(|name| name.into_owned())
+            });
+            let file = match file {
+                Some(file) => file.clone(),
+                None => continue,
+            };
+
+            rows.push(LineRow {
+                address: base_address + u64::from(rva),
+                len: line.length,
+                file,
+                line: line.line_start,
+            });
+        }
+    }
+
+    rows.sort_by_key(|row| row.address);
+    rows.dedup_by_key(|row| row.address);
+
+    Ok(rows)
+}
+
+/// The source files of the line number program, numbered from 1
+#[derive(Debug, Default)]
+struct FileTable {
+    files: Vec<String>,
+    indices: HashMap<String, u64>,
+}
+
+impl FileTable {
+    /// Returns the number of `file`, adding it to the table if it's new
+    fn index(&mut self, file: &str) -> u64 {
+        if let Some(&index) = self.indices.get(file) {
+            return index;
+        }
+
+        self.files.push(file.to_string());
+        let index = self.files.len() as u64;
+        self.indices.insert(file.to_string(), index);
+        index
+    }
+}
+
+/// Identifies a DIE which other DIEs refer to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum DieKey {
+    /// The DIE of a type in the arena
+    Type(TypeId),
+    /// The `volatile` half of a type which is both `const` and `volatile`
+    Volatile(TypeId),
+    /// A base type, by name
+    Base(&'static str),
+}
+
+/// The value of a DIE attribute, which determines the form it's written in
+#[derive(Debug, Clone)]
+enum Value {
+    String(String),
+    Udata(u64),
+    Sdata(i64),
+    Data2(u16),
+    Address(u64),
+    Reference(DieKey),
+    SectionOffset(u32),
+    Expression(Vec<u8>),
+    Flag,
+}
+
+impl Value {
+    fn form(&self) -> u8 {
+        match self {
+            Value::String(_) => DW_FORM_STRP,
+            Value::Udata(_) => DW_FORM_UDATA,
+            Value::Sdata(_) => DW_FORM_SDATA,
+            Value::Data2(_) => DW_FORM_DATA2,
+            Value::Address(_) => DW_FORM_ADDR,
+            Value::Reference(_) => DW_FORM_REF4,
+            Value::SectionOffset(_) => DW_FORM_SEC_OFFSET,
+            Value::Expression(_) => DW_FORM_EXPRLOC,
+            Value::Flag => DW_FORM_FLAG_PRESENT,
+        }
+    }
+}
+
+/// A debugging information entry and its children
+#[derive(Debug, Clone)]
+struct Die {
+    key: Option<DieKey>,
+    tag: u16,
+    attributes: Vec<(u16, Value)>,
+    children: Vec<Die>,
+}
+
+impl Die {
+    fn new(tag: u16, attributes: Vec<(u16, Value)>) -> Self {
+        Die {
+            key: None,
+            tag,
+            attributes,
+            children: vec![],
+        }
+    }
+
+    fn with_key(mut self, key: DieKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    fn with_children(mut self, children: Vec<Die>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+/// The return and parameter types of a procedure type. Each parameter is
+/// paired with whether it's the implicit `this` parameter.
+#[derive(Debug, Default)]
+struct Signature {
+    return_type: Option<DieKey>,
+    parameters: Vec<(Option<DieKey>, bool)>,
+}
+
+/// Builds the DIEs of the types of a PDB
+struct DieBuilder<'a> {
+    pdb: &'a ParsedPdb,
+    /// The definition of each class, union, and enum by its unique name, or by
+    /// its name if it has none
+    definitions: HashMap<(u8, &'a str), TypeId>,
+    /// Base types referred to so far, with their encoding and size
+    base_types: HashMap<&'static str, (u8, usize)>,
+}
+
+impl<'a> DieBuilder<'a> {
+    fn new(pdb: &'a ParsedPdb) -> Self {
+        let mut definitions = HashMap::new();
+        for (id, typ) in pdb.types.iter() {
+            if let Some((key, false)) = Self::definition_key(typ) {
+                definitions.entry(key).or_insert(id);
+            }
+        }
+
+        DieBuilder {
+            pdb,
+            definitions,
+            base_types: HashMap::new(),
+        }
+    }
+
+    /// Returns the key classes, unions, and enums are matched to their
+    /// definition by, along with whether `typ` is a forward reference
+    fn definition_key(typ: &Type) -> Option<((u8, &str), bool)> {
+        let (kind, name, unique_name, properties) = match typ {
+            Type::Class(class) => (0, &class.name, &class.unique_name, &class.properties),
+            Type::Union(union) => (1, &union.name, &union.unique_name, &union.properties),
+            Type::Enumeration(e) => (2, &e.name, &e.unique_name, &e.properties),
+            _ => return None,
+        };
+
+        Some((
+            (kind, unique_name.as_deref().unwrap_or(name)),
+            properties.forward_reference,
+        ))
+    }
+
+    /// Returns the definition of `id` if it's a forward reference to a type
+    /// which is defined, and `id` otherwise
+    fn definition(&self, id: TypeId) -> TypeId {
+        match self.pdb.types.get(id).and_then(Self::definition_key) {
+            Some((key, true)) => self.definitions.get(&key).copied().unwrap_or(id),
+            _ => id,
+        }
+    }
+
+    /// Returns the DIE which describes the type `id`, or `None` if it's `void`
+    /// or has no DWARF equivalent
+    fn type_reference(&mut self, id: TypeId) -> Option<DieKey> {
+        let id = self.definition(id);
+        match self.pdb.types.get(id)? {
+            Type::Primitive(primitive) if primitive.indirection.is_none() => {
+                self.base_type(primitive.kind)
+            }
+            Type::Modifier(modifier) if !modifier.constant && !modifier.volatile => {
+                self.type_reference(modifier.underlying_type)
+            }
+            Type::Bitfield(bitfield) => self.type_reference(bitfield.underlying_type),
+            Type::Partial(partial) => partial
+                .referenced_type
+                .and_then(|referenced| self.type_reference(referenced)),
+            Type::Primitive(_)
+            | Type::Class(_)
+            | Type::Union(_)
+            | Type::Enumeration(_)
+            | Type::Pointer(_)
+            | Type::Array(_)
+            | Type::Modifier(_)
+            | Type::Procedure(_)
+            | Type::MemberFunction(_) => Some(DieKey::Type(id)),
+            _ => None,
+        }
+    }
+
+    /// Returns the DIE of the base type `kind`, or `None` if it's `void`
+    fn base_type(&mut self, kind: crate::type_info::PrimitiveKind) -> Option<DieKey> {
+        let (name, encoding) = base_type(kind)?;
+        self.base_types.insert(name, (encoding, kind.size()));
+        Some(DieKey::Base(name))
+    }
+
+    /// Returns the DIEs of the base types referred to
+    fn base_type_dies(&self) -> Vec<Die> {
+        let mut base_types: Vec<_> = self.base_types.iter().collect();
+        base_types.sort();
+
+        base_types
+            .into_iter()
+            .map(|(&name, &(encoding, size))| {
+                Die::new(
+                    DW_TAG_BASE_TYPE,
+                    vec![
+                        (DW_AT_NAME, Value::String(name.to_string())),
+                        (DW_AT_ENCODING, Value::Udata(u64::from(encoding))),
+                        (DW_AT_BYTE_SIZE, Value::Udata(size as u64)),
+                    ],
+                )
+                .with_key(DieKey::Base(name))
+            })
+            .collect()
+    }
+
+    /// Returns the return and parameter types of the procedure type `id`
+    fn signature(&mut self, id: TypeId) -> Signature {
+        let (return_type, this_type, arguments) = match &self.pdb.types[id] {
+            Type::Procedure(procedure) => (procedure.return_type, None, &procedure.argument_list),
+            Type::MemberFunction(function) => (
+                Some(function.return_type),
+                function.this_pointer_type,
+                &function.argument_list,
+            ),
+            _ => return Signature::default(),
+        };
+
+        let mut parameters = vec![];
+        if let Some(this_type) = this_type {
+            parameters.push((self.type_reference(this_type), true));
+        }
+        for &argument in arguments {
+            parameters.push((self.type_reference(argument), false));
+        }
+
+        Signature {
+            return_type: return_type.and_then(|return_type| self.type_reference(return_type)),
+            parameters,
+        }
+    }
+
+    /// Returns a DIE for each type in the arena which has a DWARF equivalent.
+    /// Forward references to types which are defined are left out in favor of
+    /// their definition.
+    fn type_dies(&mut self) -> Vec<Die> {
+        let pdb = self.pdb;
+        let mut dies = vec![];
+        for (id, typ) in pdb.types.iter() {
+            if self.definition(id) != id {
+                continue;
+            }
+
+            let die = match typ {
+                Type::Primitive(primitive) => match &primitive.indirection {
+                    Some(indirection) => Die::new(
+                        DW_TAG_POINTER_TYPE,
+                        with_type(
+                            vec![(DW_AT_BYTE_SIZE, Value::Udata(indirection.size() as u64))],
+                            self.base_type(primitive.kind),
+                        ),
+                    ),
+                    None => continue,
+                },
+                Type::Class(class) => self.class_die(class),
+                Type::Union(union) => self.union_die(union),
+                Type::Enumeration(e) => {
+                    let mut attributes = vec![(DW_AT_NAME, Value::String(e.name.to_string()))];
+                    let underlying_type = self.type_reference(e.underlying_type);
+                    attributes = with_type(attributes, underlying_type);
+                    if e.properties.forward_reference {
+                        attributes.push((DW_AT_DECLARATION, Value::Flag));
+                    } else {
+                        let size = pdb.types[e.underlying_type].type_size(pdb);
+                        attributes.push((DW_AT_BYTE_SIZE, Value::Udata(size as u64)));
+                    }
+
+                    let variants = e
+                        .variants
+                        .iter()
+                        .map(|variant| {
+                            Die::new(
+                                DW_TAG_ENUMERATOR,
+                                vec![
+                                    (DW_AT_NAME, Value::String(variant.name.to_string())),
+                                    (
+                                        DW_AT_CONST_VALUE,
+                                        Value::Sdata(variant.value.as_u64() as i64),
+                                    ),
+                                ],
+                            )
+                        })
+                        .collect();
+                    Die::new(DW_TAG_ENUMERATION_TYPE, attributes).with_children(variants)
+                }
+                Type::Pointer(pointer) => {
+                    let attributes = &pointer.attributes;
+                    let tag = if pointer.member.is_some() {
+                        DW_TAG_PTR_TO_MEMBER_TYPE
+                    } else if attributes.is_rvalue_reference {
+                        DW_TAG_RVALUE_REFERENCE_TYPE
+                    } else if attributes.is_reference {
+                        DW_TAG_REFERENCE_TYPE
+                    } else {
+                        DW_TAG_POINTER_TYPE
+                    };
+
+                    let mut die_attributes = vec![];
+                    if attributes.size > 0 {
+                        die_attributes
+                            .push((DW_AT_BYTE_SIZE, Value::Udata(attributes.size as u64)));
+                    }
+                    let pointee = pointer
+                        .underlying_type
+                        .and_then(|pointee| self.type_reference(pointee));
+                    die_attributes = with_type(die_attributes, pointee);
+                    if let Some(class) = pointer
+                        .member
+                        .as_ref()
+                        .and_then(|member| member.containing_class)
+                        .and_then(|class| self.type_reference(class))
+                    {
+                        die_attributes.push((DW_AT_CONTAINING_TYPE, Value::Reference(class)));
+                    }
+                    Die::new(tag, die_attributes)
+                }
+                Type::Array(array) => {
+                    let element_type = self.type_reference(array.element_type);
+                    let dimensions = array
+                        .dimensions_elements
+                        .iter()
+                        .rev()
+                        .map(|&count| {
+                            Die::new(
+                                DW_TAG_SUBRANGE_TYPE,
+                                vec![(DW_AT_COUNT, Value::Udata(count as u64))],
+                            )
+                        })
+                        .collect();
+                    Die::new(DW_TAG_ARRAY_TYPE, with_type(vec![], element_type))
+                        .with_children(dimensions)
+                }
+                Type::Modifier(modifier) if modifier.constant || modifier.volatile => {
+                    let underlying_type = self.type_reference(modifier.underlying_type);
+                    if modifier.constant && modifier.volatile {
+                        dies.push(
+                            Die::new(DW_TAG_VOLATILE_TYPE, with_type(vec![], underlying_type))
+                                .with_key(DieKey::Volatile(id)),
+                        );
+                        Die::new(
+                            DW_TAG_CONST_TYPE,
+                            vec![(DW_AT_TYPE, Value::Reference(DieKey::Volatile(id)))],
+                        )
+                    } else if modifier.constant {
+                        Die::new(DW_TAG_CONST_TYPE, with_type(vec![], underlying_type))
+                    } else {
+                        Die::new(DW_TAG_VOLATILE_TYPE, with_type(vec![], underlying_type))
+                    }
+                }
+                Type::Procedure(_) | Type::MemberFunction(_) => {
+                    let signature = self.signature(id);
+                    let parameters = signature
+                        .parameters
+                        .into_iter()
+                        .map(|(parameter_type, artificial)| {
+                            let mut attributes = with_type(vec![], parameter_type);
+                            if artificial {
+                                attributes.push((DW_AT_ARTIFICIAL, Value::Flag));
+                            }
+                            Die::new(DW_TAG_FORMAL_PARAMETER, attributes)
+                        })
+                        .collect();
+                    Die::new(
+                        DW_TAG_SUBROUTINE_TYPE,
+                        with_type(vec![(DW_AT_PROTOTYPED, Value::Flag)], signature.return_type),
+                    )
+                    .with_children(parameters)
+                }
+                _ => continue,
+            };
+
+            dies.push(die.with_key(DieKey::Type(id)));
+        }
+
+        dies
+    }
+
+    fn class_die(&mut self, class: &Class) -> Die {
+        let tag = match class.kind {
+            ClassKind::Class => DW_TAG_CLASS_TYPE,
+            ClassKind::Struct | ClassKind::Interface => DW_TAG_STRUCTURE_TYPE,
+        };
+        self.aggregate_die(
+            tag,
+            &class.name,
+            class.size,
+            class.properties.forward_reference,
+            &class.fields,
+        )
+    }
+
+    fn union_die(&mut self, union: &Union) -> Die {
+        self.aggregate_die(
+            DW_TAG_UNION_TYPE,
+            &union.name,
+            union.size,
+            union.properties.forward_reference,
+            &union.fields,
+        )
+    }
+
+    /// Returns the DIE of a class or union with its data members and base classes
+    fn aggregate_die(
+        &mut self,
+        tag: u16,
+        name: &str,
+        size: usize,
+        forward_reference: bool,
+        fields: &[TypeId],
+    ) -> Die {
+        let mut attributes = vec![];
+        if !crate::type_info::is_anonymous_name(name) {
+            attributes.push((DW_AT_NAME, Value::String(name.to_string())));
+        }
+        if forward_reference {
+            attributes.push((DW_AT_DECLARATION, Value::Flag));
+            return Die::new(tag, attributes);
+        }
+        attributes.push((DW_AT_BYTE_SIZE, Value::Udata(size as u64)));
+
+        let mut children = vec![];
+        for &field in fields {
+            let child = match &self.pdb.types[field] {
+                Type::Member(member) => {
+                    let mut attributes = vec![(DW_AT_NAME, Value::String(member.name.to_string()))];
+                    let member_type = self.type_reference(member.underlying_type);
+                    attributes = with_type(attributes, member_type);
+                    match &self.pdb.types[member.underlying_type] {
+                        Type::Bitfield(bitfield) => {
+                            attributes.push((DW_AT_BIT_SIZE, Value::Udata(bitfield.len as u64)));
+                            attributes.push((
+                                DW_AT_DATA_BIT_OFFSET,
+                                Value::Udata((member.offset * 8 + bitfield.position) as u64),
+                            ));
+                        }
+                        _ => attributes.push((
+                            DW_AT_DATA_MEMBER_LOCATION,
+                            Value::Udata(member.offset as u64),
+                        )),
+                    }
+                    Die::new(DW_TAG_MEMBER, attributes)
+                }
+                Type::StaticMember(member) => {
+                    let mut attributes = vec![(DW_AT_NAME, Value::String(member.name.to_string()))];
+                    let member_type = self.type_reference(member.field_type);
+                    attributes = with_type(attributes, member_type);
+                    attributes.push((DW_AT_EXTERNAL, Value::Flag));
+                    attributes.push((DW_AT_DECLARATION, Value::Flag));
+                    Die::new(DW_TAG_MEMBER, attributes)
+                }
+                Type::BaseClass(base) => {
+                    let base_type = self.type_reference(base.base_class);
+                    Die::new(
+                        DW_TAG_INHERITANCE,
+                        with_type(
+                            vec![(DW_AT_DATA_MEMBER_LOCATION, Value::Udata(base.offset as u64))],
+                            base_type,
+                        ),
+                    )
+                }
+                Type::VirtualBaseClass(base) if base.direct => {
+                    let base_type = self.type_reference(base.base_class);
+                    let mut attributes = with_type(vec![], base_type);
+                    attributes.push((DW_AT_VIRTUALITY, Value::Udata(DW_VIRTUALITY_VIRTUAL)));
+                    Die::new(DW_TAG_INHERITANCE, attributes)
+                }
+                _ => continue,
+            };
+            children.push(child);
+        }
+
+        Die::new(tag, attributes).with_children(children)
+    }
+}
+
+/// Appends a `DW_AT_type` referring to `typ` to `attributes`, unless the type is `void`
+fn with_type(mut attributes: Vec<(u16, Value)>, typ: Option<DieKey>) -> Vec<(u16, Value)> {
+    if let Some(typ) = typ {
+        attributes.push((DW_AT_TYPE, Value::Reference(typ)));
+    }
+
+    attributes
+}
+
+/// Returns the name and `DW_ATE_*` encoding of the base type `kind`, or `None` if it's `void`
+fn base_type(kind: crate::type_info::PrimitiveKind) -> Option<(&'static str, u8)> {
+    use crate::type_info::PrimitiveKind;
+
+    let base_type = match kind {
+        PrimitiveKind::NoType | PrimitiveKind::Void => return None,
+        PrimitiveKind::Char | PrimitiveKind::RChar => ("char", DW_ATE_SIGNED_CHAR),
+        PrimitiveKind::UChar | PrimitiveKind::U8 => ("unsigned char", DW_ATE_UNSIGNED_CHAR),
+        PrimitiveKind::I8 => ("signed char", DW_ATE_SIGNED_CHAR),
+        PrimitiveKind::WChar => ("wchar_t", DW_ATE_UTF),
+        PrimitiveKind::RChar16 => ("char16_t", DW_ATE_UTF),
+        PrimitiveKind::RChar32 => ("char32_t", DW_ATE_UTF),
+        PrimitiveKind::Short | PrimitiveKind::I16 => ("short", DW_ATE_SIGNED),
+        PrimitiveKind::UShort | PrimitiveKind::U16 => ("unsigned short", DW_ATE_UNSIGNED),
+        PrimitiveKind::Long => ("long", DW_ATE_SIGNED),
+        PrimitiveKind::ULong => ("unsigned long", DW_ATE_UNSIGNED),
+        PrimitiveKind::I32 => ("int", DW_ATE_SIGNED),
+        PrimitiveKind::U32 => ("unsigned int", DW_ATE_UNSIGNED),
+        PrimitiveKind::Quad | PrimitiveKind::I64 => ("long long", DW_ATE_SIGNED),
+        PrimitiveKind::UQuad | PrimitiveKind::U64 => ("unsigned long long", DW_ATE_UNSIGNED),
+        PrimitiveKind::Octa | PrimitiveKind::I128 => ("__int128", DW_ATE_SIGNED),
+        PrimitiveKind::UOcta | PrimitiveKind::U128 => ("unsigned __int128", DW_ATE_UNSIGNED),
+        PrimitiveKind::F16 => ("_Float16", DW_ATE_FLOAT),
+        PrimitiveKind::F32 | PrimitiveKind::F32PP => ("float", DW_ATE_FLOAT),
+        PrimitiveKind::F48 => ("__float48", DW_ATE_FLOAT),
+        PrimitiveKind::F64 => ("double", DW_ATE_FLOAT),
+        PrimitiveKind::F80 => ("long double", DW_ATE_FLOAT),
+        PrimitiveKind::F128 => ("__float128", DW_ATE_FLOAT),
+        PrimitiveKind::Complex32 => ("__complex32", DW_ATE_COMPLEX_FLOAT),
+        PrimitiveKind::Complex64 => ("__complex64", DW_ATE_COMPLEX_FLOAT),
+        PrimitiveKind::Complex80 => ("__complex80", DW_ATE_COMPLEX_FLOAT),
+        PrimitiveKind::Complex128 => ("__complex128", DW_ATE_COMPLEX_FLOAT),
+        PrimitiveKind::Bool8 => ("bool", DW_ATE_BOOLEAN),
+        PrimitiveKind::Bool16 => ("__bool16", DW_ATE_BOOLEAN),
+        PrimitiveKind::Bool32 => ("BOOL", DW_ATE_SIGNED),
+        PrimitiveKind::Bool64 => ("__bool64", DW_ATE_BOOLEAN),
+        PrimitiveKind::HRESULT => ("HRESULT", DW_ATE_SIGNED),
+    };
+
+    Some(base_type)
+}
+
+/// The `.debug_str` section, with each string stored once
+#[derive(Debug, Default)]
+struct StringSection {
+    data: Vec<u8>,
+    offsets: HashMap<String, u32>,
+}
+
+impl StringSection {
+    fn offset(&mut self, s: &str) -> u32 {
+        if let Some(&offset) = self.offsets.get(s) {
+            return offset;
+        }
+
+        let offset = self.data.len() as u32;
+        self.data.extend_from_slice(s.as_bytes());
+        self.data.push(0);
+        self.offsets.insert(s.to_string(), offset);
+        offset
+    }
+}
+
+/// The tag of an abbreviation, whether it has children, and the form of each attribute
+type AbbreviationShape = (u16, bool, Vec<(u16, u8)>);
+
+/// Encodes DIEs into the `.debug_info`, `.debug_abbrev`, and `.debug_str` sections
+struct InfoWriter {
+    address_size: u8,
+    info: Vec<u8>,
+    abbreviations: Vec<u8>,
+    abbreviation_codes: HashMap<AbbreviationShape, u64>,
+    strings: StringSection,
+    /// Offset of each DIE which can be referred to
+    offsets: HashMap<DieKey, u32>,
+    /// Where each reference is written, to be filled in once every DIE's offset is known
+    references: Vec<(usize, DieKey)>,
+}
+
+impl InfoWriter {
+    fn new(address_size: u8) -> Self {
+        InfoWriter {
+            address_size,
+            info: vec![],
+            abbreviations: vec![],
+            abbreviation_codes: HashMap::new(),
+            strings: StringSection::default(),
+            offsets: HashMap::new(),
+            references: vec![],
+        }
+    }
+
+    /// Writes the unit header and `root` with its children
+    fn write_unit(&mut self, root: &Die) {
+        // The unit length is filled in last
+        self.info.extend_from_slice(&0u32.to_le_bytes());
+        self.info.extend_from_slice(&DWARF_VERSION.to_le_bytes());
+        // Offset of the abbreviations
+        self.info.extend_from_slice(&0u32.to_le_bytes());
+        self.info.push(self.address_size);
+
+        self.write_die(root);
+        // End of the abbreviations
+        self.abbreviations.push(0);
+
+        let unit_length = (self.info.len() - 4) as u32;
+        self.info[..4].copy_from_slice(&unit_length.to_le_bytes());
+
+        for (position, key) in std::mem::take(&mut self.references) {
+            // References to DIEs which weren't written are a bug, but are left
+            // as 0 rather than writing an invalid file
+            let offset = self.offsets.get(&key).copied().unwrap_or_else(|| {
+                warn!("no DWARF entry was written for {:?}", key);
+                0
+            });
+            self.info[position..position + 4].copy_from_slice(&offset.to_le_bytes());
+        }
+    }
+
+    fn write_die(&mut self, die: &Die) {
+        if let Some(key) = die.key {
+            self.offsets.insert(key, self.info.len() as u32);
+        }
+
+        let has_children = !die.children.is_empty();
+        let shape: Vec<(u16, u8)> = die
+            .attributes
+            .iter()
+            .map(|(attribute, value)| (*attribute, value.form()))
+            .collect();
+        let code = self.abbreviation_code(die.tag, has_children, shape);
+        write_uleb128(&mut self.info, code);
+
+        for (_, value) in &die.attributes {
+            match value {
+                Value::String(s) => {
+                    let offset = self.strings.offset(s);
+                    self.info.extend_from_slice(&offset.to_le_bytes());
+                }
+                Value::Udata(value) => write_uleb128(&mut self.info, *value),
+                Value::Sdata(value) => write_sleb128(&mut self.info, *value),
+                Value::Data2(value) => self.info.extend_from_slice(&value.to_le_bytes()),
+                Value::Address(address) => self
+                    .info
+                    .extend_from_slice(&address.to_le_bytes()[..self.address_size as usize]),
+                Value::Reference(key) => {
+                    self.references.push((self.info.len(), *key));
+                    self.info.extend_from_slice(&0u32.to_le_bytes());
+                }
+                Value::SectionOffset(offset) => self.info.extend_from_slice(&offset.to_le_bytes()),
+                Value::Expression(expression) => {
+                    write_uleb128(&mut self.info, expression.len() as u64);
+                    self.info.extend_from_slice(expression);
+                }
+                Value::Flag => {}
+            }
+        }
+
+        if has_children {
+            for child in &die.children {
+                self.write_die(child);
+            }
+            self.info.push(0);
+        }
+    }
+
+    /// Returns the code of the abbreviation for DIEs of this shape, adding one if it's new
+    fn abbreviation_code(&mut self, tag: u16, has_children: bool, shape: Vec<(u16, u8)>) -> u64 {
+        let next_code = self.abbreviation_codes.len() as u64 + 1;
+        let key = (tag, has_children, shape);
+        if let Some(&code) = self.abbreviation_codes.get(&key) {
+            return code;
+        }
+
+        write_uleb128(&mut self.abbreviations, next_code);
+        write_uleb128(&mut self.abbreviations, u64::from(tag));
+        self.abbreviations.push(has_children as u8);
+        for &(attribute, form) in &key.2 {
+            write_uleb128(&mut self.abbreviations, u64::from(attribute));
+            write_uleb128(&mut self.abbreviations, u64::from(form));
+        }
+        self.abbreviations.extend_from_slice(&[0, 0]);
+        self.abbreviation_codes.insert(key, next_code);
+
+        next_code
+    }
+}
+
+/// Returns the `.debug_line` section for `rows`, and the number of rows in it.
+/// Each run of rows without gaps between them becomes a sequence.
+fn line_program(rows: &[LineRow], files: &FileTable, address_size: u8) -> (Vec<u8>, usize) {
+    let mut header = vec![
+        // Minimum instruction length and maximum operations per instruction
+        1,
+        1,
+        // `is_stmt` defaults to true
+        1,
+        // Line base and range
+        (-5i8) as u8,
+        14,
+        STANDARD_OPCODE_LENGTHS.len() as u8 + 1,
+    ];
+    header.extend_from_slice(&STANDARD_OPCODE_LENGTHS);
+    // No include directories
+    header.push(0);
+    for file in &files.files {
+        header.extend_from_slice(file.as_bytes());
+        // Directory index, modification time, and length
+        header.extend_from_slice(&[0, 0, 0, 0]);
+    }
+    header.push(0);
+
+    let mut program = vec![];
+    let set_address = |program: &mut Vec<u8>, address: u64| {
+        program.push(0);
+        write_uleb128(program, u64::from(address_size) + 1);
+        program.push(DW_LNE_SET_ADDRESS);
+        program.extend_from_slice(&address.to_le_bytes()[..address_size as usize]);
+    };
+    let end_sequence = |program: &mut Vec<u8>, row: &LineRow| {
+        program.push(DW_LNS_ADVANCE_PC);
+        write_uleb128(program, u64::from(row.len.unwrap_or(1).max(1)));
+        program.extend_from_slice(&[0, 1, DW_LNE_END_SEQUENCE]);
+    };
+
+    // The state machine's registers, which each sequence resets
+    let mut line = 1i64;
+    let mut file = 1;
+    let mut previous: Option<&LineRow> = None;
+    for row in rows {
+        if let Some(previous) = previous {
+            let is_gap = previous
+                .len
+                .is_some_and(|len| previous.address + u64::from(len) < row.address);
+            if is_gap {
+                end_sequence(&mut program, previous);
+                line = 1;
+                file = 1;
+            }
+        }
+
+        set_address(&mut program, row.address);
+        let row_file = files.indices[&row.file];
+        if row_file != file {
+            program.push(DW_LNS_SET_FILE);
+            write_uleb128(&mut program, row_file);
+            file = row_file;
+        }
+        if i64::from(row.line) != line {
+            program.push(DW_LNS_ADVANCE_LINE);
+            write_sleb128(&mut program, i64::from(row.line) - line);
+            line = i64::from(row.line);
+        }
+        program.push(DW_LNS_COPY);
+        previous = Some(row);
+    }
+    if let Some(previous) = previous {
+        end_sequence(&mut program, previous);
+    }
+
+    let mut section = vec![];
+    let unit_length = 2 + 4 + header.len() + program.len();
+    section.extend_from_slice(&(unit_length as u32).to_le_bytes());
+    section.extend_from_slice(&DWARF_VERSION.to_le_bytes());
+    section.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    section.extend_from_slice(&header);
+    section.extend_from_slice(&program);
+
+    (section, rows.len())
+}
+
+/// Returns the `.debug_aranges` section mapping each of `ranges` to the compile unit
+fn address_ranges(ranges: &[(u64, u64)], address_size: u8) -> Vec<u8> {
+    let address_size = address_size as usize;
+    let mut section = vec![0; 4];
+    section.extend_from_slice(&2u16.to_le_bytes());
+    // Offset of the compile unit, then the address and segment selector sizes
+    section.extend_from_slice(&0u32.to_le_bytes());
+    section.extend_from_slice(&[address_size as u8, 0]);
+    // Ranges are aligned to twice the address size
+    section.resize(
+        section.len().div_ceil(address_size * 2) * address_size * 2,
+        0,
+    );
+
+    let mut ranges = ranges.to_vec();
+    ranges.sort_unstable();
+    for (start, len) in ranges.into_iter().chain(std::iter::once((0, 0))) {
+        section.extend_from_slice(&start.to_le_bytes()[..address_size]);
+        section.extend_from_slice(&len.to_le_bytes()[..address_size]);
+    }
+
+    let unit_length = (section.len() - 4) as u32;
+    section[..4].copy_from_slice(&unit_length.to_le_bytes());
+    section
+}
+
+fn write_uleb128(data: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            data.push(byte);
+            return;
+        }
+        data.push(byte | 0x80);
+    }
+}
+
+fn write_sleb128(data: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            data.push(byte);
+            return;
+        }
+        data.push(byte | 0x80);
+    }
+}
@@ -0,0 +1,356 @@
+//! Writing ELF files describing a Windows image, for Linux tooling such as
+//! perf and gdb to resolve addresses in it by
+use crate::error::Error;
+use crate::symbol_types::{Address, MachineType, ParsedPdb};
+use crate::ParseOptions;
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::warn;
+
+/// `IMAGE_SCN_MEM_EXECUTE`
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+/// `IMAGE_SCN_MEM_WRITE`
+const IMAGE_SCN_MEM_WRITE: u32 = 0x8000_0000;
+
+/// Writes an ELF file to `output` holding only a symbol table of the functions
+/// of `pdb` and the image's sections, described without their contents. Perf
+/// and other tools which don't understand PDBs can resolve the image's
+/// functions by it. Addresses are virtual addresses if `pdb` was parsed with a
+/// base address, and RVAs otherwise. Returns the number of symbols written.
+pub fn write_symbol_table<P: AsRef<Path>>(
+    pdb: &ParsedPdb,
+    options: &ParseOptions,
+    output: P,
+) -> Result<usize, Error> {
+    let sections = image_sections(pdb, options)?;
+    let symbols: Vec<_> = symbols(pdb)
+        .into_iter()
+        .filter(|symbol| symbol.is_function)
+        .collect();
+
+    std::fs::write(output, write_elf(pdb, sections, &symbols))?;
+
+    Ok(symbols.len())
+}
+
+/// Returns the address ELF files refer to `address` by
+pub(crate) fn address_of(address: Address) -> u64 {
+    address.virtual_address.unwrap_or(address.rva) as u64
+}
+
+/// Returns whether the image is described by a 64-bit ELF file. Images of
+/// unknown machine types are assumed to be 64-bit.
+pub(crate) fn is_64bit(pdb: &ParsedPdb) -> bool {
+    !matches!(
+        pdb.machine_type,
+        Some(
+            MachineType::X86
+                | MachineType::Arm
+                | MachineType::ArmNT
+                | MachineType::Thumb
+                | MachineType::RiscV32
+        )
+    )
+}
+
+/// Returns the sections of the image. They're only known from the linker's
+/// symbols if it recorded them, so the PDB's copy of the image's section headers
+/// is read from [ParsedPdb::path] otherwise.
+pub(crate) fn image_sections(
+    pdb: &ParsedPdb,
+    options: &ParseOptions,
+) -> Result<Vec<ElfSection>, Error> {
+    if !pdb.sections.is_empty() {
+        return Ok(pdb
+            .sections
+            .iter()
+            .map(|section| {
+                ElfSection::image(
+                    &section.name,
+                    address_of(section.offset),
+                    section.len as u64,
+                    section.characteristics,
+                )
+            })
+            .collect());
+    }
+
+    let mut source = crate::open_pdb(&pdb.path)?;
+    let headers = match source.sections() {
+        Ok(headers) => headers.unwrap_or_default(),
+        Err(e) => {
+            warn!("could not read the section headers: {}", e);
+            vec![]
+        }
+    };
+    let base_address = options.base_address.unwrap_or(0) as u64;
+
+    Ok(headers
+        .iter()
+        .map(|header| {
+            ElfSection::image(
+                header.name(),
+                base_address + u64::from(header.virtual_address),
+                u64::from(header.virtual_size),
+                header.characteristics.0,
+            )
+        })
+        .collect())
+}
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_NOBITS: u32 = 8;
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const STB_GLOBAL: u8 = 1;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const SHN_ABS: u16 = 0xfff1;
+/// `ET_EXEC`, as addresses are absolute
+const ET_EXEC: u16 = 2;
+
+/// A section of the ELF file
+#[derive(Debug)]
+pub(crate) struct ElfSection {
+    name: String,
+    kind: u32,
+    flags: u64,
+    address: u64,
+    /// Size of a section without contents
+    size: u64,
+    data: Vec<u8>,
+}
+
+impl ElfSection {
+    /// A section of the image with the characteristics of its PE section
+    /// header, described without its contents
+    pub(crate) fn image(name: &str, address: u64, size: u64, characteristics: u32) -> Self {
+        let mut flags = SHF_ALLOC;
+        if characteristics & IMAGE_SCN_MEM_EXECUTE != 0 {
+            flags |= SHF_EXECINSTR;
+        }
+        if characteristics & IMAGE_SCN_MEM_WRITE != 0 {
+            flags |= SHF_WRITE;
+        }
+
+        ElfSection {
+            name: name.to_string(),
+            kind: SHT_NOBITS,
+            flags,
+            address,
+            size,
+            data: vec![],
+        }
+    }
+
+    /// A section which isn't part of the image, e.g. debug information
+    pub(crate) fn contents(name: &str, data: Vec<u8>) -> Self {
+        ElfSection {
+            name: name.to_string(),
+            kind: SHT_PROGBITS,
+            flags: 0,
+            address: 0,
+            size: data.len() as u64,
+            data,
+        }
+    }
+}
+
+/// A function or variable of the ELF symbol table
+#[derive(Debug)]
+pub(crate) struct ElfSymbol {
+    name: String,
+    address: u64,
+    size: u64,
+    is_function: bool,
+}
+
+/// Returns the procedures and the public symbols which aren't procedures, by address
+pub(crate) fn symbols(pdb: &ParsedPdb) -> Vec<ElfSymbol> {
+    let mut symbols: Vec<ElfSymbol> = pdb
+        .procedures
+        .iter()
+        .filter_map(|procedure| {
+            Some(ElfSymbol {
+                name: procedure.name.clone(),
+                address: address_of(procedure.address?),
+                size: procedure.len as u64,
+                is_function: true,
+            })
+        })
+        .collect();
+
+    let procedure_addresses: HashSet<u64> = symbols.iter().map(|symbol| symbol.address).collect();
+    symbols.extend(pdb.public_symbols.iter().filter_map(|symbol| {
+        let address = address_of(symbol.offset?);
+        if procedure_addresses.contains(&address) {
+            return None;
+        }
+
+        Some(ElfSymbol {
+            name: symbol.name.clone(),
+            address,
+            size: 0,
+            is_function: symbol.is_function || symbol.is_code,
+        })
+    }));
+
+    symbols.sort_by_key(|symbol| symbol.address);
+    symbols
+}
+
+/// Returns the `e_machine` of the ELF file for the PDB's machine type
+fn machine(machine_type: Option<&MachineType>) -> u16 {
+    match machine_type {
+        Some(MachineType::X86) => 3,
+        Some(MachineType::Amd64) => 62,
+        Some(MachineType::Arm | MachineType::ArmNT | MachineType::Thumb) => 40,
+        Some(MachineType::Arm64) => 183,
+        Some(MachineType::Ia64) => 50,
+        Some(MachineType::RiscV32 | MachineType::RiscV64 | MachineType::RiscV128) => 243,
+        _ => 0,
+    }
+}
+
+/// Lays out an ELF file of `sections`, followed by a symbol table of
+/// `symbols` and the string tables
+pub(crate) fn write_elf(
+    pdb: &ParsedPdb,
+    mut sections: Vec<ElfSection>,
+    symbols: &[ElfSymbol],
+) -> Vec<u8> {
+    let is_64bit = is_64bit(pdb);
+    let word = |data: &mut Vec<u8>, value: u64| {
+        if is_64bit {
+            data.extend_from_slice(&value.to_le_bytes());
+        } else {
+            data.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+    };
+
+    // Section indices are 1-based, as index 0 is the null section
+    let image_sections: Vec<(u64, u64, u16)> = sections
+        .iter()
+        .enumerate()
+        .filter(|(_, section)| section.kind == SHT_NOBITS)
+        .map(|(index, section)| {
+            (
+                section.address,
+                section.address + section.size,
+                index as u16 + 1,
+            )
+        })
+        .collect();
+
+    let mut names = vec![0];
+    let mut symbol_table = vec![0; if is_64bit { 24 } else { 16 }];
+    for symbol in symbols {
+        let name = names.len() as u32;
+        names.extend_from_slice(symbol.name.as_bytes());
+        names.push(0);
+
+        let section = image_sections
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&symbol.address))
+            .map_or(SHN_ABS, |&(_, _, index)| index);
+        let kind = if symbol.is_function {
+            STT_FUNC
+        } else {
+            STT_OBJECT
+        };
+        let info = (STB_GLOBAL << 4) | kind;
+
+        symbol_table.extend_from_slice(&name.to_le_bytes());
+        if is_64bit {
+            symbol_table.extend_from_slice(&[info, 0]);
+            symbol_table.extend_from_slice(&section.to_le_bytes());
+            symbol_table.extend_from_slice(&symbol.address.to_le_bytes());
+            symbol_table.extend_from_slice(&symbol.size.to_le_bytes());
+        } else {
+            symbol_table.extend_from_slice(&(symbol.address as u32).to_le_bytes());
+            symbol_table.extend_from_slice(&(symbol.size as u32).to_le_bytes());
+            symbol_table.extend_from_slice(&[info, 0]);
+            symbol_table.extend_from_slice(&section.to_le_bytes());
+        }
+    }
+
+    let symbol_table_index = sections.len() + 1;
+    sections.push(ElfSection::contents(".symtab", symbol_table));
+    sections[symbol_table_index - 1].kind = SHT_SYMTAB;
+    sections.push(ElfSection::contents(".strtab", names));
+    sections[symbol_table_index].kind = SHT_STRTAB;
+
+    let mut section_names = vec![0];
+    let mut name_offsets = vec![];
+    for section in &sections {
+        name_offsets.push(section_names.len() as u32);
+        section_names.extend_from_slice(section.name.as_bytes());
+        section_names.push(0);
+    }
+    name_offsets.push(section_names.len() as u32);
+    section_names.extend_from_slice(b".shstrtab\0");
+    sections.push(ElfSection::contents(".shstrtab", section_names));
+    let names_index = sections.len();
+    sections[names_index - 1].kind = SHT_STRTAB;
+
+    let header_size = if is_64bit { 64 } else { 52 };
+    let section_header_size = if is_64bit { 64 } else { 40 };
+    let mut file = vec![0; header_size];
+    let mut offsets = vec![];
+    for section in &sections {
+        file.resize(file.len().div_ceil(8) * 8, 0);
+        offsets.push(file.len() as u64);
+        file.extend_from_slice(&section.data);
+    }
+    file.resize(file.len().div_ceil(8) * 8, 0);
+    let section_headers_offset = file.len() as u64;
+
+    // The null section
+    file.resize(file.len() + section_header_size, 0);
+    for (index, section) in sections.iter().enumerate() {
+        let (link, info, entry_size): (u32, u32, u64) = match section.kind {
+            SHT_SYMTAB => (
+                symbol_table_index as u32 + 1,
+                1,
+                if is_64bit { 24 } else { 16 },
+            ),
+            _ => (0, 0, 0),
+        };
+
+        file.extend_from_slice(&name_offsets[index].to_le_bytes());
+        file.extend_from_slice(&section.kind.to_le_bytes());
+        word(&mut file, section.flags);
+        word(&mut file, section.address);
+        word(&mut file, offsets[index]);
+        word(&mut file, section.size);
+        file.extend_from_slice(&link.to_le_bytes());
+        file.extend_from_slice(&info.to_le_bytes());
+        word(&mut file, 1);
+        word(&mut file, entry_size);
+    }
+
+    let mut header = vec![0x7f, b'E', b'L', b'F'];
+    // Class, little endian, version 1, and the System V ABI
+    header.extend_from_slice(&[if is_64bit { 2 } else { 1 }, 1, 1, 0]);
+    header.resize(16, 0);
+    header.extend_from_slice(&ET_EXEC.to_le_bytes());
+    header.extend_from_slice(&machine(pdb.machine_type.as_ref()).to_le_bytes());
+    header.extend_from_slice(&1u32.to_le_bytes());
+    // No entry point or program headers
+    word(&mut header, 0);
+    word(&mut header, 0);
+    word(&mut header, section_headers_offset);
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.extend_from_slice(&(header_size as u16).to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(&(section_header_size as u16).to_le_bytes());
+    header.extend_from_slice(&(sections.len() as u16 + 1).to_le_bytes());
+    header.extend_from_slice(&(names_index as u16).to_le_bytes());
+    file[..header.len()].copy_from_slice(&header);
+
+    file
+}
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::symbol_types::TypeIndexNumber;
+use crate::symbol_types::{Diagnostic, TypeIndexNumber};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -24,4 +24,33 @@ pub enum Error {
 
     #[error("could not resolve type index {0}")]
     UnresolvedType(TypeIndexNumber),
+
+    #[error("stopped at a record which couldn't be parsed: {0}")]
+    Stopped(Diagnostic),
+
+    #[error("the executable is not a valid PE image: {0}")]
+    InvalidImage(&'static str),
+}
+
+impl Error {
+    /// Returns whether a file couldn't be read
+    pub fn is_io(&self) -> bool {
+        matches!(
+            self,
+            Error::IoError(_) | Error::PdbCrateError(pdb::Error::IoError(_))
+        )
+    }
+
+    /// Returns whether the input isn't a PDB or executable, or uses a format
+    /// which isn't supported
+    pub fn is_unsupported_format(&self) -> bool {
+        matches!(
+            self,
+            Error::Unsupported(_)
+                | Error::InvalidImage(_)
+                | Error::PdbCrateError(pdb::Error::UnrecognizedFileFormat)
+                | Error::PdbCrateError(pdb::Error::InvalidPageSize(_))
+                | Error::PdbCrateError(pdb::Error::UnimplementedFeature(_))
+        )
+    }
 }
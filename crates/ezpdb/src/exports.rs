@@ -0,0 +1,193 @@
+//! Comparing a PE image's export table with the symbols of its PDB, to catch
+//! PDBs which don't belong to the image or were written by an earlier build
+use crate::error::Error;
+use crate::raw;
+use crate::symbol_types::{Address, ParsedPdb};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// How an export and the PDB disagree
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ExportMismatchKind {
+    /// The image exports a symbol which the PDB has no symbol for, neither by
+    /// name nor at its address
+    MissingSymbol,
+    /// The PDB has symbols by the export's name, but none where the image's
+    /// export table says the symbol is
+    AddressMismatch,
+    /// The PDB records the export under a different ordinal
+    OrdinalMismatch,
+    /// The PDB records an export which the image doesn't have
+    MissingExport,
+}
+
+impl std::fmt::Display for ExportMismatchKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportMismatchKind::MissingSymbol => write!(f, "missing-symbol"),
+            ExportMismatchKind::AddressMismatch => write!(f, "address-mismatch"),
+            ExportMismatchKind::OrdinalMismatch => write!(f, "ordinal-mismatch"),
+            ExportMismatchKind::MissingExport => write!(f, "missing-export"),
+        }
+    }
+}
+
+/// An export which the image and PDB disagree about
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExportMismatch {
+    pub kind: ExportMismatchKind,
+    /// Name of the export. `None` if it's only exported by ordinal.
+    pub name: Option<String>,
+    /// The export's ordinal in the image's export table
+    pub image_ordinal: Option<u32>,
+    /// Where the image's export table says the symbol is
+    pub image_address: Option<Address>,
+    /// The export's ordinal in the PDB's record of the exports
+    pub pdb_ordinal: Option<u32>,
+    /// Where the PDB's symbols by the export's name are
+    pub pdb_addresses: Vec<Address>,
+}
+
+/// Compares the export table of `image` with the symbols of `pdb`, which must
+/// be the PDB the image was linked with. Reports exports which no public
+/// symbol, procedure, or global of the PDB is found for, by name or address,
+/// and the reverse: exports the linker recorded in the PDB (`S_EXPORT`) which
+/// aren't in the image's export table. Mismatches are ordered by kind, then by
+/// ordinal.
+///
+/// Names are also matched without the decoration 32-bit x86 adds to C names,
+/// as e.g. `_Frob@4` is exported as `Frob`. Forwarders aren't in the image
+/// itself, so they're only compared with the PDB's record of the exports.
+pub fn compare_exports(
+    pdb: &ParsedPdb,
+    image: &Path,
+    base_address: Option<usize>,
+) -> Result<Vec<ExportMismatch>, Error> {
+    let data = std::fs::read(image)?;
+    crate::code::check_image(pdb, &data)?;
+    let image_exports = raw::Image::parse(&data)?.exports()?;
+
+    let symbols = pdb
+        .public_symbols
+        .iter()
+        .map(|public| (&public.name, public.offset))
+        .chain(
+            pdb.procedures
+                .iter()
+                .map(|procedure| (&procedure.name, procedure.address)),
+        )
+        .chain(pdb.global_data.iter().map(|data| (&data.name, data.offset)))
+        .filter_map(|(name, address)| Some((name.as_str(), address?)));
+    let mut by_name: HashMap<&str, Vec<Address>> = HashMap::new();
+    let mut by_undecorated_name: HashMap<&str, Vec<Address>> = HashMap::new();
+    let mut rvas = HashSet::new();
+    for (name, address) in symbols {
+        by_name.entry(name).or_default().push(address);
+        by_undecorated_name
+            .entry(crate::syscalls::undecorate(name))
+            .or_default()
+            .push(address);
+        rvas.insert(address.rva);
+    }
+    let recorded: HashMap<&str, u32> = pdb
+        .exports
+        .iter()
+        .filter(|export| !export.name.is_empty())
+        .map(|export| (export.name.as_str(), u32::from(export.ordinal)))
+        .collect();
+
+    let mut mismatches = vec![];
+    for export in &image_exports {
+        let mismatch = |kind, pdb_ordinal, pdb_addresses| ExportMismatch {
+            kind,
+            name: export.name.clone(),
+            image_ordinal: Some(export.ordinal),
+            image_address: export
+                .rva
+                .map(|rva| Address::new(rva as usize, base_address)),
+            pdb_ordinal,
+            pdb_addresses,
+        };
+
+        if let Some(name) = &export.name {
+            match recorded.get(name.as_str()) {
+                Some(&ordinal) if ordinal != export.ordinal => mismatches.push(mismatch(
+                    ExportMismatchKind::OrdinalMismatch,
+                    Some(ordinal),
+                    vec![],
+                )),
+                _ => {}
+            }
+        }
+
+        let rva = match export.rva {
+            Some(rva) => rva as usize,
+            None => continue,
+        };
+        let addresses = export
+            .name
+            .as_deref()
+            .and_then(|name| by_name.get(name).or_else(|| by_undecorated_name.get(name)));
+        match addresses {
+            Some(addresses) if !addresses.iter().any(|address| address.rva == rva) => {
+                let mut addresses = addresses.clone();
+                addresses.sort();
+                addresses.dedup();
+                mismatches.push(mismatch(
+                    ExportMismatchKind::AddressMismatch,
+                    None,
+                    addresses,
+                ));
+            }
+            Some(_) => {}
+            // Exports may be aliases, e.g. from a `.def` file, of a symbol by
+            // another name
+            None if rvas.contains(&rva) => {}
+            None => mismatches.push(mismatch(ExportMismatchKind::MissingSymbol, None, vec![])),
+        }
+    }
+
+    let exported: HashSet<&str> = image_exports
+        .iter()
+        .filter_map(|export| export.name.as_deref())
+        .collect();
+    let exported_ordinals: HashSet<u32> =
+        image_exports.iter().map(|export| export.ordinal).collect();
+    for export in &pdb.exports {
+        let ordinal = u32::from(export.ordinal);
+        let missing = if export.is_ordinal_only || export.name.is_empty() {
+            !exported_ordinals.contains(&ordinal)
+        } else {
+            !exported.contains(export.name.as_str())
+        };
+        if missing {
+            mismatches.push(ExportMismatch {
+                kind: ExportMismatchKind::MissingExport,
+                name: Some(export.name.clone()).filter(|name| !name.is_empty()),
+                image_ordinal: None,
+                image_address: None,
+                pdb_ordinal: Some(ordinal),
+                pdb_addresses: by_name
+                    .get(export.name.as_str())
+                    .cloned()
+                    .unwrap_or_default(),
+            });
+        }
+    }
+    mismatches.sort_by_key(|mismatch| {
+        (
+            mismatch.kind,
+            mismatch.image_ordinal.or(mismatch.pdb_ordinal),
+        )
+    });
+
+    Ok(mismatches)
+}
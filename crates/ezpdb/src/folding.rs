@@ -0,0 +1,213 @@
+//! Detection of procedures and data which share addresses. The linker's
+//! identical COMDAT folding (`/OPT:ICF`) keeps one copy of functions or
+//! constants with identical contents, so every symbol which was folded into it
+//! has the same address, and attributing an address to a single one of them is
+//! misleading.
+use crate::symbol_types::{Address, Data, ParsedPdb};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Functions which were folded into the same code
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FoldedGroup {
+    pub address: Address,
+    /// Length of the longest of the functions
+    pub len: usize,
+    /// Names of the functions, sorted
+    pub procedures: Vec<String>,
+}
+
+/// Returns the groups of differently named procedures which start at the same
+/// RVA, ordered by RVA. Each procedure at such an address was folded into a
+/// single copy of the code, so an address in it may belong to any of them.
+pub fn folded_groups(pdb: &ParsedPdb) -> Vec<FoldedGroup> {
+    let mut by_rva: BTreeMap<usize, FoldedGroup> = BTreeMap::new();
+    for procedure in &pdb.procedures {
+        let address = match procedure.address {
+            Some(address) => address,
+            None => continue,
+        };
+
+        let group = by_rva.entry(address.rva).or_insert_with(|| FoldedGroup {
+            address,
+            len: 0,
+            procedures: vec![],
+        });
+        group.len = group.len.max(procedure.len);
+        group.procedures.push(procedure.name.clone());
+    }
+
+    by_rva
+        .into_values()
+        .filter_map(|mut group| {
+            group.procedures.sort();
+            group.procedures.dedup();
+            (group.procedures.len() > 1).then_some(group)
+        })
+        .collect()
+}
+
+/// Where a record whose address range is checked for overlaps is stored
+#[derive(Debug, Copy, Clone)]
+enum Record {
+    Procedure(usize),
+    GlobalData(usize),
+    LocalData(usize),
+}
+
+/// A record's address range
+#[derive(Debug)]
+struct Range<'a> {
+    record: Record,
+    name: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Sets [Procedure::folded_with] and [Data::folded_with] for every procedure
+/// and global and local variable whose address range overlaps another's.
+/// Records of unknown size are taken to be a byte long. Records with the same
+/// name are the same symbol, e.g. one recorded by several modules, and aren't
+/// reported as folded with each other.
+///
+/// [Procedure::folded_with]: crate::symbol_types::Procedure::folded_with
+/// [Data::folded_with]: crate::symbol_types::Data::folded_with
+pub fn find_folded_symbols(pdb: &mut ParsedPdb) {
+    let folded: Vec<(Record, Vec<String>)> = {
+        let mut ranges = ranges(pdb);
+        ranges.sort_by_key(|range| (range.start, range.end));
+
+        let mut overlaps: Vec<BTreeSet<&str>> = vec![BTreeSet::new(); ranges.len()];
+        // Indices of the ranges which may overlap the ones after them
+        let mut active: Vec<usize> = vec![];
+        for (index, range) in ranges.iter().enumerate() {
+            active.retain(|other| ranges[*other].end > range.start);
+            for other in &active {
+                if ranges[*other].name != range.name {
+                    overlaps[*other].insert(range.name);
+                    overlaps[index].insert(ranges[*other].name);
+                }
+            }
+            active.push(index);
+        }
+
+        ranges
+            .iter()
+            .zip(overlaps)
+            .filter(|(_range, names)| !names.is_empty())
+            .map(|(range, names)| {
+                let names = names.into_iter().map(str::to_string).collect();
+                (range.record, names)
+            })
+            .collect()
+    };
+
+    for (record, names) in folded {
+        let folded_with = match record {
+            Record::Procedure(index) => &mut pdb.procedures[index].folded_with,
+            Record::GlobalData(index) => &mut pdb.global_data[index].folded_with,
+            Record::LocalData(index) => &mut pdb.local_data[index].folded_with,
+        };
+        *folded_with = names;
+    }
+}
+
+/// Returns the address ranges of the procedures and data of `pdb` whose
+/// addresses are known
+fn ranges(pdb: &ParsedPdb) -> Vec<Range<'_>> {
+    let procedures = pdb
+        .procedures
+        .iter()
+        .enumerate()
+        .filter_map(|(index, procedure)| {
+            let start = procedure.address?.rva;
+            Some(Range {
+                record: Record::Procedure(index),
+                name: &procedure.name,
+                start,
+                end: start + procedure.len.max(1),
+            })
+        });
+
+    procedures
+        .chain(data_ranges(pdb, &pdb.global_data, Record::GlobalData))
+        .chain(data_ranges(pdb, &pdb.local_data, Record::LocalData))
+        .collect()
+}
+
+/// Returns the address ranges of the variables of `data` whose addresses are
+/// known, which are sized by their types. Variables whose types have no size,
+/// such as the function types some symbols are given, are taken to be a byte long.
+fn data_ranges<'a>(
+    pdb: &'a ParsedPdb,
+    data: &'a [Data],
+    record: fn(usize) -> Record,
+) -> impl Iterator<Item = Range<'a>> {
+    data.iter().enumerate().filter_map(move |(index, data)| {
+        let start = data.offset?.rva;
+        let size = pdb
+            .types
+            .get(data.ty)
+            .and_then(|ty| ty.data_size(pdb))
+            .unwrap_or(0);
+        Some(Range {
+            record: record(index),
+            name: &data.name,
+            start,
+            end: start + size.max(1),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol_types::Address;
+    use crate::type_info::{FunctionAttributes, Primitive, PrimitiveKind, Procedure, Type, TypeId};
+    use std::path::PathBuf;
+
+    fn data(name: &str, rva: usize, ty: TypeId) -> Data {
+        Data {
+            name: name.to_string(),
+            is_global: true,
+            is_managed: false,
+            ty,
+            offset: Some(Address::new(rva, None)),
+            module: None,
+            function: None,
+            folded_with: vec![],
+            storage: None,
+        }
+    }
+
+    #[test]
+    fn data_of_function_types_is_a_byte_long() {
+        let mut pdb = ParsedPdb::new(PathBuf::from("test.pdb"));
+        let int = pdb.types.insert(Type::Primitive(Primitive {
+            kind: PrimitiveKind::I32,
+            indirection: None,
+        }));
+        let function = pdb.types.insert(Type::Procedure(Procedure {
+            return_type: Some(int),
+            argument_list: vec![],
+            attributes: FunctionAttributes {
+                calling_convention: 0,
+                cxx_return_udt: false,
+                is_constructor: false,
+                is_constructor_with_virtual_bases: false,
+            },
+        }));
+        pdb.global_data = vec![
+            data("handler", 0x1000, function),
+            data("next", 0x1001, int),
+            data("counter", 0x1002, int),
+        ];
+
+        find_folded_symbols(&mut pdb);
+        assert!(pdb.global_data[0].folded_with.is_empty());
+        assert_eq!(pdb.global_data[1].folded_with, ["counter"]);
+        assert_eq!(pdb.global_data[2].folded_with, ["next"]);
+    }
+}
@@ -0,0 +1,218 @@
+//! Finding function pointers stored in writable memory. Overwriting one of
+//! these redirects control flow, so they're a common target when exploiting a
+//! memory corruption bug.
+use crate::symbol_types::{Address, Data, ParsedPdb, Storage};
+use crate::type_info::{MemberPointerKind, Type, TypeId};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How deeply nested members are followed. Types can't contain themselves by
+/// value, so this only guards against malformed type information.
+const MAX_DEPTH: usize = 32;
+
+/// A function pointer in a variable stored in a writable section
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct WritableFunctionPointer<'a> {
+    /// The variable the pointer is in
+    pub data: &'a Data,
+    /// Members and array elements leading from the variable to the pointer,
+    /// e.g. `.ops[].close`. Empty if the variable is itself the pointer.
+    pub path: String,
+    /// Address of the pointer, or of the first one if it's in an array
+    pub address: Option<Address>,
+    /// Number of pointers at `path`, which is more than one if it's in an array
+    pub count: usize,
+    /// The type of the pointer
+    pub ty: TypeId,
+    pub storage: Storage,
+}
+
+/// Returns every function pointer in the writable global and static data of
+/// `pdb`, whether a variable is a function pointer itself or holds some in its
+/// members or array elements. Variables whose section isn't known are skipped.
+pub fn writable_function_pointers(pdb: &ParsedPdb) -> Vec<WritableFunctionPointer<'_>> {
+    let mut finder = Finder {
+        pdb,
+        definitions: definitions(pdb),
+        found: vec![],
+    };
+    let mut pointers = vec![];
+
+    for data in pdb.global_data.iter().chain(pdb.local_data.iter()) {
+        let storage = match data.storage {
+            Some(storage @ (Storage::Writable | Storage::ZeroInitialized)) => storage,
+            _ => continue,
+        };
+
+        finder.find(data.ty, 0, String::new(), 1, 0);
+        for pointer in finder.found.drain(..) {
+            let address = data.offset.map(|address| Address {
+                rva: address.rva + pointer.offset,
+                virtual_address: address
+                    .virtual_address
+                    .map(|address| address + pointer.offset),
+            });
+            pointers.push(WritableFunctionPointer {
+                data,
+                path: pointer.path,
+                address,
+                count: pointer.count,
+                ty: pointer.ty,
+                storage,
+            });
+        }
+    }
+
+    pointers
+}
+
+/// A function pointer found within a type, relative to the start of the type
+struct FoundPointer {
+    path: String,
+    offset: usize,
+    count: usize,
+    ty: TypeId,
+}
+
+/// Maps the unique names of classes and unions to their definitions, so that
+/// members whose type is a forward declaration can be followed
+fn definitions(pdb: &ParsedPdb) -> HashMap<&str, TypeId> {
+    let mut definitions = HashMap::new();
+    for (id, ty) in pdb.types.iter() {
+        let name = match ty {
+            Type::Class(class) if !class.properties.forward_reference => {
+                class.unique_name.as_deref().unwrap_or(&class.name)
+            }
+            Type::Union(union) if !union.properties.forward_reference => {
+                union.unique_name.as_deref().unwrap_or(&union.name)
+            }
+            _ => continue,
+        };
+        definitions.entry(name).or_insert(id);
+    }
+
+    definitions
+}
+
+/// Returns whether `id` is a pointer to a function or member function
+fn is_function_pointer(pdb: &ParsedPdb, id: TypeId) -> bool {
+    let pointer = match &pdb.types[id] {
+        Type::Pointer(pointer) => pointer,
+        _ => return false,
+    };
+    if pointer.attributes.is_reference || pointer.attributes.is_rvalue_reference {
+        return false;
+    }
+    if let Some(member) = &pointer.member {
+        return matches!(member.kind, MemberPointerKind::Function);
+    }
+
+    let mut pointee = pointer.underlying_type;
+    while let Some(id) = pointee {
+        match &pdb.types[id] {
+            Type::Modifier(modifier) => pointee = Some(modifier.underlying_type),
+            Type::Procedure(_) | Type::MemberFunction(_) => return true,
+            _ => return false,
+        }
+    }
+
+    false
+}
+
+/// Walks the members and array elements of types, collecting function pointers
+struct Finder<'a> {
+    pdb: &'a ParsedPdb,
+    definitions: HashMap<&'a str, TypeId>,
+    found: Vec<FoundPointer>,
+}
+
+impl<'a> Finder<'a> {
+    /// Returns the fields of the class or union `id`, following forward
+    /// declarations to their definition
+    fn fields(&self, id: TypeId) -> Option<&'a [TypeId]> {
+        let (name, forward_reference) = match &self.pdb.types[id] {
+            Type::Class(class) => (
+                class.unique_name.as_deref().unwrap_or(&class.name),
+                class.properties.forward_reference,
+            ),
+            Type::Union(union) => (
+                union.unique_name.as_deref().unwrap_or(&union.name),
+                union.properties.forward_reference,
+            ),
+            _ => return None,
+        };
+        let definition = if forward_reference {
+            *self.definitions.get(name)?
+        } else {
+            id
+        };
+
+        match &self.pdb.types[definition] {
+            Type::Class(class) => Some(&class.fields),
+            Type::Union(union) => Some(&union.fields),
+            _ => None,
+        }
+    }
+
+    fn find(&mut self, id: TypeId, offset: usize, path: String, count: usize, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        if is_function_pointer(self.pdb, id) {
+            self.found.push(FoundPointer {
+                path,
+                offset,
+                count,
+                ty: id,
+            });
+            return;
+        }
+
+        match &self.pdb.types[id] {
+            Type::Modifier(modifier) => {
+                self.find(modifier.underlying_type, offset, path, count, depth + 1)
+            }
+            Type::Array(array) => {
+                let stride = array.element_stride(self.pdb);
+                let elements = array.size.checked_div(stride).unwrap_or(0);
+                self.find(
+                    array.element_type,
+                    offset,
+                    path + "[]",
+                    count * elements,
+                    depth + 1,
+                );
+            }
+            Type::Class(_) | Type::Union(_) => {
+                let pdb = self.pdb;
+                let fields = match self.fields(id) {
+                    Some(fields) => fields,
+                    None => return,
+                };
+                for field in fields {
+                    match &pdb.types[*field] {
+                        Type::Member(member) => self.find(
+                            member.underlying_type,
+                            offset + member.offset,
+                            format!("{}.{}", path, member.name),
+                            count,
+                            depth + 1,
+                        ),
+                        // Members of base classes are members of the class
+                        Type::BaseClass(base) => self.find(
+                            base.base_class,
+                            offset + base.offset,
+                            path.clone(),
+                            count,
+                            depth + 1,
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
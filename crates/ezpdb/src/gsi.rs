@@ -0,0 +1,238 @@
+//! Support for the global symbol index (GSI) hash tables which allow looking
+//! up a symbol by name without walking the entire symbol record stream
+use crate::error::Error;
+use crate::raw::ByteReader;
+use std::collections::HashMap;
+
+/// Number of hash buckets in a GSI hash table
+const IPHR_HASH: usize = 4096;
+/// Signature which begins a GSI hash header
+const GSI_HASH_SIGNATURE: u32 = 0xFFFF_FFFF;
+/// The only GSI hash version in use (`GSIHashSCImpv70`)
+const GSI_HASH_V70: u32 = 0xEFFE_0000 + 19990810;
+/// Size of the in-memory hash record the bucket offsets were computed against
+const HASH_RECORD_IN_MEMORY_SIZE: u32 = 12;
+/// Size of the `PSGSIHDR` which precedes the hash table in the public symbols stream
+const PUBLICS_HEADER_SIZE: usize = 28;
+
+/// A parsed GSI hash table. Each bucket holds the offsets into the symbol
+/// records stream of all symbols whose name hashes into that bucket.
+#[derive(Debug)]
+pub(crate) struct GsiHashTable {
+    /// Offsets into the symbol records stream, grouped by bucket
+    records: Vec<u32>,
+    /// Index into `records` at which each bucket starts. There is one more
+    /// entry than there are buckets so that bucket `i` ends where `i + 1` begins.
+    bucket_starts: Vec<usize>,
+}
+
+impl GsiHashTable {
+    /// Parses the hash table which follows the header of the public symbols stream
+    pub fn parse_publics(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < PUBLICS_HEADER_SIZE {
+            return Err(Error::PdbCrateError(pdb::Error::UnexpectedEof));
+        }
+
+        let mut reader = ByteReader::new(data);
+        let symbol_hash_size = reader.u32()? as usize;
+        reader.seek(PUBLICS_HEADER_SIZE);
+
+        Self::parse(reader.take(symbol_hash_size)?)
+    }
+
+    /// Parses a standalone GSI hash table, such as the contents of the global symbols stream
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader::new(data);
+
+        if reader.u32()? != GSI_HASH_SIGNATURE || reader.u32()? != GSI_HASH_V70 {
+            return Err(Error::Unsupported("GSI hash table version"));
+        }
+
+        let hash_records_size = reader.u32()? as usize;
+        let buckets_size = reader.u32()? as usize;
+
+        let mut hash_records = ByteReader::new(reader.take(hash_records_size)?);
+        let mut records = Vec::with_capacity(hash_records_size / 8);
+        while hash_records.remaining() >= 8 {
+            // Offsets are stored 1-based so that 0 can represent "no symbol"
+            let offset = hash_records.u32()?;
+            let _ref_count = hash_records.u32()?;
+            records.push(offset.saturating_sub(1));
+        }
+
+        let mut buckets = ByteReader::new(reader.take(buckets_size)?);
+        let bitmap_words = (IPHR_HASH + 1).div_ceil(32);
+        let bitmap = (0..bitmap_words)
+            .map(|_| buckets.u32())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Only the starts of non-empty buckets are stored, one per set bit in the bitmap
+        let mut starts = vec![None; IPHR_HASH + 1];
+        for (bucket, start) in starts.iter_mut().enumerate() {
+            if bitmap[bucket / 32] & (1 << (bucket % 32)) != 0 {
+                let offset = buckets.u32()?;
+                *start = Some((offset / HASH_RECORD_IN_MEMORY_SIZE) as usize);
+            }
+        }
+
+        // Walk backwards so that each empty bucket inherits the start of the
+        // following bucket and is therefore treated as zero-length
+        let mut bucket_starts = vec![records.len(); IPHR_HASH + 2];
+        for bucket in (0..=IPHR_HASH).rev() {
+            bucket_starts[bucket] = starts[bucket]
+                .unwrap_or(bucket_starts[bucket + 1])
+                .min(records.len());
+        }
+
+        Ok(GsiHashTable {
+            records,
+            bucket_starts,
+        })
+    }
+
+    /// Returns the symbol records stream offsets of every symbol which could be named `name`.
+    /// Since the hash is case-insensitive and buckets are shared, callers must still
+    /// compare the name of each candidate.
+    pub fn candidates(&self, name: &str) -> &[u32] {
+        let bucket = hash_string_v1(name.as_bytes()) as usize % IPHR_HASH;
+        let start = self.bucket_starts[bucket];
+        let end = self.bucket_starts[bucket + 1].max(start);
+
+        &self.records[start..end]
+    }
+}
+
+/// Returns a GSI hash table with no symbols
+pub(crate) fn empty_hash_table() -> Vec<u8> {
+    let bitmap_words = (IPHR_HASH + 1).div_ceil(32);
+    let mut table = vec![];
+    for value in [
+        GSI_HASH_SIGNATURE,
+        GSI_HASH_V70,
+        // Sizes of the hash records and of the buckets, which are only the bitmap
+        0,
+        bitmap_words as u32 * 4,
+    ] {
+        table.extend_from_slice(&value.to_le_bytes());
+    }
+    table.resize(table.len() + bitmap_words * 4, 0);
+
+    table
+}
+
+/// Returns a GSI hash table of `symbols`, given as the offset of each symbol's
+/// record in the symbol records stream along with its name
+#[cfg(feature = "synthetic")]
+pub(crate) fn write_hash_table(symbols: &[(u32, &str)]) -> Vec<u8> {
+    let mut buckets = vec![vec![]; IPHR_HASH];
+    for &(offset, name) in symbols {
+        buckets[hash_string_v1(name.as_bytes()) as usize % IPHR_HASH].push(offset);
+    }
+
+    let bitmap_words = (IPHR_HASH + 1).div_ceil(32);
+    let mut bitmap = vec![0u32; bitmap_words];
+    let mut hash_records = vec![];
+    let mut bucket_starts = vec![];
+    let mut record_count = 0;
+    for (bucket, offsets) in buckets.iter().enumerate() {
+        if offsets.is_empty() {
+            continue;
+        }
+
+        bitmap[bucket / 32] |= 1 << (bucket % 32);
+        bucket_starts.push(record_count * HASH_RECORD_IN_MEMORY_SIZE);
+        for offset in offsets {
+            // Offsets are stored 1-based, followed by a reference count
+            hash_records.extend_from_slice(&(offset + 1).to_le_bytes());
+            hash_records.extend_from_slice(&1u32.to_le_bytes());
+            record_count += 1;
+        }
+    }
+
+    let mut table = vec![];
+    for value in [
+        GSI_HASH_SIGNATURE,
+        GSI_HASH_V70,
+        hash_records.len() as u32,
+        (bitmap.len() + bucket_starts.len()) as u32 * 4,
+    ] {
+        table.extend_from_slice(&value.to_le_bytes());
+    }
+    table.extend_from_slice(&hash_records);
+    for value in bitmap.into_iter().chain(bucket_starts) {
+        table.extend_from_slice(&value.to_le_bytes());
+    }
+
+    table
+}
+
+/// Rewrites the public symbols stream `data` for a symbol records stream whose
+/// records have moved, given the new offset of each record by its old offset.
+/// Every record the stream refers to must have a new offset.
+pub(crate) fn remap_publics(data: &[u8], offsets: &HashMap<u32, u32>) -> Result<Vec<u8>, Error> {
+    let remap = |offset: u32| {
+        offsets.get(&offset).copied().ok_or(Error::Unsupported(
+            "public symbol indices referring to records other than public symbols",
+        ))
+    };
+
+    let mut reader = ByteReader::new(data);
+    let header = reader.take(PUBLICS_HEADER_SIZE)?;
+    let mut header_fields = ByteReader::new(header);
+    let symbol_hash_size = header_fields.u32()? as usize;
+    let address_map_size = header_fields.u32()? as usize;
+    let mut remapped = header.to_vec();
+
+    let mut hash = ByteReader::new(reader.take(symbol_hash_size)?);
+    let hash_header = hash.take(16)?;
+    let hash_records_size = ByteReader::new(&hash_header[8..]).u32()? as usize;
+    remapped.extend_from_slice(hash_header);
+    let mut hash_records = ByteReader::new(hash.take(hash_records_size)?);
+    while hash_records.remaining() >= 8 {
+        // Offsets are stored 1-based
+        let offset = hash_records.u32()?;
+        let ref_count = hash_records.u32()?;
+        let offset = remap(offset.saturating_sub(1))? + 1;
+        remapped.extend_from_slice(&offset.to_le_bytes());
+        remapped.extend_from_slice(&ref_count.to_le_bytes());
+    }
+    // The buckets refer to hash records rather than symbols, so they're unchanged
+    remapped.extend_from_slice(hash.take(hash.remaining())?);
+
+    let mut address_map = ByteReader::new(reader.take(address_map_size)?);
+    while address_map.remaining() >= 4 {
+        let offset = remap(address_map.u32()?)?;
+        remapped.extend_from_slice(&offset.to_le_bytes());
+    }
+
+    // The thunk and section maps hold addresses
+    remapped.extend_from_slice(reader.take(reader.remaining())?);
+
+    Ok(remapped)
+}
+
+/// The string hash used by the GSI (`LHashPbCb` / `hashStringV1`)
+pub(crate) fn hash_string_v1(data: &[u8]) -> u32 {
+    let mut result = 0u32;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        result ^= u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    let mut remainder = chunks.remainder();
+    if remainder.len() >= 2 {
+        result ^= u16::from_le_bytes([remainder[0], remainder[1]]) as u32;
+        remainder = &remainder[2..];
+    }
+
+    if let Some(byte) = remainder.first() {
+        result ^= *byte as u32;
+    }
+
+    const TO_LOWER_MASK: u32 = 0x2020_2020;
+    result |= TO_LOWER_MASK;
+    result ^= result >> 11;
+
+    result ^ (result >> 16)
+}
@@ -0,0 +1,148 @@
+//! Support for ARM64EC and ARM64X images, whose code mixes instruction sets:
+//! ARM64EC code interoperates with x64 code, and ARM64X images also hold native
+//! ARM64 code. Also tells which instruction set each symbol's code is in.
+use crate::symbol_types::{HybridInfo, HybridSymbol, Isa, MachineType, ParsedPdb};
+use std::collections::HashMap;
+
+/// Symbols of the CHPE metadata which the loader uses to find an image's
+/// ARM64EC code and to dispatch calls between ARM64EC and x64 code
+const METADATA_SYMBOLS: &[&str] = &[
+    "__chpe_metadata",
+    "__hybrid_code_map",
+    "__hybrid_image_info_bitfield",
+    "__x64_code_ranges_to_entry_points",
+    "__arm64x_redirection_metadata",
+    "__arm64x_extra_rfe_table",
+];
+
+/// Name prefixes of CHPE metadata symbols, such as the `__os_arm64x_dispatch_*`
+/// function pointers and the auxiliary import address tables
+const METADATA_PREFIXES: &[&str] = &["__os_arm64x_", "__hybrid_auxiliary_"];
+
+/// Sections and COFF groups which only hybrid images have: hybrid export
+/// thunks and ARM64X relocations
+const HYBRID_SECTIONS: &[&str] = &[".hexpthk", ".a64xrm"];
+
+/// Name prefixes of the thunks the compiler generates between ARM64EC and x64
+/// code, which are themselves ARM64EC code
+const THUNK_PREFIXES: &[&str] = &["$ientry_thunk$", "$iexit_thunk$"];
+
+/// Returns the names of the CodeView CPU types which the `pdb` crate doesn't
+/// know, and reads as `Intel8080`
+pub(crate) fn cpu_type_name(cpu_type: u16) -> Option<&'static str> {
+    match cpu_type {
+        0x3d => Some("ARM64EC"),
+        0x3e => Some("ARM64X"),
+        0xf7 => Some("HybridX86ARM64"),
+        _ => None,
+    }
+}
+
+/// Returns the instruction set of code compiled for the CodeView CPU type
+/// named `cpu_type`, as recorded in [crate::symbol_types::CompilerInfo::cpu_type].
+/// ARM64X objects hold code of more than one instruction set, so they have none.
+pub fn cpu_isa(cpu_type: &str) -> Option<Isa> {
+    match cpu_type {
+        "Intel80386" | "Intel80486" | "Pentium" | "PentiumPro" | "Pentium3" => Some(Isa::X86),
+        "X64" => Some(Isa::X64),
+        "ARM7" | "Thumb" | "ARMNT" => Some(Isa::Arm),
+        "ARM64" => Some(Isa::Arm64),
+        "ARM64EC" => Some(Isa::Arm64EC),
+        _ => None,
+    }
+}
+
+/// Returns whether `name` is decorated as ARM64EC code. Functions with C
+/// linkage are prefixed with `#`, C++ functions have `$$h` in their mangled
+/// names, and the compiler's entry and exit thunks have their own prefixes.
+pub fn is_arm64ec_name(name: &str) -> bool {
+    name.starts_with('#')
+        || name.contains("$$h")
+        || THUNK_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+fn is_metadata_symbol(name: &str) -> bool {
+    METADATA_SYMBOLS.contains(&name)
+        || METADATA_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Sets the [Isa] of each procedure and public symbol whose instruction set can
+/// be told. ARM64EC name decorations take precedence over the CPU type of a
+/// procedure's module, as ARM64X objects don't have a single one. Public
+/// symbols take the instruction set of the procedure at the same address.
+pub fn tag_isas(pdb: &mut ParsedPdb) {
+    let modules = &pdb.debug_modules;
+    for procedure in &mut pdb.procedures {
+        let decorated = is_arm64ec_name(&procedure.name)
+            || procedure
+                .public_name
+                .as_deref()
+                .is_some_and(is_arm64ec_name);
+        procedure.isa = if decorated {
+            Some(Isa::Arm64EC)
+        } else {
+            procedure
+                .module_index
+                .and_then(|index| modules.get(index))
+                .and_then(|module| module.compiler_info.as_ref())
+                .and_then(|compiler_info| cpu_isa(&compiler_info.cpu_type))
+        };
+    }
+
+    let procedure_isas: HashMap<usize, Isa> = pdb
+        .procedures
+        .iter()
+        .filter_map(|procedure| Some((procedure.address?.rva, procedure.isa?)))
+        .collect();
+    for public in &mut pdb.public_symbols {
+        public.isa = if is_arm64ec_name(&public.name) {
+            Some(Isa::Arm64EC)
+        } else {
+            public
+                .offset
+                .and_then(|address| procedure_isas.get(&address.rva))
+                .copied()
+        };
+    }
+}
+
+/// Returns what `pdb` records about how its code mixes instruction sets, or
+/// `None` if it isn't for an ARM64EC, ARM64X, or CHPE x86 image
+pub fn hybrid_info(pdb: &ParsedPdb) -> Option<HybridInfo> {
+    let mut metadata: Vec<HybridSymbol> = pdb
+        .public_symbols
+        .iter()
+        .map(|public| (&public.name, public.offset))
+        .chain(pdb.global_data.iter().map(|data| (&data.name, data.offset)))
+        .filter(|(name, _address)| is_metadata_symbol(name))
+        .map(|(name, address)| HybridSymbol {
+            name: name.clone(),
+            address,
+        })
+        .collect();
+    metadata.sort_by(|a, b| a.name.cmp(&b.name));
+    metadata.dedup_by(|a, b| a.name == b.name);
+
+    let mut sections: Vec<String> = pdb
+        .sections
+        .iter()
+        .map(|section| &section.name)
+        .chain(pdb.coff_groups.iter().map(|group| &group.name))
+        .filter(|name| HYBRID_SECTIONS.contains(&name.as_str()))
+        .cloned()
+        .collect();
+    sections.sort();
+    sections.dedup();
+
+    let hybrid_machine = matches!(
+        pdb.machine_type,
+        Some(MachineType::Arm64EC | MachineType::Arm64X | MachineType::ChpeX86)
+    );
+    if !hybrid_machine && metadata.is_empty() && sections.is_empty() {
+        return None;
+    }
+
+    Some(HybridInfo { metadata, sections })
+}
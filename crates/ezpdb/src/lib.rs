@@ -1,49 +1,184 @@
+use crate::address::AddressMapping;
+use crate::convert::{IntoModel, TryIntoModel};
 use crate::error::Error;
-use crate::symbol_types::*;
-use log::{debug, warn};
-use pdb::{
-    AddressMap, AnnotationReferenceSymbol, FallibleIterator, IdIndex, ItemFinder, Symbol,
-    SymbolData, TypeData, TypeIndex, PDB,
-};
-use std::cell::RefCell;
-use std::convert::TryInto;
+use crate::progress::{NoProgress, ParseStage, ProgressSink};
+use crate::symbol_types::{Diagnostic, DiagnosticCategory};
+use crate::type_info::{Partial, Pointer, Type, TypeId, VTableShape, VTableSlotKind};
+use pdb::{FallibleIterator, IdIndex, ItemFinder, Symbol, SymbolData, TypeData, TypeIndex, PDB};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
 use std::fs::File;
-use std::path::Path;
-use std::rc::Rc;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, trace, warn};
 
+mod address;
+pub mod addrmap;
+pub mod audit;
+pub mod code;
+pub mod codegen;
+mod convert;
+mod digest;
+pub mod dwarf;
+pub mod elf;
 pub mod error;
+pub mod exports;
+pub mod folding;
+pub mod function_pointers;
+mod gsi;
+pub mod hybrid;
+mod msf;
+pub mod origin;
+pub mod progress;
+pub mod provenance;
+mod raw;
+pub mod render;
+pub mod search;
+pub mod security;
+pub mod sources;
+pub mod storage;
+pub mod stream;
+pub mod strip;
+pub mod symbol_path;
 pub mod symbol_types;
+#[cfg(feature = "synthetic")]
+pub mod synthetic;
+pub mod syscalls;
+pub mod timing;
 pub mod type_info;
+pub mod verify;
+pub mod visitor;
+pub mod xref;
 
 pub use crate::symbol_types::ParsedPdb;
+pub use ezpdb_model::index;
+pub use ezpdb_model::intern;
+/// The version of the `pdb` crate whose records [handle_type] and
+/// [handle_type_data] convert
+pub use pdb;
+
+/// Options controlling how a PDB is parsed by [parse_pdb_with_options]
+#[derive(Debug, Default, Clone)]
+pub struct ParseOptions {
+    /// Base address of the module in-memory. If provided, each address's
+    /// [symbol_types::Address::virtual_address] is its RVA added to this value
+    pub base_address: Option<usize>,
+    /// Retain file- and function-static variables in [ParsedPdb::local_data]
+    /// rather than discarding them
+    pub include_local_data: bool,
+    /// The executable the PDB was built for. If the PDB lacks section headers,
+    /// addresses are computed from the executable's instead.
+    pub image_path: Option<PathBuf>,
+    /// Which problems stop parsing rather than being skipped and recorded in
+    /// [ParsedPdb::diagnostics]
+    pub fail_on: FailurePolicy,
+    /// Build [ParsedPdb::index] once parsing is done, for consumers which look
+    /// up many symbols or types by name or address
+    pub build_index: bool,
+}
+
+/// How strict parsing is about records which can't be parsed. Each policy stops
+/// on everything the policies before it stop on.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Skip and record every problem which can be skipped
+    Never,
+    /// Stop on malformed records
+    #[default]
+    Corrupt,
+    /// Also stop on records of kinds which can't be parsed
+    UnknownKind,
+    /// Stop on any problem, including unresolved types and missing streams
+    Any,
+}
+
+impl FailurePolicy {
+    /// Returns whether a problem of `category` stops parsing under this policy
+    pub fn stops_on(self, category: DiagnosticCategory) -> bool {
+        match self {
+            FailurePolicy::Never => false,
+            FailurePolicy::Corrupt => category == DiagnosticCategory::Corrupt,
+            FailurePolicy::UnknownKind => matches!(
+                category,
+                DiagnosticCategory::Corrupt | DiagnosticCategory::UnknownKind
+            ),
+            FailurePolicy::Any => true,
+        }
+    }
+}
+
+/// Where a symbol was found within the PDB
+#[derive(Debug, Default, Copy, Clone)]
+struct SymbolScope<'a> {
+    /// The module whose symbol stream is being walked. `None` for the global
+    /// symbol stream.
+    module: Option<&'a str>,
+    /// Index of `module` in the debug information's module list
+    module_index: Option<usize>,
+    /// The innermost procedure enclosing the symbol
+    function: Option<&'a str>,
+    /// Whether the innermost scope is `function` itself rather than e.g. a
+    /// block or inlined call site within it
+    in_function_body: bool,
+}
 
 pub fn parse_pdb<P: AsRef<Path>>(
     path: P,
     base_address: Option<usize>,
 ) -> Result<ParsedPdb, crate::error::Error> {
-    let file = File::open(path.as_ref())?;
+    let options = ParseOptions {
+        base_address,
+        ..Default::default()
+    };
+
+    parse_pdb_with_options(path, &options, &mut NoProgress)
+}
+
+/// Parses the PDB at `path`, notifying `progress` as each [ParseStage] is worked through
+pub fn parse_pdb_with_options<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<ParsedPdb, crate::error::Error> {
     debug!("opening PDB");
-    let mut pdb = PDB::open(file)?;
+    let pdb = open_pdb(path.as_ref())?;
 
-    let mut output_pdb = ParsedPdb::new(path.as_ref().to_owned());
-    let dbi = pdb.debug_information()?;
-    let pdbi = pdb.pdb_information()?;
-    output_pdb.machine_type = dbi
-        .machine_type()
-        .ok()
-        .map(|machine_type| (&machine_type).into());
+    parse_opened_pdb(pdb, path.as_ref().to_owned(), options, progress)
+}
 
-    output_pdb.age = match dbi.age() {
-        Some(age) => age,
-        None => pdbi.age,
+/// Parses the PDB file held in `data`, such as one read into memory beforehand
+/// or generated with the `synthetic` feature, notifying `progress` as each [ParseStage] is
+/// worked through. `path` is only recorded as the [ParsedPdb::path].
+pub fn parse_pdb_from_bytes(
+    data: Vec<u8>,
+    path: PathBuf,
+    options: &ParseOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<ParsedPdb, crate::error::Error> {
+    let data = if msf::is_small_msf(&data) {
+        debug!("converting PDB 2.0 file");
+        msf::convert_small_msf(&data)?
+    } else {
+        data
     };
+    let pdb = PDB::open(PdbSource::Memory(Arc::new(data)))?;
 
-    output_pdb.guid = pdbi.guid;
-    output_pdb.timestamp = pdbi.signature;
-    output_pdb.version = (&pdbi.version).into();
+    parse_opened_pdb(pdb, path, options, progress)
+}
+
+fn parse_opened_pdb(
+    mut pdb: PDB<'static, PdbSource>,
+    path: PathBuf,
+    options: &ParseOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<ParsedPdb, crate::error::Error> {
+    let _span = tracing::info_span!("parse", path = %path.display()).entered();
+    let mut output_pdb = ParsedPdb::new(path);
+    read_header_info(&mut pdb, &mut output_pdb)?;
+    read_raw_type_records(&mut pdb, &mut output_pdb, options, None)?;
 
-    debug!("getting address map");
-    let address_map = pdb.address_map().ok();
+    let address_map = AddressMapping::new(&mut pdb, options);
     debug!("grabbing string table");
     let string_table = pdb.string_table().ok();
 
@@ -54,16 +189,39 @@ pub fn parse_pdb<P: AsRef<Path>>(
     let id_finder = match &id_information {
         Ok(id_information) => {
             debug!("ID information header was valid");
+            let total = Some(id_information.len());
+            let stage = stage_span(ParseStage::Ids).entered();
+            progress.stage_started(ParseStage::Ids, total);
+
             let mut id_finder = id_information.finder();
             let mut iter = id_information.iter();
+            let mut processed = 0;
             while let Some(_id) = iter.next()? {
                 id_finder.update(&iter);
+                processed += 1;
+                progress.progress(ParseStage::Ids, processed, total);
             }
 
+            progress.stage_finished(ParseStage::Ids);
+            stage.record("items", processed);
             Some(id_finder)
         }
         Err(e) => {
-            warn!("error when fetching id_information: {}. ID information and symbols depending on such will not be loaded", e);
+            report(
+                &mut output_pdb,
+                options,
+                Diagnostic {
+                    category: DiagnosticCategory::MissingStream,
+                    stream: Some(u32::from(raw::IPI_STREAM)),
+                    offset: None,
+                    kind: None,
+                    type_index: None,
+                    description: format!(
+                        "could not read ID information, so symbols depending on it won't be loaded: {}",
+                        e
+                    ),
+                },
+            )?;
             None
         }
     };
@@ -72,278 +230,1610 @@ pub fn parse_pdb<P: AsRef<Path>>(
     // Parse type information first. Some symbol info (such as function signatures) depends
     // upon type information, but not vice versa
     let type_information = pdb.type_information()?;
+    let total = Some(type_information.len());
+    let stage = stage_span(ParseStage::Types).entered();
+    progress.stage_started(ParseStage::Types, total);
+
     let mut type_finder = type_information.finder();
     let mut iter = type_information.iter();
+    // Each type along with the offset of its record in the TPI stream and its kind
     let mut discovered_types = vec![];
+    let mut offset = raw::TPI_HEADER_SIZE;
     while let Some(typ) = iter.next()? {
         type_finder.update(&iter);
-        discovered_types.push(typ.index());
+        discovered_types.push((typ.index(), offset, typ.raw_kind()));
+        // Records are prefixed by their length, which isn't included in `len()`
+        offset += 2 + typ.len();
     }
 
-    for typ in discovered_types.iter() {
-        let _typ = match handle_type(*typ, &mut output_pdb, &type_finder) {
-            Ok(typ) => typ,
-            Err(Error::PdbCrateError(e @ pdb::Error::UnimplementedTypeKind(_))) => {
-                warn!("Could not parse type: {}", e);
-                continue;
-            }
-            // TypeNotFound is commonly raised because the PDB spec is not open, so
-            // some types are unknown to this crate. We can ignore these and just fail
-            // any type depending on something we cannot resolve.
-            Err(Error::PdbCrateError(e @ pdb::Error::TypeNotFound(_))) => {
-                warn!("{}", e);
-                continue;
-            }
-            Err(e) => return Err(e),
+    for (processed, (typ, offset, kind)) in discovered_types.iter().enumerate() {
+        progress.progress(ParseStage::Types, processed + 1, total);
+        // TypeNotFound is commonly raised because the PDB spec is not open, so
+        // some types are unknown to this crate. Unless the policy says otherwise
+        // these are skipped, failing any type depending on something we cannot resolve.
+        // Kinds the `pdb` crate can't parse become partial types, which are still
+        // reported if there's no fallback decoder for them.
+        let description = match handle_type(*typ, &mut output_pdb, &type_finder) {
+            Ok(id) => match &output_pdb.types[id] {
+                Type::Partial(partial)
+                    if partial.kind != raw::LF_VTSHAPE
+                        && !output_pdb.partial_types.contains_key(&typ.0) =>
+                {
+                    Some((
+                        DiagnosticCategory::UnknownKind,
+                        format!(
+                            "type kind 0x{:04X} is not supported, so only its kind is known",
+                            partial.kind
+                        ),
+                    ))
+                }
+                _ => None,
+            },
+            Err(e) => Some((error_category(&e), e.to_string())),
         };
+        if let Some((category, description)) = description {
+            report(
+                &mut output_pdb,
+                options,
+                Diagnostic {
+                    category,
+                    stream: Some(u32::from(raw::TPI_STREAM)),
+                    offset: Some(*offset),
+                    kind: Some(*kind),
+                    type_index: Some(typ.0),
+                    description,
+                },
+            )?;
+        }
     }
 
-    // Iterate through all of the parsed types once just to update any necessary info
-    for typ in output_pdb.types.values() {
-        use crate::type_info::Typed;
+    complete_types(&mut output_pdb);
+    progress.stage_finished(ParseStage::Types);
+    stage.record("items", discovered_types.len());
+    drop(stage);
 
-        typ.as_ref().borrow_mut().on_complete(&output_pdb);
+    if tracing::enabled!(tracing::Level::TRACE) {
+        for (_id, typ) in output_pdb.types.iter() {
+            trace!("{:#?}", typ);
+        }
     }
 
-    // Iterate through all of the parsed types once just to update any necessary info
-    // for typ in output_pdb.types.values() {
-    //     println!("{:#?}", typ.as_ref().borrow());
-    // }
-
-    debug!("grabbing public symbols");
-    // Parse public symbols
-    let symbol_table = pdb.global_symbols()?;
-    let mut symbols = symbol_table.iter();
-    while let Some(symbol) = symbols.next()? {
-        if let Err(e) = handle_symbol(
-            symbol,
-            &mut output_pdb,
-            address_map.as_ref(),
-            &type_finder,
-            id_finder.as_ref(),
-            base_address,
-        ) {
-            warn!("Error handling symbol {:?}: {}", symbol, e);
-        }
-    }
-
-    debug!("grabbing debug modules");
-    // Parse private symbols
-    let debug_info = pdb.debug_information()?;
-    let mut modules = debug_info.modules()?;
-    while let Some(module) = modules.next()? {
-        let module_info = pdb.module_info(&module)?;
-        output_pdb
-            .debug_modules
-            .push((&module, module_info.as_ref(), string_table.as_ref()).into());
-        if module_info.is_none() {
-            warn!("Could not get module info for debug module: {:?}", module);
-            continue;
+    // Symbols, modules, and section contributions are all found through the
+    // DBI stream
+    let debug_info = match pdb.debug_information() {
+        Ok(debug_info) => Some(debug_info),
+        Err(e) => {
+            report(
+                &mut output_pdb,
+                options,
+                Diagnostic {
+                    category: DiagnosticCategory::MissingStream,
+                    stream: Some(u32::from(raw::DBI_STREAM)),
+                    offset: None,
+                    kind: None,
+                    type_index: None,
+                    description: format!(
+                        "could not read the DBI stream, so symbols and modules won't be loaded: {}",
+                        e
+                    ),
+                },
+            )?;
+            None
+        }
+    };
+    if let Some(debug_info) = debug_info {
+        debug!("grabbing public symbols");
+        // Parse public symbols
+        let dbi = raw::read_stream(&mut pdb, raw::DBI_STREAM)?;
+        let dbi_header = dbi.as_deref().map(raw::DbiHeader::parse).transpose()?;
+        if let Some(dbi) = dbi.as_deref() {
+            match read_token_rid_map(&mut pdb, dbi) {
+                Ok(map) => output_pdb.token_rid_map = map,
+                Err(e) => warn!("could not read the token map: {}", e),
+            }
         }
+        let symbol_table = pdb.global_symbols()?;
+        let stage = stage_span(ParseStage::GlobalSymbols).entered();
+        progress.stage_started(ParseStage::GlobalSymbols, None);
 
-        debug!("grabbing symbols for module: {}", module.module_name());
-        let module_info = module_info.unwrap();
-        let mut symbol_iter = module_info.symbols()?;
-        while let Some(symbol) = symbol_iter.next()? {
+        let mut symbols = symbol_table.iter();
+        let mut processed = 0;
+        while let Some(symbol) = symbols.next()? {
+            processed += 1;
+            progress.progress(ParseStage::GlobalSymbols, processed, None);
             if let Err(e) = handle_symbol(
                 symbol,
                 &mut output_pdb,
-                address_map.as_ref(),
+                &address_map,
                 &type_finder,
                 id_finder.as_ref(),
-                base_address,
+                options,
+                SymbolScope::default(),
             ) {
-                warn!("Error handling symbol {:?}: {}", symbol, e);
+                let stream = dbi_header.map(|header| header.symbol_records_stream);
+                report(
+                    &mut output_pdb,
+                    options,
+                    symbol_diagnostic(&symbol, stream, &e),
+                )?;
+            }
+        }
+        progress.stage_finished(ParseStage::GlobalSymbols);
+        stage.record("items", processed);
+        drop(stage);
+
+        debug!("grabbing debug modules");
+        // Parse private symbols
+        let modules: Vec<_> = debug_info.modules()?.collect()?;
+        let module_count = modules.len();
+        let total = Some(module_count);
+        let stage = stage_span(ParseStage::ModuleSymbols).entered();
+        progress.stage_started(ParseStage::ModuleSymbols, total);
+        let module_streams = match dbi.as_deref().map(raw::module_streams) {
+            Some(Ok(streams)) => streams,
+            Some(Err(e)) => {
+                warn!("could not read module stream indices: {}", e);
+                vec![]
+            }
+            None => vec![],
+        };
+
+        for (processed, module) in modules.into_iter().enumerate() {
+            progress.progress(ParseStage::ModuleSymbols, processed + 1, total);
+            let module_info = pdb.module_info(&module)?;
+            output_pdb
+                .debug_modules
+                .push((&module, module_info.as_ref(), string_table.as_ref()).into_model());
+            let module_stream = module_streams.get(processed).copied();
+            // Modules of stripped PDBs have no stream on purpose
+            if module_info.is_none() && module_stream == Some(u16::MAX) {
+                debug!("module {} has no symbol stream", module.module_name());
+                continue;
+            }
+            if module_info.is_none() {
+                report(
+                    &mut output_pdb,
+                    options,
+                    Diagnostic {
+                        category: DiagnosticCategory::MissingStream,
+                        stream: module_stream.map(u32::from),
+                        offset: None,
+                        kind: None,
+                        type_index: None,
+                        description: format!(
+                            "could not get module info for `{}`",
+                            module.module_name()
+                        ),
+                    },
+                )?;
+                continue;
+            }
+
+            debug!("grabbing symbols for module: {}", module.module_name());
+            let module_info = module_info.unwrap();
+            let module_name = module.module_name();
+            // Names of the procedures enclosing the current symbol, or `None` for
+            // other kinds of scopes (e.g. blocks)
+            let mut scopes: Vec<Option<String>> = vec![];
+            let line_program = module_info.line_program().ok();
+            let mut symbol_iter = module_info.symbols()?;
+            while let Some(symbol) = symbol_iter.next()? {
+                if let Some(module) = output_pdb.debug_modules.last_mut() {
+                    module.symbol_count += 1;
+                    if symbol.raw_kind() == symbol_types::S_UDT {
+                        module.type_count += 1;
+                    }
+                }
+
+                let procedure_count = output_pdb.procedures.len();
+                let scope = SymbolScope {
+                    module: Some(&module_name),
+                    module_index: Some(processed),
+                    function: scopes.iter().rev().flatten().next().map(String::as_str),
+                    in_function_body: matches!(scopes.last(), Some(Some(_))),
+                };
+                if let Err(e) = handle_symbol(
+                    symbol,
+                    &mut output_pdb,
+                    &address_map,
+                    &type_finder,
+                    id_finder.as_ref(),
+                    options,
+                    scope,
+                ) {
+                    report(
+                        &mut output_pdb,
+                        options,
+                        symbol_diagnostic(&symbol, module_stream, &e),
+                    )?;
+                }
+
+                if output_pdb.procedures.len() > procedure_count {
+                    if let (Some(program), Ok(SymbolData::Procedure(data))) =
+                        (&line_program, symbol.parse())
+                    {
+                        let source = procedure_source(program, string_table.as_ref(), data.offset);
+                        if let Some(procedure) = output_pdb.procedures.last_mut() {
+                            procedure.source = source;
+                        }
+                    }
+                }
+
+                if symbol.starts_scope() {
+                    scopes.push(match symbol.parse() {
+                        Ok(SymbolData::Procedure(data)) => Some(data.name.to_string().into_owned()),
+                        _ => None,
+                    });
+                } else if symbol.ends_scope() {
+                    scopes.pop();
+                }
+            }
+        }
+        progress.stage_finished(ParseStage::ModuleSymbols);
+        stage.record("items", module_count);
+        drop(stage);
+
+        let mut contributions = debug_info.section_contributions()?;
+        while let Some(contribution) = contributions.next()? {
+            if let Some(module) = output_pdb.debug_modules.get_mut(contribution.module) {
+                module.contribution_size += contribution.size as usize;
             }
         }
     }
 
+    // A call is usually recorded by both the caller's `S_CALLEES` and the
+    // callee's `S_CALLERS`
+    output_pdb
+        .call_graph
+        .sort_by(|a, b| (&a.caller, &a.callee).cmp(&(&b.caller, &b.callee)));
+    output_pdb
+        .call_graph
+        .dedup_by(|a, b| a.caller == b.caller && a.callee == b.callee);
+
+    link_procedures(&mut output_pdb);
+    folding::find_folded_symbols(&mut output_pdb);
+    find_static_initializers(&mut output_pdb);
+    output_pdb.security_symbols = security::security_symbols(&output_pdb);
+    output_pdb.provenance = provenance::provenance(&output_pdb);
+    origin::classify_procedures(&mut output_pdb);
+    hybrid::tag_isas(&mut output_pdb);
+    output_pdb.hybrid = hybrid::hybrid_info(&output_pdb);
+    // The section headers are only needed if the linker didn't record its sections
+    let section_headers = if output_pdb.sections.is_empty() {
+        match pdb.sections() {
+            Ok(headers) => headers.unwrap_or_default(),
+            Err(e) => {
+                warn!("could not read the section headers: {}", e);
+                vec![]
+            }
+        }
+    } else {
+        vec![]
+    };
+    storage::classify_data(&mut output_pdb, &section_headers);
+    if options.build_index {
+        output_pdb.build_index();
+    }
+
     Ok(output_pdb)
 }
 
+/// Returns the source file and range of lines of the procedure at `offset`. Lines
+/// from other files, e.g. of functions inlined from headers, are left out.
+fn procedure_source(
+    program: &pdb::LineProgram<'_>,
+    string_table: Option<&pdb::StringTable<'_>>,
+    offset: pdb::PdbInternalSectionOffset,
+) -> Option<symbol_types::SourceRange> {
+    let lines: Vec<pdb::LineInfo> = program.lines_for_symbol(offset).collect().ok()?;
+    let file_index = lines
+        .iter()
+        .min_by_key(|line| (line.offset.section, line.offset.offset))?
+        .file_index;
+    let (first_line, last_line) = lines
+        .iter()
+        .filter(|line| line.file_index == file_index)
+        .fold((u32::MAX, 0), |(first, last), line| {
+            (
+                first.min(line.line_start),
+                last.max(line.line_end.max(line.line_start)),
+            )
+        });
+
+    let file = program
+        .get_file_info(file_index)
+        .ok()?
+        .name
+        .to_string_lossy(string_table?)
+        .ok()?
+        .into_owned();
+
+    Some(symbol_types::SourceRange {
+        file,
+        first_line,
+        last_line,
+    })
+}
+
+/// The source PDB data is read from. Views of PDBs held in memory, mapped or
+/// not, borrow from the data rather than copying it whenever the pages viewed
+/// are contiguous.
+#[derive(Debug)]
+pub(crate) enum PdbSource {
+    #[cfg(feature = "mmap")]
+    Mapped(Arc<memmap2::Mmap>),
+    #[cfg(not(feature = "mmap"))]
+    File(File),
+    /// A PDB held in memory, such as a PDB 2.0 file rewritten as an MSF 7.00 file
+    Memory(Arc<Vec<u8>>),
+}
+
+impl<'s> pdb::Source<'s> for PdbSource {
+    fn view(
+        &mut self,
+        slices: &[pdb::SourceSlice],
+    ) -> std::io::Result<Box<dyn pdb::SourceView<'s>>> {
+        match self {
+            #[cfg(feature = "mmap")]
+            PdbSource::Mapped(map) => SharedView::view(map, slices),
+            #[cfg(not(feature = "mmap"))]
+            PdbSource::File(file) => pdb::Source::view(file, slices),
+            PdbSource::Memory(data) => SharedView::view(data, slices),
+        }
+    }
+}
+
+/// A view of PDB data held in memory
+enum SharedView<T> {
+    /// Contiguous pages, borrowed from the data
+    Borrowed {
+        data: Arc<T>,
+        range: std::ops::Range<usize>,
+    },
+    /// Pages scattered through the data, copied together
+    Copied(Vec<u8>),
+}
+
+impl<T: AsRef<[u8]> + Send + Sync + 'static> SharedView<T> {
+    fn view<'s>(
+        data: &Arc<T>,
+        slices: &[pdb::SourceSlice],
+    ) -> std::io::Result<Box<dyn pdb::SourceView<'s>>> {
+        let bytes: &[u8] = (**data).as_ref();
+        let range = |slice: &pdb::SourceSlice| {
+            let start = usize::try_from(slice.offset).ok()?;
+            let end = start.checked_add(slice.size)?;
+            bytes.get(start..end).map(|_| start..end)
+        };
+        let ranges = slices
+            .iter()
+            .map(range)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "view extends past the end of the PDB",
+                )
+            })?;
+
+        let is_contiguous = ranges.windows(2).all(|pair| pair[0].end == pair[1].start);
+        let view = match (ranges.first(), ranges.last()) {
+            (Some(first), Some(last)) if is_contiguous => SharedView::Borrowed {
+                data: Arc::clone(data),
+                range: first.start..last.end,
+            },
+            _ => SharedView::Copied(
+                ranges
+                    .into_iter()
+                    .flat_map(|range| &bytes[range])
+                    .copied()
+                    .collect(),
+            ),
+        };
+
+        Ok(Box::new(view))
+    }
+}
+
+impl<T: AsRef<[u8]>> pdb::SourceView<'_> for SharedView<T> {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            SharedView::Borrowed { data, range } => &(**data).as_ref()[range.clone()],
+            SharedView::Copied(bytes) => bytes,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SharedView<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SharedView::Borrowed { range, .. } => write!(f, "SharedView({:?})", range),
+            SharedView::Copied(bytes) => write!(f, "SharedView({} bytes copied)", bytes.len()),
+        }
+    }
+}
+
+/// Opens the PDB at `path`. When the `mmap` feature is enabled the file is
+/// memory-mapped so that stream access is served directly from the page cache.
+/// PDB 2.0 files are converted to the MSF 7.00 format in memory.
+pub(crate) fn open_pdb(path: &Path) -> Result<PDB<'static, PdbSource>, Error> {
+    Ok(PDB::open(open_source(path)?)?)
+}
+
+/// Opens the file at `path` for reading as an MSF 7.00 file, converting PDB 2.0 files
+pub(crate) fn open_source(path: &Path) -> Result<PdbSource, Error> {
+    let mut file = File::open(path)?;
+
+    let mut header = [0; 64];
+    let header_len = file.read(&mut header)?;
+    if msf::is_small_msf(&header[..header_len]) {
+        debug!("converting PDB 2.0 file");
+        let data = std::fs::read(path)?;
+        let converted = msf::convert_small_msf(&data)?;
+        return Ok(PdbSource::Memory(Arc::new(converted)));
+    }
+    file.seek(SeekFrom::Start(0))?;
+
+    #[cfg(feature = "mmap")]
+    let source = {
+        // SAFETY: the mapping is read-only. Like any other mmap consumer we can't
+        // guard against the file being truncated by another process while it's mapped.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        PdbSource::Mapped(Arc::new(map))
+    };
+    #[cfg(not(feature = "mmap"))]
+    let source = PdbSource::File(file);
+
+    Ok(source)
+}
+
+/// Reads the header-level information of the PDB at `path` from its PDB
+/// information and DBI streams alone. Types and module streams aren't touched,
+/// so this is fast regardless of the PDB's size.
+pub fn read_summary<P: AsRef<Path>>(path: P) -> Result<symbol_types::PdbSummary, Error> {
+    let path = path.as_ref();
+    let mut source = open_source(path)?;
+    let stream_count = msf::stream_count(&mut source)?;
+    let mut pdb = PDB::open(source)?;
+
+    let mut header = ParsedPdb::new(path.to_owned());
+    read_header_info(&mut pdb, &mut header)?;
+    let module_count = match pdb.debug_information() {
+        Ok(dbi) => dbi.modules()?.count()?,
+        // Already warned about by `read_header_info`
+        Err(_) => 0,
+    };
+
+    Ok(symbol_types::PdbSummary {
+        path: header.path,
+        version: header.version,
+        guid: header.guid,
+        age: header.age,
+        timestamp: header.timestamp,
+        machine_type: header.machine_type,
+        is_fastlink: header.is_fastlink,
+        stream_count,
+        module_count,
+    })
+}
+
+/// Fills in the header-level information (version, GUID, machine type, etc.) of `output_pdb`.
+/// PDBs without a DBI stream, or with the DBI header of Visual C++ 4.0 and
+/// older, have no machine type and the age of their PDB information stream.
+fn read_header_info<'s, S: pdb::Source<'s> + 's>(
+    pdb: &mut PDB<'s, S>,
+    output_pdb: &mut ParsedPdb,
+) -> Result<(), Error> {
+    let pdbi = pdb.pdb_information()?;
+    let dbi = match pdb.debug_information() {
+        Ok(dbi) => Some(dbi),
+        Err(e) => {
+            warn!("could not read the DBI stream's header: {}", e);
+            None
+        }
+    };
+    output_pdb.machine_type = dbi
+        .as_ref()
+        .and_then(|dbi| dbi.machine_type().ok())
+        .map(|machine_type| (&machine_type).into_model());
+    if matches!(
+        output_pdb.machine_type,
+        Some(symbol_types::MachineType::Unknown)
+    ) {
+        // The `pdb` crate reads machine types it doesn't know, such as the
+        // hybrid ones, as unknown, so look at the raw value
+        let header = raw::dbi_header(pdb)?;
+        output_pdb.machine_type = Some(symbol_types::machine_type_from_raw(header.machine_type));
+    }
+
+    output_pdb.age = match dbi.as_ref().and_then(|dbi| dbi.age()) {
+        Some(age) => age,
+        None => pdbi.age,
+    };
+
+    output_pdb.guid = pdbi.guid;
+    output_pdb.timestamp = pdbi.signature;
+    output_pdb.version = symbol_types::version_from_raw(raw::pdb_version(pdb)?);
+
+    output_pdb.is_fastlink = match raw::pdb_features(pdb) {
+        Ok(features) => features.contains(&raw::FEATURE_MINIMAL_DEBUG_INFO),
+        Err(e) => {
+            warn!("could not read PDB features: {}", e);
+            false
+        }
+    };
+    if output_pdb.is_fastlink {
+        warn!("the PDB was linked with /DEBUG:FASTLINK and references debug information in object files");
+    }
+
+    Ok(())
+}
+
+/// Reads every `LF_VTSHAPE` record into [ParsedPdb::vtable_shapes], the
+/// representation of pointers to members into
+/// [ParsedPdb::member_pointer_representations], and records of other kinds with
+/// a fallback decoder into [ParsedPdb::partial_types]. The `pdb` crate can't
+/// parse these, so the records are read directly from the TPI stream. This must
+/// be called before parsing types so that classes can be given their shape and
+/// references to the other records can be resolved.
+///
+/// If `wanted` is given, only the records with those type indices are read,
+/// and the stream isn't walked past the last of them.
+///
+/// Malformed records are reported as [DiagnosticCategory::Corrupt], and only
+/// stop parsing if `options.fail_on` does.
+fn read_raw_type_records<'s, S: pdb::Source<'s> + 's>(
+    pdb: &mut PDB<'s, S>,
+    output_pdb: &mut ParsedPdb,
+    options: &ParseOptions,
+    wanted: Option<&BTreeSet<symbol_types::TypeIndexNumber>>,
+) -> Result<(), Error> {
+    let corrupt = |offset, kind, type_index, error: &dyn std::fmt::Display| Diagnostic {
+        category: DiagnosticCategory::Corrupt,
+        stream: Some(u32::from(raw::TPI_STREAM)),
+        offset,
+        kind,
+        type_index,
+        description: format!("could not read the raw type record: {}", error),
+    };
+
+    let tpi = match raw::read_stream(pdb, raw::TPI_STREAM) {
+        Ok(Some(tpi)) => tpi,
+        Ok(None) => return Ok(()),
+        Err(e) => return report(output_pdb, options, corrupt(None, None, None, &e)),
+    };
+
+    if tpi.len() < raw::TPI_HEADER_SIZE {
+        let e = Error::PdbCrateError(pdb::Error::UnexpectedEof);
+        return report(output_pdb, options, corrupt(Some(0), None, None, &e));
+    }
+
+    // The header starts with its version, its size, and the index of the first type
+    let mut reader = raw::ByteReader::new(&tpi);
+    let _version = reader.u32()?;
+    let header_size = reader.u32()?;
+    let mut type_index = reader.u32()?;
+    reader.seek(header_size as usize);
+
+    let is_past_wanted =
+        |type_index| wanted.is_some_and(|wanted| wanted.range(type_index..).next().is_none());
+
+    // Each record is its length (not including the length itself) followed by its kind
+    while reader.remaining() >= 2 && !is_past_wanted(type_index) {
+        let offset = reader.position();
+        let len = reader.u16()?;
+        let mut record = match reader.take(len as usize) {
+            Ok(record) => raw::ByteReader::new(record),
+            // The record runs past the end of the stream, so no records follow it
+            Err(e) => {
+                return report(
+                    output_pdb,
+                    options,
+                    corrupt(Some(offset), None, Some(type_index), &e),
+                )
+            }
+        };
+
+        if wanted.is_some_and(|wanted| !wanted.contains(&type_index)) {
+            type_index += 1;
+            continue;
+        }
+
+        let kind = record.u16().ok();
+        let result = match kind {
+            Some(kind) => read_raw_type_record(kind, &mut record, type_index, output_pdb),
+            None => Err(Error::PdbCrateError(pdb::Error::UnexpectedEof)),
+        };
+        if let Err(e) = result {
+            report(
+                output_pdb,
+                options,
+                corrupt(Some(offset), kind, Some(type_index), &e),
+            )?;
+        }
+
+        type_index += 1;
+    }
+
+    Ok(())
+}
+
+/// Reads the TPI record `type_index` of kind `kind` for [read_raw_type_records].
+/// `record` starts just after the record's kind.
+pub(crate) fn read_raw_type_record(
+    kind: u16,
+    record: &mut raw::ByteReader<'_>,
+    type_index: symbol_types::TypeIndexNumber,
+    output_pdb: &mut ParsedPdb,
+) -> Result<(), Error> {
+    if kind == raw::LF_VTSHAPE {
+        let count = record.u16()?;
+        // Slots are described by 4-bit descriptors, low nibble first
+        let descriptors = record.take((count as usize).div_ceil(2))?;
+        let slots = (0..count as usize)
+            .map(|slot| {
+                let byte = descriptors[slot / 2];
+                match if slot % 2 == 0 { byte & 0xF } else { byte >> 4 } {
+                    0 => VTableSlotKind::Near16,
+                    1 => VTableSlotKind::Far16,
+                    2 => VTableSlotKind::Thin,
+                    3 => VTableSlotKind::Outer,
+                    4 => VTableSlotKind::Meta,
+                    5 => VTableSlotKind::Near32,
+                    6 => VTableSlotKind::Far32,
+                    _ => VTableSlotKind::Unused,
+                }
+            })
+            .collect();
+
+        output_pdb
+            .vtable_shapes
+            .insert(type_index, VTableShape { slots });
+    } else if kind == raw::LF_POINTER {
+        if let Some(pmtype) = raw::member_pointer_representation(record)? {
+            output_pdb
+                .member_pointer_representations
+                .insert(type_index, pmtype);
+        }
+    } else {
+        // Records the fallback decoder can't read are left to the `pdb` crate,
+        // which reports them when they're parsed
+        match raw::decode_partial_record(kind, record) {
+            Ok(Some(partial)) => {
+                let name = partial.name.map(|name| output_pdb.strings.intern(&name));
+                output_pdb.partial_types.insert(
+                    type_index,
+                    Partial {
+                        kind,
+                        kind_name: raw::leaf_kind_name(kind).map(str::to_string),
+                        name,
+                        referenced_type_index: partial.referenced_type_index,
+                        referenced_type: None,
+                    },
+                );
+            }
+            Ok(None) => {}
+            Err(e) => debug!(
+                "could not decode type 0x{:X} of kind 0x{:04X}: {}",
+                type_index, kind, e
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the token map stream of a mixed-mode PDB, returning an empty map if
+/// the PDB doesn't have one
+fn read_token_rid_map<'s, S: pdb::Source<'s> + 's>(
+    pdb: &mut PDB<'s, S>,
+    dbi: &[u8],
+) -> Result<Vec<u32>, Error> {
+    let stream = match raw::debug_header_stream(dbi, raw::DEBUG_STREAM_TOKEN_RID_MAP)? {
+        Some(stream) => stream,
+        None => return Ok(vec![]),
+    };
+    let data = match raw::read_stream(pdb, stream)? {
+        Some(data) => data,
+        None => return Ok(vec![]),
+    };
+
+    let mut reader = raw::ByteReader::new(&data);
+    let mut map = Vec::with_capacity(data.len() / 4);
+    while reader.remaining() >= 4 {
+        map.push(reader.u32()?);
+    }
+
+    Ok(map)
+}
+
+/// Reads the TPI record with the index `type_index` from the PDB at `path`
+/// without parsing it, so that records the `pdb` crate can't parse can still be
+/// inspected. Returns `None` if the TPI stream has no such record.
+pub fn read_raw_type<P: AsRef<Path>>(
+    path: P,
+    type_index: symbol_types::TypeIndexNumber,
+) -> Result<Option<symbol_types::RawTypeRecord>, Error> {
+    let mut pdb = open_pdb(path.as_ref())?;
+    let tpi = raw::read_stream(&mut pdb, raw::TPI_STREAM)?
+        .ok_or(Error::MissingDependency("TPI stream"))?;
+
+    let mut reader = raw::ByteReader::new(&tpi);
+    let _version = reader.u32()?;
+    let header_size = reader.u32()?;
+    let first_index = reader.u32()?;
+    if type_index < first_index {
+        return Ok(None);
+    }
+    reader.seek(header_size as usize);
+
+    let mut current = first_index;
+    while reader.remaining() >= 2 {
+        let offset = reader.position();
+        let len = reader.u16()?;
+        let record = reader.take(len as usize)?;
+        if current != type_index {
+            current += 1;
+            continue;
+        }
+
+        let kind = raw::ByteReader::new(record).u16()?;
+        // See whether the `pdb` crate can parse the record, so that the reason
+        // it can't is reported alongside its bytes
+        let parse_error = match pdb.type_information() {
+            Ok(type_information) => {
+                let mut iter = type_information.iter();
+                loop {
+                    match iter.next() {
+                        Ok(Some(typ)) if typ.index() == TypeIndex(type_index) => {
+                            break typ.parse().err().map(|e| e.to_string())
+                        }
+                        Ok(Some(_)) => continue,
+                        Ok(None) => break None,
+                        Err(e) => break Some(e.to_string()),
+                    }
+                }
+            }
+            Err(e) => Some(e.to_string()),
+        };
+
+        let mut data = len.to_le_bytes().to_vec();
+        data.extend_from_slice(record);
+        return Ok(Some(symbol_types::RawTypeRecord {
+            type_index,
+            kind,
+            kind_name: raw::leaf_kind_name(kind).map(str::to_string),
+            offset,
+            data,
+            parse_error,
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Parses only the class, union, or enum named `name` and the types it depends
+/// upon. The TPI stream is only walked as far as the first complete (i.e. not
+/// forward-declared) definition of `name`, and only the records the type
+/// depends upon are parsed, so this is considerably cheaper than [parse_pdb]
+/// when only a single type is needed. Malformed records elsewhere in the
+/// stream don't get in the way.
+///
+/// The returned [ParsedPdb] contains no symbols and only the types in the
+/// dependency closure of the requested type.
+pub fn parse_single_type<P: AsRef<Path>>(
+    path: P,
+    name: &str,
+    options: &ParseOptions,
+) -> Result<Option<(ParsedPdb, TypeId)>, crate::error::Error> {
+    let (output_pdb, ids) = parse_types(path, &[name], options)?;
+
+    Ok(ids[0].map(|id| (output_pdb, id)))
+}
+
+/// Parses only the classes, unions, and enums named `names` and the types they
+/// depend upon, like [parse_single_type] does for a single type. The TPI stream
+/// is only walked as far as the last of the first definitions of each name.
+///
+/// Returns the [TypeId] of each of `names` in order, or `None` for names no
+/// definition was found for.
+pub fn parse_types<P: AsRef<Path>>(
+    path: P,
+    names: &[&str],
+    options: &ParseOptions,
+) -> Result<(ParsedPdb, Vec<Option<TypeId>>), crate::error::Error> {
+    let mut pdb = open_pdb(path.as_ref())?;
+
+    let mut header = ParsedPdb::new(path.as_ref().to_owned());
+    read_header_info(&mut pdb, &mut header)?;
+
+    let type_information = pdb.type_information()?;
+    let mut type_finder = type_information.finder();
+    let mut iter = type_information.iter();
+    let mut found: Vec<Option<TypeIndex>> = vec![None; names.len()];
+    while found.iter().any(Option::is_none) {
+        let typ = match iter.next()? {
+            Some(typ) => typ,
+            None => break,
+        };
+        type_finder.update(&iter);
+
+        let (name, properties) = match typ.parse() {
+            Ok(TypeData::Class(data)) => (data.name, data.properties),
+            Ok(TypeData::Union(data)) => (data.name, data.properties),
+            Ok(TypeData::Enumeration(data)) => (data.name, data.properties),
+            _ => continue,
+        };
+        if properties.forward_reference() {
+            continue;
+        }
+
+        for (index, wanted) in names.iter().enumerate() {
+            if found[index].is_none() && name.as_bytes() == wanted.as_bytes() {
+                found[index] = Some(typ.index());
+            }
+        }
+    }
+
+    // Which raw records the types need is only known once they're converted, so
+    // they're converted again if the records read so far don't cover them all.
+    // A type only ever references types with a lower index, so everything they
+    // depend on is already known to the finder.
+    let mut raw_indices = BTreeSet::new();
+    loop {
+        let mut output_pdb = header.clone();
+        read_raw_type_records(&mut pdb, &mut output_pdb, options, Some(&raw_indices))?;
+        let ids = found
+            .iter()
+            .map(|index| {
+                index
+                    .map(|index| handle_type(index, &mut output_pdb, &type_finder))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let needed = raw_type_indices(&output_pdb, &type_finder);
+        if needed.is_subset(&raw_indices) {
+            complete_types(&mut output_pdb);
+            return Ok((output_pdb, ids));
+        }
+        raw_indices.extend(needed);
+    }
+}
+
+/// Returns the indices of the records [read_raw_type_records] reads something
+/// from which the types of `output_pdb` depend upon: those of pointers to
+/// members, of records the `pdb` crate can't parse, and of the vtable shapes
+/// of classes
+fn raw_type_indices(
+    output_pdb: &ParsedPdb,
+    type_finder: &ItemFinder<'_, TypeIndex>,
+) -> BTreeSet<symbol_types::TypeIndexNumber> {
+    let mut indices = BTreeSet::new();
+    for (&index, &id) in &output_pdb.type_indices {
+        if let Type::Partial(_) = output_pdb.types[id] {
+            indices.insert(index);
+            continue;
+        }
+
+        match type_finder.find(TypeIndex(index)).map(|typ| typ.parse()) {
+            Ok(Ok(TypeData::Pointer(pointer))) if pointer.containing_class.is_some() => {
+                indices.insert(index);
+            }
+            Ok(Ok(TypeData::Class(class))) => {
+                indices.extend(class.vtable_shape.map(|shape| shape.0));
+            }
+            _ => {}
+        }
+    }
+
+    indices
+}
+
+/// Looks up the public symbol named `name` in the PDB at `path` using the publics
+/// stream's hash table. Unlike [parse_pdb], only the symbols which share a hash
+/// bucket with `name` are read.
+pub fn find_public_symbol<P: AsRef<Path>>(
+    path: P,
+    name: &str,
+    base_address: Option<usize>,
+) -> Result<Option<crate::symbol_types::PublicSymbol>, crate::error::Error> {
+    let mut pdb = open_pdb(path.as_ref())?;
+
+    let dbi_header = raw::dbi_header(&mut pdb)?;
+    let publics = raw::read_stream(&mut pdb, dbi_header.ps_symbols_stream)?
+        .ok_or(Error::MissingDependency("public symbols stream"))?;
+    let hash_table = gsi::GsiHashTable::parse_publics(&publics)?;
+
+    let address_map = AddressMapping::from_pdb(&mut pdb);
+    let symbol_table = pdb.global_symbols()?;
+
+    for offset in hash_table.candidates(name) {
+        let symbol = match symbol_table.iter_at(pdb::SymbolIndex(*offset)).next()? {
+            Some(symbol) => symbol,
+            None => continue,
+        };
+
+        if let SymbolData::Public(data) = symbol.parse()? {
+            if data.name.as_bytes() == name.as_bytes() {
+                return Ok(Some((data, base_address, &address_map).into_model()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Fills in [ParsedPdb::static_initializers] from the names of procedures and
+/// public symbols, and the symbols which lie in the CRT's initializer tables.
+/// This is done automatically by [parse_pdb], and must be done after
+/// [link_procedures].
+pub fn find_static_initializers(pdb: &mut ParsedPdb) {
+    use crate::symbol_types::{Address, StaticInitializer, StaticInitializerKind};
+
+    // Undecorated names, as procedures have
+    const INITIALIZER: &str = "`dynamic initializer for '";
+    const DESTRUCTOR: &str = "`dynamic atexit destructor for '";
+    // Decorated names, as public symbols have
+    const DECORATED_INITIALIZER: &str = "??__E";
+    const DECORATED_DESTRUCTOR: &str = "??__F";
+
+    let mut initializers = vec![];
+    for procedure in &pdb.procedures {
+        let (kind, variable) = if let Some(rest) = procedure.name.strip_prefix(INITIALIZER) {
+            (StaticInitializerKind::DynamicInitializer, rest)
+        } else if let Some(rest) = procedure.name.strip_prefix(DESTRUCTOR) {
+            (StaticInitializerKind::AtexitDestructor, rest)
+        } else {
+            continue;
+        };
+
+        initializers.push(StaticInitializer {
+            kind,
+            name: procedure.name.clone(),
+            offset: procedure.address,
+            variable: Some(variable.trim_end_matches('\'').to_string()),
+            group: None,
+        });
+    }
+
+    // Public symbols of procedures were covered above
+    for symbol in pdb
+        .public_symbols
+        .iter()
+        .filter(|symbol| symbol.procedure_name.is_none())
+    {
+        let (kind, variable) = if let Some(rest) = symbol.name.strip_prefix(DECORATED_INITIALIZER) {
+            (StaticInitializerKind::DynamicInitializer, rest)
+        } else if let Some(rest) = symbol.name.strip_prefix(DECORATED_DESTRUCTOR) {
+            (StaticInitializerKind::AtexitDestructor, rest)
+        } else {
+            continue;
+        };
+
+        initializers.push(StaticInitializer {
+            kind,
+            name: symbol.name.clone(),
+            offset: symbol.offset,
+            // The decoration of the generated function's `void __cdecl(void)` signature
+            variable: Some(variable.trim_end_matches("@@YAXXZ").to_string()),
+            group: None,
+        });
+    }
+
+    let tables: Vec<_> = pdb
+        .coff_groups
+        .iter()
+        .filter(|group| {
+            [".CRT$XC", ".CRT$XI", ".CRT$XP", ".CRT$XT"]
+                .iter()
+                .any(|prefix| group.name.starts_with(prefix))
+        })
+        .filter_map(|group| {
+            group
+                .offset
+                .map(|offset| (offset.rva..offset.rva + group.len, group))
+        })
+        .collect();
+    let table_entry = |name: &str, offset: Option<Address>| {
+        // The CRT's `__xc_a`, `__xc_z`, etc. mark the start and end of each table
+        let is_bound = name.len() == 6
+            && name.starts_with("__x")
+            && (name.ends_with("_a") || name.ends_with("_z"));
+        if is_bound {
+            return None;
+        }
+
+        let offset = offset?;
+        let (_range, group) = tables
+            .iter()
+            .find(|(range, _group)| range.contains(&offset.rva))?;
+        Some(StaticInitializer {
+            kind: StaticInitializerKind::CrtTableEntry,
+            name: name.to_string(),
+            offset: Some(offset),
+            variable: None,
+            group: Some(group.name.clone()),
+        })
+    };
+
+    let mut entries: Vec<StaticInitializer> = pdb
+        .global_data
+        .iter()
+        .filter_map(|global| table_entry(&global.name, global.offset))
+        .collect();
+    // Entries are usually both a global and a public symbol
+    let mut entry_offsets: HashSet<Address> =
+        entries.iter().filter_map(|entry| entry.offset).collect();
+    for symbol in &pdb.public_symbols {
+        if let Some(entry) = table_entry(&symbol.name, symbol.offset) {
+            if entry_offsets.insert(symbol.offset.unwrap()) {
+                entries.push(entry);
+            }
+        }
+    }
+    initializers.extend(entries);
+
+    initializers.sort_by_key(|initializer| (initializer.offset.is_none(), initializer.offset));
+    pdb.static_initializers = initializers;
+}
+
+/// Cross-links each procedure with the public symbol at the same address by
+/// filling in [Procedure::public_name] and [PublicSymbol::procedure_name]. This
+/// is done automatically by [parse_pdb].
+///
+/// [Procedure::public_name]: crate::symbol_types::Procedure::public_name
+/// [PublicSymbol::procedure_name]: crate::symbol_types::PublicSymbol::procedure_name
+pub fn link_procedures(pdb: &mut ParsedPdb) {
+    let mut public_symbols = HashMap::new();
+    for (i, symbol) in pdb.public_symbols.iter().enumerate() {
+        if let Some(offset) = symbol.offset {
+            // Identical functions may have been folded together, in which case
+            // the first symbol at the address wins
+            public_symbols.entry(offset).or_insert(i);
+        }
+    }
+
+    for procedure in &mut pdb.procedures {
+        let symbol = match procedure
+            .address
+            .and_then(|address| public_symbols.get(&address))
+        {
+            Some(i) => &mut pdb.public_symbols[*i],
+            None => continue,
+        };
+
+        procedure.public_name = Some(symbol.name.clone());
+        if symbol.procedure_name.is_none() {
+            symbol.procedure_name = Some(procedure.name.clone());
+        }
+    }
+}
+
+/// Iterates through all of the parsed types once just to update any necessary
+/// info, such as resolving forward references to their definitions and inferring
+/// the packing of classes and unions. Types converted with [handle_type] should
+/// be completed once all of them have been converted.
+pub fn complete_types(output_pdb: &mut ParsedPdb) {
+    use crate::type_info::{PackingInference, Primitive, PrimitiveKind, Typed};
+
+    for id in output_pdb.types.ids() {
+        // The type is temporarily swapped out so that it can be updated while
+        // still having access to the rest of the PDB
+        let placeholder = Type::Primitive(Primitive {
+            kind: PrimitiveKind::NoType,
+            indirection: None,
+        });
+        let mut typ = std::mem::replace(&mut output_pdb.types[id], placeholder);
+        typ.on_complete(output_pdb);
+        output_pdb.types[id] = typ;
+    }
+
+    let mut inference = PackingInference::new(output_pdb);
+    let packings: Vec<(TypeId, Option<usize>)> = output_pdb
+        .types
+        .iter()
+        .filter(|(_id, ty)| matches!(ty, Type::Class(_) | Type::Union(_)))
+        .map(|(id, _ty)| (id, inference.packing(id)))
+        .collect();
+    for (id, packing) in packings {
+        match &mut output_pdb.types[id] {
+            Type::Class(class) => class.packing = packing,
+            Type::Union(union) => union.packing = packing,
+            _ => unreachable!(),
+        }
+    }
+}
+
 /// Converts a [pdb::SymbolData] object to a parsed symbol representation that
 /// we can serialize and adds it to the appropriate fields on the output [ParsedPdb].
+/// Returns the span `stage` runs in. The number of items the stage processed is
+/// recorded as `items` once it finishes.
+fn stage_span(stage: ParseStage) -> tracing::Span {
+    tracing::info_span!("stage", stage = stage.name(), items = tracing::field::Empty)
+}
+
+/// Records `diagnostic`, or returns it as an error if `options.fail_on` stops on it
+fn report(
+    output_pdb: &mut ParsedPdb,
+    options: &ParseOptions,
+    diagnostic: Diagnostic,
+) -> Result<(), Error> {
+    warn!("{}", diagnostic);
+    if options.fail_on.stops_on(diagnostic.category) {
+        return Err(Error::Stopped(diagnostic));
+    }
+
+    output_pdb.diagnostics.push(diagnostic);
+    Ok(())
+}
+
+/// Classifies an error raised while handling a type or symbol
+fn error_category(error: &Error) -> DiagnosticCategory {
+    match error {
+        Error::PdbCrateError(pdb::Error::UnimplementedTypeKind(_))
+        | Error::PdbCrateError(pdb::Error::UnimplementedSymbolKind(_))
+        | Error::PdbCrateError(pdb::Error::UnimplementedFeature(_))
+        | Error::Unsupported(_)
+        | Error::UnhandledType(_) => DiagnosticCategory::UnknownKind,
+        Error::PdbCrateError(pdb::Error::TypeNotFound(_)) | Error::UnresolvedType(_) => {
+            DiagnosticCategory::UnresolvedType
+        }
+        _ => DiagnosticCategory::Corrupt,
+    }
+}
+
+/// Describes the failure to handle `symbol`, which was read from stream `stream`
+fn symbol_diagnostic(symbol: &Symbol<'_>, stream: Option<u16>, error: &Error) -> Diagnostic {
+    Diagnostic {
+        category: error_category(error),
+        stream: stream.map(u32::from),
+        offset: Some(symbol.index().0 as usize),
+        kind: Some(symbol.raw_kind()),
+        type_index: None,
+        description: error.to_string(),
+    }
+}
+
 /// Errors returned from this function should not be considered fatal.
 fn handle_symbol(
     sym: Symbol,
     output_pdb: &mut ParsedPdb,
-    address_map: Option<&AddressMap>,
+    address_map: &AddressMapping<'_>,
     type_finder: &ItemFinder<'_, TypeIndex>,
     id_finder: Option<&ItemFinder<'_, IdIndex>>,
-    base_address: Option<usize>,
+    options: &ParseOptions,
+    scope: SymbolScope<'_>,
 ) -> Result<(), Error> {
-    let base_address = base_address.unwrap_or(0);
-    let sym = sym.parse()?;
+    let base_address = options.base_address;
+
+    // The `pdb` crate can't parse these symbols, so read them from the raw record
+    match sym.raw_kind() {
+        crate::symbol_types::S_ANNOTATION => {
+            // Annotations are only meaningful in module symbol streams
+            if let Some(module_index) = scope.module_index {
+                let annotation: crate::symbol_types::Annotation =
+                    (&sym, module_index, base_address, address_map).try_into_model()?;
+                debug!("annotation: {:?}", annotation);
+                output_pdb.annotations.push(annotation);
+            }
+
+            return Ok(());
+        }
+        crate::symbol_types::S_REF_MINIPDB => {
+            let reference: crate::symbol_types::FastlinkReference = (&sym).try_into_model()?;
+            debug!("fastlink reference: {:?}", reference);
+            output_pdb.fastlink_references.push(reference);
+
+            return Ok(());
+        }
+        crate::symbol_types::S_HEAPALLOCSITE => {
+            let site: crate::symbol_types::HeapAllocationSite =
+                (&sym, base_address, address_map, &output_pdb.type_indices).try_into_model()?;
+            debug!("heap allocation site: {:?}", site);
+            output_pdb.heap_allocation_sites.push(site);
+
+            return Ok(());
+        }
+        crate::symbol_types::S_CALLSITEINFO => {
+            let site: crate::symbol_types::CallSite = (
+                &sym,
+                base_address,
+                address_map,
+                &output_pdb.type_indices,
+                scope.function,
+            )
+                .try_into_model()?;
+            debug!("call site: {:?}", site);
+            output_pdb.call_sites.push(site);
+
+            return Ok(());
+        }
+        kind @ (crate::symbol_types::S_CALLERS | crate::symbol_types::S_CALLEES) => {
+            // These describe the procedure they're nested in
+            let function = match scope.function {
+                Some(function) => function,
+                None => return Ok(()),
+            };
+
+            for (other, invocations) in crate::symbol_types::parse_function_list(&sym, id_finder)? {
+                let (caller, callee) = if kind == crate::symbol_types::S_CALLERS {
+                    (other, function.to_string())
+                } else {
+                    (function.to_string(), other)
+                };
+
+                output_pdb
+                    .call_graph
+                    .push(crate::symbol_types::CallGraphEdge {
+                        caller,
+                        callee,
+                        invocations,
+                    });
+            }
+
+            return Ok(());
+        }
+        crate::symbol_types::S_FRAMEPROC => {
+            // Frame information immediately follows the procedure it belongs to
+            let procedure = output_pdb
+                .procedures
+                .last_mut()
+                .filter(|procedure| Some(procedure.name.as_str()) == scope.function);
+            if let (true, Some(procedure)) = (scope.in_function_body, procedure) {
+                crate::symbol_types::read_frame_flags(&sym, &mut procedure.flags)?;
+            }
+
+            return Ok(());
+        }
+        crate::symbol_types::S_GMANPROC | crate::symbol_types::S_LMANPROC => {
+            let procedure: crate::symbol_types::ManagedProcedure =
+                (&sym, scope.module_index, base_address, address_map).try_into_model()?;
+            debug!("managed procedure: {:?}", procedure);
+            output_pdb.managed_procedures.push(procedure);
+
+            return Ok(());
+        }
+        crate::symbol_types::S_ENVBLOCK => {
+            let environment = crate::symbol_types::read_environment_block(&sym)?;
+            debug!("environment block: {:?}", environment);
+            if let Some(module) = scope
+                .module_index
+                .and_then(|index| output_pdb.debug_modules.get_mut(index))
+            {
+                module.environment = environment;
+            }
+
+            return Ok(());
+        }
+        crate::symbol_types::S_TOKENREF => {
+            let reference: crate::symbol_types::TokenReference = (&sym).try_into_model()?;
+            debug!("token reference: {:?}", reference);
+            output_pdb.token_references.push(reference);
 
-    match sym {
+            return Ok(());
+        }
+        crate::symbol_types::S_SECTION => {
+            let section: crate::symbol_types::Section = (&sym, base_address).try_into_model()?;
+            debug!("section: {:?}", section);
+            output_pdb.sections.push(section);
+
+            return Ok(());
+        }
+        crate::symbol_types::S_COFFGROUP => {
+            let group: crate::symbol_types::CoffGroup =
+                (&sym, base_address, address_map).try_into_model()?;
+            debug!("COFF group: {:?}", group);
+            output_pdb.coff_groups.push(group);
+
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    match sym.parse()? {
         SymbolData::Public(data) => {
             debug!("public symbol: {:?}", data);
 
             let converted_symbol: crate::symbol_types::PublicSymbol =
-                (data, base_address, address_map).into();
+                (data, base_address, address_map).into_model();
             output_pdb.public_symbols.push(converted_symbol);
         }
         SymbolData::Procedure(data) => {
             debug!("procedure: {:?}", data);
 
-            let converted_symbol: crate::symbol_types::Procedure =
-                (data, base_address, address_map, type_finder).into();
+            let mut converted_symbol: crate::symbol_types::Procedure =
+                (data, base_address, address_map, type_finder).into_model();
+            converted_symbol.module_index = scope.module_index;
             output_pdb.procedures.push(converted_symbol);
         }
         SymbolData::BuildInfo(data) => {
             debug!("build info: {:?}", data);
-            let converted_symbol: crate::symbol_types::BuildInfo = (&data, id_finder).try_into()?;
+            let converted_symbol: crate::symbol_types::BuildInfo =
+                (&data, id_finder).try_into_model()?;
+            if let Some(module) = scope
+                .module_index
+                .and_then(|index| output_pdb.debug_modules.get_mut(index))
+            {
+                module.build_info = Some(converted_symbol.clone());
+            }
             output_pdb.assembly_info.build_info = Some(converted_symbol);
         }
         SymbolData::CompileFlags(data) => {
             debug!("compile flags: {:?}", data);
-            let sym: crate::symbol_types::CompilerInfo = data.into();
+            // The `pdb` crate reads CPU types it doesn't know, such as ARM64EC's,
+            // as `Intel8080`, and languages it doesn't know, such as Rust, as
+            // `Masm`. The language is the first byte of the flags, which the
+            // CPU type follows.
+            let mut reader = raw::symbol_reader(&sym);
+            let language = reader.u8()?;
+            reader.take(3)?;
+            let cpu_type = reader.u16()?;
+            let mut sym: crate::symbol_types::CompilerInfo = data.into_model();
+            if let Some(name) = raw::language_name(language) {
+                sym.language = name.to_string();
+            }
+            if let Some(name) = hybrid::cpu_type_name(cpu_type) {
+                sym.cpu_type = name.to_string();
+            }
+            if let Some(module) = scope
+                .module_index
+                .and_then(|index| output_pdb.debug_modules.get_mut(index))
+            {
+                module.compiler_info = Some(sym.clone());
+            }
             output_pdb.assembly_info.compiler_info = Some(sym);
         }
         SymbolData::AnnotationReference(annotation) => {
             debug!("annotation reference: {:?}", annotation);
 
-            // let sym: crate::symbol_types::AnnotationReference = annotation.try_into()?;
-            // output_pdb.annotation_references.push()
+            let sym: crate::symbol_types::AnnotationReference = annotation.into_model();
+            output_pdb.annotation_references.push(sym);
+        }
+        SymbolData::Local(data) if data.flags.isparam && scope.in_function_body => {
+            // Parameters immediately follow the procedure they belong to
+            let procedure = output_pdb
+                .procedures
+                .last_mut()
+                .filter(|procedure| Some(procedure.name.as_str()) == scope.function);
+            if let Some(procedure) = procedure {
+                procedure
+                    .parameter_names
+                    .push(data.name.to_string().into_owned());
+            }
+        }
+        SymbolData::Export(data) => {
+            debug!("export: {:?}", data);
+
+            let sym: crate::symbol_types::Export = data.into_model();
+            output_pdb.exports.push(sym);
+        }
+        SymbolData::Trampoline(data) => {
+            debug!("trampoline: {:?}", data);
+
+            let sym: crate::symbol_types::Trampoline =
+                (data, base_address, address_map).into_model();
+            output_pdb.trampolines.push(sym);
+        }
+        SymbolData::SeparatedCode(data) => {
+            debug!("separated code: {:?}", data);
+
+            let sym: crate::symbol_types::SeparatedCode =
+                (data, base_address, address_map).into_model();
+            output_pdb.separated_code.push(sym);
         }
         SymbolData::Data(data) => {
-            let sym: crate::symbol_types::Data =
-                (data, base_address, address_map, &output_pdb.types).try_into()?;
+            let mut sym: crate::symbol_types::Data =
+                (data, base_address, address_map, &output_pdb.type_indices).try_into_model()?;
             if sym.is_global {
                 output_pdb.global_data.push(sym);
+            } else if options.include_local_data && scope.module.is_some() {
+                // Local data also appears in the global symbol stream, but only
+                // the copy in the module stream tells us who owns it
+                sym.module = scope.module.map(str::to_string);
+                sym.function = scope.function.map(str::to_string);
+                output_pdb.local_data.push(sym);
             }
         }
         other => {
-            warn!("Unhandled SymbolData: {:?}", other);
+            debug!("Unhandled SymbolData: {:?}", other);
         }
     }
 
     Ok(())
 }
 
-/// Converts a [pdb::SymbolData] object to a parsed symbol representation that
-/// we can serialize and adds it to the appropriate fields on the output [ParsedPdb].
+/// Converts the type record at `idx` into a [Type] and adds it to the types of
+/// `output_pdb`, converting the types it refers to first. A type which was
+/// already converted isn't converted again: its existing [TypeId] is returned.
+/// Records whose kind the `pdb` crate can't parse are added as [Type::Partial].
+///
+/// This converts types incrementally, without a full [parse_pdb] run. Any
+/// [ParsedPdb] serves as the type store, e.g. one made with [ParsedPdb::new].
+/// `type_finder` must have been updated past every type `idx` refers to, which a
+/// finder updated up to `idx` is, as records only refer to earlier ones. Call
+/// [complete_types] once all the types needed have been converted.
+///
 /// Errors returned from this function should not be considered fatal.
-pub(crate) fn handle_type(
+pub fn handle_type(
     idx: pdb::TypeIndex,
     output_pdb: &mut ParsedPdb,
     type_finder: &ItemFinder<'_, TypeIndex>,
-) -> Result<TypeRef, Error> {
-    use crate::type_info::{Class, Type, Union};
-    if let Some(typ) = output_pdb.types.get(&idx.0) {
-        return Ok(Rc::clone(typ));
+) -> Result<TypeId, Error> {
+    if let Some(id) = output_pdb.type_indices.get(&idx.0) {
+        return Ok(*id);
     }
 
-    let typ = type_finder.find(idx).expect("failed to resolve type");
+    let typ = type_finder.find(idx)?;
 
-    let parsed_type = &typ.parse()?;
-    let typ = handle_type_data(parsed_type, output_pdb, type_finder)?;
+    let typ = match typ.parse() {
+        Ok(parsed_type) => handle_type_data(&parsed_type, output_pdb, type_finder)?,
+        Err(pdb::Error::UnimplementedTypeKind(kind)) => {
+            handle_partial_type(idx, kind, output_pdb, type_finder)
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if let Some(&pmtype) = output_pdb.member_pointer_representations.get(&idx.0) {
+        if let Type::Pointer(Pointer {
+            member: Some(member),
+            ..
+        }) = &mut output_pdb.types[typ]
+        {
+            member.representation = type_info::member_pointer_representation_from_raw(pmtype);
+        }
+    }
 
-    output_pdb.types.insert(idx.0, Rc::clone(&typ));
+    output_pdb.type_indices.insert(idx.0, typ);
+    output_pdb.types.set_type_index(typ, idx.0);
 
     Ok(typ)
 }
 
-pub(crate) fn handle_type_data(
+/// Inserts a [Type::Partial] for the record at `idx`, whose kind the `pdb` crate
+/// can't parse, so that types referring to it can still be parsed. The record's
+/// referenced type is parsed on a best-effort basis.
+fn handle_partial_type(
+    idx: pdb::TypeIndex,
+    kind: u16,
+    output_pdb: &mut ParsedPdb,
+    type_finder: &ItemFinder<'_, TypeIndex>,
+) -> TypeId {
+    let mut partial = output_pdb
+        .partial_types
+        .get(&idx.0)
+        .cloned()
+        .unwrap_or_else(|| Partial {
+            kind,
+            kind_name: raw::leaf_kind_name(kind).map(str::to_string),
+            name: None,
+            referenced_type_index: None,
+            referenced_type: None,
+        });
+
+    if let Some(referenced) = partial.referenced_type_index {
+        partial.referenced_type = match handle_type(TypeIndex(referenced), output_pdb, type_finder)
+        {
+            Ok(id) => Some(id),
+            Err(e) => {
+                debug!(
+                    "could not parse type 0x{:X}, which type 0x{:X} refers to: {}",
+                    referenced, idx.0, e
+                );
+                None
+            }
+        };
+    }
+
+    output_pdb.types.insert(Type::Partial(partial))
+}
+
+/// Converts an already parsed type record into a [Type] and adds it to the types
+/// of `output_pdb`, converting the types it refers to first with [handle_type].
+/// Unlike [handle_type], the record is always added anew, and isn't associated
+/// with a type index since [pdb::TypeData] doesn't carry one.
+///
+/// Returns [Error::UnhandledType] for records which aren't types, such as
+/// those of the IPI stream.
+pub fn handle_type_data(
     typ: &pdb::TypeData,
     output_pdb: &mut ParsedPdb,
     type_finder: &ItemFinder<'_, TypeIndex>,
-) -> Result<TypeRef, Error> {
-    use crate::type_info::{Class, Type};
+) -> Result<TypeId, Error> {
     let typ = match typ {
         TypeData::Class(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Class(typ)
         }
         TypeData::Union(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Union(typ)
         }
         TypeData::Bitfield(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Bitfield(typ)
         }
         TypeData::Array(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Array(typ)
         }
         TypeData::Enumerate(data) => {
-            let typ = data.try_into()?;
+            let typ = (data, &mut *output_pdb).try_into_model()?;
             Type::EnumVariant(typ)
         }
         TypeData::Enumeration(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Enumeration(typ)
         }
         TypeData::Pointer(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Pointer(typ)
         }
         TypeData::Primitive(data) => {
-            let typ = data.try_into()?;
+            let typ = data.try_into_model()?;
             Type::Primitive(typ)
         }
         TypeData::FieldList(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::FieldList(typ)
         }
         TypeData::Modifier(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Modifier(typ)
         }
         TypeData::Member(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Member(typ)
         }
         TypeData::ArgumentList(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::ArgumentList(typ)
         }
         TypeData::Procedure(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Procedure(typ)
         }
         TypeData::MemberFunction(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::MemberFunction(typ)
         }
         TypeData::MethodList(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::MethodList(typ)
         }
         TypeData::VirtualBaseClass(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::VirtualBaseClass(typ)
         }
         TypeData::Nested(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Nested(typ)
         }
         TypeData::OverloadedMethod(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::OverloadedMethod(typ)
         }
         TypeData::Method(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::Method(typ)
         }
         TypeData::StaticMember(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::StaticMember(typ)
         }
         TypeData::BaseClass(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::BaseClass(typ)
         }
         TypeData::VirtualFunctionTablePointer(data) => {
-            let typ = (data, type_finder, output_pdb).try_into()?;
+            let typ = (data, type_finder, &mut *output_pdb).try_into_model()?;
             Type::VTable(typ)
         }
         other => {
-            warn!("Unhandled type: {:?}", other);
-            panic!("type not handled: {:?}", other);
+            debug!("Unhandled type: {:?}", other);
+            return Err(Error::UnhandledType(format!("{:?}", other)));
         }
     };
 
-    Ok(Rc::new(RefCell::new(typ)))
+    Ok(output_pdb.types.insert(typ))
 }
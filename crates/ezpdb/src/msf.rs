@@ -0,0 +1,322 @@
+//! Support for PDB 2.0 files, as produced by Visual C++ 6.0 and older toolchains.
+//! These use the "small" MSF container which the `pdb` crate can't open, so
+//! their streams are copied into an in-memory MSF 7.00 file instead.
+//!
+//! Only the container is converted. Records in the formats of these older
+//! toolchains which the `pdb` crate doesn't understand are skipped like any
+//! other unparseable record.
+use crate::error::Error;
+use crate::raw::ByteReader;
+use pdb::{Source, SourceSlice};
+
+/// Magic at the start of PDB 2.0 files
+const SMALL_MSF_MAGIC: &[u8] = b"Microsoft C/C++ program database 2.00\r\n\x1aJG\0\0";
+
+/// Magic at the start of MSF 7.00 files
+pub(crate) const BIG_MSF_MAGIC: &[u8] = b"Microsoft C/C++ MSF 7.00\r\n\x1aDS\0\0\0";
+
+/// Page size of converted files. The `pdb` crate always reads the first 4096
+/// bytes of a file as its header.
+const BIG_MSF_PAGE_SIZE: usize = 4096;
+
+/// Index of the PDB information stream
+const PDB_INFORMATION_STREAM: usize = 1;
+
+/// PDB information stream version of Visual C++ 7.0, the first to include a GUID
+const PDB_VERSION_VC70: u32 = 20000404;
+
+/// The smallest and largest page sizes of PDB 2.0 files
+const MIN_PAGE_SIZE: usize = 0x200;
+const MAX_PAGE_SIZE: usize = 0x8000;
+
+/// Offset of the page list of the stream directory in the MSF 7.00 header
+const BIG_MSF_DIRECTORY_PAGE_LIST: u64 = 52;
+
+/// Returns whether `header` is the start of a PDB 2.0 file
+pub(crate) fn is_small_msf(header: &[u8]) -> bool {
+    header.starts_with(SMALL_MSF_MAGIC)
+}
+
+/// Reads the number of streams of the MSF 7.00 file `source` from the start
+/// of its stream directory, without reading the rest of the directory
+pub(crate) fn stream_count<'s>(source: &mut impl Source<'s>) -> Result<u32, Error> {
+    let is_msf = source
+        .view(&[SourceSlice {
+            offset: 0,
+            size: BIG_MSF_MAGIC.len(),
+        }])
+        .is_ok_and(|magic| magic.as_slice() == BIG_MSF_MAGIC);
+    if !is_msf {
+        return Err(Error::PdbCrateError(pdb::Error::UnrecognizedFileFormat));
+    }
+
+    let mut read_u32 = |offset: u64| -> Result<u32, Error> {
+        let view = source.view(&[SourceSlice { offset, size: 4 }])?;
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(view.as_slice());
+        Ok(u32::from_le_bytes(bytes))
+    };
+
+    let page_size = u64::from(read_u32(BIG_MSF_MAGIC.len() as u64)?);
+    let page_list_page = u64::from(read_u32(BIG_MSF_DIRECTORY_PAGE_LIST)?);
+    let first_directory_page = u64::from(read_u32(page_list_page * page_size)?);
+
+    read_u32(first_directory_page * page_size)
+}
+
+/// Rewrites the PDB 2.0 file `data` as an MSF 7.00 file
+pub(crate) fn convert_small_msf(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut streams = read_small_msf_streams(data)?;
+
+    // The information stream only gained a GUID in MSF 7.00, but the `pdb` crate
+    // always expects one
+    if let Some(Some(information)) = streams.get_mut(PDB_INFORMATION_STREAM) {
+        let mut reader = ByteReader::new(information);
+        if reader.u32()? < PDB_VERSION_VC70 {
+            if information.len() < 12 {
+                return Err(Error::PdbCrateError(pdb::Error::UnexpectedEof));
+            }
+            // After the version, signature, and age
+            information.splice(12..12, [0; 16]);
+        }
+    }
+
+    Ok(write_big_msf(&streams))
+}
+
+/// Reads every stream of the PDB 2.0 file `data`. Streams which don't exist are `None`.
+fn read_small_msf_streams(data: &[u8]) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    let mut reader = ByteReader::new(data);
+    reader.seek(SMALL_MSF_MAGIC.len());
+    let page_size = reader.u32()? as usize;
+    let _free_page_map = reader.u16()?;
+    let _page_count = reader.u16()?;
+    let directory_size = reader.u32()? as usize;
+    let _reserved = reader.u32()?;
+    if !page_size.is_power_of_two() || !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&page_size) {
+        return Err(Error::PdbCrateError(pdb::Error::InvalidPageSize(
+            page_size as u32,
+        )));
+    }
+
+    let directory_pages = read_page_numbers(&mut reader, directory_size, page_size)?;
+    let directory = read_pages(data, &directory_pages, page_size, directory_size)?;
+
+    let mut reader = ByteReader::new(&directory);
+    let stream_count = reader.u16()?;
+    let _reserved = reader.u16()?;
+    let sizes = (0..stream_count)
+        .map(|_| {
+            let size = reader.u32()?;
+            let _reserved = reader.u32()?;
+            Ok(size)
+        })
+        .collect::<Result<Vec<u32>, Error>>()?;
+
+    sizes
+        .into_iter()
+        .map(|size| {
+            if size == u32::MAX {
+                return Ok(None);
+            }
+
+            let pages = read_page_numbers(&mut reader, size as usize, page_size)?;
+            read_pages(data, &pages, page_size, size as usize).map(Some)
+        })
+        .collect()
+}
+
+/// Reads the 16-bit numbers of the pages holding `len` bytes
+fn read_page_numbers(
+    reader: &mut ByteReader<'_>,
+    len: usize,
+    page_size: usize,
+) -> Result<Vec<u16>, Error> {
+    (0..len.div_ceil(page_size)).map(|_| reader.u16()).collect()
+}
+
+/// Concatenates `pages` of `data`, truncated to `len` bytes
+fn read_pages(data: &[u8], pages: &[u16], page_size: usize, len: usize) -> Result<Vec<u8>, Error> {
+    // `len` may be far larger than the file if it's malformed
+    let mut bytes = Vec::with_capacity(len.min(data.len()));
+    for &page in pages {
+        let start = page as usize * page_size;
+        // The file may end before the last page does
+        let end = (start + page_size).min(data.len());
+        let page = data.get(start..end).ok_or(Error::PdbCrateError(
+            pdb::Error::PageReferenceOutOfRange(page.into()),
+        ))?;
+        bytes.extend_from_slice(page);
+    }
+
+    if bytes.len() < len {
+        return Err(Error::PdbCrateError(pdb::Error::UnexpectedEof));
+    }
+    bytes.truncate(len);
+
+    Ok(bytes)
+}
+
+/// Lays `streams` out as an MSF 7.00 file
+pub(crate) fn write_big_msf(streams: &[Option<Vec<u8>>]) -> Vec<u8> {
+    // Page 0 is the header
+    let mut file = vec![0; BIG_MSF_PAGE_SIZE];
+    let append = |file: &mut Vec<u8>, data: &[u8]| -> Vec<u32> {
+        data.chunks(BIG_MSF_PAGE_SIZE)
+            .map(|chunk| {
+                // The first two pages of every interval of `BIG_MSF_PAGE_SIZE`
+                // pages are reserved for the two copies of the free page map
+                while is_free_page_map_page(file.len() / BIG_MSF_PAGE_SIZE) {
+                    file.resize(file.len() + BIG_MSF_PAGE_SIZE, 0);
+                }
+
+                let page = file.len() / BIG_MSF_PAGE_SIZE;
+                file.extend_from_slice(chunk);
+                file.resize((page + 1) * BIG_MSF_PAGE_SIZE, 0);
+                page as u32
+            })
+            .collect()
+    };
+
+    let mut sizes = vec![];
+    let mut page_numbers = vec![];
+    for stream in streams {
+        match stream {
+            Some(stream) => {
+                sizes.push(stream.len() as u32);
+                page_numbers.extend(append(&mut file, stream));
+            }
+            None => sizes.push(u32::MAX),
+        }
+    }
+
+    let directory: Vec<u8> = std::iter::once(streams.len() as u32)
+        .chain(sizes)
+        .chain(page_numbers)
+        .flat_map(u32::to_le_bytes)
+        .collect();
+    let directory_pages = append(&mut file, &directory);
+    let directory_page_list: Vec<u8> = directory_pages
+        .into_iter()
+        .flat_map(u32::to_le_bytes)
+        .collect();
+    let directory_page_list_pages = append(&mut file, &directory_page_list);
+
+    let page_count = file.len() / BIG_MSF_PAGE_SIZE;
+    write_free_page_map(&mut file, page_count);
+
+    let mut header = BIG_MSF_MAGIC.to_vec();
+    let fields = [
+        BIG_MSF_PAGE_SIZE as u32,
+        // Free page map
+        1,
+        page_count as u32,
+        directory.len() as u32,
+        // Reserved
+        0,
+    ];
+    for value in fields.iter().copied().chain(directory_page_list_pages) {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    file[..header.len()].copy_from_slice(&header);
+
+    file
+}
+
+/// Returns whether `page` holds one of the two copies of the free page map
+fn is_free_page_map_page(page: usize) -> bool {
+    matches!(page % BIG_MSF_PAGE_SIZE, 1 | 2)
+}
+
+/// Fills in both copies of the free page map of `file`, marking its first
+/// `page_count` pages as in use. The map is a bit per page, set if the page is
+/// free, split across the free page map pages of each interval in turn.
+fn write_free_page_map(file: &mut [u8], page_count: usize) {
+    let mut map = vec![0xff; page_count.div_ceil(BIG_MSF_PAGE_SIZE) * BIG_MSF_PAGE_SIZE];
+    for page in 0..page_count {
+        map[page / 8] &= !(1 << (page % 8));
+    }
+
+    for (interval, chunk) in map.chunks(BIG_MSF_PAGE_SIZE).enumerate() {
+        for copy in 1..=2 {
+            let start = (interval * BIG_MSF_PAGE_SIZE + copy) * BIG_MSF_PAGE_SIZE;
+            if let Some(page) = file.get_mut(start..start + BIG_MSF_PAGE_SIZE) {
+                page.copy_from_slice(chunk);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_SIZE: usize = 0x400;
+
+    /// Lays `streams`, each of which must fit in a page, out in a PDB 2.0 file
+    fn small_msf(page_size: u32, streams: &[&[u8]]) -> Vec<u8> {
+        let mut directory = vec![];
+        directory.extend_from_slice(&(streams.len() as u16).to_le_bytes());
+        directory.extend_from_slice(&[0; 2]);
+        for stream in streams {
+            directory.extend_from_slice(&(stream.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&[0; 4]);
+        }
+        // Empty streams have no pages
+        let pages: Vec<&[u8]> = streams.iter().copied().filter(|s| !s.is_empty()).collect();
+        for page in 1..=pages.len() {
+            directory.extend_from_slice(&(page as u16).to_le_bytes());
+        }
+
+        let directory_page = pages.len() as u16 + 1;
+        let mut file = SMALL_MSF_MAGIC.to_vec();
+        file.extend_from_slice(&page_size.to_le_bytes());
+        file.extend_from_slice(&[0; 2]);
+        file.extend_from_slice(&(directory_page + 1).to_le_bytes());
+        file.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        file.extend_from_slice(&[0; 4]);
+        file.extend_from_slice(&directory_page.to_le_bytes());
+        for page in pages.into_iter().chain([directory.as_slice()]) {
+            file.resize(file.len().div_ceil(PAGE_SIZE) * PAGE_SIZE, 0);
+            file.extend_from_slice(page);
+        }
+
+        file
+    }
+
+    #[test]
+    fn small_files_are_converted() {
+        let mut information = 19970604u32.to_le_bytes().to_vec();
+        information.extend_from_slice(&[0; 8]);
+        let data = small_msf(PAGE_SIZE as u32, &[b"", &information]);
+
+        let converted = convert_small_msf(&data).unwrap();
+        assert!(converted.starts_with(BIG_MSF_MAGIC));
+        assert_eq!(
+            stream_count(&mut std::io::Cursor::new(converted)).unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn truncated_information_streams_are_rejected() {
+        let information = 19970604u32.to_le_bytes();
+        let data = small_msf(PAGE_SIZE as u32, &[b"", &information]);
+
+        assert!(matches!(
+            convert_small_msf(&data),
+            Err(Error::PdbCrateError(pdb::Error::UnexpectedEof))
+        ));
+    }
+
+    #[test]
+    fn invalid_page_sizes_are_rejected() {
+        for page_size in [0, 0x401, 0x8000_0000] {
+            let data = small_msf(page_size, &[b""]);
+            assert!(matches!(
+                convert_small_msf(&data),
+                Err(Error::PdbCrateError(pdb::Error::InvalidPageSize(size))) if size == page_size
+            ));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Heuristics for telling code the project wrote apart from code it pulled in
+//! from the C runtime, the C++ standard library, or the compiler
+use crate::symbol_types::{CodeOrigin, DebugModule, ParsedPdb};
+
+/// Libraries shipped with MSVC, the Windows SDK, or the WDK. Objects linked from
+/// these have the library's path as their object file name.
+const RUNTIME_LIBRARIES: &[&str] = &[
+    "libcmt",
+    "libcmtd",
+    "msvcrt",
+    "msvcrtd",
+    "libvcruntime",
+    "libvcruntimed",
+    "vcruntime",
+    "vcruntimed",
+    "libucrt",
+    "libucrtd",
+    "ucrt",
+    "ucrtd",
+    "libcpmt",
+    "libcpmtd",
+    "msvcprt",
+    "msvcprtd",
+    "libconcrt",
+    "libconcrtd",
+    "oldnames",
+    "uuid",
+    "kernel32",
+    "ntdll",
+    "ntoskrnl",
+    "hal",
+    "bufferoverflowk",
+    "bufferoverflowfastfailk",
+    "wdmsec",
+];
+
+/// Name prefixes of the C runtime's and compiler support library's functions
+const RUNTIME_PREFIXES: &[&str] = &[
+    "__scrt_",
+    "__acrt_",
+    "__vcrt_",
+    "__security_",
+    "__report_",
+    "__GSHandlerCheck",
+    "__C_specific_handler",
+    "__CxxFrameHandler",
+    "_RTC_",
+    "_CRT_",
+    "_guard_",
+    "__guard_",
+    "__chkstk",
+    "__raise_securityfailure",
+    "__isa_available",
+    "__local_stdio_",
+    "__crt_",
+    "_initterm",
+];
+
+/// Namespaces of the C++ standard library and its implementation details
+const STL_PREFIXES: &[&str] = &["std::", "stdext::", "Concurrency::", "__std_"];
+
+/// Sets [crate::symbol_types::Procedure::origin] for every procedure. This is
+/// done automatically by [crate::parse_pdb].
+pub fn classify_procedures(pdb: &mut ParsedPdb) {
+    let modules = &pdb.debug_modules;
+    for procedure in &mut pdb.procedures {
+        let module = procedure.module_index.and_then(|index| modules.get(index));
+        procedure.origin = classify(&procedure.name, module);
+    }
+}
+
+/// Guesses the origin of the procedure `name` from the module it was found in
+pub fn classify(name: &str, module: Option<&DebugModule>) -> CodeOrigin {
+    // Special members, e.g. `Foo::`scalar deleting destructor'`, and other
+    // functions whose names the compiler made up
+    if name.starts_with('`') || name.contains("::`") {
+        return CodeOrigin::CompilerGenerated;
+    }
+
+    if STL_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+        return CodeOrigin::Stl;
+    }
+
+    if RUNTIME_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        return CodeOrigin::Runtime;
+    }
+
+    if let Some(module) = module {
+        // Import thunks and other code emitted by the linker
+        if module.name.starts_with("Import:") || module.name == "* Linker *" {
+            return CodeOrigin::CompilerGenerated;
+        }
+
+        if is_runtime_library(&module.object_file_name) {
+            return CodeOrigin::Runtime;
+        }
+    }
+
+    CodeOrigin::User
+}
+
+/// Returns whether `object_file_name` is the path of one of [RUNTIME_LIBRARIES]
+fn is_runtime_library(object_file_name: &str) -> bool {
+    let file_name = object_file_name
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or(object_file_name)
+        .to_ascii_lowercase();
+
+    match file_name.strip_suffix(".lib") {
+        Some(stem) => RUNTIME_LIBRARIES.contains(&stem),
+        None => false,
+    }
+}
@@ -0,0 +1,91 @@
+/// A distinct phase of parsing a PDB
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseStage {
+    /// Walking the ID information (IPI) stream
+    Ids,
+    /// Walking the type information (TPI) stream and converting each type
+    Types,
+    /// Walking the global symbol stream
+    GlobalSymbols,
+    /// Walking the symbol stream of each debug module
+    ModuleSymbols,
+}
+
+impl ParseStage {
+    /// Returns a short human-readable name for this stage
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParseStage::Ids => "ID stream",
+            ParseStage::Types => "types",
+            ParseStage::GlobalSymbols => "global symbols",
+            ParseStage::ModuleSymbols => "module symbols",
+        }
+    }
+}
+
+impl std::fmt::Display for ParseStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Receives notifications as [crate::parse_pdb_with_options] works through each
+/// [ParseStage]. All methods have empty default implementations so implementors
+/// only need to handle the events they care about.
+pub trait ProgressSink {
+    /// Called when `stage` begins. `total` is the number of items the stage will
+    /// process if it is known up front.
+    fn stage_started(&mut self, _stage: ParseStage, _total: Option<usize>) {}
+
+    /// Called after each item in `stage` has been processed
+    fn progress(&mut self, _stage: ParseStage, _processed: usize, _total: Option<usize>) {}
+
+    /// Called when `stage` has completed
+    fn stage_finished(&mut self, _stage: ParseStage) {}
+}
+
+/// A [ProgressSink] which ignores all notifications
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {}
+
+/// Forwards every notification to both sinks, allowing e.g. a progress bar and
+/// a [crate::timing::StageTimer] to observe the same parse
+impl<A: ProgressSink, B: ProgressSink> ProgressSink for (A, B) {
+    fn stage_started(&mut self, stage: ParseStage, total: Option<usize>) {
+        self.0.stage_started(stage, total);
+        self.1.stage_started(stage, total);
+    }
+
+    fn progress(&mut self, stage: ParseStage, processed: usize, total: Option<usize>) {
+        self.0.progress(stage, processed, total);
+        self.1.progress(stage, processed, total);
+    }
+
+    fn stage_finished(&mut self, stage: ParseStage) {
+        self.0.stage_finished(stage);
+        self.1.stage_finished(stage);
+    }
+}
+
+/// Forwards notifications to the inner sink, if there is one
+impl<S: ProgressSink> ProgressSink for Option<S> {
+    fn stage_started(&mut self, stage: ParseStage, total: Option<usize>) {
+        if let Some(sink) = self {
+            sink.stage_started(stage, total);
+        }
+    }
+
+    fn progress(&mut self, stage: ParseStage, processed: usize, total: Option<usize>) {
+        if let Some(sink) = self {
+            sink.progress(stage, processed, total);
+        }
+    }
+
+    fn stage_finished(&mut self, stage: ParseStage) {
+        if let Some(sink) = self {
+            sink.stage_finished(stage);
+        }
+    }
+}
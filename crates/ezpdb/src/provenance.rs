@@ -0,0 +1,150 @@
+//! Collecting strings which reveal where and by whom an image was built: the
+//! absolute paths of its object files and PDBs, the working directories, tools,
+//! and command lines of the compiler and linker, and the user and machine names
+//! within those
+use crate::symbol_types::{DebugModule, ParsedPdb, ProvenanceKind, ProvenanceString};
+use std::collections::BTreeMap;
+
+/// Keys of environment block entries which hold provenance, and what they hold
+const ENVIRONMENT_KEYS: &[(&str, ProvenanceKind)] = &[
+    ("cwd", ProvenanceKind::WorkingDirectory),
+    ("exe", ProvenanceKind::BuildTool),
+    ("pdb", ProvenanceKind::PdbPath),
+    ("cmd", ProvenanceKind::CommandLine),
+];
+
+/// What the arguments of a build info record (`LF_BUILDINFO`) hold, by position.
+/// The third argument is the source file, which isn't a build's provenance.
+const BUILD_INFO_ARGUMENTS: &[(usize, ProvenanceKind)] = &[
+    (0, ProvenanceKind::WorkingDirectory),
+    (1, ProvenanceKind::BuildTool),
+    (3, ProvenanceKind::PdbPath),
+    (4, ProvenanceKind::CommandLine),
+];
+
+/// Directories whose subdirectories are named after users, in lowercase
+const HOME_DIRECTORIES: &[&str] = &["users", "home", "documents and settings"];
+
+/// Subdirectories of [HOME_DIRECTORIES] which don't belong to a user
+const SHARED_PROFILES: &[&str] = &["public", "default", "all users", "default user"];
+
+/// Returns whether `path` is an absolute Windows, UNC, or Unix path
+fn is_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let has_drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+
+    has_drive || path.starts_with('\\') || path.starts_with('/')
+}
+
+fn is_separator(c: char) -> bool {
+    c == '\\' || c == '/'
+}
+
+/// Returns the user name in `path` if it's under a home directory at the root
+/// of a drive or file system, e.g. `alice` in `C:\Users\alice\src`
+fn user_name(path: &str) -> Option<&str> {
+    let mut components = path.split(is_separator);
+    let root = components.next()?;
+    if !(root.is_empty() || root.ends_with(':')) {
+        return None;
+    }
+    let home = components.next()?.to_ascii_lowercase();
+    let user = components.next().filter(|user| !user.is_empty())?;
+    let shared = SHARED_PROFILES.contains(&user.to_ascii_lowercase().as_str());
+
+    Some(user).filter(|_| HOME_DIRECTORIES.contains(&home.as_str()) && !shared)
+}
+
+/// Returns the user and machine names in the absolute paths within `text`,
+/// which may be a command line. A user name follows a home directory, and a
+/// machine name starts a UNC path, e.g. `build01` in `\\build01\share`.
+fn names_in(text: &str) -> Vec<(ProvenanceKind, &str)> {
+    let mut names = vec![];
+    let words = text
+        .split(|c: char| c.is_whitespace() || c == '"' || c == '=')
+        .filter(|word| !word.is_empty());
+    for word in words {
+        if let Some(unc) = word.strip_prefix("\\\\") {
+            let host = unc.split(is_separator).next().unwrap_or_default();
+            if !host.is_empty() && host != "?" && host != "." {
+                names.push((ProvenanceKind::HostName, host));
+            }
+        }
+
+        // Options such as `/OUT:C:\...` put a path after the option, so paths
+        // may start at any drive letter
+        let bytes = word.as_bytes();
+        let drives = (1..bytes.len().saturating_sub(1)).filter(|&i| {
+            bytes[i] == b':'
+                && bytes[i - 1].is_ascii_alphabetic()
+                && is_separator(bytes[i + 1] as char)
+        });
+        let starts = std::iter::once(0).chain(drives.map(|i| i - 1));
+        for start in starts {
+            if let Some(user) = user_name(&word[start..]) {
+                names.push((ProvenanceKind::UserName, user));
+            }
+        }
+    }
+
+    names
+}
+
+/// Returns the provenance strings `module` records, without duplicates
+fn module_strings(module: &DebugModule) -> Vec<(ProvenanceKind, &str)> {
+    let mut strings = vec![];
+    for path in [&module.name, &module.object_file_name] {
+        if is_absolute(path) {
+            strings.push((ProvenanceKind::ObjectPath, path.as_str()));
+        }
+    }
+    if let Some(build_info) = &module.build_info {
+        for (index, kind) in BUILD_INFO_ARGUMENTS {
+            if let Some(argument) = build_info.arguments.get(*index) {
+                strings.push((*kind, argument.as_str()));
+            }
+        }
+    }
+    for entry in &module.environment {
+        if let Some((_key, kind)) = ENVIRONMENT_KEYS
+            .iter()
+            .find(|(key, _kind)| *key == entry.key)
+        {
+            strings.push((*kind, entry.value.as_str()));
+        }
+    }
+    strings.retain(|(_kind, value)| !value.trim().is_empty());
+
+    let names: Vec<_> = strings
+        .iter()
+        .flat_map(|(_kind, value)| names_in(value))
+        .collect();
+    strings.extend(names);
+    strings.sort_unstable();
+    strings.dedup();
+
+    strings
+}
+
+/// Returns the provenance strings of `pdb`'s modules, ordered by kind and value.
+/// Each string is listed once, along with the number of modules recording it.
+pub fn provenance(pdb: &ParsedPdb) -> Vec<ProvenanceString> {
+    let mut modules: BTreeMap<(ProvenanceKind, &str), usize> = BTreeMap::new();
+    for module in &pdb.debug_modules {
+        for string in module_strings(module) {
+            *modules.entry(string).or_insert(0) += 1;
+        }
+    }
+
+    modules
+        .into_iter()
+        .map(|((kind, value), modules)| ProvenanceString {
+            kind,
+            value: value.to_string(),
+            modules,
+        })
+        .collect()
+}
@@ -0,0 +1,801 @@
+//! Minimal readers for on-disk structures which the `pdb` crate doesn't expose
+use crate::error::Error;
+use crate::symbol_path::PdbReference;
+use std::collections::HashMap;
+
+/// Reads little-endian values out of a byte slice
+#[derive(Debug, Clone)]
+pub(crate) struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        ByteReader { data, pos: 0 }
+    }
+
+    pub fn seek(&mut self, pos: usize) {
+        self.pos = pos.min(self.data.len());
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.remaining() < len {
+            return Err(Error::PdbCrateError(pdb::Error::UnexpectedEof));
+        }
+
+        let data = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+
+        Ok(data)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a NUL-terminated string, replacing any invalid UTF-8
+    pub fn cstring(&mut self) -> Result<String, Error> {
+        let len = self.data[self.pos..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(Error::PdbCrateError(pdb::Error::UnexpectedEof))?;
+        let string = String::from_utf8_lossy(self.take(len)?).into_owned();
+        self.pos += 1;
+
+        Ok(string)
+    }
+}
+
+/// Returns a reader over the fields of `sym`, which starts just after its kind.
+/// Used for symbols which the `pdb` crate can't parse.
+pub(crate) fn symbol_reader<'t>(sym: &pdb::Symbol<'t>) -> ByteReader<'t> {
+    let mut reader = ByteReader::new(sym.raw_bytes());
+    reader.seek(2);
+    reader
+}
+
+/// Stream index of the PDB information stream
+pub(crate) const PDB_STREAM: u16 = 1;
+
+/// Stream index of the TPI stream
+pub(crate) const TPI_STREAM: u16 = 2;
+
+/// Stream index of the IPI stream
+pub(crate) const IPI_STREAM: u16 = 4;
+
+/// Stream index of the DBI stream
+pub(crate) const DBI_STREAM: u16 = 3;
+
+/// Size of the TPI and IPI streams' headers
+pub(crate) const TPI_HEADER_SIZE: usize = 56;
+
+/// Size of the DBI stream's header
+pub(crate) const DBI_HEADER_SIZE: usize = 64;
+
+/// Size of a module's entry in the DBI stream, excluding its module and object file names
+pub(crate) const MODULE_INFO_SIZE: usize = 64;
+
+/// Leaf kind of vtable shape records, which the `pdb` crate doesn't parse
+pub(crate) const LF_VTSHAPE: u16 = 0x000a;
+
+/// Leaf kind of pointer records
+pub(crate) const LF_POINTER: u16 = 0x1002;
+
+/// Reads the raw `CV_pmtype` of an `LF_POINTER` record, which the `pdb` crate
+/// doesn't keep. `record` starts just after the record's kind. Returns `None` if
+/// the record isn't a pointer to member.
+pub(crate) fn member_pointer_representation(
+    record: &mut ByteReader<'_>,
+) -> Result<Option<u16>, Error> {
+    let _underlying_type = record.u32()?;
+    let attributes = record.u32()?;
+    // The mode is bits 5-7 of the attributes: 2 for data and 3 for function members
+    match (attributes >> 5) & 0x7 {
+        2 | 3 => {}
+        _ => return Ok(None),
+    }
+    let _containing_class = record.u32()?;
+
+    Ok(Some(record.u16()?))
+}
+
+/// What the fallback decoder could read out of a TPI record
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PartialRecord {
+    pub name: Option<String>,
+    pub referenced_type_index: Option<u32>,
+}
+
+/// Decodes the name and referenced type of TPI records of kinds the `pdb` crate
+/// can't parse. `record` starts just after the record's kind. Returns `None` for
+/// kinds without a fallback decoder.
+pub(crate) fn decode_partial_record(
+    kind: u16,
+    record: &mut ByteReader<'_>,
+) -> Result<Option<PartialRecord>, Error> {
+    let partial = match kind {
+        // LF_VFTABLE: the owning class, the base vftable, the vfptr's offset, and
+        // the length of the names which follow. The first name is the vftable's.
+        0x151d => {
+            let owner = record.u32()?;
+            let _base_vftable = record.u32()?;
+            let _offset = record.u32()?;
+            let _names_size = record.u32()?;
+            PartialRecord {
+                name: Some(record.cstring()?),
+                referenced_type_index: Some(owner),
+            }
+        }
+        // LF_PRECOMP: the first index and count of the types taken from the
+        // precompiled header's object file, its signature, and its name
+        0x1509 => {
+            let _start = record.u32()?;
+            let _count = record.u32()?;
+            let _signature = record.u32()?;
+            PartialRecord {
+                name: Some(record.cstring()?),
+                referenced_type_index: None,
+            }
+        }
+        // LF_TYPESERVER2: the GUID and age of the PDB holding the types, and its path
+        0x1515 => {
+            let _guid = record.take(16)?;
+            let _age = record.u32()?;
+            PartialRecord {
+                name: Some(record.cstring()?),
+                referenced_type_index: None,
+            }
+        }
+        // LF_ALIAS and LF_DIMARRAY: the underlying type, then for LF_DIMARRAY the
+        // dimension information, then the name
+        0x150a | 0x1508 => {
+            let underlying_type = record.u32()?;
+            if kind == 0x1508 {
+                let _dimensions = record.u32()?;
+            }
+            PartialRecord {
+                name: Some(record.cstring()?),
+                referenced_type_index: Some(underlying_type),
+            }
+        }
+        // LF_ENDPRECOMP, LF_LABEL, and LF_VFTPATH have no name or single referenced type
+        0x0014 | 0x000e | 0x100d => PartialRecord::default(),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(partial))
+}
+
+/// Returns the `LF_*` name of the TPI leaf kind `kind`, if it's a known one
+pub(crate) fn leaf_kind_name(kind: u16) -> Option<&'static str> {
+    let name = match kind {
+        LF_VTSHAPE => "LF_VTSHAPE",
+        0x000e => "LF_LABEL",
+        0x0014 => "LF_ENDPRECOMP",
+        0x1001 => "LF_MODIFIER",
+        LF_POINTER => "LF_POINTER",
+        0x1008 => "LF_PROCEDURE",
+        0x1009 => "LF_MFUNCTION",
+        0x100d => "LF_VFTPATH",
+        0x100f => "LF_OEM",
+        0x1200 => "LF_SKIP",
+        0x1201 => "LF_ARGLIST",
+        0x1203 => "LF_FIELDLIST",
+        0x1204 => "LF_DERIVED",
+        0x1205 => "LF_BITFIELD",
+        0x1206 => "LF_METHODLIST",
+        0x1503 => "LF_ARRAY",
+        0x1504 => "LF_CLASS",
+        0x1505 => "LF_STRUCTURE",
+        0x1506 => "LF_UNION",
+        0x1507 => "LF_ENUM",
+        0x1508 => "LF_DIMARRAY",
+        0x1509 => "LF_PRECOMP",
+        0x150a => "LF_ALIAS",
+        0x1515 => "LF_TYPESERVER2",
+        0x1519 => "LF_INTERFACE",
+        0x151d => "LF_VFTABLE",
+        0x1608 => "LF_CLASS2",
+        0x1609 => "LF_STRUCTURE2",
+        0x160a => "LF_UNION2",
+        0x160b => "LF_INTERFACE2",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Returns the names of the `CV_CFL_*` source languages which the `pdb` crate
+/// doesn't know, and reads as `Masm`
+pub(crate) fn language_name(language: u8) -> Option<&'static str> {
+    let name = match language {
+        0x11 => "ObjC",
+        0x12 => "ObjCpp",
+        0x13 => "Swift",
+        0x14 => "AliasObj",
+        0x15 => "Rust",
+        0x16 => "Go",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// The header of the DBI stream. The `pdb` crate parses this internally but
+/// doesn't make it public.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct DbiHeader {
+    pub gs_symbols_stream: u16,
+    pub ps_symbols_stream: u16,
+    pub symbol_records_stream: u16,
+    pub module_list_size: u32,
+    pub section_contribution_size: u32,
+    pub section_map_size: u32,
+    pub file_info_size: u32,
+    pub type_server_map_size: u32,
+    pub debug_header_size: u32,
+    pub ec_substream_size: u32,
+    /// The `IMAGE_FILE_MACHINE_*` value of the image
+    pub machine_type: u16,
+}
+
+impl DbiHeader {
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader::new(data);
+
+        let signature = reader.u32()?;
+        if signature != u32::MAX {
+            return Err(Error::Unsupported("ancient DBI header"));
+        }
+
+        let _version = reader.u32()?;
+        let _age = reader.u32()?;
+        let gs_symbols_stream = reader.u16()?;
+        let _internal_version = reader.u16()?;
+        let ps_symbols_stream = reader.u16()?;
+        let _pdb_dll_version = reader.u16()?;
+        let symbol_records_stream = reader.u16()?;
+        let _pdb_dll_rebuild = reader.u16()?;
+        let module_list_size = reader.u32()?;
+        let section_contribution_size = reader.u32()?;
+        let section_map_size = reader.u32()?;
+        let file_info_size = reader.u32()?;
+        let type_server_map_size = reader.u32()?;
+        let _mfc_type_server_index = reader.u32()?;
+        let debug_header_size = reader.u32()?;
+        let ec_substream_size = reader.u32()?;
+        let _flags = reader.u16()?;
+        let machine_type = reader.u16()?;
+
+        Ok(DbiHeader {
+            gs_symbols_stream,
+            ps_symbols_stream,
+            symbol_records_stream,
+            module_list_size,
+            section_contribution_size,
+            section_map_size,
+            file_info_size,
+            type_server_map_size,
+            debug_header_size,
+            ec_substream_size,
+            machine_type,
+        })
+    }
+
+    /// Returns the offset of the optional debug header, which follows every
+    /// other substream of the DBI stream
+    fn debug_header_offset(&self) -> usize {
+        DBI_HEADER_SIZE
+            + [
+                self.module_list_size,
+                self.section_contribution_size,
+                self.section_map_size,
+                self.file_info_size,
+                self.type_server_map_size,
+                self.ec_substream_size,
+            ]
+            .iter()
+            .map(|&size| size as usize)
+            .sum::<usize>()
+    }
+}
+
+/// Index of the token map stream among the optional debug header's streams
+pub(crate) const DEBUG_STREAM_TOKEN_RID_MAP: usize = 6;
+
+/// Returns the stream index at position `index` of the DBI stream `dbi`'s
+/// optional debug header, or `None` if the PDB doesn't have that stream
+pub(crate) fn debug_header_stream(dbi: &[u8], index: usize) -> Result<Option<u16>, Error> {
+    let header = DbiHeader::parse(dbi)?;
+    let mut reader = ByteReader::new(dbi);
+    reader.seek(header.debug_header_offset());
+    let mut streams = ByteReader::new(reader.take(header.debug_header_size as usize)?);
+
+    if streams.remaining() < (index + 1) * 2 {
+        return Ok(None);
+    }
+    streams.seek(index * 2);
+    let stream = streams.u16()?;
+
+    Ok(if stream == u16::MAX {
+        None
+    } else {
+        Some(stream)
+    })
+}
+
+/// Returns the symbol stream index of each module in the DBI stream `dbi`, in
+/// the order of the module list. The `pdb` crate keeps these private.
+pub(crate) fn module_streams(dbi: &[u8]) -> Result<Vec<u16>, Error> {
+    let header = DbiHeader::parse(dbi)?;
+    let mut reader = ByteReader::new(dbi);
+    reader.seek(DBI_HEADER_SIZE);
+    let mut reader = ByteReader::new(reader.take(header.module_list_size as usize)?);
+
+    let mut streams = vec![];
+    while reader.remaining() > 0 {
+        let mut fields = ByteReader::new(reader.take(MODULE_INFO_SIZE)?);
+        fields.seek(34);
+        streams.push(fields.u16()?);
+
+        let _module_name = reader.cstring()?;
+        let _object_file_name = reader.cstring()?;
+        reader.seek(reader.position().div_ceil(4) * 4);
+    }
+
+    Ok(streams)
+}
+
+/// Reads the full contents of stream `index`, returning `None` if the stream doesn't exist
+pub(crate) fn read_stream<'s, S: pdb::Source<'s> + 's>(
+    pdb: &mut pdb::PDB<'s, S>,
+    index: u16,
+) -> Result<Option<Vec<u8>>, Error> {
+    Ok(pdb
+        .raw_stream(pdb::StreamIndex(index))?
+        .map(|stream| stream.as_slice().to_vec()))
+}
+
+/// Feature signature (`MINI`) of PDBs linked with `/DEBUG:FASTLINK`
+pub(crate) const FEATURE_MINIMAL_DEBUG_INFO: u32 = 0x494e_494d;
+
+/// The named stream map and feature signatures of the PDB information stream.
+/// The `pdb` crate parses the rest of the stream but stops short of the features.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PdbInformation {
+    /// Names of streams such as `/names`, with their indices
+    pub named_streams: Vec<(String, u32)>,
+    pub features: Vec<u32>,
+}
+
+impl PdbInformation {
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader::new(data);
+        let _version = reader.u32()?;
+        let _signature = reader.u32()?;
+        let _age = reader.u32()?;
+        let _guid = reader.take(16)?;
+
+        // The named stream map: its names, then a hash table of name offsets to stream indices
+        let names_size = reader.u32()?;
+        let names = reader.take(names_size as usize)?;
+        let _size = reader.u32()?;
+        let _capacity = reader.u32()?;
+        let present_words = reader.u32()?;
+        let mut present = 0;
+        for _ in 0..present_words {
+            present += reader.u32()?.count_ones();
+        }
+        let deleted_words = reader.u32()?;
+        reader.take(deleted_words as usize * 4)?;
+
+        let mut named_streams = vec![];
+        for _ in 0..present {
+            let name_offset = reader.u32()? as usize;
+            let stream = reader.u32()?;
+            let mut name = ByteReader::new(names);
+            name.seek(name_offset);
+            named_streams.push((name.cstring()?, stream));
+        }
+
+        let mut features = vec![];
+        while reader.remaining() >= 4 {
+            features.push(reader.u32()?);
+        }
+
+        Ok(PdbInformation {
+            named_streams,
+            features,
+        })
+    }
+}
+
+/// Reads the feature signatures at the end of the PDB information stream
+pub(crate) fn pdb_features<'s, S: pdb::Source<'s> + 's>(
+    pdb: &mut pdb::PDB<'s, S>,
+) -> Result<Vec<u32>, Error> {
+    match read_stream(pdb, PDB_STREAM)? {
+        Some(info) => Ok(PdbInformation::parse(&info)?.features),
+        None => Ok(vec![]),
+    }
+}
+
+/// Reads the raw version at the start of the PDB information stream
+pub(crate) fn pdb_version<'s, S: pdb::Source<'s> + 's>(
+    pdb: &mut pdb::PDB<'s, S>,
+) -> Result<u32, Error> {
+    let info =
+        read_stream(pdb, PDB_STREAM)?.ok_or(Error::MissingDependency("PDB information stream"))?;
+
+    ByteReader::new(&info).u32()
+}
+
+/// Reads and parses the DBI stream's header
+pub(crate) fn dbi_header<'s, S: pdb::Source<'s> + 's>(
+    pdb: &mut pdb::PDB<'s, S>,
+) -> Result<DbiHeader, Error> {
+    let dbi = read_stream(pdb, DBI_STREAM)?.ok_or(Error::MissingDependency("DBI stream"))?;
+
+    DbiHeader::parse(&dbi)
+}
+
+/// A section header of a PE image
+#[derive(Debug, Copy, Clone)]
+struct ImageSection {
+    virtual_address: u32,
+    raw_size: u32,
+    raw_pointer: u32,
+}
+
+/// The optional header and section headers of a PE image
+#[derive(Debug, Clone)]
+struct ImageHeaders<'a> {
+    optional_header: &'a [u8],
+    sections: Vec<ImageSection>,
+}
+
+impl<'a> ImageHeaders<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader::new(data);
+
+        if reader.take(2)? != b"MZ" {
+            return Err(Error::InvalidImage("missing DOS signature"));
+        }
+        reader.seek(0x3c);
+        let pe_header = reader.u32()? as usize;
+
+        reader.seek(pe_header);
+        if reader.take(4)? != b"PE\0\0" {
+            return Err(Error::InvalidImage("missing PE signature"));
+        }
+        let _machine = reader.u16()?;
+        let section_count = reader.u16()?;
+        let _timestamp = reader.u32()?;
+        let _symbol_table = reader.u32()?;
+        let _symbol_count = reader.u32()?;
+        let optional_header_size = reader.u16()?;
+        let _characteristics = reader.u16()?;
+        let optional_header = reader.take(optional_header_size as usize)?;
+
+        let sections = (0..section_count)
+            .map(|_| {
+                let mut header = ByteReader::new(reader.take(40)?);
+                let _name = header.take(8)?;
+                let _virtual_size = header.u32()?;
+                Ok(ImageSection {
+                    virtual_address: header.u32()?,
+                    raw_size: header.u32()?,
+                    raw_pointer: header.u32()?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(ImageHeaders {
+            optional_header,
+            sections,
+        })
+    }
+
+    /// Returns the RVA and size of data directory `index`, if the image has one
+    fn data_directory(&self, index: usize) -> Result<Option<(u32, u32)>, Error> {
+        let mut reader = ByteReader::new(self.optional_header);
+        // The directories follow the rest of the optional header, whose size depends on
+        // whether the image is 32- or 64-bit
+        let directories = match reader.u16()? {
+            PE32_MAGIC => 96,
+            PE32_PLUS_MAGIC => 112,
+            _ => return Err(Error::InvalidImage("unknown optional header magic")),
+        };
+        reader.seek(directories - 4);
+        if index >= reader.u32()? as usize {
+            return Ok(None);
+        }
+
+        reader.seek(directories + index * 8);
+        let rva = reader.u32()?;
+        let size = reader.u32()?;
+
+        Ok(if rva == 0 { None } else { Some((rva, size)) })
+    }
+
+    /// Converts `rva` to an offset within the image file
+    fn file_offset(&self, rva: u32) -> Option<usize> {
+        self.sections
+            .iter()
+            .find(|section| {
+                rva >= section.virtual_address && rva - section.virtual_address < section.raw_size
+            })
+            .map(|section| (section.raw_pointer + (rva - section.virtual_address)) as usize)
+    }
+}
+
+/// A PE image's contents, read by RVA
+#[derive(Debug, Clone)]
+pub(crate) struct Image<'a> {
+    data: &'a [u8],
+    headers: ImageHeaders<'a>,
+}
+
+impl<'a> Image<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        Ok(Image {
+            data,
+            headers: ImageHeaders::parse(data)?,
+        })
+    }
+
+    /// Returns whether the image is 64-bit (PE32+)
+    pub fn is_64bit(&self) -> Result<bool, Error> {
+        match ByteReader::new(self.headers.optional_header).u16()? {
+            PE32_MAGIC => Ok(false),
+            PE32_PLUS_MAGIC => Ok(true),
+            _ => Err(Error::InvalidImage("unknown optional header magic")),
+        }
+    }
+
+    /// Returns the address the image prefers to be loaded at
+    pub fn image_base(&self) -> Result<u64, Error> {
+        let mut reader = ByteReader::new(self.headers.optional_header);
+        if self.is_64bit()? {
+            reader.seek(24);
+            let low = reader.u32()?;
+            let high = reader.u32()?;
+            Ok(u64::from(low) | (u64::from(high) << 32))
+        } else {
+            reader.seek(28);
+            Ok(u64::from(reader.u32()?))
+        }
+    }
+
+    /// Returns a reader over the image's contents starting at `rva`
+    pub fn reader_at(&self, rva: u32) -> Result<ByteReader<'a>, Error> {
+        let offset = self
+            .headers
+            .file_offset(rva)
+            .ok_or(Error::InvalidImage("an address is outside of any section"))?;
+        let mut reader = ByteReader::new(self.data);
+        reader.seek(offset);
+
+        Ok(reader)
+    }
+
+    /// Returns the RVA and size of each address which the loader relocates when
+    /// the image isn't loaded at its preferred base, from its base relocations
+    pub fn relocations(&self) -> Result<Vec<(u32, u32)>, Error> {
+        let (rva, size) = match self.headers.data_directory(BASE_RELOCATION_DIRECTORY)? {
+            Some(directory) => directory,
+            None => return Ok(vec![]),
+        };
+        let mut reader = ByteReader::new(self.reader_at(rva)?.take(size as usize)?);
+
+        let mut relocations = vec![];
+        while reader.remaining() >= 8 {
+            let page = reader.u32()?;
+            let block_size = reader.u32()? as usize;
+            let entries = block_size.saturating_sub(8) / 2;
+            for _ in 0..entries.min(reader.remaining() / 2) {
+                let entry = reader.u16()?;
+                let offset = u32::from(entry & 0xfff);
+                match entry >> 12 {
+                    IMAGE_REL_BASED_HIGHLOW => relocations.push((page + offset, 4)),
+                    IMAGE_REL_BASED_DIR64 => relocations.push((page + offset, 8)),
+                    // Padding, or kinds which don't occur in x86, x64, or ARM64 code
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(relocations)
+    }
+
+    /// Returns the entries of the image's export table, ordered by ordinal
+    pub fn exports(&self) -> Result<Vec<ImageExport>, Error> {
+        let (directory_rva, directory_size) = match self.headers.data_directory(EXPORT_DIRECTORY)? {
+            Some(directory) => directory,
+            None => return Ok(vec![]),
+        };
+        let mut directory = self.reader_at(directory_rva)?;
+        directory.seek(directory.position() + 16);
+        let ordinal_base = directory.u32()?;
+        let function_count = directory.u32()?;
+        let name_count = directory.u32()?;
+        let functions_rva = directory.u32()?;
+        let names_rva = directory.u32()?;
+        let name_ordinals_rva = directory.u32()?;
+
+        let mut names = HashMap::new();
+        let mut name_rvas = self.reader_at(names_rva)?;
+        let mut name_ordinals = self.reader_at(name_ordinals_rva)?;
+        for _ in 0..name_count {
+            let name = self.reader_at(name_rvas.u32()?)?.cstring()?;
+            names.insert(name_ordinals.u16()? as u32, name);
+        }
+
+        let mut exports = vec![];
+        let mut functions = self.reader_at(functions_rva)?;
+        for index in 0..function_count {
+            let rva = functions.u32()?;
+            // Unused ordinals between the base and the highest one
+            if rva == 0 {
+                continue;
+            }
+            // Forwarders point at a `DLL.Symbol` string within the export directory
+            let is_forwarder = rva >= directory_rva && rva - directory_rva < directory_size;
+            exports.push(ImageExport {
+                ordinal: ordinal_base + index,
+                name: names.remove(&index),
+                rva: Some(rva).filter(|_| !is_forwarder),
+            });
+        }
+
+        Ok(exports)
+    }
+}
+
+/// An entry of a PE image's export table
+#[derive(Debug, Clone)]
+pub(crate) struct ImageExport {
+    pub ordinal: u32,
+    /// `None` if the symbol is only exported by ordinal
+    pub name: Option<String>,
+    /// Where the exported symbol is. `None` if the export forwards to a symbol
+    /// of another image.
+    pub rva: Option<u32>,
+}
+
+/// Optional header magic of 32-bit images
+const PE32_MAGIC: u16 = 0x10b;
+
+/// Optional header magic of 64-bit images
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+/// Index of the export directory among the optional header's data directories
+const EXPORT_DIRECTORY: usize = 0;
+
+/// Index of the base relocation directory among the optional header's data directories
+const BASE_RELOCATION_DIRECTORY: usize = 5;
+
+/// Index of the debug directory among the optional header's data directories
+const DEBUG_DIRECTORY: usize = 6;
+
+/// Base relocation of a 32-bit address
+const IMAGE_REL_BASED_HIGHLOW: u16 = 3;
+
+/// Base relocation of a 64-bit address
+const IMAGE_REL_BASED_DIR64: u16 = 10;
+
+/// Size of an entry of the debug directory
+const DEBUG_DIRECTORY_ENTRY_SIZE: u32 = 28;
+
+/// Debug directory entry type of CodeView information, which names the image's PDB
+const IMAGE_DEBUG_TYPE_CODEVIEW: u32 = 2;
+
+/// Signature of PDB 7.00 CodeView records
+const RSDS_SIGNATURE: &[u8] = b"RSDS";
+
+/// Reads the virtual address of each section of the PE image `data`, in the
+/// order of its section headers
+pub(crate) fn image_section_addresses(data: &[u8]) -> Result<Vec<u32>, Error> {
+    Ok(ImageHeaders::parse(data)?
+        .sections
+        .iter()
+        .map(|section| section.virtual_address)
+        .collect())
+}
+
+/// Returns whether `data` starts like a PE image rather than a PDB
+pub(crate) fn is_image(data: &[u8]) -> bool {
+    data.starts_with(b"MZ")
+}
+
+/// Reads the GUID, age, and path of the PDB which the PE image `data` was linked
+/// with from its CodeView debug directory entry. Returns `None` if the image
+/// has no such entry.
+pub(crate) fn image_pdb_reference(data: &[u8]) -> Result<Option<PdbReference>, Error> {
+    let headers = ImageHeaders::parse(data)?;
+    let (rva, size) = match headers.data_directory(DEBUG_DIRECTORY)? {
+        Some(directory) => directory,
+        None => return Ok(None),
+    };
+    let offset = headers.file_offset(rva).ok_or(Error::InvalidImage(
+        "the debug directory is outside of any section",
+    ))?;
+
+    let mut reader = ByteReader::new(data);
+    reader.seek(offset);
+    for _ in 0..size / DEBUG_DIRECTORY_ENTRY_SIZE {
+        let mut entry = ByteReader::new(reader.take(DEBUG_DIRECTORY_ENTRY_SIZE as usize)?);
+        entry.seek(12);
+        let kind = entry.u32()?;
+        let data_size = entry.u32()? as usize;
+        let _data_rva = entry.u32()?;
+        let data_offset = entry.u32()? as usize;
+        if kind != IMAGE_DEBUG_TYPE_CODEVIEW {
+            continue;
+        }
+
+        let mut record = ByteReader::new(data);
+        record.seek(data_offset);
+        let mut record = ByteReader::new(record.take(data_size)?);
+        if record.take(4)? != RSDS_SIGNATURE {
+            return Err(Error::Unsupported("CodeView records other than RSDS"));
+        }
+        let mut guid = [0; 16];
+        guid.copy_from_slice(record.take(16)?);
+        let age = record.u32()?;
+        let path = record.cstring()?;
+
+        return Ok(Some(PdbReference { guid, age, path }));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the fields of an `LF_POINTER` record after its kind
+    fn pointer_record(mode: u32, pmtype: u16) -> Vec<u8> {
+        let mut record = vec![];
+        record.extend_from_slice(&0x1000u32.to_le_bytes());
+        // A 16-byte `Ptr64` pointer
+        record.extend_from_slice(&(0xC | mode << 5 | 16 << 13).to_le_bytes());
+        record.extend_from_slice(&0x1001u32.to_le_bytes());
+        record.extend_from_slice(&pmtype.to_le_bytes());
+        record
+    }
+
+    #[test]
+    fn member_pointer_representation_is_read_from_member_pointers() {
+        let record = pointer_record(3, 0x07);
+        let pmtype = member_pointer_representation(&mut ByteReader::new(&record)).unwrap();
+        assert_eq!(pmtype, Some(0x07));
+    }
+
+    #[test]
+    fn plain_pointers_have_no_member_pointer_representation() {
+        let record = pointer_record(0, 0x07);
+        let pmtype = member_pointer_representation(&mut ByteReader::new(&record)).unwrap();
+        assert_eq!(pmtype, None);
+    }
+}
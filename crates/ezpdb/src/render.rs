@@ -0,0 +1,424 @@
+//! Formatting types as C, C++, or Rust-flavored type names and declarations,
+//! e.g. `int32_t (*)[0x8]` or `char* const name`. Every [Type] variant can be
+//! formatted: records which aren't types themselves, such as members and field
+//! lists, are formatted as the declarations they hold, and types which can't be
+//! resolved are formatted as markers such as `<UNRESOLVED_TYPE>`.
+use crate::symbol_types::ParsedPdb;
+use crate::type_info::{FieldAttributes, Partial, Pointer, Primitive, PrimitiveKind, Type, TypeId};
+
+/// How deeply types may be nested before formatting gives up, which guards
+/// against malformed PDBs whose types refer to themselves
+const MAX_DEPTH: usize = 64;
+
+/// The language whose conventions primitive type names follow
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Language {
+    C,
+    Cpp,
+    Rust,
+}
+
+/// How types are formatted
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RenderOptions {
+    /// Name primitive types the way code in this language would, e.g. `long`
+    /// and `wchar_t` for C and C++ or `i32` and `u16` for Rust. Fixed-width
+    /// names such as `int32_t` are used if this isn't set.
+    pub language: Option<Language>,
+}
+
+/// Formats the name of `ty` as it would appear in a C cast, e.g. `int (*)[0x8]`
+pub fn type_name(pdb: &ParsedPdb, ty: &Type, options: &RenderOptions) -> String {
+    declaration(pdb, ty, "", options)
+}
+
+/// Formats a C declaration of `name` with the type `ty`, e.g. `int (*x[0x4])[0x8]`.
+/// An empty `name` produces the type's name.
+pub fn declaration(pdb: &ParsedPdb, ty: &Type, name: &str, options: &RenderOptions) -> String {
+    Renderer { pdb, options }.declare(ty, name.to_string(), !name.is_empty(), false, 0)
+}
+
+/// Formats primitive type names following `options`, e.g. `int32_t` or `i32`
+pub fn primitive_name(primitive: &Primitive, options: &RenderOptions) -> String {
+    let name = match options.language {
+        Some(Language::C) => c_primitive_name(primitive.kind, false),
+        Some(Language::Cpp) => c_primitive_name(primitive.kind, true),
+        Some(Language::Rust) => rust_primitive_name(primitive.kind),
+        None => None,
+    };
+    if let Some(name) = name {
+        return name.to_string();
+    }
+
+    match primitive.kind {
+        PrimitiveKind::Void => "void".to_string(),
+        PrimitiveKind::Char | PrimitiveKind::RChar => "char".to_string(),
+        PrimitiveKind::UChar => "unsigned char".to_string(),
+
+        PrimitiveKind::I8 => "int8_t".to_string(),
+        PrimitiveKind::U8 => "uint8_t".to_string(),
+        PrimitiveKind::I16 | PrimitiveKind::Short => "int16_t".to_string(),
+        PrimitiveKind::U16 | PrimitiveKind::UShort => "uint16_t".to_string(),
+        PrimitiveKind::I32 | PrimitiveKind::Long => "int32_t".to_string(),
+        PrimitiveKind::U32 | PrimitiveKind::ULong => "uint32_t".to_string(),
+        PrimitiveKind::I64 | PrimitiveKind::Quad => "int64_t".to_string(),
+        PrimitiveKind::U64 | PrimitiveKind::UQuad => "uint64_t".to_string(),
+
+        PrimitiveKind::F32 => "float".to_string(),
+        PrimitiveKind::F64 => "double".to_string(),
+
+        PrimitiveKind::Bool8 => "bool".to_string(),
+        other => {
+            format!("{}", other)
+        }
+    }
+}
+
+/// Formats the name of a type the `pdb` crate couldn't parse, e.g.
+/// `<LF_VFTABLE Foo::vftable>`, or `<0x1234>` for an unknown kind without a name
+pub fn partial_name(partial: &Partial) -> String {
+    let kind = partial
+        .kind_name
+        .clone()
+        .unwrap_or_else(|| format!("0x{:04X}", partial.kind));
+    match &partial.name {
+        Some(name) => format!("<{} {}>", kind, name),
+        None => format!("<{}>", kind),
+    }
+}
+
+/// Formats cv-qualifiers, e.g. `const volatile`
+fn format_qualifiers(constant: bool, volatile: bool, unaligned: bool) -> String {
+    let mut qualifiers = vec![];
+    if constant {
+        qualifiers.push("const");
+    }
+    if volatile {
+        qualifiers.push("volatile");
+    }
+    if unaligned {
+        qualifiers.push("__unaligned");
+    }
+
+    qualifiers.join(" ")
+}
+
+/// Formats the storage class and virtual-ness of a method, e.g. `static `
+fn method_prefix(attributes: &FieldAttributes) -> &'static str {
+    if attributes.is_static {
+        "static "
+    } else if attributes.is_virtual {
+        "virtual "
+    } else {
+        ""
+    }
+}
+
+/// The state shared by the formatting of a declaration
+struct Renderer<'a> {
+    pdb: &'a ParsedPdb,
+    options: &'a RenderOptions,
+}
+
+impl Renderer<'_> {
+    /// Builds a declaration by wrapping `declarator` in the declarator syntax of
+    /// each type `ty` is derived from until a type name is reached. `is_pointer` is
+    /// set when `declarator` ends with a pointer, which must be parenthesized before
+    /// array or function syntax is applied to it.
+    fn declare(
+        &self,
+        ty: &Type,
+        declarator: String,
+        named: bool,
+        is_pointer: bool,
+        depth: usize,
+    ) -> String {
+        if depth > MAX_DEPTH {
+            return join_declaration("<...>".to_string(), &declarator, named);
+        }
+
+        let parenthesize = |declarator: String| {
+            if is_pointer {
+                format!("({})", declarator)
+            } else {
+                declarator
+            }
+        };
+        // Formats the type `id` with `declarator` applied to it
+        let declare_id =
+            |id: TypeId, declarator: String, is_pointer: bool| match self.pdb.types.get(id) {
+                Some(ty) => self.declare(ty, declarator, named, is_pointer, depth + 1),
+                None => join_declaration("<UNRESOLVED_TYPE>".to_string(), &declarator, named),
+            };
+        // Formats a record which isn't a type itself, as the declaration it holds
+        let describe = |description: String| join_declaration(description, &declarator, named);
+
+        match ty {
+            Type::Pointer(pointer) => self.declare_pointer(pointer, "", declarator, named, depth),
+            Type::Modifier(modifier) => {
+                let qualifiers =
+                    format_qualifiers(modifier.constant, modifier.volatile, modifier.unaligned);
+                match self.pdb.types.get(modifier.underlying_type) {
+                    _ if qualifiers.is_empty() => {
+                        declare_id(modifier.underlying_type, declarator, is_pointer)
+                    }
+                    // A qualified pointer, e.g. `char* const`
+                    Some(Type::Pointer(pointer)) => {
+                        self.declare_pointer(pointer, &qualifiers, declarator, named, depth)
+                    }
+                    _ => format!(
+                        "{} {}",
+                        qualifiers,
+                        declare_id(modifier.underlying_type, declarator, is_pointer)
+                    ),
+                }
+            }
+            Type::Array(array) => {
+                // Dimensions are stored innermost first
+                let dimensions = array
+                    .dimensions_elements
+                    .iter()
+                    .rev()
+                    .fold(String::new(), |accum, dimension| {
+                        format!("{}[0x{:X}]", accum, dimension)
+                    });
+                declare_id(
+                    array.element_type,
+                    format!("{}{}", parenthesize(declarator), dimensions),
+                    false,
+                )
+            }
+            Type::Procedure(proc) => {
+                let declarator = format!(
+                    "{}{}",
+                    parenthesize(declarator),
+                    self.argument_list(&proc.argument_list, depth)
+                );
+                match proc.return_type {
+                    Some(return_type) => declare_id(return_type, declarator, false),
+                    None => join_declaration("void".to_string(), &declarator, named),
+                }
+            }
+            Type::MemberFunction(member) => declare_id(
+                member.return_type,
+                format!(
+                    "{}{}",
+                    parenthesize(declarator),
+                    self.argument_list(&member.argument_list, depth)
+                ),
+                false,
+            ),
+            Type::Bitfield(bitfield) => format!(
+                "{}:{}",
+                declare_id(bitfield.underlying_type, declarator, false),
+                bitfield.len
+            ),
+            Type::Class(class) => join_declaration(class.name.to_string(), &declarator, named),
+            Type::Union(union) => join_declaration(union.name.to_string(), &declarator, named),
+            Type::Enumeration(e) => join_declaration(e.name.to_string(), &declarator, named),
+            Type::Primitive(primitive) => {
+                // Pointers to primitives may be encoded in the primitive itself
+                let declarator = match primitive.indirection {
+                    Some(_) => format!("*{}", declarator),
+                    None => declarator,
+                };
+                join_declaration(primitive_name(primitive, self.options), &declarator, named)
+            }
+            Type::Partial(partial) => join_declaration(partial_name(partial), &declarator, named),
+            // Base classes and vtables are named after the type they refer to
+            Type::BaseClass(base) => declare_id(base.base_class, declarator, is_pointer),
+            Type::VirtualBaseClass(base) => declare_id(base.base_class, declarator, is_pointer),
+            Type::VTable(vtable) => declare_id(vtable.0, declarator, is_pointer),
+            Type::MethodListEntry(entry) => declare_id(entry.method_type, declarator, is_pointer),
+            Type::EnumVariant(variant) => describe(variant.name.to_string()),
+            Type::Nested(nested) => describe(nested.name.to_string()),
+            Type::OverloadedMethod(overloaded) => describe(overloaded.name.to_string()),
+            Type::Member(member) => {
+                describe(self.declare_name(member.underlying_type, &member.name, depth))
+            }
+            Type::StaticMember(member) => describe(format!(
+                "static {}",
+                self.declare_name(member.field_type, &member.name, depth)
+            )),
+            Type::Method(method) => describe(format!(
+                "{}{}",
+                method_prefix(&method.attributes),
+                self.declare_name(method.method_type, &method.name, depth)
+            )),
+            Type::ArgumentList(arguments) => describe(self.argument_list(&arguments.0, depth)),
+            Type::FieldList(fields) => describe(self.braced_list(&fields.0, depth)),
+            Type::MethodList(methods) => {
+                let methods: Vec<TypeId> =
+                    methods.0.iter().map(|entry| entry.method_type).collect();
+                describe(self.braced_list(&methods, depth))
+            }
+        }
+    }
+
+    /// Adds `pointer`'s declarator syntax to `declarator`. `qualifiers` are applied
+    /// to the pointer in addition to its own.
+    fn declare_pointer(
+        &self,
+        pointer: &Pointer,
+        qualifiers: &str,
+        declarator: String,
+        named: bool,
+        depth: usize,
+    ) -> String {
+        let underlying_type = match pointer
+            .underlying_type
+            .and_then(|id| self.pdb.types.get(id))
+        {
+            Some(underlying_type) => underlying_type,
+            None => {
+                return join_declaration(
+                    "<UNRESOLVED_POINTER_TYPE>".to_string(),
+                    &format!("*{}", declarator),
+                    named,
+                )
+            }
+        };
+
+        let attributes = &pointer.attributes;
+        let sigil = match pointer
+            .member
+            .as_ref()
+            .and_then(|member| member.containing_class)
+        {
+            Some(class) => format!("{}::*", self.declare_name(class, "", depth)),
+            None if attributes.is_rvalue_reference => "&&".to_string(),
+            None if attributes.is_reference => "&".to_string(),
+            None => "*".to_string(),
+        };
+
+        let own_qualifiers = format_qualifiers(
+            attributes.is_const,
+            attributes.is_volatile,
+            attributes.is_unaligned,
+        );
+        let mut qualifiers: Vec<&str> = [qualifiers, own_qualifiers.as_str()]
+            .iter()
+            .copied()
+            .filter(|qualifiers| !qualifiers.is_empty())
+            .collect();
+        if attributes.is_restrict {
+            qualifiers.push("__restrict");
+        }
+
+        let declarator = if qualifiers.is_empty() {
+            format!("{}{}", sigil, declarator)
+        } else if declarator.is_empty() {
+            format!("{} {}", sigil, qualifiers.join(" "))
+        } else {
+            format!("{} {} {}", sigil, qualifiers.join(" "), declarator)
+        };
+
+        self.declare(underlying_type, declarator, named, true, depth + 1)
+    }
+
+    /// Formats a standalone declaration of `name` with the type `id`, or the
+    /// type's name if `name` is empty
+    fn declare_name(&self, id: TypeId, name: &str, depth: usize) -> String {
+        match self.pdb.types.get(id) {
+            Some(ty) => self.declare(ty, name.to_string(), !name.is_empty(), false, depth + 1),
+            None => join_declaration("<UNRESOLVED_TYPE>".to_string(), name, !name.is_empty()),
+        }
+    }
+
+    /// Formats a function's parameter list, e.g. `(int32_t, char*)`
+    fn argument_list(&self, arguments: &[TypeId], depth: usize) -> String {
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|argument| self.declare_name(*argument, "", depth))
+            .collect();
+
+        format!("({})", arguments.join(", "))
+    }
+
+    /// Formats a list of fields or methods, e.g. `{ int32_t x; float y; }`
+    fn braced_list(&self, entries: &[TypeId], depth: usize) -> String {
+        if entries.is_empty() {
+            return "{}".to_string();
+        }
+
+        let entries: Vec<String> = entries
+            .iter()
+            .map(|entry| format!("{};", self.declare_name(*entry, "", depth)))
+            .collect();
+
+        format!("{{ {} }}", entries.join(" "))
+    }
+}
+
+/// Joins a type name and the declarator applied to it, e.g. `int` and `(*)[0x8]`
+fn join_declaration(type_name: String, declarator: &str, named: bool) -> String {
+    let glued = declarator.is_empty()
+        || declarator.starts_with('[')
+        || (!named && (declarator.starts_with('*') || declarator.starts_with('&')));
+    if glued {
+        format!("{}{}", type_name, declarator)
+    } else {
+        format!("{} {}", type_name, declarator)
+    }
+}
+
+/// Returns the C or C++ keyword or Windows typedef for `kind`, if there is one
+fn c_primitive_name(kind: PrimitiveKind, cpp: bool) -> Option<&'static str> {
+    let name = match kind {
+        PrimitiveKind::Void => "void",
+        PrimitiveKind::Char | PrimitiveKind::RChar => "char",
+        PrimitiveKind::UChar => "unsigned char",
+        PrimitiveKind::WChar => "wchar_t",
+        PrimitiveKind::RChar16 => "char16_t",
+        PrimitiveKind::RChar32 => "char32_t",
+        PrimitiveKind::I8 => "signed char",
+        PrimitiveKind::U8 => "unsigned char",
+        PrimitiveKind::Short | PrimitiveKind::I16 => "short",
+        PrimitiveKind::UShort | PrimitiveKind::U16 => "unsigned short",
+        PrimitiveKind::Long => "long",
+        PrimitiveKind::ULong => "unsigned long",
+        PrimitiveKind::I32 => "int",
+        PrimitiveKind::U32 => "unsigned int",
+        PrimitiveKind::Quad | PrimitiveKind::I64 => "long long",
+        PrimitiveKind::UQuad | PrimitiveKind::U64 => "unsigned long long",
+        PrimitiveKind::F32 => "float",
+        PrimitiveKind::F64 => "double",
+        PrimitiveKind::F80 => "long double",
+        PrimitiveKind::Bool8 if cpp => "bool",
+        PrimitiveKind::Bool8 => "_Bool",
+        PrimitiveKind::Bool32 => "BOOL",
+        PrimitiveKind::HRESULT => "HRESULT",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Returns the Rust primitive or `std::os::raw`/`windows` type for `kind`, if
+/// there is one
+fn rust_primitive_name(kind: PrimitiveKind) -> Option<&'static str> {
+    let name = match kind {
+        PrimitiveKind::Void => "c_void",
+        PrimitiveKind::Char | PrimitiveKind::RChar => "c_char",
+        PrimitiveKind::UChar | PrimitiveKind::U8 => "u8",
+        PrimitiveKind::I8 => "i8",
+        PrimitiveKind::WChar | PrimitiveKind::RChar16 => "u16",
+        PrimitiveKind::RChar32 => "u32",
+        PrimitiveKind::Short | PrimitiveKind::I16 => "i16",
+        PrimitiveKind::UShort | PrimitiveKind::U16 => "u16",
+        PrimitiveKind::Long | PrimitiveKind::I32 => "i32",
+        PrimitiveKind::ULong | PrimitiveKind::U32 => "u32",
+        PrimitiveKind::Quad | PrimitiveKind::I64 => "i64",
+        PrimitiveKind::UQuad | PrimitiveKind::U64 => "u64",
+        PrimitiveKind::Octa | PrimitiveKind::I128 => "i128",
+        PrimitiveKind::UOcta | PrimitiveKind::U128 => "u128",
+        PrimitiveKind::F32 => "f32",
+        PrimitiveKind::F64 => "f64",
+        PrimitiveKind::Bool8 => "bool",
+        PrimitiveKind::Bool32 => "BOOL",
+        PrimitiveKind::HRESULT => "HRESULT",
+        _ => return None,
+    };
+
+    Some(name)
+}
@@ -0,0 +1,395 @@
+//! Name-based searching across every kind of named item in a [ParsedPdb], and
+//! approximate searching of symbol names
+use crate::index::SymbolId;
+use crate::symbol_types::{Address, ParsedPdb, SymbolRef};
+use crate::type_info::Type;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// A pattern to match names against. `*` matches any run of characters and
+/// `?` matches any single character. A pattern without wildcards matches any
+/// name which contains it.
+///
+/// A WinDbg-style module prefix (e.g. the `nt!` in `nt!*Token*`) is ignored
+/// since a PDB only ever describes a single module.
+#[derive(Debug, Clone)]
+pub struct NamePattern {
+    pattern: Vec<char>,
+    has_wildcards: bool,
+    case_insensitive: bool,
+    whole_word: bool,
+}
+
+impl NamePattern {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = match pattern.find('!') {
+            Some(idx) => &pattern[idx + 1..],
+            None => pattern,
+        };
+
+        NamePattern {
+            pattern: pattern.chars().collect(),
+            has_wildcards: pattern.contains(['*', '?']),
+            case_insensitive: false,
+            whole_word: false,
+        }
+    }
+
+    /// Ignore ASCII case when matching
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Only match a pattern without wildcards when it is surrounded by
+    /// characters which can't be part of an identifier
+    pub fn whole_word(mut self, whole_word: bool) -> Self {
+        self.whole_word = whole_word;
+        self
+    }
+
+    /// Returns whether `name` matches this pattern
+    pub fn is_match(&self, name: &str) -> bool {
+        let name: Vec<char> = name.chars().collect();
+
+        if self.has_wildcards {
+            return self.glob_match(&self.pattern, &name);
+        }
+
+        if self.pattern.len() > name.len() {
+            return false;
+        }
+
+        (0..=name.len() - self.pattern.len()).any(|start| {
+            let end = start + self.pattern.len();
+            let is_match = self.chars_eq_all(&self.pattern, &name[start..end]);
+
+            is_match
+                && (!self.whole_word
+                    || (!name[..start].last().is_some_and(|c| is_word_char(*c))
+                        && !name.get(end).is_some_and(|c| is_word_char(*c))))
+        })
+    }
+
+    fn chars_eq(&self, a: char, b: char) -> bool {
+        if self.case_insensitive {
+            a.eq_ignore_ascii_case(&b)
+        } else {
+            a == b
+        }
+    }
+
+    fn chars_eq_all(&self, a: &[char], b: &[char]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.chars_eq(*a, *b))
+    }
+
+    /// Matches `name` against the whole of `pattern`, backtracking to the
+    /// most recent `*` on a mismatch
+    fn glob_match(&self, pattern: &[char], name: &[char]) -> bool {
+        let (mut p, mut n) = (0, 0);
+        let mut backtrack = None;
+
+        while n < name.len() {
+            match pattern.get(p) {
+                Some('*') => {
+                    backtrack = Some((p, n));
+                    p += 1;
+                }
+                Some('?') => {
+                    p += 1;
+                    n += 1;
+                }
+                Some(c) if self.chars_eq(*c, name[n]) => {
+                    p += 1;
+                    n += 1;
+                }
+                _ => match backtrack {
+                    Some((star, star_n)) => {
+                        p = star + 1;
+                        n = star_n + 1;
+                        backtrack = Some((star, star_n + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        pattern[p..].iter().all(|c| *c == '*')
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The kind of item a [SearchHit] was found in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SearchCategory {
+    PublicSymbol,
+    Procedure,
+    GlobalData,
+    LocalData,
+    Type,
+    Field,
+    EnumVariant,
+}
+
+impl std::fmt::Display for SearchCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SearchCategory::PublicSymbol => "public",
+            SearchCategory::Procedure => "procedure",
+            SearchCategory::GlobalData => "global",
+            SearchCategory::LocalData => "local",
+            SearchCategory::Type => "type",
+            SearchCategory::Field => "field",
+            SearchCategory::EnumVariant => "enum variant",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A single item whose name matched a [NamePattern]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchHit {
+    pub category: SearchCategory,
+    pub name: String,
+    /// Name of the type the item belongs to, for fields and enum variants
+    pub parent: Option<String>,
+}
+
+/// Returns every symbol, type, field, and enum variant in `pdb` whose name matches `pattern`
+pub fn search(pdb: &ParsedPdb, pattern: &NamePattern) -> Vec<SearchHit> {
+    let mut hits = vec![];
+    let mut push = |category, name: &str, parent: Option<&str>| {
+        if pattern.is_match(name) {
+            hits.push(SearchHit {
+                category,
+                name: name.to_string(),
+                parent: parent.map(str::to_string),
+            });
+        }
+    };
+
+    for symbol in &pdb.public_symbols {
+        push(SearchCategory::PublicSymbol, &symbol.name, None);
+    }
+
+    for procedure in &pdb.procedures {
+        push(SearchCategory::Procedure, &procedure.name, None);
+    }
+
+    for global in &pdb.global_data {
+        push(SearchCategory::GlobalData, &global.name, None);
+    }
+
+    for (_id, ty) in pdb.types.iter() {
+        let (name, fields) = match ty {
+            Type::Class(class) if !class.properties.forward_reference => {
+                (&class.name, &class.fields)
+            }
+            Type::Union(union) if !union.properties.forward_reference => {
+                (&union.name, &union.fields)
+            }
+            Type::Enumeration(e) if !e.properties.forward_reference => {
+                push(SearchCategory::Type, &e.name, None);
+                for variant in &e.variants {
+                    push(SearchCategory::EnumVariant, &variant.name, Some(&e.name));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        push(SearchCategory::Type, name, None);
+        for field in fields {
+            if let Type::Member(member) = &pdb.types[*field] {
+                push(SearchCategory::Field, &member.name, Some(name));
+            }
+        }
+    }
+
+    hits
+}
+
+/// A symbol found by [find_symbol] or [FuzzyIndex::search]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SymbolMatch {
+    pub category: SearchCategory,
+    pub name: String,
+    pub address: Option<Address>,
+    /// How similar the name is to the one searched for, from 0 to 1. Exact
+    /// matches score 1.
+    pub score: f64,
+}
+
+impl SymbolMatch {
+    fn new(id: SymbolId, symbol: SymbolRef<'_>, score: f64) -> Self {
+        SymbolMatch {
+            category: match id {
+                SymbolId::Public(_) => SearchCategory::PublicSymbol,
+                SymbolId::Procedure(_) => SearchCategory::Procedure,
+                SymbolId::GlobalData(_) => SearchCategory::GlobalData,
+                SymbolId::LocalData(_) => SearchCategory::LocalData,
+            },
+            name: symbol.name().to_string(),
+            address: symbol.address(),
+            score,
+        }
+    }
+}
+
+/// Returns the public symbols, procedures, and global and local data named
+/// exactly `name`. Uses [ParsedPdb::index] if it was built.
+pub fn find_symbol(pdb: &ParsedPdb, name: &str) -> Vec<SymbolMatch> {
+    let ids: Vec<SymbolId> = match &pdb.index {
+        Some(index) => index.symbols_by_name.get(name).cloned().unwrap_or_default(),
+        None => pdb
+            .symbols()
+            .filter(|(_id, symbol)| symbol.name() == name)
+            .map(|(id, _symbol)| id)
+            .collect(),
+    };
+
+    ids.into_iter()
+        .filter_map(|id| Some(SymbolMatch::new(id, pdb.symbol(id)?, 1.0)))
+        .collect()
+}
+
+/// The symbol names of a PDB indexed by their trigrams (runs of three
+/// characters, ignoring ASCII case), for finding names similar to a
+/// misspelled or partially remembered one
+pub struct FuzzyIndex<'a> {
+    pdb: &'a ParsedPdb,
+    symbols: Vec<IndexedName>,
+    /// Maps each trigram to the indices into `symbols` of the names containing it
+    trigrams: HashMap<[char; 3], Vec<usize>>,
+}
+
+impl<'a> FuzzyIndex<'a> {
+    /// Indexes the names of the public symbols, procedures, and global and
+    /// local data of `pdb`
+    pub fn new(pdb: &'a ParsedPdb) -> Self {
+        let mut symbols = vec![];
+        let mut trigrams: HashMap<[char; 3], Vec<usize>> = HashMap::new();
+        for (id, symbol) in pdb.symbols() {
+            let name = fold_case(symbol.name());
+            let name_trigrams = name_trigrams(&name);
+            let index = symbols.len();
+            for trigram in &name_trigrams {
+                trigrams.entry(*trigram).or_default().push(index);
+            }
+            symbols.push(IndexedName {
+                id,
+                name,
+                trigrams: name_trigrams.len(),
+            });
+        }
+
+        FuzzyIndex {
+            pdb,
+            symbols,
+            trigrams,
+        }
+    }
+
+    /// Returns at most `limit` of the symbols whose names are most similar to
+    /// `query`, best first. A name's score is the mean of the share of
+    /// trigrams it has in common with `query` and its edit distance to
+    /// `query` relative to the longer of the two. Only names which share a
+    /// trigram with `query` are considered.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SymbolMatch> {
+        let query = fold_case(query);
+        let query_trigrams = name_trigrams(&query);
+
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+        for trigram in &query_trigrams {
+            for index in self.trigrams.get(trigram).into_iter().flatten() {
+                *shared.entry(*index).or_default() += 1;
+            }
+        }
+
+        let mut matches: Vec<(f64, usize)> = shared
+            .into_iter()
+            .map(|(index, shared)| {
+                let symbol = &self.symbols[index];
+                let trigram_score =
+                    2.0 * shared as f64 / (query_trigrams.len() + symbol.trigrams) as f64;
+                let longest = query.len().max(symbol.name.len()).max(1);
+                let edit_score = 1.0 - levenshtein(&query, &symbol.name) as f64 / longest as f64;
+
+                ((trigram_score + edit_score) / 2.0, index)
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut seen = HashSet::new();
+        matches
+            .into_iter()
+            .filter_map(|(score, index)| {
+                let id = self.symbols[index].id;
+                let symbol = self.pdb.symbol(id)?;
+                // A name is only reported once per kind of symbol
+                seen.insert((std::mem::discriminant(&id), symbol.name()))
+                    .then(|| SymbolMatch::new(id, symbol, score))
+            })
+            .take(limit)
+            .collect()
+    }
+}
+
+/// A symbol name in a [FuzzyIndex]
+struct IndexedName {
+    id: SymbolId,
+    /// The name with ASCII case folded
+    name: Vec<char>,
+    /// Number of distinct trigrams in `name`
+    trigrams: usize,
+}
+
+fn fold_case(name: &str) -> Vec<char> {
+    name.chars().map(|c| c.to_ascii_lowercase()).collect()
+}
+
+/// Returns the distinct trigrams of `name`. The name is padded with two NULs
+/// before and one after, so that names which only share their first
+/// characters, e.g. a misspelling which swaps two letters in a short name,
+/// still share a trigram.
+fn name_trigrams(name: &[char]) -> HashSet<[char; 3]> {
+    if name.is_empty() {
+        return HashSet::new();
+    }
+
+    let padded: Vec<char> = ['\0', '\0']
+        .iter()
+        .chain(name)
+        .chain(['\0'].iter())
+        .copied()
+        .collect();
+    padded
+        .windows(3)
+        .map(|window| [window[0], window[1], window[2]])
+        .collect()
+}
+
+/// The number of single-character insertions, deletions, and substitutions
+/// which turn `a` into `b`
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
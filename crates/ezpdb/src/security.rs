@@ -0,0 +1,78 @@
+//! Locating the load configuration and the metadata and runtime support of
+//! security mitigations, such as Control Flow Guard's tables, by their symbols
+use crate::symbol_types::{ParsedPdb, SecurityFeature, SecuritySymbol};
+
+/// Names of symbols which belong to a mitigation, without the extra leading
+/// underscore C names have on x86
+const SYMBOLS: &[(&str, SecurityFeature)] = &[
+    ("_load_config_used", SecurityFeature::LoadConfig),
+    ("__security_cookie", SecurityFeature::StackCookie),
+    ("__security_cookie_complement", SecurityFeature::StackCookie),
+    ("__security_init_cookie", SecurityFeature::StackCookie),
+    ("__security_check_cookie", SecurityFeature::StackCookie),
+    ("__safe_se_handler_table", SecurityFeature::SafeSeh),
+    ("__safe_se_handler_count", SecurityFeature::SafeSeh),
+];
+
+/// Name prefixes of symbols which belong to a mitigation. The more specific
+/// `__guard_` prefixes come first.
+const PREFIXES: &[(&str, SecurityFeature)] = &[
+    ("__guard_xfg_", SecurityFeature::ExtendedFlowGuard),
+    ("_guard_xfg_", SecurityFeature::ExtendedFlowGuard),
+    ("__guard_eh_cont_", SecurityFeature::EhContinuation),
+    ("__guard_retpoline_", SecurityFeature::Retpoline),
+    ("__guard_", SecurityFeature::ControlFlowGuard),
+    ("_guard_", SecurityFeature::ControlFlowGuard),
+    ("__GSHandlerCheck", SecurityFeature::StackCookie),
+    ("__castguard_", SecurityFeature::CastGuard),
+    ("__retpoline_", SecurityFeature::Retpoline),
+];
+
+/// Returns the mitigation the symbol `name` belongs to, if any
+pub fn security_feature(name: &str) -> Option<SecurityFeature> {
+    let name = if name.starts_with("___") || name == "__load_config_used" {
+        &name[1..]
+    } else {
+        name
+    };
+
+    SYMBOLS
+        .iter()
+        .find(|(symbol, _feature)| *symbol == name)
+        .or_else(|| {
+            PREFIXES
+                .iter()
+                .find(|(prefix, _feature)| name.starts_with(prefix))
+        })
+        .map(|(_name, feature)| *feature)
+}
+
+/// Returns the public symbols, procedures, and globals of `pdb` which belong to
+/// a mitigation, ordered by address. A symbol which is both e.g. a public
+/// symbol and a procedure is only returned once.
+pub fn security_symbols(pdb: &ParsedPdb) -> Vec<SecuritySymbol> {
+    let mut symbols: Vec<SecuritySymbol> = pdb
+        .public_symbols
+        .iter()
+        .map(|public| (&public.name, public.offset))
+        .chain(
+            pdb.procedures
+                .iter()
+                .map(|procedure| (&procedure.name, procedure.address)),
+        )
+        .chain(pdb.global_data.iter().map(|data| (&data.name, data.offset)))
+        .filter_map(|(name, offset)| {
+            Some(SecuritySymbol {
+                name: name.clone(),
+                offset,
+                feature: security_feature(name)?,
+            })
+        })
+        .collect();
+    symbols.sort_by(|a, b| {
+        (a.offset.is_none(), a.offset, &a.name).cmp(&(b.offset.is_none(), b.offset, &b.name))
+    });
+    symbols.dedup_by(|a, b| a.name == b.name && a.offset == b.offset);
+
+    symbols
+}
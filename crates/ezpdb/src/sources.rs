@@ -0,0 +1,160 @@
+//! Comparing the checksums of a PDB's source files against a local source tree
+use crate::digest;
+use crate::symbol_types::{Checksum, FileInfo, ParsedPdb};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The result of checking a single source file against the local tree
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SourceStatus {
+    /// The local file's checksum matches the PDB's
+    Match,
+    /// The local file's checksum differs from the PDB's
+    Mismatch {
+        expected: Checksum,
+        actual: Checksum,
+    },
+    /// No local file was found for the source file
+    Missing,
+    /// The PDB has no checksum for the source file, so it can't be checked
+    NoChecksum,
+}
+
+/// A source file of the PDB and what was found for it in the local tree
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceVerification {
+    /// The file's path as recorded in the PDB
+    pub name: String,
+    /// The local file it was compared against
+    pub local_path: Option<PathBuf>,
+    pub status: SourceStatus,
+}
+
+/// The code contributed by a single source file
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SourceFileStatistics {
+    /// Path of the file as recorded in the PDB
+    pub file: String,
+    /// The number of procedures defined in the file
+    pub procedures: usize,
+    /// The total length of those procedures, in bytes
+    pub bytes: usize,
+}
+
+/// Returns every source file of every module in `pdb`, without duplicates and ordered by name
+pub fn source_files(pdb: &ParsedPdb) -> Vec<&FileInfo> {
+    let files: BTreeMap<&str, &FileInfo> = pdb
+        .debug_modules
+        .iter()
+        .flat_map(|module| module.source_files.iter().flatten())
+        .map(|file| (file.name.as_str(), file))
+        .collect();
+
+    files.into_values().collect()
+}
+
+/// Totals the procedures defined in each source file and their lengths, ordered
+/// from the file contributing the most code to the least. Procedures without
+/// line information aren't counted.
+pub fn file_statistics(pdb: &ParsedPdb) -> Vec<SourceFileStatistics> {
+    let mut files: BTreeMap<&str, SourceFileStatistics> = BTreeMap::new();
+    for procedure in &pdb.procedures {
+        if let Some(source) = &procedure.source {
+            let statistics = files
+                .entry(&source.file)
+                .or_insert_with(|| SourceFileStatistics {
+                    file: source.file.clone(),
+                    procedures: 0,
+                    bytes: 0,
+                });
+            statistics.procedures += 1;
+            statistics.bytes += procedure.len;
+        }
+    }
+
+    let mut statistics: Vec<SourceFileStatistics> = files.into_values().collect();
+    statistics.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(b.procedures.cmp(&a.procedures)));
+    statistics
+}
+
+/// Hashes the local copy of each of `pdb`'s source files under `root` and
+/// compares it with the checksum recorded in the PDB.
+///
+/// Source paths are usually absolute paths on the build machine, so each is
+/// looked up under `root` by the longest trailing portion of its path which
+/// exists, e.g. `D:\build\src\lib\foo.c` is found at `<root>/src/lib/foo.c`
+/// before `<root>/lib/foo.c` or `<root>/foo.c`.
+pub fn verify_sources(pdb: &ParsedPdb, root: &Path) -> Vec<SourceVerification> {
+    source_files(pdb)
+        .into_iter()
+        .map(|file| {
+            let local_path = find_local_file(root, &file.name);
+            let status = match (&file.checksum, &local_path) {
+                (Checksum::None, _) => SourceStatus::NoChecksum,
+                (_, None) => SourceStatus::Missing,
+                (expected, Some(path)) => match std::fs::read(path) {
+                    Ok(data) => {
+                        let actual = hash_like(expected, &data);
+                        if checksum_eq(expected, &actual) {
+                            SourceStatus::Match
+                        } else {
+                            SourceStatus::Mismatch {
+                                expected: expected.clone(),
+                                actual,
+                            }
+                        }
+                    }
+                    Err(_) => SourceStatus::Missing,
+                },
+            };
+
+            SourceVerification {
+                name: file.name.clone(),
+                local_path,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Returns the file under `root` matching the longest trailing portion of the PDB path `name`
+fn find_local_file(root: &Path, name: &str) -> Option<PathBuf> {
+    // Drive letters are never part of the local path
+    let components: Vec<&str> = name
+        .split(['\\', '/'])
+        .filter(|component| !component.is_empty() && !component.ends_with(':'))
+        .collect();
+
+    (0..components.len())
+        .map(|start| {
+            components[start..]
+                .iter()
+                .fold(root.to_path_buf(), |path, component| path.join(component))
+        })
+        .find(|path| path.is_file())
+}
+
+/// Hashes `data` with the same algorithm as `checksum`
+fn hash_like(checksum: &Checksum, data: &[u8]) -> Checksum {
+    match checksum {
+        Checksum::None => Checksum::None,
+        Checksum::Md5(_) => Checksum::Md5(digest::md5(data).to_vec()),
+        Checksum::Sha1(_) => Checksum::Sha1(digest::sha1(data).to_vec()),
+        Checksum::Sha256(_) => Checksum::Sha256(digest::sha256(data).to_vec()),
+    }
+}
+
+/// Returns whether `a` and `b` are the same checksum from the same algorithm
+fn checksum_eq(a: &Checksum, b: &Checksum) -> bool {
+    match (a, b) {
+        (Checksum::Md5(a), Checksum::Md5(b))
+        | (Checksum::Sha1(a), Checksum::Sha1(b))
+        | (Checksum::Sha256(a), Checksum::Sha256(b)) => a == b,
+        _ => false,
+    }
+}
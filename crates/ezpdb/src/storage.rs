@@ -0,0 +1,104 @@
+//! Tells which kind of section each variable is stored in, e.g. to find
+//! function pointers which an attacker could overwrite
+use crate::symbol_types::{ParsedPdb, Storage};
+
+/// `IMAGE_SCN_CNT_UNINITIALIZED_DATA`
+const IMAGE_SCN_CNT_UNINITIALIZED_DATA: u32 = 0x0000_0080;
+/// `IMAGE_SCN_MEM_WRITE`
+const IMAGE_SCN_MEM_WRITE: u32 = 0x8000_0000;
+
+/// A range of RVAs and the kind of data stored in it
+#[derive(Debug, Copy, Clone)]
+struct Region {
+    start: usize,
+    end: usize,
+    storage: Storage,
+}
+
+/// Returns the kind of data a section or COFF group with the `IMAGE_SCN_*`
+/// flags `characteristics` holds
+pub fn storage_of(characteristics: u32) -> Storage {
+    if characteristics & IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0 {
+        Storage::ZeroInitialized
+    } else if characteristics & IMAGE_SCN_MEM_WRITE != 0 {
+        Storage::Writable
+    } else {
+        Storage::ReadOnly
+    }
+}
+
+/// Returns the region containing `rva`. `regions` must be sorted and not overlap.
+fn find(regions: &[Region], rva: usize) -> Option<Storage> {
+    let index = regions.partition_point(|region| region.end <= rva);
+    regions
+        .get(index)
+        .filter(|region| region.start <= rva)
+        .map(|region| region.storage)
+}
+
+fn sorted(mut regions: Vec<Region>) -> Vec<Region> {
+    regions.retain(|region| region.start < region.end);
+    regions.sort_by_key(|region| region.start);
+    regions
+}
+
+/// Sets [crate::symbol_types::Data::storage] of each variable in `pdb`. The
+/// linker's COFF groups take precedence over its sections, as `.bss` is usually
+/// merged into the `.data` section. If the linker didn't record its sections,
+/// the PDB's copy of the image's section `headers` is used instead, where the
+/// part of a section past its raw data is zero-initialized.
+pub fn classify_data(pdb: &mut ParsedPdb, headers: &[pdb::ImageSectionHeader]) {
+    let groups = sorted(
+        pdb.coff_groups
+            .iter()
+            .filter_map(|group| {
+                let start = group.offset?.rva;
+                Some(Region {
+                    start,
+                    end: start + group.len,
+                    storage: storage_of(group.characteristics),
+                })
+            })
+            .collect(),
+    );
+
+    let sections = if pdb.sections.is_empty() {
+        let mut regions = vec![];
+        for header in headers {
+            let start = header.virtual_address as usize;
+            let raw_end = start + (header.size_of_raw_data.min(header.virtual_size) as usize);
+            let end = start + header.virtual_size as usize;
+            let storage = storage_of(header.characteristics.0);
+            regions.push(Region {
+                start,
+                end: raw_end,
+                storage,
+            });
+            regions.push(Region {
+                start: raw_end,
+                end,
+                storage: match storage {
+                    Storage::ReadOnly => Storage::ReadOnly,
+                    _ => Storage::ZeroInitialized,
+                },
+            });
+        }
+        regions
+    } else {
+        pdb.sections
+            .iter()
+            .map(|section| Region {
+                start: section.offset.rva,
+                end: section.offset.rva + section.len,
+                storage: storage_of(section.characteristics),
+            })
+            .collect()
+    };
+    let sections = sorted(sections);
+
+    for data in pdb.global_data.iter_mut().chain(pdb.local_data.iter_mut()) {
+        data.storage = data
+            .offset
+            .and_then(|offset| find(&groups, offset.rva).or_else(|| find(&sections, offset.rva)));
+    }
+}
@@ -0,0 +1,99 @@
+//! Functions which hand symbols to a callback as soon as they're parsed rather
+//! than collecting the entire PDB into a [crate::ParsedPdb] first. These skip
+//! type conversion entirely, so the first record is available almost immediately
+//! even for very large PDBs.
+use crate::address::AddressMapping;
+use crate::convert::IntoModel;
+use crate::error::Error;
+use crate::symbol_types::{Procedure, PublicSymbol};
+use crate::ParseOptions;
+use pdb::{FallibleIterator, SymbolData};
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// Invokes `f` with each public symbol in the PDB at `path` in the order they
+/// appear in the global symbol stream. Returning an error from `f` stops iteration
+/// and returns that error.
+pub fn stream_public_symbols<P, F>(path: P, options: &ParseOptions, mut f: F) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+    F: FnMut(PublicSymbol) -> Result<(), Error>,
+{
+    let base_address = options.base_address;
+    let mut pdb = crate::open_pdb(path.as_ref())?;
+    let address_map = AddressMapping::new(&mut pdb, options);
+
+    let symbol_table = pdb.global_symbols()?;
+    let mut symbols = symbol_table.iter();
+    while let Some(symbol) = symbols.next()? {
+        match symbol.parse() {
+            Ok(SymbolData::Public(data)) => f((data, base_address, &address_map).into_model())?,
+            Ok(_) => {}
+            Err(e) => warn!("Error handling symbol {:?}: {}", symbol, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Invokes `f` with each procedure in the PDB at `path`, first from the global
+/// symbol stream and then from each debug module. Returning an error from `f`
+/// stops iteration and returns that error.
+pub fn stream_procedures<P, F>(path: P, options: &ParseOptions, mut f: F) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+    F: FnMut(Procedure) -> Result<(), Error>,
+{
+    let base_address = options.base_address;
+    let mut pdb = crate::open_pdb(path.as_ref())?;
+    let address_map = AddressMapping::new(&mut pdb, options);
+
+    // Procedure signatures are looked up by type index, so the finder needs to
+    // know where every type is. This is only an index of the TPI stream --
+    // no types are converted.
+    let type_information = pdb.type_information()?;
+    let mut type_finder = type_information.finder();
+    let mut iter = type_information.iter();
+    while iter.next()?.is_some() {
+        type_finder.update(&iter);
+    }
+
+    let mut handle_symbol = |symbol: pdb::Symbol<'_>| -> Result<(), Error> {
+        match symbol.parse() {
+            Ok(SymbolData::Procedure(data)) => {
+                f((data, base_address, &address_map, &type_finder).into_model())
+            }
+            Ok(_) => Ok(()),
+            Err(e) => {
+                warn!("Error handling symbol {:?}: {}", symbol, e);
+                Ok(())
+            }
+        }
+    };
+
+    let symbol_table = pdb.global_symbols()?;
+    let mut symbols = symbol_table.iter();
+    while let Some(symbol) = symbols.next()? {
+        handle_symbol(symbol)?;
+    }
+
+    let debug_info = pdb.debug_information()?;
+    let mut modules = debug_info.modules()?;
+    while let Some(module) = modules.next()? {
+        let module_info = match pdb.module_info(&module)? {
+            Some(module_info) => module_info,
+            None => {
+                warn!("Could not get module info for debug module: {:?}", module);
+                continue;
+            }
+        };
+
+        debug!("grabbing symbols for module: {}", module.module_name());
+        let mut symbols = module_info.symbols()?;
+        while let Some(symbol) = symbols.next()? {
+            handle_symbol(symbol)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,288 @@
+//! Writing a copy of a PDB which only holds its public symbols, like `pdbcopy /p`,
+//! for distributing symbols without exposing types, private symbols, or source
+//! file information
+use crate::error::Error;
+use crate::gsi;
+use crate::msf;
+use crate::raw::{
+    self, ByteReader, DbiHeader, PdbInformation, DBI_HEADER_SIZE, DBI_STREAM, IPI_STREAM,
+    MODULE_INFO_SIZE, PDB_STREAM, TPI_HEADER_SIZE, TPI_STREAM,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Options controlling what [strip_pdb] keeps besides the public symbols
+#[derive(Debug, Default, Clone)]
+pub struct StripOptions {
+    /// Keep the section contributions, which map address ranges to the modules
+    /// which contributed them
+    pub section_contributions: bool,
+}
+
+/// Symbol kind of public symbols
+const S_PUB32: u16 = 0x110e;
+
+/// DBI flag marking a PDB whose private symbols were stripped
+const DBI_FLAG_STRIPPED: u16 = 0x2;
+
+/// Offset of the flags in the DBI header
+const DBI_FLAGS_OFFSET: usize = 56;
+
+/// Offset of the sizes of the substreams in the DBI header, in the order the substreams appear
+const DBI_SUBSTREAM_SIZES_OFFSET: usize = 24;
+
+/// Name of the string table stream
+const NAMES_STREAM: &str = "/names";
+
+/// Signature and hash version of the `/names` string table
+const STRING_TABLE_HEADER: [u32; 2] = [0xeffe_effe, 1];
+
+/// Writes a copy of the PDB at `input` to `output` which only holds the PDB's
+/// public symbols and what's needed to map their addresses: the section map
+/// and the streams of the optional debug header, such as section headers and
+/// FPO data. Types, module streams, global symbols, and source file
+/// information are left out. Returns the number of public symbols kept.
+pub fn strip_pdb<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    options: &StripOptions,
+) -> Result<usize, Error> {
+    let mut source = crate::open_source(input.as_ref())?;
+    let stream_count = msf::stream_count(&mut source)?;
+    let mut pdb = pdb::PDB::open(source)?;
+
+    let read = |pdb: &mut pdb::PDB<'_, _>, index: u16, name: &'static str| {
+        raw::read_stream(pdb, index)?.ok_or(Error::MissingDependency(name))
+    };
+    let information = read(&mut pdb, PDB_STREAM, "PDB information stream")?;
+    let tpi = read(&mut pdb, TPI_STREAM, "TPI stream")?;
+    let dbi = read(&mut pdb, DBI_STREAM, "DBI stream")?;
+    let header = DbiHeader::parse(&dbi)?;
+    let publics = read(&mut pdb, header.ps_symbols_stream, "public symbols stream")?;
+    let symbol_records = read(
+        &mut pdb,
+        header.symbol_records_stream,
+        "symbol records stream",
+    )?;
+
+    let mut streams: Vec<Option<Vec<u8>>> = vec![None; stream_count as usize];
+    let mut keep = |index: u16, data: Vec<u8>| {
+        if let Some(stream) = streams.get_mut(index as usize) {
+            *stream = Some(data);
+        }
+    };
+
+    // The old stream directory, which is always present even if empty
+    keep(0, vec![]);
+
+    let names = PdbInformation::parse(&information)?
+        .named_streams
+        .into_iter()
+        .find(|(name, _)| name == NAMES_STREAM)
+        .map(|(_, index)| index as u16);
+    keep(PDB_STREAM, strip_information(&information, names)?);
+    if let Some(names) = names {
+        keep(names, empty_string_table());
+    }
+
+    keep(TPI_STREAM, empty_type_stream(&tpi)?);
+    if let Some(ipi) = raw::read_stream(&mut pdb, IPI_STREAM)? {
+        keep(IPI_STREAM, empty_type_stream(&ipi)?);
+    }
+
+    keep(DBI_STREAM, strip_debug_information(&dbi, &header, options)?);
+    for index in 0..header.debug_header_size as usize / 2 {
+        if let Some(stream) = raw::debug_header_stream(&dbi, index)? {
+            if let Some(data) = raw::read_stream(&mut pdb, stream)? {
+                keep(stream, data);
+            }
+        }
+    }
+
+    let (symbol_records, offsets) = public_symbol_records(&symbol_records)?;
+    let public_count = offsets.len();
+    keep(header.symbol_records_stream, symbol_records);
+    keep(
+        header.ps_symbols_stream,
+        gsi::remap_publics(&publics, &offsets)?,
+    );
+    keep(header.gs_symbols_stream, gsi::empty_hash_table());
+
+    std::fs::write(output, msf::write_big_msf(&streams))?;
+
+    Ok(public_count)
+}
+
+/// Rewrites the PDB information stream so that its named stream map only
+/// refers to the string table, which is stream `names`
+fn strip_information(information: &[u8], names: Option<u16>) -> Result<Vec<u8>, Error> {
+    let mut reader = ByteReader::new(information);
+    // The version, signature, age, and GUID
+    let mut stripped = reader.take(28)?.to_vec();
+    let features = PdbInformation::parse(information)?.features;
+
+    let (names_buffer, entries) = match names {
+        Some(names) => (format!("{}\0", NAMES_STREAM), vec![0, u32::from(names)]),
+        None => (String::new(), vec![]),
+    };
+    let entry_count = entries.len() as u32 / 2;
+    stripped.extend_from_slice(&(names_buffer.len() as u32).to_le_bytes());
+    stripped.extend_from_slice(names_buffer.as_bytes());
+
+    let hash_table = [
+        // Size and capacity of the hash table
+        entry_count,
+        1,
+        // Bitmap of present buckets, a single word
+        1,
+        entry_count,
+        // Bitmap of deleted buckets, which is empty
+        0,
+    ];
+    for value in hash_table.iter().chain(&entries).chain(&features) {
+        stripped.extend_from_slice(&value.to_le_bytes());
+    }
+
+    Ok(stripped)
+}
+
+/// Returns a `/names` string table holding no strings
+fn empty_string_table() -> Vec<u8> {
+    let mut table: Vec<u8> = STRING_TABLE_HEADER
+        .iter()
+        // The strings, of which there's only the empty one
+        .chain(&[1])
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+    table.push(0);
+
+    // A hash table of one empty bucket, then the number of strings
+    for value in [1u32, 0, 0] {
+        table.extend_from_slice(&value.to_le_bytes());
+    }
+
+    table
+}
+
+/// Returns a copy of the TPI or IPI stream header `data` describing a stream
+/// with no types and no hash stream
+fn empty_type_stream(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut reader = ByteReader::new(data);
+    let mut header = reader.take(TPI_HEADER_SIZE)?.to_vec();
+
+    let type_index_begin = ByteReader::new(&header[8..]).u32()?;
+    header[12..16].copy_from_slice(&type_index_begin.to_le_bytes());
+    // Size of the type records
+    header[16..20].fill(0);
+    // The hash stream and auxiliary hash stream
+    header[20..24].fill(0xff);
+    // Offsets and sizes of the hash values, type index offsets, and hash adjusters
+    header[32..56].fill(0);
+
+    Ok(header)
+}
+
+/// Rewrites the DBI stream `dbi` without source file information, edit and
+/// continue information, or references to module streams. Section
+/// contributions are kept if `options` asks for them.
+fn strip_debug_information(
+    dbi: &[u8],
+    header: &DbiHeader,
+    options: &StripOptions,
+) -> Result<Vec<u8>, Error> {
+    let mut reader = ByteReader::new(dbi);
+    let mut stripped = reader.take(DBI_HEADER_SIZE)?.to_vec();
+
+    let mut modules = ByteReader::new(reader.take(header.module_list_size as usize)?);
+    let mut module_count = 0u16;
+    while modules.remaining() > 0 {
+        let mut module = modules.take(MODULE_INFO_SIZE)?.to_vec();
+        // The module's stream, the sizes of its symbols and C11 and C13 line
+        // information, and its number of source files
+        module[34..36].fill(0xff);
+        module[36..50].fill(0);
+        stripped.extend_from_slice(&module);
+
+        // The module and object file names are kept, padded as they were
+        let names_start = modules.position();
+        let _module_name = modules.cstring()?;
+        let _object_file_name = modules.cstring()?;
+        let names_end = modules.position().div_ceil(4) * 4;
+        let module_list_end = modules.position() + modules.remaining();
+        modules.seek(names_start);
+        stripped.extend_from_slice(modules.take(names_end.min(module_list_end) - names_start)?);
+        module_count += 1;
+    }
+    let module_list_size = stripped.len() - DBI_HEADER_SIZE;
+
+    let section_contributions = reader.take(header.section_contribution_size as usize)?;
+    let section_contributions = if options.section_contributions {
+        section_contributions
+    } else {
+        // Only the version
+        section_contributions.get(..4).unwrap_or_default()
+    };
+    stripped.extend_from_slice(section_contributions);
+
+    let section_map = reader.take(header.section_map_size as usize)?;
+    stripped.extend_from_slice(section_map);
+
+    // Source file information with no files: the number of modules and files,
+    // then the index of each module's first file and its number of files
+    let file_info_start = stripped.len();
+    stripped.extend_from_slice(&module_count.to_le_bytes());
+    stripped.extend_from_slice(&0u16.to_le_bytes());
+    stripped.resize(stripped.len() + usize::from(module_count) * 4, 0);
+    stripped.resize(stripped.len().div_ceil(4) * 4, 0);
+    let file_info_size = stripped.len() - file_info_start;
+
+    reader.take(header.file_info_size as usize)?;
+    reader.take(header.type_server_map_size as usize)?;
+    reader.take(header.ec_substream_size as usize)?;
+    let debug_header = reader.take(header.debug_header_size as usize)?;
+    stripped.extend_from_slice(debug_header);
+
+    let mut set = |offset: usize, value: u32| {
+        stripped[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    };
+    set(DBI_SUBSTREAM_SIZES_OFFSET, module_list_size as u32);
+    set(
+        DBI_SUBSTREAM_SIZES_OFFSET + 4,
+        section_contributions.len() as u32,
+    );
+    set(DBI_SUBSTREAM_SIZES_OFFSET + 8, section_map.len() as u32);
+    set(DBI_SUBSTREAM_SIZES_OFFSET + 12, file_info_size as u32);
+    // The type server map and the index of the MFC type server
+    set(DBI_SUBSTREAM_SIZES_OFFSET + 16, 0);
+    set(DBI_SUBSTREAM_SIZES_OFFSET + 20, 0);
+    set(DBI_SUBSTREAM_SIZES_OFFSET + 24, debug_header.len() as u32);
+    // The edit and continue information
+    set(DBI_SUBSTREAM_SIZES_OFFSET + 28, 0);
+
+    let flags = ByteReader::new(&stripped[DBI_FLAGS_OFFSET..]).u16()? | DBI_FLAG_STRIPPED;
+    stripped[DBI_FLAGS_OFFSET..DBI_FLAGS_OFFSET + 2].copy_from_slice(&flags.to_le_bytes());
+
+    Ok(stripped)
+}
+
+/// Returns the public symbols of the symbol records stream `data` as a stream
+/// of their own, along with the new offset of each by its old offset
+fn public_symbol_records(data: &[u8]) -> Result<(Vec<u8>, HashMap<u32, u32>), Error> {
+    let mut reader = ByteReader::new(data);
+    let mut records = vec![];
+    let mut offsets = HashMap::new();
+    while reader.remaining() >= 4 {
+        let offset = reader.position();
+        let len = reader.u16()?;
+        let record = reader.take(len as usize)?;
+        if ByteReader::new(record).u16()? != S_PUB32 {
+            continue;
+        }
+
+        offsets.insert(offset as u32, records.len() as u32);
+        records.extend_from_slice(&len.to_le_bytes());
+        records.extend_from_slice(record);
+    }
+
+    Ok((records, offsets))
+}
@@ -0,0 +1,293 @@
+//! Locating the PDB of a PE image through a symbol path in the syntax of the
+//! `_NT_SYMBOL_PATH` environment variable, e.g.
+//! `C:\symbols;cache*C:\cache;srv*C:\store*\\server\share\symbols`
+use crate::error::Error;
+use crate::raw;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// The environment variable Windows debuggers read their symbol path from
+pub const SYMBOL_PATH_VARIABLE: &str = "_NT_SYMBOL_PATH";
+
+/// The PDB a PE image was linked with, as recorded in its CodeView debug directory entry
+#[derive(Debug, Clone)]
+pub struct PdbReference {
+    /// The PDB's GUID in its on-disk (mixed-endian) layout
+    pub guid: [u8; 16],
+    pub age: u32,
+    /// The path the PDB was written to when the image was linked
+    pub path: String,
+}
+
+impl PdbReference {
+    /// Returns the PDB's file name, without the build machine's directories
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['\\', '/']).next().unwrap_or(&self.path)
+    }
+
+    /// Returns the directory name symbol stores file the PDB under: its GUID
+    /// as uppercase hex digits followed by its age in hex, e.g.
+    /// `3844DBB920174967BE7AA4A2C20430FA2`
+    pub fn store_key(&self) -> String {
+        let guid = &self.guid;
+        let mut key = format!(
+            "{:08X}{:04X}{:04X}",
+            u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]),
+            u16::from_le_bytes([guid[4], guid[5]]),
+            u16::from_le_bytes([guid[6], guid[7]]),
+        );
+        for byte in &guid[8..] {
+            key.push_str(&format!("{:02X}", byte));
+        }
+        key.push_str(&format!("{:X}", self.age));
+
+        key
+    }
+}
+
+/// A single `;`-separated element of a symbol path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolPathElement {
+    /// A directory searched for the PDB by name
+    Directory(PathBuf),
+    /// `cache*<dir>`: PDBs found by later elements are copied into the directory.
+    /// `None` for `cache*` without a directory, which refers to the debugger's
+    /// default cache.
+    Cache(Option<PathBuf>),
+    /// `srv*<store>*...*<store>`: symbol stores searched in order, with PDBs found
+    /// in a store copied into the stores before it. Stores may be directories,
+    /// network shares, or HTTP servers.
+    Server(Vec<String>),
+}
+
+/// A parsed `_NT_SYMBOL_PATH`-style symbol path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolPath {
+    pub elements: Vec<SymbolPathElement>,
+}
+
+impl SymbolPath {
+    /// Parses a symbol path such as `C:\symbols;srv*C:\cache*https://msdl.microsoft.com/download/symbols`
+    pub fn parse(path: &str) -> Self {
+        let elements = path
+            .split(';')
+            .map(str::trim)
+            .filter(|element| !element.is_empty())
+            .map(|element| {
+                let mut parts = element.split('*');
+                let kind = parts.next().unwrap_or_default().to_ascii_lowercase();
+                match kind.as_str() {
+                    "srv" => SymbolPathElement::Server(stores(parts)),
+                    // The second part names the symbol server DLL, which is always symsrv
+                    "symsrv" => SymbolPathElement::Server(stores(parts.skip(1))),
+                    "cache" => SymbolPathElement::Cache(
+                        parts
+                            .next()
+                            .filter(|dir| !dir.is_empty())
+                            .map(PathBuf::from),
+                    ),
+                    _ => SymbolPathElement::Directory(PathBuf::from(element)),
+                }
+            })
+            .collect();
+
+        SymbolPath { elements }
+    }
+
+    /// Reads the symbol path from `_NT_SYMBOL_PATH`, if it's set
+    pub fn from_env() -> Option<Self> {
+        std::env::var(SYMBOL_PATH_VARIABLE)
+            .ok()
+            .map(|path| Self::parse(&path))
+    }
+
+    /// Searches for the PDB `reference` names, the way Windows debuggers do for
+    /// the image at `image_path`. Directory elements are searched for the PDB by
+    /// name, and in the `<ext>` and `symbols\<ext>` subdirectories for the image's
+    /// extension. Symbol stores are searched under `<name>\<key>\<name>` (see
+    /// [PdbReference::store_key]), and a PDB found in one is copied into the
+    /// stores before it and into any `cache*` directories. Only PDBs whose GUID
+    /// and age match are returned.
+    ///
+    /// HTTP symbol servers can't be queried, so they're skipped with a warning.
+    pub fn find_pdb(&self, reference: &PdbReference, image_path: &Path) -> Option<PathBuf> {
+        let name = reference.file_name();
+        let key = reference.store_key();
+        let extension = image_path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+
+        let mut caches: Vec<PathBuf> = vec![];
+        for element in &self.elements {
+            let found = match element {
+                SymbolPathElement::Directory(dir) => {
+                    let mut candidates = vec![dir.join(name)];
+                    if let Some(extension) = &extension {
+                        candidates.push(dir.join(extension).join(name));
+                        candidates.push(dir.join("symbols").join(extension).join(name));
+                    }
+                    candidates
+                        .into_iter()
+                        .find(|candidate| matches_reference(candidate, reference))
+                }
+                SymbolPathElement::Cache(Some(dir)) => {
+                    // Everything after the cache is copied into it, so it's checked first
+                    let candidate = store_path(dir, name, &key);
+                    if matches_reference(&candidate, reference) {
+                        return Some(candidate);
+                    }
+                    caches.push(dir.clone());
+                    None
+                }
+                SymbolPathElement::Cache(None) => {
+                    warn!("`cache*` without a directory refers to a debugger's default cache and is ignored");
+                    None
+                }
+                SymbolPathElement::Server(stores) => find_in_stores(stores, name, &key, reference),
+            };
+
+            if let Some(found) = found {
+                for cache in &caches {
+                    populate_store(cache, name, &key, &found);
+                }
+                return Some(found);
+            }
+        }
+
+        None
+    }
+}
+
+/// Splits the stores of a `srv*` element. Empty stores, which refer to a
+/// debugger's default store, are dropped.
+fn stores<'a>(parts: impl Iterator<Item = &'a str>) -> Vec<String> {
+    parts
+        .filter(|store| !store.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Searches `stores` in order, copying the PDB into the stores before the one
+/// it was found in
+fn find_in_stores(
+    stores: &[String],
+    name: &str,
+    key: &str,
+    reference: &PdbReference,
+) -> Option<PathBuf> {
+    for (i, store) in stores.iter().enumerate() {
+        if is_url(store) {
+            warn!(
+                "symbol server {} can't be queried: only directory and network share symbol stores are supported",
+                store
+            );
+            continue;
+        }
+
+        let candidate = store_path(Path::new(store), name, key);
+        if matches_reference(&candidate, reference) {
+            for downstream in stores[..i].iter().filter(|store| !is_url(store)) {
+                populate_store(Path::new(downstream), name, key, &candidate);
+            }
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+fn is_url(store: &str) -> bool {
+    let store = store.to_ascii_lowercase();
+    store.starts_with("http://") || store.starts_with("https://")
+}
+
+/// Returns where a symbol store keeps the PDB `name` with the key `key`
+fn store_path(store: &Path, name: &str, key: &str) -> PathBuf {
+    store.join(name).join(key).join(name)
+}
+
+/// Copies `pdb` into `store`. Failures are only logged, as debuggers do.
+fn populate_store(store: &Path, name: &str, key: &str, pdb: &Path) {
+    let destination = store_path(store, name, key);
+    if destination == pdb || destination.is_file() {
+        return;
+    }
+
+    debug!("copying {:?} into the symbol store {:?}", pdb, store);
+    let copied = destination
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::copy(pdb, &destination));
+    if let Err(e) = copied {
+        warn!(
+            "could not copy {:?} into the symbol store {:?}: {}",
+            pdb, store, e
+        );
+    }
+}
+
+/// Returns whether `path` is a PDB with the GUID and age `reference` names
+fn matches_reference(path: &Path, reference: &PdbReference) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+
+    match crate::read_summary(path) {
+        Ok(summary) => {
+            let key = format!("{:X}{:X}", summary.guid.simple(), summary.age);
+            if key == reference.store_key() {
+                true
+            } else {
+                debug!("{:?} is a different build of the PDB ({})", path, key);
+                false
+            }
+        }
+        Err(e) => {
+            debug!("{:?} could not be read as a PDB: {}", path, e);
+            false
+        }
+    }
+}
+
+/// Returns the PDB `image_path` was linked with if it's a PE image, or `None`
+/// if it's something else (such as a PDB itself)
+pub fn image_pdb_reference<P: AsRef<Path>>(image_path: P) -> Result<Option<PdbReference>, Error> {
+    let mut file = File::open(image_path)?;
+    let mut magic = [0; 2];
+    if file.read_exact(&mut magic).is_err() || !raw::is_image(&magic) {
+        return Ok(None);
+    }
+
+    let mut data = magic.to_vec();
+    file.read_to_end(&mut data)?;
+    raw::image_pdb_reference(&data)?
+        .map(Some)
+        .ok_or(Error::InvalidImage(
+            "the image has no CodeView debug information",
+        ))
+}
+
+/// Finds the PDB `reference` names for the image at `image_path` the way
+/// Windows debuggers do: through `symbol_path` if there is one, then at the
+/// path recorded in the image, then next to the image.
+pub fn find_image_pdb(
+    image_path: &Path,
+    reference: &PdbReference,
+    symbol_path: Option<&SymbolPath>,
+) -> Option<PathBuf> {
+    if let Some(found) = symbol_path.and_then(|path| path.find_pdb(reference, image_path)) {
+        return Some(found);
+    }
+
+    let recorded = PathBuf::from(&reference.path);
+    let beside_image = image_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(reference.file_name());
+    [recorded, beside_image]
+        .iter()
+        .find(|candidate| matches_reference(candidate, reference))
+        .cloned()
+}
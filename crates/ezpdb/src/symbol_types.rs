@@ -1,122 +1,17 @@
-use crate::type_info::Type;
-use log::warn;
-use pdb::{FallibleIterator, TypeIndex};
-#[cfg(feature = "serde")]
-use serde::Serialize;
-use std::cell::RefCell;
+//! Conversions from the `pdb` crate's symbols into the [ezpdb_model::symbol_types] model
+use crate::address::AddressMapping;
+use crate::convert::{FromPdb, IntoModel, TryFromPdb};
+use crate::error::Error;
+use crate::raw::symbol_reader;
+use crate::type_info::TypeId;
+use pdb::FallibleIterator;
 use std::collections::HashMap;
-use std::convert::{From, TryFrom};
-use std::path::PathBuf;
-use std::rc::Rc;
-
-pub type TypeRef = Rc<RefCell<Type>>;
-pub type TypeIndexNumber = u32;
-/// Represents a PDB that has been fully parsed
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct ParsedPdb {
-    pub path: PathBuf,
-    pub assembly_info: AssemblyInfo,
-    pub public_symbols: Vec<PublicSymbol>,
-    pub types: HashMap<TypeIndexNumber, TypeRef>,
-    pub procedures: Vec<Procedure>,
-    pub global_data: Vec<Data>,
-    pub debug_modules: Vec<DebugModule>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing))]
-    pub(crate) forward_references: Vec<Rc<Type>>,
-    pub version: Version,
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_uuid"))]
-    pub guid: uuid::Uuid,
-    pub age: u32,
-    pub timestamp: u32,
-    pub machine_type: Option<MachineType>,
-}
+use tracing::warn;
 
-impl ParsedPdb {
-    /// Constructs a new [ParsedPdb] with the corresponding path
-    pub fn new(path: PathBuf) -> Self {
-        ParsedPdb {
-            path,
-            assembly_info: AssemblyInfo::default(),
-            public_symbols: vec![],
-            types: Default::default(),
-            procedures: vec![],
-            global_data: vec![],
-            debug_modules: vec![],
-            forward_references: vec![],
-            version: Version::Other(0),
-            guid: uuid::Uuid::nil(),
-            age: 0,
-            timestamp: 0,
-            machine_type: None,
-        }
-    }
-}
+pub use ezpdb_model::symbol_types::*;
 
-#[cfg(feature = "serde")]
-fn serialize_uuid<S: serde::Serializer>(uuid: &uuid::Uuid, s: S) -> Result<S::Ok, S::Error> {
-    s.serialize_str(uuid.to_string().as_ref())
-}
-
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum MachineType {
-    /// The contents of this field are assumed to be applicable to any machine type.
-    Unknown,
-    /// Matsushita AM33
-    Am33,
-    /// x64
-    Amd64,
-    /// ARM little endian
-    Arm,
-    /// ARM64 little endian
-    Arm64,
-    /// ARM Thumb-2 little endian
-    ArmNT,
-    /// EFI byte code
-    Ebc,
-    /// Intel 386 or later processors and compatible processors
-    X86,
-    /// Intel Itanium processor family
-    Ia64,
-    /// Mitsubishi M32R little endian
-    M32R,
-    /// MIPS16
-    Mips16,
-    /// MIPS with FPU
-    MipsFpu,
-    /// MIPS16 with FPU
-    MipsFpu16,
-    /// Power PC little endian
-    PowerPC,
-    /// Power PC with floating point support
-    PowerPCFP,
-    /// MIPS little endian
-    R4000,
-    /// RISC-V 32-bit address space
-    RiscV32,
-    /// RISC-V 64-bit address space
-    RiscV64,
-    /// RISC-V 128-bit address space
-    RiscV128,
-    /// Hitachi SH3
-    SH3,
-    /// Hitachi SH3 DSP
-    SH3DSP,
-    /// Hitachi SH4
-    SH4,
-    /// Hitachi SH5
-    SH5,
-    /// Thumb
-    Thumb,
-    /// MIPS little-endian WCE v2
-    WceMipsV2,
-    /// Invalid value
-    Invalid,
-}
-
-impl From<&pdb::MachineType> for MachineType {
-    fn from(machine_type: &pdb::MachineType) -> Self {
+impl FromPdb<&pdb::MachineType> for MachineType {
+    fn from_pdb(machine_type: &pdb::MachineType) -> Self {
         match machine_type {
             pdb::MachineType::Unknown => MachineType::Unknown,
             pdb::MachineType::Am33 => MachineType::Am33,
@@ -144,55 +39,45 @@ impl From<&pdb::MachineType> for MachineType {
             pdb::MachineType::Thumb => MachineType::Thumb,
             pdb::MachineType::WceMipsV2 => MachineType::WceMipsV2,
             pdb::MachineType::Invalid => MachineType::Invalid,
-            other => panic!("unsupported machine type encountered: {:?}", other),
+            // Machine types which the `pdb` crate adds after this was written
+            other => MachineType::Other(*other as u16),
         }
     }
 }
 
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum Version {
-    V41,
-    V50,
-    V60,
-    V70,
-    V110,
-    Other(u32),
-}
-
-impl From<&pdb::HeaderVersion> for Version {
-    fn from(version: &pdb::HeaderVersion) -> Self {
-        match version {
-            pdb::HeaderVersion::V41 => Version::V41,
-            pdb::HeaderVersion::V50 => Version::V50,
-            pdb::HeaderVersion::V60 => Version::V60,
-            pdb::HeaderVersion::V70 => Version::V70,
-            pdb::HeaderVersion::V110 => Version::V110,
-            pdb::HeaderVersion::OtherValue(other) => Version::Other(*other),
-            other => panic!("unsupported PDB version encountered: {:?}", other),
-        }
+/// Converts the `IMAGE_FILE_MACHINE_*` value `value`, including the hybrid
+/// machine types which the `pdb` crate reads as [pdb::MachineType::Unknown]
+pub(crate) fn machine_type_from_raw(value: u16) -> MachineType {
+    match value {
+        0xa641 => MachineType::Arm64EC,
+        0xa64e => MachineType::Arm64X,
+        0x3a64 => MachineType::ChpeX86,
+        _ => match pdb::MachineType::from(value) {
+            pdb::MachineType::Unknown if value != 0 => MachineType::Other(value),
+            machine_type => (&machine_type).into_model(),
+        },
     }
 }
 
-#[derive(Debug, Default)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct AssemblyInfo {
-    pub build_info: Option<BuildInfo>,
-    pub compiler_info: Option<CompilerInfo>,
-}
-
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct BuildInfo {
-    arguments: Vec<String>,
+/// Converts the PDB information stream's raw version `value`. This doesn't go
+/// through [pdb::HeaderVersion], whose variants are non-exhaustive and don't
+/// all keep the raw value.
+#[allow(clippy::inconsistent_digit_grouping)]
+pub(crate) fn version_from_raw(value: u32) -> Version {
+    match value {
+        93_08_03 => Version::V41,
+        1996_03_07 => Version::V50,
+        1997_06_06 => Version::V60,
+        1999_09_03 => Version::V70,
+        2009_12_01 => Version::V110,
+        other => Version::Other(other),
+    }
 }
 
-impl TryFrom<(&pdb::BuildInfoSymbol, Option<&pdb::IdFinder<'_>>)> for BuildInfo {
-    type Error = crate::error::Error;
-
-    fn try_from(
+impl TryFromPdb<(&pdb::BuildInfoSymbol, Option<&pdb::IdFinder<'_>>)> for BuildInfo {
+    fn try_from_pdb(
         info: (&pdb::BuildInfoSymbol, Option<&pdb::IdFinder<'_>>),
-    ) -> Result<Self, Self::Error> {
+    ) -> Result<Self, Error> {
         let (symbol, finder) = info;
         if finder.is_none() {
             return Err(crate::error::Error::MissingDependency("IdFinder"));
@@ -206,46 +91,34 @@ impl TryFrom<(&pdb::BuildInfoSymbol, Option<&pdb::IdFinder<'_>>)> for BuildInfo
             .expect("failed to parse build info");
         match build_info {
             pdb::IdData::BuildInfo(build_info_id) => {
-                let argument_ids: Vec<_> = build_info_id
+                // TODO: Move this out into its own function for ID parsing
+                // Compilers leave arguments they don't record, such as the tool path, as ID 0
+                let arguments: Vec<String> = build_info_id
                     .arguments
                     .iter()
-                    .map(|id| finder.find(*id))
-                    .collect::<Result<Vec<_>, _>>()?;
+                    .map(|&id| {
+                        if id.0 == 0 {
+                            return Ok(String::new());
+                        }
 
-                // TODO: Move this out into its own function for ID parsing
-                let arguments: Vec<String> = argument_ids
-                    .iter()
-                    .map(|id| match id.parse()? {
-                        pdb::IdData::String(s) => {
-                            Ok::<String, Self::Error>(s.name.to_string().into_owned())
+                        match finder.find(id)?.parse()? {
+                            pdb::IdData::String(s) => {
+                                Ok::<String, Error>(s.name.to_string().into_owned())
+                            }
+                            other => panic!("unexpected ID type : {:?}", other),
                         }
-                        other => panic!("unexpected ID type : {:?}", other),
                     })
                     .collect::<Result<Vec<_>, _>>()?;
 
-                return Ok(BuildInfo { arguments });
+                Ok(BuildInfo { arguments })
             }
-            _ => unreachable!(),
-        };
-
-        Err(crate::error::Error::Unsupported("BuildInfo"))
+            _ => Err(crate::error::Error::Unsupported("BuildInfo")),
+        }
     }
 }
 
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct CompilerInfo {
-    // TODO: cpu_type, flags, language
-    pub language: String,
-    pub flags: CompileFlags,
-    pub cpu_type: String,
-    pub frontend_version: CompilerVersion,
-    pub backend_version: CompilerVersion,
-    pub version_string: String,
-}
-
-impl From<pdb::CompileFlagsSymbol<'_>> for CompilerInfo {
-    fn from(flags: pdb::CompileFlagsSymbol<'_>) -> Self {
+impl FromPdb<pdb::CompileFlagsSymbol<'_>> for CompilerInfo {
+    fn from_pdb(flags: pdb::CompileFlagsSymbol<'_>) -> Self {
         let pdb::CompileFlagsSymbol {
             language,
             flags,
@@ -257,46 +130,17 @@ impl From<pdb::CompileFlagsSymbol<'_>> for CompilerInfo {
 
         CompilerInfo {
             language: language.to_string(),
-            flags: flags.into(),
+            flags: flags.into_model(),
             cpu_type: cpu_type.to_string(),
-            frontend_version: frontend_version.into(),
-            backend_version: backend_version.into(),
+            frontend_version: frontend_version.into_model(),
+            backend_version: backend_version.into_model(),
             version_string: version_string.to_string().into_owned(),
         }
     }
 }
 
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct CompileFlags {
-    /// Compiled for edit and continue.
-    pub edit_and_continue: bool,
-    /// Compiled without debugging info.
-    pub no_debug_info: bool,
-    /// Compiled with `LTCG`.
-    pub link_time_codegen: bool,
-    /// Compiled with `/bzalign`.
-    pub no_data_align: bool,
-    /// Managed code or data is present.
-    pub managed: bool,
-    /// Compiled with `/GS`.
-    pub security_checks: bool,
-    /// Compiled with `/hotpatch`.
-    pub hot_patch: bool,
-    /// Compiled with `CvtCIL`.
-    pub cvtcil: bool,
-    /// This is a MSIL .NET Module.
-    pub msil_module: bool,
-    /// Compiled with `/sdl`.
-    pub sdl: bool,
-    /// Compiled with `/ltcg:pgo` or `pgo:`.
-    pub pgo: bool,
-    /// This is a .exp module.
-    pub exp_module: bool,
-}
-
-impl From<pdb::CompileFlags> for CompileFlags {
-    fn from(flags: pdb::CompileFlags) -> Self {
+impl FromPdb<pdb::CompileFlags> for CompileFlags {
+    fn from_pdb(flags: pdb::CompileFlags) -> Self {
         let pdb::CompileFlags {
             edit_and_continue,
             no_debug_info,
@@ -330,17 +174,8 @@ impl From<pdb::CompileFlags> for CompileFlags {
     }
 }
 
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct CompilerVersion {
-    pub major: u16,
-    pub minor: u16,
-    pub build: u16,
-    pub qfe: Option<u16>,
-}
-
-impl From<pdb::CompilerVersion> for CompilerVersion {
-    fn from(version: pdb::CompilerVersion) -> Self {
+impl FromPdb<pdb::CompilerVersion> for CompilerVersion {
+    fn from_pdb(version: pdb::CompilerVersion) -> Self {
         let pdb::CompilerVersion {
             major,
             minor,
@@ -357,25 +192,8 @@ impl From<pdb::CompilerVersion> for CompilerVersion {
     }
 }
 
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct DebugModule {
-    name: String,
-    object_file_name: String,
-    source_files: Option<Vec<FileInfo>>,
-}
-
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-enum Checksum {
-    None,
-    Md5(Vec<u8>),
-    Sha1(Vec<u8>),
-    Sha256(Vec<u8>),
-}
-
-impl From<pdb::FileChecksum<'_>> for Checksum {
-    fn from(checksum: pdb::FileChecksum<'_>) -> Self {
+impl FromPdb<pdb::FileChecksum<'_>> for Checksum {
+    fn from_pdb(checksum: pdb::FileChecksum<'_>) -> Self {
         match checksum {
             pdb::FileChecksum::None => Checksum::None,
             pdb::FileChecksum::Md5(data) => Checksum::Md5(data.to_vec()),
@@ -385,21 +203,14 @@ impl From<pdb::FileChecksum<'_>> for Checksum {
     }
 }
 
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct FileInfo {
-    name: String,
-    checksum: Checksum,
-}
-
 impl
-    From<(
+    FromPdb<(
         &pdb::Module<'_>,
         Option<&pdb::ModuleInfo<'_>>,
         Option<&pdb::StringTable<'_>>,
     )> for DebugModule
 {
-    fn from(
+    fn from_pdb(
         data: (
             &pdb::Module<'_>,
             Option<&pdb::ModuleInfo<'_>>,
@@ -422,7 +233,7 @@ impl
 
                                 Ok(FileInfo {
                                     name: file_name,
-                                    checksum: f.checksum.into(),
+                                    checksum: f.checksum.into_model(),
                                 })
                             })
                             .collect()
@@ -432,27 +243,40 @@ impl
             })
             .flatten();
 
+        let name = module.module_name().to_string();
+        let object_file_name = module.object_file_name().to_string();
+        // Modules linked from a library record the library as their object file
+        let library = Some(object_file_name.clone())
+            .filter(|file| *file != name && file.to_ascii_lowercase().ends_with(".lib"));
+
         DebugModule {
-            name: module.module_name().to_string(),
-            object_file_name: module.object_file_name().to_string(),
+            name,
+            object_file_name,
+            library,
             source_files,
+            symbol_count: 0,
+            type_count: 0,
+            contribution_size: 0,
+            compiler_info: None,
+            build_info: None,
+            environment: vec![],
         }
     }
 }
 
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct PublicSymbol {
-    pub name: String,
-    pub is_code: bool,
-    pub is_function: bool,
-    pub is_managed: bool,
-    pub is_msil: bool,
-    pub offset: Option<usize>,
+/// Converts a section offset to an [Address], if it can be mapped to an RVA
+fn to_address(
+    offset: pdb::PdbInternalSectionOffset,
+    base_address: Option<usize>,
+    address_map: &AddressMapping<'_>,
+) -> Option<Address> {
+    address_map
+        .to_rva(offset)
+        .map(|rva| Address::new(rva as usize, base_address))
 }
 
-impl From<(pdb::PublicSymbol<'_>, usize, Option<&pdb::AddressMap<'_>>)> for PublicSymbol {
-    fn from(data: (pdb::PublicSymbol<'_>, usize, Option<&pdb::AddressMap<'_>>)) -> Self {
+impl FromPdb<(pdb::PublicSymbol<'_>, Option<usize>, &AddressMapping<'_>)> for PublicSymbol {
+    fn from_pdb(data: (pdb::PublicSymbol<'_>, Option<usize>, &AddressMapping<'_>)) -> Self {
         let (sym, base_address, address_map) = data;
 
         let pdb::PublicSymbol {
@@ -471,11 +295,7 @@ impl From<(pdb::PublicSymbol<'_>, usize, Option<&pdb::AddressMap<'_>>)> for Publ
             )
         }
 
-        let offset = address_map.and_then(|address_map| {
-            offset
-                .to_rva(address_map)
-                .map(|rva| u32::from(rva) as usize + base_address)
-        });
+        let offset = to_address(offset, base_address, address_map);
 
         PublicSymbol {
             name: name.to_string().to_string(),
@@ -484,42 +304,28 @@ impl From<(pdb::PublicSymbol<'_>, usize, Option<&pdb::AddressMap<'_>>)> for Publ
             is_managed: managed,
             is_msil: msil,
             offset,
+            procedure_name: None,
+            isa: None,
         }
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Data {
-    pub name: String,
-
-    pub is_global: bool,
-
-    pub is_managed: bool,
-
-    pub ty: TypeRef,
-
-    pub offset: Option<usize>,
-}
-
 impl
-    TryFrom<(
+    TryFromPdb<(
         pdb::DataSymbol<'_>,
-        usize,
-        Option<&pdb::AddressMap<'_>>,
-        &HashMap<TypeIndexNumber, TypeRef>,
+        Option<usize>,
+        &AddressMapping<'_>,
+        &HashMap<TypeIndexNumber, TypeId>,
     )> for Data
 {
-    type Error = crate::error::Error;
-
-    fn try_from(
+    fn try_from_pdb(
         data: (
             pdb::DataSymbol<'_>,
-            usize,
-            Option<&pdb::AddressMap<'_>>,
-            &HashMap<TypeIndexNumber, TypeRef>,
+            Option<usize>,
+            &AddressMapping<'_>,
+            &HashMap<TypeIndexNumber, TypeId>,
         ),
-    ) -> Result<Self, Self::Error> {
+    ) -> Result<Self, Error> {
         let (sym, base_address, address_map, parsed_types) = data;
 
         let pdb::DataSymbol {
@@ -530,17 +336,11 @@ impl
             name,
         } = sym;
 
-        let offset = address_map.and_then(|address_map| {
-            offset
-                .to_rva(address_map)
-                .map(|rva| u32::from(rva) as usize + base_address)
-        });
+        let offset = to_address(offset, base_address, address_map);
 
-        let ty = Rc::clone(
-            parsed_types
-                .get(&type_index.0)
-                .ok_or(Self::Error::UnresolvedType(type_index.0))?,
-        );
+        let ty = *parsed_types
+            .get(&type_index.0)
+            .ok_or(Error::UnresolvedType(type_index.0))?;
 
         let data = Data {
             name: name.to_string().to_string(),
@@ -548,45 +348,29 @@ impl
             is_managed: managed,
             ty,
             offset,
+            module: None,
+            function: None,
+            folded_with: vec![],
+            storage: None,
         };
 
         Ok(data)
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Procedure {
-    pub name: String,
-
-    pub signature: Option<String>,
-    pub type_index: TypeIndexNumber,
-
-    /// This reflects the RVA in the transformed address space. See [PdbInternalSectionOffset docs](https://docs.rs/pdb/latest/pdb/struct.PdbInternalSectionOffset.html)
-    /// for more details.
-    pub address: Option<usize>,
-    pub len: usize,
-
-    pub is_global: bool,
-    pub is_dpc: bool,
-    /// length of this procedure in BYTES
-    pub prologue_end: usize,
-    pub epilogue_start: usize,
-}
-
 impl
-    From<(
+    FromPdb<(
         pdb::ProcedureSymbol<'_>,
-        usize,
-        Option<&pdb::AddressMap<'_>>,
+        Option<usize>,
+        &AddressMapping<'_>,
         &pdb::ItemFinder<'_, pdb::TypeIndex>,
     )> for Procedure
 {
-    fn from(
+    fn from_pdb(
         data: (
             pdb::ProcedureSymbol<'_>,
-            usize,
-            Option<&pdb::AddressMap<'_>>,
+            Option<usize>,
+            &AddressMapping<'_>,
             &pdb::ItemFinder<'_, pdb::TypeIndex>,
         ),
     ) -> Self {
@@ -595,16 +379,14 @@ impl
         let pdb::ProcedureSymbol {
             global,
             dpc,
-            parent,
-            end,
-            next,
             len,
             dbg_start_offset,
             dbg_end_offset,
             type_index,
             offset,
-            flags,
             name,
+            flags,
+            ..
         } = sym;
 
         if offset.section == 0 {
@@ -614,11 +396,7 @@ impl
             )
         }
 
-        let address = address_map.and_then(|address_map| {
-            offset
-                .to_rva(address_map)
-                .map(|rva| u32::from(rva) as usize + base_address)
-        });
+        let address = to_address(offset, base_address, address_map);
 
         let signature = type_finder.find(type_index).ok().map(|type_info| {
             format!(
@@ -637,6 +415,496 @@ impl
             is_dpc: dpc,
             prologue_end: dbg_start_offset as usize,
             epilogue_start: dbg_end_offset as usize,
+            public_name: None,
+            parameter_names: vec![],
+            module_index: None,
+            origin: CodeOrigin::User,
+            flags: ProcedureFlags {
+                no_return: flags.never,
+                unreachable: flags.notreached,
+                no_inline: flags.noinline,
+                custom_calling_convention: flags.cust_call,
+                frame_pointer: flags.nofpo,
+                interrupt_return: flags.int,
+                far_return: flags.far,
+                optimized_debug_info: flags.optdbginfo,
+                ..Default::default()
+            },
+            source: None,
+            folded_with: vec![],
+            isa: None,
+        }
+    }
+}
+
+/// Symbol kind of `S_UDT` records
+pub(crate) const S_UDT: u16 = 0x1108;
+
+/// Symbol kind of `S_FRAMEPROC` records, which the `pdb` crate doesn't parse
+pub(crate) const S_FRAMEPROC: u16 = 0x1012;
+
+const FRAME_HAS_EH: u32 = 0x10;
+const FRAME_HAS_SEH: u32 = 0x40;
+const FRAME_NAKED: u32 = 0x80;
+
+/// Reads the flags of the `S_FRAMEPROC` record `sym` into those of the
+/// procedure it belongs to
+pub(crate) fn read_frame_flags(
+    sym: &pdb::Symbol<'_>,
+    flags: &mut ProcedureFlags,
+) -> Result<(), Error> {
+    let mut raw = symbol_reader(sym);
+    let _frame_size = raw.u32()?;
+    let _padding_size = raw.u32()?;
+    let _padding_offset = raw.u32()?;
+    let _saved_registers_size = raw.u32()?;
+    let _exception_handler_offset = raw.u32()?;
+    let _exception_handler_section = raw.u16()?;
+    let frame_flags = raw.u32()?;
+
+    flags.has_eh = frame_flags & FRAME_HAS_EH != 0;
+    flags.has_seh = frame_flags & FRAME_HAS_SEH != 0;
+    flags.naked = frame_flags & FRAME_NAKED != 0;
+
+    Ok(())
+}
+
+/// Symbol kind of `S_ENVBLOCK` records, which the `pdb` crate doesn't parse
+pub(crate) const S_ENVBLOCK: u16 = 0x113d;
+
+/// Reads the entries of the `S_ENVBLOCK` record `sym`, which are pairs of
+/// strings ending with an empty one
+pub(crate) fn read_environment_block(
+    sym: &pdb::Symbol<'_>,
+) -> Result<Vec<EnvironmentEntry>, Error> {
+    let mut raw = symbol_reader(sym);
+    let _flags = raw.u8()?;
+
+    let mut entries = vec![];
+    while raw.remaining() > 0 {
+        let key = raw.cstring()?;
+        if key.is_empty() {
+            break;
+        }
+        let value = raw.cstring()?;
+        entries.push(EnvironmentEntry { key, value });
+    }
+
+    Ok(entries)
+}
+
+/// Symbol kind of `S_ANNOTATION` records, which the `pdb` crate doesn't parse
+pub(crate) const S_ANNOTATION: u16 = 0x1019;
+
+impl TryFromPdb<(&pdb::Symbol<'_>, usize, Option<usize>, &AddressMapping<'_>)> for Annotation {
+    fn try_from_pdb(
+        data: (&pdb::Symbol<'_>, usize, Option<usize>, &AddressMapping<'_>),
+    ) -> Result<Self, Error> {
+        let (sym, module_index, base_address, address_map) = data;
+
+        let mut raw = symbol_reader(sym);
+        let offset = raw.u32()?;
+        let section = raw.u16()?;
+        let count = raw.u16()?;
+        let strings = (0..count)
+            .map(|_| raw.cstring())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let offset = pdb::PdbInternalSectionOffset::new(section, offset);
+        if offset.section == 0 {
+            warn!(
+                "symbol type has an invalid section index and RVA will be invalid: {:?}",
+                sym
+            )
+        }
+
+        let offset = to_address(offset, base_address, address_map);
+
+        Ok(Annotation {
+            offset,
+            strings,
+            module_index,
+            symbol_index: sym.index().0,
+        })
+    }
+}
+
+impl FromPdb<pdb::AnnotationReferenceSymbol<'_>> for AnnotationReference {
+    fn from_pdb(sym: pdb::AnnotationReferenceSymbol<'_>) -> Self {
+        AnnotationReference {
+            name: sym.name.to_string().to_string(),
+            module_index: sym.module,
+            symbol_index: sym.symbol_index.0,
+        }
+    }
+}
+
+impl FromPdb<pdb::ExportSymbol<'_>> for Export {
+    fn from_pdb(sym: pdb::ExportSymbol<'_>) -> Self {
+        Export {
+            name: sym.name.to_string().to_string(),
+            ordinal: sym.ordinal,
+            is_data: sym.flags.data,
+            is_constant: sym.flags.constant,
+            is_private: sym.flags.private,
+            is_ordinal_only: sym.flags.no_name,
+            is_forwarder: sym.flags.forwarder,
+        }
+    }
+}
+
+impl FromPdb<pdb::TrampolineType> for TrampolineKind {
+    fn from_pdb(kind: pdb::TrampolineType) -> Self {
+        match kind {
+            pdb::TrampolineType::Incremental => TrampolineKind::Incremental,
+            pdb::TrampolineType::BranchIsland => TrampolineKind::BranchIsland,
+            _ => TrampolineKind::Unknown,
+        }
+    }
+}
+
+impl FromPdb<(pdb::TrampolineSymbol, Option<usize>, &AddressMapping<'_>)> for Trampoline {
+    fn from_pdb(data: (pdb::TrampolineSymbol, Option<usize>, &AddressMapping<'_>)) -> Self {
+        let (sym, base_address, address_map) = data;
+
+        let pdb::TrampolineSymbol {
+            tramp_type,
+            size,
+            thunk,
+            target,
+        } = sym;
+
+        Trampoline {
+            kind: tramp_type.into_model(),
+            offset: to_address(thunk, base_address, address_map),
+            size: size as usize,
+            target: to_address(target, base_address, address_map),
         }
     }
 }
+
+impl FromPdb<(pdb::SeparatedCodeSymbol, Option<usize>, &AddressMapping<'_>)> for SeparatedCode {
+    fn from_pdb(data: (pdb::SeparatedCodeSymbol, Option<usize>, &AddressMapping<'_>)) -> Self {
+        let (sym, base_address, address_map) = data;
+
+        if sym.offset.section == 0 {
+            warn!(
+                "symbol type has an invalid section index and RVA will be invalid: {:?}",
+                sym
+            )
+        }
+
+        SeparatedCode {
+            offset: to_address(sym.offset, base_address, address_map),
+            len: sym.len as usize,
+            parent_offset: to_address(sym.parent_offset, base_address, address_map),
+            is_lexical_scope: sym.flags.islexicalscope,
+            returns_to_parent: sym.flags.returnstoparent,
+        }
+    }
+}
+
+/// Symbol kind of `S_HEAPALLOCSITE` records, which the `pdb` crate doesn't parse
+pub(crate) const S_HEAPALLOCSITE: u16 = 0x115e;
+
+impl
+    TryFromPdb<(
+        &pdb::Symbol<'_>,
+        Option<usize>,
+        &AddressMapping<'_>,
+        &HashMap<TypeIndexNumber, TypeId>,
+    )> for HeapAllocationSite
+{
+    fn try_from_pdb(
+        data: (
+            &pdb::Symbol<'_>,
+            Option<usize>,
+            &AddressMapping<'_>,
+            &HashMap<TypeIndexNumber, TypeId>,
+        ),
+    ) -> Result<Self, Error> {
+        let (sym, base_address, address_map, parsed_types) = data;
+
+        let mut raw = symbol_reader(sym);
+        let offset = raw.u32()?;
+        let section = raw.u16()?;
+        let call_len = raw.u16()? as usize;
+        let type_index = raw.u32()?;
+
+        let offset = pdb::PdbInternalSectionOffset::new(section, offset);
+        if offset.section == 0 {
+            warn!(
+                "symbol type has an invalid section index and RVA will be invalid: {:?}",
+                sym
+            )
+        }
+
+        let offset = to_address(offset, base_address, address_map);
+
+        let ty = *parsed_types
+            .get(&type_index)
+            .ok_or(Error::UnresolvedType(type_index))?;
+
+        Ok(HeapAllocationSite {
+            offset,
+            call_len,
+            ty,
+        })
+    }
+}
+
+/// Symbol kind of `S_REF_MINIPDB` records, which the `pdb` crate doesn't parse
+pub(crate) const S_REF_MINIPDB: u16 = 0x1160;
+
+const MINIPDB_LOCAL: u16 = 0x1;
+const MINIPDB_DATA: u16 = 0x2;
+const MINIPDB_UDT: u16 = 0x4;
+const MINIPDB_LABEL: u16 = 0x8;
+const MINIPDB_CONSTANT: u16 = 0x10;
+
+impl TryFromPdb<&pdb::Symbol<'_>> for FastlinkReference {
+    fn try_from_pdb(sym: &pdb::Symbol<'_>) -> Result<Self, Error> {
+        let mut raw = symbol_reader(sym);
+        // A COFF section index, or a type index for user-defined types
+        let _data = raw.u32()?;
+        let module = raw.u16()?;
+        let flags = raw.u16()?;
+        let name = raw.cstring()?;
+
+        let kind = if flags & MINIPDB_UDT != 0 {
+            FastlinkReferenceKind::Type
+        } else if flags & MINIPDB_DATA != 0 {
+            FastlinkReferenceKind::Data
+        } else if flags & MINIPDB_LABEL != 0 {
+            FastlinkReferenceKind::Label
+        } else if flags & MINIPDB_CONSTANT != 0 {
+            FastlinkReferenceKind::Constant
+        } else {
+            FastlinkReferenceKind::Function
+        };
+
+        Ok(FastlinkReference {
+            name,
+            kind,
+            // Module indices in references are 1-based
+            module_index: (module as usize).checked_sub(1),
+            is_local: flags & MINIPDB_LOCAL != 0,
+        })
+    }
+}
+
+/// Symbol kind of `S_GMANPROC` records, which the `pdb` crate doesn't parse
+pub(crate) const S_GMANPROC: u16 = 0x112a;
+
+/// Symbol kind of `S_LMANPROC` records, which the `pdb` crate doesn't parse
+pub(crate) const S_LMANPROC: u16 = 0x112b;
+
+impl
+    TryFromPdb<(
+        &pdb::Symbol<'_>,
+        Option<usize>,
+        Option<usize>,
+        &AddressMapping<'_>,
+    )> for ManagedProcedure
+{
+    fn try_from_pdb(
+        data: (
+            &pdb::Symbol<'_>,
+            Option<usize>,
+            Option<usize>,
+            &AddressMapping<'_>,
+        ),
+    ) -> Result<Self, Error> {
+        let (sym, module_index, base_address, address_map) = data;
+
+        let mut raw = symbol_reader(sym);
+        let _parent = raw.u32()?;
+        let _end = raw.u32()?;
+        let _next = raw.u32()?;
+        let len = raw.u32()?;
+        let _debug_start = raw.u32()?;
+        let _debug_end = raw.u32()?;
+        let token = raw.u32()?;
+        let offset = raw.u32()?;
+        let section = raw.u16()?;
+        let _flags = raw.u8()?;
+        let _return_register = raw.u16()?;
+        let name = raw.cstring()?;
+
+        let address = to_address(
+            pdb::PdbInternalSectionOffset::new(section, offset),
+            base_address,
+            address_map,
+        );
+
+        Ok(ManagedProcedure {
+            name,
+            token,
+            address,
+            len: len as usize,
+            is_global: sym.raw_kind() == S_GMANPROC,
+            module_index,
+        })
+    }
+}
+
+/// Symbol kind of `S_TOKENREF` records, which the `pdb` crate doesn't parse
+pub(crate) const S_TOKENREF: u16 = 0x1129;
+
+impl TryFromPdb<&pdb::Symbol<'_>> for TokenReference {
+    fn try_from_pdb(sym: &pdb::Symbol<'_>) -> Result<Self, Error> {
+        let mut raw = symbol_reader(sym);
+        let _name_checksum = raw.u32()?;
+        let symbol_offset = raw.u32()?;
+        let module = raw.u16()?;
+        let name = raw.cstring()?;
+
+        Ok(TokenReference {
+            name,
+            // Module indices in references are 1-based
+            module_index: (module as usize).checked_sub(1),
+            symbol_offset,
+        })
+    }
+}
+
+/// Symbol kind of `S_SECTION` records, which the `pdb` crate doesn't parse
+pub(crate) const S_SECTION: u16 = 0x1136;
+
+impl TryFromPdb<(&pdb::Symbol<'_>, Option<usize>)> for Section {
+    fn try_from_pdb(data: (&pdb::Symbol<'_>, Option<usize>)) -> Result<Self, Error> {
+        let (sym, base_address) = data;
+
+        let mut raw = symbol_reader(sym);
+        let index = raw.u16()?;
+        // The alignment is stored as a power of two
+        let alignment = 1usize << raw.u8()?;
+        let _reserved = raw.u8()?;
+        let rva = raw.u32()?;
+        let len = raw.u32()?;
+        let characteristics = raw.u32()?;
+        let name = raw.cstring()?;
+
+        Ok(Section {
+            name,
+            index,
+            offset: Address::new(rva as usize, base_address),
+            len: len as usize,
+            alignment,
+            characteristics,
+        })
+    }
+}
+
+/// Symbol kind of `S_COFFGROUP` records, which the `pdb` crate doesn't parse
+pub(crate) const S_COFFGROUP: u16 = 0x1137;
+
+impl TryFromPdb<(&pdb::Symbol<'_>, Option<usize>, &AddressMapping<'_>)> for CoffGroup {
+    fn try_from_pdb(
+        data: (&pdb::Symbol<'_>, Option<usize>, &AddressMapping<'_>),
+    ) -> Result<Self, Error> {
+        let (sym, base_address, address_map) = data;
+
+        let mut raw = symbol_reader(sym);
+        let len = raw.u32()?;
+        let characteristics = raw.u32()?;
+        let offset = raw.u32()?;
+        let section = raw.u16()?;
+        let name = raw.cstring()?;
+
+        let offset = to_address(
+            pdb::PdbInternalSectionOffset::new(section, offset),
+            base_address,
+            address_map,
+        );
+
+        Ok(CoffGroup {
+            name,
+            offset,
+            len: len as usize,
+            characteristics,
+        })
+    }
+}
+
+/// Symbol kind of `S_CALLSITEINFO` records, which the `pdb` crate doesn't parse
+pub(crate) const S_CALLSITEINFO: u16 = 0x1139;
+
+impl
+    TryFromPdb<(
+        &pdb::Symbol<'_>,
+        Option<usize>,
+        &AddressMapping<'_>,
+        &HashMap<TypeIndexNumber, TypeId>,
+        Option<&str>,
+    )> for CallSite
+{
+    fn try_from_pdb(
+        data: (
+            &pdb::Symbol<'_>,
+            Option<usize>,
+            &AddressMapping<'_>,
+            &HashMap<TypeIndexNumber, TypeId>,
+            Option<&str>,
+        ),
+    ) -> Result<Self, Error> {
+        let (sym, base_address, address_map, parsed_types, caller) = data;
+
+        let mut raw = symbol_reader(sym);
+        let offset = raw.u32()?;
+        let section = raw.u16()?;
+        let _padding = raw.u16()?;
+        let type_index = raw.u32()?;
+
+        let offset = to_address(
+            pdb::PdbInternalSectionOffset::new(section, offset),
+            base_address,
+            address_map,
+        );
+
+        Ok(CallSite {
+            offset,
+            ty: parsed_types.get(&type_index).copied(),
+            caller: caller.map(str::to_string),
+        })
+    }
+}
+
+/// Symbol kind of `S_CALLEES` records, which the `pdb` crate doesn't parse
+pub(crate) const S_CALLEES: u16 = 0x115a;
+/// Symbol kind of `S_CALLERS` records, which the `pdb` crate doesn't parse
+pub(crate) const S_CALLERS: u16 = 0x115b;
+
+/// Parses the functions listed by an `S_CALLERS` or `S_CALLEES` record along
+/// with the number of times each was invoked, if recorded
+pub(crate) fn parse_function_list(
+    sym: &pdb::Symbol<'_>,
+    id_finder: Option<&pdb::IdFinder<'_>>,
+) -> Result<Vec<(String, Option<u32>)>, Error> {
+    let id_finder = id_finder.ok_or(Error::MissingDependency("IdFinder"))?;
+
+    let mut raw = symbol_reader(sym);
+    let count = raw.u32()?;
+    let ids = (0..count)
+        .map(|_| raw.u32())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Invocation counts are only present when the binary was built with profile data
+    let invocations: Vec<Option<u32>> = (0..count).map(|_| raw.u32().ok()).collect();
+
+    let mut functions = Vec::with_capacity(ids.len());
+    for (id, invocations) in ids.into_iter().zip(invocations) {
+        let name = match id_finder.find(pdb::IdIndex(id))?.parse()? {
+            pdb::IdData::Function(function) => function.name,
+            pdb::IdData::MemberFunction(function) => function.name,
+            other => {
+                warn!("unexpected ID in function list: {:?}", other);
+                continue;
+            }
+        };
+
+        functions.push((name.to_string().into_owned(), invocations));
+    }
+
+    Ok(functions)
+}
@@ -0,0 +1,569 @@
+//! Generating PDBs of any size in memory, so that parsing performance can be
+//! measured reproducibly without large real-world PDBs. The PDBs are shaped
+//! like those MSVC produces, but only use a handful of record kinds: each type
+//! is a struct with a pointer to the previous one, and each module holds a run
+//! of procedures sharing a signature. There's no line information.
+use crate::gsi;
+use crate::msf;
+use crate::raw::TPI_HEADER_SIZE;
+
+/// The number of each kind of item a synthetic PDB holds
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SyntheticOptions {
+    /// Number of structs, each of which adds three type records
+    pub types: usize,
+    /// Number of procedures, each of which has a public symbol
+    pub procedures: usize,
+    /// Number of global variables
+    pub globals: usize,
+    /// Number of modules the procedures are split between. At least one module
+    /// is always written.
+    pub modules: usize,
+}
+
+impl SyntheticOptions {
+    /// Returns options for a PDB of `n` types and procedures, with a quarter as
+    /// many globals and a module for every thousand procedures
+    pub fn scaled(n: usize) -> Self {
+        SyntheticOptions {
+            types: n,
+            procedures: n,
+            globals: n / 4,
+            modules: n.div_ceil(1000).max(1),
+        }
+    }
+}
+
+const FIRST_TYPE_INDEX: u32 = 0x1000;
+const TEXT_RVA: u32 = 0x1000;
+const PROCEDURE_SIZE: u32 = 0x20;
+/// Size of each struct: an `int32_t`, a `float`, and a pointer
+const STRUCT_SIZE: u32 = 0x10;
+
+/// Streams following the fixed PDB information, TPI, DBI, and IPI streams
+const GLOBALS_STREAM: u16 = 5;
+const PUBLICS_STREAM: u16 = 6;
+const SYMBOL_RECORDS_STREAM: u16 = 7;
+const SECTION_HEADERS_STREAM: u16 = 8;
+const FIRST_MODULE_STREAM: u16 = 9;
+
+const S_END: u16 = 0x0006;
+const S_OBJNAME: u16 = 0x1101;
+const S_GDATA32: u16 = 0x110d;
+const S_PUB32: u16 = 0x110e;
+const S_GPROC32: u16 = 0x1110;
+
+const LF_POINTER: u16 = 0x1002;
+const LF_PROCEDURE: u16 = 0x1008;
+const LF_ARGLIST: u16 = 0x1201;
+const LF_FIELDLIST: u16 = 0x1203;
+const LF_MEMBER: u16 = 0x150d;
+const LF_STRUCTURE: u16 = 0x1505;
+
+const T_INT4: u32 = 0x74;
+const T_REAL32: u32 = 0x40;
+const T_64PVOID: u32 = 0x603;
+
+/// Attributes of a 64-bit pointer: its kind and its size of 8 bytes
+const POINTER_64_ATTRIBUTES: u32 = 0xc | (8 << 13);
+/// Public symbol flag marking code
+const PUBLIC_FUNCTION: u32 = 0x2;
+/// Characteristics of the .text section: readable and executable code
+const TEXT_CHARACTERISTICS: u32 = 0x6000_0020;
+/// Characteristics of the .data section: readable and writable initialized data
+const DATA_CHARACTERISTICS: u32 = 0xc000_0040;
+
+/// Returns a synthetic PDB of the items described by `options`, as the bytes
+/// of an MSF 7.00 file
+pub fn synthetic_pdb(options: &SyntheticOptions) -> Vec<u8> {
+    let module_count = options.modules.max(1);
+    let text_size = options.procedures as u32 * PROCEDURE_SIZE;
+    let data_rva = TEXT_RVA + text_size.div_ceil(0x1000).max(1) * 0x1000;
+    let data_size = options.globals as u32 * STRUCT_SIZE;
+
+    let (symbol_records, globals, publics) = global_symbols(options);
+    let modules: Vec<Vec<u8>> = (0..module_count)
+        .map(|module| module_symbols(options, module, module_count))
+        .collect();
+
+    // The PDB information, TPI, DBI, and IPI streams are at their fixed
+    // indices, followed by those the DBI stream refers to
+    let mut streams = vec![
+        // The old stream directory
+        Some(vec![]),
+        Some(pdb_information()),
+        Some(type_stream(&type_records(options))),
+        Some(debug_information(options, &modules)),
+        Some(type_stream(&[])),
+        Some(gsi::write_hash_table(&borrow_names(&globals))),
+        Some(public_symbols(&borrow_names(&publics))),
+        Some(symbol_records),
+        Some(section_headers(&[
+            (b".text", TEXT_RVA, text_size, TEXT_CHARACTERISTICS),
+            (b".data", data_rva, data_size, DATA_CHARACTERISTICS),
+        ])),
+    ];
+    debug_assert_eq!(streams.len(), usize::from(FIRST_MODULE_STREAM));
+    streams.extend(modules.into_iter().map(Some));
+
+    msf::write_big_msf(&streams)
+}
+
+/// Returns the name of struct `index`
+fn struct_name(index: usize) -> String {
+    format!("Struct{}", index)
+}
+
+/// Returns the range of procedures held by `module`
+fn module_procedures(
+    options: &SyntheticOptions,
+    module: usize,
+    module_count: usize,
+) -> std::ops::Range<usize> {
+    let per_module = options.procedures.div_ceil(module_count);
+    let start = (module * per_module).min(options.procedures);
+    let end = (start + per_module).min(options.procedures);
+
+    start..end
+}
+
+/// Returns the type index of the pointer to struct `index`
+fn pointer_type(index: usize) -> u32 {
+    FIRST_TYPE_INDEX + index as u32 * 3 + 2
+}
+
+/// Returns the type index of the signature every procedure shares,
+/// `int32_t (Struct0*)`
+fn procedure_type(options: &SyntheticOptions) -> u32 {
+    FIRST_TYPE_INDEX + options.types as u32 * 3 + 1
+}
+
+/// Returns the type records: a field list, struct, and pointer for each struct,
+/// then the procedures' argument list and signature
+fn type_records(options: &SyntheticOptions) -> Vec<Vec<u8>> {
+    let mut records = vec![];
+    for index in 0..options.types {
+        let previous = match index {
+            0 => T_64PVOID,
+            _ => pointer_type(index - 1),
+        };
+
+        let mut fields = vec![];
+        for (ty, offset, name) in [
+            (T_INT4, 0, "id"),
+            (T_REAL32, 4, "value"),
+            (previous, 8, "previous"),
+        ] {
+            let start = fields.len();
+            fields.extend_from_slice(&LF_MEMBER.to_le_bytes());
+            // Public access
+            fields.extend_from_slice(&3u16.to_le_bytes());
+            fields.extend_from_slice(&ty.to_le_bytes());
+            fields.extend_from_slice(&(offset as u16).to_le_bytes());
+            push_name(&mut fields, name);
+            pad_type_record(&mut fields, start);
+        }
+        records.push(type_record(LF_FIELDLIST, fields));
+
+        let mut structure = vec![];
+        // Member count, properties, field list, derived classes, and vtable shape
+        structure.extend_from_slice(&3u16.to_le_bytes());
+        structure.extend_from_slice(&0u16.to_le_bytes());
+        for value in [FIRST_TYPE_INDEX + index as u32 * 3, 0, 0] {
+            structure.extend_from_slice(&value.to_le_bytes());
+        }
+        structure.extend_from_slice(&(STRUCT_SIZE as u16).to_le_bytes());
+        push_name(&mut structure, &struct_name(index));
+        records.push(type_record(LF_STRUCTURE, structure));
+
+        let mut pointer = vec![];
+        pointer.extend_from_slice(&(FIRST_TYPE_INDEX + index as u32 * 3 + 1).to_le_bytes());
+        pointer.extend_from_slice(&POINTER_64_ATTRIBUTES.to_le_bytes());
+        records.push(type_record(LF_POINTER, pointer));
+    }
+
+    let argument = match options.types {
+        0 => T_64PVOID,
+        _ => pointer_type(0),
+    };
+    let mut arguments = vec![];
+    arguments.extend_from_slice(&1u32.to_le_bytes());
+    arguments.extend_from_slice(&argument.to_le_bytes());
+    records.push(type_record(LF_ARGLIST, arguments));
+
+    let mut procedure = vec![];
+    procedure.extend_from_slice(&T_INT4.to_le_bytes());
+    // Calling convention, attributes, and parameter count
+    procedure.extend_from_slice(&[0, 0]);
+    procedure.extend_from_slice(&1u16.to_le_bytes());
+    procedure.extend_from_slice(&(procedure_type(options) - 1).to_le_bytes());
+    records.push(type_record(LF_PROCEDURE, procedure));
+
+    records
+}
+
+/// The offsets of symbol records along with the names of their symbols
+type NamedRecords = Vec<(u32, String)>;
+
+/// Returns the symbol records stream, along with the offset and name of each
+/// global and public symbol in it. Publics are in order of address.
+fn global_symbols(options: &SyntheticOptions) -> (Vec<u8>, NamedRecords, NamedRecords) {
+    let mut records = vec![];
+
+    let mut globals = vec![];
+    for index in 0..options.globals {
+        let ty = match options.types {
+            0 => T_INT4,
+            types => FIRST_TYPE_INDEX + (index % types) as u32 * 3 + 1,
+        };
+        let mut data = vec![];
+        data.extend_from_slice(&ty.to_le_bytes());
+        data.extend_from_slice(&(index as u32 * STRUCT_SIZE).to_le_bytes());
+        // The .data section
+        data.extend_from_slice(&2u16.to_le_bytes());
+        let name = global_name(index);
+        push_name(&mut data, &name);
+        globals.push((records.len() as u32, name));
+        push_symbol(&mut records, S_GDATA32, data);
+    }
+
+    let mut publics = vec![];
+    for index in 0..options.procedures {
+        let mut public = vec![];
+        public.extend_from_slice(&PUBLIC_FUNCTION.to_le_bytes());
+        public.extend_from_slice(&(index as u32 * PROCEDURE_SIZE).to_le_bytes());
+        // The .text section
+        public.extend_from_slice(&1u16.to_le_bytes());
+        let name = procedure_name(index);
+        push_name(&mut public, &name);
+        publics.push((records.len() as u32, name));
+        push_symbol(&mut records, S_PUB32, public);
+    }
+
+    (records, globals, publics)
+}
+
+/// Returns the symbol stream of `module`: its object file name, then each of
+/// its procedures
+fn module_symbols(options: &SyntheticOptions, module: usize, module_count: usize) -> Vec<u8> {
+    // The CodeView signature
+    let mut symbols = 4u32.to_le_bytes().to_vec();
+
+    let mut object_name = 0u32.to_le_bytes().to_vec();
+    push_name(&mut object_name, &module_name(module));
+    push_symbol(&mut symbols, S_OBJNAME, object_name);
+
+    for index in module_procedures(options, module, module_count) {
+        let start = symbols.len();
+        let mut procedure = vec![];
+        // Parent, end (patched below), and next
+        procedure.extend_from_slice(&[0; 12]);
+        // Length, debug start and end, type, and offset
+        for value in [
+            PROCEDURE_SIZE,
+            0,
+            PROCEDURE_SIZE,
+            procedure_type(options),
+            index as u32 * PROCEDURE_SIZE,
+        ] {
+            procedure.extend_from_slice(&value.to_le_bytes());
+        }
+        // The .text section and no flags
+        procedure.extend_from_slice(&1u16.to_le_bytes());
+        procedure.push(0);
+        push_name(&mut procedure, &procedure_name(index));
+        push_symbol(&mut symbols, S_GPROC32, procedure);
+
+        let end = symbols.len() as u32;
+        // The end field follows the record's length, kind, and parent
+        symbols[start + 8..start + 12].copy_from_slice(&end.to_le_bytes());
+        push_symbol(&mut symbols, S_END, vec![]);
+    }
+
+    symbols
+}
+
+fn module_name(module: usize) -> String {
+    format!("module{}.obj", module)
+}
+
+fn procedure_name(index: usize) -> String {
+    format!("function{}", index)
+}
+
+fn global_name(index: usize) -> String {
+    format!("g_global{}", index)
+}
+
+/// Appends `name` as a NUL-terminated string
+fn push_name(data: &mut Vec<u8>, name: &str) {
+    data.extend_from_slice(name.as_bytes());
+    data.push(0);
+}
+
+/// Appends a symbol record of `kind` holding `body`, padded with zeros to a
+/// multiple of 4 bytes
+fn push_symbol(data: &mut Vec<u8>, kind: u16, mut body: Vec<u8>) {
+    body.resize((body.len() + 4).div_ceil(4) * 4 - 4, 0);
+    data.extend_from_slice(&(body.len() as u16 + 2).to_le_bytes());
+    data.extend_from_slice(&kind.to_le_bytes());
+    data.extend_from_slice(&body);
+}
+
+/// Pads the field list member starting at `start` to a multiple of 4 bytes,
+/// with the `LF_PADn` bytes type records are padded with
+fn pad_type_record(data: &mut Vec<u8>, start: usize) {
+    let padding = (4 - (data.len() - start) % 4) % 4;
+    for remaining in (1..=padding).rev() {
+        data.push(0xf0 | remaining as u8);
+    }
+}
+
+/// Returns a type record of `kind` holding `body`, including its length
+fn type_record(kind: u16, body: Vec<u8>) -> Vec<u8> {
+    let mut record = vec![0, 0];
+    record.extend_from_slice(&kind.to_le_bytes());
+    record.extend_from_slice(&body);
+    pad_type_record(&mut record, 0);
+    let len = record.len() as u16 - 2;
+    record[..2].copy_from_slice(&len.to_le_bytes());
+
+    record
+}
+
+/// Returns a TPI or IPI stream holding `records`, with no hash stream
+fn type_stream(records: &[Vec<u8>]) -> Vec<u8> {
+    let size: usize = records.iter().map(Vec::len).sum();
+    let mut stream = vec![];
+    for value in [
+        // VC 8.0 version and header size
+        20040203,
+        TPI_HEADER_SIZE as u32,
+        FIRST_TYPE_INDEX,
+        FIRST_TYPE_INDEX + records.len() as u32,
+        size as u32,
+    ] {
+        stream.extend_from_slice(&value.to_le_bytes());
+    }
+    // No hash or auxiliary hash stream
+    stream.extend_from_slice(&u16::MAX.to_le_bytes());
+    stream.extend_from_slice(&u16::MAX.to_le_bytes());
+    stream.resize(TPI_HEADER_SIZE, 0);
+    for record in records {
+        stream.extend_from_slice(record);
+    }
+
+    stream
+}
+
+fn borrow_names(symbols: &[(u32, String)]) -> Vec<(u32, &str)> {
+    symbols
+        .iter()
+        .map(|(offset, name)| (*offset, name.as_str()))
+        .collect()
+}
+
+/// Returns the PDB information stream of a VC 7.0 PDB with no named streams
+fn pdb_information() -> Vec<u8> {
+    let mut information = vec![];
+    // Version, signature, and age
+    for value in [20000404u32, 0, 1] {
+        information.extend_from_slice(&value.to_le_bytes());
+    }
+    // GUID
+    information.extend_from_slice(&[0; 16]);
+    for value in [
+        // Size of the names of the named streams
+        0,
+        // Size and capacity of the named stream map
+        0,
+        1,
+        // Bitmaps of present and deleted buckets, each a single empty word
+        1,
+        0,
+        0,
+        // The VC 14.0 feature
+        20140508u32,
+    ] {
+        information.extend_from_slice(&value.to_le_bytes());
+    }
+
+    information
+}
+
+/// Returns the public symbols stream for public symbols `publics`, which are
+/// in order of address
+fn public_symbols(publics: &[(u32, &str)]) -> Vec<u8> {
+    let hash_table = gsi::write_hash_table(publics);
+    let address_map: Vec<u8> = publics
+        .iter()
+        .flat_map(|(offset, _name)| offset.to_le_bytes())
+        .collect();
+
+    let mut stream = vec![];
+    for value in [hash_table.len() as u32, address_map.len() as u32, 0, 0] {
+        stream.extend_from_slice(&value.to_le_bytes());
+    }
+    // No thunk table or section map
+    stream.extend_from_slice(&[0; 12]);
+    stream.extend_from_slice(&hash_table);
+    stream.extend_from_slice(&address_map);
+
+    stream
+}
+
+/// Appends a section contribution of `size` bytes at `offset` into `section`
+/// by `module`
+fn push_section_contribution(
+    data: &mut Vec<u8>,
+    section: u16,
+    offset: u32,
+    size: u32,
+    module: u16,
+) {
+    data.extend_from_slice(&section.to_le_bytes());
+    data.extend_from_slice(&[0, 0]);
+    for value in [offset, size, TEXT_CHARACTERISTICS] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&module.to_le_bytes());
+    data.extend_from_slice(&[0, 0]);
+    // CRCs of the data and relocations
+    data.extend_from_slice(&[0; 8]);
+}
+
+/// Returns the DBI stream describing `modules`, the symbol streams of each
+/// module, and the .text and .data sections
+fn debug_information(options: &SyntheticOptions, modules: &[Vec<u8>]) -> Vec<u8> {
+    let text_size = options.procedures as u32 * PROCEDURE_SIZE;
+    let contribution = |module: usize| {
+        let procedures = module_procedures(options, module, modules.len());
+        (
+            procedures.start as u32 * PROCEDURE_SIZE,
+            procedures.len() as u32 * PROCEDURE_SIZE,
+        )
+    };
+
+    let mut module_list = vec![];
+    for (module, symbols) in modules.iter().enumerate() {
+        let (offset, size) = contribution(module);
+        module_list.extend_from_slice(&[0; 4]);
+        push_section_contribution(&mut module_list, 1, offset, size, module as u16);
+        // Flags and stream
+        module_list.extend_from_slice(&0u16.to_le_bytes());
+        module_list.extend_from_slice(&(FIRST_MODULE_STREAM + module as u16).to_le_bytes());
+        // Sizes of the symbols and the C11 and C13 line information
+        for value in [symbols.len() as u32, 0, 0] {
+            module_list.extend_from_slice(&value.to_le_bytes());
+        }
+        // Source file count, padding, and unused name indices
+        module_list.extend_from_slice(&[0; 16]);
+        let name = module_name(module);
+        push_name(&mut module_list, &name);
+        push_name(&mut module_list, &name);
+        module_list.resize(module_list.len().div_ceil(4) * 4, 0);
+    }
+
+    let mut section_contributions = (0xeffe_0000u32 + 19970605).to_le_bytes().to_vec();
+    for module in 0..modules.len() {
+        let (offset, size) = contribution(module);
+        push_section_contribution(&mut section_contributions, 1, offset, size, module as u16);
+    }
+
+    let mut section_map = vec![];
+    for value in [2u16, 2] {
+        section_map.extend_from_slice(&value.to_le_bytes());
+    }
+    let data_size = options.globals as u32 * STRUCT_SIZE;
+    for (frame, size) in [(1u16, text_size), (2, data_size)] {
+        // Flags (readable, 32-bit addresses, a selector), overlay, group, and frame
+        for value in [0x10d, 0, 0, frame, u16::MAX, u16::MAX] {
+            section_map.extend_from_slice(&value.to_le_bytes());
+        }
+        section_map.extend_from_slice(&0u32.to_le_bytes());
+        section_map.extend_from_slice(&size.to_le_bytes());
+    }
+
+    // No source files: the module and file counts, then each module's first
+    // file and file count
+    let mut file_info = vec![];
+    file_info.extend_from_slice(&(modules.len() as u16).to_le_bytes());
+    file_info.extend_from_slice(&0u16.to_le_bytes());
+    file_info.resize(file_info.len() + modules.len() * 4, 0);
+    file_info.resize(file_info.len().div_ceil(4) * 4, 0);
+
+    // Only the section headers of the optional debug streams are present
+    let debug_header: Vec<u8> = (0..11)
+        .map(|index| match index {
+            5 => SECTION_HEADERS_STREAM,
+            _ => u16::MAX,
+        })
+        .flat_map(u16::to_le_bytes)
+        .collect();
+
+    let mut dbi = vec![];
+    // Signature, VC 7.0 version, and age
+    for value in [u32::MAX, 19990903, 1] {
+        dbi.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in [
+        GLOBALS_STREAM,
+        0,
+        PUBLICS_STREAM,
+        0,
+        SYMBOL_RECORDS_STREAM,
+        0,
+    ] {
+        dbi.extend_from_slice(&value.to_le_bytes());
+    }
+    for size in [
+        module_list.len(),
+        section_contributions.len(),
+        section_map.len(),
+        file_info.len(),
+        // The type server map and MFC type server index
+        0,
+        0,
+        debug_header.len(),
+        // Edit and continue information
+        0,
+    ] {
+        dbi.extend_from_slice(&(size as u32).to_le_bytes());
+    }
+    // Flags, the AMD64 machine type, and padding
+    dbi.extend_from_slice(&0u16.to_le_bytes());
+    dbi.extend_from_slice(&0x8664u16.to_le_bytes());
+    dbi.extend_from_slice(&[0; 4]);
+
+    for substream in [
+        module_list,
+        section_contributions,
+        section_map,
+        file_info,
+        debug_header,
+    ] {
+        dbi.extend_from_slice(&substream);
+    }
+
+    dbi
+}
+
+/// Returns a section headers stream holding a header for each of `sections`,
+/// given as their name, RVA, size, and characteristics
+fn section_headers(sections: &[(&[u8; 5], u32, u32, u32)]) -> Vec<u8> {
+    let mut headers = vec![];
+    for &(name, rva, size, characteristics) in sections {
+        headers.extend_from_slice(name);
+        headers.resize(headers.len() + 3, 0);
+        // Virtual size and address, size of and pointer to the raw data, and
+        // pointers to relocations and line numbers
+        for value in [size, rva, size, 0, 0, 0] {
+            headers.extend_from_slice(&value.to_le_bytes());
+        }
+        // Relocation and line number counts
+        headers.extend_from_slice(&[0; 4]);
+        headers.extend_from_slice(&characteristics.to_le_bytes());
+    }
+
+    headers
+}
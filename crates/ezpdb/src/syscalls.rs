@@ -0,0 +1,174 @@
+//! Recovering the system call table of a Windows kernel (ntoskrnl) from its
+//! PDB, and optionally the system call numbers from the kernel image itself
+use crate::error::Error;
+use crate::raw;
+use crate::symbol_types::{Address, ParsedPdb};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// A system call service and the `Zw` stub which invokes it from kernel mode
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Syscall {
+    /// The system call number, if the kernel image's service table was read
+    pub number: Option<u32>,
+    /// Name of the service, e.g. `NtCreateFile`. Unknown if no symbol is at the
+    /// address the service table gives.
+    pub name: Option<String>,
+    pub address: Option<Address>,
+    /// Name of the matching `Zw` stub, e.g. `ZwCreateFile`
+    pub stub_name: Option<String>,
+    pub stub_address: Option<Address>,
+    /// The number of arguments passed on the stack, if the service table
+    /// records it
+    pub stack_arguments: Option<u32>,
+}
+
+/// Lists the `Nt*` services of a Windows kernel PDB alongside their `Zw*` stubs.
+/// Only functions which have both are included, as other `Nt*` functions
+/// aren't necessarily system calls.
+///
+/// If `image` is the kernel image the PDB belongs to, system call numbers are
+/// read from its service table (`KiServiceTable`) instead, and every service
+/// in the table is included whether or not it has a stub.
+pub fn syscall_table(pdb: &ParsedPdb, image: Option<&Path>) -> Result<Vec<Syscall>, Error> {
+    let functions = functions(pdb);
+
+    let image = match image {
+        Some(image) => std::fs::read(image)?,
+        None => {
+            return Ok(functions
+                .iter()
+                .filter_map(|(name, address)| {
+                    let base = name.strip_prefix("Nt")?;
+                    let stub_name = format!("Zw{}", base);
+                    let stub_address = *functions.get(stub_name.as_str())?;
+                    Some(Syscall {
+                        number: None,
+                        name: Some(name.to_string()),
+                        address: Some(*address),
+                        stub_name: Some(stub_name),
+                        stub_address: Some(stub_address),
+                        stack_arguments: None,
+                    })
+                })
+                .collect());
+        }
+    };
+
+    crate::code::check_image(pdb, &image)?;
+
+    let image = raw::Image::parse(&image)?;
+    let data = data_symbols(pdb);
+    let table = *data
+        .get("KiServiceTable")
+        .ok_or(Error::MissingDependency("KiServiceTable symbol"))?;
+    let limit = *data
+        .get("KiServiceLimit")
+        .ok_or(Error::MissingDependency("KiServiceLimit symbol"))?;
+    let arguments = data.get("KiArgumentTable").copied();
+
+    let count = image.reader_at(limit.rva as u32)?.u32()?;
+    let mut entries = image.reader_at(table.rva as u32)?;
+    let mut argument_sizes = arguments
+        .map(|arguments| image.reader_at(arguments.rva as u32))
+        .transpose()?;
+    let is_64bit = image.is_64bit()?;
+    let image_base = image.image_base()?;
+
+    let by_rva: HashMap<usize, &str> = functions
+        .iter()
+        .map(|(name, address)| (address.rva, *name))
+        .collect();
+    let base_address = table.virtual_address.map(|address| address - table.rva);
+
+    let mut syscalls = Vec::with_capacity(count as usize);
+    for number in 0..count {
+        let entry = entries.u32()?;
+        let (rva, stack_arguments) = if is_64bit {
+            // Entries are offsets from the start of the table, shifted left by 4
+            // to make room for the number of stack arguments
+            let offset = (entry as i32) >> 4;
+            (
+                (table.rva as i64 + i64::from(offset)) as usize,
+                Some(entry & 0xF),
+            )
+        } else {
+            // Entries are absolute addresses, and the stack arguments' size in
+            // bytes is kept in a separate table
+            let size = argument_sizes
+                .as_mut()
+                .map(|sizes| sizes.u8())
+                .transpose()?;
+            (
+                (u64::from(entry).wrapping_sub(image_base)) as usize,
+                size.map(|size| u32::from(size) / 4),
+            )
+        };
+
+        let name = by_rva.get(&rva).map(|name| name.to_string());
+        let stub_name = name
+            .as_deref()
+            .and_then(|name| name.strip_prefix("Nt"))
+            .map(|base| format!("Zw{}", base))
+            .filter(|stub_name| functions.contains_key(stub_name.as_str()));
+        let stub_address = stub_name
+            .as_deref()
+            .and_then(|stub_name| functions.get(stub_name).copied());
+
+        syscalls.push(Syscall {
+            number: Some(number),
+            name,
+            address: Some(Address::new(rva, base_address)),
+            stub_name,
+            stub_address,
+            stack_arguments,
+        });
+    }
+
+    Ok(syscalls)
+}
+
+/// Returns the address of each function in `pdb` by its undecorated name
+fn functions(pdb: &ParsedPdb) -> BTreeMap<&str, Address> {
+    let procedures = pdb
+        .procedures
+        .iter()
+        .filter_map(|procedure| Some((procedure.name.as_str(), procedure.address?)));
+    let publics = pdb
+        .public_symbols
+        .iter()
+        .filter(|symbol| symbol.is_function || symbol.is_code)
+        .filter_map(|symbol| Some((undecorate(&symbol.name), symbol.offset?)));
+
+    // Publics come first so that procedures, whose names are never decorated,
+    // take precedence
+    publics.chain(procedures).collect()
+}
+
+/// Returns the address of each global and public symbol in `pdb` by its
+/// undecorated name. Publics flagged as code are included, as the service table
+/// is kept in `.text` on x64.
+fn data_symbols(pdb: &ParsedPdb) -> HashMap<&str, Address> {
+    let globals = pdb
+        .global_data
+        .iter()
+        .filter_map(|data| Some((data.name.as_str(), data.offset?)));
+    let publics = pdb
+        .public_symbols
+        .iter()
+        .filter_map(|symbol| Some((undecorate(&symbol.name), symbol.offset?)));
+
+    publics.chain(globals).collect()
+}
+
+/// Strips the decoration 32-bit x86 adds to C names, e.g. `_NtClose@4` becomes `NtClose`
+pub(crate) fn undecorate(name: &str) -> &str {
+    let name = name.strip_prefix('_').unwrap_or(name);
+    match name.rfind('@') {
+        Some(at) if at > 0 && name[at + 1..].bytes().all(|b| b.is_ascii_digit()) => &name[..at],
+        _ => name,
+    }
+}
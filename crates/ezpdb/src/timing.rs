@@ -0,0 +1,130 @@
+use crate::progress::{ParseStage, ProgressSink};
+use std::time::{Duration, Instant};
+
+/// Time and memory spent in a single [ParseStage]
+#[derive(Debug, Clone)]
+pub struct StageTiming {
+    pub stage: ParseStage,
+    pub elapsed: Duration,
+    /// Number of items the stage processed
+    pub items: usize,
+    /// Peak resident set size of the process (in bytes) at the time the stage
+    /// finished. This is only available on platforms that expose it (currently Linux).
+    pub peak_memory: Option<u64>,
+}
+
+/// A [ProgressSink] which records how long each [ParseStage] takes
+#[derive(Debug, Default)]
+pub struct StageTimer {
+    current: Option<(ParseStage, Instant, usize)>,
+    timings: Vec<StageTiming>,
+}
+
+impl StageTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the timings of all stages which have completed, in the order they ran
+    pub fn timings(&self) -> &[StageTiming] {
+        &self.timings
+    }
+}
+
+impl ProgressSink for StageTimer {
+    fn stage_started(&mut self, stage: ParseStage, _total: Option<usize>) {
+        self.current = Some((stage, Instant::now(), 0));
+    }
+
+    fn progress(&mut self, _stage: ParseStage, processed: usize, _total: Option<usize>) {
+        if let Some((_, _, items)) = self.current.as_mut() {
+            *items = processed;
+        }
+    }
+
+    fn stage_finished(&mut self, stage: ParseStage) {
+        if let Some((current_stage, started, items)) = self.current.take() {
+            debug_assert_eq!(current_stage, stage);
+            self.timings.push(StageTiming {
+                stage,
+                elapsed: started.elapsed(),
+                items,
+                peak_memory: peak_memory(),
+            });
+        }
+    }
+}
+
+/// The time spent in a [ParseStage] across several parses of the same PDB
+#[derive(Debug, Clone)]
+pub struct StageSummary {
+    pub stage: ParseStage,
+    /// Number of parses the stage ran in
+    pub runs: usize,
+    pub min: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+    /// Number of items the stage processed in its last run
+    pub items: usize,
+}
+
+/// Summarizes the timings of each of `runs`, which are parses of the same PDB.
+/// Stages are listed in the order they first ran.
+pub fn summarize(runs: &[Vec<StageTiming>]) -> Vec<StageSummary> {
+    let mut summaries: Vec<(StageSummary, Duration)> = vec![];
+    for timing in runs.iter().flatten() {
+        match summaries
+            .iter_mut()
+            .find(|(summary, _total)| summary.stage == timing.stage)
+        {
+            Some((summary, total)) => {
+                summary.runs += 1;
+                summary.min = summary.min.min(timing.elapsed);
+                summary.max = summary.max.max(timing.elapsed);
+                summary.items = timing.items;
+                *total += timing.elapsed;
+            }
+            None => summaries.push((
+                StageSummary {
+                    stage: timing.stage,
+                    runs: 1,
+                    min: timing.elapsed,
+                    mean: timing.elapsed,
+                    max: timing.elapsed,
+                    items: timing.items,
+                },
+                timing.elapsed,
+            )),
+        }
+    }
+
+    summaries
+        .into_iter()
+        .map(|(summary, total)| StageSummary {
+            mean: total / summary.runs as u32,
+            ..summary
+        })
+        .collect()
+}
+
+/// Returns the peak resident set size of this process in bytes
+#[cfg(target_os = "linux")]
+pub fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes: u64 = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+/// Returns the peak resident set size of this process in bytes
+#[cfg(not(target_os = "linux"))]
+pub fn peak_memory() -> Option<u64> {
+    None
+}
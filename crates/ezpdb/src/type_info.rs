@@ -1,112 +1,11 @@
+//! Conversions from the `pdb` crate's types into the [ezpdb_model::type_info] model
+use crate::convert::{FromPdb, IntoModel, TryFromPdb, TryIntoModel};
 use crate::error::Error;
-use crate::symbol_types::ParsedPdb;
-use crate::symbol_types::TypeRef;
-#[cfg(feature = "serde")]
-use serde::Serialize;
-use std::convert::{From, TryFrom, TryInto};
-use std::rc::Rc;
-use log::warn;
-
-pub trait Typed {
-    /// Returns the size (in bytes) of this type
-    fn type_size(&self, pdb: &ParsedPdb) -> usize;
-
-    /// Called after all types have been parsed
-    fn on_complete(&mut self, pdb: &ParsedPdb) {}
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum Type {
-    Class(Class),
-    VirtualBaseClass(VirtualBaseClass),
-    Union(Union),
-    Bitfield(Bitfield),
-    Enumeration(Enumeration),
-    EnumVariant(EnumVariant),
-    Pointer(Pointer),
-    Primitive(Primitive),
-    Array(Array),
-    FieldList(FieldList),
-    ArgumentList(ArgumentList),
-    Modifier(Modifier),
-    Member(Member),
-    Procedure(Procedure),
-    MemberFunction(MemberFunction),
-    MethodList(MethodList),
-    MethodListEntry(MethodListEntry),
-    Nested(Nested),
-    OverloadedMethod(OverloadedMethod),
-    Method(Method),
-    StaticMember(StaticMember),
-    BaseClass(BaseClass),
-    VTable(VTable),
-}
-
-impl Typed for Type {
-    fn type_size(&self, pdb: &ParsedPdb) -> usize {
-        match self {
-            Type::Class(class) => class.type_size(pdb),
-            Type::Union(union) => union.type_size(pdb),
-            Type::Bitfield(bitfield) => bitfield.underlying_type.borrow().type_size(pdb),
-            Type::Enumeration(e) => e.underlying_type.borrow().type_size(pdb),
-            Type::Pointer(p) => p.attributes.kind.type_size(pdb),
-            Type::Primitive(p) => p.type_size(pdb),
-            Type::Array(a) => a.type_size(pdb),
-            Type::FieldList(fields) => fields
-                .0
-                .iter()
-                .fold(0, |acc, field| acc + field.borrow().type_size(pdb)),
-            Type::EnumVariant(_) => panic!("type_size() invoked for EnumVariant"),
-            Type::Modifier(modifier) => modifier.underlying_type.borrow().type_size(pdb),
-            Type::Member(_) => panic!("type_size() invoked for Member"),
-            Type::ArgumentList(_) => panic!("type_size() invoked for ArgumentList"),
-            Type::Procedure(_) => panic!("type_size() invoked for Procedure"),
-            Type::MemberFunction(_) => panic!("type_size() invoked for MemberFunction"),
-            Type::MethodList(_) => panic!("type_size() invoked for MethodList"),
-            Type::MethodListEntry(_) => panic!("type_size() invoked for MethodListEntry"),
-            Type::VirtualBaseClass(_) => panic!("type_size() invoked for VirtualBaseClass"),
-            Type::Nested(_) => panic!("type_size() invoked for Nested"),
-            Type::OverloadedMethod(_) => panic!("type_size() invoked for overloaded method"),
-            Type::Method(_) => panic!("type_size() invoked for overloaded method"),
-            Type::StaticMember(_) => panic!("type_size() invoked for StaticMember"),
-            Type::VTable(_) => panic!("type_size() invoked for VTable"),
-            Type::BaseClass(_) => panic!("type_size() invoked for BaseClass"),
-        }
-    }
 
-    fn on_complete(&mut self, pdb: &ParsedPdb) {
-        match self {
-            Type::Class(class) => class.on_complete(pdb),
-            Type::Union(union) => union.on_complete(pdb),
-            Type::Array(a) => a.on_complete(pdb),
-            _ => {}
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct TypeProperties {
-    pub packed: bool,
-    pub constructors: bool,
-    pub overlapped_operators: bool,
-    pub is_nested_type: bool,
-    pub contains_nested_types: bool,
-    pub overload_assignment: bool,
-    pub overload_coasting: bool,
-    pub forward_reference: bool,
-    pub scoped_definition: bool,
-    pub has_unique_name: bool,
-    pub sealed: bool,
-    pub hfa: u8,
-    pub intristic_type: bool,
-    pub mocom: u8,
-}
+pub use ezpdb_model::type_info::*;
 
-impl TryFrom<pdb::TypeProperties> for TypeProperties {
-    type Error = Error;
-    fn try_from(props: pdb::TypeProperties) -> Result<Self, Self::Error> {
+impl TryFromPdb<pdb::TypeProperties> for TypeProperties {
+    fn try_from_pdb(props: pdb::TypeProperties) -> Result<Self, Error> {
         Ok(TypeProperties {
             packed: props.packed(),
             constructors: props.constructors(),
@@ -126,55 +25,18 @@ impl TryFrom<pdb::TypeProperties> for TypeProperties {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Class {
-    pub name: String,
-    pub unique_name: Option<String>,
-    pub kind: ClassKind,
-    pub properties: TypeProperties,
-    pub derived_from: Option<TypeRef>,
-    pub fields: Vec<TypeRef>,
-    pub size: usize,
-}
-
-impl Typed for Class {
-    fn type_size(&self, pdb: &ParsedPdb) -> usize {
-        if self.properties.forward_reference {
-            // Find the implementation
-            for value in pdb.types.values() {
-                if let Ok(borrow) = value.as_ref().try_borrow() {
-                    if let Type::Class(class) = &*borrow {
-                        if !class.properties.forward_reference
-                            && class.unique_name == self.unique_name
-                        {
-                            return class.type_size(pdb);
-                        }
-                    }
-                }
-            }
-
-            warn!("could not get forward reference for {}", self.name);
-        }
-
-        self.size
-    }
-}
-
 type FromClass<'a, 'b> = (
     &'b pdb::ClassType<'a>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromClass<'_, '_>> for Class {
-    type Error = Error;
-    fn try_from(info: FromClass<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromClass<'_, '_>> for Class {
+    fn try_from_pdb(info: FromClass<'_, '_>) -> Result<Self, Error> {
         let (class, type_finder, output_pdb) = info;
 
         let pdb::ClassType {
             kind,
-            count,
             properties,
             fields,
             derived_from,
@@ -182,16 +44,13 @@ impl TryFrom<FromClass<'_, '_>> for Class {
             size,
             name,
             unique_name,
+            ..
         } = *class;
 
-        let fields: Vec<TypeRef> = match fields {
+        let fields: Vec<TypeId> = match fields {
             Some(type_index) => {
-                // TODO: perhaps change FieldList to Rc<Vec<TypeRef>?
-                if let Type::FieldList(fields) =
-                    &*crate::handle_type(type_index, output_pdb, type_finder)?
-                        .as_ref()
-                        .borrow()
-                {
+                let fields_id = crate::handle_type(type_index, output_pdb, type_finder)?;
+                if let Type::FieldList(fields) = &output_pdb.types[fields_id] {
                     fields.0.clone()
                 } else {
                     panic!("got an unexpected type when FieldList was expected")
@@ -205,26 +64,24 @@ impl TryFrom<FromClass<'_, '_>> for Class {
                 .expect("failed to resolve dependent type")
         });
 
-        let unique_name = unique_name.map(|s| s.to_string().into_owned());
+        let unique_name = unique_name.map(|s| output_pdb.strings.intern(&s.to_string()));
+
+        let vtable_shape = vtable_shape
+            .and_then(|type_index| output_pdb.vtable_shapes.get(&type_index.0).cloned());
 
         Ok(Class {
-            name: name.to_string().into_owned(),
+            name: output_pdb.strings.intern(&name.to_string()),
             unique_name,
-            kind: kind.try_into()?,
-            properties: properties.try_into()?,
+            kind: kind.try_into_model()?,
+            properties: properties.try_into_model()?,
             derived_from,
             fields,
             size: size as usize,
+            vtable_shape,
+            packing: None,
         })
     }
 }
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct BaseClass {
-    pub kind: ClassKind,
-    pub base_class: TypeRef,
-    pub offset: usize,
-}
 
 type FromBaseClass<'a, 'b> = (
     &'b pdb::BaseClassType,
@@ -232,56 +89,44 @@ type FromBaseClass<'a, 'b> = (
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromBaseClass<'_, '_>> for BaseClass {
-    type Error = Error;
-    fn try_from(info: FromBaseClass<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromBaseClass<'_, '_>> for BaseClass {
+    fn try_from_pdb(info: FromBaseClass<'_, '_>) -> Result<Self, Error> {
         let (class, type_finder, output_pdb) = info;
 
         let pdb::BaseClassType {
             kind,
-            attributes,
             base_class,
             offset,
+            ..
         } = *class;
 
         let base_class = crate::handle_type(base_class, output_pdb, type_finder)?;
 
         Ok(BaseClass {
-            kind: kind.try_into()?,
+            kind: kind.try_into_model()?,
             base_class,
             offset: offset as usize,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct VirtualBaseClass {
-    pub direct: bool,
-    pub base_class: TypeRef,
-    pub base_pointer: TypeRef,
-    pub base_pointer_offset: usize,
-    pub virtual_base_offset: usize,
-}
-
 type FromVirtualBaseClass<'a, 'b> = (
     &'b pdb::VirtualBaseClassType,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromVirtualBaseClass<'_, '_>> for VirtualBaseClass {
-    type Error = Error;
-    fn try_from(info: FromVirtualBaseClass<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromVirtualBaseClass<'_, '_>> for VirtualBaseClass {
+    fn try_from_pdb(info: FromVirtualBaseClass<'_, '_>) -> Result<Self, Error> {
         let (class, type_finder, output_pdb) = info;
 
         let pdb::VirtualBaseClassType {
             direct,
-            attributes,
             base_class,
             base_pointer,
             base_pointer_offset,
             virtual_base_offset,
+            ..
         } = *class;
 
         let base_class = crate::handle_type(base_class, output_pdb, type_finder)
@@ -299,17 +144,8 @@ impl TryFrom<FromVirtualBaseClass<'_, '_>> for VirtualBaseClass {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum ClassKind {
-    Class,
-    Struct,
-    Interface,
-}
-
-impl TryFrom<pdb::ClassKind> for ClassKind {
-    type Error = Error;
-    fn try_from(kind: pdb::ClassKind) -> Result<Self, Self::Error> {
+impl TryFromPdb<pdb::ClassKind> for ClassKind {
+    fn try_from_pdb(kind: pdb::ClassKind) -> Result<Self, Error> {
         Ok(match kind {
             pdb::ClassKind::Class => ClassKind::Class,
             pdb::ClassKind::Struct => ClassKind::Struct,
@@ -318,57 +154,14 @@ impl TryFrom<pdb::ClassKind> for ClassKind {
     }
 }
 
-impl std::fmt::Display for ClassKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ClassKind::Class => write!(f, "Class"),
-            ClassKind::Struct => write!(f, "Struct"),
-            ClassKind::Interface => write!(f, "Interface"),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Union {
-    pub name: String,
-    pub unique_name: Option<String>,
-    pub properties: TypeProperties,
-    pub size: usize,
-    pub count: usize,
-    pub fields: Vec<TypeRef>,
-}
-
-impl Typed for Union {
-    fn type_size(&self, pdb: &ParsedPdb) -> usize {
-        if self.properties.forward_reference {
-            // Find the implementation
-            for (_key, value) in &pdb.types {
-                if let Some(value) = value.as_ref().try_borrow().ok() {
-                    if let Type::Union(union) = &*value {
-                        if !union.properties.forward_reference
-                            && union.unique_name == self.unique_name
-                        {
-                            return union.type_size(pdb);
-                        }
-                    }
-                }
-            }
-
-            warn!("could not get forward reference for {}", self.name);
-        }
-
-        self.size
-    }
-}
 type FromUnion<'a, 'b> = (
     &'b pdb::UnionType<'a>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
-impl TryFrom<FromUnion<'_, '_>> for Union {
-    type Error = Error;
-    fn try_from(data: FromUnion<'_, '_>) -> Result<Self, Self::Error> {
+
+impl TryFromPdb<FromUnion<'_, '_>> for Union {
+    fn try_from_pdb(data: FromUnion<'_, '_>) -> Result<Self, Error> {
         let (union, type_finder, output_pdb) = data;
         let pdb::UnionType {
             count,
@@ -381,25 +174,19 @@ impl TryFrom<FromUnion<'_, '_>> for Union {
 
         let fields_type = crate::handle_type(*fields, output_pdb, type_finder)?;
 
-        let fields;
-        let borrowed_fields = fields_type.as_ref().borrow();
-        match &*borrowed_fields {
-            Type::FieldList(fields_list) => {
-                fields = fields_list.0.clone();
-            }
-            _ => {
-                drop(borrowed_fields);
-                fields = vec![fields_type];
-            }
-        }
+        let fields = match &output_pdb.types[fields_type] {
+            Type::FieldList(fields_list) => fields_list.0.clone(),
+            _ => vec![fields_type],
+        };
 
         let union = Union {
-            name: name.to_string().into_owned(),
-            unique_name: unique_name.map(|s| s.to_string().into_owned()),
-            properties: (*properties).try_into()?,
+            name: output_pdb.strings.intern(&name.to_string()),
+            unique_name: unique_name.map(|s| output_pdb.strings.intern(&s.to_string())),
+            properties: (*properties).try_into_model()?,
             size: *size as usize,
             count: *count as usize,
             fields,
+            packing: None,
         };
 
         Ok(union)
@@ -411,16 +198,9 @@ type FromBitfield<'a, 'b> = (
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Bitfield {
-    pub underlying_type: TypeRef,
-    pub len: usize,
-    pub position: usize,
-}
-impl TryFrom<FromBitfield<'_, '_>> for Bitfield {
-    type Error = Error;
-    fn try_from(data: FromBitfield<'_, '_>) -> Result<Self, Self::Error> {
+
+impl TryFromPdb<FromBitfield<'_, '_>> for Bitfield {
+    fn try_from_pdb(data: FromBitfield<'_, '_>) -> Result<Self, Error> {
         let (bitfield, type_finder, output_pdb) = data;
         let pdb::BitfieldType {
             underlying_type,
@@ -438,61 +218,38 @@ impl TryFrom<FromBitfield<'_, '_>> for Bitfield {
     }
 }
 
-impl Typed for Bitfield {
-    fn type_size(&self, pdb: &ParsedPdb) -> usize {
-        panic!("calling type_size() directly on a bitfield is probably not what you want");
-    }
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Enumeration {
-    pub name: String,
-    pub unique_name: Option<String>,
-    pub underlying_type: TypeRef,
-    pub variants: Vec<EnumVariant>,
-    pub properties: TypeProperties,
-}
-
 type FromEnumeration<'a, 'b> = (
     &'b pdb::EnumerationType<'a>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromEnumeration<'_, '_>> for Enumeration {
-    type Error = Error;
-    fn try_from(data: FromEnumeration<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromEnumeration<'_, '_>> for Enumeration {
+    fn try_from_pdb(data: FromEnumeration<'_, '_>) -> Result<Self, Error> {
         let (e, type_finder, output_pdb) = data;
 
         let pdb::EnumerationType {
-            count,
             properties,
             underlying_type,
             fields,
             name,
             unique_name,
+            ..
         } = *e;
 
         let underlying_type = crate::handle_type(underlying_type, output_pdb, type_finder)?;
 
         let fields_type = crate::handle_type(fields, output_pdb, type_finder)?;
 
-        let fields;
-        let borrowed_fields = fields_type.as_ref().borrow();
-        match &*borrowed_fields {
-            Type::FieldList(fields_list) => {
-                fields = fields_list.0.clone();
-            }
-            _other => {
-                fields = vec![];
-            }
-        }
+        let fields = match &output_pdb.types[fields_type] {
+            Type::FieldList(fields_list) => fields_list.0.clone(),
+            _other => vec![],
+        };
 
         let fields = fields
             .iter()
             .map(|field| {
-                if let Type::EnumVariant(var) = &*field.borrow() {
+                if let Type::EnumVariant(var) = &output_pdb.types[*field] {
                     var.clone()
                 } else {
                     panic!("field {:?} is not an enumvariant", field)
@@ -501,60 +258,37 @@ impl TryFrom<FromEnumeration<'_, '_>> for Enumeration {
             .collect::<Vec<_>>();
 
         Ok(Enumeration {
-            name: name.to_string().into_owned(),
-            unique_name: unique_name.map(|s| s.to_string().into_owned()),
+            name: output_pdb.strings.intern(&name.to_string()),
+            unique_name: unique_name.map(|s| output_pdb.strings.intern(&s.to_string())),
             underlying_type,
             variants: fields,
-            properties: properties.try_into()?,
+            properties: properties.try_into_model()?,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct EnumVariant {
-    pub name: String,
-    pub value: VariantValue,
-}
-
-type FromEnumerate<'a, 'b> = &'b pdb::EnumerateType<'a>;
+type FromEnumerate<'a, 'b> = (
+    &'b pdb::EnumerateType<'a>,
+    &'b mut crate::symbol_types::ParsedPdb,
+);
 
-impl TryFrom<FromEnumerate<'_, '_>> for EnumVariant {
-    type Error = Error;
-    fn try_from(data: FromEnumerate<'_, '_>) -> Result<Self, Self::Error> {
-        let e = data;
+impl TryFromPdb<FromEnumerate<'_, '_>> for EnumVariant {
+    fn try_from_pdb(data: FromEnumerate<'_, '_>) -> Result<Self, Error> {
+        let (e, output_pdb) = data;
 
-        let pdb::EnumerateType {
-            attributes,
-            value,
-            name,
-        } = e;
+        let pdb::EnumerateType { value, name, .. } = e;
 
         Ok(Self {
-            name: name.to_string().into_owned(),
-            value: value.try_into()?,
+            name: output_pdb.strings.intern(&name.to_string()),
+            value: value.try_into_model()?,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum VariantValue {
-    U8(u8),
-    U16(u16),
-    U32(u32),
-    U64(u64),
-    I8(i8),
-    I16(i16),
-    I32(i32),
-    I64(i64),
-}
-
 type FromVariant = pdb::Variant;
 
-impl TryFrom<&FromVariant> for VariantValue {
-    type Error = Error;
-    fn try_from(data: &FromVariant) -> Result<Self, Self::Error> {
+impl TryFromPdb<&FromVariant> for VariantValue {
+    fn try_from_pdb(data: &FromVariant) -> Result<Self, Error> {
         let variant = data;
 
         let value = match *variant {
@@ -572,21 +306,14 @@ impl TryFrom<&FromVariant> for VariantValue {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Pointer {
-    pub underlying_type: Option<TypeRef>,
-    pub attributes: PointerAttributes,
-}
-
 type FromPointer<'a, 'b> = (
     &'b pdb::PointerType,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
-impl TryFrom<FromPointer<'_, '_>> for Pointer {
-    type Error = Error;
-    fn try_from(data: FromPointer<'_, '_>) -> Result<Self, Self::Error> {
+
+impl TryFromPdb<FromPointer<'_, '_>> for Pointer {
+    fn try_from_pdb(data: FromPointer<'_, '_>) -> Result<Self, Error> {
         let (pointer, type_finder, output_pdb) = data;
         let pdb::PointerType {
             underlying_type,
@@ -596,34 +323,42 @@ impl TryFrom<FromPointer<'_, '_>> for Pointer {
 
         let underlying_type = crate::handle_type(underlying_type, output_pdb, type_finder).ok();
 
+        let member = match attributes.pointer_mode() {
+            pdb::PointerMode::Member => Some(MemberPointerKind::Data),
+            pdb::PointerMode::MemberFunction => Some(MemberPointerKind::Function),
+            _ => None,
+        }
+        .map(|kind| MemberPointer {
+            containing_class: containing_class
+                .and_then(|class| crate::handle_type(class, output_pdb, type_finder).ok()),
+            kind,
+            // The `pdb` crate doesn't keep the representation. It's filled in from
+            // the raw record by [crate::handle_type].
+            representation: MemberPointerRepresentation::Undefined,
+        });
+
         Ok(Pointer {
             underlying_type,
-            attributes: attributes.try_into()?,
+            attributes: attributes.try_into_model()?,
+            member,
         })
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum PointerKind {
-    Near16,
-    Far16,
-    Huge16,
-    BaseSeg,
-    BaseVal,
-    BaseSegVal,
-    BaseAddr,
-    BaseSegAddr,
-    BaseType,
-    BaseSelf,
-    Near32,
-    Far32,
-    Ptr64,
+/// Converts a raw `CV_pmtype` value. The 16:32 representations of 16-bit code
+/// are reported as [MemberPointerRepresentation::Undefined].
+pub(crate) fn member_pointer_representation_from_raw(pmtype: u16) -> MemberPointerRepresentation {
+    match pmtype {
+        0x01 | 0x05 => MemberPointerRepresentation::Single,
+        0x02 | 0x06 => MemberPointerRepresentation::Multiple,
+        0x03 | 0x07 => MemberPointerRepresentation::Virtual,
+        0x04 | 0x08 => MemberPointerRepresentation::General,
+        _ => MemberPointerRepresentation::Undefined,
+    }
 }
 
-impl TryFrom<pdb::PointerKind> for PointerKind {
-    type Error = Error;
-    fn try_from(kind: pdb::PointerKind) -> Result<Self, Self::Error> {
+impl TryFromPdb<pdb::PointerKind> for PointerKind {
+    fn try_from_pdb(kind: pdb::PointerKind) -> Result<Self, Error> {
         let kind = match kind {
             pdb::PointerKind::Near16 => PointerKind::Near16,
             pdb::PointerKind::Far16 => PointerKind::Far16,
@@ -644,40 +379,16 @@ impl TryFrom<pdb::PointerKind> for PointerKind {
     }
 }
 
-impl Typed for PointerKind {
-    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
-        match self {
-            PointerKind::Near16 | PointerKind::Far16 | PointerKind::Huge16 => 2,
-            PointerKind::Near32 | PointerKind::Far32 => 4,
-            PointerKind::Ptr64 => 8,
-            other => panic!("type_size() not implemented for pointer type: {:?}", other),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct PointerAttributes {
-    pub kind: PointerKind,
-    pub is_volatile: bool,
-    pub is_const: bool,
-    pub is_unaligned: bool,
-    pub is_restrict: bool,
-    pub is_reference: bool,
-    pub size: usize,
-    pub is_mocom: bool,
-}
-
-impl TryFrom<pdb::PointerAttributes> for PointerAttributes {
-    type Error = Error;
-    fn try_from(attr: pdb::PointerAttributes) -> Result<Self, Self::Error> {
+impl TryFromPdb<pdb::PointerAttributes> for PointerAttributes {
+    fn try_from_pdb(attr: pdb::PointerAttributes) -> Result<Self, Error> {
         let attr = PointerAttributes {
-            kind: attr.pointer_kind().try_into()?,
+            kind: attr.pointer_kind().try_into_model()?,
             is_volatile: attr.is_volatile(),
             is_const: attr.is_const(),
             is_unaligned: attr.is_unaligned(),
             is_restrict: attr.is_restrict(),
             is_reference: attr.is_reference(),
+            is_rvalue_reference: attr.pointer_mode() == pdb::PointerMode::RValueReference,
             size: attr.size() as usize,
             is_mocom: attr.is_mocom(),
         };
@@ -686,58 +397,21 @@ impl TryFrom<pdb::PointerAttributes> for PointerAttributes {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Primitive {
-    pub kind: PrimitiveKind,
-    pub indirection: Option<Indirection>,
-}
-
-impl TryFrom<&pdb::PrimitiveType> for Primitive {
-    type Error = Error;
-    fn try_from(typ: &pdb::PrimitiveType) -> Result<Self, Self::Error> {
+impl TryFromPdb<&pdb::PrimitiveType> for Primitive {
+    fn try_from_pdb(typ: &pdb::PrimitiveType) -> Result<Self, Error> {
         let pdb::PrimitiveType { kind, indirection } = typ;
 
         let prim = Primitive {
-            kind: kind.try_into()?,
-            indirection: indirection.map(|i| i.try_into()).transpose()?,
+            kind: kind.try_into_model()?,
+            indirection: indirection.map(|i| i.try_into_model()).transpose()?,
         };
 
         Ok(prim)
     }
 }
 
-impl Typed for Primitive {
-    fn type_size(&self, pdb: &ParsedPdb) -> usize {
-        self.size()
-    }
-}
-
-impl Primitive {
-    pub fn size(&self) -> usize {
-        if let Some(indirection) = self.indirection.as_ref() {
-            return indirection.size();
-        }
-
-        return self.kind.size();
-    }
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum Indirection {
-    Near16,
-    Far16,
-    Huge16,
-    Near32,
-    Far32,
-    Near64,
-    Near128,
-}
-
-impl TryFrom<pdb::Indirection> for Indirection {
-    type Error = Error;
-    fn try_from(kind: pdb::Indirection) -> Result<Self, Self::Error> {
+impl TryFromPdb<pdb::Indirection> for Indirection {
+    fn try_from_pdb(kind: pdb::Indirection) -> Result<Self, Error> {
         let kind = match kind {
             pdb::Indirection::Near16 => Indirection::Near16,
             pdb::Indirection::Far16 => Indirection::Far16,
@@ -752,73 +426,8 @@ impl TryFrom<pdb::Indirection> for Indirection {
     }
 }
 
-impl Typed for Indirection {
-    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
-        self.size()
-    }
-}
-
-impl Indirection {
-    pub fn size(&self) -> usize {
-        match self {
-            Indirection::Near16 | Indirection::Far16 | Indirection::Huge16 => 2,
-            Indirection::Near32 | Indirection::Far32 => 4,
-            Indirection::Near64 => 8,
-            Indirection::Near128 => 8,
-        }
-    }
-}
-
-#[derive(Debug, Copy, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub enum PrimitiveKind {
-    NoType,
-    Void,
-    Char,
-    UChar,
-    RChar,
-    WChar,
-    RChar16,
-    RChar32,
-    I8,
-    U8,
-    Short,
-    UShort,
-    I16,
-    U16,
-    Long,
-    ULong,
-    I32,
-    U32,
-    Quad,
-    UQuad,
-    I64,
-    U64,
-    Octa,
-    UOcta,
-    I128,
-    U128,
-    F16,
-    F32,
-    F32PP,
-    F48,
-    F64,
-    F80,
-    F128,
-    Complex32,
-    Complex64,
-    Complex80,
-    Complex128,
-    Bool8,
-    Bool16,
-    Bool32,
-    Bool64,
-    HRESULT,
-}
-
-impl TryFrom<&pdb::PrimitiveKind> for PrimitiveKind {
-    type Error = Error;
-    fn try_from(kind: &pdb::PrimitiveKind) -> Result<Self, Self::Error> {
+impl TryFromPdb<&pdb::PrimitiveKind> for PrimitiveKind {
+    fn try_from_pdb(kind: &pdb::PrimitiveKind) -> Result<Self, Error> {
         let kind = match *kind {
             pdb::PrimitiveKind::NoType => PrimitiveKind::NoType,
             pdb::PrimitiveKind::Void => PrimitiveKind::Void,
@@ -869,165 +478,14 @@ impl TryFrom<&pdb::PrimitiveKind> for PrimitiveKind {
     }
 }
 
-impl Typed for PrimitiveKind {
-    fn type_size(&self, _pdb: &ParsedPdb) -> usize {
-        self.size()
-    }
-}
-
-impl PrimitiveKind {
-    pub fn size(&self) -> usize {
-        match self {
-            PrimitiveKind::NoType | PrimitiveKind::Void => 0,
-
-            PrimitiveKind::Char
-            | PrimitiveKind::UChar
-            | PrimitiveKind::RChar
-            | PrimitiveKind::I8
-            | PrimitiveKind::U8
-            | PrimitiveKind::Bool8 => 1,
-
-            PrimitiveKind::RChar16
-            | PrimitiveKind::WChar
-            | PrimitiveKind::Short
-            | PrimitiveKind::UShort
-            | PrimitiveKind::I16
-            | PrimitiveKind::U16
-            | PrimitiveKind::F16
-            | PrimitiveKind::Bool16 => 2,
-
-            PrimitiveKind::RChar32
-            | PrimitiveKind::Long
-            | PrimitiveKind::ULong
-            | PrimitiveKind::I32
-            | PrimitiveKind::U32
-            | PrimitiveKind::F32
-            | PrimitiveKind::F32PP
-            | PrimitiveKind::Bool32
-            | PrimitiveKind::HRESULT
-            | PrimitiveKind::Complex32 => 4,
-
-            PrimitiveKind::F48 => 6,
-
-            PrimitiveKind::Quad
-            | PrimitiveKind::UQuad
-            | PrimitiveKind::I64
-            | PrimitiveKind::U64
-            | PrimitiveKind::F64
-            | PrimitiveKind::Bool64
-            | PrimitiveKind::Complex64 => 8,
-
-            PrimitiveKind::Octa
-            | PrimitiveKind::UOcta
-            | PrimitiveKind::I128
-            | PrimitiveKind::U128 => 16,
-
-            PrimitiveKind::F80 | PrimitiveKind::Complex80 => 10,
-
-            PrimitiveKind::F128 | PrimitiveKind::Complex128 => 16,
-        }
-    }
-}
-
-impl std::fmt::Display for PrimitiveKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PrimitiveKind::NoType => write!(f, "NoType"),
-            PrimitiveKind::Void => write!(f, "Void"),
-            PrimitiveKind::Char => write!(f, "Char"),
-            PrimitiveKind::UChar => write!(f, "UChar"),
-            PrimitiveKind::RChar => write!(f, "RChar"),
-            PrimitiveKind::WChar => write!(f, "WChar"),
-            PrimitiveKind::RChar16 => write!(f, "RChar16"),
-            PrimitiveKind::RChar32 => write!(f, "RChar32"),
-            PrimitiveKind::I8 => write!(f, "I8"),
-            PrimitiveKind::U8 => write!(f, "U8"),
-            PrimitiveKind::Short => write!(f, "Short"),
-            PrimitiveKind::UShort => write!(f, "UShort"),
-            PrimitiveKind::I16 => write!(f, "I16"),
-            PrimitiveKind::U16 => write!(f, "U16"),
-            PrimitiveKind::Long => write!(f, "Long"),
-            PrimitiveKind::ULong => write!(f, "ULong"),
-            PrimitiveKind::I32 => write!(f, "I32"),
-            PrimitiveKind::U32 => write!(f, "U32"),
-            PrimitiveKind::Quad => write!(f, "Quad"),
-            PrimitiveKind::UQuad => write!(f, "UQuad"),
-            PrimitiveKind::I64 => write!(f, "I64"),
-            PrimitiveKind::U64 => write!(f, "U64"),
-            PrimitiveKind::Octa => write!(f, "Octa"),
-            PrimitiveKind::UOcta => write!(f, "UOcta"),
-            PrimitiveKind::I128 => write!(f, "I128"),
-            PrimitiveKind::U128 => write!(f, "U128"),
-            PrimitiveKind::F16 => write!(f, "F16"),
-            PrimitiveKind::F32 => write!(f, "F32"),
-            PrimitiveKind::F32PP => write!(f, "F32PP"),
-            PrimitiveKind::F48 => write!(f, "F48"),
-            PrimitiveKind::F64 => write!(f, "F64"),
-            PrimitiveKind::F80 => write!(f, "F80"),
-            PrimitiveKind::F128 => write!(f, "F128"),
-            PrimitiveKind::Complex32 => write!(f, "Complex32"),
-            PrimitiveKind::Complex64 => write!(f, "Complex64"),
-            PrimitiveKind::Complex80 => write!(f, "Complex80"),
-            PrimitiveKind::Complex128 => write!(f, "Complex128"),
-            PrimitiveKind::Bool8 => write!(f, "Bool8"),
-            PrimitiveKind::Bool16 => write!(f, "Bool16"),
-            PrimitiveKind::Bool32 => write!(f, "Bool32"),
-            PrimitiveKind::Bool64 => write!(f, "Bool64"),
-            PrimitiveKind::HRESULT => write!(f, "HRESULT"),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Array {
-    pub element_type: TypeRef,
-    pub indexing_type: TypeRef,
-    pub stride: Option<u32>,
-    pub size: usize,
-    pub dimensions_bytes: Vec<usize>,
-    pub dimensions_elements: Vec<usize>,
-}
-
-impl Typed for Array {
-    fn type_size(&self, pdb: &ParsedPdb) -> usize {
-        self.size
-    }
-
-    fn on_complete(&mut self, pdb: &ParsedPdb) {
-        self.dimensions_elements.clear();
-
-        if self.size == 0 {
-            self.dimensions_elements.push(0);
-            return;
-        }
-
-        let mut running_size = self.element_type.as_ref().borrow().type_size(pdb);
-
-        for byte_size in &self.dimensions_bytes {
-            // TODO: may be incorrect behavior
-            if running_size == 0 {
-                continue;
-            }
-
-            let size = *byte_size / running_size;
-
-            self.dimensions_elements.push(size);
-
-            running_size = size;
-        }
-    }
-}
-
 type FromArray<'a, 'b> = (
     &'b pdb::ArrayType,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromArray<'_, '_>> for Array {
-    type Error = Error;
-    fn try_from(data: FromArray<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromArray<'_, '_>> for Array {
+    fn try_from_pdb(data: FromArray<'_, '_>) -> Result<Self, Error> {
         let (array, type_finder, output_pdb) = data;
 
         let pdb::ArrayType {
@@ -1054,19 +512,14 @@ impl TryFrom<FromArray<'_, '_>> for Array {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct FieldList(pub Vec<TypeRef>);
-
 type FromFieldList<'a, 'b> = (
     &'b pdb::FieldList<'b>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromFieldList<'_, '_>> for FieldList {
-    type Error = Error;
-    fn try_from(data: FromFieldList<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromFieldList<'_, '_>> for FieldList {
+    fn try_from_pdb(data: FromFieldList<'_, '_>) -> Result<Self, Error> {
         let (fields, type_finder, output_pdb) = data;
 
         let pdb::FieldList {
@@ -1074,7 +527,7 @@ impl TryFrom<FromFieldList<'_, '_>> for FieldList {
             continuation,
         } = fields;
 
-        let result_fields: Result<Vec<TypeRef>, Self::Error> = fields
+        let result_fields: Result<Vec<TypeId>, Error> = fields
             .iter()
             .map(|typ| crate::handle_type_data(typ, output_pdb, type_finder))
             .collect();
@@ -1083,8 +536,8 @@ impl TryFrom<FromFieldList<'_, '_>> for FieldList {
 
         if let Some(continuation) = continuation {
             let field = crate::handle_type(*continuation, output_pdb, type_finder)?;
-            let field = field.as_ref().borrow();
-            if let Type::FieldList(fields) = &*field {
+            let field = &output_pdb.types[field];
+            if let Type::FieldList(fields) = field {
                 result_fields.append(&mut fields.0.clone())
             } else {
                 panic!(
@@ -1098,24 +551,19 @@ impl TryFrom<FromFieldList<'_, '_>> for FieldList {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct ArgumentList(pub Vec<TypeRef>);
-
 type FromArgumentList<'a, 'b> = (
     &'b pdb::ArgumentList,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromArgumentList<'_, '_>> for ArgumentList {
-    type Error = Error;
-    fn try_from(data: FromArgumentList<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromArgumentList<'_, '_>> for ArgumentList {
+    fn try_from_pdb(data: FromArgumentList<'_, '_>) -> Result<Self, Error> {
         let (arguments, type_finder, output_pdb) = data;
 
         let pdb::ArgumentList { arguments } = arguments;
 
-        let arguments: Result<Vec<TypeRef>, Self::Error> = arguments
+        let arguments: Result<Vec<TypeId>, Error> = arguments
             .iter()
             .map(|typ| crate::handle_type(*typ, output_pdb, type_finder))
             .collect();
@@ -1124,24 +572,14 @@ impl TryFrom<FromArgumentList<'_, '_>> for ArgumentList {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Modifier {
-    pub underlying_type: TypeRef,
-    pub constant: bool,
-    pub volatile: bool,
-    pub unaligned: bool,
-}
-
 type FromModifier<'a, 'b> = (
     &'b pdb::ModifierType,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromModifier<'_, '_>> for Modifier {
-    type Error = Error;
-    fn try_from(data: FromModifier<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromModifier<'_, '_>> for Modifier {
+    fn try_from_pdb(data: FromModifier<'_, '_>) -> Result<Self, Error> {
         let (modifier, type_finder, output_pdb) = data;
 
         let pdb::ModifierType {
@@ -1162,76 +600,57 @@ impl TryFrom<FromModifier<'_, '_>> for Modifier {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Member {
-    pub name: String,
-    pub underlying_type: TypeRef,
-    pub offset: usize,
-}
-
 type FromMember<'a, 'b> = (
     &'b pdb::MemberType<'a>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromMember<'_, '_>> for Member {
-    type Error = Error;
-
-    fn try_from(data: FromMember<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromMember<'_, '_>> for Member {
+    fn try_from_pdb(data: FromMember<'_, '_>) -> Result<Self, Error> {
         let (member, type_finder, output_pdb) = data;
 
         let pdb::MemberType {
-            attributes,
             field_type,
             offset,
             name,
+            ..
         } = *member;
 
         let underlying_type = crate::handle_type(field_type, output_pdb, type_finder)?;
 
         Ok(Member {
-            name: name.to_string().into_owned(),
+            name: output_pdb.strings.intern(&name.to_string()),
             underlying_type,
             offset: offset as usize,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Procedure {
-    pub return_type: Option<TypeRef>,
-    pub argument_list: Vec<TypeRef>,
-    pub attributes: FunctionAttributes,
-}
-
 type FromProcedure<'a, 'b> = (
     &'b pdb::ProcedureType,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromProcedure<'_, '_>> for Procedure {
-    type Error = Error;
-    fn try_from(data: FromProcedure<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromProcedure<'_, '_>> for Procedure {
+    fn try_from_pdb(data: FromProcedure<'_, '_>) -> Result<Self, Error> {
         let (proc, type_finder, output_pdb) = data;
 
         let pdb::ProcedureType {
             return_type,
             attributes,
-            parameter_count,
             argument_list,
+            ..
         } = *proc;
 
         let return_type = return_type
             .map(|return_type| crate::handle_type(return_type, output_pdb, type_finder))
             .transpose()?;
 
-        let arguments: Vec<TypeRef>;
+        let arguments: Vec<TypeId>;
         let field = crate::handle_type(argument_list, output_pdb, type_finder)?;
-        if let Type::ArgumentList(argument_list) = &*field.as_ref().borrow() {
+        if let Type::ArgumentList(argument_list) = &output_pdb.types[field] {
             arguments = argument_list.0.clone();
         } else {
             panic!(
@@ -1243,23 +662,13 @@ impl TryFrom<FromProcedure<'_, '_>> for Procedure {
         Ok(Procedure {
             return_type,
             argument_list: arguments,
-            attributes: attributes.try_into()?,
+            attributes: attributes.try_into_model()?,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct FunctionAttributes {
-    pub calling_convention: u8,
-    pub cxx_return_udt: bool,
-    pub is_constructor: bool,
-    pub is_constructor_with_virtual_bases: bool,
-}
-
-impl TryFrom<pdb::FunctionAttributes> for FunctionAttributes {
-    type Error = Error;
-    fn try_from(data: pdb::FunctionAttributes) -> Result<Self, Self::Error> {
+impl TryFromPdb<pdb::FunctionAttributes> for FunctionAttributes {
+    fn try_from_pdb(data: pdb::FunctionAttributes) -> Result<Self, Error> {
         Ok(FunctionAttributes {
             calling_convention: data.calling_convention(),
             cxx_return_udt: data.cxx_return_udt(),
@@ -1269,26 +678,14 @@ impl TryFrom<pdb::FunctionAttributes> for FunctionAttributes {
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct MemberFunction {
-    pub return_type: TypeRef,
-    pub class_type: TypeRef,
-    pub this_pointer_type: Option<TypeRef>,
-    pub argument_list: Vec<TypeRef>,
-    pub attributes: FunctionAttributes,
-    pub this_adjustment: u32,
-}
-
 type FromMemberFunction<'a, 'b> = (
     &'b pdb::MemberFunctionType,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromMemberFunction<'_, '_>> for MemberFunction {
-    type Error = Error;
-    fn try_from(data: FromMemberFunction<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromMemberFunction<'_, '_>> for MemberFunction {
+    fn try_from_pdb(data: FromMemberFunction<'_, '_>) -> Result<Self, Error> {
         let (member, type_finder, output_pdb) = data;
 
         let pdb::MemberFunctionType {
@@ -1296,9 +693,9 @@ impl TryFrom<FromMemberFunction<'_, '_>> for MemberFunction {
             class_type,
             this_pointer_type,
             attributes,
-            parameter_count,
             argument_list,
             this_adjustment,
+            ..
         } = *member;
 
         let return_type = crate::handle_type(return_type, output_pdb, type_finder)?;
@@ -1309,9 +706,9 @@ impl TryFrom<FromMemberFunction<'_, '_>> for MemberFunction {
             .map(|ptr_type| crate::handle_type(ptr_type, output_pdb, type_finder))
             .transpose()?;
 
-        let arguments: Vec<TypeRef>;
+        let arguments: Vec<TypeId>;
         let field = crate::handle_type(argument_list, output_pdb, type_finder)?;
-        if let Type::ArgumentList(argument_list) = &*field.as_ref().borrow() {
+        if let Type::ArgumentList(argument_list) = &output_pdb.types[field] {
             arguments = argument_list.0.clone();
         } else {
             panic!(
@@ -1325,53 +722,40 @@ impl TryFrom<FromMemberFunction<'_, '_>> for MemberFunction {
             class_type,
             this_pointer_type,
             argument_list: arguments,
-            attributes: attributes.try_into()?,
+            attributes: attributes.try_into_model()?,
             this_adjustment,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct MethodList(pub Vec<MethodListEntry>);
-
 type FromMethodList<'a, 'b> = (
     &'b pdb::MethodList,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromMethodList<'_, '_>> for MethodList {
-    type Error = Error;
-    fn try_from(data: FromMethodList<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromMethodList<'_, '_>> for MethodList {
+    fn try_from_pdb(data: FromMethodList<'_, '_>) -> Result<Self, Error> {
         let (method_list, type_finder, output_pdb) = data;
 
         let pdb::MethodList { methods } = method_list;
-        let converted_methods: Result<Vec<MethodListEntry>, Self::Error> = methods
+        let converted_methods: Result<Vec<MethodListEntry>, Error> = methods
             .iter()
-            .map(|method| (method, type_finder, &mut *output_pdb).try_into())
+            .map(|method| (method, type_finder, &mut *output_pdb).try_into_model())
             .collect();
 
         Ok(MethodList(converted_methods?))
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct MethodListEntry {
-    pub method_type: TypeRef,
-    pub vtable_offset: Option<usize>,
-}
-
 type FromMethodListEntry<'a, 'b> = (
     &'b pdb::MethodListEntry,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromMethodListEntry<'_, '_>> for MethodListEntry {
-    type Error = Error;
-    fn try_from(data: FromMethodListEntry<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromMethodListEntry<'_, '_>> for MethodListEntry {
+    fn try_from_pdb(data: FromMethodListEntry<'_, '_>) -> Result<Self, Error> {
         let (method_list, type_finder, output_pdb) = data;
 
         let pdb::MethodListEntry {
@@ -1383,95 +767,67 @@ impl TryFrom<FromMethodListEntry<'_, '_>> for MethodListEntry {
         let method_type = crate::handle_type(method_type, output_pdb, type_finder)?;
 
         Ok(MethodListEntry {
+            attributes: attributes.into_model(),
             method_type,
             vtable_offset: vtable_offset.map(|offset| offset as usize),
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Nested {
-    pub name: String,
-    pub nested_type: TypeRef,
-}
-
 type FromNested<'a, 'b> = (
     &'b pdb::NestedType<'a>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromNested<'_, '_>> for Nested {
-    type Error = Error;
-    fn try_from(data: FromNested<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromNested<'_, '_>> for Nested {
+    fn try_from_pdb(data: FromNested<'_, '_>) -> Result<Self, Error> {
         let (method_list, type_finder, output_pdb) = data;
 
         let pdb::NestedType {
-            attributes,
-            nested_type,
-            name,
+            nested_type, name, ..
         } = *method_list;
 
         let nested_type = crate::handle_type(nested_type, output_pdb, type_finder)?;
 
         Ok(Nested {
-            name: name.to_string().into_owned(),
+            name: output_pdb.strings.intern(&name.to_string()),
             nested_type,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct OverloadedMethod {
-    pub name: String,
-    pub method_list: TypeRef,
-}
-
 type FromOverloadedMethod<'a, 'b> = (
     &'b pdb::OverloadedMethodType<'a>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromOverloadedMethod<'_, '_>> for OverloadedMethod {
-    type Error = Error;
-    fn try_from(data: FromOverloadedMethod<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromOverloadedMethod<'_, '_>> for OverloadedMethod {
+    fn try_from_pdb(data: FromOverloadedMethod<'_, '_>) -> Result<Self, Error> {
         let (method_list, type_finder, output_pdb) = data;
 
         let pdb::OverloadedMethodType {
-            count,
-            method_list,
-            name,
+            method_list, name, ..
         } = method_list;
 
         let method_list = crate::handle_type(*method_list, output_pdb, type_finder)?;
 
         Ok(OverloadedMethod {
-            name: name.to_string().into_owned(),
+            name: output_pdb.strings.intern(&name.to_string()),
             method_list,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct Method {
-    pub name: String,
-    pub method_type: TypeRef,
-    pub vtable_offset: Option<usize>,
-}
-
 type FromMethod<'a, 'b> = (
     &'b pdb::MethodType<'a>,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromMethod<'_, '_>> for Method {
-    type Error = Error;
-    fn try_from(data: FromMethod<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromMethod<'_, '_>> for Method {
+    fn try_from_pdb(data: FromMethod<'_, '_>) -> Result<Self, Error> {
         let (method_list, type_finder, output_pdb) = data;
 
         let pdb::MethodType {
@@ -1484,18 +840,31 @@ impl TryFrom<FromMethod<'_, '_>> for Method {
         let method_type = crate::handle_type(*method_type, output_pdb, type_finder)?;
 
         Ok(Method {
-            name: name.to_string().into_owned(),
+            name: output_pdb.strings.intern(&name.to_string()),
+            attributes: (*attributes).into_model(),
             method_type,
             vtable_offset: vtable_offset.map(|offset| offset as usize),
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct StaticMember {
-    pub name: String,
-    pub field_type: TypeRef,
+impl FromPdb<pdb::FieldAttributes> for FieldAttributes {
+    fn from_pdb(attributes: pdb::FieldAttributes) -> Self {
+        let access = match attributes.access() {
+            1 => Access::Private,
+            2 => Access::Protected,
+            3 => Access::Public,
+            _ => Access::None,
+        };
+
+        FieldAttributes {
+            access,
+            is_static: attributes.is_static(),
+            is_virtual: attributes.is_virtual(),
+            is_pure_virtual: attributes.is_pure_virtual(),
+            is_introducing_virtual: attributes.is_intro_virtual(),
+        }
+    }
 }
 
 type FromStaticMember<'a, 'b> = (
@@ -1504,39 +873,32 @@ type FromStaticMember<'a, 'b> = (
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromStaticMember<'_, '_>> for StaticMember {
-    type Error = Error;
-    fn try_from(data: FromStaticMember<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromStaticMember<'_, '_>> for StaticMember {
+    fn try_from_pdb(data: FromStaticMember<'_, '_>) -> Result<Self, Error> {
         let (member, type_finder, output_pdb) = data;
 
         let pdb::StaticMemberType {
-            attributes,
-            field_type,
-            name,
+            field_type, name, ..
         } = member;
 
         let field_type = crate::handle_type(*field_type, output_pdb, type_finder)
             .expect("failed to parse dependent type");
 
         Ok(StaticMember {
-            name: name.to_string().into_owned(),
+            name: output_pdb.strings.intern(&name.to_string()),
             field_type,
         })
     }
 }
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
-pub struct VTable(TypeRef);
 type FromVirtualFunctionTablePointer<'a, 'b> = (
     &'b pdb::VirtualFunctionTablePointerType,
     &'b pdb::TypeFinder<'a>,
     &'b mut crate::symbol_types::ParsedPdb,
 );
 
-impl TryFrom<FromVirtualFunctionTablePointer<'_, '_>> for VTable {
-    type Error = Error;
-    fn try_from(data: FromVirtualFunctionTablePointer<'_, '_>) -> Result<Self, Self::Error> {
+impl TryFromPdb<FromVirtualFunctionTablePointer<'_, '_>> for VTable {
+    fn try_from_pdb(data: FromVirtualFunctionTablePointer<'_, '_>) -> Result<Self, Error> {
         let (member, type_finder, output_pdb) = data;
 
         let pdb::VirtualFunctionTablePointerType { table } = *member;
@@ -0,0 +1,1099 @@
+//! Integrity checks of a PDB's MSF container and of the stream headers the rest
+//! of the crate relies upon. Unlike [crate::parse_pdb], which stops at the first
+//! problem, every problem found is reported along with where it was found.
+use crate::error::Error;
+use crate::msf;
+use crate::raw::{
+    ByteReader, PdbInformation, DBI_HEADER_SIZE, DBI_STREAM, IPI_STREAM, MODULE_INFO_SIZE,
+    PDB_STREAM, TPI_HEADER_SIZE, TPI_STREAM,
+};
+use crate::symbol_types::ParsedPdb;
+use pdb::{FallibleIterator, TypeData, TypeIndex};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Marks a stream index which refers to no stream
+const NO_STREAM: u16 = 0xffff;
+
+/// Size of the MSF header up to the list of stream directory pages
+const MSF_HEADER_SIZE: usize = 52;
+
+/// Size of the PDB information stream's header, up to the named stream map
+const PDB_INFORMATION_HEADER_SIZE: usize = 28;
+
+/// Signature of module streams with C13 line information
+const C13_SIGNATURE: u32 = 4;
+
+/// Kind of the C13 subsection holding file checksums
+const DEBUG_S_FILECHKSMS: u32 = 0xf4;
+
+/// Signature of the `/names` string table
+const STRING_TABLE_SIGNATURE: u32 = 0xeffe_effe;
+
+/// The first type index which isn't a primitive type
+const FIRST_TYPE_INDEX: u32 = 0x1000;
+
+/// A problem found by [verify_pdb]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Issue {
+    /// The stream the problem was found in. `None` for problems with the MSF
+    /// header or stream directory.
+    pub stream: Option<u32>,
+    /// Byte offset of the problem within `stream`, or within the file if there's no stream
+    pub offset: Option<usize>,
+    /// Kind of the record the problem was found in, if it's in a record
+    pub kind: Option<u16>,
+    pub description: String,
+}
+
+/// Checks the MSF container of the PDB at `path`, the lengths of its streams,
+/// the headers of its TPI, IPI, DBI, and module streams, references into its
+/// string table, and the bounds of the type indices its types refer to. Returns
+/// an empty list if no problems were found. Only fails if the file can't be read.
+pub fn verify_pdb<P: AsRef<Path>>(path: P) -> Result<Vec<Issue>, Error> {
+    let data = std::fs::read(path)?;
+    let mut verifier = Verifier::default();
+
+    let data = if msf::is_small_msf(&data) {
+        match msf::convert_small_msf(&data) {
+            Ok(converted) => converted,
+            Err(e) => {
+                verifier.report(
+                    None,
+                    None,
+                    format!("the PDB 2.0 container can't be read: {}", e),
+                );
+                return Ok(verifier.issues);
+            }
+        }
+    } else {
+        data
+    };
+
+    if let Some(streams) = verifier.read_streams(&data) {
+        verifier.verify_streams(&streams, &data);
+    }
+
+    Ok(verifier.issues)
+}
+
+/// The fields of the MSF 7.00 header
+#[derive(Debug, Copy, Clone)]
+struct MsfHeader {
+    page_size: usize,
+    free_page_map: u32,
+    page_count: usize,
+    directory_size: usize,
+}
+
+impl MsfHeader {
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader::new(data);
+        reader.seek(msf::BIG_MSF_MAGIC.len());
+
+        Ok(MsfHeader {
+            page_size: reader.u32()? as usize,
+            free_page_map: reader.u32()?,
+            page_count: reader.u32()? as usize,
+            directory_size: reader.u32()? as usize,
+        })
+    }
+}
+
+/// The bounds of the TPI or IPI stream's type indices, and where each type's record starts
+#[derive(Debug, Clone, Default)]
+struct TypeStreamLayout {
+    begin: u32,
+    end: u32,
+    record_offsets: Vec<usize>,
+}
+
+/// A TPI record as the `pdb` crate reads it: its type index, its kind, and the
+/// type indices it refers to or why it can't be parsed
+type TypeRecord = (u32, u16, Result<Vec<TypeIndex>, pdb::Error>);
+
+#[derive(Debug, Default)]
+struct Verifier {
+    issues: Vec<Issue>,
+}
+
+impl Verifier {
+    fn report(&mut self, stream: Option<u32>, offset: Option<usize>, description: String) {
+        self.issues.push(Issue {
+            stream,
+            offset,
+            kind: None,
+            description,
+        });
+    }
+
+    /// Reports a problem with the record of kind `kind` at `offset`
+    fn report_record(
+        &mut self,
+        stream: Option<u32>,
+        offset: Option<usize>,
+        kind: u16,
+        description: String,
+    ) {
+        self.issues.push(Issue {
+            stream,
+            offset,
+            kind: Some(kind),
+            description,
+        });
+    }
+
+    /// Checks the MSF header and stream directory, returning the contents of
+    /// every stream. Streams which don't exist or can't be read in full are `None`.
+    fn read_streams(&mut self, data: &[u8]) -> Option<Vec<Option<Vec<u8>>>> {
+        if !data.starts_with(msf::BIG_MSF_MAGIC) {
+            self.report(
+                None,
+                Some(0),
+                "the file doesn't start with an MSF 7.00 signature".to_string(),
+            );
+            return None;
+        }
+
+        let header = match MsfHeader::parse(data) {
+            Ok(header) if data.len() >= MSF_HEADER_SIZE => header,
+            _ => {
+                self.report(
+                    None,
+                    Some(data.len()),
+                    "the file is truncated within the MSF header".to_string(),
+                );
+                return None;
+            }
+        };
+
+        let page_size = header.page_size;
+        if !page_size.is_power_of_two() || !(0x100..=128 * 0x10000).contains(&page_size) {
+            self.report(
+                None,
+                Some(msf::BIG_MSF_MAGIC.len()),
+                format!("the page size {} is invalid", page_size),
+            );
+            return None;
+        }
+
+        if header.free_page_map != 1 && header.free_page_map != 2 {
+            self.report(
+                None,
+                Some(msf::BIG_MSF_MAGIC.len() + 4),
+                format!(
+                    "the free page map is page {} rather than 1 or 2",
+                    header.free_page_map
+                ),
+            );
+        }
+
+        let expected_len = header.page_count * page_size;
+        if data.len() < expected_len {
+            self.report(
+                None,
+                Some(data.len()),
+                format!(
+                    "the file is {} bytes but its header describes {} pages of {} bytes",
+                    data.len(),
+                    header.page_count,
+                    page_size
+                ),
+            );
+        }
+
+        // The header lists the pages which list the stream directory's pages
+        let directory_page_count = header.directory_size.div_ceil(page_size);
+        let page_list_page_count = (directory_page_count * 4).div_ceil(page_size);
+        if MSF_HEADER_SIZE + page_list_page_count * 4 > page_size {
+            self.report(
+                None,
+                Some(msf::BIG_MSF_MAGIC.len() + 12),
+                format!(
+                    "the stream directory's size of {} bytes doesn't fit in the header",
+                    header.directory_size
+                ),
+            );
+            return None;
+        }
+
+        let mut reader = ByteReader::new(data);
+        reader.seek(MSF_HEADER_SIZE);
+        let page_list_pages = (0..page_list_page_count)
+            .map(|_| reader.u32())
+            .collect::<Result<Vec<u32>, Error>>()
+            .ok()?;
+
+        let mut owners = HashMap::new();
+        let page_list = self.read_pages(
+            data,
+            &header,
+            &page_list_pages,
+            directory_page_count * 4,
+            None,
+            &mut owners,
+        )?;
+        let mut reader = ByteReader::new(&page_list);
+        let directory_pages = (0..directory_page_count)
+            .map(|_| reader.u32())
+            .collect::<Result<Vec<u32>, Error>>()
+            .ok()?;
+        let directory = self.read_pages(
+            data,
+            &header,
+            &directory_pages,
+            header.directory_size,
+            None,
+            &mut owners,
+        )?;
+
+        self.read_directory(data, &header, &directory, &mut owners)
+    }
+
+    /// Reads every stream listed in the stream `directory`
+    fn read_directory(
+        &mut self,
+        data: &[u8],
+        header: &MsfHeader,
+        directory: &[u8],
+        owners: &mut HashMap<u32, Option<u32>>,
+    ) -> Option<Vec<Option<Vec<u8>>>> {
+        let mut reader = ByteReader::new(directory);
+        let truncated = |verifier: &mut Self, reader: &ByteReader<'_>| {
+            verifier.report(
+                None,
+                None,
+                format!(
+                    "the stream directory is truncated at offset 0x{:x}",
+                    reader.position()
+                ),
+            );
+        };
+
+        let stream_count = match reader.u32() {
+            Ok(count) => count,
+            Err(_) => {
+                truncated(self, &reader);
+                return None;
+            }
+        };
+        if stream_count as usize > reader.remaining() / 4 {
+            self.report(
+                None,
+                None,
+                format!(
+                    "the stream directory lists {} streams but is only {} bytes",
+                    stream_count,
+                    directory.len()
+                ),
+            );
+            return None;
+        }
+        let sizes: Vec<u32> = (0..stream_count)
+            .map(|_| reader.u32().expect("checked against the directory's size"))
+            .collect();
+
+        let mut streams = Vec::with_capacity(sizes.len());
+        for (index, &size) in sizes.iter().enumerate() {
+            if size == u32::MAX {
+                streams.push(None);
+                continue;
+            }
+
+            let page_count = (size as usize).div_ceil(header.page_size);
+            let pages = match (0..page_count)
+                .map(|_| reader.u32())
+                .collect::<Result<Vec<u32>, Error>>()
+            {
+                Ok(pages) => pages,
+                Err(_) => {
+                    truncated(self, &reader);
+                    return Some(streams);
+                }
+            };
+
+            streams.push(self.read_pages(
+                data,
+                header,
+                &pages,
+                size as usize,
+                Some(index as u32),
+                owners,
+            ));
+        }
+
+        if reader.remaining() > 0 {
+            self.report(
+                None,
+                None,
+                format!(
+                    "the stream directory has {} bytes past its last stream's pages",
+                    reader.remaining()
+                ),
+            );
+        }
+
+        Some(streams)
+    }
+
+    /// Concatenates `pages` of `data`, truncated to `len` bytes. Pages which are
+    /// out of range, past the end of the file, or already used by another
+    /// stream are reported against `stream`, which is `None` for the stream
+    /// directory. Returns `None` if the stream couldn't be read in full.
+    fn read_pages(
+        &mut self,
+        data: &[u8],
+        header: &MsfHeader,
+        pages: &[u32],
+        len: usize,
+        stream: Option<u32>,
+        owners: &mut HashMap<u32, Option<u32>>,
+    ) -> Option<Vec<u8>> {
+        let page_size = header.page_size;
+        let mut bytes = Vec::with_capacity(len);
+        let mut complete = true;
+        for (i, &page) in pages.iter().enumerate() {
+            let offset = stream.map(|_| i * page_size);
+            if page == 0 || page as usize >= header.page_count {
+                self.report(
+                    stream,
+                    offset,
+                    format!(
+                        "refers to page {} but the file has pages 1 to {}",
+                        page,
+                        header.page_count.saturating_sub(1)
+                    ),
+                );
+                complete = false;
+                continue;
+            }
+
+            if let Some(owner) = owners.insert(page, stream) {
+                let owner = match owner {
+                    Some(owner) => format!("stream {}", owner),
+                    None => "the stream directory".to_string(),
+                };
+                self.report(
+                    stream,
+                    offset,
+                    format!("page {} is also used by {}", page, owner),
+                );
+            }
+
+            let start = page as usize * page_size;
+            let end = (start + page_size).min(data.len());
+            match data.get(start..end) {
+                Some(page) if complete => bytes.extend_from_slice(page),
+                Some(_) => {}
+                None => {
+                    self.report(
+                        stream,
+                        offset,
+                        format!("page {} is past the end of the file", page),
+                    );
+                    complete = false;
+                }
+            }
+        }
+
+        if !complete {
+            return None;
+        }
+
+        if bytes.len() < len {
+            self.report(
+                stream,
+                Some(bytes.len()),
+                format!(
+                    "is {} bytes long but the file ends after {} of them",
+                    len,
+                    bytes.len()
+                ),
+            );
+            return None;
+        }
+        bytes.truncate(len);
+
+        Some(bytes)
+    }
+
+    /// Checks the contents of the PDB's streams
+    fn verify_streams(&mut self, streams: &[Option<Vec<u8>>], data: &[u8]) {
+        let stream = |index: u16| streams.get(index as usize).and_then(Option::as_deref);
+
+        let information = match stream(PDB_STREAM) {
+            Some(info) if info.len() < PDB_INFORMATION_HEADER_SIZE => {
+                self.report(
+                    Some(PDB_STREAM.into()),
+                    Some(info.len()),
+                    "the PDB information stream is truncated within its header".to_string(),
+                );
+                None
+            }
+            Some(info) => match PdbInformation::parse(info) {
+                Ok(information) => Some(information),
+                Err(_) => {
+                    self.report(
+                        Some(PDB_STREAM.into()),
+                        Some(PDB_INFORMATION_HEADER_SIZE),
+                        "the named stream map is truncated".to_string(),
+                    );
+                    None
+                }
+            },
+            None => {
+                self.report(
+                    Some(PDB_STREAM.into()),
+                    None,
+                    "the PDB information stream is missing".to_string(),
+                );
+                None
+            }
+        };
+
+        for (name, index) in information.iter().flat_map(|info| &info.named_streams) {
+            if *index as usize >= streams.len() {
+                self.report(
+                    Some(PDB_STREAM.into()),
+                    None,
+                    format!("named stream `{}` refers to missing stream {}", name, index),
+                );
+            }
+        }
+
+        let string_table_size = information
+            .as_ref()
+            .and_then(|info| info.named_streams.iter().find(|(name, _)| name == "/names"))
+            .and_then(|&(_, index)| {
+                let index = index as u16;
+                self.verify_string_table(index, stream(index)?)
+            });
+
+        let tpi = self.verify_type_stream(TPI_STREAM, stream(TPI_STREAM), streams.len());
+        self.verify_type_stream(IPI_STREAM, stream(IPI_STREAM), streams.len());
+        self.verify_debug_information(streams, string_table_size);
+
+        if let (Some(layout), Some(tpi)) = (tpi, stream(TPI_STREAM)) {
+            self.verify_type_indices(data, tpi, &layout);
+        }
+    }
+
+    /// Checks the header of the `/names` string table, returning the size of its strings
+    fn verify_string_table(&mut self, index: u16, data: &[u8]) -> Option<usize> {
+        let mut reader = ByteReader::new(data);
+        let header = (|| -> Result<(u32, usize), Error> {
+            let signature = reader.u32()?;
+            let _version = reader.u32()?;
+            let size = reader.u32()? as usize;
+            Ok((signature, size))
+        })();
+
+        match header {
+            Ok((STRING_TABLE_SIGNATURE, size)) if size <= data.len() - 12 => Some(size),
+            Ok((STRING_TABLE_SIGNATURE, size)) => {
+                self.report(
+                    Some(index.into()),
+                    Some(12),
+                    format!(
+                        "the string table is {} bytes but the stream only has room for {}",
+                        size,
+                        data.len() - 12
+                    ),
+                );
+                None
+            }
+            Ok((signature, _)) => {
+                self.report(
+                    Some(index.into()),
+                    Some(0),
+                    format!("the string table's signature is 0x{:x}", signature),
+                );
+                None
+            }
+            Err(_) => {
+                self.report(
+                    Some(index.into()),
+                    Some(data.len()),
+                    "the string table is truncated within its header".to_string(),
+                );
+                None
+            }
+        }
+    }
+
+    /// Checks the header and record lengths of the TPI or IPI stream `index`
+    fn verify_type_stream(
+        &mut self,
+        index: u16,
+        data: Option<&[u8]>,
+        stream_count: usize,
+    ) -> Option<TypeStreamLayout> {
+        let stream = Some(u32::from(index));
+        let data = data?;
+        if data.len() < TPI_HEADER_SIZE {
+            self.report(
+                stream,
+                Some(data.len()),
+                "the stream is truncated within its header".to_string(),
+            );
+            return None;
+        }
+
+        let mut reader = ByteReader::new(data);
+        let header = (|| -> Result<_, Error> {
+            let _version = reader.u32()?;
+            let header_size = reader.u32()? as usize;
+            let begin = reader.u32()?;
+            let end = reader.u32()?;
+            let record_bytes = reader.u32()? as usize;
+            let hash_stream = reader.u16()?;
+            Ok((header_size, begin, end, record_bytes, hash_stream))
+        })()
+        .expect("checked against the header's size");
+        let (header_size, begin, end, record_bytes, hash_stream) = header;
+
+        if hash_stream != NO_STREAM && hash_stream as usize >= stream_count {
+            self.report(
+                stream,
+                Some(20),
+                format!("the hash stream {} doesn't exist", hash_stream),
+            );
+        }
+
+        if begin > end {
+            self.report(
+                stream,
+                Some(8),
+                format!(
+                    "the first type index 0x{:x} is after the last 0x{:x}",
+                    begin, end
+                ),
+            );
+            return None;
+        }
+
+        let records_end = header_size + record_bytes;
+        if records_end > data.len() {
+            self.report(
+                stream,
+                Some(data.len()),
+                format!(
+                    "the header describes {} bytes of records after {} bytes of header, but the stream is {} bytes",
+                    record_bytes,
+                    header_size,
+                    data.len()
+                ),
+            );
+            return None;
+        }
+
+        let mut record_offsets = vec![];
+        let mut reader = ByteReader::new(&data[..records_end]);
+        reader.seek(header_size);
+        while reader.remaining() > 0 {
+            let offset = reader.position();
+            let len = match reader.u16() {
+                Ok(len) => len as usize,
+                Err(_) => {
+                    self.report(
+                        stream,
+                        Some(offset),
+                        "a record is truncated within its length".to_string(),
+                    );
+                    break;
+                }
+            };
+            if reader.take(len).is_err() {
+                self.report(
+                    stream,
+                    Some(offset),
+                    format!("a record of {} bytes runs past the end of the records", len),
+                );
+                break;
+            }
+            record_offsets.push(offset);
+        }
+
+        let expected = (end - begin) as usize;
+        if record_offsets.len() != expected {
+            self.report(
+                stream,
+                Some(header_size),
+                format!(
+                    "the header describes {} records but {} were found",
+                    expected,
+                    record_offsets.len()
+                ),
+            );
+        }
+
+        Some(TypeStreamLayout {
+            begin,
+            end,
+            record_offsets,
+        })
+    }
+
+    /// Checks the DBI stream's header and module list, and each module's stream
+    fn verify_debug_information(
+        &mut self,
+        streams: &[Option<Vec<u8>>],
+        string_table_size: Option<usize>,
+    ) {
+        let stream = Some(u32::from(DBI_STREAM));
+        let data = match streams.get(DBI_STREAM as usize).and_then(Option::as_deref) {
+            Some(data) => data,
+            None => return,
+        };
+        if data.len() < DBI_HEADER_SIZE {
+            self.report(
+                stream,
+                Some(data.len()),
+                "the stream is truncated within its header".to_string(),
+            );
+            return;
+        }
+
+        let mut reader = ByteReader::new(data);
+        let header = (|| -> Result<_, Error> {
+            let signature = reader.u32()?;
+            let _version = reader.u32()?;
+            let _age = reader.u32()?;
+            let global_symbols = reader.u16()?;
+            let _build = reader.u16()?;
+            let public_symbols = reader.u16()?;
+            let _pdb_dll_version = reader.u16()?;
+            let symbol_records = reader.u16()?;
+            let _pdb_dll_rebuild = reader.u16()?;
+            let mut sizes = [0; 5];
+            for size in &mut sizes {
+                *size = reader.u32()?;
+            }
+            let _mfc_type_server = reader.u32()?;
+            let debug_header_size = reader.u32()?;
+            let ec_size = reader.u32()?;
+            Ok((
+                signature,
+                [global_symbols, public_symbols, symbol_records],
+                sizes,
+                debug_header_size,
+                ec_size,
+            ))
+        })()
+        .expect("checked against the header's size");
+        let (signature, symbol_streams, sizes, debug_header_size, ec_size) = header;
+
+        if signature != u32::MAX {
+            self.report(
+                stream,
+                Some(0),
+                format!("the header's signature is 0x{:x}", signature),
+            );
+            return;
+        }
+
+        for (&index, name) in
+            symbol_streams
+                .iter()
+                .zip(&["global symbol", "public symbol", "symbol record"])
+        {
+            if index != NO_STREAM && !matches!(streams.get(index as usize), Some(Some(_))) {
+                self.report(
+                    stream,
+                    None,
+                    format!("the {} stream {} doesn't exist", name, index),
+                );
+            }
+        }
+        if let Some(records) = streams
+            .get(symbol_streams[2] as usize)
+            .and_then(Option::as_deref)
+        {
+            self.verify_symbol_records(symbol_streams[2], records, 0);
+        }
+
+        // Module list, section contributions, section map, file info, and
+        // type server map, then the EC substream, then the optional debug header
+        let [module_list_size, ..] = sizes;
+        let total = sizes
+            .iter()
+            .chain(&[ec_size, debug_header_size])
+            .map(|&size| size as usize)
+            .sum::<usize>();
+        if DBI_HEADER_SIZE + total > data.len() {
+            self.report(
+                stream,
+                Some(data.len()),
+                format!(
+                    "the substreams total {} bytes but the stream only has room for {}",
+                    total,
+                    data.len() - DBI_HEADER_SIZE
+                ),
+            );
+            return;
+        }
+
+        let debug_header_start = DBI_HEADER_SIZE + total - debug_header_size as usize;
+        let mut reader = ByteReader::new(&data[..DBI_HEADER_SIZE + total]);
+        reader.seek(debug_header_start);
+        while let Ok(index) = reader.u16() {
+            if index != NO_STREAM && !matches!(streams.get(index as usize), Some(Some(_))) {
+                self.report(
+                    stream,
+                    Some(reader.position() - 2),
+                    format!(
+                        "the optional debug header refers to missing stream {}",
+                        index
+                    ),
+                );
+            }
+        }
+
+        let module_list = &data[DBI_HEADER_SIZE..DBI_HEADER_SIZE + module_list_size as usize];
+        self.verify_modules(streams, module_list, string_table_size);
+    }
+
+    /// Checks each entry of the DBI stream's module list, and the module stream it refers to
+    fn verify_modules(
+        &mut self,
+        streams: &[Option<Vec<u8>>],
+        module_list: &[u8],
+        string_table_size: Option<usize>,
+    ) {
+        let dbi = Some(u32::from(DBI_STREAM));
+        let mut reader = ByteReader::new(module_list);
+        while reader.remaining() > 0 {
+            let offset = DBI_HEADER_SIZE + reader.position();
+            let module = (|| -> Result<_, Error> {
+                let mut fields = ByteReader::new(reader.take(MODULE_INFO_SIZE)?);
+                fields.seek(34);
+                let index = fields.u16()?;
+                let symbols_size = fields.u32()? as usize;
+                let lines_size = fields.u32()? as usize;
+                let c13_lines_size = fields.u32()? as usize;
+                let name = reader.cstring()?;
+                let _object_file_name = reader.cstring()?;
+                reader.seek(reader.position().div_ceil(4) * 4);
+                Ok((index, symbols_size, lines_size, c13_lines_size, name))
+            })();
+
+            let (index, symbols_size, lines_size, c13_lines_size, name) = match module {
+                Ok(module) => module,
+                Err(_) => {
+                    self.report(
+                        dbi,
+                        Some(offset),
+                        "the module list is truncated within an entry".to_string(),
+                    );
+                    return;
+                }
+            };
+
+            if index == NO_STREAM {
+                continue;
+            }
+            let data = match streams.get(index as usize) {
+                Some(Some(data)) => data,
+                Some(None) => continue,
+                None => {
+                    self.report(
+                        dbi,
+                        Some(offset),
+                        format!("module `{}` refers to missing stream {}", name, index),
+                    );
+                    continue;
+                }
+            };
+
+            let module_stream = Some(u32::from(index));
+            let total = symbols_size + lines_size + c13_lines_size;
+            if total > data.len() {
+                self.report(
+                    module_stream,
+                    Some(data.len()),
+                    format!(
+                        "module `{}` describes {} bytes of symbols and lines but its stream is {} bytes",
+                        name,
+                        total,
+                        data.len()
+                    ),
+                );
+                continue;
+            }
+
+            match ByteReader::new(data).u32() {
+                Ok(C13_SIGNATURE) => {}
+                Ok(_) if symbols_size == 0 => {}
+                Ok(signature) => {
+                    self.report(
+                        module_stream,
+                        Some(0),
+                        format!(
+                            "module `{}` has signature {} rather than 4",
+                            name, signature
+                        ),
+                    );
+                    continue;
+                }
+                Err(_) => continue,
+            }
+
+            self.verify_symbol_records(index, &data[..symbols_size], 4);
+
+            let c13_start = symbols_size + lines_size;
+            self.verify_c13_lines(
+                index,
+                &data[..c13_start + c13_lines_size],
+                c13_start,
+                string_table_size,
+            );
+        }
+    }
+
+    /// Checks that each symbol record of stream `index`, starting at `start`, fits within `data`
+    fn verify_symbol_records(&mut self, index: u16, data: &[u8], start: usize) {
+        let mut reader = ByteReader::new(data);
+        reader.seek(start);
+        while reader.remaining() > 0 {
+            let offset = reader.position();
+            let len = match reader.u16() {
+                Ok(len) => len as usize,
+                Err(_) => {
+                    self.report(
+                        Some(index.into()),
+                        Some(offset),
+                        "a symbol is truncated within its length".to_string(),
+                    );
+                    return;
+                }
+            };
+            if reader.take(len).is_err() {
+                self.report(
+                    Some(index.into()),
+                    Some(offset),
+                    format!("a symbol of {} bytes runs past the end of the symbols", len),
+                );
+                return;
+            }
+        }
+    }
+
+    /// Checks the C13 line information subsections of module stream `index`,
+    /// which start at `start` and end with `data`, and that file checksums'
+    /// names are within the string table
+    fn verify_c13_lines(
+        &mut self,
+        index: u16,
+        data: &[u8],
+        start: usize,
+        string_table_size: Option<usize>,
+    ) {
+        let stream = Some(u32::from(index));
+        let mut reader = ByteReader::new(data);
+        reader.seek(start);
+        while reader.remaining() > 0 {
+            let offset = reader.position();
+            let subsection = (|| -> Result<_, Error> {
+                let kind = reader.u32()?;
+                let len = reader.u32()? as usize;
+                let contents = reader.take(len)?;
+                reader.seek(reader.position().div_ceil(4) * 4);
+                Ok((kind, contents))
+            })();
+
+            let (kind, contents) = match subsection {
+                Ok(subsection) => subsection,
+                Err(_) => {
+                    self.report(
+                        stream,
+                        Some(offset),
+                        "a line information subsection runs past the end of the stream".to_string(),
+                    );
+                    return;
+                }
+            };
+
+            if kind != DEBUG_S_FILECHKSMS {
+                continue;
+            }
+            let string_table_size = match string_table_size {
+                Some(size) => size,
+                None => continue,
+            };
+
+            let mut checksums = ByteReader::new(contents);
+            while checksums.remaining() > 0 {
+                let checksum_offset = offset + 8 + checksums.position();
+                let checksum = (|| -> Result<_, Error> {
+                    let name = checksums.u32()? as usize;
+                    let len = checksums.u8()?;
+                    let _kind = checksums.u8()?;
+                    checksums.take(len.into())?;
+                    checksums.seek(checksums.position().div_ceil(4) * 4);
+                    Ok(name)
+                })();
+
+                match checksum {
+                    Ok(name) if name >= string_table_size => {
+                        self.report(
+                            stream,
+                            Some(checksum_offset),
+                            format!(
+                                "a file's name is at offset 0x{:x} of the string table, which is only 0x{:x} bytes",
+                                name, string_table_size
+                            ),
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(_) => {
+                        self.report(
+                            stream,
+                            Some(checksum_offset),
+                            "a file checksum runs past the end of its subsection".to_string(),
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks that the types of the TPI stream `tpi` can be read like parsing
+    /// reads them, and only refer to primitive types and to types within the
+    /// stream's bounds
+    fn verify_type_indices(&mut self, data: &[u8], tpi: &[u8], layout: &TypeStreamLayout) {
+        let stream = Some(u32::from(TPI_STREAM));
+        let result = (|| -> Result<Vec<TypeRecord>, Error> {
+            let mut pdb = pdb::PDB::open(std::io::Cursor::new(data))?;
+            let type_information = pdb.type_information()?;
+            let mut iter = type_information.iter();
+            let mut records = vec![];
+            while let Some(ty) = iter.next()? {
+                let references = ty.parse().map(|data| referenced_type_indices(&data));
+                records.push((ty.index().0, ty.raw_kind(), references));
+            }
+            Ok(records)
+        })();
+
+        let records = match result {
+            Ok(records) => records,
+            Err(e) => {
+                self.report(stream, None, format!("the types can't be read: {}", e));
+                return;
+            }
+        };
+
+        // Only holds what the fallback decoders read
+        let mut scratch = ParsedPdb::new(PathBuf::new());
+        for (ty, kind, references) in records {
+            let offset = ty
+                .checked_sub(layout.begin)
+                .and_then(|i| layout.record_offsets.get(i as usize))
+                .copied();
+            let indices = match references {
+                Ok(indices) => indices,
+                // Parsing falls back to reading these records itself
+                Err(pdb::Error::UnimplementedTypeKind(_)) => vec![],
+                Err(e) => {
+                    self.report_record(
+                        stream,
+                        offset,
+                        kind,
+                        format!("type 0x{:x} can't be parsed: {}", ty, e),
+                    );
+                    continue;
+                }
+            };
+
+            if let Some(offset) = offset {
+                let raw = (|| -> Result<(), Error> {
+                    let mut reader = ByteReader::new(&tpi[offset..]);
+                    let len = reader.u16()?;
+                    let mut record = ByteReader::new(reader.take(len as usize)?);
+                    let kind = record.u16()?;
+                    crate::read_raw_type_record(kind, &mut record, ty, &mut scratch)
+                })();
+                if let Err(e) = raw {
+                    self.report_record(
+                        stream,
+                        Some(offset),
+                        kind,
+                        format!("could not read the raw type record: {}", e),
+                    );
+                }
+            }
+
+            for index in indices {
+                if index.0 >= FIRST_TYPE_INDEX && !(layout.begin..layout.end).contains(&index.0) {
+                    self.report_record(
+                        stream,
+                        offset,
+                        kind,
+                        format!(
+                            "type 0x{:x} refers to type 0x{:x}, outside of 0x{:x} to 0x{:x}",
+                            ty, index.0, layout.begin, layout.end
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns the type indices `data` refers to
+fn referenced_type_indices(data: &TypeData<'_>) -> Vec<TypeIndex> {
+    match data {
+        TypeData::Class(class) => [class.fields, class.derived_from, class.vtable_shape]
+            .iter()
+            .flatten()
+            .copied()
+            .collect(),
+        TypeData::Member(member) => vec![member.field_type],
+        TypeData::MemberFunction(function) => {
+            let mut indices = vec![
+                function.return_type,
+                function.class_type,
+                function.argument_list,
+            ];
+            indices.extend(function.this_pointer_type);
+            indices
+        }
+        TypeData::OverloadedMethod(method) => vec![method.method_list],
+        TypeData::Method(method) => vec![method.method_type],
+        TypeData::StaticMember(member) => vec![member.field_type],
+        TypeData::Nested(nested) => vec![nested.nested_type],
+        TypeData::BaseClass(base) => vec![base.base_class],
+        TypeData::VirtualBaseClass(base) => vec![base.base_class, base.base_pointer],
+        TypeData::VirtualFunctionTablePointer(pointer) => vec![pointer.table],
+        TypeData::Procedure(procedure) => {
+            let mut indices = vec![procedure.argument_list];
+            indices.extend(procedure.return_type);
+            indices
+        }
+        TypeData::Pointer(pointer) => {
+            let mut indices = vec![pointer.underlying_type];
+            indices.extend(pointer.containing_class);
+            indices
+        }
+        TypeData::Modifier(modifier) => vec![modifier.underlying_type],
+        TypeData::Enumeration(e) => vec![e.underlying_type, e.fields],
+        TypeData::Array(array) => vec![array.element_type, array.indexing_type],
+        TypeData::Union(union) => vec![union.fields],
+        TypeData::Bitfield(bitfield) => vec![bitfield.underlying_type],
+        TypeData::FieldList(list) => {
+            let mut indices: Vec<TypeIndex> = list
+                .fields
+                .iter()
+                .flat_map(referenced_type_indices)
+                .collect();
+            indices.extend(list.continuation);
+            indices
+        }
+        TypeData::ArgumentList(list) => list.arguments.clone(),
+        TypeData::MethodList(list) => list.methods.iter().map(|m| m.method_type).collect(),
+        _ => vec![],
+    }
+}
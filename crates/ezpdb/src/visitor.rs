@@ -0,0 +1,266 @@
+//! A visitor-based alternative to [crate::parse_pdb] which hands each item to the
+//! caller as soon as it's converted instead of collecting everything into a
+//! [ParsedPdb]. Consumers who only need some of a PDB's contents can skip the
+//! rest, or stop parsing entirely once they've found what they're looking for.
+use crate::address::AddressMapping;
+use crate::convert::{IntoModel, TryIntoModel};
+use crate::error::Error;
+use crate::symbol_types::{Data, DebugModule, ParsedPdb, Procedure, PublicSymbol};
+use crate::type_info::TypeId;
+use crate::ParseOptions;
+use pdb::{FallibleIterator, SymbolData};
+use std::path::Path;
+use tracing::warn;
+
+/// Tells [parse_with_visitor] how to proceed after an item has been visited
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VisitAction {
+    /// Keep visiting items
+    Continue,
+    /// Don't visit any more items of this kind. Other kinds of items are still visited.
+    Skip,
+    /// Stop parsing immediately
+    Stop,
+}
+
+/// Receives items from [parse_with_visitor] as they're parsed. All methods
+/// default to [VisitAction::Skip] so implementors only need to handle the
+/// items they care about; anything left unimplemented is not parsed beyond the
+/// first item of its kind.
+///
+/// Items are visited in the following order: types, then public symbols,
+/// procedures, and globals from the global symbol stream, then each debug
+/// module followed by the procedures and globals in its symbol stream.
+pub trait PdbVisitor {
+    /// Called with each type in the TPI stream. `pdb` holds every type converted
+    /// so far, which includes all types that `id` refers to. Forward references
+    /// are not yet resolved, so e.g. a class's size may be zero if only its
+    /// forward declaration has been seen.
+    fn on_type(&mut self, _pdb: &ParsedPdb, _id: TypeId) -> VisitAction {
+        VisitAction::Skip
+    }
+
+    fn on_public_symbol(&mut self, _symbol: PublicSymbol) -> VisitAction {
+        VisitAction::Skip
+    }
+
+    fn on_procedure(&mut self, _procedure: Procedure) -> VisitAction {
+        VisitAction::Skip
+    }
+
+    /// Called with each global variable. `pdb` holds at least the global's type.
+    fn on_global_data(&mut self, _pdb: &ParsedPdb, _data: Data) -> VisitAction {
+        VisitAction::Skip
+    }
+
+    fn on_debug_module(&mut self, _module: DebugModule) -> VisitAction {
+        VisitAction::Skip
+    }
+}
+
+impl<V: PdbVisitor + ?Sized> PdbVisitor for &mut V {
+    fn on_type(&mut self, pdb: &ParsedPdb, id: TypeId) -> VisitAction {
+        (**self).on_type(pdb, id)
+    }
+
+    fn on_public_symbol(&mut self, symbol: PublicSymbol) -> VisitAction {
+        (**self).on_public_symbol(symbol)
+    }
+
+    fn on_procedure(&mut self, procedure: Procedure) -> VisitAction {
+        (**self).on_procedure(procedure)
+    }
+
+    fn on_global_data(&mut self, pdb: &ParsedPdb, data: Data) -> VisitAction {
+        (**self).on_global_data(pdb, data)
+    }
+
+    fn on_debug_module(&mut self, module: DebugModule) -> VisitAction {
+        (**self).on_debug_module(module)
+    }
+}
+
+/// The kinds of items the visitor still wants to see
+struct Wanted {
+    public_symbols: bool,
+    procedures: bool,
+    global_data: bool,
+    debug_modules: bool,
+}
+
+impl Wanted {
+    fn any_symbols(&self) -> bool {
+        self.public_symbols || self.procedures || self.global_data
+    }
+}
+
+/// Records `action` against the kind of item tracked by `wanted`, returning
+/// whether parsing should stop
+fn apply(wanted: &mut bool, action: VisitAction) -> bool {
+    match action {
+        VisitAction::Continue => false,
+        VisitAction::Skip => {
+            *wanted = false;
+            false
+        }
+        VisitAction::Stop => true,
+    }
+}
+
+/// Parses the PDB at `path`, handing each item to `visitor` as soon as it's been
+/// converted. Unlike [crate::parse_pdb] symbols are never retained. Types are
+/// kept once converted since later items may refer to them.
+pub fn parse_with_visitor<P, V>(
+    path: P,
+    options: &ParseOptions,
+    mut visitor: V,
+) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+    V: PdbVisitor,
+{
+    let base_address = options.base_address;
+    let mut pdb = crate::open_pdb(path.as_ref())?;
+    let address_map = AddressMapping::new(&mut pdb, options);
+    let string_table = pdb.string_table().ok();
+
+    // Only ever holds header information and types
+    let mut output_pdb = ParsedPdb::new(path.as_ref().to_owned());
+    crate::read_header_info(&mut pdb, &mut output_pdb)?;
+    crate::read_raw_type_records(&mut pdb, &mut output_pdb, options, None)?;
+
+    // Procedure signatures and globals are looked up by type index, so the finder
+    // needs to know where every type is even if the visitor skips types
+    let type_information = pdb.type_information()?;
+    let mut type_finder = type_information.finder();
+    let mut iter = type_information.iter();
+    let mut type_indices = vec![];
+    while let Some(typ) = iter.next()? {
+        type_finder.update(&iter);
+        type_indices.push(typ.index());
+    }
+
+    for index in type_indices {
+        let id = match crate::handle_type(index, &mut output_pdb, &type_finder) {
+            Ok(id) => id,
+            Err(Error::PdbCrateError(
+                e @ (pdb::Error::UnimplementedTypeKind(_) | pdb::Error::TypeNotFound(_)),
+            )) => {
+                warn!("Could not parse type: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        match visitor.on_type(&output_pdb, id) {
+            VisitAction::Continue => {}
+            VisitAction::Skip => break,
+            VisitAction::Stop => return Ok(()),
+        }
+    }
+
+    let mut wanted = Wanted {
+        public_symbols: true,
+        procedures: true,
+        global_data: true,
+        debug_modules: true,
+    };
+    let visit_symbol = |symbol: pdb::Symbol<'_>,
+                        visitor: &mut V,
+                        wanted: &mut Wanted,
+                        output_pdb: &mut ParsedPdb|
+     -> bool {
+        match symbol.parse() {
+            Ok(SymbolData::Public(data)) if wanted.public_symbols => apply(
+                &mut wanted.public_symbols,
+                visitor.on_public_symbol((data, base_address, &address_map).into_model()),
+            ),
+            Ok(SymbolData::Procedure(data)) if wanted.procedures => apply(
+                &mut wanted.procedures,
+                visitor.on_procedure((data, base_address, &address_map, &type_finder).into_model()),
+            ),
+            Ok(SymbolData::Data(data)) if wanted.global_data => {
+                // The type may not have been converted yet if the visitor skipped types
+                if let Err(e) = crate::handle_type(data.type_index, output_pdb, &type_finder) {
+                    warn!("Could not parse type of {:?}: {}", symbol, e);
+                    return false;
+                }
+
+                let data: Data = match (data, base_address, &address_map, &output_pdb.type_indices)
+                    .try_into_model()
+                {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!("Error handling symbol {:?}: {}", symbol, e);
+                        return false;
+                    }
+                };
+                data.is_global
+                    && apply(
+                        &mut wanted.global_data,
+                        visitor.on_global_data(output_pdb, data),
+                    )
+            }
+            Ok(_) => false,
+            Err(e) => {
+                warn!("Error handling symbol {:?}: {}", symbol, e);
+                false
+            }
+        }
+    };
+
+    let symbol_table = pdb.global_symbols()?;
+    let mut symbols = symbol_table.iter();
+    while wanted.any_symbols() {
+        let symbol = match symbols.next()? {
+            Some(symbol) => symbol,
+            None => break,
+        };
+
+        if visit_symbol(symbol, &mut visitor, &mut wanted, &mut output_pdb) {
+            return Ok(());
+        }
+    }
+
+    let debug_info = pdb.debug_information()?;
+    let mut modules = debug_info.modules()?;
+    while wanted.debug_modules || wanted.procedures || wanted.global_data {
+        let module = match modules.next()? {
+            Some(module) => module,
+            None => break,
+        };
+        let module_info = pdb.module_info(&module)?;
+
+        if wanted.debug_modules {
+            let converted = (&module, module_info.as_ref(), string_table.as_ref()).into_model();
+            if apply(
+                &mut wanted.debug_modules,
+                visitor.on_debug_module(converted),
+            ) {
+                return Ok(());
+            }
+        }
+
+        let module_info = match module_info {
+            Some(module_info) => module_info,
+            None => {
+                warn!("Could not get module info for debug module: {:?}", module);
+                continue;
+            }
+        };
+
+        let mut symbols = module_info.symbols()?;
+        while wanted.procedures || wanted.global_data {
+            let symbol = match symbols.next()? {
+                Some(symbol) => symbol,
+                None => break,
+            };
+
+            if visit_symbol(symbol, &mut visitor, &mut wanted, &mut output_pdb) {
+                return Ok(());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,284 @@
+//! A reverse index from types to the symbols which refer to them, and the
+//! dependencies of types upon each other
+use crate::intern::Name;
+use crate::search::NamePattern;
+use crate::symbol_types::{Data, ParsedPdb, Procedure};
+use crate::type_info::{Type, TypeId, TypeProperties};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Maps the names of classes, unions, and enums to the symbols whose types
+/// refer to them, whether directly or through pointers, arrays, modifiers, or
+/// function signatures. Types are indexed by name so that references to a
+/// forward declaration are found along with references to its definition.
+#[derive(Debug, Default)]
+pub struct TypeReferenceIndex {
+    /// Indices into [ParsedPdb::procedures] of procedures whose signature refers to the type
+    procedures: HashMap<Name, Vec<usize>>,
+    /// Indices into [ParsedPdb::global_data]
+    global_data: HashMap<Name, Vec<usize>>,
+    /// Indices into [ParsedPdb::local_data]
+    local_data: HashMap<Name, Vec<usize>>,
+}
+
+/// The symbols which refer to a type
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TypeReferences<'a> {
+    pub procedures: Vec<&'a Procedure>,
+    pub global_data: Vec<&'a Data>,
+    pub local_data: Vec<&'a Data>,
+}
+
+impl TypeReferenceIndex {
+    pub fn new(pdb: &ParsedPdb) -> Self {
+        let mut index = TypeReferenceIndex::default();
+
+        for (i, procedure) in pdb.procedures.iter().enumerate() {
+            if let Some(ty) = pdb.type_indices.get(&procedure.type_index) {
+                for name in referenced_names(pdb, *ty) {
+                    index.procedures.entry(name).or_default().push(i);
+                }
+            }
+        }
+
+        for (i, data) in pdb.global_data.iter().enumerate() {
+            for name in referenced_names(pdb, data.ty) {
+                index.global_data.entry(name).or_default().push(i);
+            }
+        }
+
+        for (i, data) in pdb.local_data.iter().enumerate() {
+            for name in referenced_names(pdb, data.ty) {
+                index.local_data.entry(name).or_default().push(i);
+            }
+        }
+
+        index
+    }
+
+    /// Returns the symbols of `pdb` which refer to the class, union, or enum
+    /// named `type_name`. `pdb` must be the PDB this index was built from.
+    pub fn find<'a>(&self, pdb: &'a ParsedPdb, type_name: &str) -> TypeReferences<'a> {
+        let lookup = |map: &HashMap<Name, Vec<usize>>| -> Vec<usize> {
+            map.get(type_name).cloned().unwrap_or_default()
+        };
+
+        TypeReferences {
+            procedures: lookup(&self.procedures)
+                .into_iter()
+                .map(|i| &pdb.procedures[i])
+                .collect(),
+            global_data: lookup(&self.global_data)
+                .into_iter()
+                .map(|i| &pdb.global_data[i])
+                .collect(),
+            local_data: lookup(&self.local_data)
+                .into_iter()
+                .map(|i| &pdb.local_data[i])
+                .collect(),
+        }
+    }
+
+    /// Returns the number of symbols which refer to the type named `type_name`
+    fn count(&self, type_name: &str) -> usize {
+        [&self.procedures, &self.global_data, &self.local_data]
+            .iter()
+            .map(|map| map.get(type_name).map_or(0, Vec::len))
+            .sum()
+    }
+}
+
+/// Returns the names of the classes, unions, and enums `ty` refers to, without duplicates
+fn referenced_names(pdb: &ParsedPdb, ty: TypeId) -> Vec<Name> {
+    let mut names = vec![];
+    let mut pending = vec![ty];
+    while let Some(ty) = pending.pop() {
+        match named_type(&pdb.types[ty]) {
+            Some((name, _, _)) => names.push(name.clone()),
+            None => pending.extend(pdb.types[ty].referenced_types()),
+        }
+    }
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// How widely a class, union, or enum is used, and how much it depends upon
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypeMetrics {
+    pub ty: TypeId,
+    pub name: Name,
+    /// The number of other classes, unions, and enums, plus procedures and
+    /// variables, which refer to the type
+    pub references: usize,
+    /// The number of classes, unions, and enums the type depends upon, directly
+    /// or transitively. Types which are only pointed to are included.
+    pub dependencies: usize,
+}
+
+/// Computes [TypeMetrics] for every class, union, and enum defined in `pdb`,
+/// ordered from the most to the least referenced. References to a forward
+/// declaration are counted against its definition.
+pub fn type_metrics(pdb: &ParsedPdb) -> Vec<TypeMetrics> {
+    let mut definitions: HashMap<&str, TypeId> = HashMap::new();
+    for (id, ty) in pdb.types.iter() {
+        if let Some((name, unique_name, properties)) = named_type(ty) {
+            if !properties.forward_reference {
+                definitions.entry(unique_name.unwrap_or(name)).or_insert(id);
+            }
+        }
+    }
+
+    let dependencies: HashMap<TypeId, Vec<TypeId>> = definitions
+        .values()
+        .map(|&id| {
+            let mut direct =
+                referenced_definitions(pdb, &definitions, pdb.types[id].referenced_types());
+            direct.retain(|&dependency| dependency != id);
+            (id, direct)
+        })
+        .collect();
+
+    let mut references: HashMap<TypeId, usize> = HashMap::new();
+    for &dependency in dependencies.values().flatten() {
+        *references.entry(dependency).or_default() += 1;
+    }
+
+    let index = TypeReferenceIndex::new(pdb);
+    let mut metrics: Vec<TypeMetrics> = definitions
+        .values()
+        .map(|&id| {
+            let (name, _, _) = named_type(&pdb.types[id]).expect("definitions are named types");
+            TypeMetrics {
+                ty: id,
+                name: name.clone(),
+                references: references.get(&id).copied().unwrap_or(0) + index.count(name),
+                dependencies: closure_size(&dependencies, id),
+            }
+        })
+        .collect();
+
+    metrics.sort_by(|a, b| {
+        b.references
+            .cmp(&a.references)
+            .then(b.dependencies.cmp(&a.dependencies))
+            .then_with(|| a.name.cmp(&b.name))
+            .then(a.ty.cmp(&b.ty))
+    });
+    metrics
+}
+
+/// Limits on which dependencies [dependency_closure] follows
+#[derive(Debug, Default, Clone)]
+pub struct ClosureOptions {
+    /// Leave out types whose names match any of these patterns, e.g. `std::*`,
+    /// along with the types only they depend upon. The roots are always
+    /// included.
+    pub exclude: Vec<NamePattern>,
+    /// Only follow dependencies this many levels from the roots, so that `0`
+    /// only includes the roots themselves
+    pub max_depth: Option<usize>,
+}
+
+/// Returns the definitions of the classes, unions, and enums `roots` depend
+/// upon, directly or transitively, including types which are only pointed to
+/// if their definitions were parsed. The roots come first, followed by the
+/// dependencies in breadth-first order.
+pub fn dependency_closure(
+    pdb: &ParsedPdb,
+    roots: &[TypeId],
+    options: &ClosureOptions,
+) -> Vec<TypeId> {
+    let mut definitions: HashMap<&str, TypeId> = HashMap::new();
+    for (id, ty) in pdb.types.iter() {
+        if let Some((name, unique_name, properties)) = named_type(ty) {
+            if !properties.forward_reference {
+                definitions.entry(unique_name.unwrap_or(name)).or_insert(id);
+            }
+        }
+    }
+
+    let is_excluded = |id: TypeId| match named_type(&pdb.types[id]) {
+        Some((name, _, _)) => options.exclude.iter().any(|pattern| pattern.is_match(name)),
+        None => false,
+    };
+
+    let mut closure = vec![];
+    let mut seen = HashSet::new();
+    let mut pending: VecDeque<(TypeId, usize)> = VecDeque::new();
+    for &root in roots {
+        if seen.insert(root) {
+            pending.push_back((root, 0));
+        }
+    }
+    while let Some((id, depth)) = pending.pop_front() {
+        closure.push(id);
+        if options
+            .max_depth
+            .is_some_and(|max_depth| depth >= max_depth)
+        {
+            continue;
+        }
+
+        let direct = referenced_definitions(pdb, &definitions, pdb.types[id].referenced_types());
+        for dependency in direct {
+            if !is_excluded(dependency) && seen.insert(dependency) {
+                pending.push_back((dependency, depth + 1));
+            }
+        }
+    }
+
+    closure
+}
+
+/// Returns the name, unique name, and properties of a class, union, or enum
+fn named_type(ty: &Type) -> Option<(&Name, Option<&Name>, &TypeProperties)> {
+    match ty {
+        Type::Class(class) => Some((&class.name, class.unique_name.as_ref(), &class.properties)),
+        Type::Union(union) => Some((&union.name, union.unique_name.as_ref(), &union.properties)),
+        Type::Enumeration(e) => Some((&e.name, e.unique_name.as_ref(), &e.properties)),
+        _ => None,
+    }
+}
+
+/// Returns the definitions of the classes, unions, and enums reachable from
+/// `pending` without passing through another class, union, or enum
+fn referenced_definitions(
+    pdb: &ParsedPdb,
+    definitions: &HashMap<&str, TypeId>,
+    mut pending: Vec<TypeId>,
+) -> Vec<TypeId> {
+    let mut found = vec![];
+    while let Some(ty) = pending.pop() {
+        match named_type(&pdb.types[ty]) {
+            Some((name, unique_name, _)) => {
+                found.extend(definitions.get(&**unique_name.unwrap_or(name)));
+            }
+            None => pending.extend(pdb.types[ty].referenced_types()),
+        }
+    }
+
+    found.sort();
+    found.dedup();
+    found
+}
+
+/// Returns the number of definitions `id` depends upon, directly or transitively
+fn closure_size(dependencies: &HashMap<TypeId, Vec<TypeId>>, id: TypeId) -> usize {
+    let mut seen = HashSet::new();
+    seen.insert(id);
+    let mut pending = vec![id];
+    while let Some(id) = pending.pop() {
+        for &dependency in dependencies.get(&id).into_iter().flatten() {
+            if seen.insert(dependency) {
+                pending.push(dependency);
+            }
+        }
+    }
+
+    seen.len() - 1
+}
@@ -0,0 +1,671 @@
+#!/usr/bin/env python3
+"""Writes the tiny PDB fixtures used by the golden output tests.
+
+The fixtures are built record by record rather than by a compiler so that they
+are small, deterministic, and can be regenerated on any machine. Each mimics the
+shape of what a toolchain produces: `msvc-*.pdb` use MSVC's decorated C++ names,
+and `rust-*.pdb` use Rust's mangled names, module names, and language flag.
+`vc*.pdb` are PDB 2.0 files, in the container and stream versions of Visual C++
+6.0 and older, though their records are in today's formats.
+`msvc-truncated.pdb` is `msvc-basic.pdb` cut short.
+`msvc-callbacks.dll` is a PE image linked with `msvc-callbacks.pdb`, with just
+enough code, relocations, and exports for the commands which read images, and
+`msvc-kernel.dll` likewise holds the system call table of `msvc-kernel.pdb`.
+`rust-lld.pdb` and `msvc-llvm.pdb` are the exceptions, written by LLVM and LLD
+from `rust-lld.rs` and `msvc-llvm.ll` as those files describe.
+
+Run from anywhere with `python3 tests/fixtures/generate.py`, then regenerate the
+golden outputs with `PDBVIEW_UPDATE_GOLDEN=1 cargo test --test golden`.
+"""
+import os
+import struct
+
+PAGE_SIZE = 4096
+TEXT_RVA = 0x1000
+DATA_RVA = 0x2000
+FIRST_TYPE_INDEX = 0x1000
+
+# Symbol record kinds
+S_END = 0x0006
+S_OBJNAME = 0x1101
+S_PUB32 = 0x110E
+S_GDATA32 = 0x110D
+S_GPROC32 = 0x1110
+S_PROCREF = 0x1125
+S_COMPILE3 = 0x113C
+S_ENVBLOCK = 0x113D
+S_EXPORT = 0x1138
+
+# Type record kinds
+LF_VTSHAPE = 0x000A
+LF_POINTER = 0x1002
+LF_PROCEDURE = 0x1008
+LF_ARGLIST = 0x1201
+LF_FIELDLIST = 0x1203
+LF_ENUMERATE = 0x1502
+LF_ENUM = 0x1507
+LF_MEMBER = 0x150D
+LF_STRUCTURE = 0x1505
+
+# Primitive type indices
+T_SHORT = 0x11
+T_INT4 = 0x74
+T_REAL32 = 0x40
+
+CV_CFL_RUST = 0x15
+
+# Flags of S_EXPORT records
+EXPORT_DATA = 0x02
+EXPORT_FORWARDER = 0x20
+
+# The GUID and age every fixture PDB is written with
+PDB_GUID = bytes(range(16))
+PDB_AGE = 1
+
+# Layout of the fixture image, whose .text and .data match the PDBs' sections
+IMAGE_BASE = 0x140000000
+RDATA_RVA = 0x3000
+RELOC_RVA = 0x4000
+FILE_ALIGNMENT = 0x200
+IMAGE_DIRECTORY_EXPORT = 0
+IMAGE_DIRECTORY_BASERELOC = 5
+IMAGE_DIRECTORY_DEBUG = 6
+IMAGE_DEBUG_TYPE_CODEVIEW = 2
+IMAGE_REL_BASED_DIR64 = 10
+
+
+def name_hash(data):
+    """The hash used by the global and public symbol hash tables"""
+    result = 0
+    words = len(data) // 4
+    for i in range(words):
+        result ^= struct.unpack_from("<I", data, i * 4)[0]
+    rest = data[words * 4 :]
+    if len(rest) >= 2:
+        result ^= struct.unpack_from("<H", rest, 0)[0]
+        rest = rest[2:]
+    if rest:
+        result ^= rest[0]
+    result |= 0x20202020
+    result ^= result >> 11
+    return (result ^ (result >> 16)) & 0xFFFFFFFF
+
+
+def symbol_record(kind, body):
+    record = struct.pack("<H", kind) + body
+    record += b"\0" * ((-len(record) - 2) % 4)
+    return struct.pack("<H", len(record)) + record
+
+
+def type_padding(length):
+    padding = (-length) % 4
+    return bytes(0xF0 | (padding - i) for i in range(padding))
+
+
+def type_record(kind, body):
+    record = struct.pack("<H", kind) + body
+    record += type_padding(len(record) + 2)
+    return struct.pack("<H", len(record)) + record
+
+
+def member(ty, offset, name):
+    body = struct.pack("<HIH", 3, ty, offset) + name.encode() + b"\0"
+    return struct.pack("<H", LF_MEMBER) + body + type_padding(len(body))
+
+
+def enumerate_(value, name):
+    """An enum variant. Values which don't fit in 15 bits are written as
+    `LF_SHORT` numeric leaves."""
+    if 0 <= value < 0x8000:
+        leaf = struct.pack("<H", value)
+    else:
+        leaf = struct.pack("<Hh", 0x8001, value)
+    body = struct.pack("<H", 3) + leaf + name.encode() + b"\0"
+    return struct.pack("<H", LF_ENUMERATE) + body + type_padding(len(body))
+
+
+def symbol_hash_table(entries):
+    """Builds a GSI hash table over `(record offset, name)` entries"""
+    buckets = {}
+    for offset, name in entries:
+        buckets.setdefault(name_hash(name.encode()) % 4096, []).append(offset)
+
+    records = b""
+    bitmap = [0] * 129
+    bucket_offsets = b""
+    count = 0
+    for bucket in sorted(buckets):
+        bitmap[bucket // 32] |= 1 << (bucket % 32)
+        bucket_offsets += struct.pack("<I", count * 12)
+        for offset in buckets[bucket]:
+            records += struct.pack("<II", offset + 1, 1)
+            count += 1
+
+    body = b"".join(struct.pack("<I", word) for word in bitmap) + bucket_offsets
+    header = struct.pack("<IIII", 0xFFFFFFFF, 0xEFFE0000 + 19990810, len(records), len(body))
+    return header + records + body
+
+
+def type_stream(records):
+    data = b"".join(records)
+    header = struct.pack("<IIIII", 20040203, 56, FIRST_TYPE_INDEX, FIRST_TYPE_INDEX + len(records), len(data))
+    header += struct.pack("<HHII", 0xFFFF, 0xFFFF, 0, 0) + b"\0" * 24
+    return header + data
+
+
+def msf(streams):
+    """Lays `streams` out in an MSF 7.00 container"""
+    file = bytearray(PAGE_SIZE * 3)
+    pages = []
+    for stream in streams:
+        stream_pages = []
+        for i in range((len(stream) + PAGE_SIZE - 1) // PAGE_SIZE):
+            stream_pages.append(len(file) // PAGE_SIZE)
+            file += stream[i * PAGE_SIZE : (i + 1) * PAGE_SIZE].ljust(PAGE_SIZE, b"\0")
+        pages.append(stream_pages)
+
+    directory = struct.pack("<I", len(streams))
+    directory += b"".join(struct.pack("<I", len(stream)) for stream in streams)
+    directory += b"".join(struct.pack("<I", page) for stream_pages in pages for page in stream_pages)
+    directory_page = len(file) // PAGE_SIZE
+    file += directory.ljust(PAGE_SIZE, b"\0")
+    directory_map_page = len(file) // PAGE_SIZE
+    file += struct.pack("<I", directory_page).ljust(PAGE_SIZE, b"\0")
+
+    header = b"Microsoft C/C++ MSF 7.00\r\n\x1aDS\0\0\0"
+    header += struct.pack("<IIIII", PAGE_SIZE, 1, len(file) // PAGE_SIZE, len(directory), 0)
+    header += struct.pack("<I", directory_map_page)
+    file[: len(header)] = header
+    return bytes(file)
+
+
+def small_msf(streams, page_size=1024):
+    """Lays `streams` out in the small MSF container of PDB 2.0 files, as
+    written by Visual C++ 6.0 and older. `None` streams don't exist."""
+    header_size = 64
+    file = bytearray()
+    pages = []
+    for stream in streams:
+        stream_pages = []
+        for i in range((len(stream or b"") + page_size - 1) // page_size):
+            stream_pages.append(1 + len(file) // page_size)
+            file += stream[i * page_size : (i + 1) * page_size].ljust(page_size, b"\0")
+        pages.append(stream_pages)
+
+    directory = struct.pack("<HH", len(streams), 0)
+    directory += b"".join(
+        struct.pack("<Ii", 0xFFFFFFFF if stream is None else len(stream), 0) for stream in streams
+    )
+    directory += b"".join(struct.pack("<H", page) for stream_pages in pages for page in stream_pages)
+    directory_pages = []
+    for i in range((len(directory) + page_size - 1) // page_size):
+        directory_pages.append(1 + len(file) // page_size)
+        file += directory[i * page_size : (i + 1) * page_size].ljust(page_size, b"\0")
+
+    header = b"Microsoft C/C++ program database 2.00\r\n\x1aJG\0\0"
+    header += struct.pack("<IHHII", page_size, 0, 1 + len(file) // page_size, len(directory), 0)
+    header += b"".join(struct.pack("<H", page) for page in directory_pages)
+    assert len(header) <= header_size
+    return bytes(header.ljust(page_size, b"\0") + file)
+
+
+def pdb(
+    *,
+    struct_name,
+    module_name,
+    source_name,
+    procedures,
+    publics,
+    global_name,
+    compile_flags=None,
+    callbacks_name=None,
+    environment=None,
+    exports=None,
+    extra_types=None,
+    small=False,
+    old_dbi=False,
+):
+    """Builds a PDB with one module and one source file, which defines a
+    two-field struct, a global of that struct, and procedures of type
+    `int (struct*)`. `procedures` are `(name, offset)` pairs in the .text
+    section, and `publics` are `(name, offset)` pairs. If `callbacks_name` is
+    given, the PDB also has a writable .data section holding a global of that
+    name whose struct type has a pointer to one of the procedures.
+    `environment` is a list of `(key, value)` pairs for the module's
+    environment block, and `exports` a list of `(name, ordinal, flags)`
+    triples the linker recorded as exported. `extra_types` are type records
+    appended after the others. If `small` is set, the PDB is a PDB 2.0 file
+    with the stream versions of Visual C++ 6.0, and if `old_dbi` is also set
+    its DBI stream has the header of Visual C++ 4.0 and older."""
+    # Global symbols, referenced by the global and public hash tables
+    symbol_records = b""
+    public_entries = []
+    global_entries = []
+
+    def add_symbol(kind, body, name, entries):
+        nonlocal symbol_records
+        entries.append((len(symbol_records), name))
+        symbol_records += symbol_record(kind, body + name.encode() + b"\0")
+
+    add_symbol(S_GDATA32, struct.pack("<IIH", FIRST_TYPE_INDEX + 1, 0x100, 1), global_name, global_entries)
+    if callbacks_name is not None:
+        add_symbol(S_GDATA32, struct.pack("<IIH", FIRST_TYPE_INDEX + 7, 0x10, 2), callbacks_name, global_entries)
+    for name, offset in publics:
+        add_symbol(S_PUB32, struct.pack("<IIH", 2 if offset < 0x100 else 0, offset, 1), name, public_entries)
+    add_symbol(S_PROCREF, struct.pack("<IIH", 0, 0, 1), "private_fn", global_entries)
+
+    global_hashes = symbol_hash_table(global_entries)
+    public_hashes = symbol_hash_table(public_entries)
+    address_map = b"".join(
+        struct.pack("<I", offset)
+        for offset, _name in sorted(
+            public_entries, key=lambda entry: struct.unpack_from("<I", symbol_records, entry[0] + 8)[0]
+        )
+    )
+    public_stream = struct.pack("<IIIIHHII", len(public_hashes), len(address_map), 0, 0, 0, 0, 0, 0)
+    public_stream += public_hashes + address_map
+
+    # Types: the struct, a pointer to it, and the procedures' signature
+    types = [
+        type_record(LF_FIELDLIST, member(T_INT4, 0, "x") + member(T_REAL32, 4, "y")),
+        type_record(LF_STRUCTURE, struct.pack("<HHIIIH", 2, 0, FIRST_TYPE_INDEX, 0, 0, 8) + struct_name.encode() + b"\0"),
+        type_record(LF_POINTER, struct.pack("<II", FIRST_TYPE_INDEX + 1, 0x1000C)),
+        type_record(LF_ARGLIST, struct.pack("<II", 1, FIRST_TYPE_INDEX + 2)),
+        type_record(LF_PROCEDURE, struct.pack("<IBBHI", T_INT4, 0, 0, 1, FIRST_TYPE_INDEX + 3)),
+    ]
+    if callbacks_name is not None:
+        # A struct with a pointer to a procedure of the same signature
+        types += [
+            type_record(LF_POINTER, struct.pack("<II", FIRST_TYPE_INDEX + 4, 0x1000C)),
+            type_record(LF_FIELDLIST, member(FIRST_TYPE_INDEX + 5, 0, "on_close")),
+            type_record(LF_STRUCTURE, struct.pack("<HHIIIH", 1, 0, FIRST_TYPE_INDEX + 6, 0, 0, 8) + b"Callbacks\0"),
+        ]
+    types += extra_types or []
+
+    # The module's symbols, followed by its C13 line information
+    module_symbols = struct.pack("<I", 4)
+    module_symbols += symbol_record(S_OBJNAME, struct.pack("<I", 0) + module_name.encode() + b"\0")
+    if environment is not None:
+        block = b"".join(key.encode() + b"\0" + value.encode() + b"\0" for key, value in environment)
+        module_symbols += symbol_record(S_ENVBLOCK, b"\0" + block + b"\0")
+    for name, ordinal, flags in exports or []:
+        module_symbols += symbol_record(S_EXPORT, struct.pack("<HH", ordinal, flags) + name.encode() + b"\0")
+    if compile_flags is not None:
+        versions = struct.pack("<8H", 1, 0, 0, 0, 1, 0, 0, 0)
+        module_symbols += symbol_record(
+            S_COMPILE3, struct.pack("<IH", compile_flags, 0xD0) + versions + b"fixture\0"
+        )
+    for name, offset in procedures:
+        start = len(module_symbols)
+        procedure = symbol_record(
+            S_GPROC32,
+            struct.pack("<IIIIIIIIHB", 0, 0, 0, 0x20, 0, 0x20, FIRST_TYPE_INDEX + 4, offset, 1, 0)
+            + name.encode()
+            + b"\0",
+        )
+        # Patch in the offset of the matching S_END
+        procedure = procedure[:8] + struct.pack("<I", start + len(procedure)) + procedure[12:]
+        module_symbols += procedure + symbol_record(S_END, b"")
+
+    checksums = struct.pack("<IBB", 1, 0, 0) + b"\0\0"
+    lines = struct.pack("<IHHI", procedures[0][1], 1, 0, 0x20)
+    lines += struct.pack("<III", 0, 2, 12 + 16)
+    lines += struct.pack("<II", 0, 0x80000003) + struct.pack("<II", 8, 0x80000004)
+    c13 = struct.pack("<II", 0xF4, len(checksums)) + checksums + struct.pack("<II", 0xF2, len(lines)) + lines
+    module_stream = module_symbols + c13
+
+    # The DBI stream and its substreams
+    section_contribution = struct.pack("<HHiiIHHII", 1, 0, 0, 0x100, 0x60000020, 0, 0, 0, 0)
+    module_info = struct.pack("<I", 0) + section_contribution
+    module_info += struct.pack("<HHIIIHHIII", 0, 8, len(module_symbols), 0, len(c13), 1, 0, 0, 0, 0)
+    module_info += (module_name + "\0" + module_name + "\0").encode()
+    module_info += b"\0" * ((-len(module_info)) % 4)
+    section_contributions = struct.pack("<I", 0xEFFE0000 + 19970605) + section_contribution
+    section_map_entries = [struct.pack("<HHHHHHII", 0x10D, 0, 0, 1, 0xFFFF, 0xFFFF, 0, 0x1000)]
+    if callbacks_name is not None:
+        section_map_entries.append(struct.pack("<HHHHHHII", 0x10B, 0, 0, 2, 0xFFFF, 0xFFFF, 0, 0x100))
+    section_map = struct.pack("<HH", len(section_map_entries), len(section_map_entries))
+    section_map += b"".join(section_map_entries)
+    file_info = struct.pack("<HH", 1, 1) + struct.pack("<HH", 0, 1) + struct.pack("<I", 0)
+    file_info += source_name.encode() + b"\0"
+    file_info += b"\0" * ((-len(file_info)) % 4)
+    ec_names = struct.pack("<III", 0xEFFEEFFE, 1, 1) + b"\0" + struct.pack("<III", 1, 0, 0)
+    debug_header = struct.pack("<11H", *([0xFFFF] * 5 + [9] + [0xFFFF] * 5))
+    section_headers = b".text\0\0\0" + struct.pack(
+        "<IIIIIIHHI", 0x1000, TEXT_RVA, 0x1000, 0x400, 0, 0, 0, 0, 0x60000020
+    )
+    if callbacks_name is not None:
+        section_headers += b".data\0\0\0" + struct.pack(
+            "<IIIIIIHHI", 0x100, DATA_RVA, 0x200, 0x1400, 0, 0, 0, 0, 0xC0000040
+        )
+    dbi_version = 19970606 if small else 19990903
+    dbi = struct.pack("<iII", -1, dbi_version, 1) + struct.pack("<HHHHHH", 5, 0, 6, 0, 7, 0)
+    dbi += struct.pack(
+        "<IIIIIIII",
+        len(module_info),
+        len(section_contributions),
+        len(section_map),
+        len(file_info),
+        0,
+        0,
+        len(debug_header),
+        len(ec_names),
+    )
+    dbi += struct.pack("<HHI", 0, 0x8664, 0)
+    dbi += module_info + section_contributions + section_map + file_info + ec_names + debug_header
+    if old_dbi:
+        # Just the stream indices and the sizes of the first four substreams
+        dbi = struct.pack(
+            "<HHHxxIIII", 5, 6, 7, len(module_info), len(section_contributions), len(section_map), len(file_info)
+        )
+        dbi += module_info + section_contributions + section_map + file_info
+
+    # The PDB info stream, naming the /names stream
+    strings = b"\0" + source_name.encode() + b"\0"
+    strings += b"\0" * ((-len(strings)) % 4)
+    names = struct.pack("<III", 0xEFFEEFFE, 1, len(strings)) + strings
+    names += struct.pack("<III", 1, 0, 1)
+    if small:
+        # Visual C++ 6.0's version, from before the GUID was added
+        info = struct.pack("<III", 19970604, 0x1234, PDB_AGE)
+    else:
+        info = struct.pack("<III", 20000404, 0x1234, PDB_AGE) + PDB_GUID
+    named_streams = b"/names\0"
+    info += struct.pack("<I", len(named_streams)) + named_streams
+    info += struct.pack("<IIIII", 1, 1, 1, 1, 0) + struct.pack("<II", 0, 10)
+    info += struct.pack("<I", 20140508)
+
+    streams = [
+        b"",
+        info,
+        type_stream(types),
+        dbi,
+        type_stream([]),
+        global_hashes,
+        public_stream,
+        symbol_records,
+        module_stream,
+        section_headers,
+        names,
+    ]
+    if small:
+        # There was no IPI stream yet
+        streams[4] = None
+        return small_msf(streams)
+    return msf(streams)
+
+
+def section_header(name, rva, virtual_size, file_offset, characteristics):
+    return struct.pack(
+        "<8sIIIIIIHHI", name, virtual_size, rva, FILE_ALIGNMENT, file_offset, 0, 0, 0, 0, characteristics
+    )
+
+
+def export_directory(rva, dll_name, exports):
+    """Builds an export directory at `rva` for `exports`, which are `(name,
+    target)` pairs exported with ordinals from 1. Targets are RVAs, or the
+    `DLL.Symbol` strings of forwarders."""
+    names_offset = 40 + 4 * len(exports) + 6 * len(exports)
+    strings = dll_name.encode() + b"\0"
+    functions = b""
+    name_rvas = {}
+    for name, target in exports:
+        name_rvas[name] = rva + names_offset + len(strings)
+        strings += name.encode() + b"\0"
+        if isinstance(target, str):
+            functions += struct.pack("<I", rva + names_offset + len(strings))
+            strings += target.encode() + b"\0"
+        else:
+            functions += struct.pack("<I", target)
+    # The name table is sorted, for the loader's binary search
+    ordered = sorted(range(len(exports)), key=lambda index: exports[index][0])
+    names = b"".join(struct.pack("<I", name_rvas[exports[index][0]]) for index in ordered)
+    ordinals = b"".join(struct.pack("<H", index) for index in ordered)
+    count = len(exports)
+    header = struct.pack(
+        "<IIHHIIIIIII", 0, 0, 0, 0, rva + names_offset, 1, count, count, rva + 40, rva + 40 + 4 * count,
+        rva + 40 + 8 * count,
+    )
+    return header + functions + names + ordinals + strings
+
+
+def image(*, pdb_name, code, relocations, exports):
+    """Builds a PE32+ DLL linked with the fixture PDBs. `code` is the contents
+    of .text, `relocations` are the .text offsets of the 64-bit addresses the
+    loader relocates, and `exports` are `(name, target)` pairs as taken by
+    `export_directory`."""
+    rsds = b"RSDS" + PDB_GUID + struct.pack("<I", PDB_AGE) + pdb_name.encode() + b"\0"
+    rsds_rva = RDATA_RVA + 28
+    exports_rva = rsds_rva + len(rsds) + (-len(rsds)) % 4
+    exports = export_directory(exports_rva, pdb_name.replace(".pdb", ".dll"), exports)
+    # .rdata is the third section, after two file alignment units of headers
+    rsds_offset = FILE_ALIGNMENT * 4 + rsds_rva - RDATA_RVA
+    rdata = struct.pack("<IIHHIIII", 0, 0, 0, 0, IMAGE_DEBUG_TYPE_CODEVIEW, len(rsds), rsds_rva, rsds_offset)
+    rdata += rsds.ljust(exports_rva - rsds_rva, b"\0") + exports
+
+    entries = [0xA000 | offset for offset in relocations]
+    entries += [0] * (len(entries) % 2)
+    reloc = struct.pack("<II", TEXT_RVA, 8 + 2 * len(entries))
+    reloc += b"".join(struct.pack("<H", entry) for entry in entries)
+
+    # Section contents follow the headers, one file alignment unit each
+    sections = [
+        (b".text", TEXT_RVA, 0x1000, code, 0x60000020),
+        (b".data", DATA_RVA, 0x200, b"", 0xC0000040),
+        (b".rdata", RDATA_RVA, len(rdata), rdata, 0x40000040),
+        (b".reloc", RELOC_RVA, len(reloc), reloc, 0x42000040),
+    ]
+    headers = bytearray(FILE_ALIGNMENT * 2)
+    headers[:2] = b"MZ"
+    headers[0x3C:0x40] = struct.pack("<I", 0x40)
+    directories = [(0, 0)] * 16
+    directories[IMAGE_DIRECTORY_EXPORT] = (exports_rva, len(exports))
+    directories[IMAGE_DIRECTORY_BASERELOC] = (RELOC_RVA, len(reloc))
+    directories[IMAGE_DIRECTORY_DEBUG] = (RDATA_RVA, 28)
+    optional_header = struct.pack("<HBBIIIII", 0x20B, 14, 0, 0x200, 0x400, 0, 0, TEXT_RVA)
+    optional_header += struct.pack(
+        "<QIIHHHHHHIIIIHHQQQQII", IMAGE_BASE, 0x1000, FILE_ALIGNMENT, 6, 0, 0, 0, 6, 0, 0, 0x5000,
+        FILE_ALIGNMENT * 2, 0, 2, 0x160, 0x100000, 0x1000, 0x100000, 0x1000, 0, 16,
+    )
+    optional_header += b"".join(struct.pack("<II", rva, size) for rva, size in directories)
+    pe_header = b"PE\0\0" + struct.pack("<HHIIIHH", 0x8664, len(sections), 0, 0, 0, len(optional_header), 0x2022)
+    pe_header += optional_header
+    contents = b""
+    for index, (name, rva, virtual_size, data, characteristics) in enumerate(sections):
+        file_offset = FILE_ALIGNMENT * (2 + index)
+        pe_header += section_header(name, rva, virtual_size, file_offset, characteristics)
+        contents += data.ljust(FILE_ALIGNMENT, b"\0")
+    headers[0x40 : 0x40 + len(pe_header)] = pe_header
+    return bytes(headers) + contents
+
+
+# Where msvc-kernel.dll's service table and its number of entries are in .text
+SERVICE_TABLE_OFFSET = 0x180
+SERVICE_LIMIT_OFFSET = 0x1C0
+
+FIXTURES = {
+    # A C++ program with a public for every procedure and a mangled global
+    "msvc-basic.pdb": dict(
+        struct_name="Point",
+        module_name="a.obj",
+        source_name="a.cpp",
+        procedures=[("main", 0x10)],
+        publics=[("main", 0x10), ("?data@@3HA", 0x200), ("helper", 0x40)],
+        global_name="g_secret",
+    ),
+    # The same program after /OPT:ICF folded two identical functions
+    "msvc-folded.pdb": dict(
+        struct_name="Point",
+        module_name="a.obj",
+        source_name="a.cpp",
+        procedures=[("main", 0x10), ("main_clone", 0x10)],
+        publics=[("main", 0x10), ("?data@@3HA", 0x200), ("helper", 0x40)],
+        global_name="g_secret",
+    ),
+    # A C program with a table of callbacks in writable data, built with
+    # Control Flow Guard and /GS
+    "msvc-callbacks.pdb": dict(
+        struct_name="Point",
+        module_name="a.obj",
+        source_name="a.c",
+        procedures=[("main", 0x10), ("close_point", 0x40)],
+        publics=[
+            ("main", 0x10),
+            ("close_point", 0x40),
+            ("__guard_check_icall_fptr", 0x300),
+            ("__security_cookie", 0x308),
+        ],
+        global_name="g_secret",
+        callbacks_name="g_callbacks",
+        environment=[
+            ("cwd", r"C:\Users\builder\src\app"),
+            ("exe", r"C:\Program Files\Microsoft Visual Studio\VC\bin\link.exe"),
+            ("pdb", r"\\build01\drops\app.pdb"),
+            ("cmd", r"/DEBUG /OUT:C:\Users\builder\src\app\app.exe"),
+        ],
+        exports=[
+            ("main", 1, 0),
+            ("g_secret", 3, EXPORT_DATA),
+            ("close_point", 4, 0),
+            ("HeapAlloc", 5, EXPORT_FORWARDER),
+            ("removed_fn", 6, 0),
+        ],
+        # A `short` flags enum whose highest flag is negative
+        extra_types=[
+            type_record(
+                LF_FIELDLIST,
+                enumerate_(1, "ACCESS_READ")
+                + enumerate_(2, "ACCESS_WRITE")
+                + enumerate_(4, "ACCESS_EXECUTE")
+                + enumerate_(-0x8000, "ACCESS_LOCKED"),
+            ),
+            type_record(LF_ENUM, struct.pack("<HHII", 4, 0, T_SHORT, FIRST_TYPE_INDEX + 8) + b"Access\0"),
+        ],
+    ),
+    # A kernel with system call services and their `Zw` stubs, except for
+    # NtQuerySystemTime, and the service table msvc-kernel.dll holds in .text
+    "msvc-kernel.pdb": dict(
+        struct_name="Point",
+        module_name="ntos.obj",
+        source_name="ntos.c",
+        procedures=[
+            ("NtClose", 0x10),
+            ("ZwClose", 0x40),
+            ("NtOpenFile", 0x70),
+            ("ZwOpenFile", 0xA0),
+            ("NtQuerySystemTime", 0xD0),
+        ],
+        publics=[("KiServiceTable", SERVICE_TABLE_OFFSET), ("KiServiceLimit", SERVICE_LIMIT_OFFSET)],
+        global_name="g_secret",
+    ),
+    # msvc-basic.pdb with a truncated vtable shape, which claims more slots than
+    # its record has descriptors for
+    "msvc-corrupt.pdb": dict(
+        struct_name="Point",
+        module_name="a.obj",
+        source_name="a.cpp",
+        procedures=[("main", 0x10)],
+        publics=[("main", 0x10), ("?data@@3HA", 0x200), ("helper", 0x40)],
+        global_name="g_secret",
+        extra_types=[type_record(LF_VTSHAPE, struct.pack("<H", 64))],
+    ),
+    # A Rust crate, with mangled names and a codegen unit for a module
+    "rust-basic.pdb": dict(
+        struct_name="demo::Point",
+        module_name="demo.demo.1a2b3c4d-cgu.0.rcgu.o",
+        source_name="src/main.rs",
+        procedures=[("demo::main", 0x10)],
+        publics=[
+            ("_ZN4demo4main17h0123456789abcdefE", 0x10),
+            ("_ZN4demo6helper17hfedcba9876543210E", 0x40),
+        ],
+        global_name="demo::SECRET",
+        compile_flags=CV_CFL_RUST,
+    ),
+    # msvc-basic.pdb as Visual C++ 6.0 would lay it out, in a PDB 2.0 file
+    "vc6-basic.pdb": dict(
+        struct_name="Point",
+        module_name="a.obj",
+        source_name="a.cpp",
+        procedures=[("main", 0x10)],
+        publics=[("main", 0x10), ("?data@@3HA", 0x200), ("helper", 0x40)],
+        global_name="g_secret",
+        small=True,
+    ),
+    # vc6-basic.pdb with the DBI header of Visual C++ 4.0, which only the
+    # information and type streams can be read from
+    "vc4-old-dbi.pdb": dict(
+        struct_name="Point",
+        module_name="a.obj",
+        source_name="a.cpp",
+        procedures=[("main", 0x10)],
+        publics=[("main", 0x10), ("?data@@3HA", 0x200), ("helper", 0x40)],
+        global_name="g_secret",
+        small=True,
+        old_dbi=True,
+    ),
+}
+
+# Fixtures cut short, as by an interrupted copy: the fixture each is cut from,
+# and how many bytes of it are kept
+TRUNCATED = {
+    "msvc-truncated.pdb": ("msvc-basic.pdb", PAGE_SIZE * 5),
+}
+
+
+# main reads g_secret through its absolute address, which the loader relocates
+MAIN_CODE = bytes.fromhex("4883EC28 48B8 0011004001000000 8B00 4883C428 C3".replace(" ", ""))
+CLOSE_POINT_CODE = bytes.fromhex("48894C2408 33C0 C3".replace(" ", ""))
+
+
+def service_table(services):
+    """Builds .text holding an x64 service table for `services`, which are
+    `(offset, stack_arguments)` pairs of the services' .text offsets. Each
+    entry is the service's offset from the table, shifted left by 4 to make
+    room for the number of stack arguments."""
+    code = bytearray(SERVICE_LIMIT_OFFSET + 4)
+    for number, (offset, stack_arguments) in enumerate(services):
+        entry = ((offset - SERVICE_TABLE_OFFSET) << 4 | stack_arguments) & 0xFFFFFFFF
+        struct.pack_into("<I", code, SERVICE_TABLE_OFFSET + 4 * number, entry)
+    struct.pack_into("<I", code, SERVICE_LIMIT_OFFSET, len(services))
+    return bytes(code)
+
+
+# The image msvc-callbacks.pdb belongs to. Its exports disagree with the PDB:
+# close_point's ordinal changed, g_secret moved, stale_fn has no symbol, and
+# removed_fn is no longer exported.
+IMAGES = {
+    "msvc-callbacks.dll": dict(
+        pdb_name="msvc-callbacks.pdb",
+        code=bytes(0x10) + MAIN_CODE.ljust(0x30, b"\xCC") + CLOSE_POINT_CODE,
+        relocations=[0x16],
+        exports=[
+            ("main", TEXT_RVA + 0x10),
+            ("close_point", TEXT_RVA + 0x40),
+            ("g_secret", DATA_RVA + 0x100),
+            ("stale_fn", TEXT_RVA + 0x80),
+            ("HeapAlloc", "NTDLL.RtlAllocateHeap"),
+        ],
+    ),
+    # The kernel msvc-kernel.pdb belongs to, standing in for ntoskrnl.exe. Its
+    # last service has no symbol.
+    "msvc-kernel.dll": dict(
+        pdb_name="msvc-kernel.pdb",
+        code=service_table([(0x10, 0), (0x70, 2), (0xD0, 0), (0xF0, 1)]),
+        relocations=[],
+        exports=[],
+    ),
+}
+
+
+if __name__ == "__main__":
+    directory = os.path.dirname(os.path.abspath(__file__))
+    for file_name, options in FIXTURES.items():
+        with open(os.path.join(directory, file_name), "wb") as file:
+            file.write(pdb(**options))
+    for file_name, (original, length) in TRUNCATED.items():
+        with open(os.path.join(directory, file_name), "wb") as file:
+            file.write(pdb(**FIXTURES[original])[:length])
+    for file_name, options in IMAGES.items():
+        with open(os.path.join(directory, file_name), "wb") as file:
+            file.write(image(**options))
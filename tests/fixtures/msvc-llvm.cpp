@@ -0,0 +1,20 @@
+// Source of `msvc-llvm.pdb`, built from `msvc-llvm.ll` as that file describes.
+// Defined by the CRT, which the fixture is linked without.
+extern "C" int _fltused = 0x9875;
+struct Point { int x; float y; };
+enum Color { Red, Green = 2, Blue = 4 };
+struct Counter {
+  int value;
+  int next();
+};
+int g_counter = 3;
+int Counter::next() { return ++value; }
+int scale(const Point *p, int factor) {
+  return p->x * factor;
+}
+int main() {
+  Point p = {1, 2.0f};
+  Counter counter = {g_counter};
+  Color color = Green;
+  return scale(&p, counter.next()) + color;
+}
@@ -0,0 +1,133 @@
+; LLVM IR of `msvc-llvm.cpp`, the source of `msvc-llvm.pdb`: a PDB written by
+; LLVM's CodeView backend and LLD, the pair clang-cl and lld-link use. The IR is
+; written out in the form clang-cl emits so that only `llc` and LLD are needed,
+; which run on Linux. Built from copies of both files in `/tmp/msvc-llvm`, which
+; the PDB records as the object file's directory, after copying rustup's
+; `rust-lld` there as `lld-link` and adding the toolchain's `lib` directory to
+; `LD_LIBRARY_PATH`:
+;
+;   llc -filetype=obj -O0 msvc-llvm.ll -o msvc-llvm.obj
+;   ./lld-link msvc-llvm.obj /out:msvc-llvm.exe /debug /pdb:msvc-llvm.pdb \
+;       /entry:main /subsystem:console /nodefaultlib /Brepro
+;   rm msvc-llvm.obj msvc-llvm.exe
+source_filename = "msvc-llvm.cpp"
+target datalayout = "e-m:w-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-windows-msvc19.33.0"
+
+%struct.Point = type { i32, float }
+%struct.Counter = type { i32 }
+
+@_fltused = dso_local global i32 39029, align 4
+@"?g_counter@@3HA" = dso_local global i32 3, align 4, !dbg !0
+
+define dso_local i32 @"?next@Counter@@QEAAHXZ"(%struct.Counter* %this) #0 !dbg !30 {
+entry:
+  %this.addr = alloca %struct.Counter*, align 8
+  store %struct.Counter* %this, %struct.Counter** %this.addr, align 8
+  call void @llvm.dbg.declare(metadata %struct.Counter** %this.addr, metadata !32, metadata !DIExpression()), !dbg !33
+  %this1 = load %struct.Counter*, %struct.Counter** %this.addr, align 8
+  %value = getelementptr inbounds %struct.Counter, %struct.Counter* %this1, i32 0, i32 0, !dbg !33
+  %0 = load i32, i32* %value, align 4, !dbg !33
+  %inc = add nsw i32 %0, 1, !dbg !33
+  store i32 %inc, i32* %value, align 4, !dbg !33
+  ret i32 %inc, !dbg !33
+}
+
+define dso_local i32 @"?scale@@YAHPEBUPoint@@H@Z"(%struct.Point* %p, i32 %factor) #0 !dbg !40 {
+entry:
+  %factor.addr = alloca i32, align 4
+  %p.addr = alloca %struct.Point*, align 8
+  store i32 %factor, i32* %factor.addr, align 4
+  call void @llvm.dbg.declare(metadata i32* %factor.addr, metadata !45, metadata !DIExpression()), !dbg !46
+  store %struct.Point* %p, %struct.Point** %p.addr, align 8
+  call void @llvm.dbg.declare(metadata %struct.Point** %p.addr, metadata !44, metadata !DIExpression()), !dbg !46
+  %0 = load %struct.Point*, %struct.Point** %p.addr, align 8, !dbg !47
+  %x = getelementptr inbounds %struct.Point, %struct.Point* %0, i32 0, i32 0, !dbg !47
+  %1 = load i32, i32* %x, align 4, !dbg !47
+  %2 = load i32, i32* %factor.addr, align 4, !dbg !47
+  %mul = mul nsw i32 %1, %2, !dbg !47
+  ret i32 %mul, !dbg !47
+}
+
+define dso_local i32 @main() #0 !dbg !50 {
+entry:
+  %p = alloca %struct.Point, align 4
+  %counter = alloca %struct.Counter, align 4
+  %color = alloca i32, align 4
+  call void @llvm.dbg.declare(metadata %struct.Point* %p, metadata !53, metadata !DIExpression()), !dbg !56
+  %x = getelementptr inbounds %struct.Point, %struct.Point* %p, i32 0, i32 0, !dbg !56
+  store i32 1, i32* %x, align 4, !dbg !56
+  %y = getelementptr inbounds %struct.Point, %struct.Point* %p, i32 0, i32 1, !dbg !56
+  store float 2.000000e+00, float* %y, align 4, !dbg !56
+  call void @llvm.dbg.declare(metadata %struct.Counter* %counter, metadata !54, metadata !DIExpression()), !dbg !57
+  %value = getelementptr inbounds %struct.Counter, %struct.Counter* %counter, i32 0, i32 0, !dbg !57
+  %0 = load i32, i32* @"?g_counter@@3HA", align 4, !dbg !57
+  store i32 %0, i32* %value, align 4, !dbg !57
+  call void @llvm.dbg.declare(metadata i32* %color, metadata !55, metadata !DIExpression()), !dbg !58
+  store i32 2, i32* %color, align 4, !dbg !58
+  %call = call i32 @"?next@Counter@@QEAAHXZ"(%struct.Counter* %counter), !dbg !59
+  %call1 = call i32 @"?scale@@YAHPEBUPoint@@H@Z"(%struct.Point* %p, i32 %call), !dbg !59
+  %1 = load i32, i32* %color, align 4, !dbg !59
+  %add = add nsw i32 %call1, %1, !dbg !59
+  ret i32 %add, !dbg !59
+}
+
+declare void @llvm.dbg.declare(metadata, metadata, metadata) #1
+
+attributes #0 = { mustprogress noinline nounwind optnone uwtable "frame-pointer"="none" "min-legal-vector-width"="0" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" }
+attributes #1 = { nofree nosync nounwind readnone speculatable willreturn }
+
+!llvm.dbg.cu = !{!2}
+!llvm.module.flags = !{!10, !11, !12}
+
+!0 = !DIGlobalVariableExpression(var: !1, expr: !DIExpression())
+!1 = distinct !DIGlobalVariable(name: "g_counter", linkageName: "?g_counter@@3HA", scope: !2, file: !3, line: 10, type: !9, isLocal: false, isDefinition: true)
+!2 = distinct !DICompileUnit(language: DW_LANG_C_plus_plus_14, file: !3, producer: "LLVM 14.0.6 llc", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, enums: !4, globals: !8, splitDebugInlining: false, nameTableKind: None)
+!3 = !DIFile(filename: "msvc-llvm.cpp", directory: "C:\\src")
+!4 = !{!5}
+!5 = !DICompositeType(tag: DW_TAG_enumeration_type, name: "Color", file: !3, line: 5, baseType: !9, size: 32, elements: !6, identifier: ".?AW4Color@@")
+!6 = !{!7, !13, !14}
+!7 = !DIEnumerator(name: "Red", value: 0)
+!13 = !DIEnumerator(name: "Green", value: 2)
+!14 = !DIEnumerator(name: "Blue", value: 4)
+!8 = !{!0}
+!9 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!10 = !{i32 2, !"CodeView", i32 1}
+!11 = !{i32 2, !"Debug Info Version", i32 3}
+!12 = !{i32 1, !"wchar_size", i32 2}
+!15 = distinct !DICompositeType(tag: DW_TAG_structure_type, name: "Point", file: !3, line: 4, size: 64, flags: DIFlagTypePassByValue, elements: !16, identifier: ".?AUPoint@@")
+!16 = !{!17, !18}
+!17 = !DIDerivedType(tag: DW_TAG_member, name: "x", scope: !15, file: !3, line: 4, baseType: !9, size: 32)
+!18 = !DIDerivedType(tag: DW_TAG_member, name: "y", scope: !15, file: !3, line: 4, baseType: !19, size: 32, offset: 32)
+!19 = !DIBasicType(name: "float", size: 32, encoding: DW_ATE_float)
+!20 = distinct !DICompositeType(tag: DW_TAG_structure_type, name: "Counter", file: !3, line: 6, size: 32, flags: DIFlagTypePassByValue, elements: !21, identifier: ".?AUCounter@@")
+!21 = !{!22, !23}
+!22 = !DIDerivedType(tag: DW_TAG_member, name: "value", scope: !20, file: !3, line: 7, baseType: !9, size: 32)
+!23 = !DISubprogram(name: "next", linkageName: "?next@Counter@@QEAAHXZ", scope: !20, file: !3, line: 8, type: !24, scopeLine: 8, flags: DIFlagPrototyped, spFlags: 0)
+!24 = !DISubroutineType(types: !25)
+!25 = !{!9, !26}
+!26 = !DIDerivedType(tag: DW_TAG_pointer_type, baseType: !20, size: 64, flags: DIFlagArtificial | DIFlagObjectPointer)
+!27 = !{}
+!28 = !DIDerivedType(tag: DW_TAG_pointer_type, baseType: !20, size: 64)
+!29 = !DIDerivedType(tag: DW_TAG_const_type, baseType: !15)
+!30 = distinct !DISubprogram(name: "next", linkageName: "?next@Counter@@QEAAHXZ", scope: !20, file: !3, line: 11, type: !24, scopeLine: 11, flags: DIFlagPrototyped, spFlags: DISPFlagDefinition, unit: !2, declaration: !23, retainedNodes: !27)
+!32 = !DILocalVariable(name: "this", arg: 1, scope: !30, type: !28, flags: DIFlagArtificial | DIFlagObjectPointer)
+!33 = !DILocation(line: 11, scope: !30)
+!40 = distinct !DISubprogram(name: "scale", linkageName: "?scale@@YAHPEBUPoint@@H@Z", scope: !3, file: !3, line: 12, type: !41, scopeLine: 12, flags: DIFlagPrototyped, spFlags: DISPFlagDefinition, unit: !2, retainedNodes: !27)
+!41 = !DISubroutineType(types: !42)
+!42 = !{!9, !43, !9}
+!43 = !DIDerivedType(tag: DW_TAG_pointer_type, baseType: !29, size: 64)
+!44 = !DILocalVariable(name: "p", arg: 1, scope: !40, file: !3, line: 12, type: !43)
+!45 = !DILocalVariable(name: "factor", arg: 2, scope: !40, file: !3, line: 12, type: !9)
+!46 = !DILocation(line: 12, scope: !40)
+!47 = !DILocation(line: 13, scope: !40)
+!50 = distinct !DISubprogram(name: "main", scope: !3, file: !3, line: 15, type: !51, scopeLine: 15, flags: DIFlagPrototyped, spFlags: DISPFlagDefinition, unit: !2, retainedNodes: !27)
+!51 = !DISubroutineType(types: !52)
+!52 = !{!9}
+!53 = !DILocalVariable(name: "p", scope: !50, file: !3, line: 16, type: !15)
+!54 = !DILocalVariable(name: "counter", scope: !50, file: !3, line: 17, type: !20)
+!55 = !DILocalVariable(name: "color", scope: !50, file: !3, line: 18, type: !5)
+!56 = !DILocation(line: 16, scope: !50)
+!57 = !DILocation(line: 17, scope: !50)
+!58 = !DILocation(line: 18, scope: !50)
+!59 = !DILocation(line: 19, scope: !50)
@@ -0,0 +1,52 @@
+//! Source of `rust-lld.pdb`, one of the fixtures a real toolchain produced, to
+//! catch what the hand-built fixtures don't mimic. Built after
+//! `rustup target add x86_64-pc-windows-msvc` from a copy of this file in
+//! `/tmp/rust-lld`, which the PDB records as the object files' directory:
+//!
+//! ```text
+//! rustc --target x86_64-pc-windows-msvc -C panic=abort -C debuginfo=2 \
+//!     -C opt-level=1 -C linker=rust-lld -C linker-flavor=lld-link \
+//!     -C link-arg=/entry:main -C link-arg=/nodefaultlib \
+//!     -C link-arg=/subsystem:console --remap-path-prefix "$PWD=." \
+//!     rust-lld.rs -o rust-lld.exe && rm rust-lld.exe
+//! ```
+#![no_std]
+#![no_main]
+
+#[repr(C)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[repr(u8)]
+pub enum Color {
+    Red = 1,
+    Green = 2,
+    Blue = 0xff,
+}
+
+#[no_mangle]
+pub static mut ORIGIN: Point = Point { x: 0, y: 0 };
+
+#[no_mangle]
+pub extern "C" fn distance(a: &Point, b: &Point) -> i32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
+
+#[no_mangle]
+pub extern "C" fn color_value(color: Color) -> u8 {
+    color as u8
+}
+
+#[no_mangle]
+pub extern "C" fn main() -> i32 {
+    let a = Point { x: 1, y: 2 };
+    let b = Point { x: 4, y: 6 };
+    distance(&a, &b) + color_value(Color::Green) as i32
+}
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
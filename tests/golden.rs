@@ -0,0 +1,663 @@
+//! Runs pdbview over the fixture PDBs in `tests/fixtures` and compares its
+//! output with the golden files in `tests/golden`. After an intended change to
+//! the output, regenerate the golden files with:
+//!
+//! ```text
+//! PDBVIEW_UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+//!
+//! and review the differences before committing them. The fixtures are written
+//! by `tests/fixtures/generate.py`, which is where new fixtures should be added.
+use object::{Object, ObjectSection, ObjectSymbol};
+use std::convert::TryInto;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const UPDATE_VAR: &str = "PDBVIEW_UPDATE_GOLDEN";
+
+fn tests_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+/// Runs pdbview from the fixtures directory, so that file names in the output
+/// don't depend on where the repository is checked out
+fn run(args: &[&str]) -> String {
+    run_with_status(args, 0)
+}
+
+/// Runs pdbview like [run], for commands which are expected to exit with
+/// `status`
+fn run_with_status(args: &[&str], status: i32) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_pdbview"))
+        .current_dir(tests_dir().join("fixtures"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run pdbview");
+    assert!(
+        output.status.code() == Some(status),
+        "pdbview {} exited with {}:\n{}",
+        args.join(" "),
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).expect("pdbview's output isn't UTF-8")
+}
+
+/// Runs pdbview like [run], returning what it wrote to stderr
+fn run_stderr(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_pdbview"))
+        .current_dir(tests_dir().join("fixtures"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run pdbview");
+    assert!(output.status.success(), "pdbview {} failed", args.join(" "));
+
+    String::from_utf8(output.stderr).expect("pdbview's output isn't UTF-8")
+}
+
+/// Runs a command which writes a file, e.g. `strip`, with the path of a file
+/// named `file_name` in a new temporary directory appended to `args`. Returns
+/// the directory, which is deleted when dropped, the file's path, and what
+/// pdbview printed with the path replaced by `file_name`.
+fn run_writer(args: &[&str], file_name: &str, status: i32) -> (tempfile::TempDir, PathBuf, String) {
+    let dir = tempfile::tempdir().expect("failed to create a temporary directory");
+    let path = dir.path().join(file_name);
+    let path_str = path.to_str().expect("the temporary directory isn't UTF-8");
+    let mut args = args.to_vec();
+    args.push(path_str);
+    let output = run_with_status(&args, status).replace(path_str, file_name);
+
+    (dir, path, output)
+}
+
+/// Pretty prints JSON with its object keys sorted, as some of the maps in the
+/// output are unordered
+fn normalize_json(json: &str) -> String {
+    let value: serde_json::Value = serde_json::from_str(json).expect("pdbview's output isn't JSON");
+    serde_json::to_string_pretty(&value).unwrap() + "\n"
+}
+
+fn check(golden: &str, actual: &str) {
+    let path = tests_dir().join("golden").join(golden);
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "failed to read {}: {}. Run with {}=1 to create it",
+            path.display(),
+            e,
+            UPDATE_VAR
+        )
+    });
+    // Don't fail on checkouts which converted the golden files' line endings
+    let expected = expected.replace("\r\n", "\n");
+    assert!(
+        expected == actual,
+        "output differs from {}. Run with {}=1 to update it if the change is intended\n\
+         --- expected\n{}\n--- actual\n{}",
+        path.display(),
+        UPDATE_VAR,
+        expected,
+        actual
+    );
+}
+
+fn check_plain(golden: &str, args: &[&str]) {
+    check(golden, &run(args));
+}
+
+fn check_json(golden: &str, args: &[&str]) {
+    let mut args = args.to_vec();
+    args.extend(["--format", "json"]);
+    check(golden, &normalize_json(&run(&args)));
+}
+
+#[test]
+fn msvc_basic() {
+    check_plain("msvc-basic.txt", &["msvc-basic.pdb"]);
+    check_json("msvc-basic.json", &["msvc-basic.pdb"]);
+}
+
+#[test]
+fn msvc_folded() {
+    check_plain("msvc-folded.txt", &["msvc-folded.pdb"]);
+    check_json("msvc-folded.json", &["msvc-folded.pdb"]);
+    check_plain("msvc-folded-folding.txt", &["folding", "msvc-folded.pdb"]);
+}
+
+#[test]
+fn msvc_corrupt() {
+    // The malformed type record stops parsing by default, and is skipped and
+    // reported with `--fail-on never`
+    run_with_status(&["msvc-corrupt.pdb"], 1);
+    let json = run_with_status(
+        &["--fail-on", "never", "--format", "json", "msvc-corrupt.pdb"],
+        2,
+    );
+    check("msvc-corrupt.json", &normalize_json(&json));
+    // `type` only reads the records `Point` depends upon, which all come before
+    // the malformed one
+    check_plain(
+        "msvc-corrupt-type.txt",
+        &["type", "msvc-corrupt.pdb", "Point"],
+    );
+}
+
+#[test]
+fn default_runs_are_quiet() {
+    // Records of kinds which aren't listed, such as the many `S_UDT`s rustc
+    // writes, are only logged with -vv
+    for pdb in ["msvc-basic.pdb", "rust-lld.pdb"] {
+        assert_eq!(run_stderr(&[pdb]), "", "{} logged messages", pdb);
+    }
+}
+
+#[test]
+fn verify() {
+    check_plain("msvc-basic-verify.txt", &["verify", "msvc-basic.pdb"]);
+    // The verifier reports the malformed record parsing stops at, and exits
+    // with 1 like parsing does
+    check(
+        "msvc-corrupt-verify.txt",
+        &run_with_status(&["verify", "msvc-corrupt.pdb"], 1),
+    );
+    check(
+        "msvc-truncated-verify.txt",
+        &run_with_status(&["verify", "msvc-truncated.pdb"], 1),
+    );
+}
+
+#[test]
+fn vc6_basic() {
+    // The IPI stream only appeared in Visual C++ 7.0, and is reported missing
+    check("vc6-basic.txt", &run_with_status(&["vc6-basic.pdb"], 2));
+    check_plain("vc6-basic-info.txt", &["info", "vc6-basic.pdb"]);
+}
+
+#[test]
+fn vc4_old_dbi() {
+    // The DBI stream can't be read, so only types are loaded, and the stream
+    // is reported as missing
+    check("vc4-old-dbi.txt", &run_with_status(&["vc4-old-dbi.pdb"], 2));
+    check_plain("vc4-old-dbi-info.txt", &["info", "vc4-old-dbi.pdb"]);
+}
+
+#[test]
+fn stdin_input() {
+    let fixtures = tests_dir().join("fixtures");
+    let output = Command::new(env!("CARGO_BIN_EXE_pdbview"))
+        .current_dir(&fixtures)
+        .arg("-")
+        .stdin(std::fs::File::open(fixtures.join("msvc-basic.pdb")).unwrap())
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run pdbview");
+    assert!(
+        output.status.success(),
+        "pdbview - exited with {}",
+        output.status
+    );
+
+    // The output is that of the file itself, under the name `<stdin>`
+    let expected = run(&["msvc-basic.pdb"]).replacen("\"msvc-basic.pdb\"", "\"<stdin>\"", 1);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn msvc_type() {
+    check_plain("msvc-basic-type.txt", &["type", "msvc-basic.pdb", "Point"]);
+}
+
+#[test]
+fn msvc_search() {
+    check_plain(
+        "msvc-basic-resolve.txt",
+        &["resolve", "msvc-basic.pdb", "main"],
+    );
+    check_plain(
+        "msvc-basic-find.txt",
+        &["find", "msvc-basic.pdb", "mian", "--fuzzy"],
+    );
+}
+
+#[test]
+fn msvc_callbacks() {
+    check_plain("msvc-callbacks.txt", &["msvc-callbacks.pdb"]);
+    // `Access` is a `short` enum, whose negative flag is only a single bit once
+    // it's masked to 16 bits
+    check_plain(
+        "msvc-callbacks-enum.txt",
+        &["enum", "msvc-callbacks.pdb", "Access"],
+    );
+    assert_eq!(
+        run(&["enum", "msvc-callbacks.pdb", "Access", "0x8003"]),
+        "ACCESS_READ | ACCESS_WRITE | ACCESS_LOCKED\n"
+    );
+    check_plain(
+        "msvc-callbacks-function-pointers.txt",
+        &["function-pointers", "msvc-callbacks.pdb"],
+    );
+    check_plain(
+        "msvc-callbacks-type.go",
+        &["type", "msvc-callbacks.pdb", "Callbacks", "--format", "go"],
+    );
+    // Backends only render the types selected by `--where`
+    check_plain(
+        "msvc-callbacks-where.go",
+        &[
+            "msvc-callbacks.pdb",
+            "--format",
+            "go",
+            "--where",
+            "name == \"Callbacks\"",
+        ],
+    );
+    check_plain(
+        "msvc-callbacks-diff.txt",
+        &["diff", "msvc-basic.pdb", "msvc-callbacks.pdb"],
+    );
+    check_json(
+        "msvc-callbacks-diff.json",
+        &["diff", "msvc-basic.pdb", "msvc-callbacks.pdb"],
+    );
+    check_plain(
+        "msvc-callbacks-type.zig",
+        &["type", "msvc-callbacks.pdb", "Callbacks", "--format", "zig"],
+    );
+    check_plain(
+        "msvc-callbacks.yar",
+        &["msvc-callbacks.pdb", "--format", "yara"],
+    );
+    check_plain(
+        "msvc-callbacks-image.yar",
+        &[
+            "msvc-callbacks.pdb",
+            "--format",
+            "yara",
+            "--image",
+            "msvc-callbacks.dll",
+            "--yara-bytes",
+            "16",
+        ],
+    );
+}
+
+#[test]
+fn msvc_callbacks_exports() {
+    // The fixture image's exports disagree with the PDB, so the command fails
+    check(
+        "msvc-callbacks-exports.txt",
+        &run_with_status(&["exports", "msvc-callbacks.dll"], 3),
+    );
+    check(
+        "msvc-callbacks-exports.json",
+        &normalize_json(&run_with_status(
+            &[
+                "exports",
+                "msvc-callbacks.pdb",
+                "--image",
+                "msvc-callbacks.dll",
+                "--format",
+                "json",
+            ],
+            3,
+        )),
+    );
+}
+
+#[test]
+fn rust_basic() {
+    check_plain("rust-basic.txt", &["rust-basic.pdb"]);
+    check_json("rust-basic.json", &["rust-basic.pdb"]);
+    check_plain(
+        "rust-basic-resolve.txt",
+        &["resolve", "rust-basic.pdb", "demo::main"],
+    );
+}
+
+#[test]
+fn rust_lld() {
+    check_plain("rust-lld.txt", &["rust-lld.pdb"]);
+    check_plain("rust-lld-audit.txt", &["audit", "rust-lld.pdb"]);
+    check_plain(
+        "rust-lld-type.txt",
+        &["type", "rust-lld.pdb", "rust_lld::Point"],
+    );
+}
+
+#[test]
+fn msvc_llvm() {
+    // LLVM describes locals with `S_DEFRANGE_FRAMEPOINTER_REL`, which the pdb
+    // crate doesn't read, so they're reported as diagnostics
+    check("msvc-llvm.txt", &run_with_status(&["msvc-llvm.pdb"], 2));
+    check(
+        "msvc-llvm.json",
+        &normalize_json(&run_with_status(&["--format", "json", "msvc-llvm.pdb"], 2)),
+    );
+    check_plain("msvc-llvm-type.txt", &["type", "msvc-llvm.pdb", "Counter"]);
+    check_plain("msvc-llvm-verify.txt", &["verify", "msvc-llvm.pdb"]);
+}
+
+/// Describes the sections and symbols of an ELF file, as read back by the
+/// `object` crate
+fn describe_elf(data: &[u8]) -> String {
+    let file = object::File::parse(data).expect("pdbview wrote an invalid ELF file");
+    let mut description = String::new();
+    writeln!(
+        description,
+        "Format: {:?} {:?} {:?}",
+        file.format(),
+        file.kind(),
+        file.architecture()
+    )
+    .unwrap();
+    writeln!(description, "Sections:").unwrap();
+    for section in file.sections() {
+        writeln!(
+            description,
+            "\t{:<16} {:<18} 0x{:08X} 0x{:X}",
+            section.name().unwrap(),
+            format!("{:?}", section.kind()),
+            section.address(),
+            section.size()
+        )
+        .unwrap();
+    }
+    writeln!(description, "Symbols:").unwrap();
+    for symbol in file.symbols() {
+        writeln!(
+            description,
+            "\t0x{:08X} 0x{:<4X} {:<8} {:<12} {}",
+            symbol.address(),
+            symbol.size(),
+            format!("{:?}", symbol.kind()),
+            format!("{:?}", symbol.section()),
+            symbol.name().unwrap()
+        )
+        .unwrap();
+    }
+
+    description
+}
+
+/// Describes the DWARF in an ELF file: every entry of each unit, indented by
+/// depth, with its attributes, followed by the rows of the unit's line program.
+/// It's read back by the `gimli` crate.
+fn describe_dwarf(data: &[u8]) -> String {
+    let file = object::File::parse(data).expect("pdbview wrote an invalid ELF file");
+    let dwarf = gimli::Dwarf::load(|id| {
+        let data = file
+            .section_by_name(id.name())
+            .map(|section| section.data().unwrap())
+            .unwrap_or(&[]);
+        Ok::<_, gimli::Error>(gimli::EndianSlice::new(data, gimli::LittleEndian))
+    })
+    .unwrap();
+
+    let mut description = String::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().expect("invalid unit header") {
+        let unit = dwarf.unit(header).expect("invalid unit");
+        let mut entries = unit.entries();
+        let mut depth = 0;
+        while let Some((delta, entry)) = entries.next_dfs().expect("invalid entry") {
+            depth += delta;
+            let indent = "  ".repeat(depth as usize);
+            writeln!(
+                description,
+                "{}<0x{:X}> {}",
+                indent,
+                entry.offset().0,
+                entry.tag()
+            )
+            .unwrap();
+
+            let mut attributes = entry.attrs();
+            while let Some(attribute) = attributes.next().expect("invalid attribute") {
+                let value = match attribute.value() {
+                    value @ (gimli::AttributeValue::DebugStrRef(_)
+                    | gimli::AttributeValue::String(_)) => dwarf
+                        .attr_string(&unit, value)
+                        .expect("invalid string")
+                        .to_string_lossy()
+                        .into_owned(),
+                    gimli::AttributeValue::UnitRef(offset) => format!("<0x{:X}>", offset.0),
+                    gimli::AttributeValue::Addr(address) => format!("0x{:X}", address),
+                    gimli::AttributeValue::Exprloc(expression) => {
+                        format!("{:02X?}", expression.0.slice())
+                    }
+                    gimli::AttributeValue::Flag(flag) => flag.to_string(),
+                    gimli::AttributeValue::Language(language) => language.to_string(),
+                    gimli::AttributeValue::Encoding(encoding) => encoding.to_string(),
+                    gimli::AttributeValue::FileIndex(index) => format!("file {}", index),
+                    gimli::AttributeValue::DebugLineRef(offset) => format!("0x{:X}", offset.0),
+                    value => match value.udata_value() {
+                        Some(value) => value.to_string(),
+                        None => format!("{:?}", value),
+                    },
+                };
+                writeln!(description, "{}  {} {}", indent, attribute.name(), value).unwrap();
+            }
+        }
+
+        if let Some(program) = unit.line_program.clone() {
+            writeln!(description, "Line rows:").unwrap();
+            let mut rows = program.rows();
+            while let Some((header, row)) = rows.next_row().expect("invalid line program") {
+                if row.end_sequence() {
+                    writeln!(description, "\t0x{:08X} end of sequence", row.address()).unwrap();
+                    continue;
+                }
+
+                let file = row.file(header).expect("the row's file isn't in the table");
+                let path = dwarf
+                    .attr_string(&unit, file.path_name())
+                    .expect("invalid file name");
+                writeln!(
+                    description,
+                    "\t0x{:08X} {}:{}",
+                    row.address(),
+                    path.to_string_lossy(),
+                    row.line().map_or(0, |line| line.get())
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    description
+}
+
+/// Describes an address map, read back by the format's documentation in
+/// `ezpdb::addrmap`
+fn describe_address_map(data: &[u8]) -> String {
+    let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    assert_eq!(data[..8], ezpdb::addrmap::MAGIC, "wrong magic");
+    assert_eq!(u32_at(8), ezpdb::addrmap::VERSION, "wrong version");
+    assert_eq!(u32_at(28), 0, "the reserved field isn't 0");
+    let count = u32_at(12) as usize;
+    let entries = u32_at(16) as usize;
+    let strings_offset = u32_at(20) as usize;
+    let strings = &data[strings_offset..strings_offset + u32_at(24) as usize];
+
+    let mut description = String::from("\tRVA        Length     Code  Name\n");
+    let mut previous_rva = 0;
+    for index in 0..count {
+        let entry = entries + 16 * index;
+        let rva = u32_at(entry);
+        assert!(rva >= previous_rva, "the entries aren't sorted by RVA");
+        previous_rva = rva;
+
+        let name = &strings[u32_at(entry + 8) as usize..];
+        let name = &name[..name
+            .iter()
+            .position(|&b| b == 0)
+            .expect("unterminated name")];
+        writeln!(
+            description,
+            "\t0x{:08X} 0x{:08X} {:<5} {}",
+            rva,
+            u32_at(entry + 4),
+            u32_at(entry + 12) & ezpdb::addrmap::FLAG_CODE != 0,
+            String::from_utf8_lossy(name)
+        )
+        .unwrap();
+    }
+
+    description
+}
+
+#[test]
+fn strip() {
+    // The stripped PDB parses back with only the public symbols, so without the
+    // diagnostics the private symbols raise
+    let (_dir, path, output) = run_writer(&["strip", "msvc-llvm.pdb"], "stripped.pdb", 0);
+    let stripped = path.to_str().unwrap();
+    let parsed = run(&[stripped]).replace(stripped, "stripped.pdb");
+    check("msvc-llvm-strip.txt", &(output + &parsed));
+    assert_eq!(run(&["verify", stripped]), "No problems found\n");
+
+    let (_dir, path, _) = run_writer(
+        &["strip", "msvc-llvm.pdb", "--section-contributions"],
+        "stripped.pdb",
+        0,
+    );
+    check_plain(
+        "msvc-llvm-strip-modules.txt",
+        &["modules", path.to_str().unwrap()],
+    );
+}
+
+#[test]
+fn dwarf() {
+    let (_dir, path, output) = run_writer(&["dwarf", "msvc-llvm.pdb"], "msvc-llvm.elf", 2);
+    let data = std::fs::read(path).unwrap();
+    check(
+        "msvc-llvm-dwarf.txt",
+        &(output + &describe_elf(&data) + &describe_dwarf(&data)),
+    );
+}
+
+#[test]
+fn symtab() {
+    let (_dir, path, output) = run_writer(&["symtab", "msvc-llvm.pdb"], "msvc-llvm.elf", 2);
+    let data = std::fs::read(path).unwrap();
+    check("msvc-llvm-symtab.txt", &(output + &describe_elf(&data)));
+}
+
+#[test]
+fn addrmap() {
+    let (_dir, path, output) = run_writer(&["addrmap", "msvc-llvm.pdb"], "msvc-llvm.map", 2);
+    let data = std::fs::read(path).unwrap();
+    check(
+        "msvc-llvm-addrmap.txt",
+        &(output + &describe_address_map(&data)),
+    );
+}
+
+#[test]
+fn syscalls() {
+    // Without the image, only services with stubs are listed
+    check_plain("msvc-kernel-syscalls.txt", &["syscalls", "msvc-kernel.pdb"]);
+    check_plain(
+        "msvc-kernel-syscalls-image.txt",
+        &["syscalls", "msvc-kernel.pdb", "--image", "msvc-kernel.dll"],
+    );
+    check_json(
+        "msvc-kernel-syscalls-image.json",
+        &["syscalls", "msvc-kernel.pdb", "--image", "msvc-kernel.dll"],
+    );
+}
+
+#[test]
+fn sources() {
+    check(
+        "msvc-llvm-sources.txt",
+        &run_with_status(&["sources", "msvc-llvm.pdb"], 2),
+    );
+    check(
+        "msvc-llvm-sources-verify.txt",
+        &run_with_status(&["sources", "msvc-llvm.pdb", "--verify", "--root", "."], 2),
+    );
+}
+
+#[test]
+fn stats() {
+    check(
+        "msvc-llvm-stats.txt",
+        &run_with_status(&["stats", "msvc-llvm.pdb"], 2),
+    );
+    check_plain(
+        "msvc-kernel-stats-by-file.txt",
+        &["stats", "msvc-kernel.pdb", "--by-file"],
+    );
+}
+
+#[test]
+fn xref_type() {
+    check(
+        "msvc-llvm-xref-type.txt",
+        &run_with_status(&["xref-type", "msvc-llvm.pdb", "Point"], 2),
+    );
+    check_plain(
+        "msvc-callbacks-xref-type.txt",
+        &["xref-type", "msvc-callbacks.pdb", "Callbacks"],
+    );
+}
+
+#[test]
+fn modules() {
+    check(
+        "msvc-llvm-modules.txt",
+        &run_with_status(&["modules", "msvc-llvm.pdb"], 2),
+    );
+    check_plain(
+        "rust-lld-modules-size.txt",
+        &["modules", "rust-lld.pdb", "--sort", "size"],
+    );
+}
+
+#[test]
+fn raw_type() {
+    check_plain(
+        "msvc-llvm-raw-type.txt",
+        &["raw-type", "msvc-llvm.pdb", "0x1005"],
+    );
+    // The malformed vtable shape is dumped along with why it couldn't be parsed
+    check_plain(
+        "msvc-corrupt-raw-type.txt",
+        &["raw-type", "msvc-corrupt.pdb", "0x1005"],
+    );
+    run_with_status(&["raw-type", "msvc-basic.pdb", "0x2000"], 1);
+}
+
+#[test]
+fn grep() {
+    check(
+        "msvc-llvm-grep.txt",
+        &run_with_status(&["grep", "msvc-llvm.pdb", "COUNT", "--ignore-case"], 2),
+    );
+    check_plain(
+        "msvc-callbacks-grep.txt",
+        &["grep", "msvc-callbacks.pdb", "ACCESS_*"],
+    );
+    // The variants' names only have `ACCESS` as part of a longer word
+    check_plain(
+        "msvc-callbacks-grep-word.txt",
+        &[
+            "grep",
+            "msvc-callbacks.pdb",
+            "access",
+            "--ignore-case",
+            "--word",
+        ],
+    );
+}
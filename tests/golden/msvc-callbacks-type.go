@@ -0,0 +1,16 @@
+// Code generated by pdbview from msvc-callbacks.pdb. DO NOT EDIT.
+
+package msvccallbacks
+
+// Callbacks is `Callbacks` (struct, 0x8 bytes)
+// unsafe.Sizeof(Callbacks{}) == 0x8
+type Callbacks struct {
+	On_close uint64 // +0x0 int32_t (*)(Point*)
+}
+
+// Point is `Point` (struct, 0x8 bytes)
+// unsafe.Sizeof(Point{}) == 0x8
+type Point struct {
+	X int32   // +0x0
+	Y float32 // +0x4
+}
@@ -0,0 +1,22 @@
+// Code generated by pdbview from msvc-callbacks.pdb. DO NOT EDIT.
+
+const std = @import("std");
+
+/// `Callbacks` (struct, 0x8 bytes)
+pub const Callbacks = extern struct {
+    on_close: u64, // +0x0 int32_t (*)(Point*)
+};
+
+comptime {
+    std.debug.assert(@sizeOf(Callbacks) == 0x8);
+}
+
+/// `Point` (struct, 0x8 bytes)
+pub const Point = extern struct {
+    x: i32, // +0x0
+    y: f32, // +0x4
+};
+
+comptime {
+    std.debug.assert(@sizeOf(Point) == 0x8);
+}
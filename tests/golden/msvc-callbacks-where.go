@@ -0,0 +1,9 @@
+// Code generated by pdbview from msvc-callbacks.pdb. DO NOT EDIT.
+
+package msvccallbacks
+
+// Callbacks is `Callbacks` (struct, 0x8 bytes)
+// unsafe.Sizeof(Callbacks{}) == 0x8
+type Callbacks struct {
+	On_close uint64 // +0x0 int32_t (*)(Point*)
+}